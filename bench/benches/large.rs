@@ -0,0 +1,88 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tui_textarea::{CursorMove, TextArea};
+use tui_textarea_bench::{dummy_terminal, TerminalExt, LOREM};
+
+const SIZE: usize = 10 * 1024 * 1024;
+
+// Minified text in one line like a minified JSON file
+fn one_line() -> TextArea<'static> {
+    let mut line = String::with_capacity(SIZE);
+    while line.len() < SIZE {
+        for l in LOREM {
+            line.push_str(l);
+            line.push(' ');
+        }
+    }
+    TextArea::new(vec![line])
+}
+
+fn many_lines() -> TextArea<'static> {
+    let mut lines = vec![];
+    let mut len = 0;
+    while len < SIZE {
+        for l in LOREM {
+            lines.push(l.to_string());
+            len += l.len() + 1;
+        }
+    }
+    TextArea::new(lines)
+}
+
+// Type a character and delete it at the cursor. This is the latency of one keystroke including rendering
+#[inline]
+fn keystroke(textarea: &mut TextArea<'_>, term: &mut impl TerminalExt) {
+    textarea.insert_char('x');
+    term.draw_textarea(textarea);
+    textarea.delete_char();
+    term.draw_textarea(textarea);
+}
+
+fn run(c: &mut Criterion, name: &str, mut textarea: TextArea<'static>, (row, col): (u64, u64)) {
+    let mut term = dummy_terminal();
+    textarea.set_max_histories(0);
+    textarea.move_cursor(CursorMove::Jump(row, col));
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            keystroke(&mut textarea, &mut term);
+            black_box(textarea.cursor())
+        })
+    });
+}
+
+fn one_line_10mb(c: &mut Criterion) {
+    run(c, "large::one_line::head", one_line(), (0, 0));
+    run(c, "large::one_line::middle", one_line(), (0, SIZE as u64 / 2));
+    run(c, "large::one_line::end", one_line(), (0, SIZE as u64 * 2));
+}
+
+fn many_lines_10mb(c: &mut Criterion) {
+    let textarea = many_lines();
+    let middle = textarea.lines().len() as u64 / 2;
+    run(c, "large::many_lines::head", textarea, (0, 0));
+    run(c, "large::many_lines::middle", many_lines(), (middle, 10));
+    run(c, "large::many_lines::end", many_lines(), (u64::MAX, 0));
+}
+
+fn rope(mut textarea: TextArea<'static>) -> TextArea<'static> {
+    textarea.set_rope(true);
+    textarea
+}
+
+fn rope_10mb(c: &mut Criterion) {
+    run(c, "large::rope::one_line::head", rope(one_line()), (0, 0));
+    run(
+        c,
+        "large::rope::one_line::middle",
+        rope(one_line()),
+        (0, SIZE as u64 / 2),
+    );
+    run(c, "large::rope::one_line::end", rope(one_line()), (0, SIZE as u64 * 2));
+    let textarea = rope(many_lines());
+    let middle = textarea.len_lines() as u64 / 2;
+    run(c, "large::rope::many_lines::head", textarea, (0, 0));
+    run(c, "large::rope::many_lines::middle", rope(many_lines()), (middle, 10));
+    run(c, "large::rope::many_lines::end", rope(many_lines()), (u64::MAX, 0));
+}
+
+criterion_group!(large, one_line_10mb, many_lines_10mb, rope_10mb);
+criterion_main!(large);
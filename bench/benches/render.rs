@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ratatui::style::{Color, Style};
+use tui_textarea::{CursorMove, TextArea};
+use tui_textarea_bench::{dummy_terminal_with_size, TerminalExt, LOREM};
+
+// 200 rows are visible on the terminal
+const HEIGHT: u16 = 200;
+
+fn textarea() -> TextArea<'static> {
+    let mut textarea: TextArea = LOREM.iter().cycle().take(1000).copied().collect();
+    textarea.set_line_number_style(Style::default().fg(Color::DarkGray));
+    textarea.set_search_pattern(r"\b[aeiou]\w+").unwrap();
+    textarea.move_cursor(CursorMove::Jump(100, 0));
+    textarea
+}
+
+#[inline]
+fn idle(textarea: &TextArea<'_>, term: &mut impl TerminalExt, cached: bool) {
+    if !cached {
+        textarea.clear_render_cache();
+    }
+    term.draw_textarea(textarea);
+}
+
+#[inline]
+fn move_cursor(textarea: &mut TextArea<'_>, term: &mut impl TerminalExt, cached: bool) {
+    for m in [CursorMove::Down, CursorMove::Forward, CursorMove::Up, CursorMove::Back] {
+        textarea.move_cursor(m);
+        if !cached {
+            textarea.clear_render_cache();
+        }
+        term.draw_textarea(textarea);
+    }
+}
+
+fn render(c: &mut Criterion) {
+    for (name, cached) in [("cached", true), ("uncached", false)] {
+        let mut textarea = textarea();
+        let mut term = dummy_terminal_with_size(80, HEIGHT);
+        c.bench_function(&format!("render::{name}::idle"), |b| {
+            b.iter(|| {
+                idle(&textarea, &mut term, cached);
+                black_box(textarea.cursor())
+            })
+        });
+        c.bench_function(&format!("render::{name}::move_cursor"), |b| {
+            b.iter(|| {
+                move_cursor(&mut textarea, &mut term, cached);
+                black_box(textarea.cursor())
+            })
+        });
+    }
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tui_textarea::{CursorMove, TextArea};
+use tui_textarea_bench::{dummy_terminal, TerminalExt, LOREM};
+
+const SIZE: usize = 1024 * 1024;
+
+fn textarea_1mb() -> TextArea<'static> {
+    let mut lines = vec![];
+    let mut len = 0;
+    while len < SIZE {
+        for l in LOREM {
+            lines.push(l.to_string());
+            len += l.len() + 1;
+        }
+    }
+    TextArea::new(lines)
+}
+
+// Render one frame with a status line showing the statistics. After warm-up, the cached statistics are returned
+// without scanning the text
+#[inline]
+fn frame(textarea: &TextArea<'_>, term: &mut impl TerminalExt) -> String {
+    term.draw_textarea(textarea);
+    let stats = textarea.stats();
+    format!(
+        "words: {} | chars: {} | {:.0}%",
+        stats.words,
+        stats.chars,
+        textarea.cursor_progress() * 100.0,
+    )
+}
+
+fn cached(c: &mut Criterion) {
+    let textarea = textarea_1mb();
+    textarea.stats(); // Warm up the cache
+    c.bench_function("stats::1mb::cached", |b| b.iter(|| black_box(textarea.stats())));
+    let mut term = dummy_terminal();
+    c.bench_function("stats::1mb::frame", |b| {
+        b.iter(|| black_box(frame(&textarea, &mut term)))
+    });
+}
+
+// Statistics are calculated again after every keystroke
+fn after_edit(c: &mut Criterion) {
+    let mut textarea = textarea_1mb();
+    let mut term = dummy_terminal();
+    textarea.set_max_histories(0);
+    textarea.move_cursor(CursorMove::Bottom);
+    c.bench_function("stats::1mb::after_edit", |b| {
+        b.iter(|| {
+            textarea.insert_char('x');
+            black_box(frame(&textarea, &mut term));
+            textarea.delete_char();
+            black_box(frame(&textarea, &mut term))
+        })
+    });
+}
+
+criterion_group!(stats, cached, after_edit);
+criterion_main!(stats);
@@ -109,6 +109,11 @@ pub fn dummy_terminal() -> Terminal<DummyBackend> {
     Terminal::new(DummyBackend::default()).unwrap()
 }
 
+#[inline]
+pub fn dummy_terminal_with_size(width: u16, height: u16) -> Terminal<DummyBackend> {
+    Terminal::new(DummyBackend { width, height, cursor: (0, 0) }).unwrap()
+}
+
 pub trait TerminalExt {
     fn draw_textarea(&mut self, textarea: &TextArea<'_>);
 }
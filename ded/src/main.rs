@@ -1,4 +1,4 @@
-use crossterm::event::Event;
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste, Event};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Constraint, Direction, Layout};
@@ -6,7 +6,7 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Terminal;
-use tui_textarea::{CursorMove, Fullscreen, Input, Key, TextArea};
+use tui_textarea::{parse_position, Fullscreen, IndentKind, Input, Key, SaveOptions, TextArea};
 
 use std::borrow::Cow;
 use std::fmt::Display;
@@ -58,7 +58,7 @@ impl<'a> Editor<'a> {
         }
         let mut stdout = io::stdout();
         enable_raw_mode()?;
-        crossterm::execute!(stdout, EnterAlternateScreen)?;
+        crossterm::execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
         let backend = CrosstermBackend::new(stdout);
         let term = Terminal::new(backend)?;
         Ok(Self {
@@ -80,6 +80,11 @@ impl<'a> Editor<'a> {
             if let Event::Resize(_, _) = event {
                 self.render()?;
             }
+            if let Event::Paste(text) = &event {
+                self.process_paste(text);
+                self.render()?;
+                continue;
+            }
 
             let event = event.into();
             // ignore Key::Null so we don't rerender unnecessarily
@@ -99,6 +104,22 @@ impl<'a> Editor<'a> {
         Ok(())
     }
 
+    // Pasted text is inserted at once as one undo unit instead of being handled as key inputs
+    fn process_paste(&mut self, text: &str) {
+        let buffer = &mut self.buffers[self.current];
+        if buffer.goto.open {
+            buffer.goto.textarea.handle_paste(text);
+        } else if buffer.search.open {
+            if let Some(query) = buffer.search.paste(text) {
+                let maybe_err = buffer.textarea.set_search_pattern(query).err();
+                buffer.search.set_error(maybe_err);
+                buffer.textarea.search_preview(true);
+            }
+        } else {
+            buffer.textarea.handle_paste(text);
+        }
+    }
+
     fn process_input(&mut self, event: Input) -> io::Result<Status> {
         match event {
             Input { key: Key::F(11), .. } => {
@@ -138,26 +159,63 @@ impl<'a> Editor<'a> {
                 let buffer = &mut self.buffers[self.current];
                 let textarea = &mut buffer.textarea;
                 let search = &mut buffer.search;
-                if search.open {
+                let goto = &mut buffer.goto;
+                if goto.open {
+                    match event {
+                        Input { key: Key::Enter, .. } => {
+                            let current = textarea.cursor().0 + 1;
+                            match parse_position(goto.input(), current) {
+                                Some((line, col)) => {
+                                    textarea.goto_line(line, col, true);
+                                }
+                                None => self.message = Some("Invalid position".into()),
+                            }
+                            goto.close();
+                        }
+                        Input { key: Key::Esc, .. } => goto.close(),
+                        input => {
+                            goto.textarea.single_line_input(input);
+                        }
+                    }
+                } else if search.open {
                     match event {
+                        // Recall previous patterns in an empty prompt like readline
+                        Input { key: Key::Up, .. } if search.is_empty() || search.recalled => {
+                            if let Some(query) = textarea.search_history_prev() {
+                                search.recall(query);
+                                textarea.search_preview(true);
+                            }
+                        }
+                        Input { key: Key::Down, .. } if search.recalled => {
+                            if let Some(query) = textarea.search_history_next() {
+                                search.recall(query);
+                                textarea.search_preview(true);
+                            } else {
+                                search.set_pattern("");
+                                textarea.set_search_pattern("").unwrap();
+                            }
+                        }
+                        // Matches are previewed without moving the cursor until Enter is pressed
                         Input { key: Key::Down, .. } => {
-                            if !textarea.search_forward(false) {
+                            if textarea.search_preview(true).is_none() {
                                 search.set_error(Some("Pattern not found"));
                             }
                         }
                         Input { key: Key::Up, .. } => {
-                            if !textarea.search_back(false) {
+                            if textarea.search_preview(false).is_none() {
                                 search.set_error(Some("Pattern not found"));
                             }
                         }
                         Input { key: Key::Enter, .. } => {
-                            if !textarea.search_forward(true) {
+                            if !textarea.search_commit() {
                                 self.message = Some("Pattern not found".into());
                             }
                             search.close();
                             textarea.set_search_pattern("").unwrap();
                         }
                         Input { key: Key::Esc, .. } => {
+                            // Go back to the place before the search
+                            textarea.search_cancel();
                             search.close();
                             textarea.set_search_pattern("").unwrap();
                         }
@@ -165,6 +223,7 @@ impl<'a> Editor<'a> {
                             if let Some(query) = search.input(input) {
                                 let maybe_err = textarea.set_search_pattern(query).err();
                                 search.set_error(maybe_err);
+                                textarea.search_preview(true);
                             }
                         }
                     }
@@ -175,18 +234,26 @@ impl<'a> Editor<'a> {
                             ctrl: true,
                             ..
                         } => {
-                            let search_pattern = {
-                                let prev_search_pattern = search.open();
-                                textarea.take_selection().unwrap_or(prev_search_pattern).to_owned()
-                            };
+                            // Prefill the selected text without cancelling the selection
+                            search.open = true;
+                            let search_pattern = textarea
+                                .selected_text()
+                                .filter(|s| !s.contains('\n'))
+                                .map_or(String::new(), Cow::into_owned);
 
                             search.set_pattern(&search_pattern);
                             let maybe_err = textarea.set_search_pattern(search_pattern).err();
                             search.set_error(maybe_err);
+                            textarea.search_preview(true);
                         }
+                        Input {
+                            key: Key::Char('g'),
+                            ctrl: true,
+                            ..
+                        } => goto.open(),
                         input => {
                             let buffer = &mut self.buffers[self.current];
-                            buffer.modified |= buffer.textarea.input(input);
+                            buffer.textarea.input(input);
                         }
                     }
                 }
@@ -202,8 +269,10 @@ impl<'a> Editor<'a> {
         let buffer = &mut self.buffers[self.current];
         let textarea = &mut buffer.textarea;
         let search = &mut buffer.search;
+        let goto = &buffer.goto;
 
-        let search_height = search.height();
+        // Only one of the search box and the go-to-line box is open at once
+        let search_height = search.height() + goto.height();
         let layout = Layout::default().direction(Direction::Vertical).constraints([
             Constraint::Length(search_height),
             Constraint::Min(1),
@@ -221,23 +290,33 @@ impl<'a> Editor<'a> {
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(search_height), Constraint::Min(1)]);
 
+        // Show the position of the current match like "[3/17]" while searching
+        let (row, col) = textarea.cursor();
+        let cursor = match textarea.current_search_match() {
+            Some(i) if search.open => {
+                let total = textarea.search_match_count(None);
+                format!("[{}/{}] ({},{})", i + 1, total, row + 1, col + 1)
+            }
+            _ => format!("({},{})", row + 1, col + 1),
+        };
+
         self.term.draw(|f| {
             match textarea.fullscreen() {
                 Fullscreen::Off => {
                     let chunks = layout.split(f.size());
 
-                    if search_height > 0 {
+                    if goto.open {
+                        f.render_widget(goto.textarea.widget(), chunks[0]);
+                    } else if search_height > 0 {
                         f.render_widget(search.textarea.widget(), chunks[0]);
                     }
 
                     f.render_widget(textarea.widget(), chunks[1]);
 
                     // Render status line
-                    let modified = if buffer.modified { " [modified]" } else { "" };
+                    let modified = if textarea.is_modified() { " [modified]" } else { "" };
                     let slot = format!("[{}/{}]", self.current + 1, num_buffers);
                     let path = format!(" {}{} ", buffer.path.display(), modified);
-                    let (row, col) = textarea.cursor();
-                    let cursor = format!("({},{})", row + 1, col + 1);
                     let status_chunks = Layout::default()
                         .direction(Direction::Horizontal)
                         .constraints(
@@ -257,17 +336,25 @@ impl<'a> Editor<'a> {
                     // Render message at bottom
                     let message = if let Some(message) = self.message.take() {
                         Line::from(Span::raw(message))
+                    } else if goto.open {
+                        Line::from(vec![
+                            Span::raw("Press "),
+                            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+                            Span::raw(" to go to LINE[:COL] or +N/-N lines, "),
+                            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+                            Span::raw(" to close"),
+                        ])
                     } else if search_height > 0 {
                         Line::from(vec![
                             Span::raw("Press "),
                             Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-                            Span::raw(" to jump to first match and close, "),
+                            Span::raw(" to jump to match and close, "),
                             Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
-                            Span::raw(" to close, "),
+                            Span::raw(" to go back and close, "),
                             Span::styled("↓", Style::default().add_modifier(Modifier::BOLD)),
                             Span::raw(" to search next, "),
                             Span::styled("↑", Style::default().add_modifier(Modifier::BOLD)),
-                            Span::raw(" to search previous"),
+                            Span::raw(" to search previous or recall history"),
                         ])
                     } else {
                         Line::from(vec![
@@ -278,6 +365,8 @@ impl<'a> Editor<'a> {
                             Span::raw(" to save, "),
                             Span::styled("^F", Style::default().add_modifier(Modifier::BOLD)),
                             Span::raw(" to search, "),
+                            Span::styled("^G", Style::default().add_modifier(Modifier::BOLD)),
+                            Span::raw(" to go to line, "),
                             Span::styled("alt + BUF_ID", Style::default().add_modifier(Modifier::BOLD)),
                             Span::raw(" to switch buffer"),
                         ])
@@ -287,18 +376,18 @@ impl<'a> Editor<'a> {
                 Fullscreen::Half => {
                     let chunks = half_fullscreen_layout.split(f.size());
 
-                    if search_height > 0 {
+                    if goto.open {
+                        f.render_widget(goto.textarea.widget(), chunks[0]);
+                    } else if search_height > 0 {
                         f.render_widget(search.textarea.widget(), chunks[0]);
                     }
 
                     f.render_widget(textarea.widget(), chunks[1]);
 
                     // Render status line
-                    let modified = if buffer.modified { " [modified]" } else { "" };
+                    let modified = if textarea.is_modified() { " [modified]" } else { "" };
                     let slot = format!("[{}/{}]", self.current + 1, num_buffers);
                     let path = format!(" {}{} ", buffer.path.display(), modified);
-                    let (row, col) = textarea.cursor();
-                    let cursor = format!("({},{})", row + 1, col + 1);
                     let status_chunks = Layout::default()
                         .direction(Direction::Horizontal)
                         .constraints(
@@ -318,7 +407,9 @@ impl<'a> Editor<'a> {
                 Fullscreen::Full => {
                     let chunks = fullscreen_layout.split(f.size());
 
-                    if search_height > 0 {
+                    if goto.open {
+                        f.render_widget(goto.textarea.widget(), chunks[0]);
+                    } else if search_height > 0 {
                         f.render_widget(search.textarea.widget(), chunks[0]);
                     }
 
@@ -335,15 +426,15 @@ impl<'a> Drop for Editor<'a> {
     fn drop(&mut self) {
         self.term.show_cursor().unwrap();
         disable_raw_mode().unwrap();
-        crossterm::execute!(self.term.backend_mut(), LeaveAlternateScreen).unwrap();
+        crossterm::execute!(self.term.backend_mut(), LeaveAlternateScreen, DisableBracketedPaste).unwrap();
     }
 }
 
 struct Buffer<'a> {
     textarea: TextArea<'a>,
     path: PathBuf,
-    modified: bool,
     search: SearchBox<'a>,
+    goto: GotoBox<'a>,
 }
 
 impl<'a> Buffer<'a> {
@@ -351,8 +442,10 @@ impl<'a> Buffer<'a> {
         let mut textarea = if let Ok(md) = path.metadata() {
             if md.is_file() {
                 let mut textarea = TextArea::new_from_file(&fs::File::open(&path)?)?;
-                if textarea.lines().iter().any(|l| l.starts_with('\t')) {
-                    textarea.set_hard_tab_indent(true);
+                match textarea.detect_indent() {
+                    IndentKind::Tabs => textarea.set_hard_tab_indent(true),
+                    IndentKind::Spaces(width) => textarea.set_tab_length(width),
+                    IndentKind::Unknown => {}
                 }
                 textarea
             } else {
@@ -365,35 +458,30 @@ impl<'a> Buffer<'a> {
         textarea.set_cursor_line_style(Style::default());
         textarea.set_line_number_style(Style::default().fg(Color::DarkGray));
         textarea.set_max_histories(100);
+        textarea.set_current_search_match_style(Style::default().bg(Color::LightBlue));
 
         Ok(Self {
             textarea,
             path,
-            modified: false,
             search: SearchBox::default(),
+            goto: GotoBox::default(),
         })
     }
 
     fn save(&mut self) -> io::Result<()> {
-        if !self.modified {
+        if !self.textarea.is_modified() {
             return Ok(());
         }
 
         let mut f = io::BufWriter::new(fs::File::create(&self.path)?);
-        let lines = self.textarea.lines();
-        for line in lines.iter().take(lines.len() - 1) {
-            f.write_all(line.as_bytes())?;
-            f.write_all(b"\n")?;
-        }
-
-        if let Some(last_line) = lines.last() {
-            f.write_all(last_line.as_bytes())?;
-            if !last_line.is_empty() {
-                f.write_all(b"\n")?;
-            }
-        }
+        let options = SaveOptions {
+            ensure_trailing_newline: true,
+            ..Default::default()
+        };
+        self.textarea.to_writer(&mut f, options)?;
+        f.flush()?;
 
-        self.modified = false;
+        self.textarea.mark_saved();
         Ok(())
     }
 }
@@ -401,6 +489,7 @@ impl<'a> Buffer<'a> {
 struct SearchBox<'a> {
     textarea: TextArea<'a>,
     open: bool,
+    recalled: bool, // The input was recalled from the search history
 }
 
 impl<'a> Default for SearchBox<'a> {
@@ -408,23 +497,31 @@ impl<'a> Default for SearchBox<'a> {
         let mut textarea = TextArea::default();
         textarea.set_block(Block::default().borders(Borders::ALL).title("Search"));
         textarea.set_cursor_line_style(Style::default());
-        textarea.set_max_histories(100);
+        textarea.set_single_line(true);
 
-        Self { textarea, open: false }
+        Self {
+            textarea,
+            open: false,
+            recalled: false,
+        }
     }
 }
 
 impl<'a> SearchBox<'a> {
-    fn open(&mut self) -> &'_ str {
-        self.open = true;
-        self.textarea.lines()[0].as_str()
-    }
-
     fn close(&mut self) {
         self.open = false;
-        // Remove input for next search. Do not recreate `self.textarea` instance to keep undo history so that users can
-        // restore previous input easily.
-        self.textarea.move_cursor(CursorMove::End);
+        // Remove input for next search. Previous inputs can be recalled from the search history of the editor
+        self.set_pattern("");
+    }
+
+    fn is_empty(&self) -> bool {
+        self.textarea.is_empty()
+    }
+
+    fn recall(&mut self, pattern: &str) {
+        self.set_pattern(pattern);
+        self.recalled = true;
+        self.set_error(None::<&str>);
     }
 
     fn height(&self) -> u16 {
@@ -438,16 +535,19 @@ impl<'a> SearchBox<'a> {
     fn set_pattern(&mut self, pattern: &str) {
         self.textarea.delete_line(false);
         self.textarea.insert_str(pattern);
+        self.recalled = false;
     }
 
     fn input(&mut self, input: Input) -> Option<&'_ str> {
-        match input {
-            Input { key: Key::Enter, .. } => None, // disable inputs which inserts a newline
-            input => {
-                let modified = self.textarea.single_line_input(input);
-                modified.then(|| self.textarea.lines()[0].as_str())
-            }
-        }
+        let modified = self.textarea.single_line_input(input);
+        self.recalled &= !modified;
+        modified.then(|| self.textarea.lines()[0].as_str())
+    }
+
+    fn paste(&mut self, text: &str) -> Option<&'_ str> {
+        let modified = self.textarea.handle_paste(text);
+        self.recalled &= !modified;
+        modified.then(|| self.textarea.lines()[0].as_str())
     }
 
     fn set_error(&mut self, err: Option<impl Display>) {
@@ -462,3 +562,42 @@ impl<'a> SearchBox<'a> {
         self.textarea.set_block(b);
     }
 }
+
+struct GotoBox<'a> {
+    textarea: TextArea<'a>,
+    open: bool,
+}
+
+impl<'a> Default for GotoBox<'a> {
+    fn default() -> Self {
+        let mut textarea = TextArea::default();
+        textarea.set_block(Block::default().borders(Borders::ALL).title("Go to line"));
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_single_line(true);
+
+        Self { textarea, open: false }
+    }
+}
+
+impl<'a> GotoBox<'a> {
+    fn open(&mut self) {
+        self.open = true;
+        self.textarea.delete_line(false);
+    }
+
+    fn close(&mut self) {
+        self.open = false;
+    }
+
+    fn height(&self) -> u16 {
+        if self.open {
+            3
+        } else {
+            0
+        }
+    }
+
+    fn input(&self) -> &'_ str {
+        self.textarea.lines()[0].as_str()
+    }
+}
@@ -1,4 +1,4 @@
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Constraint, Direction, Layout};
@@ -8,10 +8,10 @@ use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Terminal;
 use std::borrow::Cow;
 use std::fmt::Display;
-use std::io::{self, BufRead, Write};
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::{env, fs};
-use tui_textarea::{CursorMove, Input, Key, TextArea};
+use tui_textarea::{CursorMove, IndentKind, Input, Key, SaveOptions, TextArea};
 
 macro_rules! error {
     ($fmt: expr $(, $args:tt)*) => {{
@@ -84,18 +84,17 @@ impl<'a> SearchBox<'a> {
 struct Buffer<'a> {
     textarea: TextArea<'a>,
     path: PathBuf,
-    modified: bool,
 }
 
 impl<'a> Buffer<'a> {
     fn new(path: PathBuf) -> io::Result<Self> {
         let mut textarea = if let Ok(md) = path.metadata() {
             if md.is_file() {
-                let mut textarea: TextArea = io::BufReader::new(fs::File::open(&path)?)
-                    .lines()
-                    .collect::<io::Result<_>>()?;
-                if textarea.lines().iter().any(|l| l.starts_with('\t')) {
-                    textarea.set_hard_tab_indent(true);
+                let mut textarea = TextArea::new_from_file(&fs::File::open(&path)?)?;
+                match textarea.detect_indent() {
+                    IndentKind::Tabs => textarea.set_hard_tab_indent(true),
+                    IndentKind::Spaces(width) => textarea.set_tab_length(width),
+                    IndentKind::Unknown => {}
                 }
                 textarea
             } else {
@@ -105,32 +104,22 @@ impl<'a> Buffer<'a> {
             TextArea::default() // File does not exist
         };
         textarea.set_line_number_style(Style::default().fg(Color::DarkGray));
-        Ok(Self {
-            textarea,
-            path,
-            modified: false,
-        })
+        Ok(Self { textarea, path })
     }
 
     fn save(&mut self) -> io::Result<()> {
-        if !self.modified {
+        if !self.textarea.is_modified() {
             return Ok(());
         }
         let mut f = io::BufWriter::new(fs::File::create(&self.path)?);
-        let lines = self.textarea.lines();
-        for line in lines.iter().take(lines.len() - 1) {
-            f.write_all(line.as_bytes())?;
-            f.write_all(b"\n")?;
-        }
-
-        if let Some(last_line) = lines.last() {
-            f.write_all(last_line.as_bytes())?;
-            if !last_line.is_empty() {
-                f.write_all(b"\n")?;
-            }
-        }
+        let options = SaveOptions {
+            ensure_trailing_newline: true,
+            ..Default::default()
+        };
+        self.textarea.to_writer(&mut f, options)?;
+        f.flush()?;
 
-        self.modified = false;
+        self.textarea.mark_saved();
         Ok(())
     }
 }
@@ -155,7 +144,7 @@ impl<'a> Editor<'a> {
         }
         let mut stdout = io::stdout();
         enable_raw_mode()?;
-        crossterm::execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        crossterm::execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
         let backend = CrosstermBackend::new(stdout);
         let term = Terminal::new(backend)?;
         Ok(Self {
@@ -193,7 +182,7 @@ impl<'a> Editor<'a> {
                 f.render_widget(widget, chunks[1]);
 
                 // Render status line
-                let modified = if buffer.modified { " [modified]" } else { "" };
+                let modified = if textarea.is_modified() { " [modified]" } else { "" };
                 let slot = format!("[{}/{}]", self.current + 1, self.buffers.len());
                 let path = format!(" {}{} ", buffer.path.display(), modified);
                 let (row, col) = textarea.cursor();
@@ -295,7 +284,14 @@ impl<'a> Editor<'a> {
                     }
                 }
             } else {
-                match crossterm::event::read()?.into() {
+                let event = crossterm::event::read()?;
+                if let Event::Paste(text) = &event {
+                    // Pasted text is inserted at once as one undo unit
+                    let buffer = &mut self.buffers[self.current];
+                    buffer.textarea.handle_paste(text);
+                    continue;
+                }
+                match event.into() {
                     Input {
                         key: Key::Char('q'),
                         ctrl: true,
@@ -326,7 +322,7 @@ impl<'a> Editor<'a> {
                     }
                     input => {
                         let buffer = &mut self.buffers[self.current];
-                        buffer.modified |= buffer.textarea.input(input);
+                        buffer.textarea.input(input);
                     }
                 }
             }
@@ -340,7 +336,13 @@ impl<'a> Drop for Editor<'a> {
     fn drop(&mut self) {
         self.term.show_cursor().unwrap();
         disable_raw_mode().unwrap();
-        crossterm::execute!(self.term.backend_mut(), LeaveAlternateScreen, DisableMouseCapture).unwrap();
+        crossterm::execute!(
+            self.term.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        )
+        .unwrap();
     }
 }
 
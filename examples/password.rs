@@ -20,11 +20,16 @@ fn main() -> io::Result<()> {
     let mut textarea = TextArea::default();
     textarea.set_cursor_line_style(Style::default());
     textarea.set_mask_char('\u{2022}'); //U+2022 BULLET (•)
+    textarea.set_mask_reveal_last(true);
     textarea.set_placeholder_text("Please enter your password");
     let constraints = [Constraint::Length(3), Constraint::Min(1)].as_slice();
     let layout = Layout::default().constraints(constraints);
     textarea.set_style(Style::default().fg(Color::LightGreen));
-    textarea.set_block(Block::default().borders(Borders::ALL).title("Password"));
+    textarea.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Password (Ctrl+S to show)"),
+    );
 
     loop {
         term.draw(|f| {
@@ -35,6 +40,11 @@ fn main() -> io::Result<()> {
 
         match crossterm::event::read()?.into() {
             Input { key: Key::Esc | Key::Enter, .. } => break,
+            Input {
+                key: Key::Char('s'),
+                ctrl: true,
+                ..
+            } => textarea.set_mask_enabled(!textarea.mask_enabled()),
             input => {
                 if textarea.input(input) {
                     // When the input modified its text, validate the text content
@@ -6,19 +6,7 @@ use ratatui::style::{Color, Style};
 use ratatui::widgets::{Block, Borders};
 use ratatui::Terminal;
 use std::io;
-use tui_textarea::{Input, Key, TextArea};
-
-fn validate(textarea: &mut TextArea) -> bool {
-    if let Err(err) = textarea.lines()[0].parse::<f64>() {
-        textarea.set_style(Style::default().fg(Color::LightRed));
-        textarea.set_block(Block::default().borders(Borders::ALL).title(format!("ERROR: {}", err)));
-        false
-    } else {
-        textarea.set_style(Style::default().fg(Color::LightGreen));
-        textarea.set_block(Block::default().borders(Borders::ALL).title("OK"));
-        true
-    }
-}
+use tui_textarea::{Input, InputResult, Key, TextArea};
 
 fn main() -> io::Result<()> {
     let stdout = io::stdout();
@@ -31,11 +19,21 @@ fn main() -> io::Result<()> {
 
     let mut textarea = TextArea::default();
     textarea.set_cursor_line_style(Style::default());
+    textarea.set_single_line(true);
     textarea.set_placeholder_text("Enter a valid float (e.g. 1.56)");
+    textarea.set_style(Style::default().fg(Color::LightGreen));
+    textarea.set_invalid_style(Style::default().fg(Color::LightRed));
+    // The validator runs every time the input modifies the text
+    textarea.set_validator(|lines| lines[0].parse::<f64>().map(|_| ()).map_err(|e| e.to_string()));
     let layout = Layout::default().constraints([Constraint::Length(3), Constraint::Min(1)].as_slice());
-    let mut is_valid = validate(&mut textarea);
 
     loop {
+        let title = match textarea.validation_error() {
+            Some(err) => format!("ERROR: {}", err),
+            None => "OK".to_string(),
+        };
+        textarea.set_block(Block::default().borders(Borders::ALL).title(title));
+
         term.draw(|f| {
             let chunks = layout.split(f.size());
             let widget = textarea.widget();
@@ -44,17 +42,10 @@ fn main() -> io::Result<()> {
 
         match crossterm::event::read()?.into() {
             Input { key: Key::Esc, .. } => break,
-            Input { key: Key::Enter, .. } if is_valid => break,
-            Input {
-                key: Key::Char('m'),
-                ctrl: true,
-                ..
-            }
-            | Input { key: Key::Enter, .. } => {}
+            // TextArea::handle_input returns if the input modified its text or submitted it
             input => {
-                // TextArea::input returns if the input modified its text
-                if textarea.input(input) {
-                    is_valid = validate(&mut textarea);
+                if textarea.handle_input(input) == InputResult::Submit && textarea.validation_error().is_none() {
+                    break;
                 }
             }
         }
@@ -2,34 +2,12 @@ use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Constraint, Direction, Layout};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Color, Style};
 use ratatui::widgets::{Block, Borders};
 use ratatui::Terminal;
 use std::io;
 use tui_textarea::{Input, Key, TextArea};
 
-fn inactivate(textarea: &mut TextArea<'_>) {
-    textarea.set_cursor_line_style(Style::default());
-    textarea.set_cursor_style(Style::default());
-    textarea.set_block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::DarkGray))
-            .title(" Inactive (^X to switch) "),
-    );
-}
-
-fn activate(textarea: &mut TextArea<'_>) {
-    textarea.set_cursor_line_style(Style::default().add_modifier(Modifier::UNDERLINED));
-    textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-    textarea.set_block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default())
-            .title(" Active "),
-    );
-}
-
 fn main() -> io::Result<()> {
     let stdout = io::stdout();
     let mut stdout = stdout.lock();
@@ -44,9 +22,23 @@ fn main() -> io::Result<()> {
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref());
 
+    for textarea in &mut textarea {
+        textarea.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default())
+                .title(" Active "),
+        );
+        textarea.set_block_unfocused(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::DarkGray))
+                .title(" Inactive (^X to switch) "),
+        );
+        textarea.set_ignore_input_when_unfocused(true);
+    }
     let mut which = 0;
-    activate(&mut textarea[0]);
-    inactivate(&mut textarea[1]);
+    textarea[1].set_focus(false);
 
     loop {
         term.draw(|f| {
@@ -63,12 +55,14 @@ fn main() -> io::Result<()> {
                 ctrl: true,
                 ..
             } => {
-                inactivate(&mut textarea[which]);
+                textarea[which].set_focus(false);
                 which = (which + 1) % 2;
-                activate(&mut textarea[which]);
+                textarea[which].set_focus(true);
             }
             input => {
-                textarea[which].input(input);
+                for textarea in &mut textarea {
+                    textarea.input(input.clone());
+                }
             }
         }
     }
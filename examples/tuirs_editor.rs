@@ -15,7 +15,7 @@ use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph};
 use tui::Terminal;
-use tui_textarea::{CursorMove, Input, Key, TextArea};
+use tui_textarea::{CursorMove, IndentKind, Input, Key, TextArea};
 
 macro_rules! error {
     ($fmt: expr $(, $args:tt)*) => {{
@@ -98,8 +98,10 @@ impl<'a> Buffer<'a> {
                 let mut textarea: TextArea = io::BufReader::new(fs::File::open(&path)?)
                     .lines()
                     .collect::<io::Result<_>>()?;
-                if textarea.lines().iter().any(|l| l.starts_with('\t')) {
-                    textarea.set_hard_tab_indent(true);
+                match textarea.detect_indent() {
+                    IndentKind::Tabs => textarea.set_hard_tab_indent(true),
+                    IndentKind::Spaces(width) => textarea.set_tab_length(width),
+                    IndentKind::Unknown => {}
                 }
                 textarea
             } else {
@@ -0,0 +1,119 @@
+use crate::storage::Lines;
+use crate::util::Pos;
+
+const PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+fn bracket_at(line: &str, col: usize) -> Option<(usize, usize, char)> {
+    let (offset, c) = line.char_indices().nth(col)?;
+    PAIRS
+        .iter()
+        .any(|&(o, c2)| c == o || c == c2)
+        .then_some((col, offset, c))
+}
+
+/// Find the bracket matching with the bracket under the cursor, or immediately before the cursor when no bracket is
+/// under it. Nested pairs of the same kind of brackets are skipped. At most `limit` lines are searched, including the
+/// line of the cursor. `None` is returned when the bracket has no pair within the limit.
+pub fn find_matching_bracket(lines: &Lines, (row, col): (usize, usize), limit: usize) -> Option<Pos> {
+    let line = lines.line(row);
+    let (col, offset, c) = bracket_at(&line, col).or_else(|| bracket_at(&line, col.checked_sub(1)?))?;
+
+    if let Some(&(open, close)) = PAIRS.iter().find(|(o, _)| *o == c) {
+        let mut depth = 0usize;
+        let end = lines.len().min(row.saturating_add(limit));
+        for r in row..end {
+            // Search the cursor line after the bracket
+            let (start_col, start_offset) = if r == row { (col + 1, offset + 1) } else { (0, 0) };
+            for (i, (o, c)) in lines.line(r)[start_offset..].char_indices().enumerate() {
+                if c == open {
+                    depth += 1;
+                } else if c == close {
+                    if depth == 0 {
+                        return Some(Pos::new(r, start_col + i, start_offset + o));
+                    }
+                    depth -= 1;
+                }
+            }
+        }
+    } else {
+        let &(open, close) = PAIRS.iter().find(|(_, c2)| *c2 == c)?;
+        let mut depth = 0usize;
+        let end = (row + 1).saturating_sub(limit);
+        for r in (end..=row).rev() {
+            // Search the cursor line before the bracket
+            let other;
+            let text = if r == row {
+                &line[..offset]
+            } else {
+                other = lines.line(r);
+                &other
+            };
+            let mut col = text.chars().count();
+            for (o, c) in text.char_indices().rev() {
+                col -= 1;
+                if c == close {
+                    depth += 1;
+                } else if c == open {
+                    if depth == 0 {
+                        return Some(Pos::new(r, col, o));
+                    }
+                    depth -= 1;
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(lines: &[&str], cursor: (usize, usize), limit: usize) -> Option<(usize, usize, usize)> {
+        let lines = Lines::from(lines.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        find_matching_bracket(&lines, cursor, limit).map(|p| (p.row, p.col, p.offset))
+    }
+
+    #[test]
+    fn matching_bracket() {
+        #[rustfmt::skip]
+        let tests = [
+            // (lines, cursor, (row, col, offset) of matching bracket)
+            (&["(a)"][..], (0, 0), Some((0, 2, 2))),
+            (&["(a)"], (0, 2), Some((0, 0, 0))),
+            (&["(a)"], (0, 3), Some((0, 0, 0))),
+            (&["(a)"], (0, 1), Some((0, 2, 2))),
+            (&["a(b)"], (0, 0), None),
+            (&["([{}])"], (0, 1), Some((0, 4, 4))),
+            (&["(()())"], (0, 0), Some((0, 5, 5))),
+            (&["(()())"], (0, 5), Some((0, 0, 0))),
+            (&["(()())"], (0, 3), Some((0, 4, 4))),
+            (&["([)]"], (0, 0), Some((0, 2, 2))),
+            (&["{", "  ()", "}"], (0, 0), Some((2, 0, 0))),
+            (&["{", "  ()", "}"], (2, 0), Some((0, 0, 0))),
+            (&["{", "  ()", "}"], (2, 1), Some((0, 0, 0))),
+            (&["あ(い", "🐶)"], (0, 1), Some((1, 1, 4))),
+            (&["あ(い", "🐶)"], (1, 1), Some((0, 1, 3))),
+            (&["(("], (0, 0), None),
+            (&["))"], (0, 1), None),
+            (&[""], (0, 0), None),
+        ];
+
+        for test in tests {
+            let (lines, cursor, want) = test;
+            assert_eq!(find(lines, cursor, 100), want, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn matching_bracket_limit() {
+        let lines = ["(", "", ")"];
+        assert_eq!(find(&lines, (0, 0), 3), Some((2, 0, 0)));
+        assert_eq!(find(&lines, (0, 0), 2), None);
+        assert_eq!(find(&lines, (2, 0), 3), Some((0, 0, 0)));
+        assert_eq!(find(&lines, (2, 0), 2), None);
+        assert_eq!(find(&lines, (0, 0), 0), None);
+        assert_eq!(find(&lines, (0, 0), usize::MAX), Some((2, 0, 0)));
+    }
+}
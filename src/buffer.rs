@@ -0,0 +1,201 @@
+use crate::storage::TextStorage;
+use std::borrow::Cow;
+use std::io::{self, BufRead};
+use std::iter;
+use std::ops::Range;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+enum Line {
+    // Range of the line in the buffer, excluding the line ending
+    Loaded(Range<usize>),
+    // Line copied out of the buffer on editing it
+    Edited(String),
+}
+
+/// Text read from a reader and kept in one buffer. Each line is a range of the buffer, so loading the text doesn't
+/// allocate a `String` per line. A line is copied into its own `String` when it is edited for the first time, and the
+/// other lines stay in the buffer. The buffer is shared by clones.
+#[derive(Clone, Debug)]
+pub struct BufferStorage {
+    buf: Arc<String>,
+    lines: Vec<Line>,
+}
+
+/// Line endings found while reading the text.
+#[derive(Clone, Default, Debug)]
+pub struct LineEndings {
+    pub lf: usize,
+    pub crlf: usize,
+    pub crlf_rows: Vec<bool>, // Whether the line ending after each line is `\r\n`, except for the last line
+}
+
+impl BufferStorage {
+    /// Read the text from the reader. The lines are split while the reader fills the buffer, so the text is scanned only
+    /// once. An error is returned when the text is not valid UTF-8.
+    pub fn read(mut reader: impl BufRead) -> io::Result<(Self, LineEndings)> {
+        let mut buf = Vec::new();
+        let mut lines = Vec::new();
+        let mut endings = LineEndings::default();
+        let mut start = 0;
+        loop {
+            let chunk = reader.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            let len = chunk.len();
+            let offset = buf.len();
+            buf.extend_from_slice(chunk);
+            reader.consume(len);
+
+            for (i, &b) in buf[offset..].iter().enumerate() {
+                if b != b'\n' {
+                    continue;
+                }
+                let newline = offset + i;
+                let crlf = newline > start && buf[newline - 1] == b'\r';
+                let end = if crlf {
+                    endings.crlf += 1;
+                    newline - 1
+                } else {
+                    endings.lf += 1;
+                    newline
+                };
+                endings.crlf_rows.push(crlf);
+                lines.push(Line::Loaded(start..end));
+                start = newline + 1;
+            }
+        }
+        lines.push(Line::Loaded(start..buf.len()));
+
+        let buf = String::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let storage = Self { buf: Arc::new(buf), lines };
+        Ok((storage, endings))
+    }
+
+    /// Size of the buffer in bytes.
+    pub fn buffer_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    // Copy the line out of the buffer to edit it
+    fn edit(&mut self, row: usize) -> &mut String {
+        let line = &mut self.lines[row];
+        if let Line::Loaded(range) = line {
+            *line = Line::Edited(self.buf[range.clone()].to_string());
+        }
+        match line {
+            Line::Edited(s) => s,
+            Line::Loaded(_) => unreachable!(),
+        }
+    }
+}
+
+impl TextStorage for BufferStorage {
+    fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn line(&self, row: usize) -> Cow<'_, str> {
+        match &self.lines[row] {
+            Line::Loaded(range) => Cow::Borrowed(&self.buf[range.clone()]),
+            Line::Edited(s) => Cow::Borrowed(s),
+        }
+    }
+
+    fn chunks(&self, row: usize, range: Range<usize>) -> Box<dyn Iterator<Item = &str> + '_> {
+        let line = match &self.lines[row] {
+            Line::Loaded(r) => &self.buf[r.clone()],
+            Line::Edited(s) => s,
+        };
+        Box::new(iter::once(&line[range]))
+    }
+
+    fn insert_str(&mut self, row: usize, offset: usize, s: &str) {
+        self.edit(row).insert_str(offset, s);
+    }
+
+    fn remove_str(&mut self, row: usize, range: Range<usize>) -> String {
+        self.edit(row).drain(range).collect()
+    }
+
+    fn split_line(&mut self, row: usize, offset: usize) {
+        // A line in the buffer is split into two ranges without copying it
+        let next = match &mut self.lines[row] {
+            Line::Loaded(range) => {
+                let next = range.start + offset..range.end;
+                range.end = next.start;
+                Line::Loaded(next)
+            }
+            Line::Edited(s) => Line::Edited(s.split_off(offset)),
+        };
+        self.lines.insert(row + 1, next);
+    }
+
+    fn join_line(&mut self, row: usize) {
+        let next = self.lines.remove(row + 1);
+        let next = match &next {
+            Line::Loaded(range) => &self.buf[range.clone()],
+            Line::Edited(s) => s.as_str(),
+        };
+        let mut line = self.line(row).into_owned();
+        line.push_str(next);
+        self.lines[row] = Line::Edited(line);
+    }
+
+    fn splice(&mut self, rows: Range<usize>, lines: Vec<String>) -> Vec<String> {
+        let replaced = rows.clone().map(|row| self.line(row).into_owned()).collect();
+        self.lines.splice(rows, lines.into_iter().map(Line::Edited));
+        debug_assert!(!self.lines.is_empty(), "no line after splice");
+        replaced
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        match &self.lines[row] {
+            Line::Loaded(range) => range.len(),
+            Line::Edited(s) => s.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_buffer() {
+        let text = "a\r\nbc\n\r\n\rd\r";
+        for capacity in [1, 2, 64] {
+            let reader = io::BufReader::with_capacity(capacity, text.as_bytes());
+            let (buffer, endings) = BufferStorage::read(reader).unwrap();
+            assert_eq!(buffer.buffer_len(), text.len());
+            assert_eq!((endings.lf, endings.crlf), (1, 2), "{capacity}");
+            assert_eq!(endings.crlf_rows, [true, false, true], "{capacity}");
+            let lines: Vec<_> = (0..buffer.len()).map(|row| buffer.line(row)).collect();
+            assert_eq!(lines, ["a", "bc", "", "\rd\r"], "{capacity}");
+        }
+
+        let (buffer, endings) = BufferStorage::read(io::empty()).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.line(0), "");
+        assert_eq!((endings.lf, endings.crlf), (0, 0));
+        assert!(endings.crlf_rows.is_empty());
+    }
+
+    #[test]
+    fn edit_only_touched_lines() {
+        let (mut buffer, _) = BufferStorage::read("abc\ndef\nghi".as_bytes()).unwrap();
+        buffer.split_line(0, 1);
+        assert!(matches!(buffer.lines[..2], [Line::Loaded(_), Line::Loaded(_)]));
+        buffer.insert_str(3, 0, "x");
+        assert!(matches!(
+            buffer.lines[..],
+            [Line::Loaded(_), Line::Loaded(_), Line::Loaded(_), Line::Edited(_)]
+        ));
+        assert_eq!(buffer.line(1), "bc");
+        assert_eq!(buffer.line(3), "xghi");
+
+        let clone = buffer.clone();
+        assert!(Arc::ptr_eq(&clone.buf, &buffer.buf));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::cursor::CursorMove;
+use crate::ratatui::style::Style;
+use crate::ratatui::widgets::Block;
+use crate::textarea::TextArea;
+
+/// A builder to construct a configured [`TextArea`] in one expression. Created by [`TextArea::builder`].
+///
+/// Each method is the same as the corresponding `set_*` method of [`TextArea`]. Configurations which are not set by the
+/// builder have the same values as [`TextArea::default`].
+/// ```
+/// use ratatui::style::{Color, Style};
+/// use ratatui::widgets::{Block, Borders};
+/// use tui_textarea::TextArea;
+///
+/// let textarea = TextArea::builder()
+///     .lines(["hello", "world"])
+///     .cursor((1, 3))
+///     .block(Block::default().borders(Borders::ALL).title("Editor"))
+///     .cursor_line_style(Style::default())
+///     .line_number_style(Style::default().fg(Color::DarkGray))
+///     .placeholder("Enter text...")
+///     .tab_length(2)
+///     .build();
+///
+/// assert_eq!(textarea.lines(), ["hello", "world"]);
+/// assert_eq!(textarea.cursor(), (1, 3));
+/// assert!(textarea.block().is_some());
+/// assert_eq!(textarea.cursor_line_style(), Style::default());
+/// assert_eq!(textarea.line_number_style(), Some(Style::default().fg(Color::DarkGray)));
+/// assert_eq!(textarea.placeholder_text(), "Enter text...");
+/// assert_eq!(textarea.tab_length(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TextAreaBuilder<'a> {
+    textarea: TextArea<'a>,
+    lines: Option<Vec<String>>,
+    cursor: Option<(usize, usize)>,
+}
+
+impl<'a> TextAreaBuilder<'a> {
+    /// Set the initial text lines. By default, the text is empty.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::builder().lines(["hello", "world"]).build();
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
+    /// assert!(!textarea.is_modified());
+    /// ```
+    pub fn lines<I>(mut self, lines: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.lines = Some(lines.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the initial `(row, col)` position of the cursor. The position is clamped to the text. By default, the cursor
+    /// is at the beginning of the text.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::builder().lines(["abc"]).cursor((0, 10)).build();
+    /// assert_eq!(textarea.cursor(), (0, 3));
+    /// ```
+    pub fn cursor(mut self, cursor: (usize, usize)) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Set the block of the textarea. See [`TextArea::set_block`].
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.textarea.set_block(block);
+        self
+    }
+
+    /// Set the style of the textarea. See [`TextArea::set_style`].
+    pub fn style(mut self, style: Style) -> Self {
+        self.textarea.set_style(style);
+        self
+    }
+
+    /// Set the style of the cursor. See [`TextArea::set_cursor_style`].
+    pub fn cursor_style(mut self, style: Style) -> Self {
+        self.textarea.set_cursor_style(style);
+        self
+    }
+
+    /// Set the style of the cursor line. See [`TextArea::set_cursor_line_style`].
+    pub fn cursor_line_style(mut self, style: Style) -> Self {
+        self.textarea.set_cursor_line_style(style);
+        self
+    }
+
+    /// Set the style of the selection. See [`TextArea::set_selection_style`].
+    pub fn selection_style(mut self, style: Style) -> Self {
+        self.textarea.set_selection_style(style);
+        self
+    }
+
+    /// Set the placeholder text. See [`TextArea::set_placeholder_text`].
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.textarea.set_placeholder_text(placeholder);
+        self
+    }
+
+    /// Set the style of the placeholder text. See [`TextArea::set_placeholder_style`].
+    pub fn placeholder_style(mut self, style: Style) -> Self {
+        self.textarea.set_placeholder_style(style);
+        self
+    }
+
+    /// Show line numbers with the style. See [`TextArea::set_line_number_style`].
+    pub fn line_number_style(mut self, style: Style) -> Self {
+        self.textarea.set_line_number_style(style);
+        self
+    }
+
+    /// Set the length of tab character. See [`TextArea::set_tab_length`].
+    pub fn tab_length(mut self, len: u8) -> Self {
+        self.textarea.set_tab_length(len);
+        self
+    }
+
+    /// Enable or disable hard tab indentation. See [`TextArea::set_hard_tab_indent`].
+    pub fn hard_tab_indent(mut self, enabled: bool) -> Self {
+        self.textarea.set_hard_tab_indent(enabled);
+        self
+    }
+
+    /// Store the text in a rope. See [`TextArea::set_rope`].
+    #[cfg(feature = "rope")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rope")))]
+    pub fn rope(mut self, enabled: bool) -> Self {
+        self.textarea.set_rope(enabled);
+        self
+    }
+
+    /// Set the maximum number of undo histories. See [`TextArea::set_max_histories`].
+    pub fn max_histories(mut self, max: usize) -> Self {
+        self.textarea.set_max_histories(max);
+        self
+    }
+
+    /// Enable or disable soft-wrapping lines. See [`TextArea::set_wrap`].
+    pub fn wrap(mut self, enabled: bool) -> Self {
+        self.textarea.set_wrap(enabled);
+        self
+    }
+
+    /// Set the textarea read-only. See [`TextArea::set_read_only`].
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.textarea.set_read_only(read_only);
+        self
+    }
+
+    /// Enable or disable single-line mode. See [`TextArea::set_single_line`]. Multiple lines set by
+    /// [`TextAreaBuilder::lines`] are joined into one line.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::builder().single_line(true).lines(["hello", "world"]).build();
+    /// assert!(textarea.is_single_line());
+    /// assert_eq!(textarea.lines(), ["hello world"]);
+    /// ```
+    pub fn single_line(mut self, single_line: bool) -> Self {
+        self.textarea.set_single_line(single_line);
+        self
+    }
+
+    /// Build the [`TextArea`] instance.
+    pub fn build(self) -> TextArea<'a> {
+        let mut textarea = self.textarea;
+        if let Some(lines) = self.lines {
+            textarea.set_initial_lines(lines);
+        }
+        if let Some((row, col)) = self.cursor {
+            textarea.move_cursor_without_jump(CursorMove::Jump(row as u64, col as u64));
+        }
+        textarea
+    }
+}
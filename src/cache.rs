@@ -0,0 +1,168 @@
+use crate::highlight::WhitespaceStyle;
+use crate::history::TextEdit;
+use crate::ratatui::layout::Alignment;
+use crate::ratatui::style::Style;
+#[cfg(feature = "search")]
+use crate::search::{SearchMode, SearchOptions};
+use crate::sign::Sign;
+#[cfg(feature = "search")]
+use crate::util::MatchPos;
+#[cfg(feature = "ratatui")]
+use ratatui::text::Line;
+use std::collections::HashMap;
+#[cfg(feature = "search")]
+use std::ops::Range;
+#[cfg(feature = "tuirs")]
+use tui::text::Spans as Line;
+
+/// Settings of the textarea which affect the spans of every line. When any of them changes, all cached lines are
+/// discarded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSettings {
+    pub cursor_style: Style,
+    pub secondary_cursor_style: Style,
+    pub cursor_line_style: Style,
+    pub cursor_line_style_in_selection: Option<Style>,
+    pub select_style: Style,
+    pub line_number_style: Option<Style>,
+    pub relative_line_numbers: bool,
+    pub lnum_len: u8,
+    pub sign_column: bool,
+    pub tab_len: u8,
+    pub mask: Option<char>,
+    pub alignment: Alignment,
+    pub whitespace: Option<WhitespaceStyle>,
+    pub indent_guides: Option<(char, Style)>,
+    pub rulers: Vec<(u16, Style)>,
+    pub control_chars: Option<Style>,
+    pub link_style: Option<Style>,
+    pub matching_bracket_style: Option<Style>,
+    pub yank_flash_style: Option<Style>,
+    #[cfg(feature = "search")]
+    pub search: Option<(String, SearchOptions, SearchMode, Style, Style)>,
+    #[cfg(feature = "search")]
+    pub search_scope: Option<((usize, usize), (usize, usize))>,
+    #[cfg(feature = "search")]
+    pub search_rows: Option<Range<usize>>, // Rows where multi-line matches were searched
+    pub text_edits: Option<u64>, // Edits of the whole text when spans depend on the surrounding lines
+    pub hidden: usize,
+    pub max_width: usize,
+}
+
+/// State of the textarea which affects the spans of one line. Highlights calculated from the text of the line, such
+/// as line styles, links, and search matches, are not included since they are rebuilt when the line is edited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineKey {
+    pub gutter: Option<(usize, bool)>, // Number in the gutter and if it is dimmed
+    pub sign: Option<Sign>,
+    pub cursor: Option<usize>,
+    pub secondary_cursors: Vec<usize>,
+    pub secondary_selections: Vec<(usize, usize, usize, usize)>,
+    pub ghost: bool, // The cursor cell is covered by ghost text
+    #[cfg(feature = "search")]
+    pub current_match: Option<MatchPos>,
+    pub selection: Option<(usize, usize, usize, usize)>,
+    pub yank_flash: Vec<(usize, usize)>,
+    pub bracket: Option<usize>,
+    pub reveal: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedLine {
+    generation: u64,
+    key: LineKey,
+    spans: Line<'static>,
+}
+
+/// Spans of lines built on the previous rendering. Only lines rendered in the last frame are kept.
+///
+/// Each line has a generation which is renewed when the line is edited, so a cached line is reused only while its
+/// generation is unchanged. Generations follow edits of the text as [`TextEdit`]s once the first frame is rendered.
+#[derive(Debug, Clone, Default)]
+pub struct RenderCache {
+    settings: Option<RenderSettings>,
+    lines: HashMap<usize, CachedLine>,
+    rendered: HashMap<usize, CachedLine>,
+    generations: Vec<u64>, // Generation of each line
+    next_generation: u64,
+    edits: u64, // Number of edits followed
+}
+
+impl RenderCache {
+    /// Returns `true` when no frame has been rendered yet. Edits don't need to be followed until then.
+    pub fn is_empty(&self) -> bool {
+        self.generations.is_empty()
+    }
+
+    /// Number of the edits of the text followed since the cache was cleared.
+    pub fn edits(&self) -> u64 {
+        self.edits
+    }
+
+    /// Renew the generations of the lines replaced by the edit.
+    pub fn on_edit(&mut self, edit: &TextEdit) {
+        if self.is_empty() {
+            return;
+        }
+        if edit.end.0 >= self.generations.len() {
+            self.generations.clear(); // Renew all generations on the next frame
+            return;
+        }
+        let inserted = edit.inserted.matches('\n').count() + 1;
+        let start = self.next_generation;
+        self.next_generation += inserted as u64;
+        let replaced = edit.start.0..edit.end.0 + 1;
+        self.generations.splice(replaced, start..self.next_generation);
+        self.edits += 1;
+    }
+
+    /// Start rendering a frame of the text with `lines` lines. The cache is cleared when the settings were changed
+    /// since the previous frame.
+    pub fn begin(&mut self, settings: RenderSettings, lines: usize) {
+        if self.generations.len() != lines {
+            // Edits were not followed yet
+            self.generations = (self.next_generation..self.next_generation + lines as u64).collect();
+            self.next_generation += lines as u64;
+            self.lines.clear();
+        }
+        if self.settings.as_ref() != Some(&settings) {
+            self.lines.clear();
+            self.settings = Some(settings);
+        }
+        // Lines not rendered in the previous frame were already dropped
+        self.rendered.clear();
+    }
+
+    /// Get the spans of the line at the row built with the same key while the line is not edited, or build them with
+    /// `build`. Even on a hit the spans are cloned since the widget moves them into the paragraph while the cache keeps
+    /// them for the next frame.
+    pub fn get_or_build(&mut self, row: usize, key: LineKey, build: impl FnOnce() -> Line<'static>) -> Line<'static> {
+        let generation = self.generations[row];
+        let cached = match self.lines.remove(&row) {
+            Some(c) if c.generation == generation && c.key == key => c,
+            _ => CachedLine {
+                generation,
+                key,
+                spans: build(),
+            },
+        };
+        let spans = cached.spans.clone();
+        self.rendered.insert(row, cached);
+        spans
+    }
+
+    /// Finish rendering a frame. Lines not rendered in this frame are dropped.
+    pub fn end(&mut self) {
+        std::mem::swap(&mut self.lines, &mut self.rendered);
+        self.rendered.clear();
+    }
+
+    /// Drop the cached spans of the line at the row.
+    pub fn remove(&mut self, row: usize) {
+        self.lines.remove(&row);
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
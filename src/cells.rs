@@ -0,0 +1,94 @@
+use crate::history::TextEdit;
+use crate::storage::Lines;
+use crate::util::char_width;
+use std::collections::BTreeMap;
+
+// Distance in bytes between the positions recorded in a line
+const STEP: usize = 4096;
+
+/// Position of a character in a line without soft-wrap. `width` is the display column where the character starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellPos {
+    pub offset: usize,
+    pub col: usize,
+    pub width: usize,
+}
+
+/// Settings which affect the display width of characters: the tab length, the mask character, and whether control
+/// characters are rendered in their notation.
+pub type CellSettings = (u8, Option<char>, bool);
+
+/// Display columns of positions in long lines. While walking a line from its start, the position of a character is
+/// recorded every `STEP` bytes, so that the next walk to a position in the line starts from the nearest recorded
+/// position before it instead of the start of the line. Recorded positions after an edit are discarded.
+#[derive(Clone, Debug, Default)]
+pub struct CellIndex {
+    settings: Option<CellSettings>,
+    rows: BTreeMap<usize, Vec<CellPos>>,
+}
+
+impl CellIndex {
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Discard the positions which are moved by the edit.
+    pub fn on_edit(&mut self, edit: &TextEdit) {
+        let (row, col) = edit.start;
+        if edit.start.0 != edit.end.0 || edit.inserted.contains('\n') {
+            // Rows after the edit are shifted
+            self.rows.split_off(&(row + 1));
+        }
+        if let Some(positions) = self.rows.get_mut(&row) {
+            // The position at the edit is kept since the text before it is not changed
+            let kept = positions.partition_point(|p| p.col <= col);
+            positions.truncate(kept);
+        }
+    }
+
+    /// Walk the characters of the line at the row with their positions until `f` returns `false`. The walk starts from
+    /// the last recorded position where `from` returns `true`, so `from` must be `true` for the positions before the
+    /// first character `f` needs to see. The position where the walk stopped, or the end of line, is returned.
+    pub fn walk(
+        &mut self,
+        lines: &Lines,
+        row: usize,
+        settings: CellSettings,
+        from: impl Fn(&CellPos) -> bool,
+        mut f: impl FnMut(CellPos, char) -> bool,
+    ) -> CellPos {
+        if self.settings != Some(settings) {
+            self.rows.clear();
+            self.settings = Some(settings);
+        }
+        let (tab_len, mask, control) = settings;
+        let positions = self.rows.entry(row).or_default();
+        let start = positions.partition_point(&from);
+        let mut pos = start.checked_sub(1).map_or(CellPos::default(), |i| positions[i]);
+        let mut next = positions.last().map_or(STEP, |p| p.offset + STEP);
+
+        let len = lines.line_len(row);
+        'walk: for chunk in lines.chunks(row, pos.offset..len) {
+            for c in chunk.chars() {
+                if pos.offset >= next {
+                    positions.push(pos);
+                    next = pos.offset + STEP;
+                }
+                if !f(pos, c) {
+                    break 'walk;
+                }
+                pos.offset += c.len_utf8();
+                pos.col += 1;
+                pos.width += char_width(c, pos.width, tab_len, mask, control);
+            }
+        }
+        if positions.is_empty() {
+            self.rows.remove(&row); // Short lines are not recorded
+        }
+        pos
+    }
+}
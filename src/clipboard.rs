@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Connection to the system clipboard. Connecting to the clipboard is deferred until it is actually used, and any
+/// failure (e.g. no display in a headless environment) is ignored so that the textarea falls back to its internal yank
+/// buffer.
+#[derive(Default)]
+pub struct Clipboard {
+    // Keep the connection open since clipboard contents may be lost when it is closed on some platforms like X11
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    fn connect(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.inner.is_none() {
+            self.inner = arboard::Clipboard::new().ok();
+        }
+        self.inner.as_mut()
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        if let Some(c) = self.connect() {
+            let _ = c.set_text(text);
+        }
+    }
+
+    pub fn get_text(&mut self) -> Option<String> {
+        self.connect()?.get_text().ok()
+    }
+}
+
+impl Clone for Clipboard {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("connected", &self.inner.is_some())
+            .finish()
+    }
+}
@@ -1,15 +1,24 @@
+use crate::bracket::find_matching_bracket;
+use crate::storage::Lines;
 use crate::widget::Viewport;
-use crate::word::{find_word_end_forward, find_word_start_backward};
+use crate::word::{
+    find_subword_end_after_spaces, find_subword_end_forward, find_subword_start_backward, find_word_end_forward,
+    find_word_start_backward,
+};
+use crate::wrap::Wrapper;
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
 /// Specify how to move the cursor.
 ///
 /// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future.
 #[non_exhaustive]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CursorMove {
     /// Move cursor forward by one character. When the cursor is at the end of line, it moves to the head of next line.
     /// ```
@@ -135,6 +144,64 @@ pub enum CursorMove {
     /// assert_eq!(textarea.cursor(), (0, 4));
     /// ```
     WordBack,
+    /// Move cursor forward by one sub-word. In addition to word boundaries of [`CursorMove::WordForward`], sub-word
+    /// boundary appears at underscores, between lower case and upper case letters, and between letters and digits. For
+    /// example `parseHTTPResponse_v2` consists of sub-words `parse`, `HTTP`, `Response`, `_`, `v`, `2`. Only ASCII
+    /// letters are split into sub-words. Other letters follow the word boundaries. When the cursor is at the end of
+    /// line, it moves to the head of next line.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["parseHTTPResponse_v2"]);
+    ///
+    /// textarea.move_cursor(CursorMove::SubWordForward);
+    /// assert_eq!(textarea.cursor(), (0, 5));
+    /// textarea.move_cursor(CursorMove::SubWordForward);
+    /// assert_eq!(textarea.cursor(), (0, 9));
+    /// textarea.move_cursor(CursorMove::SubWordForward);
+    /// assert_eq!(textarea.cursor(), (0, 17));
+    /// textarea.move_cursor(CursorMove::SubWordForward);
+    /// assert_eq!(textarea.cursor(), (0, 18));
+    /// ```
+    SubWordForward,
+    /// Move cursor backward by one sub-word. See [`CursorMove::SubWordForward`] for sub-word boundaries. When the cursor
+    /// is at the head of line, it moves to the end of previous line.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["parseHTTPResponse_v2"]);
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.move_cursor(CursorMove::SubWordBack);
+    /// assert_eq!(textarea.cursor(), (0, 19));
+    /// textarea.move_cursor(CursorMove::SubWordBack);
+    /// assert_eq!(textarea.cursor(), (0, 18));
+    /// textarea.move_cursor(CursorMove::SubWordBack);
+    /// assert_eq!(textarea.cursor(), (0, 17));
+    /// textarea.move_cursor(CursorMove::SubWordBack);
+    /// assert_eq!(textarea.cursor(), (0, 9));
+    /// ```
+    SubWordBack,
+    /// Move cursor to the end of the next sub-word skipping spaces and underscores before it. See
+    /// [`CursorMove::SubWordForward`] for sub-word boundaries. When the cursor is at the end of line, it moves to the
+    /// head of next line.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["get_userName() "]);
+    ///
+    /// textarea.move_cursor(CursorMove::SubWordEnd);
+    /// assert_eq!(textarea.cursor(), (0, 3));
+    /// textarea.move_cursor(CursorMove::SubWordEnd);
+    /// assert_eq!(textarea.cursor(), (0, 8));
+    /// textarea.move_cursor(CursorMove::SubWordEnd);
+    /// assert_eq!(textarea.cursor(), (0, 12));
+    /// textarea.move_cursor(CursorMove::SubWordEnd);
+    /// assert_eq!(textarea.cursor(), (0, 14));
+    /// textarea.move_cursor(CursorMove::SubWordEnd);
+    /// assert_eq!(textarea.cursor(), (0, 15));
+    /// ```
+    SubWordEnd,
     /// Move cursor down by one paragraph. Paragraph is a chunk of non-empty lines. Cursor moves to the first line of paragraph.
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
@@ -223,43 +290,138 @@ pub enum CursorMove {
     /// assert_eq!(textarea.cursor(), (7, 0));
     /// ```
     InViewport,
+    /// Move cursor to the bracket matching with the bracket under the cursor, or immediately before the cursor when no
+    /// bracket is under it. `()`, `[]` and `{}` are supported and nested pairs are skipped. The cursor does not move
+    /// when the bracket has no pair. The number of lines to search is limited by
+    /// [`crate::TextArea::set_matching_bracket_limit`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["fn f() {", "    g(h())", "}"]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 7));
+    ///
+    /// textarea.move_cursor(CursorMove::MatchingBracket);
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// textarea.move_cursor(CursorMove::MatchingBracket);
+    /// assert_eq!(textarea.cursor(), (0, 7));
+    ///
+    /// // Bracket immediately before the cursor
+    /// textarea.move_cursor(CursorMove::Jump(1, 10));
+    /// textarea.move_cursor(CursorMove::MatchingBracket);
+    /// assert_eq!(textarea.cursor(), (1, 5));
+    /// ```
+    MatchingBracket,
+    /// Move cursor to the first line visible in the viewport, like `H` command of Vim. The viewport is the one where
+    /// the textarea was rendered last. Before the first rendering, the cursor moves to the first line of the text.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// // Let's say the viewport displays line 5 to line 12
+    /// textarea.move_cursor(CursorMove::Jump(12, 0));
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// textarea.move_cursor(CursorMove::ViewportTop);
+    /// assert_eq!(textarea.cursor(), (5, 0));
+    /// ```
+    ViewportTop,
+    /// Move cursor to the line at the middle of lines visible in the viewport, like `M` command of Vim. When the text
+    /// is shorter than the viewport, the cursor moves to the middle of the text. Before the first rendering, the cursor
+    /// moves to the middle line of the text.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// // Let's say the viewport displays line 5 to line 12
+    /// textarea.move_cursor(CursorMove::Jump(12, 0));
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// textarea.move_cursor(CursorMove::ViewportMiddle);
+    /// assert_eq!(textarea.cursor(), (8, 0));
+    /// ```
+    ViewportMiddle,
+    /// Move cursor to the last line visible in the viewport, like `L` command of Vim. Before the first rendering, the
+    /// cursor moves to the last line of the text.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// // Let's say the viewport displays line 0 to line 7
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// textarea.move_cursor(CursorMove::ViewportBottom);
+    /// assert_eq!(textarea.cursor(), (7, 0));
+    /// ```
+    ViewportBottom,
 }
 
 impl CursorMove {
+    // Relative movements fail when the cursor cannot move. A failure stops replaying a macro
+    pub(crate) fn is_relative(&self) -> bool {
+        matches!(
+            self,
+            Self::Forward
+                | Self::Back
+                | Self::Up
+                | Self::Down
+                | Self::WordForward
+                | Self::WordBack
+                | Self::SubWordForward
+                | Self::SubWordBack
+                | Self::SubWordEnd
+                | Self::ParagraphForward
+                | Self::ParagraphBack
+        )
+    }
+
     pub(crate) fn next_cursor(
         &self,
         (row, col): (usize, usize),
-        lines: &[String],
+        lines: &Lines,
         viewport: &Viewport,
+        bracket_limit: usize,
     ) -> Option<(usize, usize)> {
         use CursorMove::*;
 
-        fn fit_col(col: usize, line: &str) -> usize {
-            cmp::min(col, line.chars().count())
-        }
+        let fit_col = |col: usize, row: usize| cmp::min(col, lines.char_count(row));
 
         match self {
-            Forward if col >= lines[row].chars().count() => (row + 1 < lines.len()).then(|| (row + 1, 0)),
+            Forward if col >= lines.char_count(row) => (row + 1 < lines.len()).then(|| (row + 1, 0)),
             Forward => Some((row, col + 1)),
             Back if col == 0 => {
                 let row = row.checked_sub(1)?;
-                Some((row, lines[row].chars().count()))
+                Some((row, lines.char_count(row)))
             }
             Back => Some((row, col - 1)),
             Up => {
                 let row = row.checked_sub(1)?;
-                Some((row, fit_col(col, &lines[row])))
+                Some((row, fit_col(col, row)))
             }
-            Down => Some((row + 1, fit_col(col, lines.get(row + 1)?))),
+            Down => (row + 1 < lines.len()).then(|| (row + 1, fit_col(col, row + 1))),
             Head => Some((row, 0)),
-            End => Some((row, lines[row].chars().count())),
-            Top => Some((0, fit_col(col, &lines[0]))),
+            End => Some((row, lines.char_count(row))),
+            Top => Some((0, fit_col(col, 0))),
             Bottom => {
                 let row = lines.len() - 1;
-                Some((row, fit_col(col, &lines[row])))
+                Some((row, fit_col(col, row)))
             }
             WordForward => {
-                if let Some(col) = find_word_end_forward(&lines[row], col) {
+                if let Some(col) = find_word_end_forward(&lines.line(row), col) {
                     Some((row, col))
                 } else if row == lines.len() - 1 {
                     Some((row, col))
@@ -268,42 +430,65 @@ impl CursorMove {
                 }
             }
             WordBack => {
-                if let Some(col) = find_word_start_backward(&lines[row], col) {
+                if let Some(col) = find_word_start_backward(&lines.line(row), col) {
+                    Some((row, col))
+                } else if row == 0 {
+                    Some((row, col))
+                } else {
+                    Some((row - 1, lines.char_count(row - 1)))
+                }
+            }
+            SubWordForward | SubWordEnd => {
+                let line = lines.line(row);
+                let found = if *self == SubWordEnd {
+                    find_subword_end_after_spaces(&line, col)
+                } else {
+                    find_subword_end_forward(&line, col)
+                };
+                if let Some(col) = found {
+                    Some((row, col))
+                } else if row == lines.len() - 1 {
+                    Some((row, col))
+                } else {
+                    Some((row + 1, 0))
+                }
+            }
+            SubWordBack => {
+                if let Some(col) = find_subword_start_backward(&lines.line(row), col) {
                     Some((row, col))
                 } else if row == 0 {
                     Some((row, col))
                 } else {
-                    Some((row - 1, lines[row - 1].chars().count()))
+                    Some((row - 1, lines.char_count(row - 1)))
                 }
             }
             ParagraphForward => {
-                let mut prev_is_empty = lines[row].is_empty();
+                let mut prev_is_empty = lines.line_len(row) == 0;
                 for row in row + 1..lines.len() {
-                    let line = &lines[row];
-                    let is_empty = line.is_empty();
+                    let is_empty = lines.line_len(row) == 0;
                     if !is_empty && prev_is_empty {
-                        return Some((row, fit_col(col, line)));
+                        return Some((row, fit_col(col, row)));
                     }
                     prev_is_empty = is_empty;
                 }
                 let row = lines.len() - 1;
-                Some((row, fit_col(col, &lines[row])))
+                Some((row, fit_col(col, row)))
             }
             ParagraphBack => {
                 let row = row.checked_sub(1)?;
-                let mut prev_is_empty = lines[row].is_empty();
+                let mut prev_is_empty = lines.line_len(row) == 0;
                 for row in (0..row).rev() {
-                    let is_empty = lines[row].is_empty();
+                    let is_empty = lines.line_len(row) == 0;
                     if is_empty && !prev_is_empty {
-                        return Some((row + 1, fit_col(col, &lines[row + 1])));
+                        return Some((row + 1, fit_col(col, row + 1)));
                     }
                     prev_is_empty = is_empty;
                 }
-                Some((0, fit_col(col, &lines[0])))
+                Some((0, fit_col(col, 0)))
             }
             Jump(row, col) => {
                 let row = cmp::min(*row as usize, lines.len() - 1);
-                let col = fit_col(*col as usize, &lines[row]);
+                let col = fit_col(*col as usize, row);
                 Some((row, col))
             }
             InViewport => {
@@ -312,12 +497,129 @@ impl CursorMove {
                 let row = row.clamp(row_top as usize, row_bottom as usize);
                 let row = cmp::min(row, lines.len() - 1);
                 let col = col.clamp(col_top as usize, col_bottom as usize);
-                let col = fit_col(col, &lines[row]);
+                let col = fit_col(col, row);
 
                 Some((row, col))
             }
+            MatchingBracket => {
+                let pos = find_matching_bracket(lines, (row, col), bracket_limit)?;
+                Some((pos.row, pos.col))
+            }
+            ViewportTop | ViewportMiddle | ViewportBottom => {
+                let (_, _, _, height) = viewport.rect();
+                let last = lines.len() - 1;
+                let (top, bottom) = if height == 0 {
+                    (0, last) // Not rendered yet
+                } else {
+                    let (top, _, bottom, _) = viewport.position();
+                    let top = cmp::min(top as usize, last);
+                    (top, cmp::min(bottom as usize, last))
+                };
+                Some(self.viewport_cursor(top, bottom, col, lines))
+            }
+        }
+    }
+
+    // Cursor position moved by `ViewportTop`, `ViewportMiddle`, or `ViewportBottom` to the visible lines between `top`
+    // and `bottom` (inclusive)
+    fn viewport_cursor(&self, top: usize, bottom: usize, col: usize, lines: &Lines) -> (usize, usize) {
+        let row = match self {
+            CursorMove::ViewportTop => top,
+            CursorMove::ViewportMiddle => (top + bottom) / 2,
+            CursorMove::ViewportBottom => bottom,
+            _ => unreachable!("only viewport motions are supported"),
+        };
+        (row, cmp::min(col, lines.char_count(row)))
+    }
+
+    /// Calculate the next cursor position moving by display rows of soft-wrapped lines. Only `Up`, `Down`, and
+    /// the viewport motions depend on wrapping so other moves must use [`CursorMove::next_cursor`].
+    pub(crate) fn next_wrapped_cursor(
+        &self,
+        (row, col): (usize, usize),
+        lines: &Lines,
+        wrapper: &Wrapper,
+        viewport: &Viewport,
+    ) -> Option<(usize, usize)> {
+        if matches!(
+            self,
+            CursorMove::ViewportTop | CursorMove::ViewportMiddle | CursorMove::ViewportBottom
+        ) {
+            let (top, _, _, height) = viewport.rect();
+            let top = cmp::min(top as usize, lines.len() - 1);
+            // Find the last line which is visible at least partially
+            let mut remaining = height as usize + viewport.skip() as usize;
+            let mut bottom = top;
+            for i in top..lines.len() {
+                bottom = i;
+                let h = wrapper.height(&lines.line(i), false);
+                if remaining <= h {
+                    break;
+                }
+                remaining -= h;
+            }
+            return Some(self.viewport_cursor(top, bottom, col, lines));
+        }
+
+        let line = lines.line(row);
+        let (sub, offset) = wrapper.locate(&line, col);
+        match self {
+            CursorMove::Up if sub > 0 => Some((row, wrapper.col_at(&line, sub - 1, offset))),
+            CursorMove::Up => {
+                let row = row.checked_sub(1)?;
+                let line = lines.line(row);
+                let last = wrapper.height(&line, false) - 1;
+                Some((row, wrapper.col_at(&line, last, offset)))
+            }
+            CursorMove::Down if sub + 1 < wrapper.height(&line, false) => {
+                Some((row, wrapper.col_at(&line, sub + 1, offset)))
+            }
+            CursorMove::Down => Some((row + 1, wrapper.col_at(&lines.get(row + 1)?, 0, offset))),
+            _ => unreachable!("only Up, Down, and viewport motions depend on soft wrap"),
+        }
+    }
+}
+
+/// Parse a position typed in a "go to line" prompt. The result is a pair of a 1-based line number and an optional
+/// 1-based column number, which can be passed to [`TextArea::goto_line`](crate::TextArea::goto_line) as-is.
+///
+/// - `"42"` is the line 42
+/// - `"42:7"` is the column 7 of the line 42
+/// - `"+10"` and `"-10"` are 10 lines below and above the `current` line. The column can follow them like `"+10:7"`
+///
+/// `current` is the 1-based line number of the cursor. Spaces around the input are ignored. A relative line before
+/// the first line is 0. `None` is returned when the input is not in these forms.
+/// ```
+/// use tui_textarea::parse_position;
+///
+/// assert_eq!(parse_position("42", 1), Some((42, None)));
+/// assert_eq!(parse_position(" 42:7 ", 1), Some((42, Some(7))));
+/// assert_eq!(parse_position("+10", 5), Some((15, None)));
+/// assert_eq!(parse_position("-10:3", 15), Some((5, Some(3))));
+/// assert_eq!(parse_position("-10", 5), Some((0, None)));
+/// assert_eq!(parse_position("foo", 1), None);
+/// ```
+pub fn parse_position(input: &str, current: usize) -> Option<(usize, Option<usize>)> {
+    fn number(s: &str) -> Option<usize> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None; // `usize::from_str` accepts a leading `+`
         }
+        s.parse().ok()
     }
+
+    let input = input.trim();
+    let (line, col) = match input.split_once(':') {
+        Some((line, col)) => (line, Some(number(col)?)),
+        None => (input, None),
+    };
+    let line = if let Some(n) = line.strip_prefix('+') {
+        current.checked_add(number(n)?)?
+    } else if let Some(n) = line.strip_prefix('-') {
+        current.saturating_sub(number(n)?)
+    } else {
+        number(line)?
+    };
+    Some((line, col))
 }
 
 #[cfg(test)]
@@ -346,4 +648,34 @@ mod tests {
         textarea.move_cursor(CursorMove::InViewport);
         assert_eq!(textarea.cursor(), (7, 0));
     }
+
+    #[test]
+    fn parse_position() {
+        use super::parse_position;
+
+        let tests = [
+            ("1", Some((1, None))),
+            ("0", Some((0, None))),
+            ("12:34", Some((12, Some(34)))),
+            ("\t3:0 ", Some((3, Some(0)))),
+            ("+0", Some((10, None))),
+            ("+5:2", Some((15, Some(2)))),
+            ("-3", Some((7, None))),
+            ("-20", Some((0, None))),
+            ("", None),
+            (":3", None),
+            ("3:", None),
+            ("3:+1", None),
+            ("++1", None),
+            ("+-1", None),
+            ("1 :2", None),
+            ("1:2:3", None),
+            ("a", None),
+            ("99999999999999999999999", None),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(parse_position(input, 10), expected, "{input:?}");
+        }
+        assert_eq!(parse_position("+1", usize::MAX), None);
+    }
 }
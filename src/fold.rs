@@ -0,0 +1,175 @@
+use std::cmp;
+
+/// A range of lines folded into one display row. Set by [`crate::TextArea::fold`].
+///
+/// The first line of the fold is rendered with a suffix showing the number of the folded lines and the following lines
+/// are hidden.
+/// ```
+/// use tui_textarea::TextArea;
+///
+/// let mut textarea = TextArea::from(["fn f() {", "    a();", "}"]);
+/// textarea.fold(0, 2);
+///
+/// let fold = textarea.folds()[0];
+/// assert_eq!((fold.start, fold.end), (0, 2));
+/// assert_eq!(fold.lines(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fold {
+    /// Row of the first line of the fold. This line is still rendered.
+    pub start: usize,
+    /// Row of the last line of the fold. This is always greater than `start`.
+    pub end: usize,
+}
+
+impl Fold {
+    /// Number of lines in the fold including the first line.
+    pub fn lines(&self) -> usize {
+        self.end - self.start + 1
+    }
+
+    /// Check if the row is in the fold.
+    pub fn contains(&self, row: usize) -> bool {
+        self.start <= row && row <= self.end
+    }
+
+    // Check if the row is hidden by the fold. The first line is not hidden
+    pub(crate) fn hides(&self, row: usize) -> bool {
+        self.start < row && row <= self.end
+    }
+}
+
+// Display row of the row when rows hidden by the sorted folds are skipped. A hidden row is displayed at the first line
+// of its fold
+pub(crate) fn display_row(folds: &[Fold], row: usize) -> usize {
+    let mut hidden = 0;
+    for fold in folds {
+        if fold.start >= row {
+            break;
+        }
+        hidden += cmp::min(row, fold.end) - fold.start;
+    }
+    row - hidden
+}
+
+// Row displayed at the display row. This is the inverse of `display_row`
+pub(crate) fn row_at_display(folds: &[Fold], display: usize) -> usize {
+    let mut row = display;
+    for fold in folds {
+        if fold.start >= row {
+            break;
+        }
+        row += fold.end - fold.start;
+    }
+    row
+}
+
+// Ranges of blocks of lines which are more indented than the line before them. `indents` are the indentation widths of
+// lines where blank lines are `None`. Blank lines inside a block belong to it. Only blocks whose first lines are nested
+// at `level` or deeper are returned, and blocks inside the returned blocks are not since folds cannot overlap
+pub(crate) fn indent_folds(indents: &[Option<usize>], level: usize) -> Vec<Fold> {
+    // Nesting depth of each line counted by the indentation widths of its enclosing lines. This does not depend on the
+    // number of spaces per level so mixed indentation styles are nested correctly
+    let mut enclosing: Vec<usize> = vec![];
+    let depths: Vec<usize> = indents
+        .iter()
+        .map(|indent| {
+            let Some(indent) = *indent else {
+                return 0; // Blank lines never start a block
+            };
+            while enclosing.last().map_or(false, |&w| w >= indent) {
+                enclosing.pop();
+            }
+            let depth = enclosing.len();
+            enclosing.push(indent);
+            depth
+        })
+        .collect();
+
+    let mut folds = vec![];
+    let mut row = 0;
+    while row < indents.len() {
+        let Some(indent) = indents[row].filter(|_| depths[row] >= level) else {
+            row += 1;
+            continue;
+        };
+        // The block ends at the last non-blank line more indented than its first line
+        let mut end = row;
+        for (i, w) in indents.iter().enumerate().skip(row + 1) {
+            match w {
+                Some(w) if *w > indent => end = i,
+                Some(_) => break,
+                None => {}
+            }
+        }
+        if end > row {
+            folds.push(Fold { start: row, end });
+            row = end;
+        }
+        row += 1;
+    }
+    folds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_rows() {
+        let folds = [Fold { start: 1, end: 3 }, Fold { start: 5, end: 6 }];
+        // (row, display row)
+        let tests = [(0, 0), (1, 1), (2, 1), (3, 1), (4, 2), (5, 3), (6, 3), (7, 4), (8, 5)];
+        for (row, display) in tests {
+            assert_eq!(display_row(&folds, row), display, "{row}");
+            if !folds.iter().any(|f| f.hides(row)) {
+                assert_eq!(row_at_display(&folds, display), row, "{display}");
+            }
+        }
+        assert_eq!(display_row(&[], 3), 3);
+        assert_eq!(row_at_display(&[], 3), 3);
+    }
+
+    #[test]
+    fn indent_folds_by_level() {
+        // a:
+        //   b:
+        //     c
+        //
+        //     d
+        //   e
+        //
+        // f:
+        //	g (tab)
+        let indents = [
+            Some(0),
+            Some(2),
+            Some(4),
+            None,
+            Some(4),
+            Some(2),
+            None,
+            Some(0),
+            Some(4),
+        ];
+        let ranges = |level| {
+            indent_folds(&indents, level)
+                .into_iter()
+                .map(|f| (f.start, f.end))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges(0), [(0, 5), (7, 8)]);
+        assert_eq!(ranges(1), [(1, 4)]);
+        assert_eq!(ranges(2), []);
+        assert_eq!(indent_folds(&[], 0), []);
+
+        // Mixed indentation styles are nested by their widths
+        let indents = [Some(0), Some(4), Some(6), Some(1), Some(3)];
+        let ranges: Vec<_> = indent_folds(&indents, 1)
+            .into_iter()
+            .map(|f| (f.start, f.end))
+            .collect();
+        assert_eq!(ranges, [(1, 2), (3, 4)]);
+    }
+}
@@ -0,0 +1,247 @@
+// Scores of fuzzy matching. They follow the scoring of fzf: matched characters at word boundaries or in a row get
+// bonuses, and gaps between matched characters get penalties
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+const BONUS_BOUNDARY: i32 = SCORE_MATCH / 2;
+const BONUS_NON_WORD: i32 = SCORE_MATCH / 2;
+const BONUS_CAMEL123: i32 = BONUS_BOUNDARY + SCORE_GAP_EXTENSION;
+const BONUS_CONSECUTIVE: i32 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    NonWord,
+    Lower,
+    Upper,
+    Number,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        if c.is_uppercase() {
+            Self::Upper
+        } else if c.is_numeric() {
+            Self::Number
+        } else if c.is_alphabetic() {
+            Self::Lower // Letters without case are treated as lowercase letters
+        } else {
+            Self::NonWord
+        }
+    }
+
+    fn bonus(prev: Self, class: Self) -> i32 {
+        match (prev, class) {
+            (Self::NonWord, c) if c != Self::NonWord => BONUS_BOUNDARY,
+            (Self::Lower, Self::Upper) => BONUS_CAMEL123,
+            (p, Self::Number) if p != Self::Number => BONUS_CAMEL123,
+            (_, Self::NonWord) => BONUS_NON_WORD,
+            _ => 0,
+        }
+    }
+}
+
+fn lower(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+/// Characters of the query matched in a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    pub offsets: Vec<usize>, // Byte offsets of the matched characters in the line
+}
+
+impl FuzzyMatch {
+    /// Byte ranges of the matched characters. Characters in a row are merged into one range.
+    pub fn ranges(&self, line: &str) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = vec![];
+        for &offset in &self.offsets {
+            let end = offset + line[offset..].chars().next().map_or(0, char::len_utf8);
+            match ranges.last_mut() {
+                Some(last) if last.1 == offset => last.1 = end,
+                _ => ranges.push((offset, end)),
+            }
+        }
+        ranges
+    }
+
+    /// Byte range from the first matched character to the last one.
+    pub fn span(&self, line: &str) -> (usize, usize) {
+        let start = self.offsets[0];
+        let last = self.offsets[self.offsets.len() - 1];
+        let end = last + line[last..].chars().next().map_or(0, char::len_utf8);
+        (start, end)
+    }
+}
+
+/// Query of fuzzy matching. A line matches when it contains all characters of the query in the same order.
+#[derive(Clone, Debug)]
+pub struct Fuzzy {
+    query: Vec<char>,
+    ignore_case: bool,
+}
+
+impl Fuzzy {
+    pub fn new(query: &str, ignore_case: bool) -> Self {
+        let query = if ignore_case {
+            query.chars().map(lower).collect()
+        } else {
+            query.chars().collect()
+        };
+        Self { query, ignore_case }
+    }
+
+    fn eq(&self, c: char, q: char) -> bool {
+        if self.ignore_case {
+            lower(c) == q
+        } else {
+            c == q
+        }
+    }
+
+    /// Find the match in the line. The characters are matched in the shortest range after the first occurrence of the
+    /// query like fzf's v1 algorithm, which is linear to the length of the line.
+    pub fn find(&self, line: &str) -> Option<FuzzyMatch> {
+        if self.query.is_empty() {
+            return None;
+        }
+
+        // Find the end of the first occurrence
+        let mut chars = self.query.iter();
+        let mut q = chars.next();
+        let mut end = None;
+        for (i, c) in line.char_indices() {
+            if let Some(&qc) = q {
+                if self.eq(c, qc) {
+                    q = chars.next();
+                    if q.is_none() {
+                        end = Some(i);
+                        break;
+                    }
+                }
+            }
+        }
+        let end = end?;
+
+        // Find the characters backward from the end to make the match shortest
+        let mut offsets = vec![0; self.query.len()];
+        let mut remaining = self.query.len();
+        offsets[remaining - 1] = end;
+        remaining -= 1;
+        for (i, c) in line[..end].char_indices().rev() {
+            if remaining == 0 {
+                break;
+            }
+            if self.eq(c, self.query[remaining - 1]) {
+                remaining -= 1;
+                offsets[remaining] = i;
+            }
+        }
+
+        let score = self.score(line, &offsets);
+        Some(FuzzyMatch { score, offsets })
+    }
+
+    fn score(&self, line: &str, offsets: &[usize]) -> i32 {
+        let start = offsets[0];
+        let mut prev = line[..start]
+            .chars()
+            .next_back()
+            .map_or(CharClass::NonWord, CharClass::of);
+        let mut matched = offsets.iter().peekable();
+        let (mut score, mut in_gap, mut consecutive, mut first_bonus) = (0, false, 0, 0);
+        for (i, c) in line[start..].char_indices() {
+            let Some(&&next) = matched.peek() else {
+                break;
+            };
+            let class = CharClass::of(c);
+            if start + i == next {
+                matched.next();
+                let mut bonus = CharClass::bonus(prev, class);
+                if consecutive == 0 {
+                    first_bonus = bonus;
+                } else {
+                    // The bonus of the first character in a row is kept in the following characters
+                    if bonus >= BONUS_BOUNDARY && bonus > first_bonus {
+                        first_bonus = bonus;
+                    }
+                    bonus = bonus.max(first_bonus).max(BONUS_CONSECUTIVE);
+                }
+                score += SCORE_MATCH;
+                score += if i == 0 {
+                    bonus * BONUS_FIRST_CHAR_MULTIPLIER
+                } else {
+                    bonus
+                };
+                in_gap = false;
+                consecutive += 1;
+            } else {
+                score += if in_gap { SCORE_GAP_EXTENSION } else { SCORE_GAP_START };
+                in_gap = true;
+                consecutive = 0;
+                first_bonus = 0;
+            }
+            prev = class;
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_subsequence() {
+        let tests = [
+            // (query, line, ignore case, byte offsets of matched characters)
+            ("abc", "abc", false, Some(&[0, 1, 2][..])),
+            ("abc", "a_b_c", false, Some(&[0, 2, 4])),
+            ("abc", "acb", false, None),
+            ("abc", "xabxabcx", false, Some(&[4, 5, 6])),
+            ("ac", "abac", false, Some(&[2, 3])),
+            ("AB", "ab", false, None),
+            ("AB", "ab", true, Some(&[0, 1])),
+            ("ab", "AB", true, Some(&[0, 1])),
+            ("あい", "xあxい", false, Some(&[1, 5])),
+            ("", "abc", false, None),
+            ("a", "", false, None),
+        ];
+        for test in tests {
+            let (query, line, ignore_case, expected) = test;
+            let m = Fuzzy::new(query, ignore_case).find(line);
+            assert_eq!(m.as_ref().map(|m| m.offsets.as_slice()), expected, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn ranges_and_span() {
+        let line = "xabあxc";
+        let m = Fuzzy::new("abあc", false).find(line).unwrap();
+        assert_eq!(m.ranges(line), [(1, 6), (7, 8)]);
+        assert_eq!(m.span(line), (1, 8));
+    }
+
+    #[test]
+    fn score_order() {
+        let score = |query: &str, line: &str| Fuzzy::new(query, true).find(line).unwrap().score;
+        let tests = [
+            // (query, line with better score, line with worse score)
+            ("foo", "foo", "f_o_o"),
+            ("fb", "foo_bar", "xfxxbx"),
+            ("fb", "fooBar", "foobar"),
+            ("abc", "abc", "xabc"),
+            ("abc", "xx abc", "xxxabc"),
+            ("ab", "a-b", "a--b"),
+        ];
+        for test in tests {
+            let (query, better, worse) = test;
+            assert!(score(query, better) > score(query, worse), "{test:?}");
+        }
+    }
+}
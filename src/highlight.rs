@@ -1,43 +1,112 @@
-use crate::ratatui::{style::Style, text::Span};
-use crate::util::{num_digits, spaces};
+use crate::ratatui::style::{Color, Style};
+use crate::ratatui::text::Span;
+use crate::util::{char_width, control_char_notation, num_digits, spaces};
 #[cfg(feature = "ratatui")]
 use ratatui::text::Line;
 use std::borrow::Cow;
-use std::cmp::Ordering;
-use std::iter;
+use std::cmp::{max, min};
+use std::ops::Range;
 #[cfg(feature = "tuirs")]
 use tui::text::Spans as Line;
 use unicode_width::UnicodeWidthChar as _;
 
-enum Boundary {
-    Cursor(Style),
-    Select(Style),
+/// Layers of highlights. Where highlights overlap, the later layer is rendered: the selection over the cursor line,
+/// search matches over the selection, the current search match over other matches, the matching bracket over search
+/// matches, and the cursor over everything.
+#[derive(Clone, Copy)]
+enum Layer {
+    Select,
+    YankFlash,
+    #[cfg(feature = "search")]
+    Search,
     #[cfg(feature = "search")]
-    Search(Style),
-    End,
+    CurrentMatch,
+    Bracket,
+    Cursor,
+}
+
+const NUM_LAYERS: usize = Layer::Cursor as usize + 1;
+
+enum Boundary {
+    Start(Layer, Style),
+    End(Layer),
 }
 
 impl Boundary {
-    fn cmp(&self, other: &Boundary) -> Ordering {
-        fn rank(b: &Boundary) -> u8 {
-            match b {
-                Boundary::Cursor(_) => 3,
-                #[cfg(feature = "search")]
-                Boundary::Search(_) => 2,
-                Boundary::Select(_) => 1,
-                Boundary::End => 0,
-            }
-        }
-        rank(self).cmp(&rank(other))
+    // Highlights ending at an offset are closed before highlights starting at the same offset
+    fn is_start(&self) -> bool {
+        matches!(self, Boundary::Start(..))
     }
+}
 
-    fn style(&self) -> Option<Style> {
-        match self {
-            Boundary::Cursor(s) => Some(*s),
-            Boundary::Select(s) => Some(*s),
-            #[cfg(feature = "search")]
-            Boundary::Search(s) => Some(*s),
-            Boundary::End => None,
+/// Style of a range of the text set by [`crate::TextArea::set_highlights`], such as a diagnostic of a language server.
+/// Positions are pairs of (row, col) where `col` is counted in characters, and `end` is exclusive. A range can span
+/// multiple lines.
+/// ```
+/// use ratatui::style::{Color, Modifier, Style};
+/// use tui_textarea::Highlight;
+///
+/// let error = Highlight {
+///     start: (0, 4),
+///     end: (0, 8),
+///     style: Style::default().fg(Color::Red).add_modifier(Modifier::UNDERLINED),
+///     priority: 10,
+/// };
+/// assert!(error.contains((0, 4)));
+/// assert!(!error.contains((0, 8)));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Highlight {
+    /// Start position of the range.
+    pub start: (usize, usize),
+    /// End position (exclusive) of the range.
+    pub end: (usize, usize),
+    /// Style patched on the text in the range.
+    pub style: Style,
+    /// Where highlights overlap, the style of the higher priority is patched over the lower one.
+    pub priority: u8,
+}
+
+impl Highlight {
+    /// Return if the range contains the position.
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        self.start <= pos && pos < self.end
+    }
+}
+
+/// Glyphs and styles to visualize whitespaces on rendering. Set by [`crate::TextArea::set_show_whitespace`]. Glyphs
+/// should be one cell wide so that the layout of the text does not change.
+/// ```
+/// use ratatui::style::{Color, Style};
+/// use tui_textarea::WhitespaceStyle;
+///
+/// let ws = WhitespaceStyle {
+///     space: '␣',
+///     trailing_style: Style::default().bg(Color::Red),
+///     ..Default::default()
+/// };
+/// assert_eq!(ws.tab, '→');
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhitespaceStyle {
+    /// Glyph rendered for a space. The default value is `'·'`.
+    pub space: char,
+    /// Glyph rendered at the start of a tab. The rest of the tab is padded with spaces to the next tab stop. The default
+    /// value is `'→'`.
+    pub tab: char,
+    /// Style of whitespaces. The default value is dark gray foreground.
+    pub style: Style,
+    /// Style of whitespaces at the end of line. The default value is red foreground.
+    pub trailing_style: Style,
+}
+
+impl Default for WhitespaceStyle {
+    fn default() -> Self {
+        Self {
+            space: '·',
+            tab: '→',
+            style: Style::default().fg(Color::DarkGray),
+            trailing_style: Style::default().fg(Color::Red),
         }
     }
 }
@@ -46,17 +115,38 @@ struct DisplayTextBuilder {
     tab_len: u8,
     width: usize,
     mask: Option<char>,
+    reveal: Option<usize>,              // Byte offset of the character in the line not masked
+    whitespace: Option<(char, char)>,   // Glyphs of space and tab
+    guides: Option<(char, Vec<usize>)>, // Glyph of indent guides and byte offsets of the whitespaces where they are put
+    control: bool,                      // Render control characters in their notation
 }
 
 impl DisplayTextBuilder {
     fn new(tab_len: u8, mask: Option<char>) -> Self {
-        Self { tab_len, width: 0, mask }
+        Self {
+            tab_len,
+            width: 0,
+            mask,
+            reveal: None,
+            whitespace: None,
+            guides: None,
+            control: false,
+        }
     }
 
-    fn build<'s>(&mut self, s: &'s str) -> Cow<'s, str> {
+    fn is_guide(&self, offset: usize) -> Option<char> {
+        let (glyph, offsets) = self.guides.as_ref()?;
+        offsets.binary_search(&offset).is_ok().then_some(*glyph)
+    }
+
+    /// Build the display text of `s`, which starts at the byte offset `start` in the line.
+    fn build<'s>(&mut self, s: &'s str, start: usize) -> Cow<'s, str> {
         if let Some(ch) = self.mask {
             // Note: We don't need to track width on masking text since width of tab character is fixed
-            let masked = iter::repeat(ch).take(s.chars().count()).collect();
+            let masked = s
+                .char_indices()
+                .map(|(i, c)| if self.reveal == Some(start + i) { c } else { ch })
+                .collect();
             return Cow::Owned(masked);
         }
 
@@ -70,9 +160,38 @@ impl DisplayTextBuilder {
                 }
                 if self.tab_len > 0 {
                     let len = self.tab_len as usize - (self.width % self.tab_len as usize);
-                    buf.push_str(&tab[..len]);
+                    if let Some(glyph) = self.is_guide(start + i) {
+                        buf.push(glyph);
+                        buf.push_str(&tab[..len - 1]);
+                    } else if let Some((_, glyph)) = self.whitespace {
+                        buf.push(glyph);
+                        buf.push_str(&tab[..len - 1]);
+                    } else {
+                        buf.push_str(&tab[..len]);
+                    }
                     self.width += len;
                 }
+            } else if let Some(notation) = control_char_notation(c).filter(|_| self.control) {
+                if buf.is_empty() {
+                    buf.reserve(s.len() + notation.len());
+                    buf.push_str(&s[..i]);
+                }
+                buf.push_str(&notation);
+                self.width += notation.len();
+            } else if let (' ', Some(glyph)) = (c, self.is_guide(start + i)) {
+                if buf.is_empty() {
+                    buf.reserve(s.len());
+                    buf.push_str(&s[..i]);
+                }
+                buf.push(glyph);
+                self.width += 1;
+            } else if let (' ', Some((glyph, _))) = (c, self.whitespace) {
+                if buf.is_empty() {
+                    buf.reserve(s.len());
+                    buf.push_str(&s[..i]);
+                }
+                buf.push(glyph);
+                self.width += 1;
             } else {
                 if !buf.is_empty() {
                     buf.push(c);
@@ -89,8 +208,44 @@ impl DisplayTextBuilder {
     }
 }
 
+fn floor_char_boundary(s: &str, i: usize) -> usize {
+    let mut i = min(i, s.len());
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+fn ceil_char_boundary(s: &str, i: usize) -> usize {
+    let mut i = min(i, s.len());
+    while !s.is_char_boundary(i) {
+        i += 1;
+    }
+    i
+}
+
+/// Where the text given to [`LineHighlighter`] is in its whole line: the byte offset, the column, and the display column
+/// where the text starts, and the length of the whole line in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LinePart {
+    pub offset: usize,
+    pub col: usize,
+    pub width: usize,
+    pub len: usize,
+}
+
+impl LinePart {
+    pub fn whole(line: &str) -> Self {
+        Self {
+            len: line.len(),
+            ..Self::default()
+        }
+    }
+}
+
 pub struct LineHighlighter<'a> {
     line: &'a str,
+    part: LinePart,
     spans: Vec<Span<'a>>,
     boundaries: Vec<(Boundary, usize)>, // TODO: Consider smallvec
     style_begin: Style,
@@ -100,12 +255,26 @@ pub struct LineHighlighter<'a> {
     mask: Option<char>,
     select_at_end: bool,
     select_style: Style,
+    wrap_breaks: Vec<usize>,
+    wrap_prefix: Vec<Span<'a>>,
+    base_styles: Vec<(usize, usize, Style)>, // Sorted and not overlapping
+    reveal: Option<usize>,
+    whitespace: Option<(char, char)>,
+    guides: Option<(char, Vec<usize>)>,
+    control: bool,
+    virtual_guides: Option<(String, Style)>, // Guides after the end of line and their style
+    rulers: Vec<(usize, Style)>,             // Sorted display columns of rulers and their styles
+    line_width: usize,
+    hidden_end: usize, // Number of cells after the end of line scrolled out
+    clip: Option<usize>,
+    scroll: Option<(usize, usize, usize)>, // Byte offset and display width where the text starts, and padding
 }
 
 impl<'a> LineHighlighter<'a> {
     pub fn new(line: &'a str, cursor_style: Style, tab_len: u8, mask: Option<char>, select_style: Style) -> Self {
         Self {
             line,
+            part: LinePart::whole(line),
             spans: vec![],
             boundaries: vec![],
             style_begin: Style::default(),
@@ -115,34 +284,320 @@ impl<'a> LineHighlighter<'a> {
             mask,
             select_at_end: false,
             select_style,
+            wrap_breaks: vec![],
+            wrap_prefix: vec![],
+            base_styles: vec![],
+            reveal: None,
+            whitespace: None,
+            guides: None,
+            control: false,
+            virtual_guides: None,
+            rulers: vec![],
+            line_width: 0,
+            hidden_end: 0,
+            clip: None,
+            scroll: None,
+        }
+    }
+
+    /// Highlight only a part of a long line. The text given to [`LineHighlighter::new`] is the part of the whole line
+    /// described by `part`. Offsets and columns given to other methods are still counted in the whole line, and
+    /// highlights out of the part are not rendered. This must be called before other methods.
+    pub fn part(&mut self, part: LinePart) {
+        self.part = part;
+    }
+
+    // Byte offset in the whole line where the text ends
+    fn end(&self) -> usize {
+        self.part.offset + self.line.len()
+    }
+
+    // Byte range in the whole line of the character at the column. `None` is returned at the end of line, and an empty
+    // range is returned when the character is out of the part
+    fn char_range(&self, col: usize) -> Option<Range<usize>> {
+        let Some(col) = col.checked_sub(self.part.col) else {
+            return Some(self.part.offset..self.part.offset);
+        };
+        match self.line.char_indices().nth(col) {
+            Some((i, c)) => {
+                let start = self.part.offset + i;
+                Some(start..start + c.len_utf8())
+            }
+            None if self.end() == self.part.len => None,
+            None => Some(self.end()..self.end()),
         }
     }
 
+    // Byte range in the whole line clamped to the part and extended to character boundaries
+    fn clamp_range(&self, range: &Range<usize>) -> (usize, usize) {
+        let base = self.part.offset;
+        let start = floor_char_boundary(self.line, range.start.saturating_sub(base));
+        let end = ceil_char_boundary(self.line, range.end.saturating_sub(base));
+        (base + start, base + end)
+    }
+
+    /// Put the cells of the sign column before the line number.
+    pub fn sign(&mut self, cells: String, style: Style) {
+        self.spans.push(Span::styled(cells, style));
+    }
+
     pub fn line_number(&mut self, row: usize, lnum_len: u8, style: Style) {
-        let pad = spaces(lnum_len - num_digits(row + 1));
-        self.spans.push(Span::styled(format!("{}{} ", pad, row + 1), style));
+        self.gutter_number(row + 1, lnum_len, style);
+    }
+
+    /// Line number relative to the cursor row. `distance` is the number of rows between the line and the cursor.
+    pub fn relative_line_number(&mut self, distance: usize, lnum_len: u8, style: Style) {
+        self.gutter_number(distance, lnum_len, style);
+    }
+
+    fn gutter_number(&mut self, num: usize, lnum_len: u8, style: Style) {
+        let pad = spaces(lnum_len - num_digits(num));
+        self.spans.push(Span::styled(format!("{}{} ", pad, num), style));
     }
 
     pub fn cursor_line(&mut self, cursor_col: usize, style: Style) {
-        if let Some((start, c)) = self.line.char_indices().nth(cursor_col) {
-            self.boundaries.push((Boundary::Cursor(self.cursor_style), start));
-            self.boundaries.push((Boundary::End, start + c.len_utf8()));
-        } else {
-            self.cursor_at_end = true;
+        match self.char_range(cursor_col) {
+            Some(range) if range.is_empty() => {}
+            Some(range) => {
+                self.boundaries
+                    .push((Boundary::Start(Layer::Cursor, self.cursor_style), range.start));
+                self.boundaries.push((Boundary::End(Layer::Cursor), range.end));
+            }
+            None => self.cursor_at_end = true,
         }
         self.style_begin = style;
     }
 
+    /// Render a secondary cursor of multi-cursor editing at the column with the style.
+    pub fn secondary_cursor(&mut self, col: usize, style: Style) {
+        match self.char_range(col) {
+            Some(range) if range.is_empty() => {}
+            Some(range) => {
+                self.boundaries
+                    .push((Boundary::Start(Layer::Cursor, style), range.start));
+                self.boundaries.push((Boundary::End(Layer::Cursor), range.end));
+            }
+            None => {
+                // The primary cursor is never at the end of the same line since overlapping cursors are merged
+                self.cursor_at_end = true;
+                self.cursor_style = style;
+            }
+        }
+    }
+
+    /// Apply the style of the cursor line without rendering the cursor, which is rendered by other text covering the
+    /// cursor cell.
+    pub fn cursor_line_style(&mut self, style: Style) {
+        self.style_begin = style;
+    }
+
     #[cfg(feature = "search")]
     pub fn search(&mut self, matches: impl Iterator<Item = (usize, usize)>, style: Style) {
         for (start, end) in matches {
             if start != end {
-                self.boundaries.push((Boundary::Search(style), start));
-                self.boundaries.push((Boundary::End, end));
+                self.boundaries.push((Boundary::Start(Layer::Search, style), start));
+                self.boundaries.push((Boundary::End(Layer::Search), end));
             }
         }
     }
 
+    /// Highlight the byte range of the current search match with the style instead of the style of other matches.
+    #[cfg(feature = "search")]
+    pub fn current_search_match(&mut self, (start, end): (usize, usize), style: Style) {
+        if start != end {
+            self.boundaries
+                .push((Boundary::Start(Layer::CurrentMatch, style), start));
+            self.boundaries.push((Boundary::End(Layer::CurrentMatch), end));
+        }
+    }
+
+    /// Highlight the bracket at the byte offset. It is layered over the search matches and the selection.
+    pub fn matching_bracket(&mut self, offset: usize, style: Style) {
+        let text = offset.checked_sub(self.part.offset).and_then(|i| self.line.get(i..));
+        if let Some(c) = text.and_then(|t| t.chars().next()) {
+            self.boundaries.push((Boundary::Start(Layer::Bracket, style), offset));
+            self.boundaries
+                .push((Boundary::End(Layer::Bracket), offset + c.len_utf8()));
+        }
+    }
+
+    /// Styles given by users with byte ranges. They are layered under other highlights. Ranges are clamped to the line
+    /// and extended to character boundaries. When ranges overlap, the later one takes precedence.
+    pub fn line_styles(&mut self, styles: &[(Range<usize>, Style)]) {
+        let mut painted: Vec<(usize, usize, Style)> = vec![];
+        for (range, style) in styles {
+            let (start, end) = self.clamp_range(range);
+            if start >= end {
+                continue;
+            }
+            let mut next = Vec::with_capacity(painted.len() + 2);
+            for &(s, e, st) in &painted {
+                if s < start {
+                    next.push((s, min(e, start), st));
+                }
+                if end < e {
+                    next.push((max(s, end), e, st));
+                }
+            }
+            next.push((start, end, *style));
+            next.sort_unstable_by_key(|&(s, _, _)| s);
+            painted = next;
+        }
+        self.base_styles = painted;
+    }
+
+    /// Visualize spaces and tabs with the glyphs. Their styles are put on the line styles so this must be called after
+    /// [`LineHighlighter::line_styles`].
+    pub fn whitespace(&mut self, ws: &WhitespaceStyle) {
+        self.whitespace = Some((ws.space, ws.tab));
+
+        let is_space = |c: char| c == ' ' || c == '\t';
+        let base = self.part.offset;
+        // Whitespaces at the end of a part before the end of line are not regarded as trailing
+        let trailing = if self.end() == self.part.len {
+            base + self.line.trim_end_matches(is_space).len()
+        } else {
+            self.end()
+        };
+        let mut runs = vec![];
+        let mut chars = self.line.char_indices().map(|(i, c)| (base + i, c)).peekable();
+        while let Some((start, c)) = chars.next() {
+            if !is_space(c) {
+                continue;
+            }
+            let mut end = start + 1;
+            while let Some((i, _)) = chars.next_if(|&(_, c)| is_space(c)) {
+                end = i + 1;
+            }
+            if start < trailing {
+                runs.push((start, end, ws.style));
+            } else {
+                runs.push((start, end, ws.trailing_style));
+            }
+        }
+        self.patch_base_styles(&runs);
+    }
+
+    /// Draw the glyph at each indent stop (every `tab_len` columns) before the display column `width` in the leading
+    /// whitespaces. When the line has only whitespaces and is narrower than `width`, the rest of the guides are put after
+    /// the end of line. Their style is put on the line styles so this must be called after
+    /// [`LineHighlighter::whitespace`].
+    pub fn indent_guides(&mut self, width: usize, glyph: char, style: Style) {
+        if self.tab_len == 0 {
+            return;
+        }
+        let tab_len = self.tab_len as usize;
+        let mut offsets = vec![];
+        let mut runs = vec![];
+        // A part starting before `width` is in the leading whitespaces
+        let mut col = self.part.width;
+        let mut blank = true;
+        for (i, c) in self.line.char_indices() {
+            if c != ' ' && c != '\t' {
+                blank = false;
+                break;
+            }
+            if col >= width {
+                break;
+            }
+            let i = self.part.offset + i;
+            if col % tab_len == 0 {
+                offsets.push(i);
+                runs.push((i, i + 1, style));
+            }
+            col += if c == '\t' { tab_len - col % tab_len } else { 1 };
+        }
+        if blank && col < width {
+            let guides = (col..width)
+                .map(|c| if c % tab_len == 0 { glyph } else { ' ' })
+                .collect();
+            self.virtual_guides = Some((guides, style));
+        }
+        self.patch_base_styles(&runs);
+        self.guides = Some((glyph, offsets));
+    }
+
+    /// Put the styles on the cells at the display columns of `rulers`, which must be sorted. Short lines are padded with
+    /// spaces up to the rulers unless the line is soft-wrapped into multiple rows. The styles are put on the line styles
+    /// so this must be called after [`LineHighlighter::line_styles`].
+    pub fn rulers(&mut self, rulers: Vec<(usize, Style)>) {
+        let mut runs = vec![];
+        let mut width = self.part.width;
+        let mut next = rulers.iter().peekable();
+        for (i, c) in self.line.char_indices() {
+            let i = self.part.offset + i;
+            let w = char_width(c, width, self.tab_len, self.mask, self.control);
+            let mut style = None;
+            while let Some(&(col, s)) = next.next_if(|&&(col, _)| col < width + w) {
+                if width <= col {
+                    style = style.or(Some(s));
+                }
+            }
+            if let Some(style) = style {
+                runs.push((i, i + c.len_utf8(), style));
+            }
+            width += w;
+        }
+        self.patch_base_styles(&runs);
+        self.rulers = rulers;
+        self.line_width = width;
+    }
+
+    /// Render control characters in their notation such as `^L` with the style. The style is put on the line styles so
+    /// this must be called after [`LineHighlighter::line_styles`], and before [`LineHighlighter::rulers`] which
+    /// depends on the display width of characters.
+    pub fn control_chars(&mut self, style: Style) {
+        self.control = true;
+        let base = self.part.offset;
+        let runs: Vec<_> = self
+            .line
+            .char_indices()
+            .filter(|&(_, c)| control_char_notation(c).is_some())
+            .map(|(i, c)| (base + i, base + i + c.len_utf8(), style))
+            .collect();
+        self.patch_base_styles(&runs);
+    }
+
+    /// Styles of ranges set by [`crate::TextArea::set_highlights`]. They are patched on the line styles in the order of
+    /// the list so this must be called after [`LineHighlighter::line_styles`]. `ranges` are byte offsets in the line.
+    pub fn highlights(&mut self, ranges: &[(Range<usize>, Style)]) {
+        for (range, style) in ranges {
+            let (start, end) = self.clamp_range(range);
+            if start < end {
+                self.patch_base_styles(&[(start, end, *style)]);
+            }
+        }
+    }
+
+    // Patch the styles of the sorted and non-overlapping runs on the line styles
+    fn patch_base_styles(&mut self, runs: &[(usize, usize, Style)]) {
+        if runs.is_empty() {
+            return;
+        }
+        let mut points: Vec<_> = self
+            .base_styles
+            .iter()
+            .chain(runs.iter())
+            .flat_map(|&(s, e, _)| [s, e])
+            .collect();
+        points.sort_unstable();
+        points.dedup();
+        let style_at = |styles: &[(usize, usize, Style)], i: usize| {
+            styles.iter().find(|&&(s, e, _)| s <= i && i < e).map(|&(_, _, st)| st)
+        };
+        let mut painted = vec![];
+        for w in points.windows(2) {
+            let (s, e) = (w[0], w[1]);
+            let style = match (style_at(&self.base_styles, s), style_at(runs, s)) {
+                (Some(base), Some(patch)) => base.patch(patch),
+                (Some(st), None) | (None, Some(st)) => st,
+                (None, None) => continue,
+            };
+            painted.push((s, e, style));
+        }
+        self.base_styles = painted;
+    }
+
     pub fn selection(
         &mut self,
         current_row: usize,
@@ -156,26 +611,104 @@ impl<'a> LineHighlighter<'a> {
                 (start_off, end_off)
             } else {
                 self.select_at_end = true;
-                (start_off, self.line.len())
+                (start_off, self.part.len)
             }
         } else if current_row == end_row {
             (0, end_off)
         } else if start_row < current_row && current_row < end_row {
             self.select_at_end = true;
-            (0, self.line.len())
+            (0, self.part.len)
         } else {
             return;
         };
         if start != end {
-            self.boundaries.push((Boundary::Select(self.select_style), start));
-            self.boundaries.push((Boundary::End, end));
+            self.boundaries
+                .push((Boundary::Start(Layer::Select, self.select_style), start));
+            self.boundaries.push((Boundary::End(Layer::Select), end));
+        }
+    }
+
+    /// Highlight the byte range of the text copied just before. It is layered over the selection.
+    pub fn yank_flash(&mut self, start: usize, end: usize, style: Style) {
+        if start != end {
+            self.boundaries.push((Boundary::Start(Layer::YankFlash, style), start));
+            self.boundaries.push((Boundary::End(Layer::YankFlash), end));
+        }
+    }
+
+    /// Show the character at the byte offset as-is even if the text is masked.
+    pub fn reveal(&mut self, offset: usize) {
+        self.reveal = Some(offset);
+    }
+
+    /// Soft-wrap the line into multiple display rows. `breaks` are byte offsets where each continuation row starts.
+    /// `prefix` is put at the start of every continuation row instead of the line number.
+    pub fn wrap(&mut self, breaks: Vec<usize>, prefix: Vec<Span<'a>>) {
+        self.wrap_breaks = breaks;
+        self.wrap_prefix = prefix;
+    }
+
+    /// Render only the text before the byte offset. This avoids building spans for the invisible part of a very long
+    /// line. Highlights after the offset, including the cell at the end of line, are not rendered.
+    pub fn clip(&mut self, offset: usize) {
+        if offset <= self.end() {
+            self.clip = Some(offset);
         }
     }
 
+    /// Scroll the line horizontally. The gutter is hidden and the text is rendered from the byte offset `start`, which
+    /// is at the display column `width`. `pad` spaces are put before the text instead of a wide character or a tab
+    /// partially scrolled out.
+    pub fn scroll(&mut self, start: usize, width: usize, pad: usize) {
+        self.scroll = Some((start, width, pad));
+    }
+
+    /// Hide the first `cells` cells after the end of line, which are scrolled out horizontally with the whole line.
+    pub fn hide_end(&mut self, cells: usize) {
+        self.hidden_end = cells;
+    }
+
+    #[cfg(all(test, feature = "ratatui"))]
     pub fn into_spans(self) -> Line<'a> {
+        let mut lines = self.into_lines();
+        debug_assert_eq!(lines.len(), 1, "wrapped line cannot be converted into single spans");
+        lines.swap_remove(0)
+    }
+
+    /// Same as [`LineHighlighter::into_spans`] but the spans own their texts so that they can be cached.
+    pub fn into_owned_spans(self) -> Line<'static> {
+        let mut rows = self.into_rows();
+        debug_assert_eq!(rows.len(), 1, "wrapped line cannot be converted into single spans");
+        let spans: Vec<_> = rows
+            .swap_remove(0)
+            .into_iter()
+            .map(|s| Span::styled(s.content.into_owned(), s.style))
+            .collect();
+        Line::from(spans)
+    }
+
+    #[cfg(all(test, feature = "ratatui"))]
+    pub fn into_lines(self) -> Vec<Line<'a>> {
+        self.into_rows().into_iter().map(Line::from).collect()
+    }
+
+    pub fn into_owned_lines(self) -> Vec<Line<'static>> {
+        let rows = self.into_rows().into_iter();
+        rows.map(|row| {
+            let spans: Vec<_> = row
+                .into_iter()
+                .map(|s| Span::styled(s.content.into_owned(), s.style))
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+    }
+
+    fn into_rows(self) -> Vec<Vec<Span<'a>>> {
         let Self {
             line,
-            mut spans,
+            part,
+            spans,
             mut boundaries,
             tab_len,
             style_begin,
@@ -184,56 +717,163 @@ impl<'a> LineHighlighter<'a> {
             mask,
             select_at_end,
             select_style,
+            wrap_breaks,
+            wrap_prefix,
+            base_styles,
+            reveal,
+            whitespace,
+            guides,
+            control,
+            virtual_guides,
+            rulers,
+            line_width,
+            hidden_end,
+            clip,
+            scroll,
         } = self;
+        let wrapped = !wrap_breaks.is_empty();
+        let base = part.offset;
+        let (line, cursor_at_end, select_at_end) = match clip {
+            Some(offset) => (&line[..offset - base], false, false),
+            None => (line, cursor_at_end, select_at_end),
+        };
+        // Offsets are in the whole line. Highlights out of the text are not rendered
+        let line_end = base + line.len();
+        for (_, o) in &mut boundaries {
+            *o = (*o).clamp(base, line_end);
+        }
         let mut builder = DisplayTextBuilder::new(tab_len, mask);
+        builder.reveal = reveal;
+        builder.whitespace = whitespace;
+        builder.guides = guides;
+        builder.control = control;
+        let (begin, mut rows) = match scroll {
+            Some((start, width, pad)) => {
+                for (_, o) in &mut boundaries {
+                    *o = max(*o, start);
+                }
+                builder.width = width;
+                let padding = (pad > 0).then(|| Span::styled(" ".repeat(pad), style_begin));
+                (start, vec![padding.into_iter().collect()])
+            }
+            None => (base, vec![spans]),
+        };
+        let mut breaks = wrap_breaks.into_iter().peekable();
 
-        if boundaries.is_empty() {
-            let built = builder.build(line);
-            if !built.is_empty() {
-                spans.push(Span::styled(built, style_begin));
+        let mut push = |rows: &mut Vec<Vec<Span<'a>>>, mut start: usize, end: usize, style: Style| loop {
+            match breaks.peek() {
+                Some(&b) if b <= start => {
+                    rows.push(wrap_prefix.clone());
+                    breaks.next();
+                }
+                Some(&b) if b < end => {
+                    rows.last_mut()
+                        .unwrap()
+                        .push(Span::styled(builder.build(&line[start - base..b - base], start), style));
+                    start = b;
+                }
+                _ => {
+                    if start < end {
+                        rows.last_mut().unwrap().push(Span::styled(
+                            builder.build(&line[start - base..end - base], start),
+                            style,
+                        ));
+                    }
+                    break;
+                }
             }
-            if cursor_at_end {
-                spans.push(Span::styled(" ", cursor_style));
-            } else if select_at_end {
-                spans.push(Span::styled(" ", select_style));
+        };
+
+        // Split the text with the base styles. The base styles are not applied where other highlights are applied. The
+        // line style is put on the base styles
+        let mut push_base = |rows: &mut Vec<Vec<Span<'a>>>, start: usize, end: usize, style: Style, base: bool| {
+            let base_styles = if base { &base_styles[..] } else { &[] };
+            let mut pos = start;
+            for &(s, e, base_style) in base_styles.iter().filter(|(s, e, _)| *s < end && start < *e) {
+                let (s, e) = (max(s, pos), min(e, end));
+                push(rows, pos, s, style);
+                push(rows, s, e, base_style.patch(style));
+                pos = e;
             }
-            return Line::from(spans);
-        }
+            push(rows, pos, end, style);
+        };
 
-        boundaries.sort_unstable_by(|(l, i), (r, j)| match i.cmp(j) {
-            Ordering::Equal => l.cmp(r),
-            o => o,
-        });
+        if boundaries.is_empty() {
+            push_base(&mut rows, begin, line_end, style_begin, true);
+        } else {
+            boundaries.sort_by_key(|(b, i)| (*i, b.is_start()));
 
-        let mut style = style_begin;
-        let mut start = 0;
-        let mut stack = vec![];
+            // Style of each layer while its highlight is open. The style of the topmost open layer is rendered
+            let mut layers = [None; NUM_LAYERS];
+            let top = |layers: &[Option<Style>]| layers.iter().rev().find_map(|s| *s);
+            let mut start = begin;
 
-        for (next_boundary, end) in boundaries {
-            if start < end {
-                spans.push(Span::styled(builder.build(&line[start..end]), style));
+            for (boundary, end) in boundaries {
+                let style = top(&layers);
+                push_base(&mut rows, start, end, style.unwrap_or(style_begin), style.is_none());
+                match boundary {
+                    Boundary::Start(layer, style) => layers[layer as usize] = Some(style),
+                    Boundary::End(layer) => layers[layer as usize] = None,
+                }
+                start = end;
             }
 
-            style = if let Some(s) = next_boundary.style() {
-                stack.push(style);
-                s
-            } else {
-                stack.pop().unwrap_or(style_begin)
-            };
-            start = end;
+            let style = top(&layers);
+            push_base(
+                &mut rows,
+                start,
+                line_end,
+                style.unwrap_or(style_begin),
+                style.is_none(),
+            );
         }
 
-        if start != line.len() {
-            spans.push(Span::styled(builder.build(&line[start..]), style));
+        // Handle a row break at the end of line reserved for the cursor
+        push_base(&mut rows, line_end, line_end, style_begin, false);
+
+        // Cells after the end of line: the cursor or the selection, indent guides, and rulers. They are not rendered when
+        // the end of line is clipped
+        let mut cells = vec![];
+        if clip.is_none() {
+            if let Some((guides, style)) = virtual_guides {
+                let style = style.patch(style_begin);
+                cells.extend(guides.chars().map(|c| (c, if c == ' ' { style_begin } else { style })));
+            }
+            let end_style = if cursor_at_end {
+                Some(cursor_style)
+            } else if select_at_end {
+                Some(select_style)
+            } else {
+                None
+            };
+            if let Some(style) = end_style {
+                match cells.first_mut() {
+                    Some(cell) => cell.1 = style,
+                    None => cells.push((' ', style)),
+                }
+            }
+            if !wrapped {
+                for (col, style) in rulers {
+                    if let Some(i) = col.checked_sub(line_width) {
+                        if cells.len() <= i {
+                            cells.resize(i + 1, (' ', style_begin));
+                        }
+                        cells[i].1 = style.patch(cells[i].1);
+                    }
+                }
+            }
         }
 
-        if cursor_at_end {
-            spans.push(Span::styled(" ", cursor_style));
-        } else if select_at_end {
-            spans.push(Span::styled(" ", select_style));
+        let last = rows.last_mut().unwrap();
+        let mut cells = &cells[min(hidden_end, cells.len())..];
+        while let Some(&(_, style)) = cells.first() {
+            let len = cells.iter().position(|&(_, s)| s != style).unwrap_or(cells.len());
+            let text: String = cells[..len].iter().map(|&(c, _)| c).collect();
+            last.push(Span::styled(text, style));
+            cells = &cells[len..];
         }
 
-        Line::from(spans)
+        rows
     }
 }
 
@@ -241,19 +881,19 @@ impl<'a> LineHighlighter<'a> {
 #[cfg(all(test, feature = "ratatui"))]
 mod tests {
     use super::*;
-    use crate::ratatui::style::Color;
+    use crate::ratatui::style::{Color, Modifier};
     use std::fmt::Debug;
     use unicode_width::UnicodeWidthStr as _;
 
     fn build(text: &'static str, tab: u8, mask: Option<char>) -> Cow<'static, str> {
-        DisplayTextBuilder::new(tab, mask).build(text)
+        DisplayTextBuilder::new(tab, mask).build(text, 0)
     }
 
     #[track_caller]
     fn build_with_offset(offset: usize, text: &'static str, tab: u8) -> Cow<'static, str> {
         let mut b = DisplayTextBuilder::new(tab, None);
         b.width = offset;
-        let built = b.build(text);
+        let built = b.build(text, 0);
         let want = offset + built.as_ref().width();
         assert_eq!(b.width, want, "in={:?}, out={:?}", text, built); // Check post condition
         built
@@ -336,6 +976,31 @@ mod tests {
         assert_eq!(&build_with_offset(2, "あ\tあ\t", 4), "あ    あ  ");
     }
 
+    #[test]
+    #[rustfmt::skip]
+    fn line_display_text_whitespace() {
+        fn build_ws(text: &'static str, tab: u8, offset: usize) -> Cow<'static, str> {
+            let mut b = DisplayTextBuilder::new(tab, None);
+            b.whitespace = Some(('.', '>'));
+            b.width = offset;
+            let built = b.build(text, 0);
+            assert_eq!(b.width, offset + built.as_ref().width(), "in={:?}, out={:?}", text, built);
+            built
+        }
+
+        assert_eq!(&build_ws(        "", 4, 0),         "");
+        assert_eq!(&build_ws(       "a", 4, 0),        "a");
+        assert_eq!(&build_ws(     "a b", 4, 0),      "a.b");
+        assert_eq!(&build_ws(    "  a ", 4, 0),     "..a.");
+        assert_eq!(&build_ws(      "\t", 4, 0),     ">   ");
+        assert_eq!(&build_ws(     "a\tb", 4, 0),    "a>  b");
+        assert_eq!(&build_ws(    "abc\t", 4, 0),     "abc>");
+        assert_eq!(&build_ws(     " \t ", 4, 0),     ".>  .");
+        assert_eq!(&build_ws(    "あ \t", 4, 0),     "あ.>");
+        assert_eq!(&build_ws(      "\t", 4, 2),       "> ");
+        assert_eq!(&build_ws(      "\t", 4, 3),        ">");
+    }
+
     fn assert_spans<T: Debug>(lh: LineHighlighter, want: &[(&str, Style)], context: T) {
         let line = lh.into_spans();
         let have = line
@@ -399,6 +1064,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn into_spans_relative_line_number() {
+        let tests = [(1, 1, &[("1 ", LNUM)][..]), (12, 3, &[(" 12 ", LNUM)][..])];
+        for test in tests {
+            let (distance, len, want) = test;
+            let mut lh = LineHighlighter::new("", CUR, 4, None, SEL);
+            lh.relative_line_number(distance, len, LNUM);
+            assert_spans(lh, want, test);
+        }
+    }
+
     #[cfg(feature = "search")]
     #[test]
     fn into_spans_search() {
@@ -492,6 +1168,347 @@ mod tests {
         }
     }
 
+    #[test]
+    fn into_spans_line_styles() {
+        const RED: Style = Style::new().fg(Color::Red);
+        const BLUE: Style = Style::new().fg(Color::Blue);
+
+        let tests = [
+            (
+                "abcde",
+                &[(1..3, RED)][..],
+                &[("a", DEFAULT), ("bc", RED), ("de", DEFAULT)][..],
+            ),
+            ("abcde", &[(0..5, RED)][..], &[("abcde", RED)][..]),
+            ("abc", &[(1..10, RED)][..], &[("a", DEFAULT), ("bc", RED)][..]),
+            ("abc", &[(5..10, RED), (2..2, RED)][..], &[("abc", DEFAULT)][..]),
+            (
+                "abcde",
+                &[(0..3, RED), (2..5, BLUE)][..],
+                &[("ab", RED), ("cde", BLUE)][..],
+            ),
+            (
+                "abcde",
+                &[(0..5, RED), (1..2, BLUE), (3..4, BLUE)][..],
+                &[("a", RED), ("b", BLUE), ("c", RED), ("d", BLUE), ("e", RED)][..],
+            ),
+            ("abcde", &[(1..2, BLUE), (0..5, RED)][..], &[("abcde", RED)][..]),
+            // Ranges are extended to character boundaries
+            ("あいう", &[(1..4, RED)][..], &[("あい", RED), ("う", DEFAULT)][..]),
+            (
+                "a\tb",
+                &[(1..2, RED)][..],
+                &[("a", DEFAULT), ("   ", RED), ("b", DEFAULT)][..],
+            ),
+        ];
+
+        for test in tests {
+            let (line, styles, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.line_styles(styles);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_line_styles_under_highlights() {
+        const RED: Style = Style::new().fg(Color::Red);
+
+        // Cursor line style is put on the line styles
+        let mut lh = LineHighlighter::new("abcde", CUR, 4, None, SEL);
+        lh.cursor_line(4, LINE);
+        lh.line_styles(&[(0..2, RED)]);
+        assert_spans(
+            lh,
+            &[("ab", RED.bg(Color::Gray)), ("cd", LINE), ("e", CUR)],
+            "cursor line",
+        );
+
+        // Selection and cursor take priority over the line styles
+        let mut lh = LineHighlighter::new("abcdefg", CUR, 4, None, SEL);
+        lh.cursor_line(5, LINE);
+        lh.line_styles(&[(0..7, RED)]);
+        lh.selection(0, 0, 2, 0, 4);
+        let styled = RED.bg(Color::Gray);
+        let want = [("ab", styled), ("cd", SEL), ("e", styled), ("f", CUR), ("g", styled)];
+        assert_spans(lh, &want, "selection and cursor");
+    }
+
+    #[test]
+    fn into_spans_highlights() {
+        const RED: Style = Style::new().fg(Color::Red);
+        const UNDERLINE: Style = Style::new().add_modifier(Modifier::UNDERLINED);
+        const BLUE: Style = Style::new().fg(Color::Blue);
+
+        // Highlights are patched on the line styles and each other in order
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.line_styles(&[(0..4, RED)]);
+        lh.highlights(&[(2..6, UNDERLINE), (3..5, BLUE)]);
+        let want = [
+            ("ab", RED),
+            ("c", RED.add_modifier(Modifier::UNDERLINED)),
+            ("d", BLUE.add_modifier(Modifier::UNDERLINED)),
+            ("e", BLUE.add_modifier(Modifier::UNDERLINED)),
+            ("f", UNDERLINE),
+        ];
+        assert_spans(lh, &want, "patched");
+
+        // Selection takes priority over highlights
+        let mut lh = LineHighlighter::new("abc", CUR, 4, None, SEL);
+        lh.highlights(&[(0..3, UNDERLINE)]);
+        lh.selection(0, 0, 1, 0, 2);
+        assert_spans(lh, &[("a", UNDERLINE), ("b", SEL), ("c", UNDERLINE)], "selection");
+    }
+
+    #[test]
+    fn into_spans_whitespace() {
+        const WS: Style = Style::new().fg(Color::DarkGray);
+        const TRAIL: Style = Style::new().fg(Color::Red);
+        const RED: Style = Style::new().bg(Color::Red);
+        let ws = WhitespaceStyle {
+            space: '.',
+            tab: '>',
+            style: WS,
+            trailing_style: TRAIL,
+        };
+
+        let tests = [
+            ("abc", &[][..], &[("abc", DEFAULT)][..]),
+            ("a b", &[], &[("a", DEFAULT), (".", WS), ("b", DEFAULT)]),
+            (" \ta  ", &[], &[(".>  ", WS), ("a", DEFAULT), ("..", TRAIL)]),
+            ("  ", &[], &[("..", TRAIL)]),
+            // Whitespace styles are put on the line styles
+            (
+                "a b c",
+                &[(0..3, RED)],
+                &[("a", RED), (".", RED.patch(WS)), ("b", RED), (".", WS), ("c", DEFAULT)],
+            ),
+        ];
+
+        for test in tests {
+            let (line, styles, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.line_styles(styles);
+            lh.whitespace(&ws);
+            assert_spans(lh, want, test);
+        }
+
+        // Selection and cursor take priority over the whitespace styles
+        let mut lh = LineHighlighter::new("a b c ", CUR, 4, None, SEL);
+        lh.cursor_line(3, LINE);
+        lh.whitespace(&ws);
+        lh.selection(0, 0, 0, 0, 2);
+        let want = [
+            ("a.", SEL),
+            ("b", LINE),
+            (".", CUR),
+            ("c", LINE),
+            (".", TRAIL.bg(Color::Gray)),
+        ];
+        assert_spans(lh, &want, "selection and cursor");
+    }
+
+    #[test]
+    fn into_spans_indent_guides() {
+        const GUIDE: Style = Style::new().fg(Color::DarkGray);
+
+        let tests = [
+            // (line, width, expected)
+            ("abc", 0, &[("abc", DEFAULT)][..]),
+            ("  a", 2, &[("|", GUIDE), (" a", DEFAULT)]),
+            (
+                "     a",
+                5,
+                &[
+                    ("|", GUIDE),
+                    (" ", DEFAULT),
+                    ("|", GUIDE),
+                    (" ", DEFAULT),
+                    ("|", GUIDE),
+                    ("a", DEFAULT),
+                ],
+            ),
+            // Hard tabs
+            ("\t\ta", 4, &[("| ", GUIDE), ("| ", GUIDE), ("a", DEFAULT)]),
+            (" \ta", 2, &[("|", GUIDE), (" a", DEFAULT)]),
+            // Guides after the end of line which has only whitespaces
+            ("", 4, &[("|", GUIDE), (" ", DEFAULT), ("|", GUIDE), (" ", DEFAULT)]),
+            ("  ", 4, &[("|", GUIDE), (" ", DEFAULT), ("|", GUIDE), (" ", DEFAULT)]),
+            ("    ", 2, &[("|", GUIDE), ("   ", DEFAULT)]),
+        ];
+
+        for test in tests {
+            let (line, width, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 2, None, SEL);
+            lh.indent_guides(width, '|', GUIDE);
+            assert_spans(lh, want, test);
+        }
+
+        // Selection and cursor line are layered over the guides
+        let mut lh = LineHighlighter::new("    a", CUR, 2, None, SEL);
+        lh.cursor_line(4, LINE);
+        lh.indent_guides(4, '|', GUIDE);
+        lh.selection(0, 0, 0, 0, 1);
+        let want = [
+            ("|", SEL),
+            (" ", LINE),
+            ("|", GUIDE.patch(LINE)),
+            (" ", LINE),
+            ("a", CUR),
+        ];
+        assert_spans(lh, &want, "selection and cursor line");
+
+        // The cursor at the end of line is put on the first guide after the end of line
+        let mut lh = LineHighlighter::new("", CUR, 2, None, SEL);
+        lh.cursor_line(0, LINE);
+        lh.indent_guides(4, '|', GUIDE);
+        let want = [("|", CUR), (" ", LINE), ("|", GUIDE.patch(LINE)), (" ", LINE)];
+        assert_spans(lh, &want, "cursor at end");
+    }
+
+    #[test]
+    fn into_spans_rulers() {
+        const RULER: Style = Style::new().bg(Color::DarkGray);
+        const RED: Style = Style::new().fg(Color::Red);
+
+        let tests = [
+            // (line, rulers, expected)
+            (
+                "abcdef",
+                &[(3, RULER)][..],
+                &[("abc", DEFAULT), ("d", RULER), ("ef", DEFAULT)][..],
+            ),
+            ("ab", &[(4, RULER)], &[("ab", DEFAULT), ("  ", DEFAULT), (" ", RULER)]),
+            (
+                "ab",
+                &[(2, RULER), (3, RED)],
+                &[("ab", DEFAULT), (" ", RULER), (" ", RED)],
+            ),
+            // Wide character and tab on the ruler
+            ("aあb", &[(2, RULER)], &[("a", DEFAULT), ("あ", RULER), ("b", DEFAULT)]),
+            ("a\tb", &[(2, RULER)], &[("a", DEFAULT), ("   ", RULER), ("b", DEFAULT)]),
+        ];
+
+        for test in tests {
+            let (line, rulers, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.rulers(rulers.to_vec());
+            assert_spans(lh, want, test);
+        }
+
+        // The cursor line and the cursor are layered over the rulers
+        const UNDER: Style = Style::new().add_modifier(Modifier::UNDERLINED);
+        let mut lh = LineHighlighter::new("ab", CUR, 4, None, SEL);
+        lh.cursor_line(2, UNDER);
+        lh.rulers(vec![(1, RULER), (2, RULER), (4, RULER)]);
+        let want = [
+            ("a", UNDER),
+            ("b", RULER.patch(UNDER)),
+            (" ", RULER.patch(CUR)),
+            (" ", UNDER),
+            (" ", RULER.patch(UNDER)),
+        ];
+        assert_spans(lh, &want, "cursor line");
+
+        // Cells scrolled out after the end of line
+        let mut lh = LineHighlighter::new("ab", CUR, 4, None, SEL);
+        lh.rulers(vec![(5, RULER)]);
+        lh.scroll(2, 2, 0);
+        lh.hide_end(2);
+        assert_spans(lh, &[(" ", DEFAULT), (" ", RULER)], "scrolled out");
+
+        // Soft-wrapped line is not padded
+        let mut lh = LineHighlighter::new("abcd", CUR, 4, None, SEL);
+        lh.rulers(vec![(1, RULER), (8, RULER)]);
+        lh.wrap(vec![2], vec![]);
+        let rows: Vec<_> = lh.into_lines().into_iter().map(|l| l.spans.len()).collect();
+        assert_eq!(rows, [2, 1]);
+    }
+
+    #[test]
+    fn into_spans_control_chars() {
+        const CTRL: Style = Style::new().fg(Color::Blue);
+
+        let tests = [
+            ("abc", &[("abc", DEFAULT)][..]),
+            ("a\x0cb", &[("a", DEFAULT), ("^L", CTRL), ("b", DEFAULT)]),
+            ("\x1b\x00\x7f", &[("^[", CTRL), ("^@", CTRL), ("^?", CTRL)]),
+            ("a\r", &[("a", DEFAULT), ("^M", CTRL)]),
+            ("\u{200e}x\u{85}", &[("<200e>", CTRL), ("x", DEFAULT), ("<0085>", CTRL)]),
+            // Tab and zero-width joiner are not control characters
+            ("\ta\u{200d}", &[("    a\u{200d}", DEFAULT)]),
+        ];
+
+        for test in tests {
+            let (line, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.control_chars(CTRL);
+            assert_spans(lh, want, test);
+        }
+
+        // Tab after the notation is aligned with its display width
+        let mut lh = LineHighlighter::new("\x01\tb", CUR, 4, None, SEL);
+        lh.control_chars(CTRL);
+        assert_spans(lh, &[("^A", CTRL), ("  b", DEFAULT)], "tab");
+
+        // Cursor on the control character
+        let mut lh = LineHighlighter::new("a\x0c", CUR, 4, None, SEL);
+        lh.cursor_line(1, LINE);
+        lh.control_chars(CTRL);
+        assert_spans(lh, &[("a", LINE), ("^L", CUR)], "cursor");
+    }
+
+    #[test]
+    fn into_spans_clip() {
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.line_number(0, 1, LNUM);
+        lh.clip(3);
+        assert_spans(lh, &[("1 ", LNUM), ("abc", DEFAULT)], "no highlight");
+
+        // Cursor and selection after the offset are not rendered
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.cursor_line(6, LINE);
+        lh.selection(0, 0, 2, 1, 0);
+        lh.clip(3);
+        assert_spans(lh, &[("ab", LINE), ("c", SEL)], "cursor and selection");
+
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.cursor_line(6, LINE);
+        lh.clip(6);
+        assert_spans(lh, &[("abcdef", LINE)], "clip end of line");
+
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.cursor_line(1, LINE);
+        lh.clip(6);
+        assert_spans(lh, &[("a", LINE), ("b", CUR), ("cdef", LINE)], "clip at end");
+    }
+
+    #[test]
+    fn into_spans_scroll() {
+        // Gutter is hidden
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.line_number(0, 1, LNUM);
+        lh.scroll(2, 2, 0);
+        assert_spans(lh, &[("cdef", DEFAULT)], "gutter");
+
+        // Highlights before the start are cut
+        let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+        lh.cursor_line(4, LINE);
+        lh.selection(0, 0, 1, 0, 3);
+        lh.scroll(2, 2, 0);
+        assert_spans(lh, &[("c", SEL), ("d", LINE), ("e", CUR), ("f", LINE)], "selection");
+
+        // Tabs are aligned with the display width before the start
+        let mut lh = LineHighlighter::new("あ\tb\tc", CUR, 4, None, SEL);
+        lh.scroll(3, 2, 1);
+        assert_spans(lh, &[(" ", DEFAULT), ("  b   c", DEFAULT)], "padding");
+
+        let mut lh = LineHighlighter::new("ab", CUR, 4, None, SEL);
+        lh.cursor_line(2, LINE);
+        lh.scroll(2, 2, 0);
+        assert_spans(lh, &[(" ", CUR)], "cursor at end");
+    }
+
     #[test]
     fn into_spans_mixed_highlights() {
         let tests = [
@@ -1,7 +1,11 @@
+use crate::storage::Lines;
 use crate::util::Pos;
+use std::cmp;
 use std::collections::VecDeque;
+use std::mem;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EditKind {
     InsertChar(char),
     DeleteChar(char),
@@ -14,62 +18,55 @@ pub enum EditKind {
     MoveLine(bool),
     DeleteLine(usize, String),
     InsertLine(usize, String),
+    Batch(Vec<Edit>),
 }
 
 impl EditKind {
-    pub(crate) fn apply(&self, lines: &mut Vec<String>, before: &Pos, after: &Pos) {
+    pub(crate) fn apply(&self, lines: &mut Lines, before: &Pos, after: &Pos) {
         match self {
             EditKind::InsertChar(c) => {
-                lines[before.row].insert(before.offset, *c);
+                lines.insert_char(before.row, before.offset, *c);
             }
-            EditKind::DeleteChar(_) => {
-                lines[before.row].remove(after.offset);
+            EditKind::DeleteChar(c) => {
+                lines.remove_str(before.row, after.offset..after.offset + c.len_utf8());
             }
             EditKind::InsertNewline => {
-                let line = &mut lines[before.row];
-                let next_line = line[before.offset..].to_string();
-                line.truncate(before.offset);
-                lines.insert(before.row + 1, next_line);
+                lines.split_line(before.row, before.offset);
             }
             EditKind::DeleteNewline => {
                 debug_assert!(before.row > 0, "invalid pos: {:?}", before);
-                let line = lines.remove(before.row);
-                lines[before.row - 1].push_str(&line);
+                lines.join_line(before.row - 1);
             }
             EditKind::InsertStr(s) => {
-                lines[before.row].insert_str(before.offset, s.as_str());
+                lines.insert_str(before.row, before.offset, s.as_str());
             }
             EditKind::DeleteStr(s) => {
-                lines[after.row].drain(after.offset..after.offset + s.len());
+                lines.remove_str(after.row, after.offset..after.offset + s.len());
             }
             EditKind::InsertChunk(c) => {
                 debug_assert!(c.len() > 1, "Chunk size must be > 1: {:?}", c);
 
-                // Handle first line of chunk
-                let first_line = &mut lines[before.row];
-                let mut last_line = first_line.drain(before.offset..).as_str().to_string();
-                first_line.push_str(&c[0]);
-
-                // Handle last line of chunk
-                let next_row = before.row + 1;
-                last_line.insert_str(0, c.last().unwrap());
-                lines.insert(next_row, last_line);
+                // Split the line at the position and put the first and last lines of the chunk around the split
+                let (row, offset) = (before.row, before.offset);
+                lines.split_line(row, offset);
+                lines.insert_str(row, offset, &c[0]);
+                lines.insert_str(row + 1, 0, c.last().unwrap());
 
                 // Handle middle lines of chunk
-                lines.splice(next_row..next_row, c[1..c.len() - 1].iter().cloned());
+                let next_row = row + 1;
+                lines.splice(next_row..next_row, c[1..c.len() - 1].to_vec());
             }
             EditKind::DeleteChunk(c) => {
                 debug_assert!(c.len() > 1, "Chunk size must be > 1: {:?}", c);
 
                 // Remove middle lines of chunk
-                let mut last_line = lines.drain(after.row + 1..after.row + c.len()).last().unwrap();
-                // Remove last line of chunk
-                last_line.drain(..c[c.len() - 1].len());
-
-                // Remove first line of chunk and concat remaining
-                let first_line = &mut lines[after.row];
-                first_line.truncate(after.offset);
-                first_line.push_str(&last_line);
+                let (row, offset) = (after.row, after.offset);
+                lines.splice(row + 1..row + c.len() - 1, vec![]);
+                // Remove the first and last lines of chunk and concat remaining
+                lines.remove_str(row + 1, 0..c[c.len() - 1].len());
+                let len = lines.line_len(row);
+                lines.remove_str(row, offset..len);
+                lines.join_line(row);
             }
             EditKind::MoveLine(reverse) => {
                 if *reverse {
@@ -79,14 +76,70 @@ impl EditKind {
                 }
             }
             EditKind::DeleteLine(line, _string) => {
-                lines.remove(*line);
+                lines.remove_line(*line);
             }
             EditKind::InsertLine(line, string) => {
-                lines.insert(*line, string.clone());
+                lines.insert_line(*line, string.clone());
+            }
+            EditKind::Batch(edits) => {
+                for edit in edits {
+                    edit.redo(lines);
+                }
             }
         }
     }
 
+    // Apply the edit only when it is applicable to the text. Positions must be in the text and deleted texts must be the
+    // same as the text at the positions. Inner edits of a batch before an invalid one are applied
+    #[cfg(feature = "serde")]
+    fn try_apply(&self, lines: &mut Lines, before: &Pos, after: &Pos) -> bool {
+        let has_offset =
+            |lines: &Lines, pos: &Pos| pos.row < lines.len() && lines.line(pos.row).is_char_boundary(pos.offset);
+        let starts_with = |lines: &Lines, pos: &Pos, s: &str| {
+            pos.row < lines.len()
+                && lines
+                    .line(pos.row)
+                    .get(pos.offset..)
+                    .map_or(false, |rest| rest.starts_with(s))
+        };
+        let valid = match self {
+            EditKind::InsertChar(c) => *c != '\n' && has_offset(lines, before),
+            EditKind::DeleteChar(c) => starts_with(lines, after, c.encode_utf8(&mut [0; 4])),
+            EditKind::InsertNewline => has_offset(lines, before),
+            EditKind::DeleteNewline => 0 < before.row && before.row < lines.len(),
+            EditKind::InsertStr(s) => !s.contains('\n') && has_offset(lines, before),
+            EditKind::DeleteStr(s) => starts_with(lines, after, s),
+            EditKind::InsertChunk(c) => c.len() > 1 && c.iter().all(|l| !l.contains('\n')) && has_offset(lines, before),
+            EditKind::DeleteChunk(c) => {
+                let (row, last) = (after.row, c.len().saturating_sub(1));
+                c.len() > 1
+                    && row + last < lines.len()
+                    && lines.line(row).get(after.offset..) == Some(c[0].as_str())
+                    && (1..last).all(|i| lines.line(row + i) == c[i])
+                    && lines.line(row + last).starts_with(c[last].as_str())
+            }
+            EditKind::MoveLine(_) => before.row < lines.len() && after.row < lines.len(),
+            EditKind::DeleteLine(row, s) => *row < lines.len() && lines.line(*row) == s.as_str(),
+            EditKind::InsertLine(row, s) => *row <= lines.len() && !s.contains('\n'),
+            EditKind::Batch(edits) => return edits.iter().all(|e| e.kind.try_apply(lines, &e.before, &e.after)),
+        };
+        if valid {
+            self.apply(lines, before, after);
+        }
+        valid
+    }
+
+    // Bytes of the texts owned by this edit kind
+    fn heap_bytes(&self) -> usize {
+        use EditKind::*;
+        match self {
+            InsertChar(_) | DeleteChar(_) | InsertNewline | DeleteNewline | MoveLine(_) => 0,
+            InsertStr(s) | DeleteStr(s) | DeleteLine(_, s) | InsertLine(_, s) => s.len(),
+            InsertChunk(c) | DeleteChunk(c) => c.iter().map(|s| mem::size_of::<String>() + s.len()).sum(),
+            Batch(edits) => edits.iter().map(Edit::size_bytes).sum(),
+        }
+    }
+
     fn invert(&self) -> Self {
         use EditKind::*;
         match self.clone() {
@@ -101,11 +154,19 @@ impl EditKind {
             MoveLine(reverse) => MoveLine(!reverse),
             DeleteLine(line, string) => InsertLine(line, string),
             InsertLine(line, string) => DeleteLine(line, string),
+            Batch(edits) => Batch(
+                edits
+                    .into_iter()
+                    .rev()
+                    .map(|e| Edit::new(e.kind.invert(), e.after, e.before))
+                    .collect(),
+            ),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edit {
     kind: EditKind,
     before: Pos,
@@ -117,14 +178,30 @@ impl Edit {
         Self { kind, before, after }
     }
 
-    pub fn redo(&self, lines: &mut Vec<String>) {
+    pub fn redo(&self, lines: &mut Lines) {
         self.kind.apply(lines, &self.before, &self.after);
     }
 
-    pub fn undo(&self, lines: &mut Vec<String>) {
+    pub fn undo(&self, lines: &mut Lines) {
         self.kind.invert().apply(lines, &self.after, &self.before); // Undo is redo of inverted edit
     }
 
+    // Redo the edit only when it is applicable to the text. The cursor positions before and after the edit must also be
+    // in the text. See `EditKind::try_apply`
+    #[cfg(feature = "serde")]
+    fn try_redo(&self, lines: &mut Lines) -> bool {
+        let in_text = |lines: &Lines, pos: &Pos| pos.row < lines.len() && pos.col <= lines.char_count(pos.row);
+        in_text(lines, &self.before)
+            && self.kind.try_apply(lines, &self.before, &self.after)
+            && in_text(lines, &self.after)
+    }
+
+    /// Approximate memory size of this edit in bytes. Lengths of the texts are counted rather than their capacities
+    /// so that the size does not depend on the allocator.
+    pub fn size_bytes(&self) -> usize {
+        mem::size_of::<Self>() + self.kind.heap_bytes()
+    }
+
     pub fn cursor_before(&self) -> (usize, usize) {
         (self.before.row, self.before.col)
     }
@@ -132,13 +209,248 @@ impl Edit {
     pub fn cursor_after(&self) -> (usize, usize) {
         (self.after.row, self.after.col)
     }
+
+    // First row of the text which is changed by this edit. Lines above it are not touched by this edit nor its undo
+    pub fn first_row(&self) -> usize {
+        match &self.kind {
+            EditKind::DeleteLine(row, _) | EditKind::InsertLine(row, _) => *row,
+            EditKind::Batch(edits) => edits.iter().map(Edit::first_row).min().unwrap_or(self.before.row),
+            _ => cmp::min(self.before.row, self.after.row),
+        }
+    }
+
+    /// Get the edit reverting this edit. Undo is the same as redo of the inverted edit.
+    pub fn inverted(&self) -> Self {
+        Self::new(self.kind.invert(), self.after.clone(), self.before.clone())
+    }
+
+    /// Describe this edit as [`TextEdit`]s and push them to `out`. `lines` is the text after this edit was applied.
+    pub fn describe(&self, lines: &Lines, out: &mut Vec<TextEdit>) {
+        if let EditKind::Batch(_) = &self.kind {
+            // Inner edits are described on the text between them, which needs to be restored
+            let mut lines = lines.clone();
+            self.undo(&mut lines);
+            self.describe_batch(&mut lines, out);
+        } else {
+            // Describe the inverted edit on the text after this edit, and then turn it around
+            let inverted = self.inverted();
+            let e = inverted.describe_on(lines);
+            out.push(TextEdit {
+                start: e.start,
+                end: end_of(e.start, &e.inserted),
+                inserted: e.deleted,
+                deleted: e.inserted,
+            });
+        }
+    }
+
+    // `lines` is the text before this edit was applied. Each edit is applied to `lines` after it is described
+    fn describe_batch(&self, lines: &mut Lines, out: &mut Vec<TextEdit>) {
+        if let EditKind::Batch(edits) = &self.kind {
+            for edit in edits {
+                edit.describe_batch(lines, out);
+            }
+        } else {
+            out.push(self.describe_on(lines));
+            self.redo(lines);
+        }
+    }
+
+    // Describe this edit which is not a batch. `lines` is the text before this edit was applied. Positions are
+    // calculated in the same way as `EditKind::apply`.
+    fn describe_on(&self, lines: &Lines) -> TextEdit {
+        fn line_end(lines: &Lines, row: usize) -> (usize, usize) {
+            (row, lines.char_count(row))
+        }
+        fn insert(start: (usize, usize), inserted: String) -> TextEdit {
+            TextEdit {
+                start,
+                end: start,
+                inserted,
+                deleted: String::new(),
+            }
+        }
+        fn delete(start: (usize, usize), deleted: String) -> TextEdit {
+            TextEdit {
+                start,
+                end: end_of(start, &deleted),
+                inserted: String::new(),
+                deleted,
+            }
+        }
+
+        let (before, after) = (&self.before, &self.after);
+        let insert_at = || (before.row, lines.col_of_offset(before.row, before.offset));
+        match &self.kind {
+            EditKind::InsertChar(c) => insert(insert_at(), c.to_string()),
+            EditKind::DeleteChar(c) => delete(
+                (before.row, lines.col_of_offset(before.row, after.offset)),
+                c.to_string(),
+            ),
+            EditKind::InsertNewline => insert(insert_at(), "\n".to_string()),
+            EditKind::DeleteNewline => delete(line_end(lines, before.row - 1), "\n".to_string()),
+            EditKind::InsertStr(s) => insert(insert_at(), s.clone()),
+            EditKind::DeleteStr(s) => delete((after.row, lines.col_of_offset(after.row, after.offset)), s.clone()),
+            EditKind::InsertChunk(c) => insert(insert_at(), c.join("\n")),
+            EditKind::DeleteChunk(c) => delete((after.row, lines.col_of_offset(after.row, after.offset)), c.join("\n")),
+            EditKind::MoveLine(_) => {
+                let (top, bottom) = (before.row.min(after.row), before.row.max(after.row));
+                let mut moved = lines.to_vec_in(top..bottom + 1);
+                let deleted = moved.join("\n");
+                moved.swap(0, bottom - top);
+                TextEdit {
+                    start: (top, 0),
+                    end: line_end(lines, bottom),
+                    inserted: moved.join("\n"),
+                    deleted,
+                }
+            }
+            EditKind::DeleteLine(row, _) if row + 1 < lines.len() => {
+                delete((*row, 0), format!("{}\n", lines.line(*row)))
+            }
+            EditKind::DeleteLine(row, _) => delete(line_end(lines, row - 1), format!("\n{}", lines.line(*row))),
+            EditKind::InsertLine(row, s) if *row < lines.len() => insert((*row, 0), format!("{}\n", s)),
+            EditKind::InsertLine(row, s) => insert(line_end(lines, row - 1), format!("\n{}", s)),
+            EditKind::Batch(_) => unreachable!("batch edit must be described with `describe_batch`"),
+        }
+    }
+}
+
+/// A change of the text in the textarea. Edits are collected while edit tracking is enabled by
+/// [`TextArea::set_edit_tracking`](crate::TextArea::set_edit_tracking) and can be taken by
+/// [`TextArea::take_edits`](crate::TextArea::take_edits).
+///
+/// Each edit replaces the text between `start` and `end` with `inserted`. Positions are pairs of (row, col) where `col`
+/// is counted in characters. Both positions point to the text before the edit is applied. Lines are separated by
+/// `'\n'` in `inserted` and `deleted`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    /// Start position of the replaced text.
+    pub start: (usize, usize),
+    /// End position (exclusive) of the replaced text. This is the same as `start` when no text is deleted.
+    pub end: (usize, usize),
+    /// Text inserted at `start`.
+    pub inserted: String,
+    /// Text deleted between `start` and `end`.
+    pub deleted: String,
+}
+
+impl TextEdit {
+    // Where the position in the text before this edit is moved to after this edit. Positions after the replaced text
+    // are shifted and positions inside it are moved to `start`. `None` is returned when the line at the position is
+    // removed, which means the replaced text contains both the position and the newline before or after its line.
+    pub(crate) fn shift_pos(&self, pos: (usize, usize)) -> Option<(usize, usize)> {
+        if pos < self.start {
+            Some(pos)
+        } else if pos >= self.end {
+            Some(self.shift_after(pos))
+        } else if self.start.0 < pos.0 || self.start == (pos.0, 0) && self.end.0 > pos.0 {
+            None
+        } else {
+            Some(self.start)
+        }
+    }
+
+    // Where the range in the text before this edit is moved to after this edit. The deleted part of the range is cut
+    // off. Text inserted at the start of the range is not included in the range, and text inserted at its end is not
+    // either. `None` is returned when nothing remains in the range.
+    pub(crate) fn shift_range(
+        &self,
+        (start, end): ((usize, usize), (usize, usize)),
+    ) -> Option<((usize, usize), (usize, usize))> {
+        let start = if start < self.start {
+            start
+        } else if start >= self.end {
+            self.shift_after(start)
+        } else {
+            end_of(self.start, &self.inserted)
+        };
+        let end = if end <= self.start {
+            end
+        } else if end >= self.end {
+            self.shift_after(end)
+        } else {
+            self.start
+        };
+        (start < end).then_some((start, end))
+    }
+
+    // Where the range in the text before this edit is moved to after this edit. Unlike `shift_range`, text inserted at
+    // the edges of the range or replacing a part of the range is included in the range. The range becomes empty when
+    // all of its text is deleted
+    #[cfg(feature = "search")]
+    pub(crate) fn shift_range_inclusive(
+        &self,
+        (start, end): ((usize, usize), (usize, usize)),
+    ) -> ((usize, usize), (usize, usize)) {
+        let start = if start <= self.start {
+            start
+        } else if start >= self.end {
+            self.shift_after(start)
+        } else {
+            self.start
+        };
+        let end = if end < self.start {
+            end
+        } else if end >= self.end {
+            self.shift_after(end)
+        } else {
+            end_of(self.start, &self.inserted)
+        };
+        (start, end)
+    }
+
+    // Position after this edit of the position at or after the end of the replaced text
+    fn shift_after(&self, pos: (usize, usize)) -> (usize, usize) {
+        let new_end = end_of(self.start, &self.inserted);
+        if pos.0 == self.end.0 {
+            (new_end.0, new_end.1 + pos.1 - self.end.1)
+        } else {
+            (pos.0 - self.end.0 + new_end.0, pos.1)
+        }
+    }
+}
+
+// Position after the text `s` inserted at `start`
+fn end_of(start: (usize, usize), s: &str) -> (usize, usize) {
+    match s.rfind('\n') {
+        Some(i) => (start.0 + s.matches('\n').count(), s[i + 1..].chars().count()),
+        None => (start.0, start.1 + s.chars().count()),
+    }
+}
+
+/// How consecutive character insertions are merged into one undo unit. This is set by
+/// [`TextArea::set_undo_grouping`](crate::TextArea::set_undo_grouping).
+///
+/// Characters are merged only when they are inserted one after another in the same line. Moving the cursor or any
+/// other kind of edit such as inserting a newline or deleting a character ends the current undo unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UndoGrouping {
+    /// Every inserted character is a separate undo unit.
+    None,
+    /// Consecutive inserted characters are merged into one undo unit. A whitespace inserted after a non-whitespace
+    /// character starts a new undo unit so that one undo removes one word. This is the default.
+    #[default]
+    ByWord,
+    /// Consecutive inserted characters are merged into one undo unit including whitespaces.
+    ByEdit,
 }
 
 #[derive(Clone, Debug)]
 pub struct History {
     index: usize,
     max_items: usize,
+    max_bytes: usize,
+    bytes: usize, // Total size of the edits in bytes
     edits: VecDeque<Edit>,
+    states: VecDeque<u64>, // Serial number of the state of the text after each edit
+    base: u64,             // Serial number of the state of the text before the oldest edit
+    serial: u64,           // Serial number given to the newest state of the text
+    changes: u64,          // Number of modifications including undo and redo
+    group: Option<Vec<Edit>>,
+    grouping: UndoGrouping,
+    group_limit: Option<usize>,
+    merged: usize, // Number of characters merged into the last edit. 0 means the last edit cannot be merged
 }
 
 impl History {
@@ -146,54 +458,307 @@ impl History {
         Self {
             index: 0,
             max_items,
+            max_bytes: usize::MAX,
+            bytes: 0,
             edits: VecDeque::new(),
+            states: VecDeque::new(),
+            base: 0,
+            serial: 0,
+            changes: 0,
+            group: None,
+            grouping: UndoGrouping::default(),
+            group_limit: None,
+            merged: 0,
         }
     }
 
     pub fn push(&mut self, edit: Edit) {
+        self.changes += 1;
         if self.max_items == 0 {
+            // The text is modified without any edit to undo
+            self.base = self.next_serial();
             return;
         }
 
+        if let Some(group) = &mut self.group {
+            group.push(edit);
+            return;
+        }
+
+        if self.merge(&edit) {
+            return;
+        }
+        self.merged = usize::from(matches!(edit.kind, EditKind::InsertChar(_)));
+        self.push_item(edit);
+    }
+
+    // Merge the inserted character into the last edit following the undo grouping
+    fn merge(&mut self, edit: &Edit) -> bool {
+        let EditKind::InsertChar(c) = edit.kind else {
+            return false;
+        };
+        if self.grouping == UndoGrouping::None
+            || self.merged == 0
+            || self.group_limit.map_or(false, |limit| self.merged >= limit)
+            || self.index != self.edits.len()
+        {
+            return false;
+        }
+        let Some(last) = self.edits.back_mut() else {
+            return false;
+        };
+        if last.after.row != edit.before.row || last.after.offset != edit.before.offset {
+            return false;
+        }
+        let mut s = match &last.kind {
+            EditKind::InsertChar(c) => c.to_string(),
+            EditKind::InsertStr(s) => s.clone(),
+            _ => return false,
+        };
+        let prev = s.chars().next_back().unwrap();
+        if self.grouping == UndoGrouping::ByWord && c.is_whitespace() && !prev.is_whitespace() {
+            return false;
+        }
+        s.push(c);
+        self.bytes -= last.size_bytes();
+        last.kind = EditKind::InsertStr(s);
+        last.after = edit.after.clone();
+        self.bytes += last.size_bytes();
+        self.merged += 1;
+        let serial = self.next_serial();
+        *self.states.back_mut().unwrap() = serial;
+        self.evict();
+        true
+    }
+
+    fn push_item(&mut self, edit: Edit) {
         if self.edits.len() == self.max_items {
-            self.edits.pop_front();
-            self.index = self.index.saturating_sub(1);
+            self.pop_front();
         }
 
-        if self.index < self.edits.len() {
-            self.edits.truncate(self.index);
+        while self.index < self.edits.len() {
+            self.pop_back();
         }
 
         self.index += 1;
+        self.bytes += edit.size_bytes();
         self.edits.push_back(edit);
+        let serial = self.next_serial();
+        self.states.push_back(serial);
+        self.evict();
     }
 
-    pub fn apply(&mut self, lines: &mut Vec<String>) -> (usize, usize) {
-        let edit = &self.edits[self.index - 1];
-        edit.redo(lines);
-        edit.cursor_after()
+    fn pop_front(&mut self) {
+        if let Some(edit) = self.edits.pop_front() {
+            self.bytes -= edit.size_bytes();
+            self.index = self.index.saturating_sub(1);
+            self.base = self.states.pop_front().unwrap();
+        }
+    }
+
+    fn pop_back(&mut self) {
+        if let Some(edit) = self.edits.pop_back() {
+            self.bytes -= edit.size_bytes();
+            self.index = self.index.min(self.edits.len());
+            self.states.pop_back();
+        }
+    }
+
+    // Forget edits until their total size fits in the max bytes. The oldest edits for undo are forgotten first, and
+    // then the edits for redo are forgotten from the newest so that the remaining edits are still consecutive
+    fn evict(&mut self) {
+        while self.bytes > self.max_bytes && self.index > 0 {
+            self.pop_front();
+        }
+        while self.bytes > self.max_bytes {
+            self.pop_back();
+        }
+    }
+
+    /// Start grouping edits pushed after this call into one undo unit until [`History::end_group`] is called. Nested
+    /// calls are merged into the outermost group. This returns `true` when a new group was started, which means the
+    /// caller is responsible for ending it.
+    pub fn begin_group(&mut self) -> bool {
+        if self.group.is_some() {
+            return false;
+        }
+        self.group = Some(vec![]);
+        true
     }
 
-    pub fn redo(&mut self, lines: &mut Vec<String>) -> Option<(usize, usize)> {
+    pub fn end_group(&mut self) {
+        let Some(mut edits) = self.group.take() else {
+            return;
+        };
+        if edits.is_empty() || self.max_items == 0 {
+            return;
+        }
+        self.merged = 0;
+        if edits.len() == 1 {
+            self.push_item(edits.remove(0));
+        } else {
+            let before = edits[0].before.clone();
+            let after = edits[edits.len() - 1].after.clone();
+            self.push_item(Edit::new(EditKind::Batch(edits), before, after));
+        }
+    }
+
+    /// End the current undo unit so that the next inserted character is not merged into the last edit.
+    pub fn stop_merging(&mut self) {
+        self.merged = 0;
+    }
+
+    pub fn redo(&mut self, lines: &mut Lines) -> Option<&Edit> {
+        self.end_group();
+        self.merged = 0;
         if self.index == self.edits.len() {
             return None;
         }
         let edit = &self.edits[self.index];
         edit.redo(lines);
         self.index += 1;
-        Some(edit.cursor_after())
+        self.changes += 1;
+        Some(edit)
     }
 
-    pub fn undo(&mut self, lines: &mut Vec<String>) -> Option<(usize, usize)> {
+    pub fn undo(&mut self, lines: &mut Lines) -> Option<&Edit> {
+        self.end_group();
+        self.merged = 0;
         self.index = self.index.checked_sub(1)?;
+        self.changes += 1;
         let edit = &self.edits[self.index];
         edit.undo(lines);
-        Some(edit.cursor_before())
+        Some(edit)
+    }
+
+    /// Forget all edits since the text was modified without recording them. Edits in the current group are also
+    /// discarded but the group is kept open.
+    pub fn clear(&mut self) {
+        self.index = 0;
+        self.merged = 0;
+        self.bytes = 0;
+        self.edits.clear();
+        self.states.clear();
+        self.base = self.next_serial();
+        self.changes += 1;
+        if let Some(group) = &mut self.group {
+            group.clear();
+        }
+    }
+
+    fn next_serial(&mut self) -> u64 {
+        self.serial += 1;
+        self.serial
+    }
+
+    /// Serial number identifying the current state of the text in the history. Undoing and redoing back to the same
+    /// edit gives the same number, and any other modification gives a new number.
+    pub fn state(&self) -> u64 {
+        self.state_at(self.index)
+    }
+
+    /// Serial number of the state of the text after the number of edits from the oldest one.
+    pub fn state_at(&self, index: usize) -> u64 {
+        index.checked_sub(1).map_or(self.base, |i| self.states[i])
+    }
+
+    /// Edits in the history from the oldest one, and the number of edits which can be undone.
+    #[cfg(feature = "serde")]
+    pub fn edits(&self) -> (Vec<Edit>, usize) {
+        (self.edits.iter().cloned().collect(), self.index)
+    }
+
+    /// Number of edits from the oldest one to reach the state of the text.
+    #[cfg(feature = "serde")]
+    pub fn index_of_state(&self, state: u64) -> Option<usize> {
+        (0..=self.edits.len()).find(|&i| self.state_at(i) == state)
+    }
+
+    /// Replace the history with the edits. `index` is the number of edits which can be undone from the current text
+    /// `lines`. Old edits exceeding the limits are forgotten. This returns the number of the forgotten edits, or `None`
+    /// when the history was cleared since some of the edits to undo were forgotten or some edit cannot be applied to the
+    /// text.
+    #[cfg(feature = "serde")]
+    pub fn restore(&mut self, edits: Vec<Edit>, index: usize, lines: &Lines) -> Option<usize> {
+        self.clear();
+        let len = edits.len();
+        if self.max_items == 0 || index > len || !Self::is_applicable(&edits, index, lines) {
+            return None;
+        }
+        for edit in edits {
+            self.push_item(edit);
+        }
+        let forgotten = len - self.edits.len();
+        let Some(index) = index.checked_sub(forgotten) else {
+            self.clear();
+            return None;
+        };
+        self.index = index;
+        Some(forgotten)
+    }
+
+    // Check that the edits can be undone from the text to the oldest one, and then redone to the newest one. The edits
+    // come from outside, such as a saved file, so they cannot be trusted
+    #[cfg(feature = "serde")]
+    fn is_applicable(edits: &[Edit], index: usize, lines: &Lines) -> bool {
+        let mut text = lines.clone();
+        if !edits[..index].iter().rev().all(|e| e.inverted().try_redo(&mut text)) {
+            return false;
+        }
+        for (i, edit) in edits.iter().enumerate() {
+            // Redoing the edits to undo must reach the current text again
+            if i == index && text != *lines {
+                return false;
+            }
+            if !edit.try_redo(&mut text) {
+                return false;
+            }
+        }
+        index < edits.len() || text == *lines
+    }
+
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+
+    /// Take over the state of the text and the number of modifications from the other history so that replacing the
+    /// history does not look like a modification.
+    pub fn inherit(&mut self, other: &History) {
+        self.base = other.state();
+        self.serial = other.serial;
+        self.changes = other.changes;
     }
 
     pub fn max_items(&self) -> usize {
         self.max_items
     }
+
+    pub fn set_max_bytes(&mut self, max: usize) {
+        self.max_bytes = max;
+        self.evict();
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn set_grouping(&mut self, grouping: UndoGrouping, limit: Option<usize>) {
+        self.grouping = grouping;
+        self.group_limit = limit;
+        self.merged = 0;
+    }
+
+    pub fn grouping(&self) -> UndoGrouping {
+        self.grouping
+    }
+
+    pub fn group_limit(&self) -> Option<usize> {
+        self.group_limit
+    }
 }
 
 #[cfg(test)]
@@ -647,7 +1212,7 @@ mod tests {
                     .unwrap_or(before[row].len());
                 Pos::new(row, col, offset)
             };
-            let mut lines: Vec<_> = before.iter().map(|s| s.to_string()).collect();
+            let mut lines = Lines::from(before.iter().map(|s| s.to_string()).collect::<Vec<_>>());
             let chunk: Vec<_> = input.iter().map(|s| s.to_string()).collect();
             let after_pos = {
                 let row = row + input.len() - 1;
@@ -662,7 +1227,166 @@ mod tests {
 
             let edit = EditKind::DeleteChunk(chunk);
             edit.apply(&mut lines, &after_pos, &before_pos);
-            assert_eq!(&lines, &before, "{test:?}");
+            assert_eq!(&lines, before, "{test:?}");
         }
     }
+
+    #[test]
+    fn shift_pos() {
+        let edit = |start, end, inserted: &str| TextEdit {
+            start,
+            end,
+            inserted: inserted.to_string(),
+            deleted: String::new(), // Not used
+        };
+
+        // Insert "x\ny" at (1, 2)
+        let e = edit((1, 2), (1, 2), "x\ny");
+        assert_eq!(e.shift_pos((0, 5)), Some((0, 5)));
+        assert_eq!(e.shift_pos((1, 1)), Some((1, 1)));
+        assert_eq!(e.shift_pos((1, 2)), Some((2, 1)));
+        assert_eq!(e.shift_pos((1, 4)), Some((2, 3)));
+        assert_eq!(e.shift_pos((3, 4)), Some((4, 4)));
+
+        // Replace (1, 2)..(3, 1) with "z"
+        let e = edit((1, 2), (3, 1), "z");
+        assert_eq!(e.shift_pos((1, 1)), Some((1, 1)));
+        assert_eq!(e.shift_pos((1, 3)), Some((1, 2)));
+        assert_eq!(e.shift_pos((2, 0)), None); // The whole line is deleted
+        assert_eq!(e.shift_pos((3, 0)), None); // The line is joined to the previous line
+        assert_eq!(e.shift_pos((3, 1)), Some((1, 3)));
+        assert_eq!(e.shift_pos((3, 4)), Some((1, 6)));
+        assert_eq!(e.shift_pos((4, 4)), Some((2, 4)));
+
+        // Delete the line at row 1
+        let e = edit((1, 0), (2, 0), "");
+        assert_eq!(e.shift_pos((1, 3)), None);
+        assert_eq!(e.shift_pos((2, 3)), Some((1, 3)));
+
+        // Delete the last line at row 2
+        let e = edit((1, 3), (2, 3), "");
+        assert_eq!(e.shift_pos((1, 2)), Some((1, 2)));
+        assert_eq!(e.shift_pos((2, 0)), None);
+        assert_eq!(e.shift_pos((2, 3)), Some((1, 3)));
+
+        // Delete the text in the line at row 1
+        let e = edit((1, 0), (1, 3), "");
+        assert_eq!(e.shift_pos((1, 2)), Some((1, 0)));
+    }
+
+    #[test]
+    fn shift_range() {
+        let edit = |start, end, inserted: &str| TextEdit {
+            start,
+            end,
+            inserted: inserted.to_string(),
+            deleted: String::new(), // Not used
+        };
+
+        // Insert "xy" at (1, 2)
+        let e = edit((1, 2), (1, 2), "xy");
+        assert_eq!(e.shift_range(((0, 0), (1, 1))), Some(((0, 0), (1, 1))));
+        assert_eq!(e.shift_range(((1, 0), (1, 4))), Some(((1, 0), (1, 6)))); // Inserted inside
+        assert_eq!(e.shift_range(((1, 2), (1, 4))), Some(((1, 4), (1, 6)))); // Inserted at the start
+        assert_eq!(e.shift_range(((1, 0), (1, 2))), Some(((1, 0), (1, 2)))); // Inserted at the end
+        assert_eq!(e.shift_range(((1, 3), (2, 1))), Some(((1, 5), (2, 1))));
+
+        // Replace (1, 2)..(3, 1) with "z\nw"
+        let e = edit((1, 2), (3, 1), "z\nw");
+        assert_eq!(e.shift_range(((1, 0), (1, 4))), Some(((1, 0), (1, 2)))); // The end is deleted
+        assert_eq!(e.shift_range(((2, 0), (3, 3))), Some(((2, 1), (2, 3)))); // The start is deleted
+        assert_eq!(e.shift_range(((1, 0), (4, 0))), Some(((1, 0), (3, 0))));
+        assert_eq!(e.shift_range(((2, 0), (2, 5))), None); // The whole range is deleted
+        assert_eq!(e.shift_range(((3, 1), (3, 2))), Some(((2, 1), (2, 2))));
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn shift_range_inclusive() {
+        let edit = |start, end, inserted: &str| TextEdit {
+            start,
+            end,
+            inserted: inserted.to_string(),
+            deleted: String::new(), // Not used
+        };
+
+        // Insert "xy" at (1, 2)
+        let e = edit((1, 2), (1, 2), "xy");
+        assert_eq!(e.shift_range_inclusive(((0, 0), (1, 1))), ((0, 0), (1, 1)));
+        assert_eq!(e.shift_range_inclusive(((1, 0), (1, 4))), ((1, 0), (1, 6))); // Inserted inside
+        assert_eq!(e.shift_range_inclusive(((1, 2), (1, 4))), ((1, 2), (1, 6))); // Inserted at the start
+        assert_eq!(e.shift_range_inclusive(((1, 0), (1, 2))), ((1, 0), (1, 4))); // Inserted at the end
+        assert_eq!(e.shift_range_inclusive(((1, 3), (2, 1))), ((1, 5), (2, 1)));
+
+        // Replace (1, 2)..(1, 5) with "z\nw"
+        let e = edit((1, 2), (1, 5), "z\nw");
+        assert_eq!(e.shift_range_inclusive(((1, 2), (1, 5))), ((1, 2), (2, 1))); // The whole range is replaced
+        assert_eq!(e.shift_range_inclusive(((1, 0), (1, 3))), ((1, 0), (2, 1))); // The end is replaced
+        assert_eq!(e.shift_range_inclusive(((1, 3), (1, 7))), ((1, 2), (2, 3))); // The start is replaced
+        assert_eq!(e.shift_range_inclusive(((1, 6), (3, 0))), ((2, 2), (4, 0)));
+
+        // Delete (0, 1)..(2, 0)
+        let e = edit((0, 1), (2, 0), "");
+        assert_eq!(e.shift_range_inclusive(((1, 0), (1, 3))), ((0, 1), (0, 1)));
+        // The whole range is deleted
+    }
+
+    #[test]
+    fn size_bytes() {
+        let pos = |col| Pos::new(0, col, col);
+        let insert = |c, col| Edit::new(EditKind::InsertChar(c), pos(col), pos(col + 1));
+        let total = |h: &History| h.edits.iter().map(Edit::size_bytes).sum::<usize>();
+        let mut lines = Lines::from(vec![String::new()]);
+        let mut push = |h: &mut History, edit: Edit| {
+            edit.redo(&mut lines);
+            h.push(edit);
+        };
+
+        let mut h = History::new(10);
+        for (i, c) in "abc".chars().enumerate() {
+            push(&mut h, insert(c, i));
+        }
+        assert_eq!(h.edits.len(), 1); // Merged
+        assert_eq!(h.size_bytes(), total(&h));
+        h.stop_merging();
+        push(
+            &mut h,
+            Edit::new(EditKind::InsertStr("x".repeat(100)), pos(3), pos(103)),
+        );
+        assert_eq!(h.size_bytes(), total(&h));
+
+        let mut text = Lines::from(vec!["abc".to_string() + &"x".repeat(100)]);
+        h.undo(&mut text);
+        assert_eq!(text, ["abc"]);
+        h.push(insert('d', 3));
+        assert_eq!(h.edits.len(), 2); // The undone edit was removed
+        assert_eq!(h.size_bytes(), total(&h));
+
+        // Oldest edits are evicted
+        h.set_max_bytes(h.size_bytes() - 1);
+        assert_eq!(h.edits.len(), 1);
+        assert_eq!(h.index, 1);
+        assert_eq!(h.size_bytes(), total(&h));
+
+        // Edits for redo are evicted from the newest
+        let mut text = Lines::from(vec!["abcd".to_string()]);
+        h.set_max_bytes(usize::MAX);
+        h.stop_merging();
+        h.push(insert('e', 4));
+        text.insert_char(0, 4, 'e');
+        h.undo(&mut text);
+        h.undo(&mut text);
+        assert_eq!(text, ["abc"]);
+        assert_eq!(h.index, 0);
+        h.set_max_bytes(h.size_bytes() - 1);
+        assert_eq!(h.edits.len(), 1);
+        assert!(matches!(h.edits[0].kind, EditKind::InsertChar('d')));
+        assert_eq!(h.size_bytes(), total(&h));
+
+        h.set_max_bytes(0);
+        assert_eq!(h.edits.len(), 0);
+        assert_eq!(h.size_bytes(), 0);
+        h.push(insert('f', 3));
+        assert_eq!(h.edits.len(), 0);
+    }
 }
@@ -1,12 +1,15 @@
-use super::{Input, Key};
-use crate::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind};
+use super::{Input, InputConversionError, InputKind, Key, MediaKey, ModifierKey};
+use crate::crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MediaKeyCode, ModifierKeyCode, MouseButton, MouseEvent,
+    MouseEventKind,
+};
 
 impl From<Event> for Input {
     /// Convert [`crossterm::event::Event`] into [`Input`].
     fn from(event: Event) -> Self {
         match event {
             Event::Key(key) => Self::from(key),
-            // Event::Mouse(mouse) => Self::from(mouse),
+            Event::Mouse(mouse) => Self::from(mouse),
             _ => Self::default(),
         }
     }
@@ -32,26 +35,201 @@ impl From<KeyCode> for Key {
             KeyCode::PageDown => Key::PageDown,
             KeyCode::Esc => Key::Esc,
             KeyCode::F(x) => Key::F(x),
-            _ => Key::Null,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::CapsLock => Key::CapsLock,
+            KeyCode::ScrollLock => Key::ScrollLock,
+            KeyCode::NumLock => Key::NumLock,
+            KeyCode::PrintScreen => Key::PrintScreen,
+            KeyCode::Pause => Key::Pause,
+            KeyCode::Menu => Key::Menu,
+            KeyCode::KeypadBegin => Key::KeypadBegin,
+            KeyCode::Media(m) => Key::Media(m.into()),
+            KeyCode::Modifier(m) => Key::Modifier(m.into()),
+            KeyCode::Null => Key::Null,
+        }
+    }
+}
+
+impl From<MediaKeyCode> for MediaKey {
+    /// Convert [`crossterm::event::MediaKeyCode`] into [`MediaKey`].
+    fn from(code: MediaKeyCode) -> Self {
+        match code {
+            MediaKeyCode::Play => MediaKey::Play,
+            MediaKeyCode::Pause => MediaKey::Pause,
+            MediaKeyCode::PlayPause => MediaKey::PlayPause,
+            MediaKeyCode::Reverse => MediaKey::Reverse,
+            MediaKeyCode::Stop => MediaKey::Stop,
+            MediaKeyCode::FastForward => MediaKey::FastForward,
+            MediaKeyCode::Rewind => MediaKey::Rewind,
+            MediaKeyCode::TrackNext => MediaKey::TrackNext,
+            MediaKeyCode::TrackPrevious => MediaKey::TrackPrevious,
+            MediaKeyCode::Record => MediaKey::Record,
+            MediaKeyCode::LowerVolume => MediaKey::LowerVolume,
+            MediaKeyCode::RaiseVolume => MediaKey::RaiseVolume,
+            MediaKeyCode::MuteVolume => MediaKey::MuteVolume,
+        }
+    }
+}
+
+impl From<MediaKey> for MediaKeyCode {
+    /// Convert [`MediaKey`] into [`crossterm::event::MediaKeyCode`].
+    fn from(key: MediaKey) -> Self {
+        match key {
+            MediaKey::Play => MediaKeyCode::Play,
+            MediaKey::Pause => MediaKeyCode::Pause,
+            MediaKey::PlayPause => MediaKeyCode::PlayPause,
+            MediaKey::Reverse => MediaKeyCode::Reverse,
+            MediaKey::Stop => MediaKeyCode::Stop,
+            MediaKey::FastForward => MediaKeyCode::FastForward,
+            MediaKey::Rewind => MediaKeyCode::Rewind,
+            MediaKey::TrackNext => MediaKeyCode::TrackNext,
+            MediaKey::TrackPrevious => MediaKeyCode::TrackPrevious,
+            MediaKey::Record => MediaKeyCode::Record,
+            MediaKey::LowerVolume => MediaKeyCode::LowerVolume,
+            MediaKey::RaiseVolume => MediaKeyCode::RaiseVolume,
+            MediaKey::MuteVolume => MediaKeyCode::MuteVolume,
+        }
+    }
+}
+
+impl From<ModifierKeyCode> for ModifierKey {
+    /// Convert [`crossterm::event::ModifierKeyCode`] into [`ModifierKey`].
+    fn from(code: ModifierKeyCode) -> Self {
+        match code {
+            ModifierKeyCode::LeftShift => ModifierKey::LeftShift,
+            ModifierKeyCode::LeftControl => ModifierKey::LeftControl,
+            ModifierKeyCode::LeftAlt => ModifierKey::LeftAlt,
+            ModifierKeyCode::LeftSuper => ModifierKey::LeftSuper,
+            ModifierKeyCode::LeftHyper => ModifierKey::LeftHyper,
+            ModifierKeyCode::LeftMeta => ModifierKey::LeftMeta,
+            ModifierKeyCode::RightShift => ModifierKey::RightShift,
+            ModifierKeyCode::RightControl => ModifierKey::RightControl,
+            ModifierKeyCode::RightAlt => ModifierKey::RightAlt,
+            ModifierKeyCode::RightSuper => ModifierKey::RightSuper,
+            ModifierKeyCode::RightHyper => ModifierKey::RightHyper,
+            ModifierKeyCode::RightMeta => ModifierKey::RightMeta,
+            ModifierKeyCode::IsoLevel3Shift => ModifierKey::IsoLevel3Shift,
+            ModifierKeyCode::IsoLevel5Shift => ModifierKey::IsoLevel5Shift,
+        }
+    }
+}
+
+impl From<ModifierKey> for ModifierKeyCode {
+    /// Convert [`ModifierKey`] into [`crossterm::event::ModifierKeyCode`].
+    fn from(key: ModifierKey) -> Self {
+        match key {
+            ModifierKey::LeftShift => ModifierKeyCode::LeftShift,
+            ModifierKey::LeftControl => ModifierKeyCode::LeftControl,
+            ModifierKey::LeftAlt => ModifierKeyCode::LeftAlt,
+            ModifierKey::LeftSuper => ModifierKeyCode::LeftSuper,
+            ModifierKey::LeftHyper => ModifierKeyCode::LeftHyper,
+            ModifierKey::LeftMeta => ModifierKeyCode::LeftMeta,
+            ModifierKey::RightShift => ModifierKeyCode::RightShift,
+            ModifierKey::RightControl => ModifierKeyCode::RightControl,
+            ModifierKey::RightAlt => ModifierKeyCode::RightAlt,
+            ModifierKey::RightSuper => ModifierKeyCode::RightSuper,
+            ModifierKey::RightHyper => ModifierKeyCode::RightHyper,
+            ModifierKey::RightMeta => ModifierKeyCode::RightMeta,
+            ModifierKey::IsoLevel3Shift => ModifierKeyCode::IsoLevel3Shift,
+            ModifierKey::IsoLevel5Shift => ModifierKeyCode::IsoLevel5Shift,
+        }
+    }
+}
+
+impl From<KeyEventKind> for InputKind {
+    /// Convert [`crossterm::event::KeyEventKind`] into [`InputKind`].
+    fn from(kind: KeyEventKind) -> Self {
+        match kind {
+            KeyEventKind::Press => InputKind::Press,
+            KeyEventKind::Repeat => InputKind::Repeat,
+            KeyEventKind::Release => InputKind::Release,
         }
     }
 }
 
 impl From<KeyEvent> for Input {
-    /// Convert [`crossterm::event::KeyEvent`] into [`Input`].
+    /// Convert [`crossterm::event::KeyEvent`] into [`Input`]. Key repeats are converted as presses. Key releases are
+    /// converted into the input of [`Key::Null`], which [`TextArea::input`](crate::TextArea::input) ignores. Use
+    /// [`Input::from_key_event`] to handle them distinctly.
     fn from(key: KeyEvent) -> Self {
-        if key.kind == KeyEventKind::Release {
+        match Self::from_key_event(key) {
             // On Windows or when `crossterm::event::PushKeyboardEnhancementFlags` is set,
             // key release event can be reported. Ignore it. (#14)
-            return Self::default();
+            (_, InputKind::Release) => Self::default(),
+            (input, _) => input,
         }
+    }
+}
 
+impl Input {
+    /// Convert [`crossterm::event::KeyEvent`] into [`Input`] with the kind of the event. Unlike `From<KeyEvent>`, key
+    /// releases are also converted so that key repeats and releases can be handled distinctly. See [`InputKind`].
+    pub fn from_key_event(key: KeyEvent) -> (Self, InputKind) {
         let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
         let alt = key.modifiers.contains(KeyModifiers::ALT);
         let shift = key.modifiers.contains(KeyModifiers::SHIFT);
-        let key = Key::from(key.code);
+        let input = Self {
+            key: Key::from(key.code),
+            ctrl,
+            alt,
+            shift,
+        };
+        (input, key.kind.into())
+    }
+}
 
-        Self { key, ctrl, alt, shift }
+impl TryFrom<Key> for KeyCode {
+    type Error = Key;
+
+    /// Convert [`Key`] into [`crossterm::event::KeyCode`]. The key is returned as an error when it is not a key of
+    /// keyboard such as mouse inputs.
+    fn try_from(key: Key) -> Result<Self, Self::Error> {
+        Ok(match key {
+            Key::Char(c) => KeyCode::Char(c),
+            Key::Backspace => KeyCode::Backspace,
+            Key::Enter => KeyCode::Enter,
+            Key::Left => KeyCode::Left,
+            Key::Right => KeyCode::Right,
+            Key::Up => KeyCode::Up,
+            Key::Down => KeyCode::Down,
+            Key::Tab => KeyCode::Tab,
+            Key::BackTab => KeyCode::BackTab,
+            Key::Delete => KeyCode::Delete,
+            Key::Home => KeyCode::Home,
+            Key::End => KeyCode::End,
+            Key::PageUp => KeyCode::PageUp,
+            Key::PageDown => KeyCode::PageDown,
+            Key::Esc => KeyCode::Esc,
+            Key::F(x) => KeyCode::F(x),
+            Key::Insert => KeyCode::Insert,
+            Key::CapsLock => KeyCode::CapsLock,
+            Key::ScrollLock => KeyCode::ScrollLock,
+            Key::NumLock => KeyCode::NumLock,
+            Key::PrintScreen => KeyCode::PrintScreen,
+            Key::Pause => KeyCode::Pause,
+            Key::Menu => KeyCode::Menu,
+            Key::KeypadBegin => KeyCode::KeypadBegin,
+            Key::Media(m) => KeyCode::Media(m.into()),
+            Key::Modifier(m) => KeyCode::Modifier(m.into()),
+            key => return Err(key),
+        })
+    }
+}
+
+impl TryFrom<Input> for KeyEvent {
+    type Error = InputConversionError;
+
+    /// Convert [`Input`] into [`crossterm::event::KeyEvent`]. Inputs which are not key inputs such as mouse inputs
+    /// cannot be converted.
+    fn try_from(input: Input) -> Result<Self, Self::Error> {
+        let Ok(code) = KeyCode::try_from(input.key) else {
+            return Err(InputConversionError::new(input));
+        };
+        let mut modifiers = KeyModifiers::empty();
+        modifiers.set(KeyModifiers::CONTROL, input.ctrl);
+        modifiers.set(KeyModifiers::ALT, input.alt);
+        modifiers.set(KeyModifiers::SHIFT, input.shift);
+        Ok(KeyEvent::new(code, modifiers))
     }
 }
 
@@ -69,7 +247,11 @@ impl From<MouseEventKind> for Key {
 impl From<MouseEvent> for Input {
     /// Convert [`crossterm::event::MouseEvent`] into [`Input`].
     fn from(mouse: MouseEvent) -> Self {
-        let key = Key::from(mouse.kind);
+        let key = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => Key::MouseClick(mouse.column, mouse.row),
+            MouseEventKind::Drag(MouseButton::Left) => Key::MouseDrag(mouse.column, mouse.row),
+            kind => Key::from(kind),
+        };
         let ctrl = mouse.modifiers.contains(KeyModifiers::CONTROL);
         let alt = mouse.modifiers.contains(KeyModifiers::ALT);
         let shift = mouse.modifiers.contains(KeyModifiers::SHIFT);
@@ -133,6 +315,22 @@ mod tests {
             ),
             (
                 key_event(KeyCode::NumLock, KeyModifiers::CONTROL),
+                input(Key::NumLock, true, false, false),
+            ),
+            (
+                key_event(KeyCode::Insert, KeyModifiers::empty()),
+                input(Key::Insert, false, false, false),
+            ),
+            (
+                key_event(KeyCode::Media(MediaKeyCode::PlayPause), KeyModifiers::empty()),
+                input(Key::Media(MediaKey::PlayPause), false, false, false),
+            ),
+            (
+                key_event(KeyCode::Modifier(ModifierKeyCode::LeftShift), KeyModifiers::SHIFT),
+                input(Key::Modifier(ModifierKey::LeftShift), false, false, true),
+            ),
+            (
+                key_event(KeyCode::Null, KeyModifiers::CONTROL),
                 input(Key::Null, true, false, false),
             ),
         ] {
@@ -140,6 +338,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn key_event_kinds() {
+        use crate::TextArea;
+
+        let event = |c, kind| KeyEvent {
+            kind,
+            ..key_event(KeyCode::Char(c), KeyModifiers::empty())
+        };
+
+        // Releases are ignored and repeats are handled as presses
+        let mut t = TextArea::default();
+        for kind in [KeyEventKind::Press, KeyEventKind::Repeat, KeyEventKind::Release] {
+            t.input(event('a', kind));
+            t.input(Event::Key(event('b', kind)));
+        }
+        assert_eq!(t.lines(), ["abab"]);
+
+        // Releases do not interrupt key sequences
+        let g = input(Key::Char('g'), false, false, false);
+        t.key_sequences_mut()
+            .insert(vec![g.clone(), g], crate::EditAction::SelectAll);
+        t.input(event('g', KeyEventKind::Press));
+        t.input(event('g', KeyEventKind::Release));
+        t.input(event('g', KeyEventKind::Press));
+        assert_eq!(t.lines(), ["abab"]);
+        assert!(t.is_selecting());
+
+        for (kind, want) in [
+            (KeyEventKind::Press, InputKind::Press),
+            (KeyEventKind::Repeat, InputKind::Repeat),
+            (KeyEventKind::Release, InputKind::Release),
+        ] {
+            let (i, k) = Input::from_key_event(event('a', kind));
+            assert_eq!(i, input(Key::Char('a'), false, false, false), "{kind:?}");
+            assert_eq!(k, want, "{kind:?}");
+        }
+    }
+
     #[test]
     fn mouse_to_input() {
         for (from, to) in [
@@ -167,11 +403,111 @@ mod tests {
                 mouse_event(MouseEventKind::Moved, KeyModifiers::CONTROL),
                 input(Key::Null, true, false, false),
             ),
+            (
+                mouse_event(MouseEventKind::Down(MouseButton::Left), KeyModifiers::empty()),
+                input(Key::MouseClick(1, 1), false, false, false),
+            ),
+            (
+                mouse_event(MouseEventKind::Drag(MouseButton::Left), KeyModifiers::SHIFT),
+                input(Key::MouseDrag(1, 1), false, false, true),
+            ),
+            (
+                mouse_event(MouseEventKind::Down(MouseButton::Right), KeyModifiers::empty()),
+                input(Key::Null, false, false, false),
+            ),
         ] {
             assert_eq!(Input::from(from), to, "{:?} -> {:?}", from, to);
         }
     }
 
+    #[test]
+    fn input_to_key() {
+        for (from, to) in [
+            (
+                input(Key::Char('a'), false, false, false),
+                key_event(KeyCode::Char('a'), KeyModifiers::empty()),
+            ),
+            (
+                input(Key::Left, true, false, false),
+                key_event(KeyCode::Left, KeyModifiers::CONTROL),
+            ),
+            (
+                input(Key::F(1), true, true, true),
+                key_event(
+                    KeyCode::F(1),
+                    KeyModifiers::ALT | KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                ),
+            ),
+        ] {
+            assert_eq!(KeyEvent::try_from(from.clone()), Ok(to), "{:?} -> {:?}", from, to);
+        }
+
+        for key in [
+            Key::Copy,
+            Key::Cut,
+            Key::Paste,
+            Key::MouseScrollDown,
+            Key::MouseScrollUp,
+            Key::MouseClick(1, 1),
+            Key::MouseDrag(1, 1),
+            Key::Null,
+        ] {
+            let from = input(key, true, false, false);
+            let err = KeyEvent::try_from(from.clone()).unwrap_err();
+            assert_eq!(err.input(), &from, "{:?}", from);
+        }
+    }
+
+    #[test]
+    fn round_trip_key_event() {
+        let codes = [
+            KeyCode::Char('a'),
+            KeyCode::Char('A'),
+            KeyCode::Char(' '),
+            KeyCode::Char('\t'),
+            KeyCode::Char('あ'),
+            KeyCode::Backspace,
+            KeyCode::Enter,
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Up,
+            KeyCode::Down,
+            KeyCode::Tab,
+            KeyCode::BackTab,
+            KeyCode::Delete,
+            KeyCode::Home,
+            KeyCode::End,
+            KeyCode::PageUp,
+            KeyCode::PageDown,
+            KeyCode::Esc,
+            KeyCode::F(1),
+            KeyCode::F(12),
+            KeyCode::Insert,
+            KeyCode::CapsLock,
+            KeyCode::ScrollLock,
+            KeyCode::NumLock,
+            KeyCode::PrintScreen,
+            KeyCode::Pause,
+            KeyCode::Menu,
+            KeyCode::KeypadBegin,
+            KeyCode::Media(MediaKeyCode::Play),
+            KeyCode::Media(MediaKeyCode::MuteVolume),
+            KeyCode::Modifier(ModifierKeyCode::RightAlt),
+            KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift),
+        ];
+        for code in codes {
+            for bits in 0..8 {
+                let mut modifiers = KeyModifiers::empty();
+                modifiers.set(KeyModifiers::CONTROL, bits & 1 != 0);
+                modifiers.set(KeyModifiers::ALT, bits & 2 != 0);
+                modifiers.set(KeyModifiers::SHIFT, bits & 4 != 0);
+                let from = key_event(code, modifiers);
+                let input = Input::from(from);
+                assert_eq!(KeyEvent::try_from(input.clone()), Ok(from), "{:?} -> {:?}", from, input);
+            }
+        }
+    }
+
     // Regression for https://github.com/rhysd/tui-textarea/issues/14
     #[test]
     fn ignore_key_release_event() {
@@ -1,19 +1,27 @@
 #[cfg(any(feature = "crossterm", feature = "tuirs-crossterm"))]
 mod crossterm;
+mod notation;
 #[cfg(any(feature = "termion", feature = "tuirs-termion"))]
 mod termion;
 #[cfg(feature = "termwiz")]
 mod termwiz;
 
+pub use notation::ParseInputError;
+
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
 
 /// Backend-agnostic key input kind.
 ///
 /// This type is marked as `#[non_exhaustive]` since more keys may be supported in the future.
 #[non_exhaustive]
-#[derive(Default, Clone, Copy, Debug, PartialEq, Hash)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Key {
     /// Normal letter key input
     Char(char),
@@ -47,21 +55,135 @@ pub enum Key {
     PageDown,
     /// Escape key
     Esc,
+    /// Insert key
+    Insert,
+    /// Caps lock key. This key is reported only when the terminal supports it
+    CapsLock,
+    /// Scroll lock key. This key is reported only when the terminal supports it
+    ScrollLock,
+    /// Num lock key. This key is reported only when the terminal supports it
+    NumLock,
+    /// Print screen key. This key is reported only when the terminal supports it
+    PrintScreen,
+    /// Pause key. This key is reported only when the terminal supports it
+    Pause,
+    /// Menu key. This key is reported only when the terminal supports it
+    Menu,
+    /// The center key of the keypad (5 without Num Lock). This key is reported only when the terminal supports it
+    KeypadBegin,
+    /// Media key. This key is reported only when the terminal supports it
+    Media(MediaKey),
+    /// Modifier key pressed alone. This key is reported only when the terminal supports it, such as crossterm with
+    /// `KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES`
+    Modifier(ModifierKey),
     /// Copy key. This key is supported by termwiz only
     Copy,
     /// Cut key. This key is supported by termwiz only
     Cut,
-    /// Paste key. This key is supported by termwiz only
+    /// Paste key. This key is supported by termwiz only. Text pasted with bracketed paste is not a key input. Pass it to
+    /// [`TextArea::handle_paste`](crate::TextArea::handle_paste) instead
     Paste,
     /// Virtual key to scroll down by mouse
     MouseScrollDown,
     /// Virtual key to scroll up by mouse
     MouseScrollUp,
+    /// Virtual key of mouse left button press at the (column, row) position of the terminal screen
+    MouseClick(u16, u16),
+    /// Virtual key of mouse move with the left button pressed at the (column, row) position of the terminal screen
+    MouseDrag(u16, u16),
+    /// A key reported by the backend which has no variant in this enum, such as the Help key of termwiz. It carries the
+    /// name of the key in the backend so that applications can still match on it. Like [`Key::Null`], this key is
+    /// always ignored by [`TextArea`](crate::TextArea). Since the name depends on the backend, this key can be neither
+    /// deserialized nor parsed from a string
+    #[cfg_attr(feature = "arbitrary", arbitrary(skip))]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Unidentified(#[cfg_attr(feature = "serde", serde(skip_deserializing))] &'static str),
     /// An invalid key input (this key is always ignored by [`TextArea`](crate::TextArea))
     #[default]
     Null,
 }
 
+impl Key {
+    // Keys which `TextArea` never handles
+    pub(crate) fn is_ignored(&self) -> bool {
+        matches!(self, Key::Null | Key::Unidentified(_))
+    }
+}
+
+/// Media key of [`Key::Media`].
+///
+/// This type is marked as `#[non_exhaustive]` since more keys may be supported in the future.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MediaKey {
+    /// Play key
+    Play,
+    /// Pause key
+    Pause,
+    /// Play/pause toggle key
+    PlayPause,
+    /// Reverse key
+    Reverse,
+    /// Stop key
+    Stop,
+    /// Fast-forward key
+    FastForward,
+    /// Rewind key
+    Rewind,
+    /// Next track key
+    TrackNext,
+    /// Previous track key
+    TrackPrevious,
+    /// Record key
+    Record,
+    /// Volume down key
+    LowerVolume,
+    /// Volume up key
+    RaiseVolume,
+    /// Volume mute key
+    MuteVolume,
+}
+
+/// Modifier key of [`Key::Modifier`].
+///
+/// This type is marked as `#[non_exhaustive]` since more keys may be supported in the future.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ModifierKey {
+    /// Left Shift key
+    LeftShift,
+    /// Left Ctrl key
+    LeftControl,
+    /// Left Alt key
+    LeftAlt,
+    /// Left Super key (Windows or Command key)
+    LeftSuper,
+    /// Left Hyper key
+    LeftHyper,
+    /// Left Meta key
+    LeftMeta,
+    /// Right Shift key
+    RightShift,
+    /// Right Ctrl key
+    RightControl,
+    /// Right Alt key
+    RightAlt,
+    /// Right Super key (Windows or Command key)
+    RightSuper,
+    /// Right Hyper key
+    RightHyper,
+    /// Right Meta key
+    RightMeta,
+    /// ISO level 3 shift key (AltGr)
+    IsoLevel3Shift,
+    /// ISO level 5 shift key
+    IsoLevel5Shift,
+}
+
 /// Backend-agnostic key input type.
 ///
 /// When `crossterm`, `termion`, `termwiz` features are enabled, converting respective key input types into this
@@ -97,8 +219,9 @@ pub enum Key {
 ///     shift: false,
 /// });
 /// ```
-#[derive(Debug, Clone, Default, PartialEq, Hash)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Input {
     /// Typed key.
     pub key: Key,
@@ -110,6 +233,117 @@ pub struct Input {
     pub shift: bool,
 }
 
+/// Kind of a key event. On Windows or with the kitty keyboard protocol, crossterm reports key repeats and releases as
+/// separate events. Converting `crossterm::event::KeyEvent` into [`Input`] with `From` ignores releases and treats
+/// repeats as presses. `Input::from_key_event` returns the kind as well to handle them distinctly. It is available with
+/// the `crossterm` or `tuirs-crossterm` feature.
+#[cfg_attr(
+    any(feature = "crossterm", feature = "tuirs-crossterm"),
+    doc = r#"
+```
+use tui_textarea::{Input, InputKind, Key};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+
+let key = KeyEvent {
+    code: KeyCode::Char('a'),
+    modifiers: KeyModifiers::NONE,
+    kind: KeyEventKind::Repeat,
+    state: KeyEventState::empty(),
+};
+let (input, kind) = Input::from_key_event(key);
+assert_eq!(input, Input { key: Key::Char('a'), ..Default::default() });
+assert_eq!(kind, InputKind::Repeat);
+```
+"#
+)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    /// The key was pressed.
+    #[default]
+    Press,
+    /// The key is held and repeated.
+    Repeat,
+    /// The key was released.
+    Release,
+}
+
+/// Result of handling a key input with [`TextArea::handle_input`](crate::TextArea::handle_input).
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputResult {
+    /// The input did not modify the text.
+    Unchanged,
+    /// The input modified the text.
+    Modified,
+    /// Enter key was pressed in single-line mode. See [`TextArea::set_single_line`](crate::TextArea::set_single_line).
+    Submit,
+}
+
+impl InputResult {
+    /// Return `true` when the input modified the text.
+    /// ```
+    /// use tui_textarea::InputResult;
+    ///
+    /// assert!(InputResult::Modified.is_modified());
+    /// assert!(!InputResult::Submit.is_modified());
+    /// ```
+    pub fn is_modified(&self) -> bool {
+        *self == InputResult::Modified
+    }
+}
+
+/// Error returned when converting an [`Input`] back into a key event of a backend such as
+/// `crossterm::event::KeyEvent` fails because the backend cannot represent the input. For example, mouse inputs are not
+/// key events and termion cannot represent a Ctrl modifier with arrow keys.
+/// ```
+/// use tui_textarea::{Input, Key};
+/// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+///
+/// let input = Input { key: Key::Char('a'), ctrl: true, ..Default::default() };
+/// let key = KeyEvent::try_from(input).unwrap();
+/// assert_eq!(key, KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL));
+///
+/// let input = Input { key: Key::MouseScrollUp, ..Default::default() };
+/// let err = KeyEvent::try_from(input.clone()).unwrap_err();
+/// assert_eq!(err.input(), &input);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputConversionError {
+    input: Input,
+}
+
+impl InputConversionError {
+    #[cfg(any(
+        feature = "crossterm",
+        feature = "termion",
+        feature = "termwiz",
+        feature = "tuirs-crossterm",
+        feature = "tuirs-termion",
+    ))]
+    pub(crate) fn new(input: Input) -> Self {
+        Self { input }
+    }
+
+    /// The input which could not be converted.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+}
+
+impl fmt::Display for InputConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "input {:?} cannot be converted into a key event of the backend",
+            self.input
+        )
+    }
+}
+
+impl Error for InputConversionError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
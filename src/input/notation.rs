@@ -0,0 +1,376 @@
+use super::{Input, Key, MediaKey, ModifierKey};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error returned when parsing a string into [`Input`] or [`Key`] fails.
+/// ```
+/// use tui_textarea::Input;
+///
+/// let err = "ctrl+foo".parse::<Input>().unwrap_err();
+/// assert_eq!(err.to_string(), r#"unknown key "foo" in "ctrl+foo""#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseInputError {
+    message: String,
+}
+
+impl ParseInputError {
+    fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+impl fmt::Display for ParseInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ParseInputError {}
+
+// Names of keys other than characters and function keys. The first name of each key is used for formatting
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("space", Key::Char(' ')),
+    ("plus", Key::Char('+')),
+    ("backspace", Key::Backspace),
+    ("enter", Key::Enter),
+    ("return", Key::Enter),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("tab", Key::Tab),
+    ("backtab", Key::BackTab),
+    ("delete", Key::Delete),
+    ("del", Key::Delete),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("pageup", Key::PageUp),
+    ("pagedown", Key::PageDown),
+    ("esc", Key::Esc),
+    ("escape", Key::Esc),
+    ("insert", Key::Insert),
+    ("ins", Key::Insert),
+    ("capslock", Key::CapsLock),
+    ("scrolllock", Key::ScrollLock),
+    ("numlock", Key::NumLock),
+    ("printscreen", Key::PrintScreen),
+    ("pause", Key::Pause),
+    ("menu", Key::Menu),
+    ("keypadbegin", Key::KeypadBegin),
+    ("mediaplay", Key::Media(MediaKey::Play)),
+    ("mediapause", Key::Media(MediaKey::Pause)),
+    ("mediaplaypause", Key::Media(MediaKey::PlayPause)),
+    ("mediareverse", Key::Media(MediaKey::Reverse)),
+    ("mediastop", Key::Media(MediaKey::Stop)),
+    ("mediafastforward", Key::Media(MediaKey::FastForward)),
+    ("mediarewind", Key::Media(MediaKey::Rewind)),
+    ("medianext", Key::Media(MediaKey::TrackNext)),
+    ("mediaprev", Key::Media(MediaKey::TrackPrevious)),
+    ("mediarecord", Key::Media(MediaKey::Record)),
+    ("volumedown", Key::Media(MediaKey::LowerVolume)),
+    ("volumeup", Key::Media(MediaKey::RaiseVolume)),
+    ("volumemute", Key::Media(MediaKey::MuteVolume)),
+    ("leftshift", Key::Modifier(ModifierKey::LeftShift)),
+    ("leftctrl", Key::Modifier(ModifierKey::LeftControl)),
+    ("leftalt", Key::Modifier(ModifierKey::LeftAlt)),
+    ("leftsuper", Key::Modifier(ModifierKey::LeftSuper)),
+    ("lefthyper", Key::Modifier(ModifierKey::LeftHyper)),
+    ("leftmeta", Key::Modifier(ModifierKey::LeftMeta)),
+    ("rightshift", Key::Modifier(ModifierKey::RightShift)),
+    ("rightctrl", Key::Modifier(ModifierKey::RightControl)),
+    ("rightalt", Key::Modifier(ModifierKey::RightAlt)),
+    ("rightsuper", Key::Modifier(ModifierKey::RightSuper)),
+    ("righthyper", Key::Modifier(ModifierKey::RightHyper)),
+    ("rightmeta", Key::Modifier(ModifierKey::RightMeta)),
+    ("isolevel3shift", Key::Modifier(ModifierKey::IsoLevel3Shift)),
+    ("isolevel5shift", Key::Modifier(ModifierKey::IsoLevel5Shift)),
+    ("copy", Key::Copy),
+    ("cut", Key::Cut),
+    ("paste", Key::Paste),
+    ("scrolldown", Key::MouseScrollDown),
+    ("scrollup", Key::MouseScrollUp),
+    ("null", Key::Null),
+];
+
+// Parse "(x,y)" of mouse inputs
+fn parse_position(s: &str) -> Option<(u16, u16)> {
+    let (x, y) = s.strip_prefix('(')?.strip_suffix(')')?.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+// Parse "a0}" of escaped characters
+fn parse_code_point(s: &str) -> Option<char> {
+    let hex = s.strip_suffix('}')?;
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+impl fmt::Display for Key {
+    /// Format the key in the notation parsed by [`Key::from_str`]. For example, `Key::Enter` is formatted as `enter`
+    /// and `Key::F(1)` is formatted as `f1`. Whitespace and control characters other than `' '` are escaped like
+    /// `u{9}` so that they can be parsed back. [`Key::Unidentified`] is formatted as `unidentified(Help)`, which cannot
+    /// be parsed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((name, _)) = KEY_NAMES.iter().find(|(_, k)| k == self) {
+            return f.write_str(name);
+        }
+        match self {
+            Key::Char(c) if c.is_whitespace() || c.is_control() => write!(f, "u{{{:x}}}", *c as u32),
+            Key::Char(c) => write!(f, "{c}"),
+            Key::F(n) => write!(f, "f{n}"),
+            Key::MouseClick(x, y) => write!(f, "click({x},{y})"),
+            Key::MouseDrag(x, y) => write!(f, "drag({x},{y})"),
+            Key::Unidentified(name) => write!(f, "unidentified({name})"),
+            _ => unreachable!("{self:?} has no name"),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseInputError;
+
+    /// Parse a key name such as `enter`, `f11`, or `a`. Names are case-insensitive except for a single character, which
+    /// is parsed as the character itself. `space` and `plus` are also available for `' '` and `'+'`. Any character can
+    /// be written as its hexadecimal code point like `u{a0}`.
+    /// ```
+    /// use tui_textarea::Key;
+    ///
+    /// assert_eq!("PageDown".parse(), Ok(Key::PageDown));
+    /// assert_eq!("F11".parse(), Ok(Key::F(11)));
+    /// assert_eq!("A".parse(), Ok(Key::Char('A')));
+    /// assert_eq!("u{9}".parse(), Ok(Key::Char('\t')));
+    /// assert!("foo".parse::<Key>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+        let name = s.to_ascii_lowercase();
+        if let Some((_, key)) = KEY_NAMES.iter().find(|(n, _)| *n == name) {
+            return Ok(*key);
+        }
+        let key = if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(Key::F(n))
+        } else if let Some(c) = name.strip_prefix("u{").and_then(parse_code_point) {
+            Some(Key::Char(c))
+        } else if let Some(pos) = name.strip_prefix("click").and_then(parse_position) {
+            Some(Key::MouseClick(pos.0, pos.1))
+        } else {
+            name.strip_prefix("drag")
+                .and_then(parse_position)
+                .map(|(x, y)| Key::MouseDrag(x, y))
+        };
+        key.ok_or_else(|| ParseInputError::new(format!("unknown key {s:?}")))
+    }
+}
+
+impl fmt::Display for Input {
+    /// Format the input in the notation parsed by [`Input::from_str`] such as `ctrl+shift+f`. Modifiers are put in
+    /// the order of `ctrl`, `alt`, and `shift`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (pressed, name) in [(self.ctrl, "ctrl"), (self.alt, "alt"), (self.shift, "shift")] {
+            if pressed {
+                write!(f, "{name}+")?;
+            }
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for Input {
+    type Err = ParseInputError;
+
+    /// Parse an input written as modifiers and a key joined with `+` such as `ctrl+shift+f`, `alt+enter`, or `f11`.
+    /// Modifiers are `ctrl` (or `control`), `alt`, and `shift`. Modifiers and key names are case-insensitive. See
+    /// [`Key::from_str`] for the key names.
+    /// ```
+    /// use tui_textarea::{Input, Key};
+    ///
+    /// let input: Input = "Ctrl+Shift+F".parse().unwrap();
+    /// assert_eq!(input, Input { key: Key::Char('F'), ctrl: true, alt: false, shift: true });
+    ///
+    /// let input: Input = "alt+enter".parse().unwrap();
+    /// assert_eq!(input, Input { key: Key::Enter, ctrl: false, alt: true, shift: false });
+    /// assert_eq!(input.to_string(), "alt+enter");
+    ///
+    /// // `+` key itself
+    /// let input: Input = "ctrl++".parse().unwrap();
+    /// assert_eq!(input, Input { key: Key::Char('+'), ctrl: true, alt: false, shift: false });
+    /// assert_eq!(input.to_string(), "ctrl+plus");
+    ///
+    /// assert!("hyper+a".parse::<Input>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |msg: String| ParseInputError::new(format!("{msg} in {s:?}"));
+        let (modifiers, key) = match s.strip_suffix('+') {
+            // "+" or "ctrl++"
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => (rest.strip_suffix('+').unwrap_or(""), "+"),
+            _ => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(error("key is missing".to_string()));
+        }
+        let mut input = Input {
+            key: key.parse().map_err(|_| error(format!("unknown key {key:?}")))?,
+            ..Default::default()
+        };
+        for modifier in modifiers.split('+').filter(|_| !modifiers.is_empty()) {
+            let modifier = modifier.trim();
+            let pressed = match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut input.ctrl,
+                "alt" => &mut input.alt,
+                "shift" => &mut input.shift,
+                _ => return Err(error(format!("unknown modifier {modifier:?}"))),
+            };
+            if *pressed {
+                return Err(error(format!("duplicate modifier {modifier:?}")));
+            }
+            *pressed = true;
+        }
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::input;
+
+    #[test]
+    fn parse_input() {
+        for (s, want) in [
+            ("a", input(Key::Char('a'), false, false, false)),
+            ("A", input(Key::Char('A'), false, false, false)),
+            ("ctrl+shift+f", input(Key::Char('f'), true, false, true)),
+            ("Shift+Ctrl+F", input(Key::Char('F'), true, false, true)),
+            ("alt+enter", input(Key::Enter, false, true, false)),
+            ("ALT+Return", input(Key::Enter, false, true, false)),
+            ("f11", input(Key::F(11), false, false, false)),
+            ("control+alt+shift+F1", input(Key::F(1), true, true, true)),
+            (" ctrl + esc ", input(Key::Esc, true, false, false)),
+            ("+", input(Key::Char('+'), false, false, false)),
+            ("ctrl++", input(Key::Char('+'), true, false, false)),
+            ("ctrl+plus", input(Key::Char('+'), true, false, false)),
+            ("alt+space", input(Key::Char(' '), false, true, false)),
+            ("あ", input(Key::Char('あ'), false, false, false)),
+            ("click(1, 2)", input(Key::MouseClick(1, 2), false, false, false)),
+            ("ctrl+u{9}", input(Key::Char('\t'), true, false, false)),
+            ("U{A0}", input(Key::Char('\u{a0}'), false, false, false)),
+            ("u{61}", input(Key::Char('a'), false, false, false)),
+        ] {
+            assert_eq!(s.parse::<Input>(), Ok(want), "{s:?}");
+        }
+
+        for (s, err) in [
+            ("", r#"key is missing in """#),
+            ("ctrl+", r#"key is missing in "ctrl+""#),
+            ("foo", r#"unknown key "foo" in "foo""#),
+            ("ctrl+f0o", r#"unknown key "f0o" in "ctrl+f0o""#),
+            ("hyper+a", r#"unknown modifier "hyper" in "hyper+a""#),
+            ("ctrl++a", r#"unknown modifier "" in "ctrl++a""#),
+            ("ctrl+Ctrl+a", r#"duplicate modifier "Ctrl" in "ctrl+Ctrl+a""#),
+            ("f256", r#"unknown key "f256" in "f256""#),
+            ("u{d800}", r#"unknown key "u{d800}" in "u{d800}""#),
+            ("u{}", r#"unknown key "u{}" in "u{}""#),
+            (
+                "unidentified(Help)",
+                r#"unknown key "unidentified(Help)" in "unidentified(Help)""#,
+            ),
+        ] {
+            assert_eq!(s.parse::<Input>().unwrap_err().to_string(), err, "{s:?}");
+        }
+    }
+
+    #[test]
+    fn round_trip_input() {
+        let keys = [
+            Key::Char('a'),
+            Key::Char('A'),
+            Key::Char(' '),
+            Key::Char('+'),
+            Key::Char('-'),
+            Key::Char('あ'),
+            Key::Char('u'),
+            // Whitespace and control characters are escaped
+            Key::Char('\t'),
+            Key::Char('\n'),
+            Key::Char('\r'),
+            Key::Char('\0'),
+            Key::Char('\x1b'),
+            Key::Char('\x7f'),
+            Key::Char('\u{85}'),
+            Key::Char('\u{a0}'),
+            Key::Char('\u{2028}'),
+            Key::Char('\u{3000}'),
+            Key::F(1),
+            Key::F(12),
+            Key::Backspace,
+            Key::Enter,
+            Key::Left,
+            Key::Right,
+            Key::Up,
+            Key::Down,
+            Key::Tab,
+            Key::BackTab,
+            Key::Delete,
+            Key::Home,
+            Key::End,
+            Key::PageUp,
+            Key::PageDown,
+            Key::Esc,
+            Key::Insert,
+            Key::CapsLock,
+            Key::ScrollLock,
+            Key::NumLock,
+            Key::PrintScreen,
+            Key::Pause,
+            Key::Menu,
+            Key::KeypadBegin,
+            Key::Media(MediaKey::PlayPause),
+            Key::Media(MediaKey::MuteVolume),
+            Key::Modifier(ModifierKey::LeftShift),
+            Key::Modifier(ModifierKey::IsoLevel3Shift),
+            Key::Copy,
+            Key::Cut,
+            Key::Paste,
+            Key::MouseScrollDown,
+            Key::MouseScrollUp,
+            Key::MouseClick(1, 2),
+            Key::MouseDrag(3, 4),
+            Key::Null,
+        ];
+        for key in keys {
+            assert_eq!(key.to_string().parse(), Ok(key), "{key:?}");
+            for bits in 0..8 {
+                let i = input(key, bits & 1 != 0, bits & 2 != 0, bits & 4 != 0);
+                let s = i.to_string();
+                assert_eq!(s.parse(), Ok(i.clone()), "{i:?} -> {s:?}");
+            }
+        }
+        assert_eq!(input(Key::Char('x'), true, true, true).to_string(), "ctrl+alt+shift+x");
+        assert_eq!(input(Key::Char('\t'), true, false, false).to_string(), "ctrl+u{9}");
+        assert_eq!(Key::Char('\u{a0}').to_string(), "u{a0}");
+        assert_eq!(
+            input(Key::Unidentified("Help"), true, false, false).to_string(),
+            "ctrl+unidentified(Help)"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialize_input() {
+        use serde::de::value::{Error as DeError, MapDeserializer, StrDeserializer};
+        use serde::de::IntoDeserializer as _;
+        use serde::Deserialize as _;
+
+        let de: StrDeserializer<DeError> = "Enter".into_deserializer();
+        assert_eq!(Key::deserialize(de), Ok(Key::Enter));
+
+        // Omitted modifiers are not pressed
+        let de: MapDeserializer<_, DeError> = MapDeserializer::new([("key", "Esc")].into_iter());
+        assert_eq!(Input::deserialize(de), Ok(input(Key::Esc, false, false, false)));
+    }
+}
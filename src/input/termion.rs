@@ -1,4 +1,4 @@
-use super::{Input, Key};
+use super::{Input, InputConversionError, Key};
 use termion::event::{Event, Key as KeyEvent, MouseButton, MouseEvent};
 
 impl From<Event> for Input {
@@ -17,6 +17,7 @@ impl From<KeyEvent> for Input {
     fn from(key: KeyEvent) -> Self {
         let mut ctrl = false;
         let mut alt = false;
+        let mut shift = false;
         let key = match key {
             KeyEvent::Char('\n' | '\r') => Key::Enter,
             KeyEvent::Char(c) => Key::Char(c),
@@ -33,18 +34,101 @@ impl From<KeyEvent> for Input {
             KeyEvent::Right => Key::Right,
             KeyEvent::Up => Key::Up,
             KeyEvent::Down => Key::Down,
+            KeyEvent::ShiftLeft | KeyEvent::ShiftRight | KeyEvent::ShiftUp | KeyEvent::ShiftDown => {
+                shift = true;
+                arrow(key)
+            }
+            KeyEvent::AltLeft | KeyEvent::AltRight | KeyEvent::AltUp | KeyEvent::AltDown => {
+                alt = true;
+                arrow(key)
+            }
+            KeyEvent::CtrlLeft | KeyEvent::CtrlRight | KeyEvent::CtrlUp | KeyEvent::CtrlDown => {
+                ctrl = true;
+                arrow(key)
+            }
             KeyEvent::Home => Key::Home,
+            KeyEvent::CtrlHome => {
+                ctrl = true;
+                Key::Home
+            }
             KeyEvent::End => Key::End,
+            KeyEvent::CtrlEnd => {
+                ctrl = true;
+                Key::End
+            }
             KeyEvent::PageUp => Key::PageUp,
             KeyEvent::PageDown => Key::PageDown,
             KeyEvent::BackTab => Key::Tab,
             KeyEvent::Delete => Key::Delete,
+            KeyEvent::Insert => Key::Insert,
             KeyEvent::Esc => Key::Esc,
             KeyEvent::F(x) => Key::F(x),
-            _ => Key::Null,
+            KeyEvent::Null => Key::Null,
+            _ => Key::Unidentified("Unknown"),
         };
 
-        Input { key, ctrl, alt, shift: false }
+        Input { key, ctrl, alt, shift }
+    }
+}
+
+// Arrow key of the arrow key event with a modifier
+fn arrow(key: KeyEvent) -> Key {
+    match key {
+        KeyEvent::ShiftLeft | KeyEvent::AltLeft | KeyEvent::CtrlLeft => Key::Left,
+        KeyEvent::ShiftRight | KeyEvent::AltRight | KeyEvent::CtrlRight => Key::Right,
+        KeyEvent::ShiftUp | KeyEvent::AltUp | KeyEvent::CtrlUp => Key::Up,
+        _ => Key::Down,
+    }
+}
+
+impl TryFrom<Input> for KeyEvent {
+    type Error = InputConversionError;
+
+    /// Convert [`Input`] into [`termion::event::Key`]. termion can represent Ctrl and Alt modifiers only with
+    /// characters and arrow keys (and Ctrl with Home and End keys), and Shift modifier only with Tab and arrow keys.
+    /// Shift modifier with a character is ignored since the character is already affected by it. Inputs which are not
+    /// key inputs such as mouse inputs cannot be converted.
+    fn try_from(input: Input) -> Result<Self, Self::Error> {
+        let Input { key, ctrl, alt, shift } = input;
+        Ok(match (key, ctrl, alt, shift) {
+            (Key::Char(c), false, false, _) => KeyEvent::Char(c),
+            (Key::Char(c), true, false, _) => KeyEvent::Ctrl(c),
+            (Key::Char(c), false, true, _) => KeyEvent::Alt(c),
+            (Key::Tab, false, false, true) | (Key::BackTab, false, false, _) => KeyEvent::BackTab,
+            (Key::Left, false, false, true) => KeyEvent::ShiftLeft,
+            (Key::Right, false, false, true) => KeyEvent::ShiftRight,
+            (Key::Up, false, false, true) => KeyEvent::ShiftUp,
+            (Key::Down, false, false, true) => KeyEvent::ShiftDown,
+            (Key::Left, false, true, false) => KeyEvent::AltLeft,
+            (Key::Right, false, true, false) => KeyEvent::AltRight,
+            (Key::Up, false, true, false) => KeyEvent::AltUp,
+            (Key::Down, false, true, false) => KeyEvent::AltDown,
+            (Key::Left, true, false, false) => KeyEvent::CtrlLeft,
+            (Key::Right, true, false, false) => KeyEvent::CtrlRight,
+            (Key::Up, true, false, false) => KeyEvent::CtrlUp,
+            (Key::Down, true, false, false) => KeyEvent::CtrlDown,
+            (Key::Home, true, false, false) => KeyEvent::CtrlHome,
+            (Key::End, true, false, false) => KeyEvent::CtrlEnd,
+            (key, false, false, false) => match key {
+                Key::Enter => KeyEvent::Char('\n'),
+                Key::Tab => KeyEvent::Char('\t'),
+                Key::Backspace => KeyEvent::Backspace,
+                Key::Left => KeyEvent::Left,
+                Key::Right => KeyEvent::Right,
+                Key::Up => KeyEvent::Up,
+                Key::Down => KeyEvent::Down,
+                Key::Home => KeyEvent::Home,
+                Key::End => KeyEvent::End,
+                Key::PageUp => KeyEvent::PageUp,
+                Key::PageDown => KeyEvent::PageDown,
+                Key::Delete => KeyEvent::Delete,
+                Key::Insert => KeyEvent::Insert,
+                Key::Esc => KeyEvent::Esc,
+                Key::F(x) => KeyEvent::F(x),
+                _ => return Err(InputConversionError::new(input)),
+            },
+            _ => return Err(InputConversionError::new(input)),
+        })
     }
 }
 
@@ -62,10 +146,12 @@ impl From<MouseButton> for Key {
 impl From<MouseEvent> for Input {
     /// Convert [`termion::event::MouseEvent`] into [`Input`].
     fn from(mouse: MouseEvent) -> Self {
-        let key = if let MouseEvent::Press(button, ..) = mouse {
-            Key::from(button)
-        } else {
-            Key::Null
+        // Coordinates of termion mouse events are 1-based
+        let key = match mouse {
+            MouseEvent::Press(MouseButton::Left, x, y) => Key::MouseClick(x.saturating_sub(1), y.saturating_sub(1)),
+            MouseEvent::Press(button, ..) => Key::from(button),
+            MouseEvent::Hold(x, y) => Key::MouseDrag(x.saturating_sub(1), y.saturating_sub(1)),
+            MouseEvent::Release(..) => Key::Null,
         };
         Self {
             key,
@@ -91,12 +177,101 @@ mod tests {
             (KeyEvent::Char('\r'), input(Key::Enter, false, false, false)),
             (KeyEvent::F(1), input(Key::F(1), false, false, false)),
             (KeyEvent::BackTab, input(Key::Tab, false, false, false)),
+            (KeyEvent::Insert, input(Key::Insert, false, false, false)),
             (KeyEvent::Null, input(Key::Null, false, false, false)),
+            (KeyEvent::ShiftLeft, input(Key::Left, false, false, true)),
+            (KeyEvent::AltUp, input(Key::Up, false, true, false)),
+            (KeyEvent::CtrlDown, input(Key::Down, true, false, false)),
+            (KeyEvent::CtrlEnd, input(Key::End, true, false, false)),
+            (
+                KeyEvent::__IsNotComplete,
+                input(Key::Unidentified("Unknown"), false, false, false),
+            ),
         ] {
             assert_eq!(Input::from(from), to, "{:?} -> {:?}", from, to);
         }
     }
 
+    #[test]
+    fn input_to_key() {
+        for (from, to) in [
+            (input(Key::Char('a'), false, false, false), KeyEvent::Char('a')),
+            (input(Key::Char('A'), false, false, true), KeyEvent::Char('A')),
+            (input(Key::Char('a'), true, false, false), KeyEvent::Ctrl('a')),
+            (input(Key::Char('a'), false, true, false), KeyEvent::Alt('a')),
+            (input(Key::Enter, false, false, false), KeyEvent::Char('\n')),
+            (input(Key::Tab, false, false, false), KeyEvent::Char('\t')),
+            (input(Key::Tab, false, false, true), KeyEvent::BackTab),
+            (input(Key::BackTab, false, false, false), KeyEvent::BackTab),
+            (input(Key::F(1), false, false, false), KeyEvent::F(1)),
+            (input(Key::Insert, false, false, false), KeyEvent::Insert),
+        ] {
+            assert_eq!(KeyEvent::try_from(from.clone()), Ok(to), "{:?} -> {:?}", from, to);
+        }
+
+        for from in [
+            input(Key::Char('a'), true, true, false),
+            input(Key::Left, true, true, false),
+            input(Key::Enter, false, true, false),
+            input(Key::Home, false, false, true),
+            input(Key::Copy, false, false, false),
+            input(Key::Pause, false, false, false),
+            input(Key::MouseScrollUp, false, false, false),
+            input(Key::MouseClick(1, 1), false, false, false),
+            input(Key::Null, false, false, false),
+            input(Key::Unidentified("Unknown"), false, false, false),
+        ] {
+            let err = KeyEvent::try_from(from.clone()).unwrap_err();
+            assert_eq!(err.input(), &from, "{:?}", from);
+        }
+    }
+
+    #[test]
+    fn round_trip_key_event() {
+        // `Char('\r')` and `BackTab` are not included since they are converted into the same inputs as `Char('\n')` and
+        // `Char('\t')`
+        let keys = [
+            KeyEvent::Char('a'),
+            KeyEvent::Char('A'),
+            KeyEvent::Char('あ'),
+            KeyEvent::Char('\n'),
+            KeyEvent::Char('\t'),
+            KeyEvent::Ctrl('a'),
+            KeyEvent::Alt('a'),
+            KeyEvent::Backspace,
+            KeyEvent::Left,
+            KeyEvent::Right,
+            KeyEvent::Up,
+            KeyEvent::Down,
+            KeyEvent::ShiftLeft,
+            KeyEvent::ShiftRight,
+            KeyEvent::ShiftUp,
+            KeyEvent::ShiftDown,
+            KeyEvent::AltLeft,
+            KeyEvent::AltRight,
+            KeyEvent::AltUp,
+            KeyEvent::AltDown,
+            KeyEvent::CtrlLeft,
+            KeyEvent::CtrlRight,
+            KeyEvent::CtrlUp,
+            KeyEvent::CtrlDown,
+            KeyEvent::Home,
+            KeyEvent::CtrlHome,
+            KeyEvent::End,
+            KeyEvent::CtrlEnd,
+            KeyEvent::PageUp,
+            KeyEvent::PageDown,
+            KeyEvent::Delete,
+            KeyEvent::Esc,
+            KeyEvent::F(1),
+            KeyEvent::F(12),
+        ];
+        for from in keys {
+            let input = Input::from(from);
+            assert_eq!(KeyEvent::try_from(input.clone()), Ok(from), "{:?} -> {:?}", from, input);
+        }
+    }
+
     #[test]
     fn mouse_to_input() {
         for (from, to) in [
@@ -109,11 +284,15 @@ mod tests {
                 input(Key::MouseScrollUp, false, false, false),
             ),
             (
-                MouseEvent::Press(MouseButton::Left, 1, 1),
+                MouseEvent::Press(MouseButton::Left, 2, 3),
+                input(Key::MouseClick(1, 2), false, false, false),
+            ),
+            (
+                MouseEvent::Press(MouseButton::Right, 1, 1),
                 input(Key::Null, false, false, false),
             ),
             (MouseEvent::Release(1, 1), input(Key::Null, false, false, false)),
-            (MouseEvent::Hold(1, 1), input(Key::Null, false, false, false)),
+            (MouseEvent::Hold(2, 3), input(Key::MouseDrag(1, 2), false, false, false)),
         ] {
             assert_eq!(Input::from(from), to, "{:?} -> {:?}", from, to);
         }
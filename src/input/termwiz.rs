@@ -1,4 +1,5 @@
-use super::{Input, Key};
+use super::{Input, InputConversionError, Key, MediaKey, ModifierKey};
+use std::cell::Cell;
 use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent, PixelMouseEvent};
 
 impl From<InputEvent> for Input {
@@ -13,6 +14,57 @@ impl From<InputEvent> for Input {
     }
 }
 
+// Keys of termwiz which have no variant in `Key`. They are converted into `Key::Unidentified` with their names
+const UNIDENTIFIED_KEYS: &[(&str, KeyCode)] = &[
+    ("Hyper", KeyCode::Hyper),
+    ("Super", KeyCode::Super),
+    ("Meta", KeyCode::Meta),
+    ("Cancel", KeyCode::Cancel),
+    ("Clear", KeyCode::Clear),
+    ("Shift", KeyCode::Shift),
+    ("Control", KeyCode::Control),
+    ("Alt", KeyCode::Alt),
+    ("Menu", KeyCode::Menu),
+    ("LeftMenu", KeyCode::LeftMenu),
+    ("RightMenu", KeyCode::RightMenu),
+    ("Select", KeyCode::Select),
+    ("Print", KeyCode::Print),
+    ("Execute", KeyCode::Execute),
+    ("Help", KeyCode::Help),
+    ("Sleep", KeyCode::Sleep),
+    ("Numpad0", KeyCode::Numpad0),
+    ("Numpad1", KeyCode::Numpad1),
+    ("Numpad2", KeyCode::Numpad2),
+    ("Numpad3", KeyCode::Numpad3),
+    ("Numpad4", KeyCode::Numpad4),
+    ("Numpad5", KeyCode::Numpad5),
+    ("Numpad6", KeyCode::Numpad6),
+    ("Numpad7", KeyCode::Numpad7),
+    ("Numpad8", KeyCode::Numpad8),
+    ("Numpad9", KeyCode::Numpad9),
+    ("Multiply", KeyCode::Multiply),
+    ("Add", KeyCode::Add),
+    ("Separator", KeyCode::Separator),
+    ("Subtract", KeyCode::Subtract),
+    ("Decimal", KeyCode::Decimal),
+    ("Divide", KeyCode::Divide),
+    ("BrowserBack", KeyCode::BrowserBack),
+    ("BrowserForward", KeyCode::BrowserForward),
+    ("BrowserRefresh", KeyCode::BrowserRefresh),
+    ("BrowserStop", KeyCode::BrowserStop),
+    ("BrowserSearch", KeyCode::BrowserSearch),
+    ("BrowserFavorites", KeyCode::BrowserFavorites),
+    ("BrowserHome", KeyCode::BrowserHome),
+    ("ApplicationLeftArrow", KeyCode::ApplicationLeftArrow),
+    ("ApplicationRightArrow", KeyCode::ApplicationRightArrow),
+    ("ApplicationUpArrow", KeyCode::ApplicationUpArrow),
+    ("ApplicationDownArrow", KeyCode::ApplicationDownArrow),
+    ("KeyPadHome", KeyCode::KeyPadHome),
+    ("KeyPadEnd", KeyCode::KeyPadEnd),
+    ("KeyPadPageUp", KeyCode::KeyPadPageUp),
+    ("KeyPadPageDown", KeyCode::KeyPadPageDown),
+];
+
 impl From<KeyCode> for Key {
     /// Convert [`termwiz::input::KeyCode`] into [`Key`].
     fn from(key: KeyCode) -> Self {
@@ -35,7 +87,33 @@ impl From<KeyCode> for Key {
             KeyCode::Copy => Key::Copy,
             KeyCode::Cut => Key::Cut,
             KeyCode::Paste => Key::Paste,
-            _ => Key::Null,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::CapsLock => Key::CapsLock,
+            KeyCode::ScrollLock => Key::ScrollLock,
+            KeyCode::NumLock => Key::NumLock,
+            KeyCode::PrintScreen => Key::PrintScreen,
+            KeyCode::Pause => Key::Pause,
+            KeyCode::Applications => Key::Menu,
+            KeyCode::KeyPadBegin => Key::KeypadBegin,
+            KeyCode::MediaPlayPause => Key::Media(MediaKey::PlayPause),
+            KeyCode::MediaStop => Key::Media(MediaKey::Stop),
+            KeyCode::MediaNextTrack => Key::Media(MediaKey::TrackNext),
+            KeyCode::MediaPrevTrack => Key::Media(MediaKey::TrackPrevious),
+            KeyCode::VolumeDown => Key::Media(MediaKey::LowerVolume),
+            KeyCode::VolumeUp => Key::Media(MediaKey::RaiseVolume),
+            KeyCode::VolumeMute => Key::Media(MediaKey::MuteVolume),
+            KeyCode::LeftShift => Key::Modifier(ModifierKey::LeftShift),
+            KeyCode::LeftControl => Key::Modifier(ModifierKey::LeftControl),
+            KeyCode::LeftAlt => Key::Modifier(ModifierKey::LeftAlt),
+            KeyCode::LeftWindows => Key::Modifier(ModifierKey::LeftSuper),
+            KeyCode::RightShift => Key::Modifier(ModifierKey::RightShift),
+            KeyCode::RightControl => Key::Modifier(ModifierKey::RightControl),
+            KeyCode::RightAlt => Key::Modifier(ModifierKey::RightAlt),
+            KeyCode::RightWindows => Key::Modifier(ModifierKey::RightSuper),
+            code => {
+                let name = UNIDENTIFIED_KEYS.iter().find(|(_, c)| *c == code).map(|(n, _)| *n);
+                Key::Unidentified(name.unwrap_or("Unknown"))
+            }
         }
     }
 }
@@ -53,6 +131,87 @@ impl From<KeyEvent> for Input {
     }
 }
 
+impl TryFrom<Key> for KeyCode {
+    type Error = Key;
+
+    /// Convert [`Key`] into [`termwiz::input::KeyCode`]. The key is returned as an error when it is not a key of
+    /// keyboard such as mouse inputs. termwiz does not have a back tab key so [`Key::BackTab`] is converted into Tab key.
+    /// [`Key::Unidentified`] is converted back into the key of termwiz which has the name.
+    fn try_from(key: Key) -> Result<Self, Self::Error> {
+        Ok(match key {
+            Key::Char(c) => KeyCode::Char(c),
+            Key::Backspace => KeyCode::Backspace,
+            Key::Tab | Key::BackTab => KeyCode::Tab,
+            Key::Enter => KeyCode::Enter,
+            Key::Esc => KeyCode::Escape,
+            Key::PageUp => KeyCode::PageUp,
+            Key::PageDown => KeyCode::PageDown,
+            Key::End => KeyCode::End,
+            Key::Home => KeyCode::Home,
+            Key::Left => KeyCode::LeftArrow,
+            Key::Right => KeyCode::RightArrow,
+            Key::Up => KeyCode::UpArrow,
+            Key::Down => KeyCode::DownArrow,
+            Key::Delete => KeyCode::Delete,
+            Key::F(x) => KeyCode::Function(x),
+            Key::Copy => KeyCode::Copy,
+            Key::Cut => KeyCode::Cut,
+            Key::Paste => KeyCode::Paste,
+            Key::Insert => KeyCode::Insert,
+            Key::CapsLock => KeyCode::CapsLock,
+            Key::ScrollLock => KeyCode::ScrollLock,
+            Key::NumLock => KeyCode::NumLock,
+            Key::PrintScreen => KeyCode::PrintScreen,
+            Key::Pause => KeyCode::Pause,
+            Key::Menu => KeyCode::Applications,
+            Key::KeypadBegin => KeyCode::KeyPadBegin,
+            Key::Media(MediaKey::PlayPause) => KeyCode::MediaPlayPause,
+            Key::Media(MediaKey::Stop) => KeyCode::MediaStop,
+            Key::Media(MediaKey::TrackNext) => KeyCode::MediaNextTrack,
+            Key::Media(MediaKey::TrackPrevious) => KeyCode::MediaPrevTrack,
+            Key::Media(MediaKey::LowerVolume) => KeyCode::VolumeDown,
+            Key::Media(MediaKey::RaiseVolume) => KeyCode::VolumeUp,
+            Key::Media(MediaKey::MuteVolume) => KeyCode::VolumeMute,
+            Key::Modifier(ModifierKey::LeftShift) => KeyCode::LeftShift,
+            Key::Modifier(ModifierKey::LeftControl) => KeyCode::LeftControl,
+            Key::Modifier(ModifierKey::LeftAlt) => KeyCode::LeftAlt,
+            Key::Modifier(ModifierKey::LeftSuper) => KeyCode::LeftWindows,
+            Key::Modifier(ModifierKey::RightShift) => KeyCode::RightShift,
+            Key::Modifier(ModifierKey::RightControl) => KeyCode::RightControl,
+            Key::Modifier(ModifierKey::RightAlt) => KeyCode::RightAlt,
+            Key::Modifier(ModifierKey::RightSuper) => KeyCode::RightWindows,
+            Key::Unidentified(name) => match UNIDENTIFIED_KEYS.iter().find(|(n, _)| *n == name) {
+                Some((_, code)) => *code,
+                None => return Err(key),
+            },
+            key => return Err(key),
+        })
+    }
+}
+
+impl TryFrom<Input> for KeyEvent {
+    type Error = InputConversionError;
+
+    /// Convert [`Input`] into [`termwiz::input::KeyEvent`]. [`Key::BackTab`] is converted into Shift+Tab. Inputs which
+    /// are not key inputs such as mouse inputs cannot be converted.
+    fn try_from(input: Input) -> Result<Self, Self::Error> {
+        let Ok(key) = KeyCode::try_from(input.key) else {
+            return Err(InputConversionError::new(input));
+        };
+        let mut modifiers = Modifiers::NONE;
+        if input.ctrl {
+            modifiers |= Modifiers::CTRL;
+        }
+        if input.alt {
+            modifiers |= Modifiers::ALT;
+        }
+        if input.shift || input.key == Key::BackTab {
+            modifiers |= Modifiers::SHIFT;
+        }
+        Ok(KeyEvent { key, modifiers })
+    }
+}
+
 impl From<MouseButtons> for Key {
     /// Convert [`termwiz::input::MouseButtons`] into [`Key`].
     fn from(buttons: MouseButtons) -> Self {
@@ -68,11 +227,28 @@ impl From<MouseButtons> for Key {
     }
 }
 
+thread_local! {
+    // Whether the left button was pressed at the last mouse event
+    static LEFT_PRESSED: Cell<bool> = const { Cell::new(false) };
+}
+
 impl From<MouseEvent> for Input {
     /// Convert [`termwiz::input::MouseEvent`] into [`Input`].
     fn from(mouse: MouseEvent) -> Self {
-        let MouseEvent { mouse_buttons, modifiers, .. } = mouse;
-        let key = Key::from(mouse_buttons);
+        let MouseEvent { mouse_buttons, modifiers, x, y } = mouse;
+        // termwiz reports both pressing the left button and moving the mouse with it pressed as the same event. The
+        // event following a press is a drag until the button is released
+        let pressed = mouse_buttons.contains(MouseButtons::LEFT);
+        let dragging = LEFT_PRESSED.with(|p| p.replace(pressed));
+        let key = if pressed {
+            if dragging {
+                Key::MouseDrag(x, y)
+            } else {
+                Key::MouseClick(x, y)
+            }
+        } else {
+            Key::from(mouse_buttons)
+        };
         let ctrl = modifiers.contains(Modifiers::CTRL);
         let alt = modifiers.contains(Modifiers::ALT);
         let shift = modifiers.contains(Modifiers::SHIFT);
@@ -154,13 +330,139 @@ mod tests {
             ),
             (
                 key_event(KeyCode::NumLock, Modifiers::CTRL),
-                input(Key::Null, true, false, false),
+                input(Key::NumLock, true, false, false),
+            ),
+            (
+                key_event(KeyCode::VolumeMute, Modifiers::empty()),
+                input(Key::Media(MediaKey::MuteVolume), false, false, false),
+            ),
+            (
+                key_event(KeyCode::LeftWindows, Modifiers::empty()),
+                input(Key::Modifier(ModifierKey::LeftSuper), false, false, false),
+            ),
+            (
+                key_event(KeyCode::Hyper, Modifiers::CTRL),
+                input(Key::Unidentified("Hyper"), true, false, false),
+            ),
+            (
+                key_event(KeyCode::InternalPasteStart, Modifiers::empty()),
+                input(Key::Unidentified("Unknown"), false, false, false),
             ),
         ] {
             assert_eq!(Input::from(from.clone()), to, "{:?} -> {:?}", from, to);
         }
     }
 
+    #[test]
+    fn input_to_key() {
+        for (from, to) in [
+            (
+                input(Key::Char('a'), false, false, false),
+                key_event(KeyCode::Char('a'), Modifiers::NONE),
+            ),
+            (
+                input(Key::Left, true, false, false),
+                key_event(KeyCode::LeftArrow, Modifiers::CTRL),
+            ),
+            (
+                input(Key::BackTab, false, false, false),
+                key_event(KeyCode::Tab, Modifiers::SHIFT),
+            ),
+            (
+                input(Key::F(1), true, true, true),
+                key_event(
+                    KeyCode::Function(1),
+                    Modifiers::ALT | Modifiers::CTRL | Modifiers::SHIFT,
+                ),
+            ),
+        ] {
+            assert_eq!(
+                KeyEvent::try_from(from.clone()),
+                Ok(to.clone()),
+                "{:?} -> {:?}",
+                from,
+                to
+            );
+        }
+
+        for key in [
+            Key::MouseScrollDown,
+            Key::MouseScrollUp,
+            Key::MouseClick(1, 1),
+            Key::MouseDrag(1, 1),
+            Key::Media(MediaKey::Record),
+            Key::Modifier(ModifierKey::IsoLevel3Shift),
+            Key::Unidentified("Unknown"),
+            Key::Null,
+        ] {
+            let from = input(key, true, false, false);
+            let err = KeyEvent::try_from(from.clone()).unwrap_err();
+            assert_eq!(err.input(), &from, "{:?}", from);
+        }
+    }
+
+    #[test]
+    fn round_trip_key_event() {
+        let codes = || {
+            [
+                KeyCode::Char('a'),
+                KeyCode::Char('A'),
+                KeyCode::Char('あ'),
+                KeyCode::Backspace,
+                KeyCode::Tab,
+                KeyCode::Enter,
+                KeyCode::Escape,
+                KeyCode::PageUp,
+                KeyCode::PageDown,
+                KeyCode::End,
+                KeyCode::Home,
+                KeyCode::LeftArrow,
+                KeyCode::RightArrow,
+                KeyCode::UpArrow,
+                KeyCode::DownArrow,
+                KeyCode::Delete,
+                KeyCode::Function(1),
+                KeyCode::Function(12),
+                KeyCode::Copy,
+                KeyCode::Cut,
+                KeyCode::Paste,
+                KeyCode::Insert,
+                KeyCode::CapsLock,
+                KeyCode::ScrollLock,
+                KeyCode::NumLock,
+                KeyCode::PrintScreen,
+                KeyCode::Pause,
+                KeyCode::Applications,
+                KeyCode::KeyPadBegin,
+                KeyCode::MediaPlayPause,
+                KeyCode::VolumeUp,
+                KeyCode::RightControl,
+                KeyCode::RightWindows,
+                KeyCode::Help,
+                KeyCode::Numpad0,
+            ]
+        };
+        for bits in 0..8 {
+            let mut modifiers = Modifiers::NONE;
+            for (bit, m) in [(1, Modifiers::CTRL), (2, Modifiers::ALT), (4, Modifiers::SHIFT)] {
+                if bits & bit != 0 {
+                    modifiers |= m;
+                }
+            }
+            for code in codes() {
+                let from = key_event(code, modifiers);
+                let input = Input::from(from.clone());
+                assert_eq!(
+                    KeyEvent::try_from(input.clone()),
+                    Ok(from.clone()),
+                    "{:?} -> {:?}",
+                    from,
+                    input
+                );
+            }
+        }
+    }
+
     #[test]
     fn mouse_to_input() {
         for (from, to) in [
@@ -195,7 +497,7 @@ mod tests {
                 input(Key::MouseScrollDown, true, true, true),
             ),
             (
-                mouse_event(MouseButtons::LEFT, Modifiers::empty()),
+                mouse_event(MouseButtons::RIGHT, Modifiers::empty()),
                 input(Key::Null, false, false, false),
             ),
         ] {
@@ -204,6 +506,24 @@ mod tests {
             let from = pixel_mouse_event(from.mouse_buttons, from.modifiers);
             assert_eq!(Input::from(from.clone()), to, "{:?} -> {:?}", from, to);
         }
+
+        // Moving the mouse with the left button pressed is a drag until the button is released
+        for (buttons, key) in [
+            (MouseButtons::LEFT, Key::MouseClick(1, 1)),
+            (MouseButtons::LEFT, Key::MouseDrag(1, 1)),
+            (MouseButtons::LEFT, Key::MouseDrag(1, 1)),
+            (MouseButtons::NONE, Key::Null),
+            (MouseButtons::LEFT, Key::MouseClick(1, 1)),
+            (MouseButtons::NONE, Key::Null),
+        ] {
+            let from = mouse_event(buttons, Modifiers::empty());
+            let to = input(key, false, false, false);
+            assert_eq!(Input::from(from.clone()), to, "{:?} -> {:?}", from, to);
+        }
+        // Pixel coordinates cannot be converted into cells
+        let from = pixel_mouse_event(MouseButtons::LEFT, Modifiers::empty());
+        let to = input(Key::Null, false, false, false);
+        assert_eq!(Input::from(from.clone()), to, "{:?} -> {:?}", from, to);
     }
 
     #[test]
@@ -0,0 +1,112 @@
+/// Maximum number of positions remembered in the jump list.
+const MAX_JUMPS: usize = 100;
+
+/// Cursor positions before jumps. Like a history of a web browser, going back and forward moves the index in the list,
+/// and pushing a new position forgets the positions after the index.
+#[derive(Clone, Debug, Default)]
+pub struct JumpList {
+    positions: Vec<(usize, usize)>,
+    index: usize, // The same as the length of `positions` unless going back
+}
+
+impl JumpList {
+    pub fn push(&mut self, pos: (usize, usize)) {
+        self.positions.truncate(self.index);
+        if self.positions.last() != Some(&pos) {
+            if self.positions.len() == MAX_JUMPS {
+                self.positions.remove(0);
+            }
+            self.positions.push(pos);
+        }
+        self.index = self.positions.len();
+    }
+
+    /// Go back to the previous position. `current` is the current cursor position which is remembered to go forward
+    /// to it later.
+    pub fn back(&mut self, current: (usize, usize)) -> Option<(usize, usize)> {
+        if self.index == self.positions.len() {
+            if self.positions.is_empty() {
+                return None;
+            }
+            self.push(current);
+            self.index = self.positions.len().checked_sub(2)?;
+        } else {
+            self.index = self.index.checked_sub(1)?;
+        }
+        Some(self.positions[self.index])
+    }
+
+    pub fn forward(&mut self) -> Option<(usize, usize)> {
+        if self.index + 1 >= self.positions.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.positions[self.index])
+    }
+
+    pub fn positions(&self) -> &[(usize, usize)] {
+        &self.positions
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.index = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_and_forward() {
+        let mut j = JumpList::default();
+        assert_eq!(j.back((0, 0)), None);
+        assert_eq!(j.forward(), None);
+
+        j.push((1, 0));
+        j.push((2, 0));
+        j.push((2, 0)); // Deduplicated
+        assert_eq!(j.positions(), &[(1, 0), (2, 0)]);
+
+        assert_eq!(j.back((3, 0)), Some((2, 0)));
+        assert_eq!(j.back((2, 0)), Some((1, 0)));
+        assert_eq!(j.back((1, 0)), None);
+        assert_eq!(j.forward(), Some((2, 0)));
+        assert_eq!(j.forward(), Some((3, 0)));
+        assert_eq!(j.forward(), None);
+
+        // Pushing a position after going back forgets the positions after it
+        assert_eq!(j.back((3, 0)), Some((2, 0)));
+        j.push((2, 0));
+        assert_eq!(j.positions(), &[(1, 0), (2, 0)]);
+        assert_eq!(j.index(), 2);
+
+        // The current position is not duplicated when going back
+        assert_eq!(j.back((2, 0)), Some((1, 0)));
+        assert_eq!(j.forward(), Some((2, 0)));
+        assert_eq!(j.forward(), None);
+
+        // Nothing to go back to when the only position is the current one
+        let mut j = JumpList::default();
+        j.push((5, 0));
+        assert_eq!(j.back((5, 0)), None);
+        assert_eq!(j.positions(), &[(5, 0)]);
+        assert_eq!(j.index(), 1);
+    }
+
+    #[test]
+    fn max_jumps() {
+        let mut j = JumpList::default();
+        for i in 0..MAX_JUMPS + 10 {
+            j.push((i, 0));
+        }
+        assert_eq!(j.positions().len(), MAX_JUMPS);
+        assert_eq!(j.positions()[0], (10, 0));
+        assert_eq!(j.index(), MAX_JUMPS);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::cursor::CursorMove;
+use crate::input::{Input, Key};
+use crate::scroll::Scrolling;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// An editor operation bound to a key input in the keymap of [`TextArea`]. [`TextArea::input`] looks up the action of
+/// an input in [`TextArea::keymap`] and performs it with [`TextArea::perform`].
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future.
+/// ```
+/// use tui_textarea::{CursorMove, EditAction, TextArea};
+///
+/// let mut textarea = TextArea::default();
+///
+/// assert!(textarea.perform(EditAction::InsertChar('a')));
+/// assert!(textarea.perform(EditAction::InsertNewline));
+/// assert!(!textarea.perform(EditAction::MoveCursor(CursorMove::Top)));
+/// assert_eq!(textarea.lines(), ["a", ""]);
+/// assert_eq!(textarea.cursor(), (0, 0));
+/// ```
+///
+/// [`TextArea`]: crate::TextArea
+/// [`TextArea::input`]: crate::TextArea::input
+/// [`TextArea::keymap`]: crate::TextArea::keymap
+/// [`TextArea::perform`]: crate::TextArea::perform
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EditAction {
+    /// Insert the character at the cursor. See [`TextArea::insert_char`](crate::TextArea::insert_char).
+    InsertChar(char),
+    /// Insert a newline at the cursor. See [`TextArea::insert_newline`](crate::TextArea::insert_newline).
+    InsertNewline,
+    /// Indent the selected lines, or insert a tab at the cursor when nothing is selected.
+    Indent,
+    /// Dedent the selected lines, or remove a tab at the head of the cursor line when nothing is selected.
+    Dedent,
+    /// Delete one character before the cursor. See [`TextArea::delete_char`](crate::TextArea::delete_char).
+    DeleteChar,
+    /// Delete one character next to the cursor. See [`TextArea::delete_next_char`](crate::TextArea::delete_next_char).
+    DeleteNextChar,
+    /// Delete one word before the cursor. See [`TextArea::delete_word`](crate::TextArea::delete_word).
+    DeleteWord,
+    /// Delete one word next to the cursor. See [`TextArea::delete_next_word`](crate::TextArea::delete_next_word).
+    DeleteNextWord,
+    /// Delete the current or selected lines. See [`TextArea::delete_line`](crate::TextArea::delete_line).
+    DeleteLine,
+    /// Delete from the cursor until the end of line. See
+    /// [`TextArea::delete_line_by_end`](crate::TextArea::delete_line_by_end).
+    DeleteLineByEnd,
+    /// Delete from the cursor until the head of line. See
+    /// [`TextArea::delete_line_by_head`](crate::TextArea::delete_line_by_head).
+    DeleteLineByHead,
+    /// Duplicate the current or selected lines. See [`TextArea::duplicate_lines`](crate::TextArea::duplicate_lines).
+    DuplicateLines,
+    /// Move the current or selected lines up. See [`TextArea::move_lines_up`](crate::TextArea::move_lines_up).
+    MoveLinesUp,
+    /// Move the current or selected lines down. See [`TextArea::move_lines_down`](crate::TextArea::move_lines_down).
+    MoveLinesDown,
+    /// Swap the characters around the cursor. See [`TextArea::transpose_chars`](crate::TextArea::transpose_chars).
+    TransposeChars,
+    /// Swap the words around the cursor. See [`TextArea::transpose_words`](crate::TextArea::transpose_words).
+    TransposeWords,
+    /// Move the cursor and cancel the selection.
+    MoveCursor(CursorMove),
+    /// Move the cursor extending the selection. The selection starts at the cursor when nothing is selected.
+    MoveCursorSelecting(CursorMove),
+    /// Scroll the textarea and cancel the selection.
+    Scroll(Scrolling),
+    /// Scroll the textarea extending the selection when the cursor is moved by the scroll.
+    ScrollSelecting(Scrolling),
+    /// Scroll only the viewport by rows without moving the cursor. Negative rows scroll it up.
+    ScrollViewport(i64),
+    /// Select the entire text. See [`TextArea::select_all`](crate::TextArea::select_all).
+    SelectAll,
+    /// Undo the last edit. See [`TextArea::undo`](crate::TextArea::undo).
+    Undo,
+    /// Redo the last undone edit. See [`TextArea::redo`](crate::TextArea::redo).
+    Redo,
+    /// Copy the selected text. See [`TextArea::copy`](crate::TextArea::copy).
+    Copy,
+    /// Cut the selected text. See [`TextArea::cut`](crate::TextArea::cut).
+    Cut,
+    /// Paste the yanked text. See [`TextArea::paste`](crate::TextArea::paste).
+    Paste,
+    /// Replace the pasted text with an older kill. See [`TextArea::paste_rotate`](crate::TextArea::paste_rotate).
+    PasteRotate,
+    /// Toggle the overwrite mode. See [`TextArea::toggle_overwrite_mode`](crate::TextArea::toggle_overwrite_mode).
+    ToggleOverwriteMode,
+    /// Do nothing. Since a character not bound in the keymap is inserted, bind a key to this action to disable it.
+    Ignore,
+}
+
+impl EditAction {
+    // Check if the action may modify the text. Such actions are ignored by inputs while the textarea is read-only
+    pub(crate) fn modifies_text(&self) -> bool {
+        !matches!(
+            self,
+            Self::MoveCursor(_)
+                | Self::MoveCursorSelecting(_)
+                | Self::Scroll(_)
+                | Self::ScrollSelecting(_)
+                | Self::ScrollViewport(_)
+                | Self::SelectAll
+                | Self::Copy
+                | Self::ToggleOverwriteMode
+                | Self::Ignore
+        )
+    }
+
+    // Check if the action is performed at every cursor in multi-cursor editing
+    pub(crate) fn is_per_cursor(&self) -> bool {
+        matches!(
+            self,
+            Self::InsertChar(_)
+                | Self::InsertNewline
+                | Self::DeleteChar
+                | Self::DeleteNextChar
+                | Self::DeleteWord
+                | Self::DeleteNextWord
+                | Self::DeleteLineByEnd
+                | Self::DeleteLineByHead
+                | Self::MoveCursor(_)
+                | Self::MoveCursorSelecting(_)
+                | Self::Paste
+        )
+    }
+
+    // Check if the action makes sense only with multiple lines. Such actions are not performed by
+    // `TextArea::single_line_input`
+    pub(crate) fn is_multi_line(&self) -> bool {
+        match self {
+            Self::MoveCursor(m) | Self::MoveCursorSelecting(m) => !matches!(
+                m,
+                CursorMove::Forward
+                    | CursorMove::Back
+                    | CursorMove::Head
+                    | CursorMove::End
+                    | CursorMove::WordForward
+                    | CursorMove::WordBack
+                    | CursorMove::SubWordForward
+                    | CursorMove::SubWordBack
+                    | CursorMove::SubWordEnd
+            ),
+            Self::InsertNewline
+            | Self::Indent
+            | Self::Dedent
+            | Self::DeleteLine
+            | Self::DuplicateLines
+            | Self::MoveLinesUp
+            | Self::MoveLinesDown
+            | Self::Scroll(_)
+            | Self::ScrollSelecting(_)
+            | Self::ScrollViewport(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The default keymap of [`TextArea`](crate::TextArea). See the 'Minimal Usage' section of
+/// [the module document](./index.html) for the key mappings. This is useful to reset a modified keymap.
+/// ```
+/// use tui_textarea::{default_keymap, EditAction, Input, Key, TextArea};
+///
+/// let mut textarea = TextArea::default();
+/// textarea.keymap_mut().clear();
+/// assert!(textarea.keymap().is_empty());
+///
+/// *textarea.keymap_mut() = default_keymap();
+/// let ctrl_z = Input { key: Key::Char('z'), ctrl: true, ..Default::default() };
+/// assert_eq!(textarea.keymap().get(&ctrl_z), Some(&EditAction::Undo));
+/// ```
+pub fn default_keymap() -> HashMap<Input, EditAction> {
+    use EditAction::*;
+
+    let mut keymap = HashMap::new();
+    let mut bind = |key, ctrl, alt, shift, action| {
+        keymap.insert(Input { key, ctrl, alt, shift }, action);
+    };
+    let modifiers = [(false, false), (true, false), (false, true), (true, true)];
+
+    for shift in [false, true] {
+        let (move_cursor, scroll) = if shift {
+            (MoveCursorSelecting as fn(_) -> _, ScrollSelecting as fn(_) -> _)
+        } else {
+            (MoveCursor as fn(_) -> _, Scroll as fn(_) -> _)
+        };
+
+        bind(Key::Char('d'), true, false, shift, DuplicateLines);
+        bind(Key::Char('\n'), false, false, shift, InsertNewline);
+        bind(Key::Char('\r'), false, false, shift, InsertNewline);
+        bind(Key::BackTab, false, false, shift, Dedent);
+        bind(Key::Backspace, false, false, shift, DeleteChar);
+        bind(Key::Backspace, true, false, shift, DeleteWord);
+        bind(Key::Delete, false, false, shift, DeleteNextChar);
+        bind(Key::Delete, true, false, shift, DeleteNextWord);
+
+        bind(Key::Up, false, false, shift, move_cursor(CursorMove::Up));
+        bind(Key::Down, false, false, shift, move_cursor(CursorMove::Down));
+        bind(Key::Left, false, false, shift, move_cursor(CursorMove::Back));
+        bind(Key::Right, false, false, shift, move_cursor(CursorMove::Forward));
+        bind(Key::Up, true, false, shift, move_cursor(CursorMove::ParagraphBack));
+        bind(Key::Down, true, false, shift, move_cursor(CursorMove::ParagraphForward));
+        bind(Key::Left, true, false, shift, move_cursor(CursorMove::WordBack));
+        bind(Key::Right, true, false, shift, move_cursor(CursorMove::WordForward));
+        bind(Key::Up, true, true, shift, move_cursor(CursorMove::Top));
+        bind(Key::Down, true, true, shift, move_cursor(CursorMove::Bottom));
+
+        bind(Key::Char('z'), true, false, shift, Undo);
+        bind(Key::Char('y'), true, false, shift, Redo);
+        bind(Key::Char('v'), true, false, shift, Paste);
+        bind(Key::Char('x'), true, false, shift, Cut);
+        bind(Key::Char('c'), true, false, shift, Copy);
+
+        for (ctrl, alt) in modifiers {
+            bind(Key::Enter, ctrl, alt, shift, InsertNewline);
+            bind(Key::Home, ctrl, alt, shift, move_cursor(CursorMove::Head));
+            bind(Key::End, ctrl, alt, shift, move_cursor(CursorMove::End));
+            bind(Key::Paste, ctrl, alt, shift, Paste);
+            bind(Key::Cut, ctrl, alt, shift, Cut);
+            bind(Key::Copy, ctrl, alt, shift, Copy);
+            bind(Key::Insert, ctrl, alt, shift, ToggleOverwriteMode);
+            bind(Key::PageDown, ctrl, alt, shift, scroll(Scrolling::PageDown));
+            bind(Key::PageUp, ctrl, alt, shift, scroll(Scrolling::PageUp));
+            bind(
+                Key::MouseScrollDown,
+                ctrl,
+                alt,
+                shift,
+                scroll(Scrolling::Delta { rows: 1, cols: 0 }),
+            );
+            bind(
+                Key::MouseScrollUp,
+                ctrl,
+                alt,
+                shift,
+                scroll(Scrolling::Delta { rows: -1, cols: 0 }),
+            );
+        }
+    }
+
+    bind(Key::Tab, false, false, false, Indent);
+    bind(Key::Tab, false, false, true, Dedent);
+    bind(Key::Char('K'), true, false, true, DeleteLine);
+    bind(Key::Char('k'), true, false, false, DeleteLineByEnd);
+    bind(Key::Char('j'), true, false, false, DeleteLineByHead);
+    bind(Key::Char('a'), true, false, false, SelectAll);
+    bind(Key::Up, false, true, false, MoveLinesUp);
+    bind(Key::Down, false, true, false, MoveLinesDown);
+    bind(Key::Up, false, true, true, ScrollViewport(-1));
+    bind(Key::Down, false, true, true, ScrollViewport(1));
+
+    keymap
+}
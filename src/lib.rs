@@ -7,17 +7,46 @@
 #[cfg(all(feature = "ratatui", feature = "tuirs"))]
 compile_error!("ratatui support and tui-rs support are exclusive. only one of them can be enabled at the same time. see https://github.com/rhysd/tui-textarea#installation");
 
+mod bracket;
+mod buffer;
+mod builder;
+mod cache;
+mod cells;
+#[cfg(feature = "clipboard")]
+mod clipboard;
 mod cursor;
+mod fold;
+#[cfg(feature = "search")]
+mod fuzzy;
 mod highlight;
 mod history;
 mod input;
+mod jump;
+mod keymap;
+mod link;
+mod number;
+mod offset;
+#[cfg(feature = "rope")]
+mod rope;
 mod scroll;
+mod scrollbar;
 #[cfg(feature = "search")]
 mod search;
+mod sign;
+mod snippet;
+#[cfg(feature = "serde")]
+mod state;
+mod stats;
+mod storage;
 mod textarea;
+mod transform;
 mod util;
+#[cfg(feature = "vim")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vim")))]
+pub mod vim;
 mod widget;
 mod word;
+mod wrap;
 
 #[cfg(feature = "ratatui")]
 #[allow(clippy::single_component_path_imports)]
@@ -31,7 +60,22 @@ use crossterm;
 #[cfg(feature = "tuirs-crossterm")]
 use crossterm_025 as crossterm;
 
-pub use cursor::CursorMove;
-pub use input::{Input, Key};
-pub use scroll::Scrolling;
-pub use textarea::{Fullscreen, TextArea};
+pub use builder::TextAreaBuilder;
+pub use cursor::{parse_position, CursorMove};
+pub use fold::Fold;
+pub use highlight::{Highlight, WhitespaceStyle};
+pub use history::{TextEdit, UndoGrouping};
+pub use input::{Input, InputConversionError, InputKind, InputResult, Key, MediaKey, ModifierKey, ParseInputError};
+pub use keymap::{default_keymap, EditAction};
+pub use link::{detect_urls, LinkDetector};
+pub use scroll::{CursorAlign, Scrolling};
+pub use scrollbar::ScrollbarConfig;
+#[cfg(feature = "search")]
+pub use search::{SearchMode, SearchOptions, SearchScope};
+pub use sign::Sign;
+#[cfg(feature = "serde")]
+pub use state::{TextAreaState, UndoHistory};
+pub use stats::TextStats;
+pub use textarea::{Fullscreen, IndentKind, LineEnding, SaveOptions, SortOrder, TextArea};
+pub use transform::TextTransform;
+pub use widget::Viewport;
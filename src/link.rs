@@ -0,0 +1,95 @@
+use std::ops::Range;
+
+/// Function to detect links in a line. It returns the byte ranges of links in the line and their targets. Set by
+/// [`crate::TextArea::set_link_detector`].
+pub type LinkDetector = fn(&str) -> Vec<(Range<usize>, String)>;
+
+/// The default link detector which detects URLs starting with `http://` or `https://`. A URL ends at a whitespace or a
+/// quote, and punctuations at the end of the URL such as `.` or `)` are not included.
+/// ```
+/// use tui_textarea::detect_urls;
+///
+/// let links = detect_urls("See https://example.com/docs.");
+/// assert_eq!(links, [(4..28, "https://example.com/docs".to_string())]);
+/// ```
+pub fn detect_urls(line: &str) -> Vec<(Range<usize>, String)> {
+    let mut links = vec![];
+    let mut offset = 0;
+    while let Some(i) = line[offset..].find("http") {
+        let start = offset + i;
+        let rest = &line[start..];
+        let scheme = if rest.starts_with("https://") {
+            "https://".len()
+        } else if rest.starts_with("http://") {
+            "http://".len()
+        } else {
+            offset = start + "http".len();
+            continue;
+        };
+        // A URL in the middle of a word is not detected
+        let in_word = line[..start].chars().next_back().map_or(false, char::is_alphanumeric);
+        let len = rest
+            .find(|c: char| c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '<' | '>' | '`'))
+            .unwrap_or(rest.len());
+        let url = trim_url_end(&rest[..len]);
+        if !in_word && url.len() > scheme {
+            links.push((start..start + url.len(), url.to_string()));
+        }
+        offset = start + len;
+    }
+    links
+}
+
+// Trim punctuations at the end of the URL. Closing parentheses are kept when they are balanced in the URL
+fn trim_url_end(mut url: &str) -> &str {
+    loop {
+        let Some(last) = url.chars().next_back() else {
+            return url;
+        };
+        let trimmed = match last {
+            '.' | ',' | ':' | ';' | '!' | '?' => true,
+            ')' => url.matches('(').count() < url.matches(')').count(),
+            ']' => url.matches('[').count() < url.matches(']').count(),
+            '}' => url.matches('{').count() < url.matches('}').count(),
+            _ => false,
+        };
+        if !trimmed {
+            return url;
+        }
+        url = &url[..url.len() - last.len_utf8()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls() {
+        let tests = [
+            ("", &[][..]),
+            ("no link", &[]),
+            ("http://a.b", &[(0..10, "http://a.b")]),
+            ("go to https://a.b/c?d=e#f now", &[(6..25, "https://a.b/c?d=e#f")]),
+            ("(https://a.b/c)", &[(1..14, "https://a.b/c")]),
+            ("https://a.b/c_(d).", &[(0..17, "https://a.b/c_(d)")]),
+            (
+                "<http://a.b>, 'http://c.d'",
+                &[(1..11, "http://a.b"), (15..25, "http://c.d")],
+            ),
+            (
+                "http://a.b http://c.d",
+                &[(0..10, "http://a.b"), (11..21, "http://c.d")],
+            ),
+            ("https://", &[]),
+            ("xhttps://a.b", &[]),
+            ("httpx http", &[]),
+            ("あhttps://a.b", &[]),
+            ("→ https://a.b/あ", &[(4..19, "https://a.b/あ")]),
+        ];
+        for (line, want) in tests {
+            let want: Vec<_> = want.iter().map(|(r, s)| (r.clone(), s.to_string())).collect();
+            assert_eq!(detect_urls(line), want, "{line:?}");
+        }
+    }
+}
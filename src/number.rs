@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn is_hex_prefix(bytes: &[u8], i: usize) -> bool {
+    bytes[i] == b'0'
+        && matches!(bytes.get(i + 1), Some(b'x' | b'X'))
+        && bytes.get(i + 2).map_or(false, u8::is_ascii_hexdigit)
+}
+
+// Byte range of the number under or after the byte offset in the line. A hexadecimal number starts with `0x`, and a
+// decimal number may have a `-` sign which does not follow a word character
+pub(crate) fn find_number(line: &str, offset: usize) -> Option<Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let mut start = i;
+        if is_hex_prefix(bytes, i) {
+            i += 2;
+            while bytes.get(i).map_or(false, u8::is_ascii_hexdigit) {
+                i += 1;
+            }
+        } else if bytes[i].is_ascii_digit() {
+            while bytes.get(i).map_or(false, u8::is_ascii_digit) {
+                i += 1;
+            }
+            if start > 0 && bytes[start - 1] == b'-' && !(start > 1 && is_word(bytes[start - 2])) {
+                start -= 1;
+            }
+        } else {
+            i += 1;
+            continue;
+        }
+        if i > offset {
+            return Some(start..i);
+        }
+    }
+    None
+}
+
+// Add `delta` to the number found by `find_number`. Leading zeros are kept by padding the digits to the same width,
+// and hexadecimal numbers wrap around as 64-bit unsigned integers keeping the case of their letters. `None` is returned
+// when the number is too large
+pub(crate) fn increment(number: &str, delta: i64) -> Option<String> {
+    if let Some(digits) = number.get(2..).filter(|_| is_hex_prefix(number.as_bytes(), 0)) {
+        let value = u64::from_str_radix(digits, 16).ok()?.wrapping_add(delta as u64);
+        let (prefix, width) = (&number[..2], digits.len());
+        return Some(if digits.bytes().any(|b| b.is_ascii_uppercase()) {
+            format!("{prefix}{value:0width$X}")
+        } else {
+            format!("{prefix}{value:0width$x}")
+        });
+    }
+
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, number),
+    };
+    let value = sign * digits.parse::<i128>().ok()?;
+    let value = value.checked_add(delta.into())?;
+    let width = if digits.len() > 1 && digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    };
+    let sign = if value < 0 { "-" } else { "" };
+    Some(format!("{sign}{:0width$}", value.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find() {
+        let tests = [
+            ("", 0, None),
+            ("abc", 0, None),
+            ("12", 0, Some(0..2)),
+            ("12", 1, Some(0..2)),
+            ("12", 2, None),
+            ("a 12 34", 0, Some(2..4)),
+            ("a 12 34", 4, Some(5..7)),
+            ("x = -5", 0, Some(4..6)),
+            ("x = -5", 4, Some(4..6)),
+            ("a-5", 0, Some(2..3)),
+            ("v2", 0, Some(1..2)),
+            ("0xff;", 0, Some(0..4)),
+            ("0xff;", 3, Some(0..4)),
+            ("0x;", 0, Some(0..1)),
+            ("-0x1", 0, Some(1..4)),
+            ("あ 1", 0, Some(4..5)),
+        ];
+        for (line, offset, want) in tests {
+            assert_eq!(find_number(line, offset), want, "{line:?} {offset}");
+        }
+    }
+
+    #[test]
+    fn increment_number() {
+        let tests = [
+            ("0", 1, Some("1")),
+            ("9", 1, Some("10")),
+            ("1", -2, Some("-1")),
+            ("-1", 1, Some("0")),
+            ("-10", 3, Some("-7")),
+            ("007", 1, Some("008")),
+            ("099", 1, Some("100")),
+            ("0999", 1, Some("1000")),
+            ("-007", 10, Some("003")),
+            ("0xff", 1, Some("0x100")),
+            ("0x0f", 1, Some("0x10")),
+            ("0x00FE", 1, Some("0x00FF")),
+            ("0X0", -1, Some("0Xffffffffffffffff")),
+            ("99999999999999999999999999999999999999999", 1, None),
+        ];
+        for (number, delta, want) in tests {
+            assert_eq!(increment(number, delta).as_deref(), want, "{number:?} {delta}");
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::storage::Lines;
+use std::cmp;
+
+/// Unit to count offsets in the text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Char,
+    Byte,
+    Utf16,
+}
+
+impl Unit {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn len(self, line: &str) -> usize {
+        match self {
+            Unit::Char => line.chars().count(),
+            Unit::Byte => line.len(),
+            Unit::Utf16 => line.encode_utf16().count(),
+        }
+    }
+
+    // Offset of the character at the column in the line
+    fn offset_of_col(self, line: &str, col: usize) -> usize {
+        match self {
+            Unit::Char => cmp::min(col, line.chars().count()),
+            Unit::Byte => line.char_indices().nth(col).map_or(line.len(), |(i, _)| i),
+            Unit::Utf16 => line.chars().take(col).map(char::len_utf16).sum(),
+        }
+    }
+
+    // Column of the character at the offset in the line. An offset in the middle of a character is rounded down to the
+    // start of the character
+    fn col_of_offset(self, line: &str, offset: usize) -> usize {
+        let len = |c: char| match self {
+            Unit::Char => 1,
+            Unit::Byte => c.len_utf8(),
+            Unit::Utf16 => c.len_utf16(),
+        };
+        let mut end = 0;
+        line.chars()
+            .take_while(|&c| {
+                end += len(c);
+                end <= offset
+            })
+            .count()
+    }
+}
+
+/// Offsets of the starts of lines counted in each [`Unit`]. A line separator is counted as one unit. Offsets are
+/// calculated lazily and only the offsets after the first edited row are discarded on edits, so converting offsets on
+/// every edit does not scan the whole text.
+#[derive(Clone, Debug, Default)]
+pub struct LineOffsets {
+    starts: [Vec<usize>; 3],
+}
+
+impl LineOffsets {
+    /// Discard the offsets which may be changed by an edit at the row.
+    pub fn invalidate(&mut self, row: usize) {
+        for starts in &mut self.starts {
+            starts.truncate(row + 1);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.invalidate(0);
+    }
+
+    // Offsets of the starts of lines up to the row
+    fn starts(&mut self, lines: &Lines, unit: Unit, row: usize) -> &[usize] {
+        let starts = &mut self.starts[unit.index()];
+        starts.truncate(lines.len());
+        if starts.is_empty() {
+            starts.push(0);
+        }
+        while starts.len() <= row {
+            let prev = starts.len() - 1;
+            starts.push(starts[prev] + unit.len(&lines.line(prev)) + 1);
+        }
+        starts
+    }
+
+    /// Offset of the `(row, col)` position. The position is clamped to the text.
+    pub fn offset(&mut self, lines: &Lines, unit: Unit, (row, col): (usize, usize)) -> usize {
+        let row = cmp::min(row, lines.len() - 1);
+        let start = self.starts(lines, unit, row)[row];
+        start + unit.offset_of_col(&lines.line(row), col)
+    }
+
+    /// `(row, col)` position of the offset. The offset is clamped to the end of the text.
+    pub fn position(&mut self, lines: &Lines, unit: Unit, offset: usize) -> (usize, usize) {
+        let last = lines.len() - 1;
+        let starts = self.starts(lines, unit, last);
+        let row = starts.partition_point(|&s| s <= offset) - 1;
+        (row, unit.col_of_offset(&lines.line(row), offset - starts[row]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Lines {
+        Lines::from(lines.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn offset_and_position() {
+        let lines = lines(&["aあ", "", "😀b"]);
+        let mut offsets = LineOffsets::default();
+        let tests = [
+            // (position, char offset, byte offset, UTF-16 offset)
+            ((0, 0), 0, 0, 0),
+            ((0, 1), 1, 1, 1),
+            ((0, 2), 2, 4, 2),
+            ((1, 0), 3, 5, 3),
+            ((2, 0), 4, 6, 4),
+            ((2, 1), 5, 10, 6),
+            ((2, 2), 6, 11, 7),
+        ];
+        for (pos, c, b, u) in tests {
+            for (unit, offset) in [(Unit::Char, c), (Unit::Byte, b), (Unit::Utf16, u)] {
+                assert_eq!(offsets.offset(&lines, unit, pos), offset, "{unit:?} {pos:?}");
+                assert_eq!(offsets.position(&lines, unit, offset), pos, "{unit:?} {offset}");
+            }
+        }
+    }
+
+    #[test]
+    fn clamp_out_of_range() {
+        let lines = lines(&["aあ", "😀b"]);
+        let mut offsets = LineOffsets::default();
+        assert_eq!(offsets.offset(&lines, Unit::Char, (0, 10)), 2);
+        assert_eq!(offsets.offset(&lines, Unit::Char, (5, 1)), 4);
+        assert_eq!(offsets.position(&lines, Unit::Char, 100), (1, 2));
+        // Offsets in the middle of a character are rounded down
+        assert_eq!(offsets.position(&lines, Unit::Byte, 2), (0, 1));
+        assert_eq!(offsets.position(&lines, Unit::Utf16, 4), (1, 0));
+    }
+
+    #[test]
+    fn invalidate_after_edit() {
+        let mut lines = lines(&["ab", "cd", "ef"]);
+        let mut offsets = LineOffsets::default();
+        assert_eq!(offsets.offset(&lines, Unit::Char, (2, 0)), 6);
+        lines.insert_char(1, 2, 'x');
+        offsets.invalidate(1);
+        assert_eq!(offsets.offset(&lines, Unit::Char, (2, 0)), 7);
+        lines.remove_line(2);
+        offsets.invalidate(2);
+        assert_eq!(offsets.position(&lines, Unit::Char, 100), (1, 3));
+    }
+}
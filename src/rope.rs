@@ -0,0 +1,144 @@
+use crate::storage::TextStorage;
+use ropey::{Rope, RopeBuilder, RopeSlice};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Text stored in a rope. Lines are separated by `\n` in the rope. Editing a long line is O(log n) since the rope does
+/// not move the text after the edited position, and the offsets and columns in a line are found without scanning it.
+#[derive(Clone, Debug)]
+pub struct RopeStorage(Rope);
+
+impl RopeStorage {
+    pub fn new<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Self {
+        let mut builder = RopeBuilder::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                builder.append("\n");
+            }
+            builder.append(line.as_ref());
+        }
+        Self(builder.finish())
+    }
+
+    // Byte offset of the start of the line in the rope
+    fn start(&self, row: usize) -> usize {
+        self.0.line_to_byte(row)
+    }
+
+    // Byte offset of the end of the line in the rope, before the newline
+    fn end(&self, row: usize) -> usize {
+        if row + 1 < self.0.len_lines() {
+            self.0.line_to_byte(row + 1) - 1
+        } else {
+            self.0.len_bytes()
+        }
+    }
+
+    fn slice_of(&self, row: usize) -> RopeSlice<'_> {
+        self.0.byte_slice(self.start(row)..self.end(row))
+    }
+
+    fn char_at(&self, byte: usize) -> usize {
+        self.0.byte_to_char(byte)
+    }
+}
+
+fn into_cow(slice: RopeSlice<'_>) -> Cow<'_, str> {
+    match slice.as_str() {
+        Some(s) => Cow::Borrowed(s),
+        None => Cow::Owned(slice.to_string()),
+    }
+}
+
+impl TextStorage for RopeStorage {
+    fn len(&self) -> usize {
+        self.0.len_lines()
+    }
+
+    fn line(&self, row: usize) -> Cow<'_, str> {
+        into_cow(self.slice_of(row))
+    }
+
+    fn chunks(&self, row: usize, range: Range<usize>) -> Box<dyn Iterator<Item = &str> + '_> {
+        let start = self.start(row);
+        Box::new(self.0.byte_slice(start + range.start..start + range.end).chunks())
+    }
+
+    fn insert_str(&mut self, row: usize, offset: usize, s: &str) {
+        let idx = self.char_at(self.start(row) + offset);
+        self.0.insert(idx, s);
+    }
+
+    fn remove_str(&mut self, row: usize, range: Range<usize>) -> String {
+        let start = self.start(row);
+        let chars = self.char_at(start + range.start)..self.char_at(start + range.end);
+        let removed = self.0.slice(chars.clone()).to_string();
+        self.0.remove(chars);
+        removed
+    }
+
+    fn split_line(&mut self, row: usize, offset: usize) {
+        self.insert_str(row, offset, "\n");
+    }
+
+    fn join_line(&mut self, row: usize) {
+        let newline = self.char_at(self.end(row));
+        self.0.remove(newline..newline + 1);
+    }
+
+    fn splice(&mut self, rows: Range<usize>, lines: Vec<String>) -> Vec<String> {
+        let replaced = rows.clone().map(|row| self.line(row).into_owned()).collect();
+        let len = self.len();
+
+        // Range of the text to replace including one of the newlines around it, and the text replacing it
+        let (start, end, mut text) = if rows.end < len {
+            (self.start(rows.start), self.start(rows.end), String::new())
+        } else if rows.start > 0 {
+            (self.end(rows.start - 1), self.0.len_bytes(), String::new())
+        } else {
+            debug_assert!(!lines.is_empty(), "no line after splice");
+            (0, self.0.len_bytes(), String::new())
+        };
+        for (i, line) in lines.iter().enumerate() {
+            if rows.end >= len && (rows.start > 0 || i > 0) {
+                text.push('\n');
+            }
+            text.push_str(line);
+            if rows.end < len {
+                text.push('\n');
+            }
+        }
+
+        let (start, end) = (self.char_at(start), self.char_at(end));
+        if start < end {
+            self.0.remove(start..end);
+        }
+        self.0.insert(start, &text);
+        replaced
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.end(row) - self.start(row)
+    }
+
+    fn char_count(&self, row: usize) -> usize {
+        self.char_at(self.end(row)) - self.char_at(self.start(row))
+    }
+
+    fn offset_of_col(&self, row: usize, col: usize) -> usize {
+        let start = self.start(row);
+        let first = self.char_at(start);
+        let last = self.char_at(self.end(row));
+        self.0.char_to_byte((first + col).min(last)) - start
+    }
+
+    fn col_of_offset(&self, row: usize, offset: usize) -> usize {
+        let start = self.start(row);
+        self.char_at(start + offset) - self.char_at(start)
+    }
+
+    fn slice(&self, row: usize, range: Range<usize>) -> Cow<'_, str> {
+        let start = self.start(row);
+        into_cow(self.0.byte_slice(start + range.start..start + range.end))
+    }
+}
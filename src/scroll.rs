@@ -7,6 +7,8 @@ use crate::widget::Viewport;
 ///
 /// [`TextArea::scroll`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.scroll
 #[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Scrolling {
     /// Scroll the textarea by rows (vertically) and columns (horizontally). Passing positive scroll amounts to `rows` and `cols`
     /// scolls it to down and right. Negative integers means the opposite directions. `(i16, i16)` pair can be converted into
@@ -148,10 +150,54 @@ pub enum Scrolling {
     /// assert_eq!(textarea.cursor(), (11, 0));
     /// ```
     HalfPageUp,
+    /// Scroll the textarea to the first line.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{TextArea, Scrolling, CursorMove};
+    ///
+    /// // Let's say terminal height is 8.
+    ///
+    /// // Create textarea with 20 lines "0", "1", "2", "3", ...
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// # // Call `render` at least once to populate terminal size
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.widget().render(r.clone(), &mut b);
+    ///
+    /// textarea.scroll(Scrolling::Top);
+    /// assert_eq!(textarea.cursor(), (7, 0));
+    /// ```
+    Top,
+    /// Scroll the textarea so that the last line is at the bottom of the viewport.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// // Let's say terminal height is 8.
+    ///
+    /// // Create textarea with 20 lines "0", "1", "2", "3", ...
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// # // Call `render` at least once to populate terminal size
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.widget().render(r.clone(), &mut b);
+    ///
+    /// textarea.scroll(Scrolling::Bottom);
+    /// assert_eq!(textarea.cursor(), (12, 0));
+    /// ```
+    Bottom,
 }
 
 impl Scrolling {
-    pub(crate) fn scroll(self, viewport: &mut Viewport) {
+    pub(crate) fn scroll(self, viewport: &mut Viewport, lines_len: usize) {
+        let last = lines_len.saturating_sub(1) as u64;
         let (rows, cols) = match self {
             Self::Delta { rows, cols } => (rows, cols),
             Self::PageDown => {
@@ -170,8 +216,18 @@ impl Scrolling {
                 let (_, _, _, height) = viewport.rect();
                 (-i64::from(height) / 2, 0)
             }
+            Self::Top => {
+                let (row, _, _, _) = viewport.rect();
+                (-(row as i64), 0)
+            }
+            Self::Bottom => {
+                let (row, _, _, height) = viewport.rect();
+                let bottom = (lines_len as u64).saturating_sub(height.into());
+                (bottom as i64 - row as i64, 0)
+            }
         };
         viewport.scroll(rows, cols);
+        viewport.clamp_row(last);
     }
 }
 
@@ -181,6 +237,32 @@ impl From<(i64, i64)> for Scrolling {
     }
 }
 
+/// Position in the viewport where the cursor line is put by [`TextArea::scroll_cursor`], like `zt`, `zz`, and `zb`
+/// commands of Vim.
+///
+/// [`TextArea::scroll_cursor`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.scroll_cursor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CursorAlign {
+    /// Put the cursor line at the top of the viewport.
+    Top,
+    /// Put the cursor line at the center of the viewport.
+    Center,
+    /// Put the cursor line at the bottom of the viewport.
+    Bottom,
+}
+
+impl CursorAlign {
+    /// Number of display rows above the cursor in the viewport with the height.
+    pub(crate) fn rows_above(self, height: u16) -> usize {
+        let height = height.max(1) as usize;
+        match self {
+            Self::Top => 0,
+            Self::Center => (height - 1) / 2,
+            Self::Bottom => height - 1,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,187 @@
+use crate::ratatui::buffer::Buffer;
+use crate::ratatui::layout::Rect;
+use crate::ratatui::style::{Color, Style};
+use std::cmp;
+use std::ops::Range;
+
+/// Symbols and styles of the scrollbars rendered by the textarea. Set by [`crate::TextArea::set_scrollbar`].
+///
+/// The vertical scrollbar takes the rightmost column inside the block and the horizontal scrollbar takes the bottom row
+/// inside the block, so the text never renders under them. Symbols should be one cell wide.
+/// ```
+/// use ratatui::style::{Color, Style};
+/// use tui_textarea::ScrollbarConfig;
+///
+/// let config = ScrollbarConfig {
+///     thumb: '┃',
+///     thumb_style: Style::default().fg(Color::Yellow),
+///     horizontal: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(config.track, '│');
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollbarConfig {
+    /// Symbol of the thumb which shows the visible part of the text. The default value is `'█'`.
+    pub thumb: char,
+    /// Symbol of the track of the vertical scrollbar. The default value is `'│'`.
+    pub track: char,
+    /// Symbol of the track of the horizontal scrollbar. The default value is `'─'`.
+    pub horizontal_track: char,
+    /// Style of the thumb. The default value is the default style.
+    pub thumb_style: Style,
+    /// Style of the track. The default value is dark gray foreground.
+    pub track_style: Style,
+    /// Render the horizontal scrollbar in addition to the vertical one. The default value is `false`.
+    pub horizontal: bool,
+}
+
+impl Default for ScrollbarConfig {
+    fn default() -> Self {
+        Self {
+            thumb: '█',
+            track: '│',
+            horizontal_track: '─',
+            thumb_style: Style::default(),
+            track_style: Style::default().fg(Color::DarkGray),
+            horizontal: false,
+        }
+    }
+}
+
+impl ScrollbarConfig {
+    /// Split the area inside the block into the area of the text, the vertical scrollbar, and the horizontal scrollbar.
+    pub(crate) fn split(&self, area: Rect) -> (Rect, Option<Rect>, Option<Rect>) {
+        let mut text = area;
+        if text.width == 0 || text.height == 0 {
+            return (text, None, None);
+        }
+        text.width -= 1;
+        let vertical = Rect {
+            x: text.x + text.width,
+            width: 1,
+            ..text
+        };
+        if !self.horizontal || text.height == 1 {
+            return (text, Some(vertical), None);
+        }
+        text.height -= 1;
+        let horizontal = Rect {
+            y: text.y + text.height,
+            height: 1,
+            ..text
+        };
+        let vertical = Rect {
+            height: text.height,
+            ..vertical
+        };
+        (text, Some(vertical), Some(horizontal))
+    }
+
+    /// Render the vertical scrollbar. `total` is the length of the content, `visible` is the length of its visible part,
+    /// and `offset` is the start of the visible part.
+    pub(crate) fn render_vertical(&self, area: Rect, buf: &mut Buffer, total: usize, visible: usize, offset: usize) {
+        let thumb = thumb_range(area.height, total, visible, offset);
+        for (i, y) in (area.y..area.y + area.height).enumerate() {
+            let (symbol, style) = if thumb.contains(&(i as u16)) {
+                (self.thumb, self.thumb_style)
+            } else {
+                (self.track, self.track_style)
+            };
+            buf.get_mut(area.x, y).set_char(symbol).set_style(style);
+        }
+    }
+
+    /// Render the horizontal scrollbar. Parameters are the same as [`ScrollbarConfig::render_vertical`].
+    pub(crate) fn render_horizontal(&self, area: Rect, buf: &mut Buffer, total: usize, visible: usize, offset: usize) {
+        let thumb = thumb_range(area.width, total, visible, offset);
+        for (i, x) in (area.x..area.x + area.width).enumerate() {
+            let (symbol, style) = if thumb.contains(&(i as u16)) {
+                (self.thumb, self.thumb_style)
+            } else {
+                (self.horizontal_track, self.track_style)
+            };
+            buf.get_mut(x, area.y).set_char(symbol).set_style(style);
+        }
+    }
+}
+
+// Cells of the thumb in the track of `len` cells. The thumb is at least one cell and reaches the end of the track only
+// when the end of the content is visible.
+fn thumb_range(len: u16, total: usize, visible: usize, offset: usize) -> Range<u16> {
+    let len = len as usize;
+    if len == 0 || total <= visible {
+        return 0..len as u16;
+    }
+    let size = (len * visible / total).clamp(1, len);
+    let max_offset = total - visible;
+    let start = if offset >= max_offset {
+        len - size
+    } else {
+        // Round up so that the thumb leaves the start of the track as soon as the viewport scrolls
+        let start = ((len - size) * offset + max_offset - 1) / max_offset;
+        cmp::min(start, (len - size).saturating_sub(1))
+    };
+    start as u16..(start + size) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumb() {
+        let tests = [
+            // (len, total, visible, offset, expected)
+            (10, 5, 10, 0, 0..10),
+            (10, 20, 10, 0, 0..5),
+            (10, 20, 10, 1, 1..6),
+            (10, 20, 10, 9, 4..9),
+            (10, 20, 10, 10, 5..10),
+            (10, 20, 10, 15, 5..10),
+            (10, 1000, 10, 0, 0..1),
+            (10, 1000, 10, 1, 1..2),
+            (10, 1000, 10, 989, 8..9),
+            (10, 1000, 10, 990, 9..10),
+            (0, 20, 10, 0, 0..0),
+            // The thumb fills a track of one cell
+            (1, 20, 1, 0, 0..1),
+            (1, 20, 1, 5, 0..1),
+            (1, 20, 1, 19, 0..1),
+            (2, 20, 1, 0, 0..1),
+            (2, 20, 1, 1, 0..1),
+            (2, 20, 1, 18, 0..1),
+            (2, 20, 1, 19, 1..2),
+        ];
+        for (len, total, visible, offset, expected) in tests {
+            assert_eq!(
+                thumb_range(len, total, visible, offset),
+                expected,
+                "{len} {total} {visible} {offset}"
+            );
+        }
+    }
+
+    #[test]
+    fn split() {
+        let area = Rect::new(1, 2, 10, 5);
+        let config = ScrollbarConfig::default();
+        assert_eq!(
+            config.split(area),
+            (Rect::new(1, 2, 9, 5), Some(Rect::new(10, 2, 1, 5)), None),
+        );
+        let config = ScrollbarConfig {
+            horizontal: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.split(area),
+            (
+                Rect::new(1, 2, 9, 4),
+                Some(Rect::new(10, 2, 1, 4)),
+                Some(Rect::new(1, 6, 9, 1)),
+            ),
+        );
+        assert_eq!(config.split(Rect::new(0, 0, 0, 5)), (Rect::new(0, 0, 0, 5), None, None));
+    }
+}
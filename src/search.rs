@@ -1,10 +1,169 @@
+use crate::fuzzy::{Fuzzy, FuzzyMatch};
 use crate::ratatui::style::{Color, Style};
-use regex::Regex;
+use crate::storage::Lines;
+use crate::util::MatchPos;
+use regex::{Captures, Regex, RegexBuilder};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use regex_syntax::Parser;
+use std::borrow::Cow;
+use std::cmp;
+use std::ops::Range;
+
+/// Check if the pattern can match across line boundaries. It is true when the pattern explicitly matches a newline
+/// with `\n` (also in a class like `[\n ]`) or matches any character with `.` under the `s` flag. Classes which only
+/// happen to contain a newline such as `\s`, `\W` or `[^a]` don't make the pattern multi-line since they are matched
+/// within each line. An invalid pattern is not multi-line since it is rejected on building the regular expression.
+fn is_multiline(query: &str) -> bool {
+    Parser::new().parse(query).map_or(false, |hir| matches_newline(&hir))
+}
+
+fn matches_newline(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => false,
+        HirKind::Literal(lit) => lit.0.contains(&b'\n'),
+        HirKind::Class(Class::Unicode(class)) => {
+            let ranges = class.ranges();
+            let any = ranges.len() == 1 && ranges[0].start() == '\0' && ranges[0].end() == char::MAX;
+            any || ranges.iter().any(|r| r.start() == '\n' && r.end() == '\n')
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let ranges = class.ranges();
+            let any = ranges.len() == 1 && ranges[0].start() == 0 && ranges[0].end() == u8::MAX;
+            any || ranges.iter().any(|r| r.start() == b'\n' && r.end() == b'\n')
+        }
+        HirKind::Repetition(rep) => rep.max != Some(0) && matches_newline(&rep.sub),
+        HirKind::Capture(cap) => matches_newline(&cap.sub),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().any(matches_newline),
+    }
+}
+
+/// Check if the pattern ends with `\b` which is not escaped.
+fn ends_with_word_boundary(query: &str) -> bool {
+    let Some(rest) = query.strip_suffix("\\b") else {
+        return false;
+    };
+    let backslashes = rest.len() - rest.trim_end_matches('\\').len();
+    backslashes % 2 == 0
+}
+
+/// Check if the pattern contains an uppercase letter. Characters escaped with `\` such as `\W` or `\S` are not
+/// letters to match so they are ignored.
+fn has_uppercase(query: &str) -> bool {
+    let mut chars = query.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Options of text search applied on top of the pattern set by
+/// [`TextArea::set_search_pattern`](crate::TextArea::set_search_pattern). This is set by
+/// [`TextArea::set_search_options`](crate::TextArea::set_search_options).
+/// ```
+/// use tui_textarea::SearchOptions;
+///
+/// let options = SearchOptions {
+///     smart_case: true,
+///     whole_word: true,
+///     ..Default::default()
+/// };
+/// assert!(!options.case_insensitive);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SearchOptions {
+    /// Match letters case-insensitively.
+    pub case_insensitive: bool,
+    /// Match letters case-insensitively unless the pattern contains an uppercase letter. This has no effect when
+    /// `case_insensitive` is `true`.
+    pub smart_case: bool,
+    /// Match only whole words. The pattern must be surrounded by word boundaries (`\b`) to match.
+    pub whole_word: bool,
+}
+
+/// How the query set by [`TextArea::set_search_pattern`](crate::TextArea::set_search_pattern) is matched. This is set
+/// by [`TextArea::set_search_mode`](crate::TextArea::set_search_mode).
+/// ```
+/// use tui_textarea::{SearchMode, TextArea};
+///
+/// let mut textarea = TextArea::from(["version 1.5 (beta)"]);
+/// textarea.set_search_mode(SearchMode::Literal);
+///
+/// // No escape is needed in literal mode
+/// textarea.set_search_pattern("1.5 (beta)").unwrap();
+/// assert_eq!(textarea.search_matches(), [((0, 8), (0, 18))]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SearchMode {
+    /// The query is a regular expression.
+    #[default]
+    Regex,
+    /// The query is a plain substring. Characters with special meanings in regular expressions don't need escaping.
+    Literal,
+    /// The query matches lines containing all its characters in the same order, like fzf. Each line has at most one
+    /// match, and only the matched characters are highlighted. Matches are ranked by scores which prefer characters
+    /// at word boundaries or in a row.
+    Fuzzy,
+}
+
+/// Range of the text where [`TextArea::set_search_pattern`](crate::TextArea::set_search_pattern) finds matches. This
+/// is set by [`TextArea::set_search_scope`](crate::TextArea::set_search_scope).
+/// ```
+/// use tui_textarea::{CursorMove, SearchScope, TextArea};
+///
+/// let mut textarea = TextArea::from(["foo", "foo", "foo"]);
+/// textarea.start_selection();
+/// textarea.move_cursor(CursorMove::Down);
+/// textarea.set_search_scope(SearchScope::Selection);
+/// textarea.cancel_selection();
+///
+/// // The match in the last line is out of the scope
+/// textarea.set_search_pattern("foo").unwrap();
+/// assert_eq!(textarea.search_match_count(None), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SearchScope {
+    /// Matches are searched in the entire text.
+    #[default]
+    Document,
+    /// Matches are searched only in the text which was selected when the scope was set.
+    Selection,
+}
+
+impl SearchOptions {
+    fn build(&self, query: &str, multiline: bool) -> Result<Regex, regex::Error> {
+        let case_insensitive = self.case_insensitive || self.smart_case && !has_uppercase(query);
+        let mut builder = if self.whole_word {
+            // Avoid doubling the word boundaries which the pattern already has
+            let start = if query.starts_with("\\b") { "" } else { "\\b" };
+            let end = if ends_with_word_boundary(query) { "" } else { "\\b" };
+            RegexBuilder::new(&format!("{start}(?:{query}){end}"))
+        } else {
+            RegexBuilder::new(query)
+        };
+        builder.multi_line(multiline).case_insensitive(case_insensitive).build()
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Search {
     pub pat: Option<Regex>,
     pub style: Style,
+    pub current_style: Option<Style>,
+    pub current: Option<MatchPos>, // Match which the last search moved the cursor to
+    query: String,
+    options: SearchOptions,
+    multiline: bool,
+    mode: SearchMode,
+    fuzzy: Option<Fuzzy>, // Query of the fuzzy search mode
+    history: Vec<String>, // Newest pattern comes first
+    max_history: usize,
+    history_index: Option<usize>, // Index of the pattern recalled from the history
+    set_by_user: bool,            // The current pattern was set by `set_pattern` rather than recalled from the history
+    pub scope: Option<((usize, usize), (usize, usize))>, // `(row, col)` range where matches are searched
 }
 
 impl Default for Search {
@@ -12,34 +171,512 @@ impl Default for Search {
         Self {
             pat: None,
             style: Style::default().bg(Color::Blue),
+            current_style: None,
+            current: None,
+            query: String::new(),
+            options: SearchOptions::default(),
+            multiline: false,
+            mode: SearchMode::default(),
+            fuzzy: None,
+            history: vec![],
+            max_history: 50,
+            history_index: None,
+            set_by_user: false,
+            scope: None,
         }
     }
 }
 
 impl Search {
-    pub fn matches<'a>(&'a self, line: &'a str) -> Option<impl Iterator<Item = (usize, usize)> + 'a> {
+    /// Byte ranges of the matches in the line at the row. In the fuzzy search mode, they are the ranges of the matched
+    /// characters.
+    pub fn matches(&self, row: usize, line: &str) -> Option<Vec<(usize, usize)>> {
+        if self.fuzzy.is_some() {
+            return Some(self.fuzzy_find(row, line).map(|m| m.ranges(line)).unwrap_or_default());
+        }
         let pat = self.pat.as_ref()?;
         let matches = pat.find_iter(line).map(|m| (m.start(), m.end()));
-        Some(matches)
+        Some(matches.filter(|&(s, e)| self.in_row_scope(row, line, s, e)).collect())
     }
 
+    // Byte range of the line at the row in the scope. `None` is returned when the row is out of the scope
+    fn scope_in_row(&self, row: usize, line: &str) -> Option<(usize, usize)> {
+        let Some(((start_row, start_col), (end_row, end_col))) = self.scope else {
+            return Some((0, line.len()));
+        };
+        if row < start_row || end_row < row {
+            return None;
+        }
+        let offset = |col| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+        let start = if row == start_row { offset(start_col) } else { 0 };
+        let end = if row == end_row { offset(end_col) } else { line.len() };
+        Some((start, end))
+    }
+
+    /// Return if the byte range in the line at the row is in the scope.
+    pub fn in_row_scope(&self, row: usize, line: &str, start: usize, end: usize) -> bool {
+        self.scope_in_row(row, line)
+            .map_or(false, |(s, e)| s <= start && end <= e)
+    }
+
+    fn in_scope(&self, lines: &Lines, ((start_row, start), (end_row, end)): MatchPos) -> bool {
+        if self.scope.is_none() {
+            return true;
+        }
+        let in_row = |row: usize, offset| self.in_row_scope(row, &lines.line(row), offset, offset);
+        in_row(start_row, start) && in_row(end_row, end)
+    }
+
+    // Fuzzy match in the part of the line in the scope
+    fn fuzzy_find(&self, row: usize, line: &str) -> Option<FuzzyMatch> {
+        let fuzzy = self.fuzzy.as_ref()?;
+        let (start, end) = self.scope_in_row(row, line)?;
+        let mut m = fuzzy.find(&line[start..end])?;
+        for offset in &mut m.offsets {
+            *offset += start;
+        }
+        Some(m)
+    }
+
+    /// Build the regular expression of the query in the current mode. In the fuzzy search mode, it matches the query
+    /// characters with any characters between them so that methods using the regular expression, such as replacing
+    /// matches, still work.
+    fn build(&self, query: &str) -> Result<(Regex, bool), regex::Error> {
+        let (source, multiline, options) = match self.mode {
+            SearchMode::Regex => (Cow::Borrowed(query), is_multiline(query), self.options),
+            SearchMode::Literal => (Cow::Owned(regex::escape(query)), query.contains('\n'), self.options),
+            SearchMode::Fuzzy => {
+                let mut source = String::new();
+                for (i, c) in query.chars().enumerate() {
+                    if i > 0 {
+                        source.push_str(".*?");
+                    }
+                    source.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                }
+                let options = SearchOptions {
+                    whole_word: false,
+                    ..self.options
+                };
+                (Cow::Owned(source), false, options)
+            }
+        };
+        Ok((options.build(&source, multiline)?, multiline))
+    }
+
+    fn build_fuzzy(&self, query: &str) -> Option<Fuzzy> {
+        if self.mode != SearchMode::Fuzzy {
+            return None;
+        }
+        let options = self.options;
+        let ignore_case = options.case_insensitive || options.smart_case && !query.chars().any(char::is_uppercase);
+        Some(Fuzzy::new(query, ignore_case))
+    }
+
+    /// Set the pattern and record it in the history. An empty pattern is not recorded. While the pattern is typed
+    /// incrementally, a pattern extending the newest entry replaces it instead of adding a new entry.
     pub fn set_pattern(&mut self, query: &str) -> Result<(), regex::Error> {
+        self.apply_pattern(query)?;
+        let typing = self.set_by_user && self.history_index.is_none();
+        self.history_index = None;
+        self.set_by_user = !query.is_empty();
+        if self.set_by_user {
+            self.record(query, typing);
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, query: &str, typing: bool) {
+        // Consecutive duplicates are collapsed
+        if self.max_history == 0 || self.history.first().map_or(false, |q| q == query) {
+            return;
+        }
+        if let Some(newest) = self
+            .history
+            .first_mut()
+            .filter(|q| typing && query.starts_with(q.as_str()))
+        {
+            *newest = query.to_string();
+            return;
+        }
+        self.history.insert(0, query.to_string());
+        self.history.truncate(self.max_history);
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn max_history(&self) -> usize {
+        self.max_history
+    }
+
+    pub fn set_max_history(&mut self, max: usize) {
+        self.max_history = max;
+        self.history.truncate(max);
+        self.history_index = None;
+    }
+
+    /// Apply the older pattern in the history than the recalled one. The pattern of the ongoing search is skipped.
+    pub fn history_prev(&mut self) -> Option<&str> {
+        let i = match self.history_index {
+            Some(i) => i + 1,
+            None if self.set_by_user && self.history.first() == Some(&self.query) => 1,
+            None => 0,
+        };
+        self.recall(i)
+    }
+
+    /// Apply the newer pattern in the history than the recalled one. `None` is returned after the newest pattern.
+    pub fn history_next(&mut self) -> Option<&str> {
+        let Some(i) = self.history_index.and_then(|i| i.checked_sub(1)) else {
+            self.history_index = None;
+            return None;
+        };
+        self.recall(i)
+    }
+
+    fn recall(&mut self, i: usize) -> Option<&str> {
+        let query = self.history.get(i)?.clone();
+        // The pattern in the history was valid when it was recorded
+        self.apply_pattern(&query).ok()?;
+        self.history_index = Some(i);
+        self.set_by_user = false;
+        Some(&self.history[i])
+    }
+
+    fn apply_pattern(&mut self, query: &str) -> Result<(), regex::Error> {
         match &self.pat {
-            Some(r) if r.as_str() == query => {}
-            _ if query.is_empty() => self.pat = None,
-            _ => self.pat = Some(Regex::new(query)?),
+            Some(_) if self.query == query => {}
+            _ if query.is_empty() => self.stop(),
+            _ => {
+                // Multi-line patterns are matched against the entire text. `^` and `$` still match at start and end of
+                // each line
+                let (pat, multiline) = self.build(query)?;
+                self.pat = Some(pat);
+                self.fuzzy = self.build_fuzzy(query);
+                self.current = None;
+                self.query = query.to_string();
+                self.multiline = multiline;
+            }
         }
         Ok(())
     }
 
-    pub fn forward(&mut self, lines: &[String], cursor: (usize, usize), match_cursor: bool) -> Option<(usize, usize)> {
+    fn stop(&mut self) {
+        self.pat = None;
+        self.fuzzy = None;
+        self.current = None;
+        self.query.clear();
+        self.multiline = false;
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Set the search options and rebuild the current pattern with them.
+    pub fn set_options(&mut self, options: SearchOptions) {
+        if self.options == options {
+            return;
+        }
+        self.options = options;
+        self.current = None;
+        if self.pat.is_some() {
+            // The pattern was already built successfully. Applying the options does not make it invalid
+            if let Ok((pat, _)) = self.build(&self.query) {
+                self.pat = Some(pat);
+                self.fuzzy = self.build_fuzzy(&self.query);
+            }
+        }
+    }
+
+    pub fn mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    /// Set the search mode and rebuild the current pattern in the mode. The search is stopped when the query is not a
+    /// valid pattern in the mode.
+    pub fn set_mode(&mut self, mode: SearchMode) {
+        if self.mode == mode {
+            return;
+        }
+        self.mode = mode;
+        self.current = None;
+        if self.pat.is_none() {
+            return;
+        }
+        match self.build(&self.query) {
+            Ok((pat, multiline)) => {
+                self.pat = Some(pat);
+                self.fuzzy = self.build_fuzzy(&self.query);
+                self.multiline = multiline;
+            }
+            Err(_) => self.stop(),
+        }
+    }
+
+    /// Fuzzy matches of the lines sorted by their scores in descending order. Lines with the same score are sorted by
+    /// their rows. `None` is returned when the search mode is not fuzzy.
+    pub fn ranked(&self, lines: &Lines) -> Option<Vec<(usize, FuzzyMatch)>> {
+        self.fuzzy.as_ref()?;
+        let mut ranked: Vec<_> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(row, line)| Some((row, self.fuzzy_find(row, &line)?)))
+            .collect();
+        ranked.sort_by(|(r1, m1), (r2, m2)| m2.score.cmp(&m1.score).then(r1.cmp(r2)));
+        Some(ranked)
+    }
+
+    // Visit the lines in the order of their scores. The line at the cursor is visited first when `match_cursor` is
+    // `true` and it matches
+    fn visit_ranked(
+        &self,
+        lines: &Lines,
+        cursor: (usize, usize),
+        match_cursor: bool,
+        back: bool,
+    ) -> Option<(usize, usize)> {
+        let ranked = self.ranked(lines)?;
+        let len = ranked.len();
+        if len == 0 {
+            return None;
+        }
+        let i = match ranked.iter().position(|&(row, _)| row == cursor.0) {
+            Some(i) if match_cursor => i,
+            Some(i) if back => (i + len - 1) % len,
+            Some(i) => (i + 1) % len,
+            None if back => len - 1,
+            None => 0,
+        };
+        let (row, m) = &ranked[i];
+        Some((*row, lines.col_of_offset(*row, m.offsets[0])))
+    }
+
+    /// Byte range from the first matched character to the last one in the line at the row in the fuzzy search mode.
+    pub fn fuzzy_span(&self, row: usize, line: &str) -> Option<(usize, usize)> {
+        let m = self.fuzzy_find(row, line)?;
+        Some(m.span(line))
+    }
+
+    /// Return if a pattern is set and its matches are highlighted.
+    pub fn is_active(&self) -> bool {
+        self.pat.is_some()
+    }
+
+    /// Return if the current pattern is matched against the entire text rather than each line.
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    /// Find all matches in the text. Multi-line patterns are matched against lines joined with `\n`. Matches are
+    /// sorted by their start positions.
+    pub fn find_all(&self, lines: &Lines) -> Vec<MatchPos> {
+        let rows = match self.scope {
+            Some(((start, _), (end, _))) => cmp::min(start, lines.len())..cmp::min(end + 1, lines.len()),
+            None => 0..lines.len(),
+        };
+        self.find_in_rows(lines, rows)
+    }
+
+    /// Index of the match in the non-empty matches of the text. Only the rows until the match are searched so that the
+    /// rest of a large text is not scanned.
+    pub fn index_of(&self, lines: &Lines, m: MatchPos) -> Option<usize> {
+        let (first, last) = match self.scope {
+            Some(((start, _), (end, _))) => (start, cmp::min(end + 1, lines.len())),
+            None => (0, lines.len()),
+        };
+        let rows = first..cmp::min((m.1).0 + 1, last);
+        self.find_in_rows(lines, rows)
+            .into_iter()
+            .filter(|(start, end)| start != end)
+            .position(|found| found == m)
+    }
+
+    /// Find matches only in the rows. Multi-line patterns are matched against the rows joined with `\n` so that
+    /// matching does not cost more than the rows even on a large text. Matches are sorted by their start positions.
+    pub fn find_in_rows(&self, lines: &Lines, rows: Range<usize>) -> Vec<MatchPos> {
+        let Some(pat) = &self.pat else {
+            return vec![];
+        };
+        let first = rows.start;
+
+        if self.fuzzy.is_some() {
+            let matches = rows.filter_map(|row| {
+                let (start, end) = self.fuzzy_span(row, &lines.line(row))?;
+                Some(((row, start), (row, end)))
+            });
+            return matches.collect();
+        }
+
+        if !self.multiline {
+            let mut matches = vec![];
+            for row in rows {
+                let line = lines.line(row);
+                let found = pat.find_iter(&line).map(|m| ((row, m.start()), (row, m.end())));
+                matches.extend(found.filter(|&((_, s), (_, e))| self.in_row_scope(row, &line, s, e)));
+            }
+            return matches;
+        }
+
+        let joined = Joined::new(rows.map(|row| lines.line(row)));
+        pat.find_iter(&joined.text)
+            .map(|m| {
+                let ((sr, so), (er, eo)) = (joined.pos(m.start()), joined.pos(m.end()));
+                ((first + sr, so), (first + er, eo))
+            })
+            .filter(|&m| self.in_scope(lines, m))
+            .collect()
+    }
+
+    /// Find the match starting at the cursor position.
+    pub fn match_at(&self, lines: &Lines, cursor: (usize, usize)) -> Option<MatchPos> {
+        let pat = self.pat.as_ref()?;
+        if self.fuzzy.is_some() {
+            let (row, col) = cursor;
+            let line = lines.line(row);
+            let (start, end) = self
+                .fuzzy_span(row, &line)
+                .filter(|&(start, _)| to_col(&line, start) == col)?;
+            return Some(((row, start), (row, end)));
+        }
+        if self.multiline {
+            let joined = Joined::new(lines.iter());
+            let offset = joined.offset(lines, cursor);
+            let m = pat.find_at(&joined.text, offset).filter(|m| m.start() == offset)?;
+            let m = (joined.pos(m.start()), joined.pos(m.end()));
+            return self.in_scope(lines, m).then_some(m);
+        }
+        let (row, col) = cursor;
+        let line = lines.line(row);
+        let offset = lines.offset_of_col(row, col);
+        let m = pat
+            .find_at(&line, offset)
+            .filter(|m| m.start() == offset && self.in_row_scope(row, &line, m.start(), m.end()))?;
+        Some(((row, m.start()), (row, m.end())))
+    }
+
+    /// Count non-empty matches in the text. Matching stops when the count reaches `limit`.
+    pub fn count(&self, lines: &Lines, limit: Option<usize>) -> usize {
+        let Some(pat) = &self.pat else {
+            return 0;
+        };
+        let limit = limit.unwrap_or(usize::MAX);
+        if self.fuzzy.is_some() {
+            // Each line has at most one fuzzy match
+            return lines
+                .iter()
+                .enumerate()
+                .filter(|(row, line)| self.fuzzy_find(*row, line).is_some())
+                .take(limit)
+                .count();
+        }
+        if self.scope.is_some() {
+            let matches = self.find_all(lines).into_iter();
+            return matches.filter(|(start, end)| start != end).take(limit).count();
+        }
+        if self.multiline {
+            let text = lines.join("\n");
+            pat.find_iter(&text).filter(|m| !m.is_empty()).take(limit).count()
+        } else {
+            let mut count = 0;
+            for line in lines.iter() {
+                count += pat
+                    .find_iter(&line)
+                    .filter(|m| !m.is_empty())
+                    .take(limit - count)
+                    .count();
+                if count == limit {
+                    break;
+                }
+            }
+            count
+        }
+    }
+
+    // Visit the matches in the scope in the order of their positions
+    fn visit_scoped(
+        &self,
+        lines: &Lines,
+        cursor: (usize, usize),
+        match_cursor: bool,
+        back: bool,
+    ) -> Option<(usize, usize)> {
+        let (row, col) = cursor;
+        let cursor = (row, lines.offset_of_col(row, col));
+        let matches = self.find_all(lines);
+        let mut starts = matches.iter().map(|&(start, _)| start);
+        let found = if back {
+            let before = starts.rfind(|&s| s < cursor || match_cursor && s == cursor);
+            before.or_else(|| matches.last().map(|&(start, _)| start)) // Wrap around
+        } else {
+            let after = starts.find(|&s| s > cursor || match_cursor && s == cursor);
+            after.or_else(|| matches.first().map(|&(start, _)| start)) // Wrap around
+        };
+        let (row, offset) = found?;
+        Some((row, lines.col_of_offset(row, offset)))
+    }
+
+    fn forward_multiline(&self, lines: &Lines, cursor: (usize, usize), match_cursor: bool) -> Option<(usize, usize)> {
+        let pat = self.pat.as_ref()?;
+        let joined = Joined::new(lines.iter());
+        let offset = joined.offset(lines, cursor);
+        // Skip the character at the cursor. At the end of line, it is the newline
+        let start = if match_cursor {
+            offset
+        } else {
+            joined.text[offset..]
+                .chars()
+                .next()
+                .map_or(offset + 1, |c| offset + c.len_utf8())
+        };
+        let m = (start <= joined.text.len())
+            .then(|| pat.find_at(&joined.text, start))
+            .flatten()
+            .or_else(|| pat.find(&joined.text))?; // Wrap around
+        let (row, offset) = joined.pos(m.start());
+        Some((row, lines.col_of_offset(row, offset)))
+    }
+
+    fn back_multiline(&self, lines: &Lines, cursor: (usize, usize), match_cursor: bool) -> Option<(usize, usize)> {
+        let pat = self.pat.as_ref()?;
+        let joined = Joined::new(lines.iter());
+        let offset = joined.offset(lines, cursor);
+        let mut found = None;
+        let mut last = None;
+        for m in pat.find_iter(&joined.text) {
+            if m.start() < offset || match_cursor && m.start() == offset {
+                found = Some(m.start());
+            } else if found.is_some() {
+                break; // Matches after the cursor are not needed
+            }
+            last = Some(m.start());
+        }
+        let (row, offset) = joined.pos(found.or(last)?); // Wrap around
+        Some((row, lines.col_of_offset(row, offset)))
+    }
+
+    pub fn forward(&mut self, lines: &Lines, cursor: (usize, usize), match_cursor: bool) -> Option<(usize, usize)> {
+        if self.fuzzy.is_some() {
+            return self.visit_ranked(lines, cursor, match_cursor, false);
+        }
+        if self.scope.is_some() {
+            return self.visit_scoped(lines, cursor, match_cursor, false);
+        }
+        if self.multiline {
+            return self.forward_multiline(lines, cursor, match_cursor);
+        }
         let pat = if let Some(pat) = &self.pat {
             pat
         } else {
             return None;
         };
         let (row, col) = cursor;
-        let current_line = &lines[row];
+        let current_line = &lines.line(row);
 
         // Search current line after cursor
         let start_col = if match_cursor { col } else { col + 1 };
@@ -51,16 +688,16 @@ impl Search {
         }
 
         // Search lines after cursor
-        for (i, line) in lines[row + 1..].iter().enumerate() {
-            if let Some(m) = pat.find(line) {
+        for (i, line) in lines.iter().enumerate().skip(row + 1) {
+            if let Some(m) = pat.find(&line) {
                 let col = line[..m.start()].chars().count();
-                return Some((row + 1 + i, col));
+                return Some((i, col));
             }
         }
 
         // Search lines before cursor (wrap)
-        for (i, line) in lines[..row].iter().enumerate() {
-            if let Some(m) = pat.find(line) {
+        for (i, line) in lines.iter().enumerate().take(row) {
+            if let Some(m) = pat.find(&line) {
                 let col = line[..m.start()].chars().count();
                 return Some((i, col));
             }
@@ -83,14 +720,23 @@ impl Search {
         None
     }
 
-    pub fn back(&mut self, lines: &[String], cursor: (usize, usize), match_cursor: bool) -> Option<(usize, usize)> {
+    pub fn back(&mut self, lines: &Lines, cursor: (usize, usize), match_cursor: bool) -> Option<(usize, usize)> {
+        if self.fuzzy.is_some() {
+            return self.visit_ranked(lines, cursor, match_cursor, true);
+        }
+        if self.scope.is_some() {
+            return self.visit_scoped(lines, cursor, match_cursor, true);
+        }
+        if self.multiline {
+            return self.back_multiline(lines, cursor, match_cursor);
+        }
         let pat = if let Some(pat) = &self.pat {
             pat
         } else {
             return None;
         };
         let (row, col) = cursor;
-        let current_line = &lines[row];
+        let current_line = &lines.line(row);
 
         // Search current line before cursor
         if col > 0 || match_cursor {
@@ -104,18 +750,18 @@ impl Search {
         }
 
         // Search lines before cursor
-        for (i, line) in lines[..row].iter().enumerate().rev() {
-            if let Some(m) = pat.find_iter(line).last() {
+        for (i, line) in lines.iter().enumerate().take(row).rev() {
+            if let Some(m) = pat.find_iter(&line).last() {
                 let col = line[..m.start()].chars().count();
                 return Some((i, col));
             }
         }
 
         // Search lines after cursor (wrap)
-        for (i, line) in lines[row + 1..].iter().enumerate().rev() {
-            if let Some(m) = pat.find_iter(line).last() {
+        for (i, line) in lines.iter().enumerate().skip(row + 1).rev() {
+            if let Some(m) = pat.find_iter(&line).last() {
                 let col = line[..m.start()].chars().count();
-                return Some((row + 1 + i, col));
+                return Some((i, col));
             }
         }
 
@@ -129,4 +775,447 @@ impl Search {
 
         None
     }
+
+    /// Find the first non-empty match at or after the cursor position. When `containing` is `true`, a match containing
+    /// the cursor is also found. The search wraps around the text buffer. Multi-line patterns are matched against lines
+    /// joined with `\n`.
+    pub fn find_non_empty(&self, lines: &Lines, cursor: (usize, usize), containing: bool) -> Option<MatchPos> {
+        let pat = self.pat.as_ref()?;
+
+        if self.multiline {
+            let joined = Joined::new(lines.iter());
+            let offset = joined.offset(lines, cursor);
+            let mut first = None;
+            for m in pat.find_iter(&joined.text).filter(|m| !m.is_empty()) {
+                let pos = (joined.pos(m.start()), joined.pos(m.end()));
+                if !self.in_scope(lines, pos) {
+                    continue;
+                }
+                if m.start() >= offset || containing && m.end() > offset {
+                    return Some(pos);
+                }
+                first = first.or(Some(pos));
+            }
+            return first; // Wrap around
+        }
+
+        let (row, col) = cursor;
+        let offset = lines.offset_of_col(row, col);
+
+        // Search the current line after cursor, lines after the cursor, lines before the cursor (wrap), and finally
+        // the current line before cursor
+        let rows = (row..lines.len()).chain(0..=row);
+        for (i, r) in rows.enumerate() {
+            let line = lines.line(r);
+            let (first, last) = (i == 0, i == lines.len());
+            let found = pat.find_iter(&line).find(|m| {
+                !m.is_empty()
+                    && (!first || m.start() >= offset || containing && m.end() > offset)
+                    && (!last || m.start() < offset)
+                    && self.in_row_scope(r, &line, m.start(), m.end())
+            });
+            if let Some(m) = found {
+                return Some(((r, m.start()), (r, m.end())));
+            }
+        }
+        None
+    }
+
+    /// Expand `$1` or `${name}` in the replacement text with capture groups of the match starting at the row and the
+    /// byte offset.
+    pub fn expand(&self, lines: &Lines, (row, start): (usize, usize), replacement: &str) -> String {
+        let mut dst = String::new();
+        let Some(pat) = &self.pat else {
+            return dst;
+        };
+        let found = |text: &str, start: usize, dst: &mut String| {
+            if let Some(caps) = pat.captures_iter(text).find(|c| c.get(0).unwrap().start() == start) {
+                caps.expand(replacement, dst);
+            }
+        };
+        if self.multiline {
+            let joined = Joined::new(lines.iter());
+            found(&joined.text, joined.starts[row] + start, &mut dst);
+        } else {
+            found(&lines.line(row), start, &mut dst);
+        }
+        dst
+    }
+
+    /// Find all non-empty matches in the scope with the replacement text expanded with their capture groups.
+    /// Multi-line patterns are matched against lines joined with `\n`. Matches are sorted by their start positions.
+    pub fn replacements(&self, lines: &Lines, replacement: &str) -> Vec<(MatchPos, String)> {
+        let Some(pat) = &self.pat else {
+            return vec![];
+        };
+        let expand = |caps: Captures<'_>| {
+            let mut dst = String::new();
+            caps.expand(replacement, &mut dst);
+            dst
+        };
+
+        if self.multiline {
+            let joined = Joined::new(lines.iter());
+            return pat
+                .captures_iter(&joined.text)
+                .filter_map(|caps| {
+                    let m = caps.get(0).unwrap();
+                    let pos = (joined.pos(m.start()), joined.pos(m.end()));
+                    (!m.is_empty() && self.in_scope(lines, pos)).then(|| (pos, expand(caps)))
+                })
+                .collect();
+        }
+
+        let mut found = vec![];
+        for (row, line) in lines.iter().enumerate() {
+            for caps in pat.captures_iter(&line) {
+                let m = caps.get(0).unwrap();
+                if !m.is_empty() && self.in_row_scope(row, &line, m.start(), m.end()) {
+                    found.push((((row, m.start()), (row, m.end())), expand(caps)));
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Lines joined with `\n` to match multi-line patterns.
+struct Joined {
+    text: String,
+    starts: Vec<usize>, // Byte offset of each line in the text
+}
+
+impl Joined {
+    fn new<'a>(lines: impl Iterator<Item = Cow<'a, str>>) -> Self {
+        let mut text = String::new();
+        let mut starts = vec![];
+        for line in lines {
+            if !starts.is_empty() {
+                text.push('\n');
+            }
+            starts.push(text.len());
+            text.push_str(&line);
+        }
+        Self { text, starts }
+    }
+
+    /// Row and byte offset in the row of the byte offset in the text.
+    fn pos(&self, offset: usize) -> (usize, usize) {
+        let row = self.starts.partition_point(|&s| s <= offset) - 1;
+        (row, offset - self.starts[row])
+    }
+
+    /// Byte offset in the text of the row and column.
+    fn offset(&self, lines: &Lines, (row, col): (usize, usize)) -> usize {
+        self.starts[row] + lines.offset_of_col(row, col)
+    }
+}
+
+fn to_col(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count()
+}
+
+/// Byte ranges of the matches in the row. `matches` must be sorted. A match spanning multiple lines is split into
+/// ranges in each line.
+pub fn ranges_in_row<'a>(
+    matches: &'a [MatchPos],
+    row: usize,
+    line_len: usize,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let i = matches.partition_point(|&(_, (end_row, _))| end_row < row);
+    matches[i..]
+        .iter()
+        .take_while(move |&&((start_row, _), _)| start_row <= row)
+        .map(move |&((start_row, start), (end_row, end))| {
+            let start = if start_row == row { start } else { 0 };
+            let end = if end_row == row { end } else { line_len };
+            (start, end)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiline_pattern() {
+        let tests = [
+            ("foo", false),
+            ("foo\nbar", true),
+            ("foo\\nbar", true),
+            ("(?s)start.*end", true),
+            ("(?is)start", false),
+            ("(?s:.)", true),
+            ("(?i-s)foo", false),
+            ("(?i)s", false),
+            ("(?i-s).", false),
+            ("a\\x0Ab", true),
+            ("a\\sb", false),
+            ("\\W", false),
+            ("\\D+", false),
+            ("\"[^\"]*\"", false),
+            ("[\\n]", true),
+            ("[ \\n]", true),
+            ("[\\s\\S]", true),
+            ("[^a]", false),
+            ("\\Sx{0}\\n{0}", false),
+            ("foo$", false),
+            ("(", false),
+        ];
+        for (query, expected) in tests {
+            assert_eq!(is_multiline(query), expected, "{query:?}");
+        }
+    }
+
+    #[test]
+    fn build_with_options() {
+        let whole_word = SearchOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        let tests = [
+            (r"foo", whole_word, r"\b(?:foo)\b"),
+            (r"\bfoo", whole_word, r"(?:\bfoo)\b"),
+            (r"\bfoo\b", whole_word, r"(?:\bfoo\b)"),
+            (r"foo\\b", whole_word, r"\b(?:foo\\b)\b"),
+            (r"foo\\\b", whole_word, r"\b(?:foo\\\b)"),
+            (r"foo", SearchOptions::default(), r"foo"),
+        ];
+        for (query, options, expected) in tests {
+            assert_eq!(options.build(query, false).unwrap().as_str(), expected, "{query:?}");
+        }
+
+        let tests = [
+            (r"foo", false, false),
+            (r"Foo", false, false),
+            (r"foo", true, true),
+            (r"Foo", true, false),
+            (r"\Wfoo", true, true),
+        ];
+        for (query, smart_case, expected) in tests {
+            let options = SearchOptions {
+                smart_case,
+                ..Default::default()
+            };
+            let pat = options.build(query, false).unwrap();
+            assert_eq!(pat.is_match("FOO") || pat.is_match(" FOO"), expected, "{query:?}");
+        }
+    }
+
+    #[test]
+    fn find_all_across_lines() {
+        let lines: Vec<_> = ["foo", "bar", "", "foo bar", "baz"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let lines = Lines::from(lines);
+        let mut search = Search::default();
+
+        search.set_pattern("o\\nb").unwrap();
+        assert!(search.is_multiline());
+        assert_eq!(search.find_all(&lines), [((0, 2), (1, 1))]);
+
+        search.set_pattern("(?s)bar.*?baz").unwrap();
+        assert_eq!(search.find_all(&lines), [((1, 0), (4, 3))]);
+        let m = search.find_all(&lines);
+        let ranges: Vec<_> = (0..5)
+            .map(|row| ranges_in_row(&m, row, lines.line_len(row)).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(ranges, [vec![], vec![(0, 3)], vec![(0, 0)], vec![(0, 7)], vec![(0, 3)]]);
+
+        // `^` and `$` match at each line
+        search.set_pattern("^bar$\\n").unwrap();
+        assert_eq!(search.find_all(&lines), [((1, 0), (2, 0))]);
+
+        search.set_pattern("bar").unwrap();
+        assert!(!search.is_multiline());
+        assert_eq!(search.find_all(&lines), [((1, 0), (1, 3)), ((3, 4), (3, 7))]);
+    }
+
+    #[test]
+    fn find_in_rows() {
+        let lines: Vec<_> = ["foo", "bar", "foo", "bar", "foo"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let lines = Lines::from(lines);
+        let mut search = Search::default();
+
+        search.set_pattern("foo").unwrap();
+        assert_eq!(search.find_in_rows(&lines, 1..3), [((2, 0), (2, 3))]);
+        assert_eq!(search.find_in_rows(&lines, 1..2), []);
+
+        search.set_pattern("foo\\nbar").unwrap();
+        assert_eq!(search.find_in_rows(&lines, 1..4), [((2, 0), (3, 3))]);
+        // Matches across the boundary of the rows are not found
+        assert_eq!(search.find_in_rows(&lines, 0..1), []);
+        assert_eq!(search.find_in_rows(&lines, 3..5), []);
+    }
+
+    #[test]
+    fn count_matches() {
+        let lines: Vec<_> = ["foo foo", "", "foo"].into_iter().map(String::from).collect();
+        let lines = Lines::from(lines);
+        let mut search = Search::default();
+        assert_eq!(search.count(&lines, None), 0);
+
+        search.set_pattern("foo").unwrap();
+        assert_eq!(search.count(&lines, None), 3);
+        assert_eq!(search.count(&lines, Some(2)), 2);
+        assert_eq!(search.count(&lines, Some(0)), 0);
+
+        // Empty matches are not counted
+        search.set_pattern("x*").unwrap();
+        assert_eq!(search.count(&lines, None), 0);
+
+        search.set_pattern("foo\\n").unwrap();
+        assert_eq!(search.count(&lines, None), 1);
+        search.set_pattern("(?s).").unwrap();
+        assert_eq!(search.count(&lines, Some(5)), 5);
+        assert_eq!(search.count(&lines, None), 12);
+    }
+
+    #[test]
+    fn index_of_match() {
+        let lines: Vec<_> = ["foo foo", "", "foo", "x"].into_iter().map(String::from).collect();
+        let lines = Lines::from(lines);
+        let mut search = Search::default();
+        search.set_pattern("foo").unwrap();
+        for (i, m) in search.find_all(&lines).into_iter().enumerate() {
+            assert_eq!(search.index_of(&lines, m), Some(i), "{m:?}");
+        }
+        assert_eq!(search.index_of(&lines, ((0, 1), (0, 4))), None);
+        assert_eq!(search.index_of(&lines, ((9, 0), (9, 3))), None);
+
+        search.set_pattern("o\n\nf").unwrap();
+        assert_eq!(search.index_of(&lines, ((0, 6), (2, 1))), Some(0));
+
+        search.set_pattern("foo").unwrap();
+        search.scope = Some(((0, 3), (2, 3)));
+        assert_eq!(search.index_of(&lines, ((0, 0), (0, 3))), None);
+        assert_eq!(search.index_of(&lines, ((2, 0), (2, 3))), Some(1));
+    }
+
+    #[test]
+    fn matches_in_scope() {
+        let lines: Vec<_> = ["aあ aあ", "aあ aあ", "aあ"].into_iter().map(String::from).collect();
+        let lines = Lines::from(lines);
+        let mut search = Search::default();
+        search.set_pattern("aあ").unwrap();
+        search.scope = Some(((0, 1), (1, 2)));
+        assert_eq!(search.find_all(&lines), [((0, 5), (0, 9)), ((1, 0), (1, 4))]);
+        assert_eq!(search.matches(0, &lines.line(0)), Some(vec![(5, 9)]));
+        assert_eq!(search.matches(2, &lines.line(2)), Some(vec![]));
+        assert_eq!(search.match_at(&lines, (0, 0)), None);
+        assert_eq!(search.match_at(&lines, (1, 0)), Some(((1, 0), (1, 4))));
+        assert_eq!(search.find_non_empty(&lines, (1, 1), false), Some(((0, 5), (0, 9))));
+
+        // Matches partially in the scope are excluded
+        search.set_pattern("あ aあ\\naあ").unwrap();
+        assert_eq!(search.find_all(&lines), [((0, 1), (1, 4))]);
+        search.scope = Some(((0, 2), (1, 2)));
+        assert_eq!(search.find_all(&lines), []);
+        search.scope = Some(((0, 1), (1, 1)));
+        assert_eq!(search.find_all(&lines), []);
+    }
+
+    #[test]
+    fn record_history() {
+        let mut search = Search::default();
+        let set = |search: &mut Search, queries: &[&str]| {
+            for q in queries {
+                let _ = search.set_pattern(q);
+            }
+        };
+
+        // Each distinct pattern is recorded and empty patterns are not
+        set(&mut search, &["a", "", "ab"]);
+        assert_eq!(search.history(), ["ab", "a"]);
+        set(&mut search, &["", "x", ""]);
+        assert_eq!(search.history(), ["x", "ab", "a"]);
+
+        // Consecutive duplicates are collapsed
+        set(&mut search, &["x", "", "x"]);
+        assert_eq!(search.history(), ["x", "ab", "a"]);
+        set(&mut search, &["", "x", "", "xz", "", "x"]);
+        assert_eq!(search.history(), ["x", "xz", "x", "ab", "a"]);
+        search.set_max_history(3);
+        assert_eq!(search.history(), ["x", "xz", "x"]);
+
+        // Invalid pattern is not recorded
+        set(&mut search, &["", "(", "(a"]);
+        assert_eq!(search.history(), ["x", "xz", "x"]);
+        set(&mut search, &["(a)"]);
+        assert_eq!(search.history(), ["(a)", "x", "xz"]);
+
+        search.set_max_history(2);
+        assert_eq!(search.history(), ["(a)", "x"]);
+        set(&mut search, &["", "y", ""]);
+        assert_eq!(search.history(), ["y", "(a)"]);
+
+        search.set_max_history(0);
+        set(&mut search, &["z", ""]);
+        assert_eq!(search.history(), [""; 0]);
+    }
+
+    #[test]
+    fn record_typed_history() {
+        let mut search = Search::default();
+        let set = |search: &mut Search, queries: &[&str]| {
+            for q in queries {
+                let _ = search.set_pattern(q);
+            }
+        };
+
+        // Each keystroke of the typed pattern replaces the newest entry
+        set(&mut search, &["f", "fo", "foo", ""]);
+        assert_eq!(search.history(), ["foo"]);
+        set(&mut search, &["b", "ba", "bar"]);
+        assert_eq!(search.history(), ["bar", "foo"]);
+
+        // Deleting a character adds an entry since the pattern no longer extends the newest one
+        set(&mut search, &["ba", "baz"]);
+        assert_eq!(search.history(), ["baz", "bar", "foo"]);
+
+        // The recalled pattern is kept when it is edited
+        set(&mut search, &[""]);
+        assert_eq!(search.history_prev(), Some("baz"));
+        assert_eq!(search.history_prev(), Some("bar"));
+        set(&mut search, &["bar.", "bar.*"]);
+        assert_eq!(search.history(), ["bar.*", "baz", "bar", "foo"]);
+
+        // Invalid patterns in the middle of typing are skipped
+        set(&mut search, &["", "(", "(a", "(a)"]);
+        assert_eq!(search.history(), ["(a)", "bar.*", "baz", "bar", "foo"]);
+    }
+
+    #[test]
+    fn recall_history() {
+        let mut search = Search::default();
+        assert_eq!(search.history_prev(), None);
+        assert_eq!(search.history_next(), None);
+
+        for q in ["a", "b", "c"] {
+            search.set_pattern(q).unwrap();
+            search.set_pattern("").unwrap();
+        }
+        assert_eq!(search.history_prev(), Some("c"));
+        assert_eq!(search.history_prev(), Some("b"));
+        assert_eq!(search.query(), "b");
+        assert_eq!(search.history_next(), Some("c"));
+        assert_eq!(search.history_next(), None);
+        assert_eq!(search.query(), "c");
+        assert_eq!(search.history_prev(), Some("c"));
+
+        // Editing the recalled pattern records a new entry
+        search.set_pattern("cd").unwrap();
+        assert_eq!(search.history(), ["cd", "c", "b", "a"]);
+        // The pattern of the ongoing search is skipped
+        assert_eq!(search.history_prev(), Some("c"));
+        assert_eq!(search.history(), ["cd", "c", "b", "a"]);
+        assert_eq!(search.history_prev(), Some("b"));
+        assert_eq!(search.history_prev(), Some("a"));
+        assert_eq!(search.history_prev(), None);
+        assert_eq!(search.query(), "a");
+    }
 }
@@ -0,0 +1,67 @@
+use crate::ratatui::style::Style;
+use unicode_width::UnicodeWidthChar as _;
+
+/// Number of display cells of the sign column.
+pub(crate) const SIGN_WIDTH: usize = 2;
+
+/// A sign rendered in the sign column at the left of line numbers, such as a breakpoint, a diff marker, or an icon of a
+/// diagnostic. Set by [`crate::TextArea::set_sign`].
+///
+/// The sign column is two cells wide. A shorter symbol is padded with spaces and a longer symbol is truncated.
+/// ```
+/// use ratatui::style::{Color, Style};
+/// use tui_textarea::Sign;
+///
+/// let breakpoint = Sign::new("●", Style::default().fg(Color::Red));
+/// assert_eq!(breakpoint.symbol, "●");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sign {
+    /// Symbol of the sign. It should be one or two cells wide.
+    pub symbol: String,
+    /// Style of the sign.
+    pub style: Style,
+}
+
+impl Sign {
+    /// Create a new sign with the symbol and the style.
+    pub fn new(symbol: impl Into<String>, style: Style) -> Self {
+        Self { symbol: symbol.into(), style }
+    }
+
+    // The symbol fit in the sign column
+    pub(crate) fn cells(&self) -> String {
+        let mut cells = String::new();
+        let mut width = 0;
+        for c in self.symbol.chars() {
+            let w = c.width().unwrap_or(0);
+            if width + w > SIGN_WIDTH {
+                break;
+            }
+            cells.push(c);
+            width += w;
+        }
+        cells.extend(std::iter::repeat(' ').take(SIGN_WIDTH - width));
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells() {
+        let tests = [
+            ("", "  "),
+            ("●", "● "),
+            ("E!", "E!"),
+            ("abc", "ab"),
+            ("あ", "あ"),
+            ("aあ", "a "),
+        ];
+        for (symbol, want) in tests {
+            assert_eq!(Sign::new(symbol, Style::default()).cells(), want, "{symbol:?}");
+        }
+    }
+}
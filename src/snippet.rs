@@ -0,0 +1,233 @@
+use crate::history::TextEdit;
+
+type Range = ((usize, usize), (usize, usize));
+
+/// Text of a snippet expanded from the snippet syntax and its tab stops. Positions of tab stops are pairs of (row, col)
+/// relative to the start of the text where `col` is counted in characters.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Expanded {
+    pub text: String,
+    pub stops: Vec<Range>, // In the order of visiting. The last one is `$0`
+}
+
+/// Expand a snippet written in the subset of LSP snippet syntax: `$1` and `${1:default}` are tab stops and `$0` is the
+/// final cursor position. `\$`, `\}`, and `\\` escape the characters. `indent` is inserted after each newline and
+/// tabs at the start of each line after the first are replaced with `tab`.
+pub(crate) fn expand(snippet: &str, indent: &str, tab: &str) -> Expanded {
+    let mut text = String::new();
+    let mut pos = (0, 0);
+    let mut stops: Vec<(usize, Range)> = vec![];
+    let mut chars = snippet.chars().peekable();
+    let mut line_head = false;
+
+    fn push(text: &mut String, pos: &mut (usize, usize), s: &str) {
+        text.push_str(s);
+        pos.1 += s.chars().count();
+    }
+
+    while let Some(c) = chars.next() {
+        if line_head && c != '\t' {
+            line_head = false;
+        }
+        match c {
+            '\\' if matches!(chars.peek(), Some('$' | '}' | '\\')) => {
+                let c = chars.next().unwrap();
+                push(&mut text, &mut pos, c.encode_utf8(&mut [0; 4]));
+            }
+            '\n' => {
+                text.push('\n');
+                pos = (pos.0 + 1, 0);
+                push(&mut text, &mut pos, indent);
+                line_head = true;
+            }
+            '\t' if line_head => push(&mut text, &mut pos, tab),
+            '$' => {
+                let braced = chars.peek() == Some(&'{');
+                let mut lookahead = chars.clone();
+                if braced {
+                    lookahead.next();
+                }
+                let mut digits = String::new();
+                while let Some(d) = lookahead.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(*d);
+                    lookahead.next();
+                }
+                let Ok(index) = digits.parse::<usize>() else {
+                    push(&mut text, &mut pos, "$");
+                    continue;
+                };
+                let (start, len) = (pos, text.len());
+                if braced {
+                    match lookahead.next() {
+                        Some('}') => {}
+                        Some(':') => {
+                            // Default text of the placeholder until the closing brace
+                            let mut closed = false;
+                            while let Some(c) = lookahead.next() {
+                                match c {
+                                    '}' => {
+                                        closed = true;
+                                        break;
+                                    }
+                                    '\\' if matches!(lookahead.peek(), Some('$' | '}' | '\\')) => {
+                                        let c = lookahead.next().unwrap();
+                                        push(&mut text, &mut pos, c.encode_utf8(&mut [0; 4]));
+                                    }
+                                    '\n' => {
+                                        text.push('\n');
+                                        pos = (pos.0 + 1, 0);
+                                        push(&mut text, &mut pos, indent);
+                                    }
+                                    c => push(&mut text, &mut pos, c.encode_utf8(&mut [0; 4])),
+                                }
+                            }
+                            if !closed {
+                                // Not a placeholder. Treat the rest as plain text
+                                text.truncate(len);
+                                pos = start;
+                                push(&mut text, &mut pos, "$");
+                                continue;
+                            }
+                        }
+                        _ => {
+                            push(&mut text, &mut pos, "$");
+                            continue;
+                        }
+                    }
+                }
+                chars = lookahead;
+                // Only the first tab stop of the same index is visited
+                if !stops.iter().any(|(i, _)| *i == index) {
+                    stops.push((index, (start, pos)));
+                }
+            }
+            c => push(&mut text, &mut pos, c.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    // `$0` is visited last. Without it, the end of the snippet is the final position
+    let last = stops.iter().position(|(i, _)| *i == 0).map(|i| stops.remove(i).1);
+    stops.sort_by_key(|(i, _)| *i);
+    let mut stops: Vec<_> = stops.into_iter().map(|(_, r)| r).collect();
+    stops.push(last.unwrap_or((pos, pos)));
+    Expanded { text, stops }
+}
+
+/// Session of a snippet inserted by [`crate::TextArea::insert_snippet`]. Tab stops follow edits in the active stop.
+#[derive(Clone, Debug)]
+pub(crate) struct Snippet {
+    stops: Vec<Range>, // Positions in the text
+    index: usize,      // Active stop
+}
+
+impl Snippet {
+    pub(crate) fn new(stops: Vec<Range>) -> Self {
+        Self { stops, index: 0 }
+    }
+
+    pub(crate) fn active(&self) -> Range {
+        self.stops[self.index]
+    }
+
+    pub(crate) fn next(&mut self) -> Option<Range> {
+        if self.index + 1 >= self.stops.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.active())
+    }
+
+    pub(crate) fn prev(&mut self) -> Option<Range> {
+        self.index = self.index.checked_sub(1)?;
+        Some(self.active())
+    }
+
+    // Follow the edit. Text inserted at the edges of the active stop is included in it. `false` is returned when the
+    // edit is not inside the active stop, which cancels the session
+    pub(crate) fn on_edit(&mut self, edit: &TextEdit) -> bool {
+        let (start, end) = self.active();
+        if edit.start < start || end < edit.end {
+            return false;
+        }
+        for (i, (s, e)) in self.stops.iter_mut().enumerate() {
+            if i == self.index {
+                *e = edit.shift_pos(*e).unwrap_or(edit.start);
+            } else if *e > start || *s >= end {
+                // Stops before the active stop are not moved even when they end at its start
+                *s = edit.shift_pos(*s).unwrap_or(edit.start);
+                *e = edit.shift_pos(*e).unwrap_or(edit.start);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_snippet() {
+        let tests = [
+            // (snippet, text, stops)
+            ("abc", "abc", vec![((0, 3), (0, 3))]),
+            ("a$1b$0c", "abc", vec![((0, 1), (0, 1)), ((0, 2), (0, 2))]),
+            (
+                "f(${1:x}, ${2:yy})$0",
+                "f(x, yy)",
+                vec![((0, 2), (0, 3)), ((0, 5), (0, 7)), ((0, 8), (0, 8))],
+            ),
+            // Stops are visited in the order of indices
+            ("$2a$1", "a", vec![((0, 1), (0, 1)), ((0, 0), (0, 0)), ((0, 1), (0, 1))]),
+            ("${1}x$1", "x", vec![((0, 0), (0, 0)), ((0, 1), (0, 1))]),
+            (
+                "if {\n\t$1\n}",
+                "if {\n  \t  \n  }",
+                vec![((1, 5), (1, 5)), ((2, 3), (2, 3))],
+            ),
+            ("\\$1 \\} \\\\", "$1 } \\", vec![((0, 6), (0, 6))]),
+            ("$ ${ ${x} $", "$ ${ ${x} $", vec![((0, 11), (0, 11))]),
+            ("${1:a", "${1:a", vec![((0, 5), (0, 5))]),
+            ("${1:a\nb}", "a\n  b", vec![((0, 0), (1, 3)), ((1, 3), (1, 3))]),
+            ("あ${1:い}", "あい", vec![((0, 1), (0, 2)), ((0, 2), (0, 2))]),
+        ];
+        for (snippet, text, stops) in tests {
+            let expanded = expand(snippet, "  ", "\t  ");
+            assert_eq!(expanded, Expanded { text: text.into(), stops }, "{snippet:?}");
+        }
+    }
+
+    #[test]
+    fn follow_edits() {
+        let edit = |start, end, inserted: &str| TextEdit {
+            start,
+            end,
+            inserted: inserted.to_string(),
+            deleted: String::new(), // Not used
+        };
+        // "f(x, y)" with stops at "x" and "y", and the end
+        let mut s = Snippet::new(vec![((0, 2), (0, 3)), ((0, 5), (0, 6)), ((0, 7), (0, 7))]);
+
+        // Replace "x" with "ab"
+        assert!(s.on_edit(&edit((0, 2), (0, 3), "ab")));
+        assert_eq!(s.stops, [((0, 2), (0, 4)), ((0, 6), (0, 7)), ((0, 8), (0, 8))]);
+
+        // Insert a newline at the end of the active stop
+        assert!(s.on_edit(&edit((0, 4), (0, 4), "\n")));
+        assert_eq!(s.stops, [((0, 2), (1, 0)), ((1, 2), (1, 3)), ((1, 4), (1, 4))]);
+
+        assert_eq!(s.next(), Some(((1, 2), (1, 3))));
+        // Insert at the start of the active stop. The previous stop is not extended
+        assert!(s.on_edit(&edit((1, 2), (1, 2), "z")));
+        assert_eq!(s.stops, [((0, 2), (1, 0)), ((1, 2), (1, 4)), ((1, 5), (1, 5))]);
+
+        // An edit outside the active stop cancels the session
+        assert!(!s.on_edit(&edit((0, 0), (0, 0), "x")));
+
+        assert_eq!(s.next(), Some(((1, 5), (1, 5))));
+        assert_eq!(s.next(), None);
+        assert_eq!(s.prev(), Some(((1, 2), (1, 4))));
+        assert_eq!(s.prev(), Some(((0, 2), (1, 0))));
+        assert_eq!(s.prev(), None);
+    }
+}
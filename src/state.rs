@@ -0,0 +1,76 @@
+use crate::fold::Fold;
+use crate::history::Edit;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// State of the editor saved by [`crate::TextArea::snapshot`] and restored by [`crate::TextArea::restore`] to keep
+/// sessions across runs of an application. This type is available with the `serde` feature.
+///
+/// Unknown fields are ignored and missing fields are filled with their default values on deserialization, so a state
+/// saved by another version of this crate can still be restored. Positions are clamped to the text on restoring.
+/// ```
+/// use tui_textarea::{TextArea, CursorMove};
+///
+/// let mut textarea = TextArea::from(["hello", "world"]);
+/// textarea.move_cursor(CursorMove::Jump(1, 3));
+///
+/// let state = textarea.snapshot();
+/// assert_eq!(state.cursor, (1, 3));
+/// assert_eq!(state.lines, None);
+///
+/// let mut textarea = TextArea::from(["hello", "world"]);
+/// textarea.restore(state);
+/// assert_eq!(textarea.cursor(), (1, 3));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct TextAreaState {
+    /// `(row, col)` position of the cursor.
+    pub cursor: (usize, usize),
+    /// `(row, col)` position where the selection started. `None` means nothing is selected.
+    pub selection_start: Option<(usize, usize)>,
+    /// Row and column of the text at the top-left of the viewport.
+    pub scroll_top: (u64, u64),
+    /// Query of the text search pattern. It is only restored with the `search` feature.
+    pub search_pattern: Option<String>,
+    /// Folded ranges of lines.
+    pub folds: Vec<Fold>,
+    /// `(row, col)` positions of the marks by their names.
+    pub marks: BTreeMap<char, (usize, usize)>,
+    /// Lines of the text. This is only saved by [`crate::TextArea::snapshot_with_text`].
+    pub lines: Option<Vec<String>>,
+    /// Undo history. This is only saved by [`crate::TextArea::snapshot_with_history`]. The format of the history is
+    /// internal and may change between versions of this crate. A history which cannot be decoded is dropped instead of
+    /// failing to decode the entire state.
+    #[serde(deserialize_with = "deserialize_history")]
+    pub history: Option<UndoHistory>,
+}
+
+// The history is decoded as `None` when it is not in the format of this version. This relies on the format being
+// self-describing like JSON
+fn deserialize_history<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<UndoHistory>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MaybeHistory {
+        Decoded(Option<UndoHistory>),
+        Unknown(IgnoredAny),
+    }
+    Ok(match MaybeHistory::deserialize(deserializer)? {
+        MaybeHistory::Decoded(history) => history,
+        MaybeHistory::Unknown(_) => None,
+    })
+}
+
+/// Undo history saved in [`TextAreaState`]. The history is restored only when the text is the same as the text when
+/// the history was saved, since edits in the history cannot be applied to other text. The history is also dropped when
+/// some edit in it cannot be applied to the text, for example when the saved state was modified by hand.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UndoHistory {
+    pub(crate) lines: Vec<String>,
+    pub(crate) edits: Vec<Edit>,
+    pub(crate) index: usize,         // Number of edits which can be undone
+    pub(crate) saved: Option<usize>, // Index where the text was marked as saved
+}
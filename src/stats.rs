@@ -0,0 +1,137 @@
+use crate::history::TextEdit;
+use crate::storage::Lines;
+use std::iter;
+use std::ops::{AddAssign, SubAssign};
+use unicode_segmentation::UnicodeSegmentation as _;
+
+/// Statistics of the text returned by [`crate::TextArea::stats`].
+///
+/// A line separator is counted as one character, one grapheme, and one byte like `\n` regardless of
+/// [`crate::TextArea::line_ending`]. Words are counted with the Unicode word segmentation (UAX #29). Each CJK
+/// ideograph and each hiragana is counted as one word since they are not joined into words by the segmentation.
+/// ```
+/// use tui_textarea::TextArea;
+///
+/// let textarea = TextArea::from(["Hello, world!", "こんにちは"]);
+/// let stats = textarea.stats();
+/// assert_eq!(stats.lines, 2);
+/// assert_eq!(stats.words, 7);
+/// assert_eq!(stats.chars, 19);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct TextStats {
+    /// Number of lines.
+    pub lines: usize,
+    /// Number of words.
+    pub words: usize,
+    /// Number of characters as Unicode scalar values.
+    pub chars: usize,
+    /// Number of extended grapheme clusters, which are characters perceived by users.
+    pub graphemes: usize,
+    /// Number of bytes in UTF-8.
+    pub bytes: usize,
+}
+
+// Counts in one line without the line separator
+#[derive(Clone, Copy, Debug, Default)]
+struct Counts {
+    words: usize,
+    chars: usize,
+    graphemes: usize,
+    bytes: usize,
+}
+
+impl Counts {
+    fn new(line: &str) -> Self {
+        Self {
+            words: line.unicode_words().count(),
+            chars: line.chars().count(),
+            graphemes: line.graphemes(true).count(),
+            bytes: line.len(),
+        }
+    }
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.words += other.words;
+        self.chars += other.chars;
+        self.graphemes += other.graphemes;
+        self.bytes += other.bytes;
+    }
+}
+
+impl SubAssign for Counts {
+    fn sub_assign(&mut self, other: Self) {
+        self.words -= other.words;
+        self.chars -= other.chars;
+        self.graphemes -= other.graphemes;
+        self.bytes -= other.bytes;
+    }
+}
+
+/// Counts of each line cached to update the statistics of the text incrementally. Only the lines changed by edits are
+/// counted again, so getting the statistics after typing a character does not scan the whole text.
+#[derive(Clone, Debug, Default)]
+pub struct StatsCache {
+    lines: Vec<Option<Counts>>, // `None` means the line was changed after it was counted
+    total: Counts,              // Sum of the counted lines
+    changed: usize,             // Number of the lines which need to be counted
+}
+
+impl StatsCache {
+    /// Returns `true` when the statistics have not been calculated yet. Edits don't need to be followed until then.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Mark the lines replaced by the edit as changed.
+    pub fn on_edit(&mut self, edit: &TextEdit) {
+        if self.is_empty() {
+            return;
+        }
+        let inserted = edit.inserted.matches('\n').count() + 1;
+        let replaced = edit.start.0..edit.end.0 + 1;
+        let removed: Vec<_> = self.lines.splice(replaced, iter::repeat(None).take(inserted)).collect();
+        for counts in removed {
+            match counts {
+                Some(counts) => self.total -= counts,
+                None => self.changed -= 1,
+            }
+        }
+        self.changed += inserted;
+    }
+
+    pub fn stats(&mut self, lines: &Lines) -> TextStats {
+        if self.lines.len() != lines.len() {
+            *self = Self {
+                lines: vec![None; lines.len()],
+                total: Counts::default(),
+                changed: lines.len(),
+            };
+        }
+        if self.changed > 0 {
+            for (row, counts) in self.lines.iter_mut().enumerate() {
+                if counts.is_none() {
+                    let c = Counts::new(&lines.line(row));
+                    self.total += c;
+                    *counts = Some(c);
+                }
+            }
+            self.changed = 0;
+        }
+        let separators = lines.len() - 1;
+        TextStats {
+            lines: lines.len(),
+            words: self.total.words,
+            chars: self.total.chars + separators,
+            graphemes: self.total.graphemes + separators,
+            bytes: self.total.bytes + separators,
+        }
+    }
+}
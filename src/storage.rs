@@ -0,0 +1,527 @@
+use crate::buffer::BufferStorage;
+#[cfg(feature = "rope")]
+use crate::rope::RopeStorage;
+use once_cell::unsync::OnceCell;
+use std::borrow::Cow;
+use std::fmt;
+use std::iter;
+use std::ops::Range;
+
+/// Storage of the text lines. Rows are 0-based and offsets are byte offsets in a line. A storage always has at least
+/// one line, and a line never contains a newline.
+///
+/// Only the required methods need to be implemented. The provided methods read the whole line through
+/// [`TextStorage::line`], so storages which can answer them without building the line should override them.
+pub trait TextStorage: fmt::Debug + Send {
+    /// Number of the lines.
+    fn len(&self) -> usize;
+
+    /// Text of the line at the row. It is borrowed when the line is stored in one piece.
+    fn line(&self, row: usize) -> Cow<'_, str>;
+
+    /// Pieces of the text in the range of the line in order. They are borrowed from the storage, so reading a part of a
+    /// long line does not copy the line.
+    fn chunks(&self, row: usize, range: Range<usize>) -> Box<dyn Iterator<Item = &str> + '_>;
+
+    /// Insert the text without a newline at the offset in the line.
+    fn insert_str(&mut self, row: usize, offset: usize, s: &str);
+
+    /// Remove the range of the line and return the removed text.
+    fn remove_str(&mut self, row: usize, range: Range<usize>) -> String;
+
+    /// Split the line at the offset. The text after the offset is moved to a new line inserted after the row.
+    fn split_line(&mut self, row: usize, offset: usize);
+
+    /// Join the line after the row to the end of the line.
+    fn join_line(&mut self, row: usize);
+
+    /// Replace the lines in the range of rows with `lines` and return the replaced lines. The storage must not be
+    /// empty after the replacement.
+    fn splice(&mut self, rows: Range<usize>, lines: Vec<String>) -> Vec<String>;
+
+    /// Length of the line in bytes.
+    fn line_len(&self, row: usize) -> usize {
+        self.line(row).len()
+    }
+
+    /// Number of the characters in the line.
+    fn char_count(&self, row: usize) -> usize {
+        self.line(row).chars().count()
+    }
+
+    /// Offset of the character at the column in the line. The column is clamped to the end of line.
+    fn offset_of_col(&self, row: usize, col: usize) -> usize {
+        let line = self.line(row);
+        line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+    }
+
+    /// Column of the character at the offset in the line.
+    fn col_of_offset(&self, row: usize, offset: usize) -> usize {
+        self.line(row)[..offset].chars().count()
+    }
+
+    /// Text in the range of the line.
+    fn slice(&self, row: usize, range: Range<usize>) -> Cow<'_, str> {
+        match self.line(row) {
+            Cow::Borrowed(line) => Cow::Borrowed(&line[range]),
+            Cow::Owned(line) => Cow::Owned(line[range].to_string()),
+        }
+    }
+}
+
+impl TextStorage for Vec<String> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn line(&self, row: usize) -> Cow<'_, str> {
+        Cow::Borrowed(&self[row])
+    }
+
+    fn chunks(&self, row: usize, range: Range<usize>) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(iter::once(&self[row][range]))
+    }
+
+    fn insert_str(&mut self, row: usize, offset: usize, s: &str) {
+        self[row].insert_str(offset, s);
+    }
+
+    fn remove_str(&mut self, row: usize, range: Range<usize>) -> String {
+        self[row].drain(range).collect()
+    }
+
+    fn split_line(&mut self, row: usize, offset: usize) {
+        let next = self[row].split_off(offset);
+        self.insert(row + 1, next);
+    }
+
+    fn join_line(&mut self, row: usize) {
+        let next = self.remove(row + 1);
+        self[row].push_str(&next);
+    }
+
+    fn splice(&mut self, rows: Range<usize>, lines: Vec<String>) -> Vec<String> {
+        let replaced = Vec::splice(self, rows, lines).collect();
+        debug_assert!(!self.is_empty(), "no line after splice");
+        replaced
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Storage {
+    Vec(Vec<String>),
+    Buffer(BufferStorage),
+    #[cfg(feature = "rope")]
+    Rope(RopeStorage),
+}
+
+/// Lines of the text of a textarea. Reads and edits are forwarded to the [`TextStorage`] which stores the text.
+#[derive(Clone, Debug)]
+pub struct Lines {
+    storage: Storage,
+    // Copy of the lines built by `Lines::as_slice` when the storage is not `Vec<String>`. Edits are applied to both
+    materialized: OnceCell<Vec<String>>,
+    // Whether the line ending after each line is `\r\n`. This is kept only for a text mixing `\n` and `\r\n`
+    crlf: Option<Vec<bool>>,
+}
+
+impl From<Vec<String>> for Lines {
+    fn from(mut lines: Vec<String>) -> Self {
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        Self {
+            storage: Storage::Vec(lines),
+            materialized: OnceCell::new(),
+            crlf: None,
+        }
+    }
+}
+
+impl From<BufferStorage> for Lines {
+    fn from(buffer: BufferStorage) -> Self {
+        Self {
+            storage: Storage::Buffer(buffer),
+            materialized: OnceCell::new(),
+            crlf: None,
+        }
+    }
+}
+
+impl PartialEq for Lines {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Lines {}
+
+impl PartialEq<[String]> for Lines {
+    fn eq(&self, other: &[String]) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(l, r)| l == r.as_str())
+    }
+}
+
+impl PartialEq<Vec<String>> for Lines {
+    fn eq(&self, other: &Vec<String>) -> bool {
+        self == other.as_slice()
+    }
+}
+
+impl PartialEq<[&str]> for Lines {
+    fn eq(&self, other: &[&str]) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(l, r)| l == *r)
+    }
+}
+
+impl<const N: usize> PartialEq<[&str; N]> for Lines {
+    fn eq(&self, other: &[&str; N]) -> bool {
+        self == &other[..]
+    }
+}
+
+impl Lines {
+    fn storage(&self) -> &dyn TextStorage {
+        match &self.storage {
+            Storage::Vec(lines) => lines,
+            Storage::Buffer(buffer) => buffer,
+            #[cfg(feature = "rope")]
+            Storage::Rope(rope) => rope,
+        }
+    }
+
+    fn storage_mut(&mut self) -> &mut dyn TextStorage {
+        match &mut self.storage {
+            Storage::Vec(lines) => lines,
+            Storage::Buffer(buffer) => buffer,
+            #[cfg(feature = "rope")]
+            Storage::Rope(rope) => rope,
+        }
+    }
+
+    /// Returns `true` when the lines are stored in a rope.
+    #[cfg(feature = "rope")]
+    pub fn is_rope(&self) -> bool {
+        matches!(self.storage, Storage::Rope(_))
+    }
+
+    /// Move the lines into a rope, or back into a `Vec<String>`.
+    #[cfg(feature = "rope")]
+    pub fn set_rope(&mut self, enabled: bool) {
+        if self.is_rope() == enabled {
+            return;
+        }
+        let storage = if enabled {
+            Storage::Rope(RopeStorage::new(self.iter()))
+        } else {
+            Storage::Vec(self.to_vec())
+        };
+        self.storage = storage;
+        self.materialized.take();
+    }
+
+    /// Replace all the lines. The lines are kept in the same kind of storage.
+    pub fn set(&mut self, lines: Vec<String>) {
+        #[cfg(feature = "rope")]
+        let rope = self.is_rope();
+        *self = Self::from(lines);
+        #[cfg(feature = "rope")]
+        self.set_rope(rope);
+    }
+
+    pub fn len(&self) -> usize {
+        self.storage().len()
+    }
+
+    /// Keep the line ending of each line so that the text is written back as-is. `crlf[row]` is `true` when the line
+    /// ending after the line is `\r\n`. The flag of the last line is used when a line is added after it. A new line
+    /// break takes the line ending of the line where it is inserted. `None` stops keeping the line endings.
+    pub fn set_line_endings(&mut self, crlf: Option<Vec<bool>>) {
+        debug_assert!(
+            crlf.as_ref().map_or(true, |c| c.len() == self.len()),
+            "flag for each line"
+        );
+        self.crlf = crlf;
+    }
+
+    /// Line ending after the line at the row, or `None` when the line endings are not kept.
+    pub fn line_ending_at(&self, row: usize) -> Option<&'static str> {
+        let crlf = *self.crlf.as_ref()?.get(row)?;
+        Some(if crlf { "\r\n" } else { "\n" })
+    }
+
+    /// Text of the line at the row. Panics when the row is out of the text.
+    pub fn line(&self, row: usize) -> Cow<'_, str> {
+        self.storage().line(row)
+    }
+
+    pub fn get(&self, row: usize) -> Option<Cow<'_, str>> {
+        (row < self.len()).then(|| self.line(row))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Cow<'_, str>> + ExactSizeIterator {
+        self.iter_in(0..self.len())
+    }
+
+    /// Iterate over the lines in the range of rows.
+    pub fn iter_in(&self, rows: Range<usize>) -> impl DoubleEndedIterator<Item = Cow<'_, str>> + ExactSizeIterator {
+        let storage = self.storage();
+        rows.map(move |row| storage.line(row))
+    }
+
+    /// Lines in the range of rows as owned strings.
+    pub fn to_vec_in(&self, rows: Range<usize>) -> Vec<String> {
+        rows.map(|row| self.line(row).into_owned()).collect()
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.to_vec_in(0..self.len())
+    }
+
+    pub fn into_vec(self) -> Vec<String> {
+        match self.storage {
+            Storage::Vec(lines) => lines,
+            _ => self.to_vec(),
+        }
+    }
+
+    /// The lines as a slice. When the lines are not stored in `Vec<String>`, they are copied into a vector on the first
+    /// call, and the following edits are applied to the copy as well so that it is not copied again.
+    pub fn as_slice(&self) -> &[String] {
+        match &self.storage {
+            Storage::Vec(lines) => lines,
+            _ => self.materialized.get_or_init(|| self.to_vec()),
+        }
+    }
+
+    pub fn join(&self, sep: &str) -> String {
+        let mut joined = String::new();
+        for (i, line) in self.iter().enumerate() {
+            if i > 0 {
+                joined.push_str(sep);
+            }
+            joined.push_str(&line);
+        }
+        joined
+    }
+
+    pub fn line_len(&self, row: usize) -> usize {
+        self.storage().line_len(row)
+    }
+
+    pub fn char_count(&self, row: usize) -> usize {
+        self.storage().char_count(row)
+    }
+
+    pub fn offset_of_col(&self, row: usize, col: usize) -> usize {
+        self.storage().offset_of_col(row, col)
+    }
+
+    pub fn col_of_offset(&self, row: usize, offset: usize) -> usize {
+        self.storage().col_of_offset(row, offset)
+    }
+
+    pub fn slice(&self, row: usize, range: Range<usize>) -> Cow<'_, str> {
+        self.storage().slice(row, range)
+    }
+
+    /// Pieces of the text in the range of the line. See [`TextStorage::chunks`].
+    pub fn chunks(&self, row: usize, range: Range<usize>) -> impl Iterator<Item = &str> {
+        self.storage().chunks(row, range)
+    }
+
+    /// Character at the offset in the line, or `None` at the end of line.
+    pub fn char_at(&self, row: usize, offset: usize) -> Option<char> {
+        let len = self.line_len(row);
+        self.chunks(row, offset..len).find_map(|chunk| chunk.chars().next())
+    }
+
+    // Apply the edit to the storage, and to the copy of the lines made by `Lines::as_slice`
+    fn edit<T>(&mut self, f: impl Fn(&mut dyn TextStorage) -> T) -> T {
+        if let Some(copy) = self.materialized.get_mut() {
+            f(copy);
+        }
+        f(self.storage_mut())
+    }
+
+    pub fn insert_str(&mut self, row: usize, offset: usize, s: &str) {
+        if !s.is_empty() {
+            self.edit(|storage| storage.insert_str(row, offset, s));
+        }
+    }
+
+    pub fn insert_char(&mut self, row: usize, offset: usize, c: char) {
+        self.insert_str(row, offset, c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn remove_str(&mut self, row: usize, range: Range<usize>) -> String {
+        if range.is_empty() {
+            return String::new();
+        }
+        self.edit(|storage| storage.remove_str(row, range.clone()))
+    }
+
+    pub fn split_line(&mut self, row: usize, offset: usize) {
+        self.edit(|storage| storage.split_line(row, offset));
+        if let Some(crlf) = &mut self.crlf {
+            crlf.insert(row + 1, crlf[row]);
+        }
+    }
+
+    pub fn join_line(&mut self, row: usize) {
+        self.edit(|storage| storage.join_line(row));
+        if let Some(crlf) = &mut self.crlf {
+            crlf.remove(row);
+        }
+    }
+
+    pub fn splice(&mut self, rows: Range<usize>, lines: Vec<String>) -> Vec<String> {
+        if let Some(crlf) = &mut self.crlf {
+            // Replaced lines keep their line endings and added lines take the one of the line before them
+            let (replaced, inserted) = (rows.len(), lines.len());
+            if inserted < replaced {
+                crlf.drain(rows.start + inserted..rows.end);
+            } else {
+                let fill = crlf.get(rows.end.saturating_sub(1)).copied().unwrap_or(false);
+                crlf.splice(rows.end..rows.end, std::iter::repeat(fill).take(inserted - replaced));
+            }
+        }
+        if let Some(copy) = self.materialized.get_mut() {
+            TextStorage::splice(copy, rows.clone(), lines.clone());
+        }
+        self.storage_mut().splice(rows, lines)
+    }
+
+    pub fn insert_line(&mut self, row: usize, line: String) {
+        self.splice(row..row, vec![line]);
+    }
+
+    pub fn remove_line(&mut self, row: usize) -> String {
+        self.splice(row..row + 1, vec![]).swap_remove(0)
+    }
+
+    /// Replace the text of the line at the row and return the previous text.
+    pub fn replace_line(&mut self, row: usize, line: String) -> String {
+        self.splice(row..row + 1, vec![line]).swap_remove(0)
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a != b {
+            let line = self.line(a).into_owned();
+            let line = self.replace_line(b, line);
+            self.replace_line(a, line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Apply the same edits to the lines in each storage and compare the results
+    fn storages(lines: &[&str]) -> Vec<Lines> {
+        let lines = Lines::from(lines.iter().map(|l| l.to_string()).collect::<Vec<_>>());
+        let (buffer, _) = BufferStorage::read(lines.join("\n").as_bytes()).unwrap();
+        #[allow(unused_mut)]
+        let mut storages = vec![lines, Lines::from(buffer)];
+        #[cfg(feature = "rope")]
+        {
+            let mut rope = storages[0].clone();
+            rope.set_rope(true);
+            storages.push(rope);
+        }
+        storages
+    }
+
+    #[test]
+    fn read_lines() {
+        for lines in storages(&["aあ", "", "😀b"]) {
+            assert_eq!(lines.len(), 3);
+            assert_eq!(lines.line(0), "aあ");
+            assert_eq!(lines.get(1).as_deref(), Some(""));
+            assert_eq!(lines.get(3), None);
+            assert_eq!(lines.line_len(2), 5);
+            assert_eq!(lines.char_count(0), 2);
+            assert_eq!(lines.offset_of_col(0, 1), 1);
+            assert_eq!(lines.offset_of_col(2, 10), 5);
+            assert_eq!(lines.col_of_offset(2, 4), 1);
+            assert_eq!(lines.slice(0, 1..4), "あ");
+            assert_eq!(lines.iter().rev().collect::<Vec<_>>(), ["😀b", "", "aあ"]);
+            assert_eq!(lines.to_vec_in(1..3), ["", "😀b"]);
+            assert_eq!(lines.join("\n"), "aあ\n\n😀b");
+            assert_eq!(lines.as_slice(), ["aあ", "", "😀b"]);
+            assert_eq!(lines, ["aあ", "", "😀b"]);
+        }
+    }
+
+    #[test]
+    fn edit_lines() {
+        for mut lines in storages(&["abc", "def", "ghi"]) {
+            assert_eq!(lines.as_slice(), ["abc", "def", "ghi"]);
+
+            lines.insert_str(0, 1, "xy");
+            lines.insert_char(2, 3, 'あ');
+            assert_eq!(lines, ["axybc", "def", "ghiあ"]);
+
+            assert_eq!(lines.remove_str(0, 1..3), "xy");
+            assert_eq!(lines.remove_str(2, 3..6), "あ");
+            assert_eq!(lines, ["abc", "def", "ghi"]);
+
+            lines.split_line(1, 1);
+            lines.split_line(3, 2);
+            assert_eq!(lines, ["abc", "d", "ef", "gh", "i"]);
+
+            lines.join_line(3);
+            lines.join_line(1);
+            assert_eq!(lines, ["abc", "def", "ghi"]);
+
+            assert_eq!(lines.splice(0..2, vec!["x".into()]), ["abc", "def"]);
+            assert_eq!(lines, ["x", "ghi"]);
+            assert_eq!(lines.splice(2..2, vec!["y".into(), "z".into()]), [""; 0]);
+            assert_eq!(lines, ["x", "ghi", "y", "z"]);
+            assert_eq!(lines.splice(0..4, vec!["".into()]), ["x", "ghi", "y", "z"]);
+            assert_eq!(lines, [""]);
+
+            lines.insert_line(0, "a".into());
+            lines.insert_line(2, "b".into());
+            assert_eq!(lines, ["a", "", "b"]);
+            lines.swap(0, 2);
+            assert_eq!(lines, ["b", "", "a"]);
+            assert_eq!(lines.replace_line(1, "c".into()), "");
+            assert_eq!(lines.remove_line(2), "a");
+            assert_eq!(lines.remove_line(0), "b");
+            assert_eq!(lines.as_slice(), ["c"]);
+            assert_eq!(lines.into_vec(), ["c"]);
+        }
+    }
+
+    #[test]
+    fn keep_line_endings() {
+        for mut lines in storages(&["a", "b", "c"]) {
+            let endings = |lines: &Lines| {
+                (0..lines.len())
+                    .map(|row| lines.line_ending_at(row).unwrap())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(lines.line_ending_at(0), None);
+            lines.set_line_endings(Some(vec![true, false, true]));
+            assert_eq!(endings(&lines), ["\r\n", "\n", "\r\n"]);
+
+            lines.split_line(0, 0);
+            assert_eq!(endings(&lines), ["\r\n", "\r\n", "\n", "\r\n"]);
+            lines.join_line(1);
+            assert_eq!(endings(&lines), ["\r\n", "\n", "\r\n"]);
+
+            lines.splice(1..2, vec!["x".into(), "y".into(), "z".into()]);
+            assert_eq!(endings(&lines), ["\r\n", "\n", "\n", "\n", "\r\n"]);
+            lines.splice(0..3, vec!["w".into()]);
+            assert_eq!(endings(&lines), ["\r\n", "\n", "\r\n"]);
+            lines.insert_line(0, "v".into());
+            assert_eq!(endings(&lines), ["\r\n", "\r\n", "\n", "\r\n"]);
+            assert_eq!(lines, ["v", "w", "z", "c"]);
+
+            lines.set(vec!["a".into()]);
+            assert_eq!(lines.line_ending_at(0), None);
+        }
+    }
+}
@@ -1,30 +1,105 @@
+use crate::bracket::find_matching_bracket;
+use crate::buffer::{BufferStorage, LineEndings};
+use crate::builder::TextAreaBuilder;
+use crate::cache::{LineKey, RenderCache, RenderSettings};
+use crate::cells::{CellIndex, CellPos};
+#[cfg(feature = "clipboard")]
+use crate::clipboard::Clipboard;
 use crate::cursor::CursorMove;
-use crate::highlight::LineHighlighter;
-use crate::history::{Edit, EditKind, History};
-use crate::input::{Input, Key};
-use crate::ratatui::layout::Alignment;
+use crate::fold::{self, Fold};
+use crate::highlight::{Highlight, LineHighlighter, LinePart, WhitespaceStyle};
+use crate::history::{Edit, EditKind, History, TextEdit, UndoGrouping};
+use crate::input::{Input, InputResult, Key};
+use crate::jump::JumpList;
+use crate::keymap::{default_keymap, EditAction};
+use crate::link::{detect_urls, LinkDetector};
+use crate::number;
+use crate::offset::{LineOffsets, Unit};
+use crate::ratatui::layout::{Alignment, Rect};
 use crate::ratatui::style::{Color, Modifier, Style};
+use crate::ratatui::text::{Span, Text};
 use crate::ratatui::widgets::{Block, Widget};
+use crate::scroll::CursorAlign;
 use crate::scroll::Scrolling;
+use crate::scrollbar::ScrollbarConfig;
 #[cfg(feature = "search")]
-use crate::search::Search;
-use crate::util::{spaces, Pos};
+use crate::search::{self, Search, SearchMode, SearchOptions, SearchScope};
+use crate::sign::{Sign, SIGN_WIDTH};
+use crate::snippet::{self, Snippet};
+#[cfg(feature = "serde")]
+use crate::state::{TextAreaState, UndoHistory};
+use crate::stats::{StatsCache, TextStats};
+use crate::storage::Lines;
+use crate::transform::TextTransform;
+#[cfg(feature = "search")]
+use crate::util::MatchPos;
+use crate::util::{char_width, num_digits, spaces, Pos};
 use crate::widget::{Renderer, Viewport};
-use crate::word::{find_word_end_forward, find_word_start_backward};
+use crate::word::{
+    find_identifier, find_subword_end_forward, find_subword_start_backward, find_word_end_forward,
+    find_word_prefix_start, find_word_start_backward, word_ranges, ChunkWords,
+};
+use crate::wrap::Wrapper;
 #[cfg(feature = "ratatui")]
 use ratatui::text::Line;
-use std::cmp::{min, Ordering};
-use std::fmt::Display;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::{max, min, Ordering, Reverse};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display};
 use std::io::BufRead;
-use std::{fs, io};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{fs, io, iter};
 #[cfg(feature = "tuirs")]
 use tui::text::Spans as Line;
-use unicode_width::UnicodeWidthChar as _;
+use unicode_segmentation::UnicodeSegmentation as _;
+use unicode_width::{UnicodeWidthChar as _, UnicodeWidthStr as _};
+
+#[derive(Debug, Clone)]
+struct LineStyles {
+    text: String, // Line text when the styles were set
+    styles: Vec<(Range<usize>, Style)>,
+}
+
+// Size of the text from which `TextArea::new_from_reader` keeps the lines in one buffer
+const BUFFER_THRESHOLD: usize = 1024 * 1024;
+// Number of characters around the rendered part of a line where links are detected. See `TextArea::links_in`
+const LINK_CONTEXT: usize = 1024;
+
+type ValidatorFn = dyn Fn(&[String]) -> Result<(), String> + Send + Sync;
+// Byte ranges `(row, start, end)` of text in rows
+type RowRanges = Vec<(usize, usize, usize)>;
+
+#[derive(Clone)]
+struct Validator(Arc<ValidatorFn>);
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Validator")
+    }
+}
+
+/// Highlights depending on the entire text rather than each line
+#[derive(Default)]
+pub(crate) struct TextHighlights {
+    #[cfg(feature = "search")]
+    matches: Vec<MatchPos>, // Matches of the multi-line search pattern
+    #[cfg(feature = "search")]
+    search_rows: Option<Range<usize>>, // Rows where the multi-line matches were searched
+    bracket: Option<Pos>,  // Bracket matching with the one at the cursor
+    revealed: Option<Pos>, // Character shown without the mask
+    yank_flash: RowRanges, // Byte ranges of the text copied last in each row
+}
 
 #[derive(Debug, Clone)]
 enum YankText {
     Piece(String),
     Chunk(Vec<String>),
+    Block(Vec<String>), // Fragments of rows copied from the column selection
+    Lines(Vec<String>), // Whole lines copied from the linewise selection
 }
 
 impl Default for YankText {
@@ -48,20 +123,84 @@ impl From<Vec<String>> for YankText {
     }
 }
 
+impl YankText {
+    // Split the text into lines. `\n` and `\r\n` are recognized as newline but `\r` isn't
+    fn from_text(text: &str) -> Self {
+        // `str::lines` is not available since it strips a newline at end
+        let lines: Vec<_> = text
+            .split('\n')
+            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+            .collect();
+        lines.into()
+    }
+}
+
 impl Display for YankText {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Piece(s) => f.write_str(s),
-            Self::Chunk(s) => f.write_str(&s.join("\n")),
+            Self::Chunk(s) | Self::Block(s) => f.write_str(&s.join("\n")),
+            Self::Lines(s) => s.iter().try_for_each(|l| writeln!(f, "{}", l)),
+        }
+    }
+}
+
+// Kind of the ongoing text selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SelectionMode {
+    Char,
+    Column, // Rectangle between the anchor and the cursor
+    Line,   // Whole lines between the anchor and the cursor
+}
+
+// Cursor added for multi-cursor editing in addition to the primary cursor. `anchor` is the start of its selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SecondaryCursor {
+    cursor: (usize, usize),
+    anchor: Option<(usize, usize)>,
+}
+
+impl SecondaryCursor {
+    fn range(&self) -> ((usize, usize), (usize, usize)) {
+        match self.anchor {
+            Some(a) if a < self.cursor => (a, self.cursor),
+            Some(a) => (self.cursor, a),
+            None => (self.cursor, self.cursor),
         }
     }
 }
 
+// Match previewed by `TextArea::search_preview` without moving the cursor
+#[cfg(feature = "search")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchPreview {
+    pos: Option<(usize, usize)>, // Start of the previewed match. `None` until a match is previewed for the pattern
+    scroll: ((u64, u64), u16),   // Scroll position of the viewport before the preview
+    cursor: (usize, usize),      // Cursor position when the preview started. Moving the cursor ends the preview
+    edits: u64,                  // `TextArea::edit_count` when the preview started. Editing the text ends the preview
+}
+
+// How deleted text is saved to the kill ring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kill {
+    Forward,  // Appended to the last entry on consecutive kills
+    Backward, // Prepended to the last entry on consecutive kills
+    Region,   // Always saved as a new entry
+}
+
 /// A type to manage state of textarea.
 ///
 /// [`TextArea::default`] creates an empty textarea. [`TextArea::new`] creates a textarea with given text lines.
 /// [`TextArea::from`] creates a textarea from an iterator of lines. [`TextArea::input`] handles key input.
 /// [`TextArea::widget`] builds a widget to render. And [`TextArea::lines`] returns line texts.
+///
+/// Cloning a textarea copies the text, the cursor, the selection, styles, configuration, and the undo history, so the
+/// clone can be edited to preview changes without affecting the original. Comparing textareas with `==` compares only
+/// their text. Formatting a textarea with `Display` joins the lines with the line ending of
+/// [`TextArea::line_ending`] like [`TextArea::text`].
+///
+/// A textarea is `Send` but not `Sync`. Rendering through `&TextArea` updates caches such as the spans of rendered lines
+/// with interior mutability, so wrap it in `Mutex` rather than sharing `&TextArea` between threads.
 /// ```
 /// use tui_textarea::{TextArea, Input, Key};
 ///
@@ -79,27 +218,125 @@ impl Display for YankText {
 /// ```
 #[derive(Clone, Debug)]
 pub struct TextArea<'a> {
-    lines: Vec<String>,
+    lines: Lines,
     block: Option<Block<'a>>,
     style: Style,
     cursor: (usize, usize), // 0-base
     tab_len: u8,
     hard_tab_indent: bool,
     history: History,
+    saved_state: u64,             // State of the history when the text was saved last
+    edits: Option<Vec<TextEdit>>, // Edits not taken yet while edit tracking is enabled
     cursor_line_style: Style,
+    cursor_line_style_in_selection: Option<Option<Style>>, // `None` means the same as `cursor_line_style`
     line_number_style: Option<Style>,
+    relative_line_numbers: bool,
+    line_number_min_width: u8,
     pub(crate) viewport: Viewport,
     cursor_style: Style,
+    secondary_cursors: Vec<SecondaryCursor>,
+    secondary_cursor_style: Style,
     yank: YankText,
+    kill_ring: Vec<String>, // Newest entry first
+    kill_ring_size: usize,
+    last_kill: Option<Kill>, // Kill made just before. Cleared by other edits and cursor moves
+    killing: Option<Kill>,   // Kill being recorded in the history
+    last_paste: Option<((usize, usize), (usize, usize))>, // Range of the text inserted by the last paste
     #[cfg(feature = "search")]
     search: Search,
+    #[cfg(feature = "search")]
+    search_preview: Option<SearchPreview>,
     alignment: Alignment,
     pub(crate) placeholder: String,
+    pub(crate) placeholder_lines: Option<Text<'a>>,
     pub(crate) placeholder_style: Style,
     mask: Option<char>,
+    mask_enabled: bool,
+    mask_reveal_last: bool,
+    mask_reveal_renders: usize,
+    // Position of the character typed last and the number of renderings to reveal it
+    mask_revealed: Cell<Option<((usize, usize), usize)>>,
     selection_start: Option<(usize, usize)>,
+    selection_mode: SelectionMode,
+    column_padding: bool,
     select_style: Style,
+    keep_selection_on_copy: bool,
+    yank_flash: Option<(Style, u8)>,
+    yank_flashed: RefCell<Option<(RowRanges, u8)>>, // Text copied last and the number of renderings to flash it
     fullscreen: Fullscreen,
+    wrap: bool,
+    wrap_indicator: Option<(String, Style)>,
+    line_styles: HashMap<usize, LineStyles>,
+    scroll_follow: bool,
+    following: bool,
+    cursor_follow: bool,
+    scroll_padding: (u16, u16),
+    scrollbar: Option<ScrollbarConfig>,
+    cursor_align: Cell<Option<CursorAlign>>, // Alignment of the cursor line applied on the next rendering
+    jumps: JumpList,
+    jump_threshold: Option<usize>, // `None` means the height of the viewport
+    marks: HashMap<char, (usize, usize)>,
+    highlights: Vec<Highlight>,
+    signs: BTreeMap<usize, Sign>,
+    sign_column: bool,
+    folds: Vec<Fold>, // Sorted and not overlapping
+    snippet: Option<Snippet>,
+    ghost_text: Option<String>,
+    ghost_text_style: Style,
+    unicode_input: Option<String>, // "u" followed by the hex digits typed while composing a character
+    keymap: HashMap<Input, EditAction>,
+    key_sequences: HashMap<Vec<Input>, EditAction>,
+    pending_keys: Vec<Input>,
+    pending_since: Option<Instant>,
+    pending_keys_timeout: Option<Duration>,
+    replay_pending_keys: bool,
+    macro_recording: Option<Vec<Input>>,
+    replaying_macro: bool,
+    macro_aborted: bool,
+    fold_style: Style,
+    read_only: bool,
+    single_line: bool,
+    newline_replacement: String,
+    validator: Option<Validator>,
+    validation_error: Option<String>,
+    invalid_style: Style,
+    cursor_hidden: bool,
+    focused: bool,
+    block_unfocused: Option<Block<'a>>,
+    cursor_style_unfocused: Option<Style>,
+    overwrite_mode: bool,
+    overwrite_cursor_style: Option<Style>,
+    ignore_input_unfocused: bool,
+    max_chars: Option<usize>,
+    #[cfg(feature = "clipboard")]
+    clipboard: Option<Clipboard>,
+    auto_indent: bool,
+    auto_indent_backspace: bool,
+    auto_indented: Option<(usize, usize)>,
+    auto_pairs: bool,
+    auto_pair_chars: Vec<(char, char)>,
+    auto_closers: Vec<(usize, usize)>, // Closing characters inserted by auto-pairs which can be typed over
+    comment_line: Option<String>,
+    comment_block: Option<(String, String)>,
+    comment_blank_lines: bool,
+    matching_bracket_style: Option<Style>,
+    matching_bracket_limit: usize,
+    whitespace: Option<WhitespaceStyle>,
+    indent_guides: Option<Style>,
+    indent_guide_char: char,
+    rulers: Vec<(u16, Style)>,
+    empty_line_marker: Option<(String, Style)>,
+    truncation_indicator: Option<(String, Style)>,
+    render_control_chars: bool,
+    control_char_style: Style,
+    link_detector: Option<LinkDetector>,
+    link_style: Style,
+    line_ending: LineEnding,
+    newline: &'static str, // Line ending used on writing the text
+    render_cache: RefCell<RenderCache>,
+    line_offsets: RefCell<LineOffsets>, // Offsets of the starts of lines to convert positions into offsets
+    cells: RefCell<CellIndex>,          // Display columns of positions in long lines
+    stats: RefCell<StatsCache>,         // Statistics of the text calculated lazily
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -110,6 +347,68 @@ pub enum Fullscreen {
     Full,
 }
 
+/// How lines are ordered by [`TextArea::sort_lines`]. Lines are compared by their characters (code points). Sorting is
+/// stable so lines comparing equal keep their order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SortOrder {
+    /// Sort lines in ascending order. This is the default.
+    #[default]
+    Ascending,
+    /// Sort lines in descending order.
+    Descending,
+    /// Sort lines in ascending order ignoring case.
+    AscendingCaseInsensitive,
+    /// Sort lines in descending order ignoring case.
+    DescendingCaseInsensitive,
+}
+
+/// Line ending of the text. It is detected by [`TextArea::new_from_file`] and used by [`TextArea::write_to`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\n`. This is the default.
+    #[default]
+    Lf,
+    /// `\r\n`.
+    CrLf,
+    /// Both `\n` and `\r\n` are used. Each line is written with the line ending it was read with. A new line break
+    /// takes the line ending of the line where it is inserted.
+    Mixed,
+}
+
+/// Options to normalize the text written by [`TextArea::to_writer`]. The text in the textarea is not modified. The
+/// default options are neutral, which write the text as-is like [`TextArea::write_to`].
+/// ```
+/// use tui_textarea::{LineEnding, SaveOptions};
+///
+/// let options = SaveOptions {
+///     line_ending: Some(LineEnding::CrLf),
+///     ensure_trailing_newline: true,
+///     ..Default::default()
+/// };
+/// assert!(!options.trim_trailing_whitespace);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SaveOptions {
+    /// Line ending to join lines. `None` or [`LineEnding::Mixed`] uses the line ending of [`TextArea::line_ending`].
+    pub line_ending: Option<LineEnding>,
+    /// Write a line ending at the end when the text does not end with a newline.
+    pub ensure_trailing_newline: bool,
+    /// Remove spaces and tabs at the end of every line.
+    pub trim_trailing_whitespace: bool,
+}
+
+/// Kind of indentation detected by [`TextArea::detect_indent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndentKind {
+    /// Lines are indented with hard tabs.
+    Tabs,
+    /// Lines are indented with the number of spaces per level.
+    Spaces(u8),
+    /// No line is indented.
+    Unknown,
+}
+
 impl Fullscreen {
     pub fn toggle(&self) -> Self {
         match self {
@@ -187,6 +486,83 @@ impl<'a> Default for TextArea<'a> {
     }
 }
 
+/// Compare the text of textareas. The cursor, styles, and other states are not compared.
+/// ```
+/// use tui_textarea::{TextArea, CursorMove};
+///
+/// let mut textarea = TextArea::from(["hello", "world"]);
+/// let mut preview = textarea.clone();
+/// preview.move_cursor(CursorMove::Bottom);
+/// assert_eq!(preview, textarea);
+///
+/// preview.insert_str("!");
+/// assert_ne!(preview, textarea);
+/// ```
+impl<'a> PartialEq for TextArea<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.lines == other.lines
+    }
+}
+
+impl<'a> Eq for TextArea<'a> {}
+
+/// Format the text with the lines joined by the line ending of [`TextArea::line_ending`]. This is the same as
+/// [`TextArea::text`].
+/// ```
+/// use tui_textarea::{LineEnding, TextArea};
+///
+/// let mut textarea = TextArea::from(["hello", "world"]);
+/// assert_eq!(textarea.to_string(), "hello\nworld");
+///
+/// textarea.set_line_ending(LineEnding::CrLf);
+/// assert_eq!(format!("{textarea}"), "hello\r\nworld");
+/// ```
+impl<'a> Display for TextArea<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.newline)?;
+            }
+            f.write_str(&line)?;
+        }
+        Ok(())
+    }
+}
+
+// Display width of the indentation of the line. `None` is returned for a blank line
+fn line_indent_width(line: &str, tab_len: usize) -> Option<usize> {
+    let text = line.trim_start_matches([' ', '\t']);
+    (!text.is_empty()).then(|| indent_width(&line[..line.len() - text.len()], tab_len))
+}
+
+// Display width of the indentation consisting of spaces and tabs
+fn indent_width(indent: &str, tab_len: usize) -> usize {
+    indent
+        .chars()
+        .fold(0, |w, c| if c == '\t' { w + tab_len - w % tab_len } else { w + 1 })
+}
+
+// Plain text of the line without styles
+fn line_text(line: &Line<'_>) -> String {
+    #[cfg(feature = "ratatui")]
+    let spans = &line.spans;
+    #[cfg(feature = "tuirs")]
+    let spans = &line.0;
+    spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+fn is_newline_input(input: &Input) -> bool {
+    matches!(
+        input,
+        Input {
+            key: Key::Char('\n' | '\r'),
+            ctrl: false,
+            alt: false,
+            ..
+        } | Input { key: Key::Enter, .. }
+    )
+}
+
 impl<'a> TextArea<'a> {
     /// Create [`TextArea`] instance with given lines. If you have value other than `Vec<String>`, [`TextArea::from`]
     /// may be more useful.
@@ -197,68 +573,314 @@ impl<'a> TextArea<'a> {
     /// let textarea = TextArea::new(lines);
     /// assert_eq!(textarea.lines(), ["hello", "...", "goodbye"]);
     /// ```
-    pub fn new(mut lines: Vec<String>) -> Self {
-        if lines.is_empty() {
-            lines.push(String::new());
-        }
-
+    pub fn new(lines: Vec<String>) -> Self {
         Self {
-            lines,
+            lines: Lines::from(lines),
             block: None,
             style: Style::default(),
             cursor: (0, 0),
             tab_len: 4,
             hard_tab_indent: false,
             history: History::new(50),
+            saved_state: 0,
+            edits: None,
             cursor_line_style: Style::default().add_modifier(Modifier::UNDERLINED),
+            cursor_line_style_in_selection: None,
             line_number_style: None,
+            relative_line_numbers: false,
+            line_number_min_width: 0,
             viewport: Viewport::default(),
             cursor_style: Style::default().add_modifier(Modifier::REVERSED),
+            secondary_cursors: vec![],
+            secondary_cursor_style: Style::default().add_modifier(Modifier::REVERSED),
             yank: YankText::default(),
+            kill_ring: vec![],
+            kill_ring_size: 0,
+            last_kill: None,
+            killing: None,
+            last_paste: None,
             #[cfg(feature = "search")]
             search: Search::default(),
+            #[cfg(feature = "search")]
+            search_preview: None,
             alignment: Alignment::Left,
             placeholder: String::new(),
+            placeholder_lines: None,
             placeholder_style: Style::default().fg(Color::DarkGray),
             mask: None,
+            mask_enabled: true,
+            mask_reveal_last: false,
+            mask_reveal_renders: 1,
+            mask_revealed: Cell::new(None),
             selection_start: None,
+            selection_mode: SelectionMode::Char,
+            column_padding: false,
             select_style: Style::default().bg(Color::LightBlue),
+            keep_selection_on_copy: true,
+            yank_flash: None,
+            yank_flashed: RefCell::new(None),
             fullscreen: Fullscreen::default(),
+            wrap: false,
+            wrap_indicator: None,
+            line_styles: HashMap::new(),
+            scroll_follow: false,
+            following: false,
+            cursor_follow: true,
+            scroll_padding: (0, 0),
+            scrollbar: None,
+            cursor_align: Cell::new(None),
+            jumps: JumpList::default(),
+            jump_threshold: None,
+            marks: HashMap::new(),
+            highlights: vec![],
+            signs: BTreeMap::new(),
+            sign_column: false,
+            folds: vec![],
+            snippet: None,
+            ghost_text: None,
+            ghost_text_style: Style::default().fg(Color::DarkGray),
+            unicode_input: None,
+            keymap: default_keymap(),
+            key_sequences: HashMap::new(),
+            pending_keys: vec![],
+            pending_since: None,
+            pending_keys_timeout: None,
+            replay_pending_keys: true,
+            macro_recording: None,
+            replaying_macro: false,
+            macro_aborted: false,
+            fold_style: Style::default().fg(Color::DarkGray),
+            read_only: false,
+            single_line: false,
+            newline_replacement: " ".to_string(),
+            validator: None,
+            validation_error: None,
+            invalid_style: Style::default().fg(Color::Red),
+            cursor_hidden: false,
+            focused: true,
+            block_unfocused: None,
+            cursor_style_unfocused: None,
+            overwrite_mode: false,
+            overwrite_cursor_style: None,
+            ignore_input_unfocused: false,
+            max_chars: None,
+            #[cfg(feature = "clipboard")]
+            clipboard: None,
+            auto_indent: false,
+            auto_indent_backspace: true,
+            auto_indented: None,
+            auto_pairs: false,
+            auto_pair_chars: vec![('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\''), ('`', '`')],
+            auto_closers: vec![],
+            comment_line: None,
+            comment_block: None,
+            comment_blank_lines: false,
+            matching_bracket_style: None,
+            matching_bracket_limit: 10000,
+            whitespace: None,
+            indent_guides: None,
+            indent_guide_char: '│',
+            rulers: vec![],
+            empty_line_marker: None,
+            truncation_indicator: None,
+            render_control_chars: true,
+            control_char_style: Style::default().fg(Color::Blue),
+            link_detector: Some(detect_urls),
+            link_style: Style::default().add_modifier(Modifier::UNDERLINED),
+            line_ending: LineEnding::Lf,
+            newline: "\n",
+            render_cache: RefCell::new(RenderCache::default()),
+            line_offsets: RefCell::new(LineOffsets::default()),
+            cells: RefCell::new(CellIndex::default()),
+            stats: RefCell::new(StatsCache::default()),
         }
     }
 
-    pub fn new_from_file(file: &fs::File) -> io::Result<Self> {
-        let mut file_reader = io::BufReader::new(file);
+    /// Create a [`TextAreaBuilder`] to construct a configured textarea in one expression. The defaults of the builder
+    /// are the same as [`TextArea::default`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::builder().placeholder("Search...").single_line(true).build();
+    /// assert_eq!(textarea.placeholder_text(), "Search...");
+    /// assert!(textarea.is_single_line());
+    /// ```
+    pub fn builder() -> TextAreaBuilder<'a> {
+        TextAreaBuilder::default()
+    }
 
-        let mut buf = String::new();
-        let mut lines = Vec::new();
-        let mut ends_in_newline = false;
-        loop {
-            buf.clear();
-            match file_reader.read_line(&mut buf)? {
-                0 => break,
-                _n => {
-                    ends_in_newline = buf.ends_with('\n');
-                    if ends_in_newline {
-                        buf.pop();
-                        if buf.ends_with('\r') {
-                            buf.pop();
-                        }
-                    }
-                    lines.push(buf.clone());
-                }
-            };
+    // Set the text of the textarea which has not been edited yet. The text is not recorded in the undo history
+    pub(crate) fn set_initial_lines(&mut self, mut lines: Vec<String>) {
+        if lines.is_empty() {
+            lines.push(String::new());
         }
+        if self.single_line && lines.len() > 1 {
+            lines = vec![lines.join(&self.newline_replacement)];
+        }
+        self.lines.set(lines);
+        self.line_offsets.get_mut().clear();
+        self.cells.get_mut().clear();
+        self.stats.get_mut().clear();
+        self.render_cache.get_mut().clear();
+    }
 
-        if ends_in_newline {
-            lines.push(String::new());
+    /// Create a textarea with the text read from the file. The line ending used in the file is detected and can be got
+    /// by [`TextArea::line_ending`]. When the file ends with a newline, the last line is empty. See
+    /// [`TextArea::new_from_reader`] for more details.
+    pub fn new_from_file(file: &fs::File) -> io::Result<Self> {
+        Self::new_from_reader(io::BufReader::new(file))
+    }
+
+    /// Create a textarea with the text read from the reader. The line ending is detected as [`TextArea::new_from_file`]
+    /// does. An error is returned when the text is not valid UTF-8.
+    ///
+    /// The reader is read chunk by chunk into one buffer and the lines are found while reading, so the true number of
+    /// lines is known when this method returns. When the text is 1 MiB or larger, the lines are kept as ranges of the
+    /// buffer instead of allocating one `String` per line. A line is copied out of the buffer only when it is edited,
+    /// and reading lines with [`TextArea::line`] or [`TextArea::iter_lines`] doesn't copy them. Note that
+    /// [`TextArea::lines`] copies the whole text into `String`s on the first call after each edit. The threshold can be
+    /// changed with [`TextArea::new_from_reader_with_threshold`].
+    /// ```
+    /// use tui_textarea::{LineEnding, TextArea};
+    ///
+    /// let textarea = TextArea::new_from_reader("hello\r\nworld\r\n".as_bytes()).unwrap();
+    /// assert_eq!(textarea.lines(), ["hello", "world", ""]);
+    /// assert_eq!(textarea.line_ending(), LineEnding::CrLf);
+    /// ```
+    pub fn new_from_reader(reader: impl BufRead) -> io::Result<Self> {
+        Self::new_from_reader_with_threshold(reader, BUFFER_THRESHOLD)
+    }
+
+    /// Create a textarea with the text read from the reader like [`TextArea::new_from_reader`]. The lines are kept in
+    /// one buffer when the text is `threshold` bytes or larger. `0` keeps any text in the buffer and `usize::MAX`
+    /// always allocates one `String` per line.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::new_from_reader_with_threshold("foo\nbar".as_bytes(), 0).unwrap();
+    /// assert_eq!(textarea.len_lines(), 2);
+    /// assert_eq!(textarea.line(1).as_deref(), Some("bar"));
+    ///
+    /// // Only the edited line is copied out of the buffer
+    /// textarea.insert_str("baz ");
+    /// assert_eq!(textarea.line(0).as_deref(), Some("baz foo"));
+    /// ```
+    pub fn new_from_reader_with_threshold(reader: impl BufRead, threshold: usize) -> io::Result<Self> {
+        let (buffer, LineEndings { lf, crlf, mut crlf_rows }) = BufferStorage::read(reader)?;
+        let buffered = buffer.buffer_len() >= threshold;
+        let mut lines = Lines::from(buffer);
+        if !buffered {
+            lines = Lines::from(lines.into_vec());
         }
+        if lf > 0 && crlf > 0 {
+            // Each line keeps its own line ending so that the text is written back as-is
+            crlf_rows.push(crlf > lf);
+            lines.set_line_endings(Some(crlf_rows));
+        }
+
+        Ok(Self {
+            lines,
+            newline: if crlf > lf { "\r\n" } else { "\n" },
+            line_ending: match (lf, crlf) {
+                (_, 0) => LineEnding::Lf,
+                (0, _) => LineEnding::CrLf,
+                _ => LineEnding::Mixed,
+            },
+            ..Self::default()
+        })
+    }
 
-        Ok(Self::new(lines))
+    /// Write the text to the writer. Lines are joined with the line ending of [`TextArea::line_ending`]. Since a text
+    /// ending with a newline has an empty last line, the line ending is put at the end only when the last line is
+    /// empty. This is the counterpart of [`TextArea::new_from_file`] which keeps the text as-is on round-trip.
+    /// ```
+    /// use tui_textarea::{LineEnding, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", ""]);
+    /// textarea.set_line_ending(LineEnding::CrLf);
+    ///
+    /// let mut buf = vec![];
+    /// textarea.write_to(&mut buf).unwrap();
+    /// assert_eq!(buf, b"a\r\nb\r\n");
+    /// ```
+    pub fn write_to(&self, w: &mut impl io::Write) -> io::Result<()> {
+        self.to_writer(w, SaveOptions::default()).map(|_| ())
+    }
+
+    /// Write the text to the writer normalizing it with the options, and return the number of written bytes. The text
+    /// in the textarea is not modified. With the default options, the text is written as-is like
+    /// [`TextArea::write_to`], so a text loaded by [`TextArea::new_from_reader`] is written back to the same bytes even
+    /// when the line endings in it are mixed.
+    /// ```
+    /// use tui_textarea::{LineEnding, SaveOptions, TextArea};
+    ///
+    /// let textarea = TextArea::new_from_reader("a  \nb\n\n".as_bytes()).unwrap();
+    ///
+    /// let mut buf = vec![];
+    /// textarea.to_writer(&mut buf, SaveOptions::default()).unwrap();
+    /// assert_eq!(buf, b"a  \nb\n\n");
+    ///
+    /// let options = SaveOptions {
+    ///     line_ending: Some(LineEnding::CrLf),
+    ///     trim_trailing_whitespace: true,
+    ///     ..Default::default()
+    /// };
+    /// let mut buf = vec![];
+    /// let written = textarea.to_writer(&mut buf, options).unwrap();
+    /// assert_eq!(buf, b"a\r\nb\r\n\r\n");
+    /// assert_eq!(written, buf.len());
+    ///
+    /// // The trailing newline is added only when it is missing
+    /// let textarea = TextArea::from(["a", "b"]);
+    /// let options = SaveOptions { ensure_trailing_newline: true, ..Default::default() };
+    /// let mut buf = vec![];
+    /// textarea.to_writer(&mut buf, options).unwrap();
+    /// assert_eq!(buf, b"a\nb\n");
+    ///
+    /// // Mixed line endings are kept
+    /// let textarea = TextArea::new_from_reader("x\r\ny\n".as_bytes()).unwrap();
+    /// let mut buf = vec![];
+    /// textarea.to_writer(&mut buf, SaveOptions::default()).unwrap();
+    /// assert_eq!(buf, b"x\r\ny\n");
+    /// ```
+    pub fn to_writer(&self, w: &mut impl io::Write, options: SaveOptions) -> io::Result<usize> {
+        let (newline, keep) = match options.line_ending {
+            Some(LineEnding::Lf) => ("\n", false),
+            Some(LineEnding::CrLf) => ("\r\n", false),
+            Some(LineEnding::Mixed) | None => (self.newline, self.line_ending == LineEnding::Mixed),
+        };
+        // Mixed line endings are written as they were read
+        let newline_at = |row: usize| {
+            let kept = if keep { self.lines.line_ending_at(row) } else { None };
+            kept.unwrap_or(newline)
+        };
+        let mut written = 0;
+        let mut write = |s: &str| {
+            written += s.len();
+            w.write_all(s.as_bytes())
+        };
+        let mut last_empty = true;
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                write(newline_at(i - 1))?;
+            }
+            let line = if options.trim_trailing_whitespace {
+                line.trim_end_matches([' ', '\t'])
+            } else {
+                &line
+            };
+            write(line)?;
+            last_empty = line.is_empty();
+        }
+        if options.ensure_trailing_newline && !last_empty {
+            write(newline_at(self.lines.len() - 1))?;
+        }
+        Ok(written)
     }
 
-    /// Handle a key input with default key mappings. For default key mappings, see the table in
-    /// [the module document](./index.html).
+    /// Handle a key input with the key mappings in [`TextArea::keymap`]. For default key mappings, see the table in
+    /// [the module document](./index.html). A character input not found in the keymap is inserted unless it is typed
+    /// with Ctrl key.
     /// `crossterm`, `termion`, and `termwiz` features enable conversion from their own key event types into
     /// [`Input`] so this method can take the event values directly.
     /// This method returns if the input modified text contents or not in the textarea.
@@ -295,2117 +917,9930 @@ impl<'a> TextArea<'a> {
     /// ```
     pub fn input(&mut self, input: impl Into<Input>) -> bool {
         let input = input.into();
-        let modified = match input {
-            Input {
-                key: Key::Char('d'),
-                ctrl: true,
-                alt: false,
-                ..
-            } => {
-                self.duplicate_line();
-                true
-            }
+        self.record_input(&input);
+        self.dispatch_input(input)
+    }
 
-            Input {
-                key: Key::Char('\n' | '\r'),
-                ctrl: false,
-                alt: false,
-                ..
+    fn dispatch_input(&mut self, input: Input) -> bool {
+        if self.ignores_input() || input.key.is_ignored() {
+            return false;
+        }
+        if self.unicode_input.is_some() && !self.read_only {
+            if let Some(modified) = self.compose_unicode(&input) {
+                return modified;
             }
-            | Input { key: Key::Enter, .. } => {
-                self.insert_newline();
-                true
+            let committed = self.commit_unicode_input();
+            return self.dispatch_input(input) || committed;
+        }
+        let modified = match input.key {
+            Key::MouseClick(x, y) => {
+                let modified = self.flush_pending_keys();
+                if let Some(cursor) = self.screen_to_cursor(x, y) {
+                    if !input.shift {
+                        self.cancel_selection();
+                        self.clear_secondary_cursors();
+                    } else if self.selection_start.is_none() {
+                        self.start_selection();
+                    }
+                    self.cursor = cursor;
+                }
+                modified
             }
-
-            Input {
-                key: Key::Tab,
-                ctrl: false,
-                alt: false,
-                shift: false,
-            } => self.insert_tab(),
-            Input {
-                key: Key::BackTab,
-                ctrl: false,
-                alt: false,
-                ..
+            Key::MouseDrag(x, y) => {
+                let modified = self.flush_pending_keys();
+                if let Some(cursor) = self.screen_to_cursor(x, y) {
+                    if self.selection_start.is_none() {
+                        self.start_selection();
+                    }
+                    self.cursor = cursor;
+                }
+                modified
             }
-            | Input {
-                key: Key::Tab,
-                ctrl: false,
-                alt: false,
-                shift: true,
-            } => self.delete_tab_from_head(),
+            _ => {
+                let actions = self.feed_key(input.clone());
+                self.perform_inputs(actions, false)
+            }
+        };
 
+        // Check invariants
+        debug_assert!(self.lines.len() > 0, "no line after {:?}", input);
+        let (r, c) = self.cursor;
+        debug_assert!(
+            self.lines.len() > r,
+            "cursor {:?} exceeds max lines {} after {:?}",
+            self.cursor,
+            self.lines.len(),
+            input,
+        );
+        debug_assert!(
+            self.lines.char_count(r) >= c,
+            "cursor {:?} exceeds max col {} at line {:?} after {:?}",
+            self.cursor,
+            self.lines.char_count(r),
+            self.lines.line(r),
+            input
+        );
+
+        if modified {
+            self.validate();
+        }
+        modified
+    }
+
+    /// Handle a key input like [`TextArea::input`], but return a richer result. In single-line mode, Enter key (and
+    /// other inputs inserting a newline) returns [`InputResult::Submit`] so that applications can tell the user
+    /// submitted the input. See [`TextArea::set_single_line`].
+    /// ```
+    /// use tui_textarea::{Input, InputResult, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_single_line(true);
+    ///
+    /// let res = textarea.handle_input(Input { key: Key::Char('a'), ..Default::default() });
+    /// assert_eq!(res, InputResult::Modified);
+    /// let res = textarea.handle_input(Input { key: Key::Right, ..Default::default() });
+    /// assert_eq!(res, InputResult::Unchanged);
+    /// let res = textarea.handle_input(Input { key: Key::Enter, ..Default::default() });
+    /// assert_eq!(res, InputResult::Submit);
+    /// assert_eq!(textarea.lines(), ["a"]);
+    /// ```
+    pub fn handle_input(&mut self, input: impl Into<Input>) -> InputResult {
+        let input = input.into();
+        if self.ignores_input() {
+            InputResult::Unchanged
+        } else if self.single_line
+            && is_newline_input(&input)
+            && self.unicode_input.is_none()
+            && self.pending_keys.is_empty()
+        {
+            InputResult::Submit
+        } else if self.input(input) {
+            InputResult::Modified
+        } else {
+            InputResult::Unchanged
+        }
+    }
+
+    /// Handle a key input without default key mappings. This method handles only
+    ///
+    /// - Single character input without modifier keys
+    /// - Tab
+    /// - Enter
+    /// - Backspace
+    /// - Delete
+    ///
+    /// This method returns if the input modified text contents or not in the textarea.
+    ///
+    /// This method is useful when you want to define your own key mappings and don't want default key mappings.
+    /// See 'Define your own key mappings' section in [the module document](./index.html).
+    pub fn input_without_shortcuts(&mut self, input: impl Into<Input>) -> bool {
+        if self.read_only || self.ignores_input() {
+            return false;
+        }
+        let modified = match input.into() {
             Input {
-                key: Key::Backspace,
-                ctrl: false,
-                alt: false,
-                ..
-            } => self.delete_char(),
-            Input {
-                key: Key::Backspace,
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.delete_word(),
-            Input {
-                key: Key::Delete,
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.delete_next_word(),
-            Input {
-                key: Key::Delete,
+                key: Key::Char(c),
                 ctrl: false,
                 alt: false,
                 ..
-            } => self.delete_next_char(),
-            Input {
-                key: Key::Char('K'),
-                ctrl: true,
-                alt: false,
-                shift: true,
-            } => self.delete_line(false),
-            Input {
-                key: Key::Char('k'),
-                ctrl: true,
-                alt: false,
-                shift: false,
-            } => self.delete_line_by_end(),
-            Input {
-                key: Key::Char('j'),
-                ctrl: true,
-                alt: false,
-                shift: false,
-            } => self.delete_line_by_head(),
+            } => self.insert_char(c),
+            Input { key: Key::Backspace, .. } => self.delete_char(),
+            Input { key: Key::Delete, .. } => self.delete_next_char(),
+            _ => false,
+        };
+        if modified {
+            self.validate();
+        }
+        modified
+    }
 
-            Input {
-                key: Key::Char('a'),
-                ctrl: true,
-                alt: false,
-                shift: false,
-            } => {
-                self.select_all();
-                false
-            }
+    pub fn single_line_input(&mut self, input: impl Into<Input>) -> bool {
+        let input = input.into();
+        self.record_input(&input);
+        self.dispatch_single_line_input(input)
+    }
 
-            Input {
-                key: Key::Up,
-                ctrl: false,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Up, shift);
-                false
-            }
-            Input {
-                key: Key::Down,
-                ctrl: false,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Down, shift);
-                false
+    fn dispatch_single_line_input(&mut self, input: Input) -> bool {
+        if self.ignores_input() || input.key.is_ignored() {
+            return false;
+        }
+        if self.unicode_input.is_some() && !self.read_only {
+            if let Some(modified) = self.compose_unicode(&input) {
+                return modified;
             }
-            Input {
-                key: Key::Left,
-                ctrl: false,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Back, shift);
-                false
-            }
-            Input {
-                key: Key::Right,
-                ctrl: false,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Forward, shift);
-                false
+            let committed = self.commit_unicode_input();
+            return self.dispatch_single_line_input(input) || committed;
+        }
+        let actions = self.feed_key(input);
+        let modified = self.perform_inputs(actions, true);
+        if modified {
+            self.validate();
+        }
+        modified
+    }
+
+    /// Get the keymap consulted by [`TextArea::input`] and [`TextArea::single_line_input`]. It is populated with
+    /// [`default_keymap`](crate::default_keymap) by default.
+    /// ```
+    /// use tui_textarea::{EditAction, Input, Key, TextArea};
+    ///
+    /// let textarea = TextArea::default();
+    /// let ctrl_a = Input { key: Key::Char('a'), ctrl: true, ..Default::default() };
+    /// assert_eq!(textarea.keymap().get(&ctrl_a), Some(&EditAction::SelectAll));
+    /// ```
+    pub fn keymap(&self) -> &HashMap<Input, EditAction> {
+        &self.keymap
+    }
+
+    /// Get the mutable reference to the keymap to rebind or disable key mappings. A character not bound in the keymap
+    /// is inserted regardless of its modifier keys, so bind [`EditAction::Ignore`](crate::EditAction::Ignore) to
+    /// disable a key instead of removing it.
+    /// ```
+    /// use tui_textarea::{CursorMove, EditAction, Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abc"]);
+    /// let ctrl_k = Input { key: Key::Char('k'), ctrl: true, ..Default::default() };
+    /// let ctrl_e = Input { key: Key::Char('e'), ctrl: true, ..Default::default() };
+    ///
+    /// // Disable Ctrl+K
+    /// textarea.keymap_mut().insert(ctrl_k.clone(), EditAction::Ignore);
+    /// assert!(!textarea.input(ctrl_k));
+    /// assert_eq!(textarea.lines(), ["abc"]);
+    ///
+    /// // Bind Ctrl+E to moving the cursor to the end of line
+    /// textarea.keymap_mut().insert(ctrl_e.clone(), EditAction::MoveCursor(CursorMove::End));
+    /// textarea.input(ctrl_e);
+    /// assert_eq!(textarea.cursor(), (0, 3));
+    /// ```
+    pub fn keymap_mut(&mut self) -> &mut HashMap<Input, EditAction> {
+        &mut self.keymap
+    }
+
+    /// Get the key sequences consulted by [`TextArea::input`] and [`TextArea::single_line_input`] in addition to
+    /// [`TextArea::keymap`]. Keys in a sequence are typed one after another. This is empty by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::default();
+    /// assert!(textarea.key_sequences().is_empty());
+    /// ```
+    pub fn key_sequences(&self) -> &HashMap<Vec<Input>, EditAction> {
+        &self.key_sequences
+    }
+
+    /// Get the mutable reference to the key sequences to bind multi-key inputs such as `Ctrl+X Ctrl+S` or `g g`.
+    ///
+    /// While the typed keys are a prefix of some sequence, they are buffered as [`TextArea::pending_keys`] without
+    /// doing anything. When the keys complete a sequence, its action is performed. When a key stops matching any
+    /// sequence, the buffered keys are performed when they complete a binding. Otherwise they are replayed as single
+    /// key inputs or discarded depending on [`TextArea::set_replay_pending_keys`]. Then the key is handled as a new
+    /// input.
+    ///
+    /// When the keys both complete a binding and are a prefix of a longer sequence, the longer sequence is preferred.
+    /// The shorter binding is performed when the next key does not continue the longer sequence, when the pending keys
+    /// timed out, or when [`TextArea::flush_pending_keys`] is called. This also applies to single keys in
+    /// [`TextArea::keymap`] and characters inserted by default.
+    /// ```
+    /// use tui_textarea::{CursorMove, EditAction, Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// let g = Input { key: Key::Char('g'), ..Default::default() };
+    /// textarea.key_sequences_mut().insert(vec![g.clone(), g.clone()], EditAction::MoveCursor(CursorMove::Bottom));
+    ///
+    /// textarea.input(g.clone());
+    /// assert_eq!(textarea.pending_keys(), [g.clone()]);
+    /// textarea.input(g.clone());
+    /// assert!(textarea.pending_keys().is_empty());
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// // 'g' followed by other key is inserted as usual
+    /// textarea.input(g);
+    /// textarea.input(Input { key: Key::Char('x'), ..Default::default() });
+    /// assert_eq!(textarea.lines(), ["abc", "gxdef"]);
+    /// ```
+    pub fn key_sequences_mut(&mut self) -> &mut HashMap<Vec<Input>, EditAction> {
+        &mut self.key_sequences
+    }
+
+    /// Get the keys typed as a prefix of some sequence in [`TextArea::key_sequences`] and waiting for the next key.
+    /// This is useful to show the pending keys in your application.
+    /// ```
+    /// use tui_textarea::{EditAction, Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let ctrl_x: Input = "ctrl+x".parse().unwrap();
+    /// let ctrl_s: Input = "ctrl+s".parse().unwrap();
+    /// textarea.key_sequences_mut().insert(vec![ctrl_x.clone(), ctrl_s], EditAction::Undo);
+    ///
+    /// textarea.input(ctrl_x.clone());
+    /// assert_eq!(textarea.pending_keys(), [ctrl_x]);
+    /// ```
+    pub fn pending_keys(&self) -> &[Input] {
+        &self.pending_keys
+    }
+
+    /// Discard the pending keys without performing or replaying them.
+    /// ```
+    /// use tui_textarea::{EditAction, Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let g = Input { key: Key::Char('g'), ..Default::default() };
+    /// textarea.key_sequences_mut().insert(vec![g.clone(), g.clone()], EditAction::SelectAll);
+    ///
+    /// textarea.input(g);
+    /// textarea.cancel_pending_keys();
+    /// assert!(textarea.pending_keys().is_empty());
+    /// assert_eq!(textarea.lines(), [""]);
+    /// ```
+    pub fn cancel_pending_keys(&mut self) {
+        self.pending_keys.clear();
+        self.pending_since = None;
+    }
+
+    /// Resolve the pending keys now as if they timed out. When the pending keys complete a binding, its action is
+    /// performed. Otherwise they are replayed or discarded depending on [`TextArea::set_replay_pending_keys`]. This
+    /// method returns if the text was modified or not. This is useful to resolve the pending keys with your own timer.
+    /// ```
+    /// use tui_textarea::{EditAction, Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let g = Input { key: Key::Char('g'), ..Default::default() };
+    /// textarea.key_sequences_mut().insert(vec![g.clone(), g.clone()], EditAction::SelectAll);
+    ///
+    /// textarea.input(g);
+    /// assert!(textarea.flush_pending_keys());
+    /// assert_eq!(textarea.lines(), ["g"]);
+    /// ```
+    pub fn flush_pending_keys(&mut self) -> bool {
+        let actions = self.resolve_pending_keys();
+        let modified = self.perform_inputs(actions, self.single_line);
+        if modified {
+            self.validate();
+        }
+        modified
+    }
+
+    /// Set the timeout of the pending keys. When the next key is typed after the timeout, the pending keys are resolved
+    /// as [`TextArea::flush_pending_keys`] before handling the key. `None` means no timeout, which is the default.
+    /// ```
+    /// use std::time::Duration;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_pending_keys_timeout(Some(Duration::from_secs(1)));
+    /// assert_eq!(textarea.pending_keys_timeout(), Some(Duration::from_secs(1)));
+    /// ```
+    pub fn set_pending_keys_timeout(&mut self, timeout: Option<Duration>) {
+        self.pending_keys_timeout = timeout;
+    }
+
+    /// Get the timeout of the pending keys set by [`TextArea::set_pending_keys_timeout`].
+    pub fn pending_keys_timeout(&self) -> Option<Duration> {
+        self.pending_keys_timeout
+    }
+
+    /// Set if the pending keys are replayed as single key inputs when they stop matching any key sequence. When
+    /// `false`, they are discarded. The default value is `true`.
+    /// ```
+    /// use tui_textarea::{EditAction, Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let keys = ["ctrl+q", "a", "b"].map(|k| k.parse::<Input>().unwrap());
+    /// textarea.key_sequences_mut().insert(keys.to_vec(), EditAction::SelectAll);
+    /// textarea.set_replay_pending_keys(false);
+    ///
+    /// // "ctrl+q a" is discarded when "x" is typed
+    /// for input in [&keys[0], &keys[1]] {
+    ///     textarea.input(input.clone());
+    /// }
+    /// textarea.input(Input { key: Key::Char('x'), ..Default::default() });
+    /// assert_eq!(textarea.lines(), ["x"]);
+    /// ```
+    pub fn set_replay_pending_keys(&mut self, enabled: bool) {
+        self.replay_pending_keys = enabled;
+    }
+
+    /// Get if the pending keys are replayed or not. See [`TextArea::set_replay_pending_keys`].
+    pub fn replay_pending_keys(&self) -> bool {
+        self.replay_pending_keys
+    }
+
+    /// Start recording inputs handled by [`TextArea::input`], [`TextArea::handle_input`], and
+    /// [`TextArea::single_line_input`] as a macro. Inputs your application handles by itself are not recorded since
+    /// they are not passed to the textarea. If recording is already ongoing, the recorded inputs are discarded.
+    /// ```
+    /// use tui_textarea::{Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.start_macro_recording();
+    /// assert!(textarea.is_recording_macro());
+    /// textarea.input(Input { key: Key::Char('a'), ..Default::default() });
+    ///
+    /// let recorded = textarea.stop_macro_recording();
+    /// assert_eq!(recorded, [Input { key: Key::Char('a'), ..Default::default() }]);
+    /// assert!(!textarea.is_recording_macro());
+    /// ```
+    pub fn start_macro_recording(&mut self) {
+        self.macro_recording = Some(vec![]);
+    }
+
+    /// Stop recording a macro and return the recorded inputs. An empty vector is returned when recording is not
+    /// ongoing. See [`TextArea::start_macro_recording`].
+    pub fn stop_macro_recording(&mut self) -> Vec<Input> {
+        self.macro_recording.take().unwrap_or_default()
+    }
+
+    /// Return if a macro is being recorded or not. See [`TextArea::start_macro_recording`].
+    pub fn is_recording_macro(&self) -> bool {
+        self.macro_recording.is_some()
+    }
+
+    /// Replay the inputs `count` times through [`TextArea::input`]. All edits by the replay are grouped into one undo
+    /// unit so that one [`TextArea::undo`] call reverts them. Replayed inputs are not recorded into an ongoing macro
+    /// recording.
+    ///
+    /// Like Vim, the replay stops early when a relative cursor movement such as [`CursorMove::Down`] or
+    /// [`CursorMove::Forward`] cannot move the cursor, for example at the end of the text. This method returns the
+    /// number of iterations which ran, including the one stopped in the middle.
+    /// ```
+    /// use tui_textarea::{Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    /// let key = |key| Input { key, ..Default::default() };
+    ///
+    /// // Insert "- " at the head of line and go to the next line
+    /// textarea.start_macro_recording();
+    /// for input in [key(Key::Char('-')), key(Key::Char(' ')), key(Key::Down), key(Key::Home)] {
+    ///     textarea.input(input);
+    /// }
+    /// let recorded = textarea.stop_macro_recording();
+    ///
+    /// // The replay stops at the last line
+    /// assert_eq!(textarea.replay(&recorded, 10), 2);
+    /// assert_eq!(textarea.lines(), ["- a", "- b", "- c"]);
+    ///
+    /// // Undo the whole replay at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["- a", "b", "c"]);
+    /// ```
+    pub fn replay(&mut self, inputs: &[Input], count: usize) -> usize {
+        if inputs.is_empty() {
+            return 0;
+        }
+        let recording = self.macro_recording.take();
+        self.replaying_macro = true;
+        self.macro_aborted = false;
+        self.history.stop_merging();
+        let grouped = self.history.begin_group();
+
+        let mut iterations = 0;
+        while iterations < count && !self.macro_aborted {
+            for input in inputs {
+                self.dispatch_input(input.clone());
+                if self.macro_aborted {
+                    break;
+                }
             }
+            iterations += 1;
+        }
 
-            Input {
-                key: Key::Up,
-                ctrl: true,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::ParagraphBack, shift);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        self.replaying_macro = false;
+        self.macro_recording = recording;
+        iterations
+    }
+
+    /// Perform the editor operation. This is what [`TextArea::input`] does for the action bound to the input in
+    /// [`TextArea::keymap`]. This method returns if the action modified text contents or not. Unlike inputs, actions
+    /// are performed even if the textarea is read-only.
+    /// ```
+    /// use tui_textarea::{CursorMove, EditAction, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["hello world"]);
+    ///
+    /// textarea.perform(EditAction::MoveCursor(CursorMove::End));
+    /// assert!(textarea.perform(EditAction::DeleteWord));
+    /// assert_eq!(textarea.lines(), ["hello "]);
+    ///
+    /// assert!(textarea.perform(EditAction::Undo));
+    /// assert_eq!(textarea.lines(), ["hello world"]);
+    /// ```
+    pub fn perform(&mut self, action: EditAction) -> bool {
+        if !self.secondary_cursors.is_empty() && action.is_per_cursor() {
+            return self.perform_at_cursors(action);
+        }
+        self.perform_at_cursor(action)
+    }
+
+    fn perform_at_cursor(&mut self, action: EditAction) -> bool {
+        match action {
+            EditAction::InsertChar(c) => self.insert_char(c),
+            EditAction::InsertNewline => self.insert_newline(),
+            EditAction::Indent => {
+                if self.selection_start.is_some() {
+                    self.indent_selection()
+                } else {
+                    self.insert_tab()
+                }
+            }
+            EditAction::Dedent => {
+                if self.selection_start.is_some() {
+                    self.dedent_selection()
+                } else {
+                    self.delete_tab_from_head()
+                }
+            }
+            EditAction::DeleteChar => self.delete_char(),
+            EditAction::DeleteNextChar => self.delete_next_char(),
+            EditAction::DeleteWord => self.delete_word(),
+            EditAction::DeleteNextWord => self.delete_next_word(),
+            EditAction::DeleteLine => self.delete_line(false),
+            EditAction::DeleteLineByEnd => self.delete_line_by_end(),
+            EditAction::DeleteLineByHead => self.delete_line_by_head(),
+            EditAction::DuplicateLines => self.duplicate_lines(),
+            EditAction::MoveLinesUp => self.move_lines_up(),
+            EditAction::MoveLinesDown => self.move_lines_down(),
+            EditAction::TransposeChars => self.transpose_chars(),
+            EditAction::TransposeWords => self.transpose_words(),
+            EditAction::MoveCursor(m) => {
+                self.move_cursor_with_shift(m, false);
                 false
             }
-            Input {
-                key: Key::Down,
-                ctrl: true,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::ParagraphForward, shift);
+            EditAction::MoveCursorSelecting(m) => {
+                self.move_cursor_with_shift(m, true);
                 false
             }
-            Input {
-                key: Key::Left,
-                ctrl: true,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::WordBack, shift);
+            EditAction::Scroll(scrolling) => {
+                self.scroll_with_shift(scrolling, false);
                 false
             }
-            Input {
-                key: Key::Right,
-                ctrl: true,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::WordForward, shift);
+            EditAction::ScrollSelecting(scrolling) => {
+                self.scroll_with_shift(scrolling, true);
                 false
             }
-
-            Input {
-                key: Key::Up,
-                ctrl: true,
-                alt: true,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Top, shift);
+            EditAction::ScrollViewport(rows) => {
+                self.viewport.scroll(rows, 0);
                 false
             }
-            Input {
-                key: Key::Down,
-                ctrl: true,
-                alt: true,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Bottom, shift);
+            EditAction::SelectAll => {
+                self.select_all();
                 false
             }
-
-            Input { key: Key::Home, shift, .. } => {
-                self.move_cursor_with_shift(CursorMove::Head, shift);
+            EditAction::Undo => self.undo(),
+            EditAction::Redo => self.redo(),
+            EditAction::Copy => {
+                self.copy();
                 false
             }
-            Input { key: Key::End, shift, .. } => {
-                self.move_cursor_with_shift(CursorMove::End, shift);
+            EditAction::Cut => self.cut(),
+            EditAction::Paste => self.paste(),
+            EditAction::PasteRotate => self.paste_rotate(),
+            EditAction::ToggleOverwriteMode => {
+                self.toggle_overwrite_mode();
                 false
             }
+            EditAction::Ignore => false,
+        }
+    }
 
-            Input {
-                key: key @ (Key::Up | Key::Down),
-                ctrl: false,
-                alt: true,
-                shift: false,
-            } => self.move_line(key),
-
-            Input {
-                key: Key::Char('z'),
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.undo(),
-            Input {
-                key: Key::Char('y'),
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.redo(),
+    /// Perform the editor operation `n` times as one operation, like numeric prefixes of Vim such as `12j` or `4dw`.
+    /// All edits are grouped into one undo unit. Deleting characters, words, and lines deletes the whole range at once
+    /// so that it is recorded as one edit, and a character is inserted `n` times as one string. Counts running past the
+    /// start or the end of the text are clamped there. When `n` is 0, nothing happens. This method returns if the text
+    /// was modified or not.
+    /// ```
+    /// use tui_textarea::{CursorMove, EditAction, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc ddd", "eee"]);
+    ///
+    /// // Delete "aaa", " ", "bbb", and " " as one edit
+    /// assert!(textarea.perform_n(EditAction::DeleteNextWord, 4));
+    /// assert_eq!(textarea.lines(), ["ccc ddd", "eee"]);
+    ///
+    /// // 100j stops at the last line
+    /// textarea.perform_n(EditAction::MoveCursor(CursorMove::Down), 100);
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// // Undo the deletion at once
+    /// assert!(textarea.undo());
+    /// assert_eq!(textarea.lines(), ["aaa bbb ccc ddd", "eee"]);
+    /// ```
+    pub fn perform_n(&mut self, action: EditAction, n: usize) -> bool {
+        if n <= 1 {
+            return n == 1 && self.perform(action);
+        }
+        self.history.stop_merging();
+        let grouped = self.history.begin_group();
+        let modified = if !self.secondary_cursors.is_empty() && action.is_per_cursor() {
+            (0..n).fold(false, |modified, _| self.perform(action) || modified)
+        } else {
+            self.perform_repeated(action, n)
+        };
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        modified
+    }
 
-            Input {
-                key: Key::Char('v'),
-                ctrl: true,
-                alt: false,
-                ..
+    fn perform_repeated(&mut self, action: EditAction, n: usize) -> bool {
+        let selecting = self.selection_start.is_some();
+        match action {
+            EditAction::InsertChar(c) if c != '\n' && c != '\r' && !self.overwrite_mode => {
+                let n = n.min(self.remaining_chars());
+                self.insert_str(iter::repeat(c).take(n).collect::<String>())
             }
-            | Input { key: Key::Paste, .. } => self.paste(),
-            Input {
-                key: Key::Char('x'),
-                ctrl: true,
-                alt: false,
-                ..
+            EditAction::DeleteChar
+            | EditAction::DeleteWord
+            | EditAction::DeleteNextChar
+            | EditAction::DeleteNextWord
+                if !selecting =>
+            {
+                self.delete_repeated(action, n)
             }
-            | Input { key: Key::Cut, .. } => self.delete_line(true),
-            Input {
-                key: Key::Char('c'),
-                ctrl: true,
-                alt: false,
-                ..
+            EditAction::DeleteLine | EditAction::Cut if !selecting && !self.single_line => {
+                let modified = self.delete_lines(n, action == EditAction::Cut);
+                if modified && action == EditAction::Cut {
+                    self.sync_clipboard();
+                }
+                modified
             }
-            | Input { key: Key::Copy, .. } => {
-                self.copy();
+            EditAction::Scroll(Scrolling::Delta { rows, cols })
+            | EditAction::ScrollSelecting(Scrolling::Delta { rows, cols }) => {
+                let n = i64::try_from(n).unwrap_or(i64::MAX);
+                let scrolling = Scrolling::Delta {
+                    rows: rows.saturating_mul(n),
+                    cols: cols.saturating_mul(n),
+                };
+                self.scroll_with_shift(scrolling, matches!(action, EditAction::ScrollSelecting(_)));
                 false
             }
-
-            Input { key: Key::PageDown, shift, .. } => {
-                self.scroll_with_shift(Scrolling::PageDown, shift);
+            EditAction::ScrollViewport(rows) => {
+                let n = i64::try_from(n).unwrap_or(i64::MAX);
+                self.viewport.scroll(rows.saturating_mul(n), 0);
                 false
             }
-            Input { key: Key::PageUp, shift, .. } => {
-                self.scroll_with_shift(Scrolling::PageUp, shift);
-                false
+            _ => {
+                let mut modified = false;
+                for _ in 0..n {
+                    let cursor = self.cursor;
+                    let m = self.perform(action);
+                    modified |= m;
+                    if !m && self.cursor == cursor {
+                        break; // Repeating the action no longer has any effect
+                    }
+                }
+                modified
             }
+        }
+    }
 
-            Input {
-                key: key @ (Key::Up | Key::Down),
-                ctrl: false,
-                alt: true,
-                shift: true,
-            } => {
-                let rows = match key {
-                    Key::Up => -1,
-                    Key::Down => 1,
-                    _ => 0,
-                };
-                self.viewport.scroll(rows, 0);
-                false
-            }
+    // Delete characters or words `n` times from the cursor as one range
+    fn delete_repeated(&mut self, action: EditAction, n: usize) -> bool {
+        let forward = matches!(action, EditAction::DeleteNextChar | EditAction::DeleteNextWord);
+        let line_len = |row: usize| self.lines.char_count(row);
+        let mut pos = self.cursor;
+        for _ in 0..n {
+            let (row, col) = pos;
+            let line = &self.lines.line(row);
+            let next = match action {
+                EditAction::DeleteNextWord => find_word_end_forward(line, col).map(|c| (row, c)),
+                EditAction::DeleteWord => find_word_start_backward(line, col).map(|c| (row, c)),
+                _ => None,
+            };
+            pos = match next {
+                Some(next) => next,
+                None if forward && col < line_len(row) => {
+                    if action == EditAction::DeleteNextWord {
+                        (row, line_len(row))
+                    } else {
+                        (row, col + 1)
+                    }
+                }
+                None if forward && row + 1 < self.lines.len() => (row + 1, 0),
+                None if !forward && col > 0 => {
+                    if action == EditAction::DeleteWord {
+                        (row, 0)
+                    } else {
+                        (row, col - 1)
+                    }
+                }
+                None if !forward && row > 0 => (row - 1, line_len(row - 1)),
+                None => break,
+            };
+        }
+        if pos == self.cursor {
+            return false;
+        }
 
-            Input { key: Key::Char(c), .. } => {
-                self.insert_char(c);
-                true
-            }
-            _ => false,
+        let (start, end) = if forward {
+            (self.cursor, pos)
+        } else {
+            (pos, self.cursor)
         };
+        let kill = match action {
+            EditAction::DeleteNextWord => Some(Kill::Forward),
+            EditAction::DeleteWord => Some(Kill::Backward),
+            _ => None,
+        };
+        let start = self.pos_at(start);
+        let end = self.pos_at(end);
+        self.delete_range(start, end, kill);
+        true
+    }
 
-        // Check invariants
-        debug_assert!(!self.lines.is_empty(), "no line after {:?}", input);
-        let (r, c) = self.cursor;
-        debug_assert!(
-            self.lines.len() > r,
-            "cursor {:?} exceeds max lines {} after {:?}",
-            self.cursor,
-            self.lines.len(),
-            input,
-        );
-        debug_assert!(
-            self.lines[r].chars().count() >= c,
-            "cursor {:?} exceeds max col {} at line {:?} after {:?}",
-            self.cursor,
-            self.lines[r].chars().count(),
-            self.lines[r],
-            input
-        );
+    // Delete `n` lines from the cursor line as one range
+    fn delete_lines(&mut self, n: usize, yank: bool) -> bool {
+        let (row, col) = self.cursor;
+        let len = self.lines.len();
+        if len == 1 && self.lines.line_len(0) == 0 {
+            return false;
+        }
+        let last = row.saturating_add(n).min(len);
+        let end_of = |row: usize| (row, self.lines.char_count(row));
+        let (start, end) = if last < len {
+            ((row, 0), (last, 0))
+        } else if row > 0 {
+            (end_of(row - 1), end_of(len - 1))
+        } else {
+            ((0, 0), end_of(len - 1))
+        };
+        // Like cutting one line, the lines are yanked without the newline after the last one
+        if yank {
+            self.kill(self.lines.to_vec_in(row..last).into(), Kill::Region);
+        }
+        let start = self.pos_at(start);
+        let end = self.pos_at(end);
+        self.delete_range(start, end, None);
+        let row = self.cursor.0.min(row);
+        self.cursor = (row, col.min(self.lines.char_count(row)));
+        true
+    }
 
-        modified
+    fn pos_at(&self, (row, col): (usize, usize)) -> Pos {
+        let line = &self.lines.line(row);
+        let offset = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+        Pos::new(row, col, offset)
     }
 
-    /// Handle a key input without default key mappings. This method handles only
-    ///
-    /// - Single character input without modifier keys
-    /// - Tab
-    /// - Enter
-    /// - Backspace
-    /// - Delete
-    ///
-    /// This method returns if the input modified text contents or not in the textarea.
-    ///
-    /// This method is useful when you want to define your own key mappings and don't want default key mappings.
-    /// See 'Define your own key mappings' section in [the module document](./index.html).
-    pub fn input_without_shortcuts(&mut self, input: impl Into<Input>) -> bool {
-        match input.into() {
-            Input {
-                key: Key::Char(c),
-                ctrl: false,
-                alt: false,
-                ..
-            } => {
-                self.insert_char(c);
-                true
-            }
-            Input { key: Key::Backspace, .. } => self.delete_char(),
-            Input { key: Key::Delete, .. } => self.delete_next_char(),
-            _ => false,
+    // Record the input handled by the textarea in the macro being recorded. Inputs ignored by the textarea are skipped
+    fn record_input(&mut self, input: &Input) {
+        if self.ignores_input() || input.key.is_ignored() {
+            return;
+        }
+        if let Some(recording) = &mut self.macro_recording {
+            recording.push(input.clone());
         }
     }
 
-    pub fn single_line_input(&mut self, input: impl Into<Input>) -> bool {
-        match input.into() {
-            Input {
-                key: Key::Backspace,
-                ctrl: false,
-                alt: false,
-                ..
-            } => self.delete_char(),
-            Input {
-                key: Key::Backspace,
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.delete_word(),
-            Input {
-                key: Key::Delete,
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.delete_next_word(),
-            Input {
-                key: Key::Delete,
-                ctrl: false,
-                alt: false,
-                ..
-            } => self.delete_next_char(),
-            Input {
-                key: Key::Char('k'),
-                ctrl: true,
-                alt: false,
-                shift: false,
-            } => self.delete_line_by_end(),
-            Input {
-                key: Key::Char('j'),
-                ctrl: true,
-                alt: false,
-                shift: false,
-            } => self.delete_line_by_head(),
+    fn ignores_input(&self) -> bool {
+        !self.focused && self.ignore_input_unfocused
+    }
 
-            Input {
-                key: Key::Char('a'),
-                ctrl: true,
-                alt: false,
-                shift: false,
-            } => {
-                self.select_all();
-                false
+    // Action of the input looked up in the keymap. A character not bound in the keymap is inserted regardless of its
+    // modifier keys
+    fn input_action(&self, input: &Input) -> Option<EditAction> {
+        if let Some(action) = self.keymap.get(input) {
+            return Some(*action);
+        }
+        match *input {
+            Input { key: Key::Char(c), .. } => Some(EditAction::InsertChar(c)),
+            _ => None,
+        }
+    }
+
+    // Perform the action of an input. Actions modifying the text are ignored while read-only, and cut is handled as copy
+    fn perform_input(&mut self, action: EditAction) -> bool {
+        if self.read_only && action.modifies_text() {
+            if action == EditAction::Cut {
+                self.copy();
+            }
+            return false;
+        }
+        let cursor = self.cursor;
+        let modified = self.perform(action);
+        if self.replaying_macro && self.cursor == cursor {
+            // Relative cursor movement failed. Stop replaying the macro like Vim
+            if let EditAction::MoveCursor(m) | EditAction::MoveCursorSelecting(m) = action {
+                self.macro_aborted |= m.is_relative();
             }
+        }
+        modified
+    }
 
-            Input {
-                key: Key::Left,
-                ctrl: false,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Back, shift);
-                false
+    // Perform the actions of inputs in order. Multi-line actions are skipped in single-line input
+    fn perform_inputs(&mut self, actions: Vec<EditAction>, single_line: bool) -> bool {
+        let mut modified = false;
+        for action in actions {
+            if !single_line || !action.is_multi_line() {
+                modified |= self.perform_input(action);
             }
-            Input {
-                key: Key::Right,
-                ctrl: false,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::Forward, shift);
-                false
+        }
+        modified
+    }
+
+    // Look up the keys in the keymaps. Returns the action bound to the keys and if they are a prefix of a longer key
+    // sequence
+    fn lookup_keys(&self, keys: &[Input]) -> (Option<EditAction>, bool) {
+        let action = match (self.key_sequences.get(keys), keys) {
+            (Some(action), _) => Some(*action),
+            (None, [input]) => self.input_action(input),
+            (None, _) => None,
+        };
+        let prefix = self
+            .key_sequences
+            .keys()
+            .any(|seq| seq.len() > keys.len() && seq.starts_with(keys));
+        (action, prefix)
+    }
+
+    // Take the pending keys and return the actions to resolve them: the action bound to the keys, or actions of
+    // replayed keys
+    fn resolve_pending_keys(&mut self) -> Vec<EditAction> {
+        let keys = std::mem::take(&mut self.pending_keys);
+        self.pending_since = None;
+        if keys.is_empty() {
+            return vec![];
+        }
+        if let (Some(action), _) = self.lookup_keys(&keys) {
+            return vec![action];
+        }
+        if !self.replay_pending_keys {
+            return vec![];
+        }
+        keys.iter().filter_map(|input| self.input_action(input)).collect()
+    }
+
+    // Feed the key to the pending key sequence and return the actions to perform
+    fn feed_key(&mut self, input: Input) -> Vec<EditAction> {
+        let mut actions = vec![];
+        let expired = match (self.pending_since, self.pending_keys_timeout) {
+            (Some(since), Some(timeout)) => since.elapsed() >= timeout,
+            _ => false,
+        };
+        if expired {
+            actions.extend(self.resolve_pending_keys());
+        }
+
+        self.pending_keys.push(input);
+        loop {
+            let (action, prefix) = self.lookup_keys(&self.pending_keys);
+            if prefix {
+                // Wait for the next key even if the keys complete a binding
+                self.pending_since = Some(Instant::now());
+                return actions;
             }
-            Input {
-                key: Key::Left,
-                ctrl: true,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::WordBack, shift);
-                false
+            if let Some(action) = action {
+                actions.push(action);
+                self.cancel_pending_keys();
+                return actions;
             }
-            Input {
-                key: Key::Right,
-                ctrl: true,
-                alt: false,
-                shift,
-            } => {
-                self.move_cursor_with_shift(CursorMove::WordForward, shift);
-                false
+            if self.pending_keys.len() == 1 {
+                // The key is not bound to anything
+                self.cancel_pending_keys();
+                return actions;
             }
+            let last = self.pending_keys.pop().unwrap();
+            // The key stops matching. Resolve the keys before it and handle the key as a new input
+            actions.extend(self.resolve_pending_keys());
+            self.pending_keys.push(last);
+        }
+    }
 
-            Input { key: Key::Home, shift, .. } => {
-                self.move_cursor_with_shift(CursorMove::Head, shift);
-                false
+    fn push_change(&mut self, edit: Edit) {
+        edit.redo(&mut self.lines);
+        self.cursor = edit.cursor_after();
+        self.record(edit);
+    }
+
+    // Record the edit applied to the text. Edits made by methods are recorded even while read-only since only inputs
+    // modifying the text are ignored, and the older edits must remain undoable
+    fn record(&mut self, edit: Edit) {
+        // Kills are merged only when no other edit was made between them
+        self.last_kill = self.killing.take();
+        self.last_paste = None;
+        self.describe_edit(&edit);
+        self.line_offsets.get_mut().invalidate(edit.first_row());
+        self.open_fold_at_cursor();
+        self.ghost_text = None;
+        self.yank_flashed.take();
+        self.history.push(edit);
+    }
+
+    // Describe the edit applied to the text for edit tracking and for positions which must follow the text
+    fn describe_edit(&mut self, edit: &Edit) {
+        if !self.tracks_edits() {
+            return;
+        }
+        let mut described = vec![];
+        edit.describe(&self.lines, &mut described);
+        for e in described {
+            self.on_text_edit(e);
+        }
+    }
+
+    // Edits are described as `TextEdit`s only while something follows them
+    fn tracks_edits(&self) -> bool {
+        #[cfg(feature = "search")]
+        if self.search.scope.is_some() {
+            return true;
+        }
+        self.edits.is_some()
+            || !self.marks.is_empty()
+            || !self.highlights.is_empty()
+            || !self.signs.is_empty()
+            || !self.folds.is_empty()
+            || self.snippet.is_some()
+            || !self.auto_closers.is_empty()
+            || !self.secondary_cursors.is_empty()
+            || !self.stats.borrow().is_empty()
+            || !self.render_cache.borrow().is_empty()
+            || !self.cells.borrow().is_empty()
+    }
+
+    fn on_text_edit(&mut self, edit: TextEdit) {
+        for c in &mut self.secondary_cursors {
+            c.cursor = edit.shift_pos(c.cursor).unwrap_or(edit.start);
+            c.anchor = c.anchor.map(|a| edit.shift_pos(a).unwrap_or(edit.start));
+        }
+        self.marks.retain(|_, pos| match edit.shift_pos(*pos) {
+            Some(p) => {
+                *pos = p;
+                true
             }
-            Input { key: Key::End, shift, .. } => {
-                self.move_cursor_with_shift(CursorMove::End, shift);
-                false
+            None => false,
+        });
+        self.highlights
+            .retain_mut(|h| match edit.shift_range((h.start, h.end)) {
+                Some((start, end)) => {
+                    h.start = start;
+                    h.end = end;
+                    true
+                }
+                None => false,
+            });
+        if !self.signs.is_empty() && (edit.start.0 != edit.end.0 || edit.inserted.contains('\n')) {
+            let mut signs = BTreeMap::new();
+            for (row, sign) in std::mem::take(&mut self.signs) {
+                // When lines are joined, the sign of the upper line is kept
+                if let Some((row, _)) = edit.shift_pos((row, 0)) {
+                    signs.entry(row).or_insert(sign);
+                }
             }
+            self.signs = signs;
+        }
+        if !self.folds.is_empty() && (edit.start.0 != edit.end.0 || edit.inserted.contains('\n')) {
+            self.folds
+                .retain_mut(|f| match (edit.shift_pos((f.start, 0)), edit.shift_pos((f.end, 0))) {
+                    (Some((start, _)), Some((end, _))) if start < end => {
+                        (f.start, f.end) = (start, end);
+                        true
+                    }
+                    _ => false,
+                });
+        }
+        // A closing character is forgotten once it is deleted
+        self.auto_closers
+            .retain_mut(|pos| match edit.shift_range((*pos, (pos.0, pos.1 + 1))) {
+                Some((start, _)) => {
+                    *pos = start;
+                    true
+                }
+                None => false,
+            });
+        if self.snippet.as_mut().map_or(false, |s| !s.on_edit(&edit)) {
+            self.snippet = None;
+        }
+        self.stats.get_mut().on_edit(&edit);
+        self.render_cache.get_mut().on_edit(&edit);
+        self.cells.get_mut().on_edit(&edit);
+        #[cfg(feature = "search")]
+        if let Some(scope) = self.search.scope {
+            // Text typed in the scope is searched as well
+            self.search.scope = Some(edit.shift_range_inclusive(scope));
+        }
+        if let Some(edits) = &mut self.edits {
+            edits.push(edit);
+        }
+    }
 
-            Input {
-                key: Key::Char('z'),
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.undo(),
-            Input {
-                key: Key::Char('y'),
-                ctrl: true,
-                alt: false,
-                ..
-            } => self.redo(),
+    #[cfg(feature = "search")]
+    fn push_batch(&mut self, edits: Vec<Edit>, before: Pos, after: Pos) {
+        self.push_change(Edit::new(EditKind::Batch(edits), before, after));
+    }
 
-            Input {
-                key: Key::Char('v'),
-                ctrl: true,
-                alt: false,
-                ..
+    #[cfg(feature = "search")]
+    fn cursor_pos(&self) -> Pos {
+        let (row, col) = self.cursor;
+        Pos::new(row, col, self.lines.offset_of_col(row, col))
+    }
+
+    fn push_history(&mut self, kind: EditKind, before: Pos, after_offset: usize) {
+        let (row, col) = self.cursor;
+        let after = Pos::new(row, col, after_offset);
+        let edit = Edit::new(kind, before, after);
+        self.record(edit);
+    }
+
+    /// Insert a single character at current cursor position. This method returns if the character was inserted or
+    /// not. It is not inserted when the number of characters reaches the limit set by [`TextArea::set_max_chars`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.insert_char('a');
+    /// assert_eq!(textarea.lines(), ["a"]);
+    /// ```
+    pub fn insert_char(&mut self, c: char) -> bool {
+        if c == '\n' || c == '\r' {
+            return self.insert_newline();
+        }
+        if self.overwrite_mode && self.selection_start.is_none() {
+            let (row, col) = self.cursor;
+            if col < self.lines.char_count(row) {
+                // Replace the character under the cursor. At the end of line, the character is appended as usual
+                self.replace_in_line(row, col, col + 1, c.encode_utf8(&mut [0; 4]));
+                if self.mask_reveal_last {
+                    self.mask_revealed.set(Some(((row, col), self.mask_reveal_renders)));
+                }
+                return true;
             }
-            | Input { key: Key::Paste, .. } => self.paste(),
-            Input {
-                key: Key::Char('x'),
-                ctrl: true,
-                alt: false,
-                ..
+        }
+        if self.auto_pairs {
+            if let Some(modified) = self.insert_auto_pair(c) {
+                return modified;
+            }
+        }
+
+        let modified = self.replace_selection();
+        if self.remaining_chars() == 0 {
+            return modified;
+        }
+        let (row, col) = self.cursor;
+        let i = self.lines.offset_of_col(row, col);
+        self.lines.insert_char(row, i, c);
+        self.cursor.1 += 1;
+        self.push_history(EditKind::InsertChar(c), Pos::new(row, col, i), i + c.len_utf8());
+        if self.mask_reveal_last {
+            self.mask_revealed.set(Some(((row, col), self.mask_reveal_renders)));
+        }
+        true
+    }
+
+    // Handle the character typed with auto-pairs. `None` is returned when the character should be inserted as usual
+    fn insert_auto_pair(&mut self, c: char) -> Option<bool> {
+        let (row, col) = self.cursor;
+        let next = self.lines.char_at(row, self.lines.offset_of_col(row, col));
+
+        // Type over the closing character inserted automatically
+        if self.selection_start.is_none() && next == Some(c) && self.auto_closers.last() == Some(&self.cursor) {
+            self.auto_closers.pop();
+            self.cursor.1 += 1;
+            self.history.stop_merging();
+            self.ghost_text = None;
+            return Some(false);
+        }
+
+        let &(open, close) = self.auto_pair_chars.iter().find(|(o, _)| *o == c)?;
+
+        // Wrap the selection with the pair keeping the selection on the wrapped text
+        if let Some((start, end)) = self.selection_range() {
+            let cursor_at_end = self.cursor == end;
+            let grouped = self.history.begin_group();
+            self.selection_start = None;
+            self.cursor = end;
+            self.insert_str(close.encode_utf8(&mut [0; 4]));
+            self.cursor = start;
+            self.insert_str(open.encode_utf8(&mut [0; 4]));
+            if grouped {
+                self.history.end_group();
             }
-            | Input { key: Key::Cut, .. } => self.delete_line(true),
+            let start = (start.0, start.1 + 1);
+            let end = if end.0 == start.0 { (end.0, end.1 + 1) } else { end };
+            let (anchor, cursor) = if cursor_at_end { (start, end) } else { (end, start) };
+            self.selection_start = Some(anchor);
+            self.cursor = cursor;
+            return Some(true);
+        }
+
+        // Do not close the pair before a word, or a quote after a word
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let prev = col
+            .checked_sub(1)
+            .and_then(|i| self.lines.char_at(row, self.lines.offset_of_col(row, i)));
+        if next.map_or(false, is_word) || open == close && prev.map_or(false, is_word) {
+            return None;
+        }
+        if !self.insert_str(format!("{open}{close}")) {
+            return Some(false);
+        }
+        if self.cursor == (row, col + 2) {
+            self.cursor.1 -= 1;
+            self.auto_closers.push(self.cursor);
+        }
+        Some(true)
+    }
+
+    /// Insert the character of the Unicode code point at current cursor position as one undo unit. This method returns
+    /// if the character was inserted or not. Surrogates and values out of the range of Unicode scalar values are
+    /// rejected.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert!(textarea.insert_unicode(0x3b1));
+    /// assert!(textarea.insert_unicode(0x1f600));
+    /// assert_eq!(textarea.lines(), ["α😀"]);
+    ///
+    /// assert!(!textarea.insert_unicode(0xd800));
+    /// assert!(!textarea.insert_unicode(0x110000));
+    /// ```
+    pub fn insert_unicode(&mut self, codepoint: u32) -> bool {
+        let Some(c) = char::from_u32(codepoint) else {
+            return false;
+        };
+        self.history.stop_merging();
+        let grouped = self.history.begin_group();
+        let modified = self.insert_str(c.encode_utf8(&mut [0; 4]));
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        modified
+    }
+
+    /// Start composing a character by its code point with key inputs like `Ctrl+Shift+U` in terminals. While composing,
+    /// [`TextArea::input`] accumulates hexadecimal digits and shows them with `u` prefix as ghost text at the cursor.
+    /// `Backspace` deletes the last digit and `Esc` cancels composing. `Enter` commits the character, and any other
+    /// key commits it and is then handled as usual. See [`TextArea::insert_unicode`] for inserting the character.
+    /// ```
+    /// use tui_textarea::{Input, Key, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let key = |key| Input { key, ..Default::default() };
+    ///
+    /// textarea.start_unicode_input();
+    /// for c in "3b1".chars() {
+    ///     textarea.input(key(Key::Char(c)));
+    /// }
+    /// assert_eq!(textarea.unicode_input(), Some("3b1"));
+    /// assert_eq!(textarea.lines(), [""]);
+    ///
+    /// // Enter commits the character
+    /// assert!(textarea.input(key(Key::Enter)));
+    /// assert_eq!(textarea.unicode_input(), None);
+    /// assert_eq!(textarea.lines(), ["α"]);
+    ///
+    /// // A non-hex key commits the character and is inserted after it
+    /// textarea.start_unicode_input();
+    /// textarea.input(key(Key::Char('3')));
+    /// textarea.input(key(Key::Char('2')));
+    /// textarea.input(key(Key::Char('x')));
+    /// assert_eq!(textarea.lines(), ["α2x"]);
+    /// ```
+    pub fn start_unicode_input(&mut self) {
+        self.unicode_input = Some("u".to_string());
+    }
+
+    /// Cancel composing a character started by [`TextArea::start_unicode_input`]. The typed digits are discarded.
+    pub fn cancel_unicode_input(&mut self) {
+        self.unicode_input = None;
+    }
+
+    /// Commit the character composed since [`TextArea::start_unicode_input`] and stop composing. This method returns
+    /// if the character was inserted or not. Nothing is inserted when no digit was typed or the digits are not a valid
+    /// code point.
+    pub fn commit_unicode_input(&mut self) -> bool {
+        let Some(pending) = self.unicode_input.take() else {
+            return false;
+        };
+        u32::from_str_radix(&pending[1..], 16).map_or(false, |c| self.insert_unicode(c))
+    }
+
+    /// Get the hexadecimal digits typed while composing a character. `None` is returned when not composing. See
+    /// [`TextArea::start_unicode_input`].
+    pub fn unicode_input(&self) -> Option<&str> {
+        self.unicode_input.as_deref().map(|s| &s[1..])
+    }
+
+    // Handle the input while composing a character. `None` is returned when the input commits the character and then
+    // should be handled as usual
+    fn compose_unicode(&mut self, input: &Input) -> Option<bool> {
+        let pending = self.unicode_input.as_mut()?;
+        match input {
             Input {
-                key: Key::Char('c'),
-                ctrl: true,
+                key: Key::Char(c),
+                ctrl: false,
                 alt: false,
                 ..
+            } if c.is_ascii_hexdigit() => {
+                // The largest code point U+10FFFF has 6 digits
+                if pending.len() <= 6 {
+                    pending.push(*c);
+                }
+                Some(false)
             }
-            | Input { key: Key::Copy, .. } => {
-                self.copy();
-                false
+            Input { key: Key::Backspace, .. } => {
+                pending.pop();
+                if pending.is_empty() {
+                    self.unicode_input = None;
+                }
+                Some(false)
             }
-            Input { key: Key::Char(c), .. } => {
-                self.insert_char(c);
-                true
+            Input { key: Key::Esc, .. } => {
+                self.unicode_input = None;
+                Some(false)
             }
-            _ => false,
+            Input { key: Key::Enter, .. } => Some(self.commit_unicode_input()),
+            _ => None,
         }
     }
 
-    fn push_change(&mut self, edit: Edit) {
-        self.history.push(edit);
-        self.cursor = self.history.apply(&mut self.lines);
+    /// Insert a string at current cursor position. This method returns if some text was inserted or not in the textarea.
+    /// Both `\n` and `\r\n` are recognized as newlines but `\r` isn't.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.insert_str("hello");
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    ///
+    /// textarea.insert_str(", world\ngoodbye, world");
+    /// assert_eq!(textarea.lines(), ["hello, world", "goodbye, world"]);
+    /// ```
+    pub fn insert_str<S: AsRef<str>>(&mut self, s: S) -> bool {
+        let modified = self.replace_selection();
+        let lines: Vec<_> = s
+            .as_ref()
+            .split('\n')
+            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+            .collect();
+        self.insert_lines(lines) || modified
+    }
+
+    /// Insert the text read from the reader at the cursor and return the number of the read lines. A last line without
+    /// a line ending is also counted. `\n`, `\r\n`, and `\r` are all recognized as line endings. The text is read line
+    /// by line into one reused buffer, so inserting a large text does not make an extra copy of the whole text. The
+    /// insertion replaces the selection and is undone at once. The cursor moves to the end of the inserted text.
+    ///
+    /// An error is returned when reading fails or the text is not valid UTF-8. In that case, the text in the textarea is
+    /// not modified.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["ab"]);
+    /// textarea.move_cursor(tui_textarea::CursorMove::Forward);
+    ///
+    /// let n = textarea.insert_from_reader(&mut "x\r\ny\rz\n".as_bytes()).unwrap();
+    /// assert_eq!(n, 3);
+    /// assert_eq!(textarea.lines(), ["ax", "y", "z", "b"]);
+    /// assert_eq!(textarea.cursor(), (3, 0));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["ab"]);
+    /// ```
+    pub fn insert_from_reader(&mut self, reader: &mut impl BufRead) -> io::Result<usize> {
+        let mut buf = Vec::new();
+        let mut lines = Vec::new();
+        let mut ends_in_newline = false;
+        loop {
+            buf.clear();
+            if reader.read_until(b'\n', &mut buf)? == 0 {
+                break;
+            }
+            ends_in_newline = buf.ends_with(b"\n");
+            if ends_in_newline {
+                buf.pop();
+                if buf.ends_with(b"\r") {
+                    buf.pop();
+                }
+            }
+            let line = std::str::from_utf8(&buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            lines.extend(line.split('\r').map(String::from));
+        }
+
+        // A lone `\r` at the end of the text is also a line ending
+        if !ends_in_newline && lines.last().map_or(false, String::is_empty) {
+            lines.pop();
+            ends_in_newline = true;
+        }
+        let count = lines.len();
+        if count == 0 {
+            return Ok(0);
+        }
+        if ends_in_newline {
+            lines.push(String::new());
+        }
+
+        let grouped = self.history.begin_group();
+        self.replace_selection();
+        self.insert_lines(lines);
+        if grouped {
+            self.history.end_group();
+        }
+        Ok(count)
+    }
+
+    /// Insert the content of the file at the path at the cursor and return the number of the read lines. See
+    /// [`TextArea::insert_from_reader`] for more details.
+    pub fn insert_file(&mut self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let file = fs::File::open(path)?;
+        self.insert_from_reader(&mut io::BufReader::new(file))
+    }
+
+    /// Insert a snippet at the cursor and start visiting its tab stops. A subset of the LSP snippet syntax is supported:
+    /// `$1`, `$2`, ... are tab stops, `${1:default}` is a tab stop with the default text, and `$0` is the final cursor
+    /// position. `\$`, `\}`, and `\\` escape the characters. Only the first tab stop of the same number is visited.
+    ///
+    /// Lines after the first one are indented as the cursor line, and tabs at their start are replaced with
+    /// [`TextArea::indent`]. After the insertion, the default text of the first tab stop is selected so that typing
+    /// replaces it. [`TextArea::snippet_next`] and [`TextArea::snippet_prev`] move between the tab stops. The tab stops
+    /// follow the text typed in the current tab stop, and any edit outside it ends visiting the tab stops. The
+    /// insertion is undone at once. This method returns `false` when nothing was inserted.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["    "]);
+    /// textarea.move_cursor(tui_textarea::CursorMove::End);
+    ///
+    /// textarea.insert_snippet("if ${1:cond} {\n\t$0\n}");
+    /// assert_eq!(textarea.lines(), ["    if cond {", "        ", "    }"]);
+    /// assert_eq!(textarea.selection_range(), Some(((0, 7), (0, 11))));
+    ///
+    /// // Typing replaces the default text
+    /// textarea.insert_str("x > 0");
+    /// assert_eq!(textarea.lines()[0], "    if x > 0 {");
+    ///
+    /// assert!(textarea.snippet_next());
+    /// assert_eq!(textarea.cursor(), (1, 8));
+    /// assert!(!textarea.snippet_next());
+    /// ```
+    pub fn insert_snippet(&mut self, snippet: &str) -> bool {
+        self.snippet = None;
+        // The snippet replacing the selection is inserted in one undo unit
+        let grouped = self.history.begin_group();
+        let deleted = self.replace_selection();
+        let (row, col) = self.cursor;
+        let line = &self.lines.line(row);
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let offset = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+        let indent = line[..min(indent_len, offset)].to_string();
+        let expanded = snippet::expand(snippet, &indent, self.indent());
+        let inserted = self.insert_str(&expanded.text);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+
+        // Tab stops are not visited when the text was changed on the insertion, e.g. by the limit of characters
+        let to_text_pos = |(r, c): (usize, usize)| if r == 0 { (row, col + c) } else { (row + r, c) };
+        let end = expanded.text.split('\n').enumerate().last().unwrap();
+        if !inserted || self.cursor != to_text_pos((end.0, end.1.chars().count())) {
+            return inserted || deleted;
+        }
+        let stops: Vec<_> = expanded
+            .stops
+            .into_iter()
+            .map(|(s, e)| (to_text_pos(s), to_text_pos(e)))
+            .collect();
+        if stops.len() == 1 {
+            self.select_snippet_stop(stops[0]);
+        } else {
+            let snippet = Snippet::new(stops);
+            self.select_snippet_stop(snippet.active());
+            self.snippet = Some(snippet);
+        }
+        true
+    }
+
+    /// Move to the next tab stop of the snippet inserted by [`TextArea::insert_snippet`] and select its text. When the
+    /// cursor is already at the last tab stop (`$0` or the end of the snippet), this method ends visiting the tab stops
+    /// and returns `false`. `false` is also returned when no snippet is being visited.
+    pub fn snippet_next(&mut self) -> bool {
+        match self.snippet.as_mut().and_then(Snippet::next) {
+            Some(stop) => {
+                self.select_snippet_stop(stop);
+                true
+            }
+            None => {
+                self.snippet = None;
+                false
+            }
+        }
+    }
+
+    /// Move to the previous tab stop of the snippet inserted by [`TextArea::insert_snippet`] and select its text. This
+    /// method returns `false` when the cursor is at the first tab stop or no snippet is being visited.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.insert_snippet("${1:a}, ${2:b}");
+    /// assert!(!textarea.snippet_prev());
+    /// assert!(textarea.snippet_next());
+    /// assert_eq!(textarea.selection_range(), Some(((0, 3), (0, 4))));
+    /// assert!(textarea.snippet_prev());
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (0, 1))));
+    /// ```
+    pub fn snippet_prev(&mut self) -> bool {
+        match self.snippet.as_mut().and_then(Snippet::prev) {
+            Some(stop) => {
+                self.select_snippet_stop(stop);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check if the tab stops of a snippet inserted by [`TextArea::insert_snippet`] are being visited.
+    pub fn is_snippet_active(&self) -> bool {
+        self.snippet.is_some()
+    }
+
+    fn select_snippet_stop(&mut self, (start, end): ((usize, usize), (usize, usize))) {
+        self.history.stop_merging();
+        self.ghost_text = None;
+        self.selection_start = (start != end).then_some(start);
+        self.selection_mode = SelectionMode::Char;
+        self.cursor = end;
+        self.open_fold_at_cursor();
+    }
+
+    /// Get the word before the cursor and its start column, which is the prefix to complete by autocompletion. A word
+    /// consists of characters which are neither whitespaces nor ASCII punctuations as [`CursorMove::WordBack`]. An
+    /// empty string is returned with the cursor column when the character before the cursor is not in a word, such as
+    /// at the head of a line or after a punctuation.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["let x = foo.ba"]);
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    /// assert_eq!(textarea.word_before_cursor(), ("ba".into(), 12));
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 12));
+    /// assert_eq!(textarea.word_before_cursor(), ("".into(), 12));
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 10));
+    /// assert_eq!(textarea.word_before_cursor(), ("fo".into(), 8));
+    /// ```
+    pub fn word_before_cursor(&self) -> (Cow<'_, str>, usize) {
+        let (row, col) = self.cursor;
+        let start = find_word_prefix_start(&self.lines.line(row), col);
+        let range = self.lines.offset_of_col(row, start)..self.lines.offset_of_col(row, col);
+        (self.lines.slice(row, range), start)
+    }
+
+    /// Replace the word before the cursor returned by [`TextArea::word_before_cursor`] with the completion and put the
+    /// cursor after it. When no word is before the cursor, the completion is inserted at the cursor. The selection is
+    /// cancelled and the replacement is one undo unit. This method returns `false` when nothing was modified.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["foo.ba()"]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 6));
+    ///
+    /// assert!(textarea.complete_word("bar"));
+    /// assert_eq!(textarea.lines(), ["foo.bar()"]);
+    /// assert_eq!(textarea.cursor(), (0, 7));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["foo.ba()"]);
+    /// ```
+    pub fn complete_word(&mut self, completion: &str) -> bool {
+        let (prefix, start) = self.word_before_cursor();
+        if prefix.is_empty() && completion.is_empty() {
+            return false;
+        }
+        let (row, col) = self.cursor;
+        let line = &self.lines.line(row);
+        let end = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+        let start = Pos::new(row, start, end - prefix.len());
+        let end = Pos::new(row, col, end);
+
+        self.cancel_selection();
+        let grouped = self.history.begin_group();
+        if start.col < end.col {
+            self.delete_range(start, end, None);
+        }
+        self.insert_str(completion);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        true
+    }
+
+    /// Iterate over the words in the text from the first line. Words are split in the same way as
+    /// [`TextArea::word_before_cursor`] and the same word is yielded as many times as it appears. This is handy for a
+    /// simple completion source suggesting the words in the text.
+    ///
+    /// Words are borrowed from the text without copying lines. Only a word split across the pieces of the rope enabled
+    /// with the `rope` feature is copied into an owned string.
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["foo.bar(baz)", "", "  foo + 1"]);
+    ///
+    /// let words: Vec<_> = textarea.words_iter().collect();
+    /// assert_eq!(words, ["foo", "bar", "baz", "foo", "1"]);
+    ///
+    /// // Candidates starting with the prefix
+    /// let candidates: BTreeSet<_> = textarea.words_iter().filter(|w| w.starts_with("ba")).collect();
+    /// assert_eq!(candidates.into_iter().collect::<Vec<_>>(), ["bar", "baz"]);
+    /// ```
+    pub fn words_iter(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        let lines = &self.lines;
+        (0..lines.len()).flat_map(move |row| ChunkWords::new(lines.chunks(row, 0..lines.line_len(row))))
+    }
+
+    /// Set the ghost text, which is an inline suggestion such as a completion rendered at the cursor, or remove it with
+    /// `None`. The ghost text is only rendered and the text is not modified until it is accepted by
+    /// [`TextArea::accept_ghost_text`]. It is rendered with the style set by [`TextArea::set_ghost_text_style`] and
+    /// pushes the text after the cursor to the right. When it has multiple lines, the following lines are rendered in
+    /// extra rows which push the lines below down. The cursor is rendered on the first cell of the ghost text.
+    ///
+    /// The ghost text is removed automatically on any edit or cursor move. It is not rendered while lines are
+    /// soft-wrapped or the text is masked.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["fn main"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.set_ghost_text(Some("() {}".to_string()));
+    /// assert_eq!(textarea.ghost_text(), Some("() {}"));
+    /// assert_eq!(textarea.lines(), ["fn main"]);
+    ///
+    /// // Moving the cursor removes the suggestion
+    /// textarea.move_cursor(CursorMove::Back);
+    /// assert_eq!(textarea.ghost_text(), None);
+    /// ```
+    pub fn set_ghost_text(&mut self, text: Option<String>) {
+        self.ghost_text = text;
+    }
+
+    /// Get the ghost text. See [`TextArea::set_ghost_text`].
+    pub fn ghost_text(&self) -> Option<&str> {
+        self.ghost_text.as_deref()
+    }
+
+    /// Set the style of the ghost text. The default value is dark gray foreground.
+    /// ```
+    /// use ratatui::style::{Modifier, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC);
+    /// textarea.set_ghost_text_style(style);
+    /// assert_eq!(textarea.ghost_text_style(), style);
+    /// ```
+    pub fn set_ghost_text_style(&mut self, style: Style) {
+        self.ghost_text_style = style;
+    }
+
+    /// Get the style of the ghost text. See [`TextArea::set_ghost_text_style`].
+    pub fn ghost_text_style(&self) -> Style {
+        self.ghost_text_style
+    }
+
+    /// Insert the ghost text set by [`TextArea::set_ghost_text`] at the cursor and remove it. The cursor moves to the
+    /// end of the inserted text and the insertion is one undo unit. This method returns `false` when no ghost text is
+    /// set or nothing was inserted.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["fn main"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.set_ghost_text(Some("() {\n}".to_string()));
+    /// assert!(textarea.accept_ghost_text());
+    /// assert_eq!(textarea.lines(), ["fn main() {", "}"]);
+    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// assert_eq!(textarea.ghost_text(), None);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["fn main"]);
+    /// ```
+    pub fn accept_ghost_text(&mut self) -> bool {
+        let Some(text) = self.ghost_text.take() else {
+            return false;
+        };
+        self.cancel_selection();
+        self.history.stop_merging();
+        let inserted = self.insert_str(text);
+        self.history.stop_merging();
+        inserted
+    }
+
+    // Ghost text rendered at the cursor. The digits of a character being composed are shown instead while composing.
+    // Soft-wrapped lines are not supported and masked text does not show it
+    pub(crate) fn shown_ghost_text(&self) -> Option<&str> {
+        self.unicode_input
+            .as_deref()
+            .or(self.ghost_text.as_deref())
+            .filter(|t| !t.is_empty() && !self.wrap && self.display_mask().is_none())
+    }
+
+    // Number of extra display rows taken by the lines of the ghost text after its first line
+    pub(crate) fn ghost_text_rows(&self) -> usize {
+        self.shown_ghost_text().map_or(0, |t| t.matches('\n').count())
+    }
+
+    /// Replace the text between `start` and `end` positions with `text`. Positions are pairs of (row, col) where `col`
+    /// is counted in characters, and `end` is exclusive. This method is useful to apply edits made outside the
+    /// textarea such as collaborative editing or reloading a file.
+    ///
+    /// Unlike other editing methods, the current selection is not deleted and the text is not truncated by
+    /// [`TextArea::set_max_chars`]. The cursor and the selection are kept at the same place in the text: positions
+    /// after the edited range are shifted, positions inside it are moved to `start`, and positions before it are not
+    /// changed. The edit is recorded in the undo history as one change. This method returns `false` when the positions
+    /// are out of the text, `end` is before `start`, or nothing is replaced.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.apply_edit((0, 1), (0, 5), "i\nthere");
+    /// assert_eq!(textarea.lines(), ["hi", "there", "world"]);
+    /// assert_eq!(textarea.cursor(), (2, 5));
+    ///
+    /// textarea.apply_edit((0, 2), (2, 0), ", ");
+    /// assert_eq!(textarea.lines(), ["hi, world"]);
+    /// assert_eq!(textarea.cursor(), (0, 9));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hi", "there", "world"]);
+    /// ```
+    pub fn apply_edit(&mut self, start: (usize, usize), end: (usize, usize), text: &str) -> bool {
+        if end < start {
+            return false;
+        }
+        let pos_at = |(row, col): (usize, usize)| {
+            let line = self.lines.get(row)?;
+            let offset = line
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain(iter::once(line.len()))
+                .nth(col)?;
+            Some(Pos::new(row, col, offset))
+        };
+        let (Some(start_pos), Some(end_pos)) = (pos_at(start), pos_at(end)) else {
+            return false;
+        };
+        if start == end && text.is_empty() {
+            return false;
+        }
+
+        let mut inserted: Vec<_> = text
+            .split('\n')
+            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+            .collect();
+        if self.single_line && inserted.len() > 1 {
+            inserted = vec![inserted.join(&self.newline_replacement)];
+        }
+        let (edits, new_end) = self.replace_range(start_pos, end_pos, inserted);
+
+        let shift = |pos: (usize, usize)| {
+            if pos <= start {
+                pos
+            } else if pos < end {
+                start
+            } else if pos.0 == end.0 {
+                (new_end.0, new_end.1 + pos.1 - end.1)
+            } else {
+                (pos.0 - end.0 + new_end.0, pos.1)
+            }
+        };
+        let before = self.cursor;
+        self.cursor = shift(before);
+        self.selection_start = self.selection_start.map(shift);
+        let before = Pos::new(before.0, before.1, 0);
+        let after = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.record(Edit::new(EditKind::Batch(edits), before, after));
+        true
+    }
+
+    // Replace the text between `start` and `end` with the lines. This returns the applied edits and the end position of
+    // the inserted text. The edits are not recorded in the history
+    fn replace_range(&mut self, start: Pos, end: Pos, mut inserted: Vec<String>) -> (Vec<Edit>, (usize, usize)) {
+        let mut edits = vec![];
+        if (start.row, start.col) != (end.row, end.col) {
+            let kind = if start.row == end.row {
+                EditKind::DeleteStr(self.lines.line(start.row)[start.offset..end.offset].to_string())
+            } else {
+                let mut chunk = vec![self.lines.line(start.row)[start.offset..].to_string()];
+                chunk.extend(self.lines.iter_in(start.row + 1..end.row).map(Cow::into_owned));
+                chunk.push(self.lines.line(end.row)[..end.offset].to_string());
+                EditKind::DeleteChunk(chunk)
+            };
+            edits.push(Edit::new(kind, end, start.clone()));
+        }
+
+        let inserted_end = if inserted.len() == 1 {
+            let s = &inserted[0];
+            Pos::new(start.row, start.col + s.chars().count(), start.offset + s.len())
+        } else {
+            let s = inserted.last().unwrap();
+            Pos::new(start.row + inserted.len() - 1, s.chars().count(), s.len())
+        };
+        let new_end = (inserted_end.row, inserted_end.col);
+        if inserted.len() > 1 {
+            edits.push(Edit::new(EditKind::InsertChunk(inserted), start, inserted_end));
+        } else if !inserted[0].is_empty() {
+            edits.push(Edit::new(EditKind::InsertStr(inserted.remove(0)), start, inserted_end));
+        }
+
+        for edit in &edits {
+            edit.redo(&mut self.lines);
+        }
+        (edits, new_end)
+    }
+
+    // Insert lines joined with newlines. The lines are truncated not to exceed the maximum number of characters
+    fn insert_lines(&mut self, mut lines: Vec<String>) -> bool {
+        if self.single_line && lines.len() > 1 {
+            lines = vec![lines.join(&self.newline_replacement)];
+        }
+        if self.max_chars.is_some() {
+            let mut remaining = self.remaining_chars();
+            for (i, line) in lines.iter_mut().enumerate() {
+                if i > 0 {
+                    if remaining == 0 {
+                        lines.truncate(i);
+                        break;
+                    }
+                    remaining -= 1; // Newline
+                }
+                if let Some((idx, _)) = line.char_indices().nth(remaining) {
+                    line.truncate(idx);
+                }
+                remaining -= line.chars().count();
+            }
+        }
+        match lines.len() {
+            0 => false,
+            1 => self.insert_piece(lines.remove(0)),
+            _ => self.insert_chunk(lines),
+        }
+    }
+
+    fn insert_chunk(&mut self, chunk: Vec<String>) -> bool {
+        debug_assert!(chunk.len() > 1, "Chunk size must be > 1: {:?}", chunk);
+
+        let (row, col) = self.cursor;
+        let line = &mut self.lines.line(row);
+        let i = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+        let before = Pos::new(row, col, i);
+
+        let (row, col) = (row + chunk.len() - 1, chunk[chunk.len() - 1].chars().count());
+        self.cursor = (row, col);
+
+        let end_offset = chunk.last().unwrap().len();
+
+        let edit = EditKind::InsertChunk(chunk);
+        edit.apply(&mut self.lines, &before, &Pos::new(row, col, end_offset));
+
+        self.push_history(edit, before, end_offset);
+        true
+    }
+
+    fn insert_piece(&mut self, s: String) -> bool {
+        if s.is_empty() {
+            return false;
+        }
+
+        let (row, col) = self.cursor;
+        debug_assert!(
+            !s.contains('\n'),
+            "string given to TextArea::insert_piece must not contain newline: {:?}",
+            self.lines.line(row),
+        );
+
+        let i = self.lines.offset_of_col(row, col);
+        self.lines.insert_str(row, i, &s);
+        let end_offset = i + s.len();
+
+        self.cursor.1 += s.chars().count();
+        self.push_history(EditKind::InsertStr(s), Pos::new(row, col, i), end_offset);
+        true
+    }
+
+    fn delete_range(&mut self, start: Pos, end: Pos, kill: Option<Kill>) {
+        self.cursor = (start.row, start.col);
+
+        if start.row == end.row {
+            let removed = self.lines.remove_str(start.row, start.offset..end.offset);
+            if let Some(kill) = kill {
+                self.kill(removed.clone().into(), kill);
+            }
+            self.push_history(EditKind::DeleteStr(removed), end, start.offset);
+            return;
+        }
+
+        let mut deleted = vec![self
+            .lines
+            .remove_str(start.row, start.offset..self.lines.line_len(start.row))];
+        deleted.extend(self.lines.splice(start.row + 1..end.row, vec![]));
+        if start.row + 1 < self.lines.len() {
+            deleted.push(self.lines.remove_str(start.row + 1, 0..end.offset));
+            self.lines.join_line(start.row);
+        }
+
+        if let Some(kill) = kill {
+            self.kill(YankText::Chunk(deleted.clone()), kill);
+        }
+
+        let edit = if deleted.len() == 1 {
+            EditKind::DeleteStr(deleted.remove(0))
+        } else {
+            EditKind::DeleteChunk(deleted)
+        };
+
+        self.push_history(edit, end, start.offset);
+    }
+
+    /// Delete a string from the current cursor position. The `chars` parameter means number of characters, not a byte
+    /// length of the string. Newlines at the end of lines are counted in the number. This method returns if some text
+    /// was deleted or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["🐱🐶🐰🐮"]);
+    /// textarea.move_cursor(CursorMove::Forward);
+    ///
+    /// textarea.delete_str(2);
+    /// assert_eq!(textarea.lines(), ["🐱🐮"]);
+    ///
+    /// let mut textarea = TextArea::from(["🐱", "🐶", "🐰", "🐮"]);
+    /// textarea.move_cursor(CursorMove::Down);
+    ///
+    /// textarea.delete_str(4); // Deletes 🐶, \n, 🐰, \n
+    /// assert_eq!(textarea.lines(), ["🐱", "🐮"]);
+    /// ```
+    pub fn delete_str(&mut self, chars: usize) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+        if chars == 0 {
+            return false;
+        }
+
+        let (start_row, start_col) = self.cursor;
+
+        let mut remaining = chars;
+        let mut find_end = move |line: &str| {
+            let mut col = 0usize;
+            for (i, _) in line.char_indices() {
+                if remaining == 0 {
+                    return Some((i, col));
+                }
+                col += 1;
+                remaining -= 1;
+            }
+            if remaining == 0 {
+                Some((line.len(), col))
+            } else {
+                remaining -= 1;
+                None
+            }
+        };
+
+        let line = &self.lines.line(start_row);
+        let start_offset = { line.char_indices().nth(start_col).map(|(i, _)| i).unwrap_or(line.len()) };
+
+        // First line
+        if let Some((offset_delta, col_delta)) = find_end(&line[start_offset..]) {
+            let end_offset = start_offset + offset_delta;
+            let end_col = start_col + col_delta;
+            let removed = self.lines.remove_str(start_row, start_offset..end_offset);
+            self.kill(removed.clone().into(), Kill::Forward);
+            self.push_history(
+                EditKind::DeleteStr(removed),
+                Pos::new(start_row, end_col, end_offset),
+                start_offset,
+            );
+            return true;
+        }
+
+        let mut r = start_row + 1;
+        let mut offset = 0;
+        let mut col = 0;
+
+        while r < self.lines.len() {
+            let line = &self.lines.line(r);
+            if let Some((o, c)) = find_end(line) {
+                offset = o;
+                col = c;
+                break;
+            }
+            r += 1;
+        }
+
+        let start = Pos::new(start_row, start_col, start_offset);
+        let end = Pos::new(r, col, offset);
+        self.delete_range(start, end, Some(Kill::Forward));
+        true
+    }
+
+    fn delete_piece(&mut self, col: usize, chars: usize) -> bool {
+        if chars == 0 {
+            return false;
+        }
+
+        #[inline]
+        fn bytes_and_chars(claimed: usize, s: &str) -> (usize, usize) {
+            // Note: `claimed` may be larger than characters in `s` (e.g. usize::MAX)
+            let mut last_col = 0;
+            for (col, (bytes, _)) in s.char_indices().enumerate() {
+                if col == claimed {
+                    return (bytes, claimed);
+                }
+                last_col = col;
+            }
+            (s.len(), last_col + 1)
+        }
+
+        let (row, _) = self.cursor;
+        let line = &self.lines.line(row);
+        if let Some((i, _)) = line.char_indices().nth(col) {
+            let (bytes, chars) = bytes_and_chars(chars, &line[i..]);
+            let removed = self.lines.remove_str(row, i..i + bytes);
+
+            let kill = if col < self.cursor.1 {
+                Kill::Backward
+            } else {
+                Kill::Forward
+            };
+            self.kill(removed.clone().into(), kill);
+            self.cursor = (row, col);
+            self.push_history(EditKind::DeleteStr(removed), Pos::new(row, col + chars, i + bytes), i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Insert a tab at current cursor position. Note that this method does nothing when the tab length is 0. This
+    /// method returns if a tab string was inserted or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hi"]);
+    ///
+    /// textarea.move_cursor(CursorMove::End); // Move to the end of line
+    ///
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["hi  "]);
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["hi      "]);
+    /// ```
+    pub fn insert_tab(&mut self) -> bool {
+        let modified = self.replace_selection();
+        if self.tab_len == 0 {
+            return modified;
+        }
+
+        if self.hard_tab_indent {
+            return self.insert_char('\t') || modified;
+        }
+
+        let (row, col) = self.cursor;
+        let width: usize = self
+            .lines
+            .line(row)
+            .chars()
+            .take(col)
+            .map(|c| c.width().unwrap_or(0))
+            .sum();
+        let len = self.tab_len - (width % self.tab_len as usize) as u8;
+        self.insert_lines(vec![spaces(len).to_string()]) || modified
+    }
+
+    // First and last rows touched by the selection, or the cursor row when nothing is selected. A selection ending
+    // at the head of a later line does not touch that line.
+    fn selected_rows(&self) -> (usize, usize) {
+        if let Some(rows) = self.linewise_rows() {
+            return rows;
+        }
+        let (start, end) = self.selection_range().unwrap_or((self.cursor, self.cursor));
+        if end.0 > start.0 && end.1 == 0 {
+            (start.0, end.0 - 1)
+        } else {
+            (start.0, end.0)
+        }
+    }
+
+    /// Remove a tab at the start of the line of the current cursor position.
+    /// Note that this method does nothing when the tab length is 0.
+    /// This method returns if a tab string was removed or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["    hi"]);
+    ///
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["        hi"]);
+    /// textarea.delete_tab_from_head();
+    /// assert_eq!(textarea.lines(), ["    hi"]);
+    ///
+    /// let mut textarea = TextArea::from(["\thi"]);
+    /// textarea.set_hard_tab_indent(true);
+    ///
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["\t\thi"]);
+    /// textarea.delete_tab_from_head();
+    /// assert_eq!(textarea.lines(), ["\thi"]);
+    /// ```
+    pub fn delete_tab_from_head(&mut self) -> bool {
+        if self.tab_len == 0 {
+            return false;
+        }
+
+        let (row, col) = self.cursor;
+        let (tab, tab_symbol) = if self.hard_tab_indent {
+            ("\t", '\t')
+        } else {
+            (spaces(self.tab_len), ' ')
+        };
+        let tab_len = tab.chars().count();
+
+        if let Some(char_idx) = self
+            .lines
+            .line(row)
+            .char_indices()
+            .find_map(|(char_idx, char)| (char != tab_symbol).then_some(char_idx))
+        {
+            if char_idx == 0 {
+                return false;
+            }
+
+            let mut chars_to_remove = char_idx % tab_len;
+            if chars_to_remove == 0 {
+                chars_to_remove = tab_len;
+            }
+
+            let col_byte_offset = self
+                .lines
+                .line(row)
+                .char_indices()
+                .nth(col)
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| self.lines.line_len(row));
+
+            self.push_change(Edit::new(
+                EditKind::DeleteStr(tab.chars().take(chars_to_remove).collect()),
+                Pos::new(row, col, col_byte_offset),
+                Pos::new(row, col.saturating_sub(chars_to_remove), 0),
+            ));
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Indent every line touched by the selection with one indent unit (a hard tab or `tab_length` spaces, see
+    /// [`TextArea::indent`]). When nothing is selected, the line at the cursor is indented. Empty lines are not
+    /// indented, and a line where the selection ends at its head is not touched. The selection is kept so that the
+    /// operation can be repeated. This method returns if some line was indented or not. Note that this method does
+    /// nothing when the tab length is 0.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo", "", "  bar", "baz"]);
+    /// textarea.set_selection((0, 1), (2, 3));
+    ///
+    /// textarea.indent_selection();
+    /// assert_eq!(textarea.lines(), ["    foo", "", "      bar", "baz"]);
+    /// assert_eq!(textarea.selection_range(), Some(((0, 5), (2, 7))));
+    ///
+    /// // One undo reverts the indentation of all lines
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["foo", "", "  bar", "baz"]);
+    /// ```
+    pub fn indent_selection(&mut self) -> bool {
+        if self.tab_len == 0 {
+            return false;
+        }
+        let indent = self.indent();
+        self.edit_line_heads(true, |line| (!line.is_empty()).then(|| indent.to_string()))
+    }
+
+    /// Remove one indent unit from the head of every line touched by the selection. When nothing is selected, the line
+    /// at the cursor is dedented. Leading whitespaces are removed up to the width of one indent unit (`tab_length`
+    /// cells), so lines with mixed spaces and tabs, or with less than one full indent, are also handled. The selection
+    /// is kept so that the operation can be repeated. This method returns if some line was dedented or not. Note that
+    /// this method does nothing when the tab length is 0.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["      foo", "\tbar", "  \tbaz", " qux"]);
+    /// textarea.select_all();
+    ///
+    /// textarea.dedent_selection();
+    /// assert_eq!(textarea.lines(), ["  foo", "bar", "baz", "qux"]);
+    /// ```
+    pub fn dedent_selection(&mut self) -> bool {
+        if self.tab_len == 0 {
+            return false;
+        }
+        let tab_len = self.tab_len;
+        self.edit_line_heads(false, |line| {
+            let mut width = 0;
+            let mut end = 0;
+            for (i, c) in line.char_indices() {
+                if width >= tab_len as usize || c != ' ' && c != '\t' {
+                    break;
+                }
+                width += char_width(c, width, tab_len, None, false);
+                end = i + c.len_utf8();
+            }
+            (end > 0).then(|| line[..end].to_string())
+        })
+    }
+
+    /// Set the tokens of line comments and block comments used by [`TextArea::toggle_comment`]. For example,
+    /// `Some("//")` and `Some(("/*", "*/"))` for Rust. Both are not set by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_comment_tokens(Some("#"), None);
+    /// assert_eq!(textarea.comment_tokens(), (Some("#"), None));
+    /// ```
+    pub fn set_comment_tokens(&mut self, line: Option<&str>, block: Option<(&str, &str)>) {
+        self.comment_line = line.filter(|t| !t.is_empty()).map(str::to_string);
+        self.comment_block = block
+            .filter(|(o, c)| !o.is_empty() && !c.is_empty())
+            .map(|(o, c)| (o.to_string(), c.to_string()));
+    }
+
+    /// Get the tokens of line comments and block comments. See [`TextArea::set_comment_tokens`].
+    pub fn comment_tokens(&self) -> (Option<&str>, Option<(&str, &str)>) {
+        (
+            self.comment_line.as_deref(),
+            self.comment_block.as_ref().map(|(o, c)| (o.as_str(), c.as_str())),
+        )
+    }
+
+    /// Set if [`TextArea::toggle_comment`] also comments out blank lines. By default, blank lines are skipped unless
+    /// all lines are blank.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "", "b"]);
+    /// textarea.set_comment_tokens(Some("//"), None);
+    /// textarea.set_comment_blank_lines(true);
+    /// assert!(textarea.comment_blank_lines());
+    ///
+    /// textarea.select_all();
+    /// textarea.toggle_comment();
+    /// assert_eq!(textarea.lines(), ["// a", "// ", "// b"]);
+    /// ```
+    pub fn set_comment_blank_lines(&mut self, enabled: bool) {
+        self.comment_blank_lines = enabled;
+    }
+
+    /// Get if blank lines are commented out. See [`TextArea::set_comment_blank_lines`].
+    pub fn comment_blank_lines(&self) -> bool {
+        self.comment_blank_lines
+    }
+
+    /// Comment out or uncomment the line at the cursor, or every line touched by the selection, with the tokens set by
+    /// [`TextArea::set_comment_tokens`]. When all non-blank lines start with the line comment token after their
+    /// indentation, the token and one space after it are removed. Otherwise the token and a space are inserted at the
+    /// smallest indentation of the lines so that the tokens are aligned.
+    ///
+    /// When the selection is in one line and the block comment tokens are set, the selected text is wrapped with them,
+    /// or unwrapped when it is already wrapped. The block comment tokens are also used when the line comment token is
+    /// not set.
+    ///
+    /// The selection is kept and the change is one undo unit. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["fn f() {", "    a();", "", "  b();", "}"]);
+    /// textarea.set_comment_tokens(Some("//"), Some(("/*", "*/")));
+    ///
+    /// textarea.set_selection((1, 0), (3, 6));
+    /// textarea.toggle_comment();
+    /// assert_eq!(textarea.lines(), ["fn f() {", "  //   a();", "", "  // b();", "}"]);
+    ///
+    /// textarea.toggle_comment();
+    /// assert_eq!(textarea.lines(), ["fn f() {", "    a();", "", "  b();", "}"]);
+    ///
+    /// // Block comment for the selection in one line
+    /// textarea.set_selection((0, 3), (0, 6));
+    /// textarea.toggle_comment();
+    /// assert_eq!(textarea.lines()[0], "fn /*f()*/ {");
+    /// assert_eq!(textarea.selection_range(), Some(((0, 5), (0, 8))));
+    /// ```
+    pub fn toggle_comment(&mut self) -> bool {
+        let range = self.selection_range().filter(|(start, end)| start != end);
+        if let Some((open, close)) = self.comment_block.clone() {
+            match range {
+                Some((start, end)) if start.0 == end.0 => {
+                    return self.toggle_block_comment(start, end, &open, &close, true);
+                }
+                Some((start, end)) if self.comment_line.is_none() => {
+                    return self.toggle_block_comment(start, end, &open, &close, true);
+                }
+                None if self.comment_line.is_none() => {
+                    // Comment out the text of the cursor line after its indentation
+                    let (row, _) = self.cursor;
+                    let line = &self.lines.line(row);
+                    let trimmed = line.trim();
+                    if trimmed.is_empty() {
+                        return false;
+                    }
+                    let head = line.len() - line.trim_start().len();
+                    let start = (row, line[..head].chars().count());
+                    let end = (row, start.1 + trimmed.chars().count());
+                    return self.toggle_block_comment(start, end, &open, &close, false);
+                }
+                _ => {}
+            }
+        }
+        let Some(token) = self.comment_line.clone() else {
+            return false;
+        };
+
+        let (first, last) = self.selected_rows();
+        let indent_len = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+        let blank = |line: &str| line.trim().is_empty();
+        let all_blank = self.lines.iter_in(first..last + 1).all(|l| blank(&l));
+        let targets = || (first..=last).filter(|&r| all_blank || !blank(&self.lines.line(r)));
+
+        let commented = !all_blank
+            && targets().all(|r| {
+                let line = &self.lines.line(r);
+                line[indent_len(line)..].starts_with(token.as_str())
+            });
+        if commented {
+            let items = targets()
+                .map(|r| {
+                    let line = &self.lines.line(r);
+                    let indent = indent_len(line);
+                    let rest = &line[indent + token.len()..];
+                    let removed = if rest.starts_with(' ') {
+                        format!("{token} ")
+                    } else {
+                        token.clone()
+                    };
+                    (r, indent, removed) // Indentation is ASCII so bytes are chars
+                })
+                .collect();
+            return self.edit_lines(false, items);
+        }
+
+        // Blank lines are padded to the column only when they are shorter
+        let col = targets().map(|r| indent_len(&self.lines.line(r))).min().unwrap_or(0);
+        let items = (first..=last)
+            .filter(|&r| all_blank || self.comment_blank_lines || !blank(&self.lines.line(r)))
+            .map(|r| (r, min(col, self.lines.char_count(r)), format!("{token} ")))
+            .collect();
+        self.edit_lines(true, items)
+    }
+
+    // Wrap the text between `start` and `end` with the block comment tokens, or unwrap it when the tokens are inside or
+    // around it. When `select` is true, the text between the tokens is selected after the change
+    fn toggle_block_comment(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        open: &str,
+        close: &str,
+        select: bool,
+    ) -> bool {
+        fn text(line: &str, start: usize, end: usize) -> &str {
+            let offset = |col| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+            &line[offset(start)..offset(end)]
+        }
+        let (on, cn) = (open.chars().count(), close.chars().count());
+        let same_row = start.0 == end.0;
+        let forward = self.selection_start == Some(start);
+        let (start_line, end_line) = (&self.lines.line(start.0), &self.lines.line(end.0));
+
+        let inside = (!same_row || end.1 - start.1 >= on + cn)
+            && text(start_line, start.1, start.1 + on) == open
+            && end.1 >= cn
+            && text(end_line, end.1 - cn, end.1) == close;
+        let around = start.1 >= on
+            && text(start_line, start.1 - on, start.1) == open
+            && text(end_line, end.1, end.1 + cn) == close;
+
+        let shift_end = |n: usize, add: bool| {
+            let n = if same_row { n } else { 0 };
+            if add {
+                end.1 + n
+            } else {
+                end.1 - n
+            }
+        };
+        let (modified, selected) = if inside {
+            let items = vec![
+                (start.0, start.1, open.to_string()),
+                (end.0, end.1 - cn, close.to_string()),
+            ];
+            (
+                self.edit_lines(false, items),
+                (start, (end.0, shift_end(on, false) - cn)),
+            )
+        } else if around {
+            let items = vec![
+                (start.0, start.1 - on, open.to_string()),
+                (end.0, end.1, close.to_string()),
+            ];
+            (
+                self.edit_lines(false, items),
+                ((start.0, start.1 - on), (end.0, shift_end(on, false))),
+            )
+        } else {
+            let items = vec![(start.0, start.1, open.to_string()), (end.0, end.1, close.to_string())];
+            (
+                self.edit_lines(true, items),
+                ((start.0, start.1 + on), (end.0, shift_end(on, true))),
+            )
+        };
+
+        if select {
+            let (anchor, cursor) = if forward { selected } else { (selected.1, selected.0) };
+            self.selection_start = Some(anchor);
+            self.cursor = cursor;
+        }
+        modified
+    }
+
+    /// Add `delta` to the number under or after the cursor in the cursor line, like `Ctrl+A` and `Ctrl+X` in Vim. When
+    /// some text is selected, the number is searched from the start of the selection. A decimal number may have a `-`
+    /// sign, and a number starting with `0x` is hexadecimal. Leading zeros are kept by padding the number to the same
+    /// width. The cursor moves to the last digit of the number and the change is one undo unit. This method returns
+    /// `false` when no number is found.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["width = 9, height = 0x0f"]);
+    ///
+    /// assert!(textarea.increment_number(1));
+    /// assert_eq!(textarea.lines(), ["width = 10, height = 0x0f"]);
+    /// assert_eq!(textarea.cursor(), (0, 9));
+    ///
+    /// assert!(textarea.increment_number(-20));
+    /// assert_eq!(textarea.lines(), ["width = -10, height = 0x0f"]);
+    ///
+    /// textarea.set_selection((0, 13), (0, 14));
+    /// assert!(textarea.increment_number(1));
+    /// assert_eq!(textarea.lines(), ["width = -10, height = 0x10"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["width = -10, height = 0x0f"]);
+    ///
+    /// let mut textarea = TextArea::from(["no number"]);
+    /// assert!(!textarea.increment_number(1));
+    /// ```
+    pub fn increment_number(&mut self, delta: i64) -> bool {
+        let (row, col) = self.selection_range().map_or(self.cursor, |(start, _)| start);
+        let line = &self.lines.line(row);
+        let offset = line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+        let Some(range) = number::find_number(line, offset) else {
+            return false;
+        };
+        let Some(number) = number::increment(&line[range.clone()], delta) else {
+            return false;
+        };
+        // The number consists of ASCII characters so its length in bytes is the number of characters
+        let col = line[..range.start].chars().count();
+        let start = Pos::new(row, col, range.start);
+        let end = Pos::new(row, col + range.len(), range.end);
+
+        self.cancel_selection();
+        let grouped = self.history.begin_group();
+        self.delete_range(start, end, None);
+        self.insert_str(&number);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        self.cursor = (row, col + number.len() - 1);
+        true
+    }
+
+    /// Transform the selected text, or the word under the cursor when nothing is selected, such as converting it to
+    /// upper case. See [`TextTransform`] for the transforms. A selection over lines is transformed line by line. The
+    /// selection is kept on the transformed text so that the transform can be repeated, and the change is one undo
+    /// unit. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea, TextTransform};
+    ///
+    /// let mut textarea = TextArea::from(["let fooBar = 1;"]);
+    ///
+    /// // The word under the cursor
+    /// textarea.move_cursor(CursorMove::Jump(0, 4));
+    /// assert!(textarea.transform_selection(TextTransform::SnakeCase));
+    /// assert_eq!(textarea.lines(), ["let foo_bar = 1;"]);
+    ///
+    /// // The selection is kept
+    /// textarea.set_selection((0, 0), (0, 7));
+    /// assert!(textarea.transform_selection(TextTransform::ToggleCase));
+    /// assert_eq!(textarea.lines(), ["LET FOO_bar = 1;"]);
+    /// assert!(textarea.transform_selection(TextTransform::ToggleCase));
+    /// assert_eq!(textarea.lines(), ["let foo_bar = 1;"]);
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (0, 7))));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["LET FOO_bar = 1;"]);
+    /// ```
+    pub fn transform_selection(&mut self, transform: TextTransform) -> bool {
+        let cursor = self.cursor;
+        let (start, end, selected) = match self.selection_range() {
+            Some((start, end)) if start != end => (start, end, true),
+            _ => {
+                let (row, col) = cursor;
+                let Some((start, end)) = find_identifier(&self.lines.line(row), col) else {
+                    return false;
+                };
+                ((row, start), (row, end), false)
+            }
+        };
+        let forward = self.selection_start == Some(start);
+
+        let mut replaced = vec![];
+        let mut end_col = end.1;
+        for row in start.0..=end.0 {
+            let line = &self.lines.line(row);
+            let first = if row == start.0 { start.1 } else { 0 };
+            let last = if row == end.0 { end.1 } else { line.chars().count() };
+            let offset = |col| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+            let (from, to) = (Pos::new(row, first, offset(first)), Pos::new(row, last, offset(last)));
+            let text = &line[from.offset..to.offset];
+            let transformed = transform.apply(text);
+            if transformed != text {
+                if row == end.0 {
+                    // Case mapping may change the number of characters like 'ß' to "SS"
+                    end_col = end_col + transformed.chars().count() - (last - first);
+                }
+                replaced.push((from, to, transformed));
+            }
+        }
+        if replaced.is_empty() {
+            return false;
+        }
+
+        self.cancel_selection();
+        let grouped = self.history.begin_group();
+        for (from, to, text) in replaced {
+            self.delete_range(from, to, None);
+            self.insert_str(text);
+        }
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+
+        if selected {
+            let end = (end.0, end_col);
+            let (anchor, cursor) = if forward { (start, end) } else { (end, start) };
+            self.selection_start = Some(anchor);
+            self.cursor = cursor;
+        } else {
+            let len = self.lines.char_count(cursor.0);
+            self.cursor = (cursor.0, min(cursor.1, len));
+        }
+        true
+    }
+
+    /// Swap the character before the cursor with the character at the cursor and move the cursor forward like `Ctrl+T`
+    /// in Emacs. At the end of line, the last two characters are swapped instead. Characters are grapheme clusters so
+    /// that a flag emoji or a character with combining marks is not split. The swap is one undo unit. This method
+    /// returns if the text was modified or not. It is not bound to any key by default. Bind
+    /// [`EditAction::TransposeChars`](crate::EditAction::TransposeChars) with [`TextArea::keymap_mut`] to swap with a
+    /// key such as `Ctrl+T`.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abcd"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 1));
+    /// assert!(textarea.transpose_chars());
+    /// assert_eq!(textarea.lines(), ["bacd"]);
+    /// assert_eq!(textarea.cursor(), (0, 2));
+    ///
+    /// // At the end of line
+    /// textarea.move_cursor(CursorMove::End);
+    /// assert!(textarea.transpose_chars());
+    /// assert_eq!(textarea.lines(), ["badc"]);
+    /// assert_eq!(textarea.cursor(), (0, 4));
+    ///
+    /// // Nothing to swap at the head of line
+    /// textarea.move_cursor(CursorMove::Head);
+    /// assert!(!textarea.transpose_chars());
+    /// ```
+    pub fn transpose_chars(&mut self) -> bool {
+        let (row, col) = self.cursor;
+        let line = &self.lines.line(row);
+        // (start column, text) of each grapheme cluster
+        let mut graphemes = vec![];
+        let mut start = 0;
+        for g in line.graphemes(true) {
+            graphemes.push((start, g));
+            start += g.chars().count();
+        }
+        // The cluster containing the cursor is the one at the cursor
+        let i = graphemes
+            .iter()
+            .take_while(|(s, g)| s + g.chars().count() <= col)
+            .count();
+        let i = match i {
+            0 => return false,
+            i if i == graphemes.len() && i >= 2 => i - 1,
+            i if i == graphemes.len() => return false,
+            i => i,
+        };
+        let ((start, before), (_, at)) = (graphemes[i - 1], graphemes[i]);
+        let end = start + before.chars().count() + at.chars().count();
+        let swapped = format!("{at}{before}");
+        self.replace_in_line(row, start, end, &swapped);
+        self.cursor = (row, end);
+        true
+    }
+
+    /// Swap the word before the cursor with the word after it like `Alt+T` in Emacs. The whitespaces and punctuations
+    /// between the words are kept and the cursor is moved after the second word. When the cursor is in a word, the word
+    /// is swapped with the next word. At the end of line, the last two words are swapped. Words are split in the same
+    /// way as [`CursorMove::WordForward`] except that punctuations are not words. The swap is one undo unit. This
+    /// method returns if the text was modified or not. It is not bound to any key by default. Bind
+    /// [`EditAction::TransposeWords`](crate::EditAction::TransposeWords) with [`TextArea::keymap_mut`] to swap with a
+    /// key such as `Alt+T`.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["foo, bar baz"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 5));
+    /// assert!(textarea.transpose_words());
+    /// assert_eq!(textarea.lines(), ["bar, foo baz"]);
+    /// assert_eq!(textarea.cursor(), (0, 8));
+    ///
+    /// // At the end of line
+    /// textarea.move_cursor(CursorMove::End);
+    /// assert!(textarea.transpose_words());
+    /// assert_eq!(textarea.lines(), ["bar, baz foo"]);
+    /// assert_eq!(textarea.cursor(), (0, 12));
+    /// ```
+    pub fn transpose_words(&mut self) -> bool {
+        let (row, col) = self.cursor;
+        let words = word_ranges(&self.lines.line(row));
+        if words.len() < 2 {
+            return false;
+        }
+        let i = words.iter().take_while(|(start, _)| *start < col).count();
+        let i = i.saturating_sub(1).min(words.len() - 2);
+        let ((start, first_end), (second_start, end)) = (words[i], words[i + 1]);
+
+        let chars: Vec<char> = self.lines.line(row).chars().collect();
+        let text = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+        let swapped = text(second_start, end) + &text(first_end, second_start) + &text(start, first_end);
+        self.replace_in_line(row, start, end, &swapped);
+        self.cursor = (row, end);
+        true
+    }
+
+    // Replace the text between the columns in the row with `text` as one undo unit
+    fn replace_in_line(&mut self, row: usize, start: usize, end: usize, text: &str) {
+        let line = &self.lines.line(row);
+        let offset = |col| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+        let (start, end) = (Pos::new(row, start, offset(start)), Pos::new(row, end, offset(end)));
+        self.cancel_selection();
+        let grouped = self.history.begin_group();
+        self.delete_range(start, end, None);
+        self.insert_str(text);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+    }
+
+    // Insert or delete the string returned from `f` at the head of each line touched by the selection (or the cursor
+    // line) as one undo unit
+    fn edit_line_heads(&mut self, insert: bool, mut f: impl FnMut(&str) -> Option<String>) -> bool {
+        let (start, last) = self.selected_rows();
+        let items = (start..=last)
+            .filter_map(|row| f(&self.lines.line(row)).map(|s| (row, 0, s)))
+            .collect();
+        self.edit_lines(insert, items)
+    }
+
+    // Insert or delete the strings at the (row, col) positions as one undo unit. The positions must be sorted and the
+    // deleted strings must not overlap. The selection and the cursor are kept on the same characters, and positions at
+    // an insertion are not moved
+    fn edit_lines(&mut self, insert: bool, items: Vec<(usize, usize, String)>) -> bool {
+        if items.is_empty() {
+            return false;
+        }
+
+        // Edit from the end so that the offsets of the former items are not changed
+        let mut edits = Vec::with_capacity(items.len());
+        for (row, col, s) in items.iter().rev() {
+            let line = &self.lines.line(*row);
+            let offset = line.char_indices().nth(*col).map_or(line.len(), |(i, _)| i);
+            let head = Pos::new(*row, *col, offset);
+            let tail = Pos::new(*row, col + s.chars().count(), offset + s.len());
+            let edit = if insert {
+                Edit::new(EditKind::InsertStr(s.clone()), head, tail)
+            } else {
+                Edit::new(EditKind::DeleteStr(s.clone()), tail, head)
+            };
+            edit.redo(&mut self.lines);
+            edits.push(edit);
+        }
+
+        let shift = |(row, col): (usize, usize)| {
+            let mut moved = col;
+            for (r, c, s) in &items {
+                if *r == row && col > *c {
+                    let chars = s.chars().count();
+                    if insert {
+                        moved += chars;
+                    } else {
+                        moved -= min(chars, col - c);
+                    }
+                }
+            }
+            (row, moved)
+        };
+        let before = self.cursor;
+        self.cursor = shift(before);
+        self.selection_start = self.selection_start.map(shift);
+        let before = Pos::new(before.0, before.1, 0);
+        let after = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.record(Edit::new(EditKind::Batch(edits), before, after));
+        true
+    }
+
+    /// Duplicate the line at the cursor. The selection is canceled. Use [`TextArea::duplicate_lines`] to duplicate all
+    /// lines touched by the selection.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 1));
+    ///
+    /// textarea.duplicate_line();
+    /// assert_eq!(textarea.lines(), ["abc", "abc", "def"]);
+    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// ```
+    pub fn duplicate_line(&mut self) {
+        self.cancel_selection();
+        self.duplicate_lines();
+    }
+
+    /// Duplicate the line at the cursor, or all lines touched by the selection, and insert the copy below them. A line
+    /// where the selection ends at its head is not duplicated. The cursor and the selection are moved to the copy at
+    /// the same columns. The yanked text is not changed. This method returns if the lines were duplicated or not. They
+    /// are not duplicated when the copy would exceed the limit set by [`TextArea::set_max_chars`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def", "ghi"]);
+    /// // Select "abc" and "def". The selection ends at the head of "ghi"
+    /// textarea.set_selection((0, 0), (2, 0));
+    ///
+    /// assert!(textarea.duplicate_lines());
+    /// assert_eq!(textarea.lines(), ["abc", "def", "abc", "def", "ghi"]);
+    /// assert_eq!(textarea.selection_range(), Some(((2, 0), (4, 0))));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc", "def", "ghi"]);
+    /// ```
+    pub fn duplicate_lines(&mut self) -> bool {
+        if self.single_line {
+            return false;
+        }
+        let (start, last) = self.selected_rows();
+        let block = self.lines.to_vec_in(start..last + 1);
+        let chars = block.iter().map(|l| l.chars().count() + 1).sum::<usize>();
+        if chars > self.remaining_chars() {
+            return false;
+        }
+
+        let edits: Vec<_> = block
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let row = last + 1 + i;
+                let kind = EditKind::InsertLine(row, line.clone());
+                Edit::new(kind, Pos::new(row, 0, 0), Pos::new(row, 0, 0))
+            })
+            .collect();
+        for edit in &edits {
+            edit.redo(&mut self.lines);
+        }
+
+        // Positions on the block, and the end of a selection at the head of the next line, move to the copy
+        let n = edits.len();
+        let shift = |(row, col): (usize, usize)| {
+            if (start..=last + 1).contains(&row) {
+                (row + n, col)
+            } else {
+                (row, col)
+            }
+        };
+        let before = self.cursor;
+        self.cursor = shift(before);
+        self.selection_start = self.selection_start.map(shift);
+        let before = Pos::new(before.0, before.1, 0);
+        let after = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.record(Edit::new(EditKind::Batch(edits), before, after));
+        true
+    }
+
+    // Rows of the lines touched by the selection, or all lines when nothing is selected
+    fn selected_rows_or_all(&self) -> (usize, usize) {
+        if self.selection_start.is_some() {
+            self.selected_rows()
+        } else {
+            (0, self.lines.len() - 1)
+        }
+    }
+
+    // Replace the lines between the rows with the new lines as one undo unit. The cursor is moved to the head of the
+    // first row and the selection is canceled
+    fn replace_lines(&mut self, start: usize, last: usize, lines: Vec<String>) {
+        let end = {
+            let line = &self.lines.line(last);
+            Pos::new(last, line.chars().count(), line.len())
+        };
+        let (edits, _) = self.replace_range(Pos::new(start, 0, 0), end, lines);
+        let before = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.cancel_selection();
+        self.cursor = (start, 0);
+        self.record(Edit::new(EditKind::Batch(edits), before, Pos::new(start, 0, 0)));
+    }
+
+    // Replace the lines with the reordered lines. This returns the number of lines whose positions were changed
+    fn reorder_lines(&mut self, start: usize, last: usize, lines: Vec<String>) -> usize {
+        let changed = lines
+            .iter()
+            .zip(self.lines.iter_in(start..last + 1))
+            .filter(|(new, old)| *new != old)
+            .count();
+        if changed > 0 {
+            self.replace_lines(start, last, lines);
+        }
+        changed
+    }
+
+    /// Join the lines touched by the selection into one line, or all lines when nothing is selected. Leading
+    /// whitespaces of the joined lines are replaced with one space, and empty lines are removed. This method returns
+    /// the number of joined lines, which is 0 when only one line is selected.
+    ///
+    /// This method and other line operations ([`TextArea::sort_lines`], [`TextArea::reverse_lines`] and
+    /// [`TextArea::dedup_lines`]) are one undo unit. The cursor is moved to the head of the first line and the selection
+    /// is canceled. A line where the selection ends at its head is not included.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["fn f(", "    a: i32,", "", "    b: i32,", ")", "{}"]);
+    /// textarea.set_selection((0, 2), (4, 1));
+    ///
+    /// assert_eq!(textarea.join_lines(), 5);
+    /// assert_eq!(textarea.lines(), ["fn f( a: i32, b: i32, )", "{}"]);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// ```
+    pub fn join_lines(&mut self) -> usize {
+        let (start, last) = self.selected_rows_or_all();
+        if start == last {
+            return 0;
+        }
+        let mut joined = self.lines.line(start).into_owned();
+        for line in self.lines.iter_in(start + 1..last + 1) {
+            let line = line.trim_start_matches([' ', '\t']);
+            if line.is_empty() {
+                continue;
+            }
+            if !joined.is_empty() {
+                joined.push(' ');
+            }
+            joined.push_str(line);
+        }
+        self.replace_lines(start, last, vec![joined]);
+        last - start + 1
+    }
+
+    /// Sort the lines touched by the selection, or all lines when nothing is selected, in the given order. This method
+    /// returns the number of lines whose positions were changed. See [`TextArea::join_lines`] for the selection,
+    /// the cursor, and the undo history.
+    /// ```
+    /// use tui_textarea::{SortOrder, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["banana", "Cherry", "apple"]);
+    ///
+    /// assert_eq!(textarea.sort_lines(SortOrder::Ascending), 3);
+    /// assert_eq!(textarea.lines(), ["Cherry", "apple", "banana"]);
+    ///
+    /// assert_eq!(textarea.sort_lines(SortOrder::AscendingCaseInsensitive), 3);
+    /// assert_eq!(textarea.lines(), ["apple", "banana", "Cherry"]);
+    ///
+    /// // Already sorted
+    /// assert_eq!(textarea.sort_lines(SortOrder::AscendingCaseInsensitive), 0);
+    /// ```
+    pub fn sort_lines(&mut self, order: SortOrder) -> usize {
+        let (start, last) = self.selected_rows_or_all();
+        let mut lines = self.lines.to_vec_in(start..last + 1);
+        match order {
+            SortOrder::Ascending => lines.sort(),
+            SortOrder::Descending => lines.sort_by(|a, b| b.cmp(a)),
+            SortOrder::AscendingCaseInsensitive => lines.sort_by_cached_key(|l| l.to_lowercase()),
+            SortOrder::DescendingCaseInsensitive => lines.sort_by_cached_key(|l| Reverse(l.to_lowercase())),
+        }
+        self.reorder_lines(start, last, lines)
+    }
+
+    /// Reverse the order of the lines touched by the selection, or all lines when nothing is selected. This method
+    /// returns the number of lines whose positions were changed. See [`TextArea::join_lines`] for the selection, the
+    /// cursor, and the undo history.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    /// textarea.set_selection((1, 0), (2, 1));
+    ///
+    /// assert_eq!(textarea.reverse_lines(), 2);
+    /// assert_eq!(textarea.lines(), ["a", "c", "b", "d"]);
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// ```
+    pub fn reverse_lines(&mut self) -> usize {
+        let (start, last) = self.selected_rows_or_all();
+        let lines = self.lines.iter_in(start..last + 1).rev().map(Cow::into_owned).collect();
+        self.reorder_lines(start, last, lines)
+    }
+
+    /// Remove duplicate lines in the lines touched by the selection, or all lines when nothing is selected. The first
+    /// occurrence of each line is kept. Duplicates don't need to be adjacent. This method returns the number of removed
+    /// lines. See [`TextArea::join_lines`] for the selection, the cursor, and the undo history.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "a", "c", "b"]);
+    ///
+    /// assert_eq!(textarea.dedup_lines(), 2);
+    /// assert_eq!(textarea.lines(), ["a", "b", "c"]);
+    /// ```
+    pub fn dedup_lines(&mut self) -> usize {
+        let (start, last) = self.selected_rows_or_all();
+        let mut seen = HashSet::new();
+        let lines: Vec<_> = self
+            .lines
+            .iter_in(start..last + 1)
+            .filter(|l| seen.insert(l.to_string()))
+            .map(Cow::into_owned)
+            .collect();
+        let removed = last - start + 1 - lines.len();
+        if removed > 0 {
+            self.replace_lines(start, last, lines);
+        }
+        removed
+    }
+
+    /// Rewrite the leading whitespaces of every line with spaces. Each tab in the indentation is expanded to the next tab
+    /// stop of [`TextArea::tab_length`]. Tabs after the indentation are not changed. The cursor stays at the same
+    /// visual column and the selection is canceled. The conversion is one undo unit. This method returns the number of
+    /// modified lines.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["\tfoo", "  \tbar\tbaz"]);
+    ///
+    /// assert_eq!(textarea.convert_indent_to_spaces(), 2);
+    /// assert_eq!(textarea.lines(), ["    foo", "    bar\tbaz"]);
+    /// ```
+    pub fn convert_indent_to_spaces(&mut self) -> usize {
+        self.convert_indent(false)
+    }
+
+    /// Rewrite the leading whitespaces of every line with tabs. The indentation is filled with tabs for each tab stop of
+    /// [`TextArea::tab_length`], and the remainder which does not reach the next tab stop is kept as spaces. Tabs after
+    /// the indentation are not changed. The cursor stays at the same visual column as much as possible and the
+    /// selection is canceled. The conversion is one undo unit. This method returns the number of modified lines.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["    foo", "      bar", "  \tbaz"]);
+    ///
+    /// assert_eq!(textarea.convert_indent_to_tabs(), 3);
+    /// assert_eq!(textarea.lines(), ["\tfoo", "\t  bar", "\tbaz"]);
+    /// ```
+    pub fn convert_indent_to_tabs(&mut self) -> usize {
+        self.convert_indent(true)
+    }
+
+    fn convert_indent(&mut self, to_tabs: bool) -> usize {
+        let tab_len = self.tab_len as usize;
+        if tab_len == 0 {
+            return 0;
+        }
+
+        let before = self.cursor;
+        let mut edits = vec![];
+        let mut modified = 0;
+        for row in 0..self.lines.len() {
+            let line = &self.lines.line(row);
+            let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+            let width = indent_width(indent, tab_len);
+            let new_indent = if to_tabs {
+                let mut s = "\t".repeat(width / tab_len);
+                s.push_str(&" ".repeat(width % tab_len));
+                s
+            } else {
+                " ".repeat(width)
+            };
+            if indent == new_indent {
+                continue;
+            }
+
+            let old_len = indent.chars().count();
+            if self.cursor.0 == row {
+                let (_, col) = self.cursor;
+                self.cursor.1 = if col >= old_len {
+                    col - old_len + new_indent.len()
+                } else {
+                    // Put the cursor on the character at the visual column in the new indentation
+                    let visual = indent_width(&indent[..col], tab_len);
+                    let mut w = 0;
+                    let mut col = 0;
+                    for c in new_indent.chars() {
+                        w += if c == '\t' { tab_len - w % tab_len } else { 1 };
+                        if w > visual {
+                            break;
+                        }
+                        col += 1;
+                    }
+                    col
+                };
+            }
+
+            let end = Pos::new(row, old_len, indent.len());
+            let (e, _) = self.replace_range(Pos::new(row, 0, 0), end, vec![new_indent]);
+            edits.extend(e);
+            modified += 1;
+        }
+
+        if modified == 0 {
+            return 0;
+        }
+        self.cancel_selection();
+        let before = Pos::new(before.0, before.1, 0);
+        let after = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.record(Edit::new(EditKind::Batch(edits), before, after));
+        modified
+    }
+
+    /// Remove spaces and tabs at the end of every line. When `keep_cursor_line` is `true`, the line of the cursor is not
+    /// trimmed so that the indentation being typed is not removed. The cursor in the removed whitespaces is moved to
+    /// the end of its line. The selection is canceled when some line is modified. Trimming is one undo unit. This method
+    /// returns the number of modified lines.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a  ", "b\t", "    "]);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
+    /// assert_eq!(textarea.trim_trailing_whitespace(true), 2);
+    /// assert_eq!(textarea.lines(), ["a", "b", "    "]);
+    ///
+    /// assert_eq!(textarea.trim_trailing_whitespace(false), 1);
+    /// assert_eq!(textarea.lines(), ["a", "b", ""]);
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// ```
+    pub fn trim_trailing_whitespace(&mut self, keep_cursor_line: bool) -> usize {
+        let before = self.cursor;
+        let mut edits = vec![];
+        for row in 0..self.lines.len() {
+            if keep_cursor_line && row == self.cursor.0 {
+                continue;
+            }
+            let line = &self.lines.line(row);
+            let trimmed = line.trim_end_matches([' ', '\t']);
+            if trimmed.len() == line.len() {
+                continue;
+            }
+            let col = trimmed.chars().count();
+            let start = Pos::new(row, col, trimmed.len());
+            let end = Pos::new(row, col + line.len() - trimmed.len(), line.len());
+            let (e, _) = self.replace_range(start, end, vec![String::new()]);
+            edits.extend(e);
+            if self.cursor.0 == row {
+                self.cursor.1 = self.cursor.1.min(col);
+            }
+        }
+
+        let modified = edits.len();
+        if modified > 0 {
+            self.cancel_selection();
+            let before = Pos::new(before.0, before.1, 0);
+            let after = Pos::new(self.cursor.0, self.cursor.1, 0);
+            self.record(Edit::new(EditKind::Batch(edits), before, after));
+        }
+        modified
+    }
+
+    /// Append an empty line when the last line is not empty, so that the text ends with a newline when the lines are
+    /// joined with newlines. The cursor and the selection are not changed. This method returns if the line was appended
+    /// or not. Nothing is appended in single-line mode.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "b"]);
+    ///
+    /// assert!(textarea.ensure_final_newline());
+    /// assert_eq!(textarea.lines(), ["a", "b", ""]);
+    /// assert!(!textarea.ensure_final_newline());
+    /// assert_eq!(textarea.lines().join("\n"), "a\nb\n");
+    /// ```
+    pub fn ensure_final_newline(&mut self) -> bool {
+        let row = self.lines.len() - 1;
+        let last = &self.lines.line(row);
+        if last.is_empty() || self.single_line {
+            return false;
+        }
+        let end = Pos::new(row, last.chars().count(), last.len());
+        let (edits, _) = self.replace_range(end.clone(), end, vec![String::new(), String::new()]);
+        let cursor = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.record(Edit::new(EditKind::Batch(edits), cursor.clone(), cursor));
+        true
+    }
+
+    /// Detect the kind of indentation mostly used in the text. Lines indented with tabs and with spaces are counted,
+    /// and the more common kind is returned. For spaces, the most frequent increase of the indentation between lines is
+    /// reported as the width of one level. [`IndentKind::Unknown`] is returned when no line is indented.
+    /// ```
+    /// use tui_textarea::{IndentKind, TextArea};
+    ///
+    /// let textarea = TextArea::from(["fn f() {", "  if x {", "    y();", "  }", "}"]);
+    /// assert_eq!(textarea.detect_indent(), IndentKind::Spaces(2));
+    ///
+    /// let textarea = TextArea::from(["fn f() {", "\tif x {", "\t\ty();", "\t}", "}"]);
+    /// assert_eq!(textarea.detect_indent(), IndentKind::Tabs);
+    ///
+    /// let textarea = TextArea::from(["a", "b"]);
+    /// assert_eq!(textarea.detect_indent(), IndentKind::Unknown);
+    /// ```
+    pub fn detect_indent(&self) -> IndentKind {
+        let (mut tabs, mut spaces) = (0usize, 0usize);
+        let mut deltas = [0usize; 9]; // Frequencies of increases of space indentation from 1 to 8
+        let mut prev = 0;
+        let mut min_width = usize::MAX;
+        for line in self.lines.iter() {
+            let trimmed = line.trim_start_matches([' ', '\t']);
+            if trimmed.is_empty() {
+                continue; // Whitespace-only lines don't tell the indentation
+            }
+            let indent = &line[..line.len() - trimmed.len()];
+            if indent.starts_with('\t') {
+                tabs += 1;
+                continue;
+            }
+            let width = indent.len() - indent.trim_start_matches(' ').len();
+            if width > 0 {
+                spaces += 1;
+                min_width = min_width.min(width);
+            }
+            if prev < width && width - prev < deltas.len() {
+                deltas[width - prev] += 1;
+            }
+            prev = width;
+        }
+
+        if tabs == 0 && spaces == 0 {
+            IndentKind::Unknown
+        } else if tabs >= spaces {
+            IndentKind::Tabs
+        } else {
+            // When counts are tied, the smaller width wins. When no increase is small enough, the shallowest
+            // indentation is one level
+            let width = (1..deltas.len()).rev().max_by_key(|&w| deltas[w]).unwrap();
+            if deltas[width] > 0 {
+                IndentKind::Spaces(width as u8)
+            } else {
+                IndentKind::Spaces(min_width.min(u8::MAX as usize) as u8)
+            }
+        }
+    }
+
+    /// Insert a newline at current cursor position. This method returns if the newline was inserted or not. It is not
+    /// inserted when the number of characters reaches the limit set by [`TextArea::set_max_chars`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hi"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.lines(), ["h", "i"]);
+    /// ```
+    pub fn insert_newline(&mut self) -> bool {
+        if self.single_line {
+            return false;
+        }
+        let modified = self.replace_selection();
+        if self.remaining_chars() == 0 {
+            return modified;
+        }
+        if !self.auto_indent {
+            self.insert_newline_raw();
+            return true;
+        }
+
+        // Inserting a newline with indentation is one undo unit
+        let grouped = self.history.begin_group();
+        let (row, col) = self.cursor;
+        let line = &self.lines.line(row);
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        if indent_len > 0 && indent_len == line.len() {
+            // Clear the indentation of the line which has only whitespaces instead of propagating it
+            let removed = self.lines.remove_str(row, 0..indent_len);
+            self.cursor = (row, 0);
+            self.push_history(EditKind::DeleteStr(removed), Pos::new(row, col, indent_len), 0);
+            self.insert_newline_raw();
+        } else {
+            let offset = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+            let indent = line[..indent_len.min(offset)].to_string();
+            self.insert_newline_raw();
+            if self.insert_lines(vec![indent]) {
+                self.auto_indented = Some(self.cursor);
+            }
+        }
+        if grouped {
+            self.history.end_group();
+        }
+        true
+    }
+
+    fn insert_newline_raw(&mut self) {
+        let (row, col) = self.cursor;
+        let offset = self.lines.offset_of_col(row, col);
+        self.lines.split_line(row, offset);
+        self.cursor = (row + 1, 0);
+        self.push_history(EditKind::InsertNewline, Pos::new(row, col, offset), 0);
+    }
+
+    /// Delete a newline from **head** of current cursor line. This method returns if a newline was deleted or not in
+    /// the textarea. When some text is selected, it is deleted instead.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Down);
+    /// textarea.delete_newline();
+    /// assert_eq!(textarea.lines(), ["helloworld"]);
+    /// ```
+    pub fn delete_newline(&mut self) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+
+        let (row, _) = self.cursor;
+        if row == 0 {
+            return false;
+        }
+
+        let prev_line_end = self.lines.line_len(row - 1);
+        self.cursor = (row - 1, self.lines.char_count(row - 1));
+        self.lines.join_line(row - 1);
+        self.push_history(EditKind::DeleteNewline, Pos::new(row, 0, 0), prev_line_end);
+        true
+    }
+
+    /// Delete one character before cursor. When the cursor is at head of line, the newline before the cursor will be
+    /// removed. This method returns if some text was deleted or not in the textarea. When some text is selected, it is
+    /// deleted instead.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.delete_char();
+    /// assert_eq!(textarea.lines(), ["bc"]);
+    /// ```
+    pub fn delete_char(&mut self) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+
+        let (row, col) = self.cursor;
+        if col == 0 {
+            return self.delete_newline();
+        }
+
+        if self.auto_closers.last() == Some(&self.cursor) {
+            let start = self.lines.offset_of_col(row, col - 1);
+            let open = self.lines.char_at(row, start);
+            let offset = start + open.map_or(0, char::len_utf8);
+            if let (Some(open), Some(close)) = (open, self.lines.char_at(row, offset)) {
+                if self.auto_pair_chars.contains(&(open, close)) {
+                    // Remove the empty pair inserted automatically at once
+                    let end = offset + close.len_utf8();
+                    let removed = self.lines.remove_str(row, start..end);
+                    self.cursor = (row, col - 1);
+                    self.push_history(EditKind::DeleteStr(removed), Pos::new(row, col, offset), start);
+                    return true;
+                }
+            }
+        }
+
+        if self.auto_indent_backspace && self.auto_indented == Some(self.cursor) {
+            let line = &self.lines.line(row);
+            let offset = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+            if line[..offset].chars().all(|c| c == ' ' || c == '\t') {
+                // Remove the entire indentation inserted automatically at once
+                let removed = self.lines.remove_str(row, 0..offset);
+                self.cursor = (row, 0);
+                self.auto_indented = None;
+                self.push_history(EditKind::DeleteStr(removed), Pos::new(row, col, offset), 0);
+                return true;
+            }
+        }
+
+        let offset = self.lines.offset_of_col(row, col - 1);
+        if let Some(c) = self.lines.char_at(row, offset) {
+            self.lines.remove_str(row, offset..offset + c.len_utf8());
+            self.cursor.1 -= 1;
+            self.push_history(
+                EditKind::DeleteChar(c),
+                Pos::new(row, col, offset + c.len_utf8()),
+                offset,
+            );
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Delete one character next to cursor. When the cursor is at end of line, the newline next to the cursor will be
+    /// removed. This method returns if a character was deleted or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.delete_next_char();
+    /// assert_eq!(textarea.lines(), ["ac"]);
+    /// ```
+    pub fn delete_next_char(&mut self) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+
+        let before = self.cursor;
+        self.move_cursor_with_shift(CursorMove::Forward, false);
+        if before == self.cursor {
+            return false; // Cursor didn't move, meant no character at next of cursor.
+        }
+
+        self.delete_char()
+    }
+
+    /// Cut the selected text to the yank buffer. When nothing is selected, the line at the cursor is cut. This method
+    /// returns if some text was deleted or not in the textarea. When the system clipboard is synchronized by
+    /// `TextArea::set_clipboard_sync` (`clipboard` feature), the cut text is also written to the clipboard.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// assert!(textarea.cut());
+    /// assert_eq!(textarea.lines(), ["bc", "def"]);
+    /// assert_eq!(textarea.yank_text(), "a");
+    ///
+    /// assert!(textarea.cut());
+    /// assert_eq!(textarea.lines(), ["def"]);
+    /// assert_eq!(textarea.yank_text(), "bc");
+    /// ```
+    pub fn cut(&mut self) -> bool {
+        let modified = self.delete_line(true);
+        if modified {
+            self.sync_clipboard();
+        }
+        modified
+    }
+
+    pub fn delete_line(&mut self, should_yank: bool) -> bool {
+        if self.delete_selection(should_yank) {
+            return true;
+        }
+
+        let (row, col) = self.cursor;
+
+        let edit = if self.lines.len() == 1 {
+            // if there is only one line and it is empty there is nothin to do
+            if self.lines.line_len(0) == 0 {
+                return false;
+            }
+
+            let line_to_remove = &self.lines.line(row);
+
+            Edit::new(
+                EditKind::DeleteStr(line_to_remove.to_string()),
+                Pos::new(row, col, col),
+                Pos::new(0, 0, 0),
+            )
+        } else if row == self.lines.len().saturating_sub(1) {
+            let line_to_remove = &self.lines.line(row);
+            let new_row = row - 1;
+            let new_col = min(col, self.lines.char_count(new_row).saturating_sub(1));
+
+            Edit::new(
+                EditKind::DeleteLine(row, line_to_remove.to_string()),
+                Pos::new(row, col, 0),
+                Pos::new(new_row, new_col, 0),
+            )
+        } else {
+            let line_to_remove = &self.lines.line(row);
+            let new_col = min(col, self.lines.char_count(row + 1).saturating_sub(1));
+
+            Edit::new(
+                EditKind::DeleteLine(row, line_to_remove.to_string()),
+                Pos::new(row, col, 0),
+                Pos::new(row, new_col, 0),
+            )
+        };
+
+        if should_yank {
+            self.kill(self.lines.line(row).into_owned().into(), Kill::Region);
+        }
+        self.push_change(edit);
+
+        true
+    }
+
+    /// Delete string from cursor to end of the line. When the cursor is at end of line, the newline next to the cursor
+    /// is removed. This method returns if some text was deleted or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abcde"]);
+    ///
+    /// // Move to 'c'
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.move_cursor(CursorMove::Forward);
+    ///
+    /// textarea.delete_line_by_end();
+    /// assert_eq!(textarea.lines(), ["ab"]);
+    /// ```
+    pub fn delete_line_by_end(&mut self) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+        if self.delete_piece(self.cursor.1, usize::MAX) {
+            return true;
+        }
+        self.delete_next_char() // At the end of the line. Try to delete next line
+    }
+
+    /// Delete string from cursor to head of the line. When the cursor is at head of line, the newline before the cursor
+    /// will be removed. This method returns if some text was deleted or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abcde"]);
+    ///
+    /// // Move to 'c'
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.move_cursor(CursorMove::Forward);
+    ///
+    /// textarea.delete_line_by_head();
+    /// assert_eq!(textarea.lines(), ["cde"]);
+    /// ```
+    pub fn delete_line_by_head(&mut self) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+        if self.delete_piece(0, self.cursor.1) {
+            return true;
+        }
+        self.delete_newline()
+    }
+
+    /// Delete a word before cursor. Word boundary appears at spaces, punctuations, and others. For example `fn foo(a)`
+    /// consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at head of line, the newline before the cursor
+    /// will be removed.
+    ///
+    /// This method returns if some text was deleted or not in the textarea.
+    ///
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.delete_word();
+    /// assert_eq!(textarea.lines(), ["aaa bbb "]);
+    /// textarea.delete_word();
+    /// assert_eq!(textarea.lines(), ["aaa bbb"]);
+    /// ```
+    pub fn delete_word(&mut self) -> bool {
+        self.delete_word_by(find_word_start_backward)
+    }
+
+    /// Delete a sub-word before cursor. See [`CursorMove::SubWordForward`] for sub-word boundaries. When the cursor is
+    /// at head of line, the newline before the cursor will be removed.
+    ///
+    /// This method returns if some text was deleted or not in the textarea.
+    ///
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["parseHTTPResponse"]);
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.delete_subword();
+    /// assert_eq!(textarea.lines(), ["parseHTTP"]);
+    /// textarea.delete_subword();
+    /// assert_eq!(textarea.lines(), ["parse"]);
+    /// ```
+    pub fn delete_subword(&mut self) -> bool {
+        self.delete_word_by(find_subword_start_backward)
+    }
+
+    fn delete_word_by(&mut self, find_start: fn(&str, usize) -> Option<usize>) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+        let (r, c) = self.cursor;
+        if let Some(col) = find_start(&self.lines.line(r), c) {
+            self.delete_piece(col, c - col)
+        } else if c > 0 {
+            self.delete_piece(0, c)
+        } else {
+            self.delete_newline()
+        }
+    }
+
+    /// Delete a word next to cursor. Word boundary appears at spaces, punctuations, and others. For example `fn foo(a)`
+    /// consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at end of line, the newline next to the cursor
+    /// will be removed.
+    ///
+    /// This method returns if some text was deleted or not in the textarea.
+    ///
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), [" bbb ccc"]);
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), ["bbb ccc"]);
+    /// ```
+    pub fn delete_next_word(&mut self) -> bool {
+        self.delete_next_word_by(find_word_end_forward)
+    }
+
+    /// Delete a sub-word next to cursor. See [`CursorMove::SubWordForward`] for sub-word boundaries. When the cursor is
+    /// at end of line, the newline next to the cursor will be removed.
+    ///
+    /// This method returns if some text was deleted or not in the textarea.
+    ///
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["parseHTTPResponse"]);
+    ///
+    /// textarea.delete_next_subword();
+    /// assert_eq!(textarea.lines(), ["HTTPResponse"]);
+    /// textarea.delete_next_subword();
+    /// assert_eq!(textarea.lines(), ["Response"]);
+    /// ```
+    pub fn delete_next_subword(&mut self) -> bool {
+        self.delete_next_word_by(find_subword_end_forward)
+    }
+
+    fn delete_next_word_by(&mut self, find_end: fn(&str, usize) -> Option<usize>) -> bool {
+        if self.delete_selection(false) {
+            return true;
+        }
+        let (r, c) = self.cursor;
+        let line = &self.lines.line(r);
+        if let Some(col) = find_end(line, c) {
+            self.delete_piece(c, col - c)
+        } else {
+            let end_col = line.chars().count();
+            if c < end_col {
+                self.delete_piece(c, end_col - c)
+            } else if r + 1 < self.lines.len() {
+                self.cursor = (r + 1, 0);
+                self.delete_newline()
+            } else {
+                false
+            }
+        }
+    }
+
+    /// Paste a string previously deleted by [`TextArea::delete_line_by_head`], [`TextArea::delete_line_by_end`],
+    /// [`TextArea::delete_word`], [`TextArea::delete_next_word`] and their sub-word variants. This method returns if
+    /// some text was inserted or not in the textarea. When the system clipboard is synchronized by
+    /// `TextArea::set_clipboard_sync` (`clipboard` feature) and it has text different from the yanked text, the
+    /// clipboard text is pasted and yanked instead.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.delete_next_word();
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.paste();
+    /// assert_eq!(textarea.lines(), [" bbb cccaaa"]);
+    /// ```
+    pub fn paste(&mut self) -> bool {
+        #[cfg(feature = "clipboard")]
+        if let Some(text) = self.clipboard.as_mut().and_then(|c| c.get_text()) {
+            if text != self.yank.to_string() {
+                self.set_yank_text(text);
+            }
+        }
+
+        // Lines and a block replace the selection as they are. Other text replaces the content of selected lines
+        let modified = match &self.yank {
+            YankText::Block(_) | YankText::Lines(_) if !self.single_line => self.delete_selection(false),
+            _ => self.replace_selection(),
+        };
+        let lines = match self.yank.clone() {
+            YankText::Piece(s) => vec![s],
+            YankText::Block(b) if !self.single_line => return self.paste_block(b) || modified,
+            YankText::Lines(l) if !self.single_line => return self.paste_lines(l) || modified,
+            YankText::Chunk(c) | YankText::Block(c) | YankText::Lines(c) => c,
+        };
+        let start = self.cursor;
+        if self.insert_lines(lines) {
+            self.last_paste = Some((start, self.cursor));
+            true
+        } else {
+            modified
+        }
+    }
+
+    /// Replace the text inserted by [`TextArea::paste`] just before with the next older entry in the kill ring, and
+    /// rotate the kill ring so that the inserted entry becomes the newest one. This is like `M-y` in Emacs. This
+    /// method does nothing and returns `false` when the last edit was not a paste, the cursor was moved after the
+    /// paste, or the kill ring has less than 2 entries. See [`TextArea::set_kill_ring_size`] to enable the kill ring.
+    /// It is not bound to any key by default. Bind [`EditAction::PasteRotate`](crate::EditAction::PasteRotate) with
+    /// [`TextArea::keymap_mut`] to rotate with a key such as `Alt+Y`.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// textarea.set_kill_ring_size(10);
+    ///
+    /// textarea.delete_next_word();
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.delete_next_word();
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), ["  "]);
+    /// assert_eq!(textarea.kill_ring(), ["ccc", "bbb", "aaa"]);
+    ///
+    /// textarea.paste();
+    /// assert_eq!(textarea.lines(), ["  ccc"]);
+    /// textarea.paste_rotate();
+    /// assert_eq!(textarea.lines(), ["  bbb"]);
+    /// textarea.paste_rotate();
+    /// assert_eq!(textarea.lines(), ["  aaa"]);
+    /// assert_eq!(textarea.kill_ring(), ["aaa", "ccc", "bbb"]);
+    ///
+    /// // Rotation is one undo unit
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["  bbb"]);
+    /// ```
+    pub fn paste_rotate(&mut self) -> bool {
+        let Some((start, end)) = self.last_paste else {
+            return false;
+        };
+        if self.kill_ring.len() < 2 || self.cursor != end {
+            return false;
+        }
+        self.kill_ring.rotate_left(1);
+        let text = self.kill_ring[0].clone();
+        self.yank = YankText::from_text(&text);
+        if !self.apply_edit(start, end, &text) {
+            return false;
+        }
+        self.last_paste = Some((start, self.cursor));
+        true
+    }
+
+    /// Paste the given text at the cursor position as one undo unit, replacing the selected text. This is useful when
+    /// an application reads the system clipboard by itself. Unlike [`TextArea::insert_str`], both `\r\n` and `\r` are
+    /// treated as newlines since some terminals send pasted newlines as `\r`. The yank buffer is not changed. This
+    /// method returns if some text was inserted or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["ab"]);
+    /// textarea.move_cursor(CursorMove::Forward);
+    ///
+    /// textarea.paste_str("x\ry\r\nz");
+    /// assert_eq!(textarea.lines(), ["ax", "y", "zb"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["ab"]);
+    /// ```
+    pub fn paste_str(&mut self, text: &str) -> bool {
+        let modified = self.replace_selection();
+        let lines = text
+            .split("\r\n")
+            .flat_map(|s| s.split(['\r', '\n']))
+            .map(String::from)
+            .collect();
+        self.insert_lines(lines) || modified
+    }
+
+    /// Handle text pasted by the terminal with bracketed paste such as crossterm's `Event::Paste` or termwiz's
+    /// `InputEvent::Paste`. The text is inserted verbatim as one undo unit without processing each character as a key
+    /// input, so features like auto-pairs are not applied. `\r\n` and `\r` are normalized to newlines as
+    /// [`TextArea::paste_str`]. Like [`TextArea::input`], nothing is inserted while the textarea is read-only or ignores
+    /// inputs. This method returns if some text was inserted or not in the textarea.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_auto_pairs(true);
+    /// textarea.insert_str("x");
+    ///
+    /// assert!(textarea.handle_paste("f(a,\r\n  b)"));
+    /// assert_eq!(textarea.lines(), ["xf(a,", "  b)"]);
+    ///
+    /// // Pasted text is one undo entry
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["x"]);
+    /// ```
+    ///
+    /// With crossterm, bracketed paste must be enabled to receive pasted text as one event.
+    /// ```ignore
+    /// crossterm::execute!(stdout, crossterm::event::EnableBracketedPaste)?;
+    ///
+    /// match crossterm::event::read()? {
+    ///     crossterm::event::Event::Paste(text) => textarea.handle_paste(&text),
+    ///     event => textarea.input(event),
+    /// };
+    /// ```
+    pub fn handle_paste(&mut self, text: &str) -> bool {
+        if self.ignores_input() || self.read_only {
+            return false;
+        }
+        self.unicode_input = None;
+        self.history.stop_merging();
+        let grouped = self.history.begin_group();
+        let modified = self.paste_str(text);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        if modified {
+            self.validate();
+        }
+        modified
+    }
+
+    /// Start text selection at the cursor position. If text selection is already ongoing, the start position is reset.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "aaa");
+    /// ```
+    pub fn start_selection(&mut self) {
+        self.selection_start = Some(self.cursor);
+        self.selection_mode = SelectionMode::Char;
+    }
+
+    /// Stop the current text selection. This method does nothing if text selection is not ongoing.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::WordForward);
+    ///
+    /// // Cancel the ongoing text selection
+    /// textarea.cancel_selection();
+    ///
+    /// // As the result, this `copy` call copies the whole line when no selection
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "aaa bbb ccc");
+    /// ```
+    pub fn cancel_selection(&mut self) {
+        self.selection_start = None;
+        self.selection_mode = SelectionMode::Char;
+    }
+
+    /// Get the selected text and cancel the selection. This is the same as [`TextArea::selected_text`] followed by
+    /// [`TextArea::cancel_selection`] except that `None` is returned when the selection spans multiple lines. The
+    /// selection is canceled in any case.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb", "ccc"]);
+    ///
+    /// textarea.set_selection((0, 4), (0, 7));
+    /// assert_eq!(textarea.take_selection().as_deref(), Some("bbb"));
+    /// assert!(!textarea.is_selecting());
+    ///
+    /// textarea.set_selection((0, 4), (1, 3));
+    /// assert_eq!(textarea.take_selection(), None);
+    /// assert!(!textarea.is_selecting());
+    /// ```
+    pub fn take_selection(&mut self) -> Option<Cow<'_, str>> {
+        let range = self.take_selection_range().filter(|(start, end)| start.row == end.row);
+        let (start, end) = range?;
+        Some(self.lines.slice(start.row, start.offset..end.offset))
+    }
+
+    /// Get the selected text without any side effects. The selection, the cursor, and the yank buffer are not changed,
+    /// so this method can be called on rendering. Lines of a multi-line selection are joined with `\n`, and so are the
+    /// fragments of column selection. `None` is returned when nothing is selected.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb", "ccc"]);
+    /// assert_eq!(textarea.selected_text(), None);
+    ///
+    /// textarea.set_selection((0, 4), (1, 3));
+    /// assert_eq!(textarea.selected_text().as_deref(), Some("bbb\nccc"));
+    /// assert!(textarea.is_selecting());
+    /// assert_eq!(textarea.yank_text(), "");
+    /// ```
+    pub fn selected_text(&self) -> Option<Cow<'_, str>> {
+        if self.is_column_selecting() {
+            return Some(self.column_text().join("\n").into());
+        }
+        let (start, end) = self.selection_positions()?;
+        if start.row == end.row {
+            return Some(self.lines.slice(start.row, start.offset..end.offset));
+        }
+        let mut text = self.lines.line(start.row)[start.offset..].to_string();
+        for line in self.lines.iter_in(start.row + 1..end.row) {
+            text.push('\n');
+            text.push_str(&line);
+        }
+        text.push('\n');
+        text.push_str(&self.lines.line(end.row)[..end.offset]);
+        Some(Cow::Owned(text))
+    }
+
+    /// Get the number of lines touched by the selection for status display. A line where the selection ends at its
+    /// head is not counted. `0` is returned when nothing is selected.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
+    /// assert_eq!(textarea.selection_line_count(), 0);
+    ///
+    /// textarea.set_selection((0, 1), (1, 2));
+    /// assert_eq!(textarea.selection_line_count(), 2);
+    ///
+    /// // The selection ends at the head of the last line
+    /// textarea.set_selection((0, 0), (2, 0));
+    /// assert_eq!(textarea.selection_line_count(), 2);
+    /// ```
+    pub fn selection_line_count(&self) -> usize {
+        if let Some((top, bottom, _, _)) = self.column_rect() {
+            return bottom - top + 1;
+        }
+        if self.selection_positions().is_none() {
+            return 0;
+        }
+        let (start, last) = self.selected_rows();
+        last - start + 1
+    }
+
+    /// Select the entire text. Cursor moves to the end of the text buffer. When text selection is already ongoing,
+    /// it is canceled.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
+    ///
+    /// textarea.select_all();
+    ///
+    /// // Cut the entire text;
+    /// textarea.delete_line(true);
+    ///
+    /// assert_eq!(textarea.lines(), [""]); // Buffer is now empty
+    /// assert_eq!(textarea.yank_text(), "aaa\nbbb\nccc");
+    /// ```
+    pub fn select_all(&mut self) {
+        self.move_cursor_without_jump(CursorMove::Jump(u64::MAX, u64::MAX));
+        self.selection_start = Some((0, 0));
+        self.selection_mode = SelectionMode::Char;
+    }
+
+    /// Select the word at the cursor, or the word ending at the cursor when the cursor is not on a word. Words are
+    /// split in the same way as [`CursorMove::WordForward`]. The cursor moves to the end of the word. This method
+    /// returns if a word was selected or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 5));
+    /// assert!(textarea.select_word());
+    /// assert_eq!(textarea.selection_range(), Some(((0, 4), (0, 7))));
+    /// ```
+    pub fn select_word(&mut self) -> bool {
+        let (row, col) = self.cursor;
+        let words = word_ranges(&self.lines.line(row));
+        let Some(&(start, end)) = words
+            .iter()
+            .find(|(start, end)| *start <= col && col < *end)
+            .or_else(|| words.iter().find(|(_, end)| *end == col))
+        else {
+            return false;
+        };
+        self.set_selection((row, start), (row, end));
+        true
+    }
+
+    /// Select the line at the cursor with linewise selection. See [`TextArea::start_selection_linewise`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb"]);
+    ///
+    /// textarea.select_line();
+    /// textarea.cut();
+    /// assert_eq!(textarea.lines(), ["bbb"]);
+    /// assert_eq!(textarea.yank_text(), "aaa\n");
+    /// ```
+    pub fn select_line(&mut self) {
+        self.start_selection_linewise();
+    }
+
+    /// Return if text selection is ongoing or not.
+    /// ```
+    /// use tui_textarea::{TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert!(!textarea.is_selecting());
+    /// textarea.start_selection();
+    /// assert!(textarea.is_selecting());
+    /// textarea.cancel_selection();
+    /// assert!(!textarea.is_selecting());
+    /// ```
+    pub fn is_selecting(&self) -> bool {
+        self.selection_start.is_some()
+    }
+
+    /// Get the range of the current text selection as a pair of start and end `(row, col)` positions. The start position
+    /// always precedes the end position regardless of the direction of the selection. `None` is returned when text
+    /// selection is not ongoing.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb", "ccc"]);
+    /// assert_eq!(textarea.selection_range(), None);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Jump(0, 4));
+    /// assert_eq!(textarea.selection_range(), Some(((0, 4), (1, 0))));
+    /// ```
+    pub fn selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        if let Some((top, bottom)) = self.linewise_rows() {
+            return Some(((top, 0), self.linewise_end(bottom)));
+        }
+        let start = self.selection_start?;
+        let end = self.cursor;
+        Some(if start <= end { (start, end) } else { (end, start) })
+    }
+
+    /// Select the text between the `start` and `end` `(row, col)` positions. The cursor moves to the `end` position.
+    /// `end` may precede `start` to select the text backward. Positions out of the text buffer are clamped to the
+    /// nearest valid positions.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb", "ccc"]);
+    ///
+    /// textarea.set_selection((0, 4), (1, 100));
+    /// assert_eq!(textarea.cursor(), (1, 3));
+    /// assert_eq!(textarea.selection_range(), Some(((0, 4), (1, 3))));
+    ///
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "bbb\nccc");
+    /// ```
+    pub fn set_selection(&mut self, start: (usize, usize), end: (usize, usize)) {
+        let clamp = |(row, col): (usize, usize)| {
+            let row = min(row, self.lines.len() - 1);
+            (row, min(col, self.lines.char_count(row)))
+        };
+        self.selection_start = Some(clamp(start));
+        self.selection_mode = SelectionMode::Char;
+        self.cursor = clamp(end);
+    }
+
+    /// Start linewise selection at the cursor like `Shift+V` in Vim. The selection always covers the whole lines from
+    /// the line of the start position to the line of the cursor regardless of their columns. Copying or cutting the
+    /// linewise selection yanks the whole lines, and pasting them inserts them as new lines above the cursor line
+    /// instead of splitting the line. Typing or pasting other text while lines are selected replaces the content of the
+    /// lines and keeps the line break after them.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 1));
+    /// textarea.start_selection_linewise();
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert!(textarea.is_linewise_selecting());
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (2, 0))));
+    ///
+    /// textarea.cut();
+    /// assert_eq!(textarea.lines(), ["ccc"]);
+    /// assert_eq!(textarea.yank_text(), "aaa\nbbb\n");
+    ///
+    /// // Pasting inserts the lines above the cursor line
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.paste();
+    /// assert_eq!(textarea.lines(), ["aaa", "bbb", "ccc"]);
+    /// assert_eq!(textarea.cursor(), (2, 3));
+    /// ```
+    pub fn start_selection_linewise(&mut self) {
+        self.selection_start = Some(self.cursor);
+        self.selection_mode = SelectionMode::Line;
+    }
+
+    /// Return if linewise selection started by [`TextArea::start_selection_linewise`] is ongoing or not.
+    pub fn is_linewise_selecting(&self) -> bool {
+        self.selection_mode == SelectionMode::Line && self.selection_start.is_some()
+    }
+
+    /// Start column (rectangular) selection at the cursor position like `Ctrl+V` in Vim. The selected rectangle spans
+    /// the rows between the start position and the cursor, and the display columns between them. Wide characters and
+    /// hard tabs are counted by their display width. Moving the cursor extends the rectangle. Copying the column
+    /// selection yields the fragments of the rows joined with newlines, and pasting them inserts the fragments
+    /// block-wise at the same column of the following rows. Cutting or typing a character deletes the fragments.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a = 1", "bb = 2", "c = 3"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 1));
+    /// textarea.start_column_selection();
+    /// textarea.move_cursor(CursorMove::Jump(2, 3));
+    /// assert!(textarea.is_column_selecting());
+    ///
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), " =\nb \n =");
+    ///
+    /// // Paste the block at the head of lines
+    /// textarea.cancel_selection();
+    /// textarea.move_cursor(CursorMove::Jump(0, 0));
+    /// textarea.paste();
+    /// assert_eq!(textarea.lines(), [" =a = 1", "b bb = 2", " =c = 3"]);
+    /// ```
+    pub fn start_column_selection(&mut self) {
+        self.selection_start = Some(self.cursor);
+        self.selection_mode = SelectionMode::Column;
+    }
+
+    /// Return if column selection started by [`TextArea::start_column_selection`] is ongoing or not.
+    pub fn is_column_selecting(&self) -> bool {
+        self.selection_mode == SelectionMode::Column && self.selection_start.is_some()
+    }
+
+    /// Insert the string at the left edge of the column selection on every selected row as one undo unit. Without
+    /// column selection, the string is inserted at the cursor. Only the first line of the string is inserted. Rows
+    /// shorter than the column are skipped, or padded with spaces when [`TextArea::set_column_padding`] is enabled. The
+    /// column selection moves after the inserted string so that calling this method again appends to it. This method
+    /// returns if some text was inserted or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo = 1", "x", "bar = 2"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 3));
+    /// textarea.start_column_selection();
+    /// textarea.move_cursor(CursorMove::Jump(2, 3));
+    ///
+    /// assert!(textarea.insert_str_columnwise(":"));
+    /// assert_eq!(textarea.lines(), ["foo: = 1", "x", "bar: = 2"]);
+    /// assert!(textarea.insert_str_columnwise("!"));
+    /// assert_eq!(textarea.lines(), ["foo:! = 1", "x", "bar:! = 2"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["foo: = 1", "x", "bar: = 2"]);
+    /// ```
+    pub fn insert_str_columnwise<S: AsRef<str>>(&mut self, s: S) -> bool {
+        let s = s.as_ref().split(['\n', '\r']).next().unwrap_or_default();
+        if s.is_empty() {
+            return false;
+        }
+        let (top, bottom, left) = match self.column_rect() {
+            Some((top, bottom, left, _)) => (top, bottom, left),
+            None => {
+                let (row, col) = self.cursor;
+                (row, row, self.display_col(row, col))
+            }
+        };
+        let texts = vec![s.to_string(); bottom - top + 1];
+        let items = self.column_insertions(top, left, texts, self.column_padding);
+        let chars = items.iter().map(|(_, _, s)| s.chars().count()).sum::<usize>();
+        if chars > self.remaining_chars() {
+            return false;
+        }
+
+        // Positions at the insertion column move after the inserted string
+        let moved = |(row, col): (usize, usize)| {
+            items
+                .iter()
+                .find(|(r, c, _)| (*r, *c) == (row, col))
+                .map(|(r, c, s)| (*r, c + s.chars().count()))
+        };
+        let cursor = moved(self.cursor);
+        let anchor = self.selection_start.and_then(moved);
+        self.history.stop_merging();
+        if !self.edit_lines(true, items) {
+            return false;
+        }
+        if let Some(cursor) = cursor {
+            self.cursor = cursor;
+        }
+        if anchor.is_some() {
+            self.selection_start = anchor;
+        }
+        true
+    }
+
+    /// Delete the text in the column selection on every selected row as one undo unit. The column selection shrinks to
+    /// the left edge so that [`TextArea::insert_str_columnwise`] can insert replacements. The deleted text is not
+    /// yanked. Use [`TextArea::cut`] to yank it. This method returns if some text was deleted or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a: 1", "b: 2"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 1));
+    /// textarea.start_column_selection();
+    /// textarea.move_cursor(CursorMove::Jump(1, 3));
+    ///
+    /// assert!(textarea.delete_column_selection());
+    /// assert_eq!(textarea.lines(), ["a1", "b2"]);
+    /// textarea.insert_str_columnwise(" = ");
+    /// assert_eq!(textarea.lines(), ["a = 1", "b = 2"]);
+    /// ```
+    pub fn delete_column_selection(&mut self) -> bool {
+        let items = self
+            .column_ranges()
+            .into_iter()
+            .filter(|(_, start, end)| start < end)
+            .map(|(row, start, end)| {
+                let text = self.lines.line(row).chars().skip(start).take(end - start).collect();
+                (row, start, text)
+            })
+            .collect();
+        self.history.stop_merging();
+        self.edit_lines(false, items)
+    }
+
+    /// Set if rows shorter than the column are padded with spaces by [`TextArea::insert_str_columnwise`]. When
+    /// disabled, such rows are skipped. This is disabled by default.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "", "abc"]);
+    /// textarea.set_column_padding(true);
+    /// assert!(textarea.column_padding());
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 2));
+    /// textarea.start_column_selection();
+    /// textarea.move_cursor(CursorMove::Jump(2, 2));
+    /// textarea.insert_str_columnwise("|");
+    /// assert_eq!(textarea.lines(), ["ab|c", "  |", "ab|c"]);
+    /// ```
+    pub fn set_column_padding(&mut self, enabled: bool) {
+        self.column_padding = enabled;
+    }
+
+    /// Get if rows shorter than the column are padded. See [`TextArea::set_column_padding`].
+    pub fn column_padding(&self) -> bool {
+        self.column_padding
+    }
+
+    /// Add a secondary cursor at the `(row, col)` position for multi-cursor editing. The position is clamped to the
+    /// text. Actions performed by [`TextArea::input`] and [`TextArea::perform`] which insert or delete characters,
+    /// move the cursor, or paste text are applied at every cursor as one undo unit. Positions of cursors are adjusted
+    /// for edits made at other cursors, and overlapping cursors are merged. Other methods such as
+    /// [`TextArea::insert_char`] edit only at the primary cursor returned by [`TextArea::cursor`]. This method returns
+    /// if the cursor was added or not. A cursor is not added at the position of another cursor.
+    /// ```
+    /// use tui_textarea::{EditAction, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    ///
+    /// assert!(textarea.add_cursor(1, 1));
+    /// assert_eq!(textarea.secondary_cursors(), [(1, 1)]);
+    ///
+    /// textarea.perform(EditAction::InsertChar('x'));
+    /// assert_eq!(textarea.lines(), ["xabc", "dxef"]);
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    /// assert_eq!(textarea.secondary_cursors(), [(1, 2)]);
+    ///
+    /// // All cursors' edits are undone at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc", "def"]);
+    /// ```
+    pub fn add_cursor(&mut self, row: usize, col: usize) -> bool {
+        let row = min(row, self.lines.len() - 1);
+        let cursor = (row, min(col, self.lines.char_count(row)));
+        if cursor == self.cursor || self.secondary_cursors.iter().any(|c| c.cursor == cursor) {
+            return false;
+        }
+        self.secondary_cursors.push(SecondaryCursor { cursor, anchor: None });
+        self.normalize_cursors();
+        true
+    }
+
+    /// Add a secondary cursor on the line below the lowest cursor at the same column as the primary cursor. This
+    /// method returns `false` when there is no line below it. See [`TextArea::add_cursor`] for multi-cursor editing.
+    /// ```
+    /// use tui_textarea::{EditAction, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a = 1", "b = 2", "c"]);
+    ///
+    /// assert!(textarea.add_cursor_below());
+    /// assert!(textarea.add_cursor_below());
+    /// assert!(!textarea.add_cursor_below());
+    /// assert_eq!(textarea.secondary_cursors(), [(1, 0), (2, 0)]);
+    ///
+    /// textarea.perform(EditAction::InsertChar('#'));
+    /// assert_eq!(textarea.lines(), ["#a = 1", "#b = 2", "#c"]);
+    /// ```
+    pub fn add_cursor_below(&mut self) -> bool {
+        let row = self
+            .secondary_cursors
+            .iter()
+            .map(|c| c.cursor.0)
+            .fold(self.cursor.0, max);
+        !self.single_line && row + 1 < self.lines.len() && self.add_cursor(row + 1, self.cursor.1)
+    }
+
+    /// Add a secondary cursor on the line above the highest cursor at the same column as the primary cursor. This
+    /// method returns `false` when there is no line above it. See [`TextArea::add_cursor`] for multi-cursor editing.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "abc", "abc"]);
+    /// textarea.move_cursor(CursorMove::Jump(2, 2));
+    ///
+    /// assert!(textarea.add_cursor_above());
+    /// assert!(textarea.add_cursor_above());
+    /// assert!(!textarea.add_cursor_above());
+    /// assert_eq!(textarea.secondary_cursors(), [(0, 2), (1, 2)]);
+    /// ```
+    pub fn add_cursor_above(&mut self) -> bool {
+        let row = self
+            .secondary_cursors
+            .iter()
+            .map(|c| c.cursor.0)
+            .fold(self.cursor.0, min);
+        !self.single_line && row > 0 && self.add_cursor(row - 1, self.cursor.1)
+    }
+
+    /// Add a secondary cursor selecting the next occurrence of the selected text like `Ctrl+D` in modern editors. The
+    /// occurrence is searched after the selections of all cursors and the search wraps around at the end of the text.
+    /// When nothing is selected, the identifier under the primary cursor is selected first. This method returns if a
+    /// selection was added or not. See [`TextArea::add_cursor`] for multi-cursor editing.
+    /// ```
+    /// use tui_textarea::{EditAction, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["let foo = foo + foo;"]);
+    ///
+    /// // Select `foo` at the cursor and the next 2 occurrences
+    /// textarea.set_selection((0, 4), (0, 7));
+    /// assert!(textarea.select_next_occurrence());
+    /// assert!(textarea.select_next_occurrence());
+    /// assert!(!textarea.select_next_occurrence());
+    ///
+    /// // Replace all of them
+    /// for c in "bar".chars() {
+    ///     textarea.perform(EditAction::InsertChar(c));
+    /// }
+    /// assert_eq!(textarea.lines(), ["let bar = bar + bar;"]);
+    /// ```
+    pub fn select_next_occurrence(&mut self) -> bool {
+        let Some((start, end)) = self.selection_range().filter(|(s, e)| s != e) else {
+            let (row, col) = self.cursor;
+            let Some((start, end)) = find_identifier(&self.lines.line(row), col) else {
+                return false;
+            };
+            self.set_selection((row, start), (row, end));
+            return true;
+        };
+
+        let text = self.lines.join("\n");
+        let row_offsets: Vec<_> = iter::once(0)
+            .chain(self.lines.iter().scan(0, |offset, line| {
+                *offset += line.len() + 1;
+                Some(*offset)
+            }))
+            .collect();
+        let offset = |(row, col): (usize, usize)| row_offsets[row] + self.line_offset(row, col);
+        let pos = |offset: usize| {
+            let row = row_offsets.partition_point(|&o| o <= offset) - 1;
+            (row, self.lines.line(row)[..offset - row_offsets[row]].chars().count())
+        };
+
+        let selected = &text[offset(start)..offset(end)];
+        let mut ranges = vec![(start, end)];
+        ranges.extend(self.secondary_cursors.iter().map(SecondaryCursor::range));
+        let from = ranges.iter().map(|(_, e)| offset(*e)).max().unwrap_or(0);
+        let occurrences: Vec<_> = text
+            .match_indices(selected)
+            .map(|(i, _)| (i, i + selected.len()))
+            .filter(|(s, e)| !ranges.iter().any(|r| (offset(r.0), offset(r.1)) == (*s, *e)))
+            .collect();
+        let Some(&(s, e)) = occurrences.iter().find(|(s, _)| *s >= from).or(occurrences.first()) else {
+            return false;
+        };
+        let (start, end) = (pos(s), pos(e));
+        let overlapped = ranges.iter().any(|&(rs, re)| start < re && rs < end);
+        if overlapped {
+            return false;
+        }
+        self.secondary_cursors.push(SecondaryCursor {
+            cursor: end,
+            anchor: Some(start),
+        });
+        self.normalize_cursors();
+        true
+    }
+
+    /// Remove all secondary cursors added by [`TextArea::add_cursor`] and other methods for multi-cursor editing.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// textarea.add_cursor_below();
+    ///
+    /// textarea.clear_secondary_cursors();
+    /// assert!(textarea.secondary_cursors().is_empty());
+    /// ```
+    pub fn clear_secondary_cursors(&mut self) {
+        self.secondary_cursors.clear();
+    }
+
+    /// Get the `(row, col)` positions of the secondary cursors in the order of the text. The primary cursor is not
+    /// included. See [`TextArea::add_cursor`] for multi-cursor editing.
+    pub fn secondary_cursors(&self) -> Vec<(usize, usize)> {
+        self.secondary_cursors.iter().map(|c| c.cursor).collect()
+    }
+
+    // Perform the action at every cursor as one undo unit. Other cursors follow the edits made at each cursor
+    fn perform_at_cursors(&mut self, action: EditAction) -> bool {
+        self.normalize_cursors();
+        self.selection_mode = SelectionMode::Char;
+        self.secondary_cursors.push(SecondaryCursor {
+            cursor: self.cursor,
+            anchor: self.selection_start,
+        });
+        let primary = self.secondary_cursors.len() - 1;
+
+        self.history.stop_merging();
+        let grouped = self.history.begin_group();
+        let mut modified = false;
+        for i in 0..self.secondary_cursors.len() {
+            let c = self.secondary_cursors[i];
+            self.cursor = c.cursor;
+            self.selection_start = c.anchor;
+            // Edits at different cursors must not be merged into one edit
+            self.history.stop_merging();
+            modified |= self.perform_at_cursor(action);
+            self.secondary_cursors[i] = SecondaryCursor {
+                cursor: self.cursor,
+                anchor: self.selection_start,
+            };
+        }
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+
+        let c = self.secondary_cursors.remove(primary);
+        self.cursor = c.cursor;
+        self.selection_start = c.anchor;
+        self.normalize_cursors();
+        modified
+    }
+
+    // Clamp the secondary cursors to the text, sort them, and merge cursors overlapping with the primary cursor or
+    // with each other
+    fn normalize_cursors(&mut self) {
+        if self.secondary_cursors.is_empty() {
+            return;
+        }
+        let clamp = |(row, col): (usize, usize)| {
+            let row = min(row, self.lines.len() - 1);
+            (row, min(col, self.lines.char_count(row)))
+        };
+        let mut cursors = std::mem::take(&mut self.secondary_cursors);
+        for c in &mut cursors {
+            c.cursor = clamp(c.cursor);
+            c.anchor = c.anchor.map(clamp).filter(|&a| a != c.cursor);
+        }
+        cursors.sort_by_key(SecondaryCursor::range);
+
+        let overlaps = |(s1, e1): ((usize, usize), (usize, usize)), (s2, e2): ((usize, usize), (usize, usize))| {
+            s1 == s2 || s1 < e2 && s2 < e1
+        };
+        let primary = SecondaryCursor {
+            cursor: self.cursor,
+            anchor: self.selection_start,
+        }
+        .range();
+        let mut merged: Vec<SecondaryCursor> = vec![];
+        for c in cursors {
+            let range = c.range();
+            if overlaps(range, primary) || merged.last().map_or(false, |m| overlaps(m.range(), range)) {
+                continue;
+            }
+            merged.push(c);
+        }
+        self.secondary_cursors = merged;
+    }
+
+    // Columns of the secondary cursors in the line at the row
+    fn secondary_cursor_cols(&self, row: usize) -> Vec<usize> {
+        self.secondary_cursors
+            .iter()
+            .filter(|c| c.cursor.0 == row)
+            .map(|c| c.cursor.1)
+            .collect()
+    }
+
+    // Selections of the secondary cursors touching the row as start row, start offset, end row, and end offset
+    fn secondary_selections(&self, row: usize) -> Vec<(usize, usize, usize, usize)> {
+        self.secondary_cursors
+            .iter()
+            .filter(|c| c.anchor.is_some())
+            .map(SecondaryCursor::range)
+            .filter(|(s, e)| s.0 <= row && row <= e.0 && e.0 < self.lines.len())
+            .map(|(s, e)| (s.0, self.line_offset(s.0, s.1), e.0, self.line_offset(e.0, e.1)))
+            .collect()
+    }
+
+    // Display column where the character at the col starts in the line at the row
+    fn display_col(&self, row: usize, col: usize) -> usize {
+        self.walk_cells(row, |p| p.col <= col, |p, _| p.col < col).width
+    }
+
+    // Column of the first character starting at or after the display column in the line at the row. `None` is
+    // returned when the line is shorter than the display column
+    fn col_at_display(&self, row: usize, display: usize) -> Option<usize> {
+        let end = self.walk_cells(row, |p| p.width <= display, |p, _| p.width < display);
+        (end.width >= display).then_some(end.col)
+    }
+
+    // Top and bottom rows, and left and right display columns of the column selection
+    fn column_rect(&self) -> Option<(usize, usize, usize, usize)> {
+        if self.selection_mode != SelectionMode::Column {
+            return None;
+        }
+        let (ar, ac) = self.selection_start?;
+        let (cr, cc) = self.cursor;
+        let (a, c) = (self.display_col(ar, ac), self.display_col(cr, cc));
+        Some((min(ar, cr), max(ar, cr), min(a, c), max(a, c)))
+    }
+
+    // Row and the range of columns in the column selection for each selected row. Rows shorter than the left edge are
+    // not included
+    fn column_ranges(&self) -> Vec<(usize, usize, usize)> {
+        let Some((top, bottom, left, right)) = self.column_rect() else {
+            return vec![];
+        };
+        (top..=bottom)
+            .filter_map(|row| {
+                let (start, end) = self.column_range_at(row, left, right)?;
+                Some((row, start, end))
+            })
+            .collect()
+    }
+
+    // Range of columns between the display columns `left` and `right` in the line at the row. `None` when the line is
+    // shorter than the left edge
+    fn column_range_at(&self, row: usize, left: usize, right: usize) -> Option<(usize, usize)> {
+        let start = self.col_at_display(row, left)?;
+        let end = self
+            .col_at_display(row, right)
+            .unwrap_or_else(|| self.lines.char_count(row));
+        Some((start, end))
+    }
+
+    // Byte offsets of the column selection in the line at the row for highlighting
+    fn column_selection_offsets(&self, row: usize) -> Option<(usize, usize)> {
+        let (top, bottom, left, right) = self.column_rect()?;
+        if row < top || bottom < row {
+            return None;
+        }
+        let (start, end) = self.column_range_at(row, left, right)?;
+        Some((self.line_offset(row, start), self.line_offset(row, end)))
+    }
+
+    // Fragments of all selected rows in the column selection. Fragments of rows shorter than the left edge are empty
+    fn column_text(&self) -> Vec<String> {
+        let Some((top, bottom, _, _)) = self.column_rect() else {
+            return vec![];
+        };
+        let ranges = self.column_ranges();
+        (top..=bottom)
+            .map(|row| match ranges.iter().find(|(r, _, _)| *r == row) {
+                Some((_, start, end)) => self.lines.line(row).chars().skip(*start).take(end - start).collect(),
+                None => String::new(),
+            })
+            .collect()
+    }
+
+    // Items for `edit_lines` to insert the texts at the display column of the rows from `top`. Empty texts are skipped.
+    // Rows shorter than the column are padded with spaces when `pad` is `true`, or skipped otherwise. Rows after the
+    // end of the text are handled as empty lines
+    fn column_insertions(&self, top: usize, left: usize, texts: Vec<String>, pad: bool) -> Vec<(usize, usize, String)> {
+        let mut items = vec![];
+        for (row, text) in (top..).zip(texts) {
+            if text.is_empty() {
+                continue;
+            }
+            if row >= self.lines.len() {
+                if pad {
+                    items.push((row, 0, " ".repeat(left) + &text));
+                }
+                continue;
+            }
+            match self.col_at_display(row, left) {
+                Some(col) => items.push((row, col, text)),
+                None if pad => {
+                    let spaces = " ".repeat(left - self.display_width(row));
+                    items.push((row, self.lines.char_count(row), spaces + &text));
+                }
+                None => {}
+            }
+        }
+        items
+    }
+
+    // Paste the fragments copied from the column selection at the cursor column of the following rows. Short rows are
+    // padded with spaces and lines are added after the end of the text if necessary. The cursor stays at the top-left
+    // of the pasted block
+    // Insert the lines copied from the linewise selection above the cursor line. The cursor stays on the same line
+    fn paste_lines(&mut self, mut lines: Vec<String>) -> bool {
+        let (row, col) = self.cursor;
+        let len = lines.len();
+        lines.push(String::new());
+        self.cursor = (row, 0);
+        if !self.insert_lines(lines) {
+            self.cursor = (row, col);
+            return false;
+        }
+        self.cursor = (min(row + len, self.lines.len() - 1), col);
+        self.last_paste = None;
+        true
+    }
+
+    fn paste_block(&mut self, block: Vec<String>) -> bool {
+        let (row, col) = self.cursor;
+        let left = self.display_col(row, col);
+        let added = (row + block.len()).saturating_sub(self.lines.len());
+        let items = self.column_insertions(row, left, block, true);
+        let chars = items.iter().map(|(_, _, s)| s.chars().count()).sum::<usize>() + added;
+        if items.is_empty() || chars > self.remaining_chars() {
+            return false;
+        }
+
+        self.history.stop_merging();
+        let grouped = self.history.begin_group();
+        if added > 0 {
+            let last = self.lines.len() - 1;
+            self.cursor = (last, self.lines.char_count(last));
+            self.insert_str("\n".repeat(added));
+            self.cursor = (row, col);
+        }
+        self.edit_lines(true, items);
+        if grouped {
+            self.history.end_group();
+        }
+        self.history.stop_merging();
+        self.last_paste = None;
+        true
+    }
+
+    fn line_offset(&self, row: usize, col: usize) -> usize {
+        self.lines.offset_of_col(min(row, self.lines.len() - 1), col)
+    }
+
+    /// Set the style used for text selection. The default style is light blue.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Change the selection color from the default to Red
+    /// textarea.set_selection_style(Style::default().bg(Color::Red));
+    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::Red));
+    /// ```
+    pub fn set_selection_style(&mut self, style: Style) {
+        self.select_style = style;
+    }
+
+    /// Set if the selection is kept after [`TextArea::copy`] so that the same region can be edited next, for example
+    /// indented. When `false`, copying cancels the selection. The default value is `true`.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb"]);
+    ///
+    /// textarea.set_keep_selection_on_copy(false);
+    /// assert!(!textarea.keep_selection_on_copy());
+    ///
+    /// textarea.set_selection((0, 0), (0, 3));
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "aaa");
+    /// assert!(!textarea.is_selecting());
+    /// ```
+    pub fn set_keep_selection_on_copy(&mut self, enabled: bool) {
+        self.keep_selection_on_copy = enabled;
+    }
+
+    /// Get if the selection is kept after copying. See [`TextArea::set_keep_selection_on_copy`].
+    pub fn keep_selection_on_copy(&self) -> bool {
+        self.keep_selection_on_copy
+    }
+
+    /// Briefly highlight the region copied by [`TextArea::copy`] with the style for the number of renderings, like
+    /// highlight-on-yank of Neovim. The highlight is layered over the selection. It is only visual and disappears when
+    /// the text is modified. `None` disables it, which is the default. Cutting text does not flash since the region
+    /// is deleted.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Flash the copied text in yellow on the next 3 renderings
+    /// textarea.set_yank_flash(Some((Style::default().bg(Color::Yellow), 3)));
+    /// assert_eq!(textarea.yank_flash(), Some((Style::default().bg(Color::Yellow), 3)));
+    /// ```
+    pub fn set_yank_flash(&mut self, flash: Option<(Style, u8)>) {
+        self.yank_flash = flash;
+        self.yank_flashed.take();
+    }
+
+    /// Get the style and the number of renderings of the highlight of copied text. See [`TextArea::set_yank_flash`].
+    pub fn yank_flash(&self) -> Option<(Style, u8)> {
+        self.yank_flash
+    }
+
+    /// Get the style used for text selection.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::LightBlue));
+    /// ```
+    pub fn selection_style(&mut self) -> Style {
+        self.select_style
+    }
+
+    /// Set styles of the line at `row` for syntax highlighting. Each range is a byte offset range in the line. The
+    /// styles are layered under the cursor line style, selection, and search matches. Ranges are clamped to the line
+    /// and extended to character boundaries. When ranges overlap, the later one in the list takes precedence.
+    ///
+    /// The styles are not applied after the line is edited or moved to another row. Set the styles again for the new
+    /// text. Setting an empty list removes the styles.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["fn main() {}"]);
+    ///
+    /// textarea.set_line_styles(0, vec![(0..2, Style::default().fg(Color::Red))]);
+    /// assert_eq!(textarea.line_styles(0), Some(&[(0..2, Style::default().fg(Color::Red))][..]));
+    ///
+    /// // Styles are cleared when the line is edited
+    /// textarea.insert_str("pub ");
+    /// assert_eq!(textarea.line_styles(0), None);
+    /// ```
+    pub fn set_line_styles(&mut self, row: usize, styles: Vec<(Range<usize>, Style)>) {
+        if self.line_styles(row) == Some(&styles) {
+            return; // Keep the cached spans when the same styles are set on every frame
+        }
+        self.render_cache.get_mut().remove(row);
+        if styles.is_empty() {
+            self.line_styles.remove(&row);
+        } else if let Some(line) = self.lines.get(row) {
+            let text = line.into_owned();
+            self.line_styles.insert(row, LineStyles { text, styles });
+        }
+    }
+
+    /// Get styles of the line at `row` set by [`TextArea::set_line_styles`]. `None` is returned when no style is set
+    /// or the line was edited after the styles were set.
+    pub fn line_styles(&self, row: usize) -> Option<&[(Range<usize>, Style)]> {
+        let s = self.line_styles.get(&row)?;
+        (self.lines.get(row).as_deref() == Some(s.text.as_str())).then_some(s.styles.as_slice())
+    }
+
+    /// Remove all styles set by [`TextArea::set_line_styles`].
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    ///
+    /// textarea.set_line_styles(0, vec![(0..5, Style::default().fg(Color::Red))]);
+    /// textarea.set_line_styles(1, vec![(0..5, Style::default().fg(Color::Blue))]);
+    /// textarea.clear_line_styles();
+    /// assert_eq!(textarea.line_styles(0), None);
+    /// assert_eq!(textarea.line_styles(1), None);
+    /// ```
+    pub fn clear_line_styles(&mut self) {
+        for row in self.line_styles.keys() {
+            self.render_cache.get_mut().remove(*row);
+        }
+        self.line_styles.clear();
+    }
+
+    /// Set styles of ranges in the text such as diagnostics of a language server. Unlike
+    /// [`TextArea::set_line_styles`], a range can span multiple lines and follows edits of the text: it is shifted by
+    /// edits before it, and the deleted part of it is cut off. A highlight whose text is entirely deleted is removed.
+    ///
+    /// Each style is patched on the line styles. Where highlights overlap, the one with the higher priority is patched
+    /// later, and the later one in the list is patched later among the same priority. Highlights are rendered under the
+    /// selection, search matches, and the cursor. Setting an empty list removes all highlights.
+    /// ```
+    /// use ratatui::style::{Color, Modifier, Style};
+    /// use tui_textarea::{Highlight, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["let x = 1;", "x + y"]);
+    ///
+    /// let error = Style::default().fg(Color::Red).add_modifier(Modifier::UNDERLINED);
+    /// textarea.set_highlights(vec![Highlight { start: (1, 4), end: (1, 5), style: error, priority: 1 }]);
+    ///
+    /// // Highlights follow edits
+    /// textarea.move_cursor(tui_textarea::CursorMove::Jump(1, 0));
+    /// textarea.insert_str("z - ");
+    /// assert_eq!(textarea.highlights()[0].start, (1, 8));
+    /// assert_eq!(textarea.highlights()[0].end, (1, 9));
+    /// ```
+    pub fn set_highlights(&mut self, highlights: Vec<Highlight>) {
+        if self.highlights != highlights {
+            self.render_cache.get_mut().clear();
+            self.highlights = highlights;
+        }
+    }
+
+    /// Get the highlights set by [`TextArea::set_highlights`] with their positions updated by edits of the text.
+    pub fn highlights(&self) -> &[Highlight] {
+        &self.highlights
+    }
+
+    /// Get the highlights containing the position, ordered from the highest priority. This is useful to show the
+    /// diagnostic message for the range under the cursor.
+    /// ```
+    /// use ratatui::style::Style;
+    /// use tui_textarea::{Highlight, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["hello, world"]);
+    /// let warning = Highlight { start: (0, 0), end: (0, 12), style: Style::default(), priority: 1 };
+    /// let error = Highlight { start: (0, 7), end: (0, 12), style: Style::default(), priority: 2 };
+    /// textarea.set_highlights(vec![warning, error]);
+    ///
+    /// assert_eq!(textarea.highlights_at(0, 3), [&warning]);
+    /// assert_eq!(textarea.highlights_at(0, 8), [&error, &warning]);
+    /// assert!(textarea.highlights_at(0, 12).is_empty());
+    /// ```
+    pub fn highlights_at(&self, row: usize, col: usize) -> Vec<&Highlight> {
+        let mut found: Vec<_> = self.highlights.iter().filter(|h| h.contains((row, col))).collect();
+        found.sort_by_key(|h| Reverse(h.priority));
+        found
+    }
+
+    // Returns `true` when the line at the row is the text. The line is compared by its chunks without copying it
+    fn line_equals(&self, row: usize, text: &str) -> bool {
+        let len = self.lines.line_len(row);
+        let mut rest = text;
+        len == text.len()
+            && self
+                .lines
+                .chunks(row, 0..len)
+                .all(|chunk| match rest.strip_prefix(chunk) {
+                    Some(r) => {
+                        rest = r;
+                        true
+                    }
+                    None => false,
+                })
+    }
+
+    // Byte ranges and styles of the highlights in the row, in the order of patching them
+    fn highlight_ranges(&self, row: usize) -> Vec<(Range<usize>, Style)> {
+        let mut found: Vec<_> = self
+            .highlights
+            .iter()
+            .filter(|h| h.start.0 <= row && row <= h.end.0)
+            .collect();
+        found.sort_by_key(|h| h.priority);
+        let len = self.lines.line_len(row);
+        found
+            .into_iter()
+            .map(|h| {
+                let start = if h.start.0 == row {
+                    self.lines.offset_of_col(row, h.start.1)
+                } else {
+                    0
+                };
+                let end = if h.end.0 == row {
+                    self.lines.offset_of_col(row, h.end.1)
+                } else {
+                    len
+                };
+                (start..end, h.style)
+            })
+            .filter(|(range, _)| !range.is_empty())
+            .collect()
+    }
+
+    // Links detected in the line. Links are not detected while the text is masked not to leak the text
+    pub(crate) fn links(&self, line: &str) -> Vec<(Range<usize>, String)> {
+        match (self.link_detector, self.display_mask()) {
+            (Some(detect), None) => detect(line),
+            _ => vec![],
+        }
+    }
+
+    // Links overlapping the columns of the line at the row. Links are detected in the columns extended by
+    // `LINK_CONTEXT` characters on both sides so that a long line is not read entirely. Links touching the edges of the
+    // extended columns are dropped since they may continue out of them. Byte ranges are offsets in the whole line
+    pub(crate) fn links_in(&self, row: usize, cols: Range<usize>) -> Vec<(Range<usize>, String)> {
+        if self.link_detector.is_none() || self.display_mask().is_some() {
+            return vec![];
+        }
+        let len = self.lines.line_len(row);
+        let start = self.lines.offset_of_col(row, cols.start.saturating_sub(LINK_CONTEXT));
+        let end = self.lines.offset_of_col(row, cols.end.saturating_add(LINK_CONTEXT));
+        let (first, last) = (
+            self.lines.offset_of_col(row, cols.start),
+            self.lines.offset_of_col(row, cols.end),
+        );
+        let mut links = self.links(&self.lines.slice(row, start..end));
+        links.retain_mut(|(range, _)| {
+            *range = start + range.start..start + range.end;
+            let cut = (range.start == start && start > 0) || (range.end == end && end < len);
+            !cut && range.start <= last && first <= range.end
+        });
+        links
+    }
+
+    /// Get the target of the link under the cursor detected by the link detector set by
+    /// [`TextArea::set_link_detector`]. This is useful to open the link with a key such as Enter. `None` is returned
+    /// when the cursor is not on a link.
+    ///
+    /// The target is returned as an owned `String` rather than a `&str` borrowed from the line because the link
+    /// detector creates it on each call and it may differ from the text in the line. For example, a detector can map
+    /// an issue number `#12` to the URL of the issue. Links are not cached since they are detected only on rendered
+    /// lines.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["See https://example.com for details"]);
+    /// assert_eq!(textarea.link_at_cursor(), None);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 10));
+    /// assert_eq!(textarea.link_at_cursor().as_deref(), Some("https://example.com"));
+    /// ```
+    pub fn link_at_cursor(&self) -> Option<String> {
+        let (row, col) = self.cursor;
+        let offset = self.line_offset(row, col);
+        self.links_in(row, col..col)
+            .into_iter()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(_, target)| target)
+    }
+
+    fn selection_positions(&self) -> Option<(Pos, Pos)> {
+        let ((sr, sc), (er, ec)) = if self.selection_mode == SelectionMode::Line {
+            self.selection_range()?
+        } else {
+            (self.selection_start?, self.cursor)
+        };
+        let (so, eo) = (self.line_offset(sr, sc), self.line_offset(er, ec));
+        let s = Pos::new(sr, sc, so);
+        let e = Pos::new(er, ec, eo);
+        match (sr, so).cmp(&(er, eo)) {
+            Ordering::Less => Some((s, e)),
+            Ordering::Equal => None,
+            Ordering::Greater => Some((e, s)),
+        }
+    }
+
+    // Top and bottom rows of the linewise selection
+    fn linewise_rows(&self) -> Option<(usize, usize)> {
+        if self.selection_mode != SelectionMode::Line {
+            return None;
+        }
+        let (row, _) = self.selection_start?;
+        Some((min(row, self.cursor.0), max(row, self.cursor.0)))
+    }
+
+    // End of the linewise selection is the head of the next line, or the end of the text at the last line
+    fn linewise_end(&self, bottom: usize) -> (usize, usize) {
+        if bottom + 1 < self.lines.len() {
+            (bottom + 1, 0)
+        } else {
+            (bottom, self.lines.char_count(bottom))
+        }
+    }
+
+    fn take_selection_range(&mut self) -> Option<(Pos, Pos)> {
+        let range = self.selection_positions();
+        self.cancel_selection();
+        range
+    }
+
+    /// Copy the selection text to the yank buffer. When nothing is selected, this method does nothing.
+    /// To get the yanked text, use [`TextArea::yank_text`].
+    /// ```
+    /// use tui_textarea::{TextArea, Key, Input, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["Hello World"]);
+    ///
+    /// // Start text selection at 'W'
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.start_selection();
+    ///
+    /// // Select the word "World" and copy the selected text
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.copy();
+    ///
+    /// assert_eq!(textarea.yank_text(), " World");
+    /// assert_eq!(textarea.lines(), ["Hello World"]); // Text does not change
+    /// ```
+    pub fn copy(&mut self) {
+        if self.is_column_selecting() {
+            self.yank = YankText::Block(self.column_text());
+        } else if let Some((top, bottom)) = self.linewise_rows() {
+            self.yank = YankText::Lines(self.lines.to_vec_in(top..bottom + 1));
+        } else if let Some((start, end)) = self.selection_positions() {
+            if start.row == end.row {
+                self.yank = self.lines.line(start.row)[start.offset..end.offset].to_string().into();
+            } else {
+                let mut chunk = vec![self.lines.line(start.row)[start.offset..].to_string()];
+                chunk.extend(self.lines.iter_in(start.row + 1..end.row).map(Cow::into_owned));
+                chunk.push(self.lines.line(end.row)[..end.offset].to_string());
+                self.yank = YankText::Chunk(chunk);
+            }
+        } else {
+            let (row, _) = self.cursor;
+            self.yank = self.lines.line(row).into_owned().into();
+        }
+        if let Some((_, renders)) = self.yank_flash {
+            self.yank_flashed.replace(Some((self.yank_region(), renders)));
+        }
+        if !self.keep_selection_on_copy {
+            self.cancel_selection();
+        }
+        self.push_kill_ring(self.yank.to_string());
+        self.sync_clipboard();
+    }
+
+    // Byte ranges `(row, start, end)` of the text copied by `copy`
+    fn yank_region(&self) -> RowRanges {
+        if let Some((top, bottom, _, _)) = self.column_rect() {
+            return (top..=bottom)
+                .filter_map(|row| self.column_selection_offsets(row).map(|(s, e)| (row, s, e)))
+                .filter(|(_, s, e)| s < e)
+                .collect();
+        }
+        let Some((start, end)) = self.selection_positions() else {
+            let (row, _) = self.cursor;
+            return vec![(row, 0, self.lines.line_len(row))];
+        };
+        (start.row..=end.row)
+            .map(|row| {
+                let s = if row == start.row { start.offset } else { 0 };
+                let e = if row == end.row {
+                    end.offset
+                } else {
+                    self.lines.line_len(row)
+                };
+                (row, s, e)
+            })
+            .filter(|(_, s, e)| s < e)
+            .collect()
+    }
+
+    // Save the deleted text to the yank buffer and the kill ring. This must be called before the deletion is recorded
+    // in the history to know if the last edit was also a kill
+    fn kill(&mut self, text: YankText, kill: Kill) {
+        self.yank = text;
+        if self.kill_ring_size == 0 {
+            return;
+        }
+        let text = self.yank.to_string();
+        match self.kill_ring.first_mut() {
+            Some(entry) if kill != Kill::Region && self.last_kill == Some(kill) => {
+                if kill == Kill::Forward {
+                    entry.push_str(&text);
+                } else {
+                    entry.insert_str(0, &text);
+                }
+                self.yank = YankText::from_text(entry);
+            }
+            _ => self.push_kill_ring(text),
+        }
+        self.killing = Some(kill);
+    }
+
+    fn push_kill_ring(&mut self, text: String) {
+        self.last_kill = None;
+        if self.kill_ring_size == 0 {
+            return;
+        }
+        self.kill_ring.insert(0, text);
+        self.kill_ring.truncate(self.kill_ring_size);
+    }
+
+    // Write the yanked text to the system clipboard when it is synchronized
+    fn sync_clipboard(&mut self) {
+        #[cfg(feature = "clipboard")]
+        if let Some(c) = &mut self.clipboard {
+            c.set_text(self.yank.to_string());
+        }
+    }
+
+    fn delete_selection(&mut self, should_yank: bool) -> bool {
+        if self.is_column_selecting() {
+            let deleted = self.column_ranges().iter().any(|(_, start, end)| start < end);
+            if deleted && should_yank {
+                self.kill(YankText::Block(self.column_text()), Kill::Region);
+            }
+            let modified = deleted && self.delete_column_selection();
+            // Like normal selection, the cursor moves to the start of the deleted block
+            if let Some(anchor) = self.selection_start {
+                self.cursor = min(self.cursor, anchor);
+            }
+            self.cancel_selection();
+            return modified;
+        }
+        if let Some((top, bottom)) = self.linewise_rows() {
+            if should_yank {
+                self.kill(YankText::Lines(self.lines.to_vec_in(top..bottom + 1)), Kill::Region);
+            }
+            self.cancel_selection();
+            // Delete the newline before the lines instead when they are at the end of the text
+            let end = self.linewise_end(bottom);
+            let start = match top.checked_sub(1) {
+                Some(prev) if bottom + 1 == self.lines.len() => (prev, self.lines.char_count(prev)),
+                _ => (top, 0),
+            };
+            if start == end {
+                return false;
+            }
+            self.delete_range(self.pos_at(start), self.pos_at(end), None);
+            self.cursor = (min(top, self.lines.len() - 1), 0);
+            return true;
+        }
+        if let Some((s, e)) = self.take_selection_range() {
+            self.delete_range(s, e, should_yank.then_some(Kill::Region));
+            return true;
+        }
+        false
+    }
+
+    // Delete the selection to insert text in place of it. Unlike `delete_selection`, lines selected linewise are
+    // replaced with one empty line so that the inserted text does not join the line after the selection
+    fn replace_selection(&mut self) -> bool {
+        let Some((top, bottom)) = self.linewise_rows() else {
+            return self.delete_selection(false);
+        };
+        self.cancel_selection();
+        let (start, end) = ((top, 0), (bottom, self.lines.char_count(bottom)));
+        self.cursor = start;
+        if start == end {
+            return false;
+        }
+        self.delete_range(self.pos_at(start), self.pos_at(end), None);
+        true
+    }
+
+    /// Move the cursor to the position specified by the [`CursorMove`] parameter. For each kind of cursor moves, see
+    /// the document of [`CursorMove`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// ```
+    pub fn move_cursor(&mut self, m: CursorMove) {
+        self.move_cursor_with_shift(m, self.selection_start.is_some());
+    }
+
+    // Move the cursor without recording the position in the jump list. This is for cursor moves which are steps of
+    // other operations rather than jumps requested by the user
+    pub(crate) fn move_cursor_without_jump(&mut self, m: CursorMove) {
+        self.move_cursor_impl(m, self.selection_start.is_some(), false);
+    }
+
+    fn move_cursor_with_shift(&mut self, m: CursorMove, shift: bool) {
+        self.move_cursor_impl(m, shift, true);
+    }
+
+    // Move the cursor without recording the previous position in the jump list when `record_jump` is `false`. This is
+    // used by cursor moves caused by scrolling.
+    fn move_cursor_impl(&mut self, m: CursorMove, shift: bool, record_jump: bool) {
+        let folds = self.shown_folds();
+        let next = match (m, self.wrapper(self.viewport.rect().2)) {
+            (CursorMove::Up | CursorMove::Down, _) if self.single_line => None,
+            (CursorMove::Up | CursorMove::Down, None) if !folds.is_empty() => {
+                let (row, col) = self.cursor;
+                let display = fold::display_row(&folds, row);
+                let display = if m == CursorMove::Up {
+                    display.checked_sub(1)
+                } else {
+                    Some(display + 1)
+                };
+                display
+                    .map(|d| fold::row_at_display(&folds, d))
+                    .filter(|&r| r < self.lines.len())
+                    .map(|r| (r, min(col, self.lines.char_count(r))))
+            }
+            (
+                CursorMove::InViewport
+                | CursorMove::ViewportTop
+                | CursorMove::ViewportMiddle
+                | CursorMove::ViewportBottom,
+                None,
+            ) if !folds.is_empty() => {
+                // Stretch the viewport over the hidden rows and move the cursor out of them to the first line of the fold
+                let viewport = self.viewport.clone();
+                let (top, _, _, height) = viewport.rect();
+                if height > 0 {
+                    let display = fold::display_row(&folds, top as usize);
+                    let bottom = fold::row_at_display(&folds, display + height as usize - 1);
+                    let height = (bottom + 1).saturating_sub(top as usize);
+                    viewport.set_height(height.try_into().unwrap_or(u16::MAX));
+                }
+                m.next_cursor(self.cursor, &self.lines, &viewport, self.matching_bracket_limit)
+                    .map(|(row, col)| match folds.iter().find(|f| f.hides(row)) {
+                        Some(f) => (f.start, min(col, self.lines.char_count(f.start))),
+                        None => (row, col),
+                    })
+            }
+            (
+                CursorMove::Up
+                | CursorMove::Down
+                | CursorMove::ViewportTop
+                | CursorMove::ViewportMiddle
+                | CursorMove::ViewportBottom,
+                Some(wrapper),
+            ) => m.next_wrapped_cursor(self.cursor, &self.lines, &wrapper, &self.viewport),
+            _ => m.next_cursor(self.cursor, &self.lines, &self.viewport, self.matching_bracket_limit),
+        };
+        if let Some(cursor) = next {
+            if shift {
+                if self.selection_start.is_none() {
+                    self.start_selection();
+                }
+            } else {
+                self.cancel_selection();
+            }
+            if self.cursor != cursor {
+                if record_jump && self.is_far_move(m, cursor) {
+                    self.jumps.push(self.cursor);
+                }
+                self.history.stop_merging();
+                self.auto_indented = None;
+                self.auto_closers.clear();
+                self.mask_revealed.set(None);
+                self.last_kill = None;
+                self.last_paste = None;
+                self.ghost_text = None;
+            }
+            self.cursor = cursor;
+            self.open_fold_at_cursor();
+        } else if !shift {
+            self.cancel_selection();
+        }
+    }
+
+    fn is_far_move(&self, m: CursorMove, to: (usize, usize)) -> bool {
+        match m {
+            CursorMove::Top
+            | CursorMove::Bottom
+            | CursorMove::Jump(_, _)
+            | CursorMove::MatchingBracket
+            | CursorMove::ViewportTop
+            | CursorMove::ViewportMiddle
+            | CursorMove::ViewportBottom => true,
+            CursorMove::InViewport => false,
+            _ => {
+                let threshold = match self.jump_threshold {
+                    Some(t) => t,
+                    None => match self.viewport.rect().3 {
+                        0 => return false, // Not rendered yet
+                        h => h as usize,
+                    },
+                };
+                self.cursor.0.abs_diff(to.0) > threshold
+            }
+        }
+    }
+
+    /// Record the current cursor position in the jump list so that [`TextArea::jump_back`] can return to it later.
+    /// Positions after the current position in the list are forgotten as a history of web browser does. The same
+    /// position as the last one is not recorded twice. At most 100 positions are remembered and the oldest one is
+    /// dropped first.
+    ///
+    /// The cursor position before a far cursor move is recorded automatically. Moves by [`CursorMove::Top`],
+    /// [`CursorMove::Bottom`], [`CursorMove::Jump`], [`CursorMove::MatchingBracket`], the viewport motions such as
+    /// [`CursorMove::ViewportTop`] and text search are always far. Other moves are far when the cursor moves more rows
+    /// than the threshold set by [`TextArea::set_jump_threshold`]. Cursor moves caused by scrolling, or made as a part of
+    /// other operations such as [`TextArea::select_all`] or the Vim word motions, are never recorded.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Down);
+    /// textarea.push_jump();
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.jump_list(), [(1, 0)]);
+    ///
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// ```
+    pub fn push_jump(&mut self) {
+        self.jumps.push(self.cursor);
+    }
+
+    /// Move the cursor back to the previous position in the jump list. The current position is remembered so that
+    /// [`TextArea::jump_forward`] can return to it. When the text was modified and the position no longer exists, the
+    /// cursor moves to the nearest valid position. This method returns `false` and does nothing when there is no
+    /// previous position.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// assert_eq!(textarea.cursor(), (3, 0));
+    ///
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// assert!(!textarea.jump_back());
+    ///
+    /// assert!(textarea.jump_forward());
+    /// assert_eq!(textarea.cursor(), (3, 0));
+    /// assert!(!textarea.jump_forward());
+    ///
+    /// // The position is clamped when the text became shorter
+    /// textarea.jump_back();
+    /// textarea.delete_str(4);
+    /// assert_eq!(textarea.lines(), ["c", "d"]);
+    /// assert!(textarea.jump_forward());
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// ```
+    pub fn jump_back(&mut self) -> bool {
+        match self.jumps.back(self.cursor) {
+            Some(pos) => self.restore_jump(pos),
+            None => false,
+        }
+    }
+
+    /// Move the cursor forward to the next position in the jump list after going back with [`TextArea::jump_back`].
+    /// This method returns `false` and does nothing when there is no next position. See [`TextArea::jump_back`] for
+    /// the example.
+    pub fn jump_forward(&mut self) -> bool {
+        match self.jumps.forward() {
+            Some(pos) => self.restore_jump(pos),
+            None => false,
+        }
+    }
+
+    fn restore_jump(&mut self, (row, col): (usize, usize)) -> bool {
+        let shift = self.selection_start.is_some();
+        self.move_cursor_impl(CursorMove::Jump(row as u64, col as u64), shift, false);
+        true
+    }
+
+    /// Get the cursor positions in the jump list from the oldest one. Positions may be out of the text when it was
+    /// modified after they were recorded.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    /// assert!(textarea.jump_list().is_empty());
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::Top);
+    /// assert_eq!(textarea.jump_list(), [(0, 0), (2, 0)]);
+    /// ```
+    pub fn jump_list(&self) -> &[(usize, usize)] {
+        self.jumps.positions()
+    }
+
+    /// Get the index of the current position in the jump list. It is equal to the length of [`TextArea::jump_list`]
+    /// unless going back with [`TextArea::jump_back`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// assert_eq!(textarea.jump_list_index(), 1);
+    ///
+    /// textarea.jump_back();
+    /// assert_eq!(textarea.jump_list(), [(0, 0), (2, 0)]);
+    /// assert_eq!(textarea.jump_list_index(), 0);
+    /// ```
+    pub fn jump_list_index(&self) -> usize {
+        self.jumps.index()
+    }
+
+    /// Clear all positions in the jump list.
+    pub fn clear_jump_list(&mut self) {
+        self.jumps.clear();
+    }
+
+    /// Set the number of rows a cursor move must exceed to record the previous cursor position in the jump list
+    /// automatically. `None` means the height of the viewport, which is the default. Before the textarea is rendered,
+    /// only the moves which are always far are recorded in that case. See [`TextArea::push_jump`] for more details.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c", "", "d"]);
+    /// textarea.set_jump_threshold(Some(1));
+    ///
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert!(textarea.jump_list().is_empty());
+    ///
+    /// // Moving 3 rows exceeds the threshold
+    /// textarea.move_cursor(CursorMove::ParagraphForward);
+    /// assert_eq!(textarea.cursor(), (4, 0));
+    /// assert_eq!(textarea.jump_list(), [(1, 0)]);
+    /// ```
+    pub fn set_jump_threshold(&mut self, rows: Option<usize>) {
+        self.jump_threshold = rows;
+    }
+
+    /// Get the number of rows set by [`TextArea::set_jump_threshold`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.jump_threshold(), None);
+    ///
+    /// textarea.set_jump_threshold(Some(10));
+    /// assert_eq!(textarea.jump_threshold(), Some(10));
+    /// ```
+    pub fn jump_threshold(&self) -> Option<usize> {
+        self.jump_threshold
+    }
+
+    /// Record the current cursor position as a mark named `name` like `m` command of Vim. A mark with the same name is
+    /// overwritten. Marks follow the text on edits: they are shifted when text is inserted or deleted before them, and
+    /// moved to the start of the deleted text when the text around them is deleted. A mark is removed when its line is
+    /// deleted, or joined to the previous line with the text before the mark.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo", "bar", "baz"]);
+    /// textarea.move_cursor(CursorMove::Jump(1, 2));
+    /// textarea.set_mark('a');
+    /// assert_eq!(textarea.mark('a'), Some((1, 2)));
+    ///
+    /// // Insert a line above the mark
+    /// textarea.move_cursor(CursorMove::Top);
+    /// textarea.insert_str("new\n");
+    /// assert_eq!(textarea.mark('a'), Some((2, 2)));
+    ///
+    /// // Delete the line of the mark
+    /// textarea.move_cursor(CursorMove::Jump(2, 0));
+    /// textarea.delete_line(false);
+    /// assert_eq!(textarea.mark('a'), None);
+    /// ```
+    pub fn set_mark(&mut self, name: char) {
+        self.marks.insert(name, self.cursor);
+    }
+
+    /// Get the position of the mark named `name` set by [`TextArea::set_mark`].
+    pub fn mark(&self, name: char) -> Option<(usize, usize)> {
+        self.marks.get(&name).copied()
+    }
+
+    /// Get all marks set by [`TextArea::set_mark`] with their positions.
+    /// ```
+    /// use std::collections::HashMap;
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo", "bar"]);
+    /// textarea.set_mark('a');
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.set_mark('b');
+    ///
+    /// assert_eq!(textarea.marks(), &HashMap::from([('a', (0, 0)), ('b', (1, 0))]));
+    /// ```
+    pub fn marks(&self) -> &HashMap<char, (usize, usize)> {
+        &self.marks
+    }
+
+    /// Move the cursor to the mark named `name` like `` ` `` command of Vim. The position before the move is recorded
+    /// in the jump list. This method returns `false` and does nothing when the mark does not exist.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo", "bar"]);
+    /// textarea.move_cursor(CursorMove::Jump(1, 1));
+    /// textarea.set_mark('a');
+    /// textarea.move_cursor(CursorMove::Top);
+    ///
+    /// assert!(textarea.jump_to_mark('a'));
+    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// assert!(!textarea.jump_to_mark('b'));
+    ///
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    /// ```
+    pub fn jump_to_mark(&mut self, name: char) -> bool {
+        let Some((row, col)) = self.mark(name) else {
+            return false;
+        };
+        self.move_cursor(CursorMove::Jump(row as u64, col as u64));
+        true
+    }
+
+    /// Remove the mark named `name`. The removed position is returned when the mark existed.
+    pub fn remove_mark(&mut self, name: char) -> Option<(usize, usize)> {
+        self.marks.remove(&name)
+    }
+
+    /// Remove all marks set by [`TextArea::set_mark`].
+    pub fn clear_marks(&mut self) {
+        self.marks.clear();
+    }
+
+    /// Move the cursor to the 1-based `line` and the optional 1-based column `col`, like `:42` command of Vim. When
+    /// `col` is `None`, the cursor moves to the head of the line. The position before the move is recorded in the jump
+    /// list and the cursor line is put at the center of the viewport on the next rendering. [`parse_position`] helps
+    /// to parse the input of "go to line" prompt.
+    ///
+    /// When `clamp` is `true`, the line and the column out of the text are clamped to the nearest valid position.
+    /// Otherwise this method returns `false` and does nothing for them. The column just after the end of line is valid.
+    /// ```
+    /// use tui_textarea::{parse_position, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def", "ghi"]);
+    ///
+    /// assert!(textarea.goto_line(2, Some(3), false));
+    /// assert_eq!(textarea.cursor(), (1, 2));
+    ///
+    /// assert!(!textarea.goto_line(10, None, false));
+    /// assert_eq!(textarea.cursor(), (1, 2));
+    /// assert!(textarea.goto_line(10, Some(10), true));
+    /// assert_eq!(textarea.cursor(), (2, 3));
+    ///
+    /// // Go to the line typed in the prompt
+    /// let (line, col) = parse_position("-2", textarea.cursor().0 + 1).unwrap();
+    /// assert!(textarea.goto_line(line, col, false));
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (2, 3));
+    /// ```
+    ///
+    /// [`parse_position`]: crate::parse_position
+    pub fn goto_line(&mut self, line: usize, col: Option<usize>, clamp: bool) -> bool {
+        // Convert the 1-based number to 0-based index
+        let in_range = |n: usize, max: usize| (clamp || (1..=max).contains(&n)).then(|| n.clamp(1, max) - 1);
+        let Some(row) = in_range(line, self.lines.len()) else {
+            return false;
+        };
+        let col = match col {
+            Some(col) => match in_range(col, self.lines.char_count(row) + 1) {
+                Some(col) => col,
+                None => return false,
+            },
+            None => 0,
+        };
+        self.move_cursor(CursorMove::Jump(row as u64, col as u64));
+        self.scroll_cursor(CursorAlign::Center);
+        true
+    }
+
+    /// Move the line at the cursor, or all lines touched by the selection, by one line in the direction of the given
+    /// key. Only [`Key::Up`] and [`Key::Down`] are accepted. See [`TextArea::move_lines_up`] and
+    /// [`TextArea::move_lines_down`] for more details.
+    pub fn move_line(&mut self, key: Key) -> bool {
+        match key {
+            Key::Up => self.move_lines_up(),
+            Key::Down => self.move_lines_down(),
+            _ => false,
+        }
+    }
+
+    /// Move the line at the cursor, or all lines touched by the selection, up by one line. The line above them is
+    /// moved below the block. A line where the selection ends at its head is not moved. The cursor column and the
+    /// selection are kept on the moved lines. This method returns `false` and does nothing when the block is already
+    /// at the first line. The move is undone by one undo.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    /// // Select "b" and "c". The selection ends at the head of "d"
+    /// textarea.set_selection((1, 0), (3, 0));
+    ///
+    /// assert!(textarea.move_lines_up());
+    /// assert_eq!(textarea.lines(), ["b", "c", "a", "d"]);
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (2, 0))));
+    ///
+    /// assert!(!textarea.move_lines_up());
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["a", "b", "c", "d"]);
+    /// ```
+    pub fn move_lines_up(&mut self) -> bool {
+        self.move_lines(true)
+    }
+
+    /// Move the line at the cursor, or all lines touched by the selection, down by one line. The line below them is
+    /// moved above the block. A line where the selection ends at its head is not moved. The cursor column and the
+    /// selection are kept on the moved lines. This method returns `false` and does nothing when the block is already
+    /// at the last line. The move is undone by one undo.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def", "ghi"]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 2));
+    ///
+    /// assert!(textarea.move_lines_down());
+    /// assert_eq!(textarea.lines(), ["def", "abc", "ghi"]);
+    /// assert_eq!(textarea.cursor(), (1, 2));
+    ///
+    /// assert!(textarea.move_lines_down());
+    /// assert!(!textarea.move_lines_down());
+    /// assert_eq!(textarea.lines(), ["def", "ghi", "abc"]);
+    /// ```
+    pub fn move_lines_down(&mut self) -> bool {
+        self.move_lines(false)
+    }
+
+    fn move_lines(&mut self, up: bool) -> bool {
+        let (start, last) = self.selected_rows();
+        // Bubble the adjacent line through the block by swapping it with each line of the block
+        let swaps: Vec<(usize, usize)> = if up {
+            if start == 0 {
+                return false;
+            }
+            (start..=last).map(|row| (row - 1, row)).collect()
+        } else {
+            if last + 1 >= self.lines.len() {
+                return false;
+            }
+            (start..=last).rev().map(|row| (row + 1, row)).collect()
+        };
+
+        let edits: Vec<_> = swaps
+            .into_iter()
+            .map(|(from, to)| Edit::new(EditKind::MoveLine(false), Pos::new(from, 0, 0), Pos::new(to, 0, 0)))
+            .collect();
+        for edit in &edits {
+            edit.redo(&mut self.lines);
+        }
+
+        // Positions on the block, and the end of a selection at the head of the next line, move with the block
+        let last_row = self.lines.len() - 1;
+        let shift = |(row, col): (usize, usize)| {
+            if row < start || last + 1 < row {
+                return (row, col);
+            }
+            let row = if up { row - 1 } else { row + 1 };
+            if row > last_row {
+                (last_row, self.lines.char_count(last_row))
+            } else {
+                (row, col)
+            }
+        };
+        let before = self.cursor;
+        self.cursor = shift(before);
+        self.selection_start = self.selection_start.map(shift);
+        let before = Pos::new(before.0, before.1, 0);
+        let after = Pos::new(self.cursor.0, self.cursor.1, 0);
+        self.record(Edit::new(EditKind::Batch(edits), before, after));
+        true
+    }
+
+    /// Undo the last modification. This method returns if the undo modified text contents or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc def"]);
+    ///
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), [" def"]);
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc def"]);
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let tracks_edits = self.tracks_edits();
+        let Some(edit) = self.history.undo(&mut self.lines) else {
+            return false;
+        };
+        self.line_offsets.get_mut().invalidate(edit.first_row());
+        let mut described = vec![];
+        if tracks_edits {
+            edit.inverted().describe(&self.lines, &mut described);
+        }
+        self.cursor = edit.cursor_before();
+        self.last_kill = None;
+        self.last_paste = None;
+        described.into_iter().for_each(|e| self.on_text_edit(e));
+        self.open_fold_at_cursor();
+        self.ghost_text = None;
+        self.yank_flashed.take();
+        self.auto_closers.clear();
+        self.cancel_selection();
+        true
+    }
+
+    /// Redo the last undo change. This method returns if the redo modified text contents or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc def"]);
+    ///
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), [" def"]);
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc def"]);
+    /// textarea.redo();
+    /// assert_eq!(textarea.lines(), [" def"]);
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        let tracks_edits = self.tracks_edits();
+        let Some(edit) = self.history.redo(&mut self.lines) else {
+            return false;
+        };
+        self.line_offsets.get_mut().invalidate(edit.first_row());
+        let mut described = vec![];
+        if tracks_edits {
+            edit.describe(&self.lines, &mut described);
+        }
+        self.cursor = edit.cursor_after();
+        self.last_kill = None;
+        self.last_paste = None;
+        described.into_iter().for_each(|e| self.on_text_edit(e));
+        self.open_fold_at_cursor();
+        self.ghost_text = None;
+        self.yank_flashed.take();
+        self.auto_closers.clear();
+        self.cancel_selection();
+        true
+    }
+
+    /// Check if the text was modified since it was created or [`TextArea::mark_saved`] was called last. This is based
+    /// on the undo history, so undoing the edits back to the saved point makes the text not modified again and redoing
+    /// them makes it modified. When the saved point is no longer in the history, for example, after undoing and making
+    /// another edit, the text is modified until it is marked as saved again.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// assert!(!textarea.is_modified());
+    ///
+    /// textarea.insert_str("world");
+    /// assert!(textarea.is_modified());
+    ///
+    /// textarea.undo();
+    /// assert!(!textarea.is_modified());
+    /// textarea.redo();
+    /// assert!(textarea.is_modified());
+    ///
+    /// // After the text is saved
+    /// textarea.mark_saved();
+    /// assert!(!textarea.is_modified());
+    /// textarea.undo();
+    /// assert!(textarea.is_modified());
+    /// ```
+    pub fn is_modified(&self) -> bool {
+        self.history.state() != self.saved_state
+    }
+
+    /// Mark the current text as saved, such as after the text was written to a file successfully. See
+    /// [`TextArea::is_modified`]. Characters typed after this call are not merged into the undo unit before it so that
+    /// undo can return to the saved text.
+    pub fn mark_saved(&mut self) {
+        self.history.stop_merging();
+        self.saved_state = self.history.state();
+    }
+
+    /// Get the number of modifications made to the text including undo and redo. The number only increases, so it can
+    /// be compared with the number remembered before to cheaply detect changes of the text, for example, to invalidate
+    /// caches computed from the text.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let count = textarea.edit_count();
+    ///
+    /// textarea.insert_str("hello");
+    /// assert!(textarea.edit_count() > count);
+    ///
+    /// // Moving the cursor does not modify the text
+    /// let count = textarea.edit_count();
+    /// textarea.move_cursor(tui_textarea::CursorMove::Head);
+    /// assert_eq!(textarea.edit_count(), count);
+    /// ```
+    pub fn edit_count(&self) -> u64 {
+        self.history.changes()
+    }
+
+    /// Save the state of the editor such as the cursor, the selection, the scroll position, the search pattern, folds,
+    /// and marks to restore it later by [`TextArea::restore`]. The text and the undo history are not included. Use
+    /// [`TextArea::snapshot_with_text`] or [`TextArea::snapshot_with_history`] to include them. The state can be
+    /// serialized with `serde` to keep sessions across runs of an application.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.move_cursor(CursorMove::Jump(1, 1));
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.set_mark('a');
+    ///
+    /// let state = textarea.snapshot();
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.restore(state);
+    /// assert_eq!(textarea.cursor(), (1, 5));
+    /// assert_eq!(textarea.selection_range(), Some(((1, 1), (1, 5))));
+    /// assert_eq!(textarea.marks()[&'a'], (1, 5));
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn snapshot(&self) -> TextAreaState {
+        #[cfg(feature = "search")]
+        let search_pattern = self.search.pat.as_ref().map(|_| self.search.query().to_string());
+        #[cfg(not(feature = "search"))]
+        let search_pattern = None;
+        TextAreaState {
+            cursor: self.cursor,
+            selection_start: self.selection_start,
+            scroll_top: self.viewport.scroll_top(),
+            search_pattern,
+            folds: self.folds.clone(),
+            marks: self.marks.iter().map(|(&name, &pos)| (name, pos)).collect(),
+            lines: None,
+            history: None,
+        }
+    }
+
+    /// Save the state of the editor like [`TextArea::snapshot`] including the text. Restoring the state replaces the
+    /// text.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello"]);
+    /// let state = textarea.snapshot_with_text();
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.restore(state);
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn snapshot_with_text(&self) -> TextAreaState {
+        let mut state = self.snapshot();
+        state.lines = Some(self.lines.to_vec());
+        state
+    }
+
+    /// Save the state of the editor like [`TextArea::snapshot`] including the undo history. The history may be large
+    /// since it contains the text to check that the history can be applied on restoring. The history is restored only
+    /// when the text is the same as the text on saving, and whether the text is modified since it was saved last (see
+    /// [`TextArea::is_modified`]) is also restored.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.insert_str("world");
+    /// let state = textarea.snapshot_with_history();
+    ///
+    /// let mut textarea = TextArea::from(["worldhello"]);
+    /// textarea.restore(state);
+    /// assert!(textarea.undo());
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn snapshot_with_history(&self) -> TextAreaState {
+        let mut state = self.snapshot();
+        let (edits, index) = self.history.edits();
+        state.history = Some(UndoHistory {
+            lines: self.lines.to_vec(),
+            edits,
+            index,
+            saved: self.history.index_of_state(self.saved_state),
+        });
+        state
+    }
+
+    /// Restore the state of the editor saved by [`TextArea::snapshot`]. Positions in the state are clamped to the
+    /// current text, and folds out of the text are ignored. When the state contains the text, the text is replaced
+    /// first. When the state contains the undo history saved on the same text, the history is replaced as well. The
+    /// history is dropped when some edit in it cannot be applied to the text.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.move_cursor(CursorMove::Jump(1, 5));
+    /// let state = textarea.snapshot();
+    ///
+    /// // The text was shortened since the state was saved
+    /// let mut textarea = TextArea::from(["hi"]);
+    /// textarea.restore(state);
+    /// assert_eq!(textarea.cursor(), (0, 2));
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn restore(&mut self, state: TextAreaState) {
+        if let Some(mut lines) = state.lines {
+            if lines.is_empty() {
+                lines.push(String::new());
+            }
+            if self.lines != lines {
+                self.replace_text(lines);
+            }
+        }
+        if let Some(history) = state.history.filter(|h| self.lines == h.lines) {
+            if let Some(forgotten) = self.history.restore(history.edits, history.index, &self.lines) {
+                if let Some(i) = history.saved.and_then(|i| i.checked_sub(forgotten)) {
+                    self.saved_state = self.history.state_at(i);
+                }
+            }
+        }
+
+        self.clear_secondary_cursors();
+        self.cancel_selection();
+        let clamp = |(row, col): (usize, usize)| {
+            let row = min(row, self.lines.len() - 1);
+            (row, min(col, self.lines.char_count(row)))
+        };
+        self.cursor = clamp(state.cursor);
+        self.selection_start = state.selection_start.map(clamp);
+        self.marks = state.marks.into_iter().map(|(name, pos)| (name, clamp(pos))).collect();
+        self.folds.clear();
+        for fold in state.folds {
+            self.fold(fold.start, fold.end);
+        }
+        #[cfg(feature = "search")]
+        {
+            // The pattern is ignored when it is no longer valid
+            let _ = self.set_search_pattern(state.search_pattern.unwrap_or_default());
+        }
+        let (row, col) = state.scroll_top;
+        self.viewport.set_row(min(row, self.lines.len() as u64 - 1));
+        self.viewport.set_col(col);
+        self.history.stop_merging();
+    }
+
+    // Replace the entire text without recording it in the undo history
+    #[cfg(feature = "serde")]
+    fn replace_text(&mut self, lines: Vec<String>) {
+        let last = self.lines.len() - 1;
+        let edit = TextEdit {
+            start: (0, 0),
+            end: (last, self.lines.char_count(last)),
+            inserted: lines.join("\n"),
+            deleted: self.lines.join("\n"),
+        };
+        self.on_text_edit(edit);
+        self.lines.set(lines);
+        self.line_offsets.get_mut().clear();
+        self.history.clear();
+        self.yank_flashed.take();
+    }
+
+    /// Start grouping the following edits into one undo unit. All edits until [`TextArea::end_undo_group`] is called are
+    /// undone or redone at once. Calling this method while grouping is ongoing does nothing. Calling
+    /// [`TextArea::undo`] or [`TextArea::redo`] finishes the ongoing group.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.begin_undo_group();
+    /// textarea.insert_str("hello");
+    /// textarea.insert_newline();
+    /// textarea.insert_str("world");
+    /// textarea.end_undo_group();
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), [""]);
+    /// ```
+    pub fn begin_undo_group(&mut self) {
+        self.history.begin_group();
+    }
+
+    /// Finish the undo group started by [`TextArea::begin_undo_group`]. This method does nothing when no group is
+    /// ongoing.
+    pub fn end_undo_group(&mut self) {
+        self.history.end_group();
+    }
+
+    // Byte range in the row of the match which the last search moved the cursor to. `None` when the match is not in the
+    // row or it is no longer a match of the pattern after the text was modified
+    #[cfg(feature = "search")]
+    fn current_match_range(&self, row: usize, line: &str, highlights: &TextHighlights) -> Option<(usize, usize)> {
+        let current = self.search.current?;
+        let ((start_row, start), (end_row, end)) = current;
+        if row < start_row || end_row < row {
+            return None;
+        }
+        if self.search.is_multiline() {
+            // Visible matches include the current match. See `TextArea::text_highlights`
+            if !highlights.matches.contains(&current) {
+                return None;
+            }
+            return search::ranges_in_row(std::slice::from_ref(&current), row, line.len()).next();
+        }
+        if self.search.mode() == SearchMode::Fuzzy {
+            return self.search.fuzzy_span(row, line).filter(|&m| m == (start, end));
+        }
+        self.search.matches(row, line)?.into_iter().find(|&m| m == (start, end))
+    }
+
+    /// Highlights depending on the entire text rather than each line, calculated once per rendering for the rendered
+    /// `rows`. Matches of a multi-line search pattern are searched only in the rows, extended to the start of the
+    /// current match when it begins above them.
+    #[cfg_attr(not(feature = "search"), allow(unused_variables))]
+    pub(crate) fn text_highlights(&self, rows: Range<usize>) -> TextHighlights {
+        let mut highlights = TextHighlights {
+            yank_flash: self.next_yank_flash(),
+            ..Default::default()
+        };
+        if self.display_mask().is_some() {
+            // Highlights depending on the text would leak the masked text
+            highlights.revealed = self.next_revealed();
+            return highlights;
+        }
+        #[cfg(feature = "search")]
+        if self.search.is_multiline() {
+            let mut rows = rows;
+            if let Some(((start_row, _), (end_row, _))) = self.search.current {
+                // Find the current match even when its start is scrolled out
+                if start_row < rows.start && rows.start <= end_row {
+                    rows.start = start_row;
+                }
+            }
+            // Multi-line search matches cannot be found line by line
+            highlights.matches = self.search.find_in_rows(&self.lines, rows.clone());
+            highlights.search_rows = Some(rows);
+        }
+        if self.matching_bracket_style.is_some() && self.shown_cursor_style().is_some() {
+            highlights.bracket = find_matching_bracket(&self.lines, self.cursor, self.matching_bracket_limit);
+        }
+        highlights
+    }
+
+    // The text copied last is flashed while its count lasts. Each rendering consumes one count
+    fn next_yank_flash(&self) -> RowRanges {
+        let mut flashed = self.yank_flashed.borrow_mut();
+        match flashed.as_mut() {
+            Some((ranges, count)) if *count > 0 => {
+                *count -= 1;
+                ranges.clone()
+            }
+            _ => {
+                *flashed = None;
+                vec![]
+            }
+        }
+    }
+
+    // The character typed last is revealed while the cursor stays right after it. Each rendering consumes one count
+    fn next_revealed(&self) -> Option<Pos> {
+        let ((row, col), count) = self.mask_revealed.take()?;
+        if count == 0 || self.cursor != (row, col + 1) {
+            return None;
+        }
+        self.mask_revealed.set(Some(((row, col), count - 1)));
+        let offset = self.lines.offset_of_col(row, col);
+        let c = self.lines.char_at(row, offset)?;
+        // Revealing a character whose width differs from the mask character would shift the following text
+        let mask = self.mask?;
+        (c != '\t' && c.width() == mask.width()).then(|| Pos::new(row, col, offset))
+    }
+
+    // Display width of the leading whitespaces where indent guides are drawn. A line with only whitespaces continues the
+    // guides of the nearest non-blank lines above and below it
+    fn indent_guide_width(&self, row: usize) -> usize {
+        if self.indent_guides.is_none() || self.display_mask().is_some() || self.tab_len == 0 {
+            return 0;
+        }
+        let tab_len = self.tab_len as usize;
+        let width = |l: &str| line_indent_width(l, tab_len);
+        if let Some(w) = width(&self.lines.line(row)) {
+            return w;
+        }
+        let above = self.lines.iter_in(0..row).rev().find_map(|l| width(&l));
+        let below = self.lines.iter_in(row + 1..self.lines.len()).find_map(|l| width(&l));
+        min(above.unwrap_or(0), below.unwrap_or(0))
+    }
+
+    fn display_mask(&self) -> Option<char> {
+        self.mask.filter(|_| self.mask_enabled)
+    }
+
+    // Start rendering lines without soft-wrap through the render cache. The first `hidden` display columns of the text
+    // are scrolled out with the gutter, and only the following `max_width` columns are rendered
+    #[cfg_attr(not(feature = "search"), allow(unused_variables))]
+    pub(crate) fn begin_render_cache(
+        &self,
+        lnum_len: u8,
+        highlights: &TextHighlights,
+        hidden: usize,
+        max_width: usize,
+    ) {
+        #[cfg(feature = "search")]
+        let multiline_search = self.search.is_multiline();
+        #[cfg(not(feature = "search"))]
+        let multiline_search = false;
+        // Indent guides of blank lines and multi-line matches depend on the surrounding lines
+        let surrounded = self.indent_guides.is_some() || multiline_search;
+        let settings = RenderSettings {
+            cursor_style: self.shown_cursor_style().unwrap_or_default(),
+            secondary_cursor_style: self.secondary_cursor_style,
+            cursor_line_style: self.cursor_line_style,
+            cursor_line_style_in_selection: self.cursor_line_style_in_selection(),
+            select_style: self.select_style,
+            line_number_style: self.line_number_style,
+            relative_line_numbers: self.relative_line_numbers,
+            lnum_len,
+            sign_column: self.sign_column_width() > 0,
+            tab_len: self.tab_len,
+            mask: self.display_mask(),
+            alignment: self.alignment,
+            whitespace: self.whitespace,
+            indent_guides: self.indent_guides.map(|style| (self.indent_guide_char, style)),
+            rulers: self.rulers.clone(),
+            control_chars: self.render_control_chars.then_some(self.control_char_style),
+            link_style: self.link_detector.map(|_| self.link_style),
+            matching_bracket_style: self.matching_bracket_style,
+            yank_flash_style: self.yank_flash.map(|(style, _)| style),
+            #[cfg(feature = "search")]
+            search: self.search.pat.as_ref().map(|_| {
+                (
+                    self.search.query().to_string(),
+                    self.search.options(),
+                    self.search.mode(),
+                    self.search.style,
+                    self.current_search_match_style(),
+                )
+            }),
+            #[cfg(feature = "search")]
+            search_scope: self.search.scope,
+            #[cfg(feature = "search")]
+            search_rows: highlights.search_rows.clone(),
+            text_edits: surrounded.then(|| self.render_cache.borrow().edits()),
+            hidden,
+            max_width,
+        };
+        self.render_cache.borrow_mut().begin(settings, self.lines.len());
+    }
+
+    pub(crate) fn end_render_cache(&self) {
+        self.render_cache.borrow_mut().end();
+    }
+
+    // Spans of the line rendered without soft-wrap. When the line and its highlights are not changed since the previous
+    // rendering, the cached spans are reused without reading the line. This must be called between `begin_render_cache`
+    // and `end_render_cache`
+    pub(crate) fn line_spans(
+        &self,
+        cursor_row: usize,
+        row: usize,
+        lnum_len: u8,
+        highlights: &TextHighlights,
+        hidden: usize,
+        max_width: usize,
+    ) -> Line<'static> {
+        let gutter = self.line_number_style.map(|_| {
+            if cursor_row == row {
+                (row + 1, false)
+            } else if self.relative_line_numbers {
+                (row.abs_diff(cursor_row), true)
+            } else {
+                (row + 1, true)
+            }
+        });
+        let key = LineKey {
+            gutter,
+            sign: self.signs.get(&row).filter(|_| self.sign_column_width() > 0).cloned(),
+            cursor: (row == self.cursor.0 && self.shown_cursor_style().is_some()).then_some(self.cursor.1),
+            secondary_cursors: if self.shown_cursor_style().is_some() {
+                self.secondary_cursor_cols(row)
+            } else {
+                vec![]
+            },
+            secondary_selections: self.secondary_selections(row),
+            ghost: row == self.cursor.0 && self.shown_ghost_text().is_some(),
+            #[cfg(feature = "search")]
+            current_match: self
+                .search
+                .current
+                .filter(|&((start_row, _), (end_row, _))| start_row <= row && row <= end_row),
+            selection: if self.selection_mode == SelectionMode::Column {
+                self.column_selection_offsets(row).map(|(s, e)| (row, s, row, e))
+            } else if let Some((top, bottom)) = self.linewise_rows() {
+                (top <= row && row <= bottom).then_some((top, 0, bottom + 1, 0))
+            } else {
+                self.selection_positions()
+                    .filter(|(s, e)| s.row <= row && row <= e.row)
+                    .map(|(s, e)| (s.row, s.offset, e.row, e.offset))
+            },
+            yank_flash: highlights
+                .yank_flash
+                .iter()
+                .filter(|(r, _, _)| *r == row)
+                .map(|&(_, s, e)| (s, e))
+                .collect(),
+            bracket: highlights.bracket.as_ref().filter(|p| p.row == row).map(|p| p.offset),
+            reveal: highlights.revealed.as_ref().filter(|p| p.row == row).map(|p| p.offset),
+        };
+
+        self.render_cache.borrow_mut().get_or_build(row, key, || {
+            // Other alignments need the width of the whole line
+            if self.alignment != Alignment::Left {
+                let line = &self.lines.line(row);
+                let hl = self.line_highlighter(cursor_row, line, LinePart::whole(line), row, lnum_len, highlights);
+                return hl.into_owned_spans();
+            }
+            // Only the visible part of the line is read so that a very long line is not copied out of a rope
+            let (part, end, _) = self.visible_part(row, hidden, max_width);
+            let line = &self.lines.slice(row, part.offset..end);
+            let mut hl = self.line_highlighter(cursor_row, line, part, row, lnum_len, highlights);
+            if hidden > 0 {
+                hl.scroll(part.offset, part.width, part.width.saturating_sub(hidden));
+                hl.hide_end(hidden.saturating_sub(part.width)); // The end of line may also be scrolled out
+            }
+            if end < part.len {
+                hl.clip(end);
+            }
+            hl.into_owned_spans()
+        })
+    }
+
+    /// Clear the spans of lines cached for rendering. Spans of visible lines are cached and reused while the lines and
+    /// their highlights are not changed. The cache is updated automatically on editing text or changing styles, so
+    /// this method is usually not necessary.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello"]);
+    /// textarea.clear_render_cache();
+    /// ```
+    pub fn clear_render_cache(&self) {
+        self.render_cache.borrow_mut().clear();
+    }
+
+    // Display rows of the line rendered with soft-wrap. Only the visible lines are passed, so the spans are copied out of
+    // the line which may be a temporary copy of a rope slice
+    pub(crate) fn wrapped_line_spans(
+        &self,
+        cursor_row: usize,
+        line: &str,
+        row: usize,
+        lnum_len: u8,
+        highlights: &TextHighlights,
+        wrapper: &Wrapper,
+    ) -> Vec<Line<'static>> {
+        let mut hl = self.line_highlighter(cursor_row, line, LinePart::whole(line), row, lnum_len, highlights);
+
+        let end_cell = row == self.cursor.0 && self.cursor.1 >= line.chars().count();
+        let rows = wrapper.rows(line, end_cell);
+        if rows.len() > 1 {
+            let mut offsets = line.char_indices().map(|(i, _)| i).chain(iter::once(line.len()));
+            let mut prev = 0;
+            let breaks = rows[1..]
+                .iter()
+                .map(|&col| {
+                    let offset = offsets.nth(col - prev).unwrap();
+                    prev = col + 1;
+                    offset
+                })
+                .collect();
+
+            let mut prefix = vec![];
+            let gutter = self.gutter_width();
+            if gutter > 0 {
+                prefix.push(Span::raw(spaces(gutter as u8)));
+            }
+            if let Some((indicator, style)) = &self.wrap_indicator {
+                prefix.push(Span::styled(indicator.as_str(), *style));
+            }
+            hl.wrap(breaks, prefix);
+        }
+
+        hl.into_owned_lines()
+    }
+
+    // Highlighter of the line at the row. `line` is the part of the line described by `part`, and the rest of the line
+    // is read only when a highlight needs the whole line such as search matches
+    fn line_highlighter<'b>(
+        &'b self,
+        cursor_row: usize,
+        line: &'b str,
+        part: LinePart,
+        row: usize,
+        lnum_len: u8,
+        highlights: &TextHighlights,
+    ) -> LineHighlighter<'b> {
+        let mask = self.display_mask();
+        let cursor_style = self.shown_cursor_style();
+        let mut hl = LineHighlighter::new(
+            line,
+            cursor_style.unwrap_or_default(),
+            self.tab_len,
+            mask,
+            self.select_style,
+        );
+        hl.part(part);
+
+        if self.sign_column_width() > 0 {
+            match self.signs.get(&row) {
+                Some(sign) => hl.sign(sign.cells(), sign.style),
+                None => hl.sign(spaces(SIGN_WIDTH as u8).to_string(), Style::default()),
+            }
+        }
+
+        if let Some(style) = self.line_number_style {
+            if cursor_row == row {
+                hl.line_number(row, lnum_len, style);
+            } else if self.relative_line_numbers {
+                let distance = row.abs_diff(cursor_row);
+                hl.relative_line_number(distance, lnum_len, style.add_modifier(Modifier::DIM));
+            } else {
+                hl.line_number(row, lnum_len, style.add_modifier(Modifier::DIM));
+            }
+        }
+
+        if row == self.cursor.0 && cursor_style.is_some() {
+            if self.shown_ghost_text().is_some() {
+                hl.cursor_line_style(self.shown_cursor_line_style());
+            } else {
+                hl.cursor_line(self.cursor.1, self.shown_cursor_line_style());
+            }
+        }
+        if cursor_style.is_some() {
+            for col in self.secondary_cursor_cols(row) {
+                hl.secondary_cursor(col, self.secondary_cursor_style);
+            }
+        }
+
+        if let Some(s) = self.line_styles.get(&row) {
+            if self.line_equals(row, &s.text) {
+                hl.line_styles(&s.styles);
+            }
+        }
+
+        if self.render_control_chars && mask.is_none() {
+            hl.control_chars(self.control_char_style);
+        }
+
+        if !self.rulers.is_empty() && self.alignment == Alignment::Left {
+            let mut rulers: Vec<_> = self.rulers.iter().map(|&(col, style)| (col as usize, style)).collect();
+            rulers.sort_by_key(|&(col, _)| col);
+            hl.rulers(rulers);
+        }
+
+        if !self.highlights.is_empty() {
+            hl.highlights(&self.highlight_ranges(row));
+        }
+
+        let cols = part.col..part.col + line.chars().count();
+        let links: Vec<_> = self
+            .links_in(row, cols)
+            .into_iter()
+            .map(|(range, _)| (range, self.link_style))
+            .collect();
+        if !links.is_empty() {
+            hl.highlights(&links);
+        }
+
+        if let (Some(ws), None) = (&self.whitespace, mask) {
+            hl.whitespace(ws);
+        }
+
+        if let Some(style) = self.indent_guides {
+            let width = self.indent_guide_width(row);
+            if width > 0 {
+                hl.indent_guides(width, self.indent_guide_char, style);
+            }
+        }
+
+        #[cfg(feature = "search")]
+        if mask.is_some() {
+            // Search matches are not highlighted not to leak the masked text
+        } else if self.search.is_active() {
+            // Matches are searched in the whole line even when only a part of it is rendered
+            let line = &if part.len == line.len() {
+                Cow::Borrowed(line)
+            } else {
+                self.lines.line(row)
+            };
+            if self.search.is_multiline() {
+                let ranges = search::ranges_in_row(&highlights.matches, row, line.len());
+                hl.search(ranges, self.search.style);
+            } else if let Some(matches) = self.search.matches(row, line) {
+                hl.search(matches.into_iter(), self.search.style);
+            }
+            if let Some(range) = self.current_match_range(row, line, highlights) {
+                let style = self.current_search_match_style();
+                if self.search.mode() == SearchMode::Fuzzy {
+                    // Only the matched characters of the fuzzy match are highlighted
+                    for range in self.search.matches(row, line).unwrap_or_default() {
+                        hl.current_search_match(range, style);
+                    }
+                } else {
+                    hl.current_search_match(range, style);
+                }
+            }
+        }
+
+        if self.selection_mode == SelectionMode::Column {
+            if let Some((start, end)) = self.column_selection_offsets(row) {
+                hl.selection(row, row, start, row, end);
+            }
+        } else if let Some((top, bottom)) = self.linewise_rows() {
+            // The selection ends at the head of the next line so that the newline cell of the bottom row is selected
+            hl.selection(row, top, 0, bottom + 1, 0);
+        } else if let Some((start, end)) = self.selection_positions() {
+            hl.selection(row, start.row, start.offset, end.row, end.offset);
+        }
+        for (start_row, start, end_row, end) in self.secondary_selections(row) {
+            hl.selection(row, start_row, start, end_row, end);
+        }
+        if let Some((style, _)) = self.yank_flash {
+            for &(_, start, end) in highlights.yank_flash.iter().filter(|(r, _, _)| *r == row) {
+                hl.yank_flash(start, end, style);
+            }
+        }
+
+        if let (Some(style), Some(pos)) = (self.matching_bracket_style, &highlights.bracket) {
+            if pos.row == row {
+                hl.matching_bracket(pos.offset, style);
+            }
+        }
+
+        if let Some(pos) = &highlights.revealed {
+            if pos.row == row {
+                hl.reveal(pos.offset);
+            }
+        }
+
+        hl
+    }
+
+    /// Build a ratatui (or tui-rs) widget to render the current state of the textarea. The widget instance returned
+    /// from this method can be rendered with [`ratatui::terminal::Frame::render_widget`]. `&TextArea` can also be
+    /// rendered directly as a widget, or as a stateful widget with a separate [`crate::Viewport`].
+    /// ```no_run
+    /// use ratatui::backend::CrosstermBackend;
+    /// use ratatui::layout::{Constraint, Direction, Layout};
+    /// use ratatui::Terminal;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let layout = Layout::default()
+    ///     .direction(Direction::Vertical)
+    ///     .constraints([Constraint::Min(1)].as_ref());
+    /// let backend = CrosstermBackend::new(std::io::stdout());
+    /// let mut term = Terminal::new(backend).unwrap();
+    ///
+    /// loop {
+    ///     term.draw(|f| {
+    ///         let chunks = layout.split(f.size());
+    ///         let widget = textarea.widget();
+    ///         f.render_widget(widget, chunks[0]);
+    ///     }).unwrap();
+    ///
+    ///     // ...
+    /// }
+    /// ```
+    pub fn widget(&'a self) -> impl Widget + 'a {
+        Renderer::new(self)
+    }
+
+    /// Set the style of textarea. By default, textarea is not styled.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let style = Style::default().fg(Color::Red);
+    /// textarea.set_style(style);
+    /// assert_eq!(textarea.style(), style);
+    /// ```
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// Get the current style of textarea.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Set the block of textarea. By default, no block is set.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::widgets::{Block, Borders};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
+    /// textarea.set_block(block);
+    /// assert!(textarea.block().is_some());
+    /// ```
+    pub fn set_block(&mut self, block: Block<'a>) {
+        self.block = Some(block);
+    }
+
+    /// Remove the block of textarea which was set by [`TextArea::set_block`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::widgets::{Block, Borders};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
+    /// textarea.set_block(block);
+    /// textarea.remove_block();
+    /// assert!(textarea.block().is_none());
+    /// ```
+    pub fn remove_block(&mut self) {
+        self.block = None;
+    }
+
+    /// Get the block of textarea if exists.
+    pub fn block<'s>(&'s self) -> Option<&'s Block<'a>> {
+        self.block.as_ref()
+    }
+
+    /// Set the length of tab character. Setting 0 disables tab inputs.
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let tab_input = Input { key: Key::Tab, ctrl: false, alt: false, shift: false };
+    ///
+    /// textarea.set_tab_length(8);
+    /// textarea.input(tab_input.clone());
+    /// assert_eq!(textarea.lines(), ["        "]);
+    ///
+    /// textarea.set_tab_length(2);
+    /// textarea.input(tab_input);
+    /// assert_eq!(textarea.lines(), ["          "]);
+    /// ```
+    pub fn set_tab_length(&mut self, len: u8) {
+        self.tab_len = len;
+    }
+
+    /// Get how many spaces are used for representing tab character. The default value is 4.
+    pub fn tab_length(&self) -> u8 {
+        self.tab_len
+    }
+
+    /// Set if a hard tab is used or not for indent. When `true` is set, typing a tab key inserts a hard tab instead of
+    /// spaces. By default, hard tab is disabled.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_hard_tab_indent(true);
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["\t"]);
+    /// ```
+    pub fn set_hard_tab_indent(&mut self, enabled: bool) {
+        self.hard_tab_indent = enabled;
+    }
+
+    /// Get if a hard tab is used for indent or not.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert!(!textarea.hard_tab_indent());
+    /// textarea.set_hard_tab_indent(true);
+    /// assert!(textarea.hard_tab_indent());
+    /// ```
+    pub fn hard_tab_indent(&self) -> bool {
+        self.hard_tab_indent
+    }
+
+    /// Get a string for indent. It consists of spaces by default. When hard tab is enabled, it is a tab character.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert_eq!(textarea.indent(), "    ");
+    /// textarea.set_tab_length(2);
+    /// assert_eq!(textarea.indent(), "  ");
+    /// textarea.set_hard_tab_indent(true);
+    /// assert_eq!(textarea.indent(), "\t");
+    /// ```
+    pub fn indent(&self) -> &'static str {
+        if self.hard_tab_indent {
+            "\t"
+        } else {
+            spaces(self.tab_len)
+        }
+    }
+
+    /// Enable or disable auto-indent. When enabled, inserting a newline copies the leading whitespaces of the current
+    /// line to the new line as they are (tabs stay tabs and spaces stay spaces). When the current line has only
+    /// whitespaces, they are cleared instead of being copied. Inserting the newline and the indentation is one undo
+    /// unit. By default, auto-indent is disabled.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["\tfoo:"]);
+    /// textarea.set_auto_indent(true);
+    /// assert!(textarea.auto_indent());
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.lines(), ["\tfoo:", "\t"]);
+    /// assert_eq!(textarea.cursor(), (1, 1));
+    ///
+    /// // Pressing Enter on the line with only whitespaces clears them
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.lines(), ["\tfoo:", "", ""]);
+    ///
+    /// // One undo removes both the newline and the indentation
+    /// textarea.undo();
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["\tfoo:"]);
+    /// ```
+    pub fn set_auto_indent(&mut self, enabled: bool) {
+        self.auto_indent = enabled;
+    }
+
+    /// Get if auto-indent is enabled. See [`TextArea::set_auto_indent`].
+    pub fn auto_indent(&self) -> bool {
+        self.auto_indent
+    }
+
+    /// Set if [`TextArea::delete_char`] (Backspace) right after the indentation inserted by auto-indent removes the
+    /// entire indentation at once. This is enabled by default. See [`TextArea::set_auto_indent`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["    foo"]);
+    /// textarea.set_auto_indent(true);
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.lines(), ["    foo", "    "]);
+    ///
+    /// textarea.delete_char();
+    /// assert_eq!(textarea.lines(), ["    foo", ""]);
+    ///
+    /// textarea.undo();
+    /// textarea.set_auto_indent_backspace(false);
+    /// textarea.delete_char();
+    /// assert_eq!(textarea.lines(), ["    foo", "   "]);
+    /// ```
+    pub fn set_auto_indent_backspace(&mut self, enabled: bool) {
+        self.auto_indent_backspace = enabled;
+    }
+
+    /// Get if Backspace removes the entire indentation inserted by auto-indent. See
+    /// [`TextArea::set_auto_indent_backspace`].
+    pub fn auto_indent_backspace(&self) -> bool {
+        self.auto_indent_backspace
+    }
+
+    /// Set if [`TextArea::insert_char`] closes brackets and quotes automatically. By default, auto-pairs is disabled.
+    /// When enabled:
+    ///
+    /// - Typing an opening character inserts the pair and puts the cursor between them. The pair is not closed before
+    ///   a word character, and a quote is not closed after a word character either.
+    /// - Typing the closing character inserted automatically moves the cursor over it instead of inserting another.
+    /// - [`TextArea::delete_char`] (Backspace) between an empty pair inserted automatically removes both.
+    /// - Typing an opening character while some text is selected wraps the selection with the pair.
+    ///
+    /// Each of these is one undo unit. The pairs can be customized with [`TextArea::set_auto_pair_chars`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_auto_pairs(true);
+    /// assert!(textarea.auto_pairs());
+    ///
+    /// textarea.insert_char('(');
+    /// assert_eq!(textarea.lines(), ["()"]);
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    ///
+    /// // Typing the closing character moves the cursor over it
+    /// textarea.insert_char('x');
+    /// textarea.insert_char(')');
+    /// assert_eq!(textarea.lines(), ["(x)"]);
+    /// assert_eq!(textarea.cursor(), (0, 3));
+    ///
+    /// // A quote after a word is not closed
+    /// textarea.insert_char('s');
+    /// textarea.insert_char('\'');
+    /// assert_eq!(textarea.lines(), ["(x)s'"]);
+    ///
+    /// // The selection is wrapped
+    /// textarea.select_all();
+    /// textarea.insert_char('[');
+    /// assert_eq!(textarea.lines(), ["[(x)s']"]);
+    /// ```
+    pub fn set_auto_pairs(&mut self, enabled: bool) {
+        self.auto_pairs = enabled;
+        self.auto_closers.clear();
+    }
+
+    /// Get if auto-pairs is enabled. See [`TextArea::set_auto_pairs`].
+    pub fn auto_pairs(&self) -> bool {
+        self.auto_pairs
+    }
+
+    /// Set if the overwrite mode is enabled. In the overwrite mode, a typed character replaces the character under the
+    /// cursor instead of being inserted. At the end of line, the character is appended. Each replacement can be undone.
+    /// Other edits such as deleting characters with backspace and pasting text work as usual. The mode is toggled with
+    /// the Insert key by default. The overwrite mode is disabled by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["abc"]);
+    /// textarea.set_overwrite_mode(true);
+    /// assert!(textarea.overwrite_mode());
+    ///
+    /// textarea.insert_char('x');
+    /// textarea.insert_char('y');
+    /// assert_eq!(textarea.lines(), ["xyc"]);
+    ///
+    /// // The character is appended at the end of line
+    /// textarea.insert_char('z');
+    /// textarea.insert_char('w');
+    /// assert_eq!(textarea.lines(), ["xyzw"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["xyz"]);
+    /// ```
+    pub fn set_overwrite_mode(&mut self, enabled: bool) {
+        self.overwrite_mode = enabled;
+    }
+
+    /// Get if the overwrite mode is enabled. See [`TextArea::set_overwrite_mode`].
+    pub fn overwrite_mode(&self) -> bool {
+        self.overwrite_mode
+    }
+
+    /// Toggle the overwrite mode. See [`TextArea::set_overwrite_mode`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.toggle_overwrite_mode();
+    /// assert!(textarea.overwrite_mode());
+    /// textarea.toggle_overwrite_mode();
+    /// assert!(!textarea.overwrite_mode());
+    /// ```
+    pub fn toggle_overwrite_mode(&mut self) {
+        self.overwrite_mode = !self.overwrite_mode;
+    }
+
+    /// Set the style of the cursor in the overwrite mode. By default, the cursor is rendered with the style set by
+    /// [`TextArea::set_cursor_style`] in the overwrite mode as well.
+    /// ```
+    /// use ratatui::style::{Modifier, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.overwrite_cursor_style(), None);
+    ///
+    /// let style = Style::default().add_modifier(Modifier::UNDERLINED);
+    /// textarea.set_overwrite_cursor_style(style);
+    /// assert_eq!(textarea.overwrite_cursor_style(), Some(style));
+    /// ```
+    pub fn set_overwrite_cursor_style(&mut self, style: Style) {
+        self.overwrite_cursor_style = Some(style);
+    }
+
+    /// Get the style of the cursor in the overwrite mode set by [`TextArea::set_overwrite_cursor_style`].
+    pub fn overwrite_cursor_style(&self) -> Option<Style> {
+        self.overwrite_cursor_style
+    }
+
+    /// Set the pairs of opening and closing characters inserted by auto-pairs. A pair whose opening and closing
+    /// characters are the same is handled as a quote. The default pairs are `()`, `[]`, `{}`, `""`, `''`, and ``` `` ```.
+    /// See [`TextArea::set_auto_pairs`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_auto_pairs(true);
+    /// textarea.set_auto_pair_chars([('(', ')'), ('<', '>')]);
+    /// assert_eq!(textarea.auto_pair_chars(), [('(', ')'), ('<', '>')]);
+    ///
+    /// textarea.insert_char('<');
+    /// textarea.insert_char('"');
+    /// assert_eq!(textarea.lines(), ["<\">"]);
+    /// ```
+    pub fn set_auto_pair_chars(&mut self, pairs: impl IntoIterator<Item = (char, char)>) {
+        self.auto_pair_chars = pairs.into_iter().collect();
+        self.auto_closers.clear();
+    }
+
+    /// Get the pairs of characters inserted by auto-pairs. See [`TextArea::set_auto_pair_chars`].
+    pub fn auto_pair_chars(&self) -> &[(char, char)] {
+        &self.auto_pair_chars
+    }
+
+    /// Set the style of the bracket matching with the bracket at the cursor. By setting the style with this method,
+    /// the pair of the bracket under the cursor, or immediately before the cursor, is highlighted on rendering. `()`,
+    /// `[]` and `{}` are supported. Matching brackets are not highlighted by default.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().bg(Color::Yellow);
+    /// textarea.set_matching_bracket_style(style);
+    /// assert_eq!(textarea.matching_bracket_style(), Some(style));
+    /// ```
+    pub fn set_matching_bracket_style(&mut self, style: Style) {
+        self.matching_bracket_style = Some(style);
+    }
+
+    /// Remove the style of matching bracket which was set by [`TextArea::set_matching_bracket_style`]. After calling
+    /// this method, matching brackets will no longer be highlighted.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_matching_bracket_style(Style::default().bg(Color::Yellow));
+    /// textarea.remove_matching_bracket_style();
+    /// assert_eq!(textarea.matching_bracket_style(), None);
+    /// ```
+    pub fn remove_matching_bracket_style(&mut self) {
+        self.matching_bracket_style = None;
+    }
+
+    /// Get the style of matching bracket if set.
+    pub fn matching_bracket_style(&self) -> Option<Style> {
+        self.matching_bracket_style
+    }
+
+    /// Set the line ending used by [`TextArea::write_to`]. Setting [`LineEnding::Mixed`] keeps the line ending used on
+    /// writing as-is.
+    /// ```
+    /// use tui_textarea::{LineEnding, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b"]);
+    /// assert_eq!(textarea.line_ending(), LineEnding::Lf);
+    ///
+    /// textarea.set_line_ending(LineEnding::CrLf);
+    /// assert_eq!(textarea.line_ending(), LineEnding::CrLf);
+    /// ```
+    pub fn set_line_ending(&mut self, ending: LineEnding) {
+        match ending {
+            LineEnding::Lf => self.newline = "\n",
+            LineEnding::CrLf => self.newline = "\r\n",
+            LineEnding::Mixed => {}
+        }
+        self.line_ending = ending;
+    }
+
+    /// Get the line ending of the text. It is detected by [`TextArea::new_from_file`]. The default value is
+    /// [`LineEnding::Lf`].
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Visualize whitespaces on rendering. Spaces and tabs are rendered with the glyphs of [`WhitespaceStyle`], and
+    /// whitespaces at the end of each line are rendered with its trailing style. Only the rendering is affected. The
+    /// text and cursor positions are not changed. The styles are layered under the selection and the search matches.
+    /// Whitespaces are not visualized while the text is masked. Whitespaces are not visualized by default.
+    /// ```
+    /// use tui_textarea::{TextArea, WhitespaceStyle};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_show_whitespace(WhitespaceStyle::default());
+    /// assert_eq!(textarea.show_whitespace(), Some(&WhitespaceStyle::default()));
+    /// ```
+    pub fn set_show_whitespace(&mut self, ws: WhitespaceStyle) {
+        self.whitespace = Some(ws);
+    }
+
+    /// Stop visualizing whitespaces which was enabled by [`TextArea::set_show_whitespace`].
+    /// ```
+    /// use tui_textarea::{TextArea, WhitespaceStyle};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_show_whitespace(WhitespaceStyle::default());
+    /// textarea.hide_whitespace();
+    /// assert_eq!(textarea.show_whitespace(), None);
+    /// ```
+    pub fn hide_whitespace(&mut self) {
+        self.whitespace = None;
+    }
+
+    /// Get the glyphs and styles to visualize whitespaces if enabled.
+    pub fn show_whitespace(&self) -> Option<&WhitespaceStyle> {
+        self.whitespace.as_ref()
+    }
+
+    /// Draw vertical indent guides with the style. A guide is drawn at each indent stop (every [`TextArea::tab_length`]
+    /// columns) in the leading whitespaces of lines. Lines with only whitespaces continue the guides of the nearest
+    /// non-blank lines above and below them. Only the rendering is affected. The guides are layered under the
+    /// selection and the cursor line style, and are not drawn while the text is masked. Passing `None` disables the
+    /// guides, which is the default.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().fg(Color::DarkGray);
+    /// textarea.set_indent_guides(Some(style));
+    /// assert_eq!(textarea.indent_guides(), Some(style));
+    /// ```
+    pub fn set_indent_guides(&mut self, style: Option<Style>) {
+        self.indent_guides = style;
+    }
+
+    /// Get the style of indent guides if they are enabled by [`TextArea::set_indent_guides`].
+    pub fn indent_guides(&self) -> Option<Style> {
+        self.indent_guides
+    }
+
+    /// Set the character drawn as an indent guide. It should be one cell wide. The default value is `'│'`.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_indent_guide_char('┊');
+    /// assert_eq!(textarea.indent_guide_char(), '┊');
+    /// ```
+    pub fn set_indent_guide_char(&mut self, c: char) {
+        self.indent_guide_char = c;
+    }
+
+    /// Get the character drawn as an indent guide.
+    pub fn indent_guide_char(&self) -> char {
+        self.indent_guide_char
+    }
+
+    /// Set rulers which put the styles on the cells at the display columns on every line, such as a color column at the
+    /// 80th column. Columns are counted from 0 and do not include the gutter, so a ruler stays at the same column of the
+    /// text while the text is scrolled horizontally. Short lines are padded with spaces up to the rulers. The styles are
+    /// layered under the highlights, the selection, and the cursor line style. Rulers are rendered only when the text
+    /// is aligned to the left. Lines soft-wrapped into multiple rows are not padded. An empty list removes all rulers,
+    /// which is the default.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let rulers = vec![(80, Style::default().bg(Color::DarkGray)), (120, Style::default().bg(Color::Red))];
+    /// textarea.set_ruler_columns(rulers.clone());
+    /// assert_eq!(textarea.ruler_columns(), rulers);
+    /// ```
+    pub fn set_ruler_columns(&mut self, rulers: Vec<(u16, Style)>) {
+        self.rulers = rulers;
+    }
+
+    /// Get the display columns and the styles of rulers set by [`TextArea::set_ruler_columns`].
+    pub fn ruler_columns(&self) -> &[(u16, Style)] {
+        &self.rulers
+    }
+
+    /// Set the marker put at the start of each row below the last line, like `~` of Vim, with its style. This
+    /// distinguishes the end of the text from empty lines in it. The marker is put at the leftmost column regardless
+    /// of the gutter and is not shown with the placeholder. Passing `None` removes the marker, which is the default.
+    /// ```
+    /// use ratatui::style::{Modifier, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let style = Style::default().add_modifier(Modifier::DIM);
+    ///
+    /// textarea.set_empty_line_marker(Some(("~".to_string(), style)));
+    /// assert_eq!(textarea.empty_line_marker(), Some(("~", style)));
+    /// ```
+    pub fn set_empty_line_marker(&mut self, marker: Option<(String, Style)>) {
+        self.empty_line_marker = marker;
+    }
+
+    /// Get the marker of the rows below the last line and its style if set.
+    pub fn empty_line_marker(&self) -> Option<(&str, Style)> {
+        self.empty_line_marker.as_ref().map(|(s, style)| (s.as_str(), *style))
+    }
+
+    /// Set the indicator overlaid on the first or the last cell of each row whose line continues beyond the left or the
+    /// right edge of the textarea, with its style. This is useful with long lines scrolled horizontally. The indicator
+    /// is not drawn over the cursor, and is not drawn when lines are soft-wrapped or the text is not aligned to the
+    /// left. Passing `None` removes the indicator, which is the default.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let style = Style::default().fg(Color::DarkGray);
+    ///
+    /// textarea.set_truncation_indicator(Some(("…".to_string(), style)));
+    /// assert_eq!(textarea.truncation_indicator(), Some(("…", style)));
+    /// ```
+    pub fn set_truncation_indicator(&mut self, indicator: Option<(String, Style)>) {
+        self.truncation_indicator = indicator;
+    }
+
+    /// Get the indicator of lines truncated at the edges of the textarea and its style if set.
+    pub fn truncation_indicator(&self) -> Option<(&str, Style)> {
+        self.truncation_indicator
+            .as_ref()
+            .map(|(s, style)| (s.as_str(), *style))
+    }
+
+    /// Enable or disable rendering control characters in their notation. C0 control characters and DEL are rendered in
+    /// caret notation such as `^L` for form feed and `^[` for escape, and C1 control characters and invisible formatting
+    /// characters such as bidi controls are rendered with their code points such as `<200e>`. Otherwise they are
+    /// written to the terminal as-is, which may be invisible or garble the screen. Only the rendering is affected. The
+    /// text is not modified, and the cursor moves over the notation as one character. This is enabled by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a\x0cb"]);
+    /// assert!(textarea.render_control_chars());
+    ///
+    /// textarea.set_render_control_chars(false);
+    /// assert!(!textarea.render_control_chars());
+    /// assert_eq!(textarea.lines(), ["a\x0cb"]);
+    /// ```
+    pub fn set_render_control_chars(&mut self, enabled: bool) {
+        self.render_control_chars = enabled;
+    }
+
+    /// Get if control characters are rendered in their notation.
+    pub fn render_control_chars(&self) -> bool {
+        self.render_control_chars
+    }
+
+    /// Set the style of control characters rendered in their notation. The default value is blue foreground.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().fg(Color::Magenta);
+    /// textarea.set_control_char_style(style);
+    /// assert_eq!(textarea.control_char_style(), style);
+    /// ```
+    pub fn set_control_char_style(&mut self, style: Style) {
+        self.control_char_style = style;
+    }
+
+    /// Get the style of control characters rendered in their notation.
+    pub fn control_char_style(&self) -> Style {
+        self.control_char_style
+    }
+
+    /// Set the function to detect links in each line. Detected links are rendered with the style set by
+    /// [`TextArea::set_link_style`], and the link under the cursor can be got by [`TextArea::link_at_cursor`]. Only
+    /// lines rendered on the screen are passed to the function. With the `hyperlinks` feature, links are also emitted
+    /// as OSC 8 hyperlinks which are clickable on terminals supporting them. OSC 8 hyperlinks are not emitted when soft
+    /// wrap is enabled or the text is not aligned to the left. The default detector is [`detect_urls`]
+    /// which detects URLs starting with `http://` or `https://`. Passing `None` disables detecting links.
+    /// ```
+    /// use std::ops::Range;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// // Detect issue numbers like `#123`
+    /// fn detect_issues(line: &str) -> Vec<(Range<usize>, String)> {
+    ///     let mut links = vec![];
+    ///     for (i, _) in line.match_indices('#') {
+    ///         let num = line[i + 1..].split(|c: char| !c.is_ascii_digit()).next().unwrap();
+    ///         if !num.is_empty() {
+    ///             links.push((i..i + 1 + num.len(), format!("https://example.com/issues/{num}")));
+    ///         }
+    ///     }
+    ///     links
+    /// }
+    ///
+    /// let mut textarea = TextArea::from(["Fixed #12"]);
+    /// textarea.set_link_detector(Some(detect_issues));
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.move_cursor(CursorMove::Back);
+    /// assert_eq!(textarea.link_at_cursor().as_deref(), Some("https://example.com/issues/12"));
+    /// ```
+    pub fn set_link_detector(&mut self, detector: Option<LinkDetector>) {
+        self.link_detector = detector;
+        self.render_cache.get_mut().clear();
     }
 
-    fn push_history(&mut self, kind: EditKind, before: Pos, after_offset: usize) {
-        let (row, col) = self.cursor;
-        let after = Pos::new(row, col, after_offset);
-        let edit = Edit::new(kind, before, after);
-        self.history.push(edit);
+    /// Get the function to detect links if set.
+    pub fn link_detector(&self) -> Option<LinkDetector> {
+        self.link_detector
     }
 
-    /// Insert a single character at current cursor position.
+    /// Set the style of links detected by the link detector. It is patched on the line styles and the highlights. The
+    /// default value is underlined.
     /// ```
+    /// use ratatui::style::{Color, Modifier, Style};
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// textarea.insert_char('a');
-    /// assert_eq!(textarea.lines(), ["a"]);
+    /// let style = Style::default().fg(Color::Cyan).add_modifier(Modifier::UNDERLINED);
+    /// textarea.set_link_style(style);
+    /// assert_eq!(textarea.link_style(), style);
     /// ```
-    pub fn insert_char(&mut self, c: char) {
-        if c == '\n' || c == '\r' {
-            self.insert_newline();
-            return;
-        }
+    pub fn set_link_style(&mut self, style: Style) {
+        self.link_style = style;
+    }
 
-        self.delete_selection(false);
-        let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
-        let i = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
-        line.insert(i, c);
-        self.cursor.1 += 1;
-        self.push_history(EditKind::InsertChar(c), Pos::new(row, col, i), i + c.len_utf8());
+    /// Get the style of links.
+    pub fn link_style(&self) -> Style {
+        self.link_style
     }
 
-    /// Insert a string at current cursor position. This method returns if some text was inserted or not in the textarea.
-    /// Both `\n` and `\r\n` are recognized as newlines but `\r` isn't.
+    /// Set the maximum number of lines to search for the matching bracket, including the line of the cursor. This
+    /// bounds the cost of [`CursorMove::MatchingBracket`] and of highlighting the matching bracket on each rendering.
+    /// A bracket whose pair is further than the limit is treated as unmatched. The default value is 10000.
     /// ```
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, CursorMove};
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["{", "", "}"]);
     ///
-    /// textarea.insert_str("hello");
-    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// textarea.set_matching_bracket_limit(2);
+    /// textarea.move_cursor(CursorMove::MatchingBracket);
+    /// assert_eq!(textarea.cursor(), (0, 0));
     ///
-    /// textarea.insert_str(", world\ngoodbye, world");
-    /// assert_eq!(textarea.lines(), ["hello, world", "goodbye, world"]);
+    /// textarea.set_matching_bracket_limit(3);
+    /// textarea.move_cursor(CursorMove::MatchingBracket);
+    /// assert_eq!(textarea.cursor(), (2, 0));
     /// ```
-    pub fn insert_str<S: AsRef<str>>(&mut self, s: S) -> bool {
-        let modified = self.delete_selection(false);
-        let mut lines: Vec<_> = s
-            .as_ref()
-            .split('\n')
-            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
-            .collect();
-        match lines.len() {
-            0 => modified,
-            1 => self.insert_piece(lines.remove(0)),
-            _ => self.insert_chunk(lines),
-        }
+    pub fn set_matching_bracket_limit(&mut self, limit: usize) {
+        self.matching_bracket_limit = limit;
     }
 
-    fn insert_chunk(&mut self, chunk: Vec<String>) -> bool {
-        debug_assert!(chunk.len() > 1, "Chunk size must be > 1: {:?}", chunk);
-
-        let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
-        let i = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
-        let before = Pos::new(row, col, i);
-
-        let (row, col) = (row + chunk.len() - 1, chunk[chunk.len() - 1].chars().count());
-        self.cursor = (row, col);
-
-        let end_offset = chunk.last().unwrap().len();
-
-        let edit = EditKind::InsertChunk(chunk);
-        edit.apply(&mut self.lines, &before, &Pos::new(row, col, end_offset));
-
-        self.push_history(edit, before, end_offset);
-        true
+    /// Get the maximum number of lines to search for the matching bracket. See
+    /// [`TextArea::set_matching_bracket_limit`].
+    pub fn matching_bracket_limit(&self) -> usize {
+        self.matching_bracket_limit
     }
 
-    fn insert_piece(&mut self, s: String) -> bool {
-        if s.is_empty() {
-            return false;
-        }
-
-        let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
-        debug_assert!(
-            !s.contains('\n'),
-            "string given to TextArea::insert_piece must not contain newline: {:?}",
-            line,
-        );
-
-        let i = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
-        line.insert_str(i, &s);
-        let end_offset = i + s.len();
-
-        self.cursor.1 += s.chars().count();
-        self.push_history(EditKind::InsertStr(s), Pos::new(row, col, i), end_offset);
-        true
+    /// Set how many modifications are remembered for undo/redo. Setting 0 disables undo/redo.
+    pub fn set_max_histories(&mut self, max: usize) {
+        let mut history = History::new(max);
+        history.set_grouping(self.history.grouping(), self.history.group_limit());
+        history.set_max_bytes(self.history.max_bytes());
+        history.inherit(&self.history);
+        self.history = history;
     }
 
-    fn delete_range(&mut self, start: Pos, end: Pos, should_yank: bool) {
-        self.cursor = (start.row, start.col);
-
-        if start.row == end.row {
-            let removed = self.lines[start.row]
-                .drain(start.offset..end.offset)
-                .as_str()
-                .to_string();
-            if should_yank {
-                self.yank = removed.clone().into();
-            }
-            self.push_history(EditKind::DeleteStr(removed), end, start.offset);
-            return;
-        }
-
-        let mut deleted = vec![self.lines[start.row].drain(start.offset..).as_str().to_string()];
-        deleted.extend(self.lines.drain(start.row + 1..end.row));
-        if start.row + 1 < self.lines.len() {
-            let mut last_line = self.lines.remove(start.row + 1);
-            self.lines[start.row].push_str(&last_line[end.offset..]);
-            last_line.truncate(end.offset);
-            deleted.push(last_line);
-        }
-
-        if should_yank {
-            self.yank = YankText::Chunk(deleted.clone());
-        }
+    /// Get how many modifications are remembered for undo/redo. The default value is 50.
+    pub fn max_histories(&self) -> usize {
+        self.history.max_items()
+    }
 
-        let edit = if deleted.len() == 1 {
-            EditKind::DeleteStr(deleted.remove(0))
-        } else {
-            EditKind::DeleteChunk(deleted)
-        };
+    /// Set the maximum total size in bytes of modifications remembered for undo/redo, in addition to the number of
+    /// modifications set by [`TextArea::set_max_histories`]. When the size exceeds the limit, the oldest modifications
+    /// are forgotten. A modification larger than the limit cannot be undone, and modifications before it are also
+    /// forgotten. The size of each modification is approximated by the bytes of the inserted or deleted text. There is
+    /// no limit by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_max_history_bytes(1024);
+    ///
+    /// textarea.insert_str("hello");
+    /// textarea.insert_str(&"x".repeat(2000));
+    /// assert_eq!(textarea.history_size_bytes(), 0);
+    /// assert!(!textarea.undo());
+    ///
+    /// textarea.insert_str("world");
+    /// assert!(textarea.history_size_bytes() <= 1024);
+    /// assert!(textarea.undo());
+    /// assert!(!textarea.undo());
+    /// ```
+    pub fn set_max_history_bytes(&mut self, max: usize) {
+        self.history.set_max_bytes(max);
+    }
 
-        self.push_history(edit, end, start.offset);
+    /// Get the maximum total size in bytes of modifications remembered for undo/redo. See
+    /// [`TextArea::set_max_history_bytes`].
+    pub fn max_history_bytes(&self) -> usize {
+        self.history.max_bytes()
     }
 
-    /// Delete a string from the current cursor position. The `chars` parameter means number of characters, not a byte
-    /// length of the string. Newlines at the end of lines are counted in the number. This method returns if some text
-    /// was deleted or not.
+    /// Get the approximate total size in bytes of modifications currently remembered for undo/redo. This is useful to
+    /// show the memory usage of the history in a status bar.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["🐱🐶🐰🐮"]);
-    /// textarea.move_cursor(CursorMove::Forward);
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.history_size_bytes(), 0);
     ///
-    /// textarea.delete_str(2);
-    /// assert_eq!(textarea.lines(), ["🐱🐮"]);
+    /// textarea.insert_str("hello");
+    /// assert!(textarea.history_size_bytes() >= 5);
+    /// ```
+    pub fn history_size_bytes(&self) -> usize {
+        self.history.size_bytes()
+    }
+
+    /// Set how consecutive character insertions are merged into one undo unit. See [`UndoGrouping`] for each option.
+    /// The default value is [`UndoGrouping::ByWord`]. Characters are merged only while they are inserted one after
+    /// another in the same line. Moving the cursor or other kinds of edits end the undo unit. [`TextArea::undo`]
+    /// restores the cursor position at the start of the undo unit.
+    /// ```
+    /// use tui_textarea::{TextArea, UndoGrouping};
     ///
-    /// let mut textarea = TextArea::from(["🐱", "🐶", "🐰", "🐮"]);
-    /// textarea.move_cursor(CursorMove::Down);
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.undo_grouping(), UndoGrouping::ByWord);
     ///
-    /// textarea.delete_str(4); // Deletes 🐶, \n, 🐰, \n
-    /// assert_eq!(textarea.lines(), ["🐱", "🐮"]);
+    /// for c in "hello world".chars() {
+    ///     textarea.insert_char(c);
+    /// }
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// assert_eq!(textarea.cursor(), (0, 5));
+    ///
+    /// textarea.set_undo_grouping(UndoGrouping::None);
+    /// textarea.insert_char('!');
+    /// textarea.insert_char('!');
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello!"]);
     /// ```
-    pub fn delete_str(&mut self, chars: usize) -> bool {
-        if self.delete_selection(false) {
-            return true;
-        }
-        if chars == 0 {
-            return false;
-        }
+    pub fn set_undo_grouping(&mut self, grouping: UndoGrouping) {
+        let limit = self.history.group_limit();
+        self.history.set_grouping(grouping, limit);
+    }
 
-        let (start_row, start_col) = self.cursor;
+    /// Get how consecutive character insertions are merged into one undo unit.
+    pub fn undo_grouping(&self) -> UndoGrouping {
+        self.history.grouping()
+    }
 
-        let mut remaining = chars;
-        let mut find_end = move |line: &str| {
-            let mut col = 0usize;
-            for (i, _) in line.char_indices() {
-                if remaining == 0 {
-                    return Some((i, col));
-                }
-                col += 1;
-                remaining -= 1;
-            }
-            if remaining == 0 {
-                Some((line.len(), col))
-            } else {
-                remaining -= 1;
-                None
-            }
-        };
+    /// Set the maximum number of characters merged into one undo unit by [`TextArea::set_undo_grouping`]. Once an
+    /// undo unit reaches the limit, the next inserted character starts a new one. `None` means no limit, which is the
+    /// default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_undo_group_limit(Some(3));
+    /// for c in "abcde".chars() {
+    ///     textarea.insert_char(c);
+    /// }
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc"]);
+    /// ```
+    pub fn set_undo_group_limit(&mut self, limit: Option<usize>) {
+        let grouping = self.history.grouping();
+        self.history.set_grouping(grouping, limit);
+    }
 
-        let line = &self.lines[start_row];
-        let start_offset = { line.char_indices().nth(start_col).map(|(i, _)| i).unwrap_or(line.len()) };
+    /// Get the maximum number of characters merged into one undo unit.
+    pub fn undo_group_limit(&self) -> Option<usize> {
+        self.history.group_limit()
+    }
 
-        // First line
-        if let Some((offset_delta, col_delta)) = find_end(&line[start_offset..]) {
-            let end_offset = start_offset + offset_delta;
-            let end_col = start_col + col_delta;
-            let removed = self.lines[start_row]
-                .drain(start_offset..end_offset)
-                .as_str()
-                .to_string();
-            self.yank = removed.clone().into();
-            self.push_history(
-                EditKind::DeleteStr(removed),
-                Pos::new(start_row, end_col, end_offset),
-                start_offset,
-            );
-            return true;
+    /// Enable or disable tracking edits of the text. While enabled, every modification of the text is recorded as
+    /// [`TextEdit`] and can be taken by [`TextArea::take_edits`]. This is useful to know what was changed without
+    /// comparing [`TextArea::lines`], for example to notify a language server of changes. Edits made by any methods
+    /// including [`TextArea::input`], [`TextArea::paste`], [`TextArea::undo`] and [`TextArea::redo`] are recorded.
+    /// Disabling the tracking discards edits which were not taken yet. By default, edit tracking is disabled.
+    /// ```
+    /// use tui_textarea::{TextArea, TextEdit};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.set_edit_tracking(true);
+    /// assert!(textarea.edit_tracking());
+    ///
+    /// textarea.move_cursor(tui_textarea::CursorMove::End);
+    /// textarea.insert_str(" world");
+    /// assert_eq!(
+    ///     textarea.take_edits(),
+    ///     [TextEdit {
+    ///         start: (0, 5),
+    ///         end: (0, 5),
+    ///         inserted: " world".to_string(),
+    ///         deleted: String::new(),
+    ///     }],
+    /// );
+    /// ```
+    pub fn set_edit_tracking(&mut self, enabled: bool) {
+        if enabled != self.edits.is_some() {
+            self.edits = enabled.then(Vec::new);
         }
+    }
 
-        let mut r = start_row + 1;
-        let mut offset = 0;
-        let mut col = 0;
-
-        while r < self.lines.len() {
-            let line = &self.lines[r];
-            if let Some((o, c)) = find_end(line) {
-                offset = o;
-                col = c;
-                break;
-            }
-            r += 1;
-        }
+    /// Get if edit tracking is enabled. See [`TextArea::set_edit_tracking`].
+    pub fn edit_tracking(&self) -> bool {
+        self.edits.is_some()
+    }
 
-        let start = Pos::new(start_row, start_col, start_offset);
-        let end = Pos::new(r, col, offset);
-        self.delete_range(start, end, true);
-        true
+    /// Take the edits of the text recorded since the last call. Edits are returned in the order they were applied.
+    /// Applying them to the text in order reproduces the current text. An empty vector is returned when edit tracking
+    /// is disabled. See [`TextArea::set_edit_tracking`].
+    /// ```
+    /// use tui_textarea::{TextArea, TextEdit};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// textarea.set_edit_tracking(true);
+    ///
+    /// textarea.delete_line_by_end(); // Delete "abc"
+    /// textarea.delete_newline();     // Do nothing at the beginning of the text
+    /// textarea.delete_next_char();   // Delete the newline
+    /// textarea.undo();
+    ///
+    /// let edits = textarea.take_edits();
+    /// assert_eq!(edits.len(), 3);
+    /// assert_eq!(edits[0].deleted, "abc");
+    /// assert_eq!((edits[1].start, edits[1].end), ((0, 0), (1, 0)));
+    /// assert_eq!(edits[1].deleted, "\n");
+    /// assert_eq!(edits[2].inserted, "\n");
+    /// assert!(textarea.take_edits().is_empty());
+    /// ```
+    pub fn take_edits(&mut self) -> Vec<TextEdit> {
+        self.edits.as_mut().map(std::mem::take).unwrap_or_default()
     }
 
-    fn delete_piece(&mut self, col: usize, chars: usize) -> bool {
-        if chars == 0 {
-            return false;
-        }
+    /// Set the style of line at cursor. By default, the cursor line is styled with underline. To stop styling the
+    /// cursor line, set the default style.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().fg(Color::Red);
+    /// textarea.set_cursor_line_style(style);
+    /// assert_eq!(textarea.cursor_line_style(), style);
+    ///
+    /// // Disable cursor line style
+    /// textarea.set_cursor_line_style(Style::default());
+    /// ```
+    pub fn set_cursor_line_style(&mut self, style: Style) {
+        self.cursor_line_style = style;
+    }
 
-        #[inline]
-        fn bytes_and_chars(claimed: usize, s: &str) -> (usize, usize) {
-            // Note: `claimed` may be larger than characters in `s` (e.g. usize::MAX)
-            let mut last_col = 0;
-            for (col, (bytes, _)) in s.char_indices().enumerate() {
-                if col == claimed {
-                    return (bytes, claimed);
-                }
-                last_col = col;
-            }
-            (s.len(), last_col + 1)
-        }
+    /// Get the style of cursor line. By default it is styled with underline.
+    pub fn cursor_line_style(&self) -> Style {
+        self.cursor_line_style
+    }
 
-        let (row, _) = self.cursor;
-        let line = &mut self.lines[row];
-        if let Some((i, _)) = line.char_indices().nth(col) {
-            let (bytes, chars) = bytes_and_chars(chars, &line[i..]);
-            let removed = line.drain(i..i + bytes).as_str().to_string();
+    /// Set the style of line at cursor while text is selected. `None` stops styling the cursor line during selection so
+    /// that it does not mix with the selection style. By default, the style set by [`TextArea::set_cursor_line_style`]
+    /// is used.
+    ///
+    /// Highlights are layered in this order from bottom to top: the cursor line, the selection, search matches, the
+    /// current search match, the matching bracket, and the cursor. Where they overlap, only the style of the topmost
+    /// one is rendered.
+    /// ```
+    /// use ratatui::style::{Color, Modifier, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let underline = Style::default().add_modifier(Modifier::UNDERLINED);
+    /// assert_eq!(textarea.cursor_line_style_in_selection(), Some(underline));
+    ///
+    /// textarea.set_cursor_line_style_in_selection(None);
+    /// assert_eq!(textarea.cursor_line_style_in_selection(), None);
+    ///
+    /// let style = Style::default().bg(Color::DarkGray);
+    /// textarea.set_cursor_line_style_in_selection(Some(style));
+    /// assert_eq!(textarea.cursor_line_style_in_selection(), Some(style));
+    /// ```
+    pub fn set_cursor_line_style_in_selection(&mut self, style: Option<Style>) {
+        self.cursor_line_style_in_selection = Some(style);
+    }
 
-            self.cursor = (row, col);
-            self.push_history(
-                EditKind::DeleteStr(removed.clone()),
-                Pos::new(row, col + chars, i + bytes),
-                i,
-            );
-            self.yank = removed.into();
-            true
+    /// Get the style of line at cursor while text is selected. `None` means the cursor line is not styled during
+    /// selection. See [`TextArea::set_cursor_line_style_in_selection`].
+    pub fn cursor_line_style_in_selection(&self) -> Option<Style> {
+        self.cursor_line_style_in_selection
+            .unwrap_or(Some(self.cursor_line_style))
+    }
+
+    // Style of the cursor line on rendering
+    fn shown_cursor_line_style(&self) -> Style {
+        if self.selection_positions().is_some() {
+            self.cursor_line_style_in_selection().unwrap_or_default()
         } else {
-            false
+            self.cursor_line_style
         }
     }
 
-    /// Insert a tab at current cursor position. Note that this method does nothing when the tab length is 0. This
-    /// method returns if a tab string was inserted or not in the textarea.
+    /// Set the textarea read-only. While read-only, [`TextArea::input`] ignores inputs which modify the text such as
+    /// inserting and deleting characters, pasting, undo, and redo. Cut is handled as copy. Moving the cursor, selecting
+    /// and copying text, and scrolling still work. Since no input modifies the text, nothing is recorded in the undo
+    /// history while read-only. Methods to edit the text like [`TextArea::insert_str`] still modify the text and their
+    /// edits are recorded as usual so that the older edits can still be undone. By default, the textarea is not
+    /// read-only.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::{Input, Key, TextArea};
     ///
-    /// let mut textarea = TextArea::from(["hi"]);
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.set_read_only(true);
+    /// assert!(textarea.is_read_only());
     ///
-    /// textarea.move_cursor(CursorMove::End); // Move to the end of line
+    /// let modified = textarea.input(Input { key: Key::Char('a'), ..Default::default() });
+    /// assert!(!modified);
+    /// assert_eq!(textarea.lines(), ["hello"]);
     ///
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["hi  "]);
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["hi      "]);
+    /// // The cursor can still be moved
+    /// textarea.input(Input { key: Key::Right, ..Default::default() });
+    /// assert_eq!(textarea.cursor(), (0, 1));
     /// ```
-    pub fn insert_tab(&mut self) -> bool {
-        let modified = self.delete_selection(false);
-        if self.tab_len == 0 {
-            return modified;
-        }
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
 
-        if self.hard_tab_indent {
-            self.insert_char('\t');
-            return true;
+    /// Get if the textarea is read-only. See [`TextArea::set_read_only`].
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Enable or disable single-line mode, which is useful for an input field like `<input>` in HTML. In single-line
+    /// mode:
+    ///
+    /// - [`TextArea::insert_newline`] does nothing
+    /// - Newlines in text inserted by [`TextArea::insert_str`], [`TextArea::paste`] and [`TextArea::paste_str`] are
+    ///   replaced with the string set by [`TextArea::set_newline_replacement`]
+    /// - [`CursorMove::Up`] and [`CursorMove::Down`] do not move the cursor even if the line is soft-wrapped
+    /// - [`TextArea::handle_input`] returns [`InputResult::Submit`] on Enter key
+    ///
+    /// When the textarea has multiple lines on enabling single-line mode, they are joined into one line with the
+    /// newline replacement, the cursor is moved to the end of the line, and the undo history is cleared. By default,
+    /// single-line mode is disabled.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_single_line(true);
+    /// assert!(textarea.is_single_line());
+    ///
+    /// textarea.insert_str("hello\nworld");
+    /// assert!(!textarea.insert_newline());
+    /// assert_eq!(textarea.lines(), ["hello world"]);
+    /// ```
+    pub fn set_single_line(&mut self, single_line: bool) {
+        self.single_line = single_line;
+        if single_line && self.lines.len() > 1 {
+            let line = self.lines.join(&self.newline_replacement);
+            self.cursor = (0, line.chars().count());
+            let last = self.lines.len() - 1;
+            let edit = TextEdit {
+                start: (0, 0),
+                end: (last, self.lines.char_count(last)),
+                inserted: line.clone(),
+                deleted: self.lines.join("\n"),
+            };
+            self.on_text_edit(edit);
+            self.lines.set(vec![line]);
+            self.line_offsets.get_mut().clear();
+            self.yank_flashed.take();
+            self.cancel_selection();
+            self.history.clear();
         }
+    }
 
-        let (row, col) = self.cursor;
-        let width: usize = self.lines[row].chars().take(col).map(|c| c.width().unwrap_or(0)).sum();
-        let len = self.tab_len - (width % self.tab_len as usize) as u8;
-        self.insert_piece(spaces(len).to_string())
+    /// Get if the textarea is in single-line mode. See [`TextArea::set_single_line`].
+    pub fn is_single_line(&self) -> bool {
+        self.single_line
     }
 
-    /// Remove a tab at the start of the line of the current cursor position.
-    /// Note that this method does nothing when the tab length is 0.
-    /// This method returns if a tab string was removed or not in the textarea.
+    /// Set the string replacing newlines in text inserted in single-line mode. The default value is one space. An
+    /// empty string removes newlines.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["    hi"]);
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_single_line(true);
+    /// textarea.set_newline_replacement("");
+    /// assert_eq!(textarea.newline_replacement(), "");
     ///
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["        hi"]);
-    /// textarea.delete_tab_from_head();
-    /// assert_eq!(textarea.lines(), ["    hi"]);
+    /// textarea.insert_str("abc\ndef");
+    /// assert_eq!(textarea.lines(), ["abcdef"]);
+    /// ```
+    pub fn set_newline_replacement(&mut self, replacement: impl Into<String>) {
+        self.newline_replacement = replacement.into();
+    }
+
+    /// Get the string replacing newlines in single-line mode. See [`TextArea::set_newline_replacement`].
+    pub fn newline_replacement(&self) -> &str {
+        &self.newline_replacement
+    }
+
+    /// Set a function to validate the text. The function receives the lines of the text and returns an error message
+    /// when the text is invalid. Validation runs when the validator is set and after every [`TextArea::input`] call which
+    /// modified the text. Methods to edit the text like [`TextArea::insert_str`] do not run validation, so call
+    /// [`TextArea::validate`] after them if needed. While the text is invalid, the style set by
+    /// [`TextArea::set_invalid_style`] is applied on rendering and the error message is available from
+    /// [`TextArea::validation_error`].
+    /// ```
+    /// use tui_textarea::{Input, Key, TextArea};
     ///
-    /// let mut textarea = TextArea::from(["\thi"]);
-    /// textarea.set_hard_tab_indent(true);
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_validator(|lines| {
+    ///     if lines[0].parse::<u32>().is_ok() {
+    ///         Ok(())
+    ///     } else {
+    ///         Err("Not a number".to_string())
+    ///     }
+    /// });
+    /// assert_eq!(textarea.validation_error(), Some("Not a number"));
+    ///
+    /// textarea.input(Input { key: Key::Char('1'), ..Default::default() });
+    /// assert_eq!(textarea.validation_error(), None);
+    /// textarea.input(Input { key: Key::Char('x'), ..Default::default() });
+    /// assert_eq!(textarea.validation_error(), Some("Not a number"));
+    /// ```
+    pub fn set_validator<F>(&mut self, validator: F)
+    where
+        F: Fn(&[String]) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Validator(Arc::new(validator)));
+        self.validate();
+    }
+
+    /// Remove the function set by [`TextArea::set_validator`]. The validation error is also cleared so the textarea is
+    /// rendered with its original style.
+    /// ```
+    /// use tui_textarea::TextArea;
     ///
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["\t\thi"]);
-    /// textarea.delete_tab_from_head();
-    /// assert_eq!(textarea.lines(), ["\thi"]);
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_validator(|_| Err("Invalid".to_string()));
+    /// assert!(textarea.validation_error().is_some());
+    ///
+    /// textarea.clear_validator();
+    /// assert_eq!(textarea.validation_error(), None);
     /// ```
-    pub fn delete_tab_from_head(&mut self) -> bool {
-        if self.tab_len == 0 {
-            return false;
-        }
+    pub fn clear_validator(&mut self) {
+        self.validator = None;
+        self.validation_error = None;
+    }
 
-        let (row, col) = self.cursor;
-        let (tab, tab_symbol) = if self.hard_tab_indent {
-            ("\t", '\t')
-        } else {
-            (spaces(self.tab_len), ' ')
+    /// Validate the current text with the function set by [`TextArea::set_validator`]. This method returns if the text
+    /// is valid or not. When no validator is set, the text is always valid.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_validator(|lines| {
+    ///     if lines.iter().all(|l| l.is_empty()) {
+    ///         Err("Empty".to_string())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// });
+    ///
+    /// textarea.insert_str("hello");
+    /// // Editing methods don't run validation
+    /// assert_eq!(textarea.validation_error(), Some("Empty"));
+    /// assert!(textarea.validate());
+    /// assert_eq!(textarea.validation_error(), None);
+    /// ```
+    pub fn validate(&mut self) -> bool {
+        self.validation_error = match &self.validator {
+            Some(Validator(f)) => f(self.lines.as_slice()).err(),
+            None => None,
         };
-        let tab_len = tab.chars().count();
+        self.validation_error.is_none()
+    }
 
-        if let Some(char_idx) = self.lines[row]
-            .char_indices()
-            .find_map(|(char_idx, char)| (char != tab_symbol).then_some(char_idx))
-        {
-            if char_idx == 0 {
-                return false;
-            }
+    /// Get the error message returned by the function set by [`TextArea::set_validator`] on the last validation.
+    /// `None` is returned when the text was valid.
+    pub fn validation_error(&self) -> Option<&str> {
+        self.validation_error.as_deref()
+    }
 
-            let mut chars_to_remove = char_idx % tab_len;
-            if chars_to_remove == 0 {
-                chars_to_remove = tab_len;
-            }
+    /// Set the style applied while the text is invalid. It is put on both the text and the border of the block. The
+    /// default style is red foreground color.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().bg(Color::Red);
+    /// textarea.set_invalid_style(style);
+    /// assert_eq!(textarea.invalid_style(), style);
+    /// ```
+    pub fn set_invalid_style(&mut self, style: Style) {
+        self.invalid_style = style;
+    }
 
-            let col_byte_offset = self.lines[row]
-                .char_indices()
-                .nth(col)
-                .map(|(i, _)| i)
-                .unwrap_or_else(|| self.lines[row].len());
+    /// Get the style applied while the text is invalid. See [`TextArea::set_invalid_style`].
+    pub fn invalid_style(&self) -> Style {
+        self.invalid_style
+    }
 
-            self.push_change(Edit::new(
-                EditKind::DeleteStr(tab.chars().take(chars_to_remove).collect()),
-                Pos::new(row, col, col_byte_offset),
-                Pos::new(row, col.saturating_sub(chars_to_remove), 0),
-            ));
+    /// Set the maximum number of characters in the textarea. Characters are counted as Unicode scalar values and a
+    /// newline is counted as one character. Once the number reaches the limit, [`TextArea::insert_char`] and
+    /// [`TextArea::insert_newline`] insert nothing, and text inserted by [`TextArea::insert_str`] and
+    /// [`TextArea::paste`] is truncated to fit. Text which already exceeds the limit is not removed. `None` means no
+    /// limit, which is the default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_max_chars(Some(5));
+    /// assert_eq!(textarea.max_chars(), Some(5));
+    ///
+    /// textarea.insert_str("あいうえおか");
+    /// assert_eq!(textarea.lines(), ["あいうえお"]);
+    /// assert!(!textarea.insert_char('x'));
+    /// assert_eq!(textarea.char_count(), 5);
+    /// ```
+    pub fn set_max_chars(&mut self, max: Option<usize>) {
+        self.max_chars = max;
+    }
 
-            return true;
+    /// Get the maximum number of characters set by [`TextArea::set_max_chars`].
+    pub fn max_chars(&self) -> Option<usize> {
+        self.max_chars
+    }
+
+    /// Get the number of characters in the textarea. Characters are counted as Unicode scalar values and each newline
+    /// between lines is counted as one character.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["abc", "あい"]);
+    /// assert_eq!(textarea.char_count(), 6);
+    /// ```
+    pub fn char_count(&self) -> usize {
+        (0..self.lines.len()).map(|r| self.lines.char_count(r)).sum::<usize>() + self.lines.len() - 1
+    }
+
+    fn remaining_chars(&self) -> usize {
+        match self.max_chars {
+            Some(max) => max.saturating_sub(self.char_count()),
+            None => usize::MAX,
         }
+    }
 
-        false
+    /// Hide the cursor and the cursor line style on rendering. The cursor position is still tracked and can be moved.
+    /// This is useful for a read-only textarea used as a viewer. By default, the cursor is shown.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_cursor_hidden(true);
+    /// assert!(textarea.is_cursor_hidden());
+    /// ```
+    pub fn set_cursor_hidden(&mut self, hidden: bool) {
+        self.cursor_hidden = hidden;
     }
 
-    /// Remove a tab at the start of the line of the current cursor position.
-    /// Note that this method does nothing when the tab length is 0.
-    /// This method returns if a tab string was removed or not in the textarea.
+    /// Get if the cursor is hidden. See [`TextArea::set_cursor_hidden`].
+    pub fn is_cursor_hidden(&self) -> bool {
+        self.cursor_hidden
+    }
+
+    // Style of the cursor on rendering. `None` means the cursor is hidden
+    pub(crate) fn shown_cursor_style(&self) -> Option<Style> {
+        if self.cursor_hidden {
+            None
+        } else if self.focused {
+            match self.overwrite_cursor_style {
+                Some(style) if self.overwrite_mode => Some(style),
+                _ => Some(self.cursor_style),
+            }
+        } else {
+            self.cursor_style_unfocused
+        }
+    }
+
+    /// Set if the textarea is focused. When several textareas are on one screen, this switches how the textarea is
+    /// rendered: an unfocused textarea is rendered with the block set by [`TextArea::set_block_unfocused`] and the
+    /// cursor style set by [`TextArea::set_cursor_style_unfocused`]. Inputs can also be ignored while unfocused with
+    /// [`TextArea::set_ignore_input_when_unfocused`]. By default, the textarea is focused.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::widgets::{Block, Borders};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["    hi"]);
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_block(Block::default().borders(Borders::ALL).title("Focused"));
+    /// textarea.set_block_unfocused(Block::default().borders(Borders::ALL).title("Unfocused"));
     ///
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["        hi"]);
-    /// textarea.delete_tab_from_head();
-    /// assert_eq!(textarea.lines(), ["    hi"]);
+    /// textarea.set_focus(false);
+    /// assert!(!textarea.is_focused());
+    /// ```
+    pub fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Get if the textarea is focused. See [`TextArea::set_focus`].
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Set the block rendered while the textarea is unfocused instead of the block set by [`TextArea::set_block`].
+    /// When no block is set for the unfocused state, the same block is rendered in both states.
+    /// ```
+    /// use ratatui::widgets::{Block, Borders};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["\thi"]);
-    /// textarea.set_hard_tab_indent(true);
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_block_unfocused(Block::default().borders(Borders::ALL).title("Press Tab to focus"));
+    /// assert!(textarea.block_unfocused().is_some());
     ///
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["\t\thi"]);
-    /// textarea.delete_tab_from_head();
-    /// assert_eq!(textarea.lines(), ["\thi"]);
+    /// textarea.remove_block_unfocused();
+    /// assert!(textarea.block_unfocused().is_none());
     /// ```
-    pub fn duplicate_line(&mut self) {
-        self.cancel_selection();
+    pub fn set_block_unfocused(&mut self, block: Block<'a>) {
+        self.block_unfocused = Some(block);
+    }
 
-        let (row, col) = self.cursor;
+    /// Remove the block set by [`TextArea::set_block_unfocused`].
+    pub fn remove_block_unfocused(&mut self) {
+        self.block_unfocused = None;
+    }
+
+    /// Get the block rendered while the textarea is unfocused if set.
+    pub fn block_unfocused<'s>(&'s self) -> Option<&'s Block<'a>> {
+        self.block_unfocused.as_ref()
+    }
 
-        self.push_change(Edit::new(
-            EditKind::InsertChunk(vec![self.lines[row].clone(), String::new()]),
-            Pos::new(row, col, 0),
-            Pos::new(row + 1, col, 0),
-        ));
+    // Block rendered in the current focus state
+    pub(crate) fn shown_block<'s>(&'s self) -> Option<&'s Block<'a>> {
+        if self.focused {
+            self.block()
+        } else {
+            self.block_unfocused.as_ref().or(self.block.as_ref())
+        }
     }
 
-    /// Insert a newline at current cursor position.
+    /// Set the style of the cursor rendered while the textarea is unfocused. `None` hides the cursor and the cursor
+    /// line style while unfocused, which is the default.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["hi"]);
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.cursor_style_unfocused(), None);
     ///
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// textarea.insert_newline();
-    /// assert_eq!(textarea.lines(), ["h", "i"]);
+    /// let style = Style::default().bg(Color::DarkGray);
+    /// textarea.set_cursor_style_unfocused(Some(style));
+    /// assert_eq!(textarea.cursor_style_unfocused(), Some(style));
     /// ```
-    pub fn insert_newline(&mut self) {
-        self.delete_selection(false);
-
-        let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
-        let offset = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
-        let next_line = line[offset..].to_string();
-        line.truncate(offset);
+    pub fn set_cursor_style_unfocused(&mut self, style: Option<Style>) {
+        self.cursor_style_unfocused = style;
+    }
 
-        self.lines.insert(row + 1, next_line);
-        self.cursor = (row + 1, 0);
-        self.push_history(EditKind::InsertNewline, Pos::new(row, col, offset), 0);
+    /// Get the style of the cursor set by [`TextArea::set_cursor_style_unfocused`].
+    pub fn cursor_style_unfocused(&self) -> Option<Style> {
+        self.cursor_style_unfocused
     }
 
-    /// Delete a newline from **head** of current cursor line. This method returns if a newline was deleted or not in
-    /// the textarea. When some text is selected, it is deleted instead.
+    /// Set if inputs are ignored while the textarea is unfocused. When enabled, [`TextArea::input`],
+    /// [`TextArea::input_without_shortcuts`], [`TextArea::single_line_input`], and [`TextArea::handle_input`] do nothing
+    /// while unfocused so that the same input can be passed to all textareas on the screen. Methods to edit the text
+    /// directly still work. By default, inputs are handled regardless of the focus.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::{Input, Key, TextArea};
     ///
-    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// let mut textareas = [TextArea::default(), TextArea::default()];
+    /// for textarea in &mut textareas {
+    ///     textarea.set_ignore_input_when_unfocused(true);
+    /// }
+    /// textareas[1].set_focus(false);
     ///
-    /// textarea.move_cursor(CursorMove::Down);
-    /// textarea.delete_newline();
-    /// assert_eq!(textarea.lines(), ["helloworld"]);
+    /// let input = Input { key: Key::Char('a'), ..Default::default() };
+    /// for textarea in &mut textareas {
+    ///     textarea.input(input.clone());
+    /// }
+    /// assert_eq!(textareas[0].lines(), ["a"]);
+    /// assert_eq!(textareas[1].lines(), [""]);
     /// ```
-    pub fn delete_newline(&mut self) -> bool {
-        if self.delete_selection(false) {
-            return true;
-        }
+    pub fn set_ignore_input_when_unfocused(&mut self, ignore: bool) {
+        self.ignore_input_unfocused = ignore;
+    }
 
-        let (row, _) = self.cursor;
-        if row == 0 {
-            return false;
-        }
+    /// Get if inputs are ignored while unfocused. See [`TextArea::set_ignore_input_when_unfocused`].
+    pub fn ignore_input_when_unfocused(&self) -> bool {
+        self.ignore_input_unfocused
+    }
+
+    /// Set the style of line number. By setting the style with this method, line numbers are drawn in textarea, meant
+    /// that line numbers are disabled by default. If you want to show line numbers but don't want to style them, set
+    /// the default style.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Show line numbers in dark gray background
+    /// let style = Style::default().bg(Color::DarkGray);
+    /// textarea.set_line_number_style(style);
+    /// assert_eq!(textarea.line_number_style(), Some(style));
+    /// ```
+    pub fn set_line_number_style(&mut self, style: Style) {
+        self.line_number_style = Some(style);
+    }
 
-        let line = self.lines.remove(row);
-        let prev_line = &mut self.lines[row - 1];
-        let prev_line_end = prev_line.len();
+    /// Remove the style of line number which was set by [`TextArea::set_line_number_style`]. After calling this
+    /// method, Line numbers will no longer be shown.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_line_number_style(Style::default().bg(Color::DarkGray));
+    /// textarea.remove_line_number();
+    /// assert_eq!(textarea.line_number_style(), None);
+    /// ```
+    pub fn remove_line_number(&mut self) {
+        self.line_number_style = None;
+    }
 
-        self.cursor = (row - 1, prev_line.chars().count());
-        prev_line.push_str(&line);
-        self.push_history(EditKind::DeleteNewline, Pos::new(row, 0, 0), prev_line_end);
-        true
+    /// Get the style of line number if set.
+    pub fn line_number_style(&self) -> Option<Style> {
+        self.line_number_style
     }
 
-    /// Delete one character before cursor. When the cursor is at head of line, the newline before the cursor will be
-    /// removed. This method returns if some text was deleted or not in the textarea. When some text is selected, it is
-    /// deleted instead.
+    /// Enable or disable relative line numbers. When enabled, the absolute line number is shown on the cursor line and
+    /// the distance from the cursor line is shown on other lines, like `number` and `relativenumber` options of Vim.
+    /// Line numbers are shown only when their style is set by [`TextArea::set_line_number_style`]. By default,
+    /// relative line numbers are disabled.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::style::Style;
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// textarea.delete_char();
-    /// assert_eq!(textarea.lines(), ["bc"]);
+    /// textarea.set_line_number_style(Style::default());
+    /// textarea.set_relative_line_numbers(true);
+    /// assert!(textarea.relative_line_numbers());
     /// ```
-    pub fn delete_char(&mut self) -> bool {
-        if self.delete_selection(false) {
-            return true;
-        }
-
-        let (row, col) = self.cursor;
-        if col == 0 {
-            return self.delete_newline();
-        }
+    pub fn set_relative_line_numbers(&mut self, enabled: bool) {
+        self.relative_line_numbers = enabled;
+    }
 
-        let line = &mut self.lines[row];
-        if let Some((offset, c)) = line.char_indices().nth(col - 1) {
-            line.remove(offset);
-            self.cursor.1 -= 1;
-            self.push_history(
-                EditKind::DeleteChar(c),
-                Pos::new(row, col, offset + c.len_utf8()),
-                offset,
-            );
-            true
-        } else {
-            false
-        }
+    /// Get if relative line numbers are enabled. See [`TextArea::set_relative_line_numbers`].
+    pub fn relative_line_numbers(&self) -> bool {
+        self.relative_line_numbers
     }
 
-    /// Delete one character next to cursor. When the cursor is at end of line, the newline next to the cursor will be
-    /// removed. This method returns if a character was deleted or not in the textarea.
+    /// Set the minimum number of digits reserved for line numbers. The width of line numbers is decided by the number
+    /// of lines in the textarea, so the text shifts horizontally when the number of digits changes, e.g. when the 10th
+    /// line is added. Reserving enough digits avoids the shift while the text grows. The default value is 0.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::style::Style;
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// textarea.delete_next_char();
-    /// assert_eq!(textarea.lines(), ["ac"]);
+    /// textarea.set_line_number_style(Style::default());
+    /// textarea.set_line_number_min_width(4);
+    /// assert_eq!(textarea.line_number_min_width(), 4);
     /// ```
-    pub fn delete_next_char(&mut self) -> bool {
-        if self.delete_selection(false) {
-            return true;
-        }
-
-        let before = self.cursor;
-        self.move_cursor_with_shift(CursorMove::Forward, false);
-        if before == self.cursor {
-            return false; // Cursor didn't move, meant no character at next of cursor.
-        }
-
-        self.delete_char()
+    pub fn set_line_number_min_width(&mut self, width: u8) {
+        self.line_number_min_width = width;
     }
 
-    pub fn delete_line(&mut self, should_yank: bool) -> bool {
-        if self.delete_selection(should_yank) {
-            return true;
-        }
-
-        let (row, col) = self.cursor;
+    /// Get the minimum number of digits reserved for line numbers. See [`TextArea::set_line_number_min_width`].
+    pub fn line_number_min_width(&self) -> u8 {
+        self.line_number_min_width
+    }
 
-        let edit = if self.lines.len() == 1 {
-            // if there is only one line and it is empty there is nothin to do
-            if self.lines[0].is_empty() {
-                return false;
+    /// Set the sign at the line of `row`, or remove it with `None`. Signs are rendered in the sign column at the left of
+    /// line numbers. The sign column is two cells wide and shown while any sign is set or it is enabled by
+    /// [`TextArea::set_sign_column`]. Signs follow their lines when lines are inserted or deleted above them, and a sign
+    /// is removed with its line.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::{Sign, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b"]);
+    ///
+    /// textarea.set_sign(1, Some(Sign::new("●", Style::default().fg(Color::Red))));
+    /// assert_eq!(textarea.sign(1).unwrap().symbol, "●");
+    ///
+    /// // Insert a line above the sign
+    /// textarea.insert_newline();
+    /// assert!(textarea.sign(1).is_none());
+    /// assert_eq!(textarea.sign(2).unwrap().symbol, "●");
+    ///
+    /// textarea.set_sign(2, None);
+    /// assert!(textarea.signs().is_empty());
+    /// ```
+    pub fn set_sign(&mut self, row: usize, sign: Option<Sign>) {
+        match sign {
+            Some(sign) => {
+                self.signs.insert(row, sign);
             }
-
-            let line_to_remove = &self.lines[row];
-
-            if should_yank {
-                self.yank = line_to_remove.to_owned().into();
+            None => {
+                self.signs.remove(&row);
             }
+        }
+    }
 
-            Edit::new(
-                EditKind::DeleteStr(line_to_remove.to_owned()),
-                Pos::new(row, col, col),
-                Pos::new(0, 0, 0),
-            )
-        } else if row == self.lines.len().saturating_sub(1) {
-            let line_to_remove = &self.lines[row];
-            let new_row = row - 1;
-            let new_col = min(col, self.lines[new_row].len().saturating_sub(1));
-
-            if should_yank {
-                self.yank = line_to_remove.to_owned().into();
-            }
+    /// Replace all signs with the pairs of row and sign. See [`TextArea::set_sign`].
+    /// ```
+    /// use ratatui::style::Style;
+    /// use tui_textarea::{Sign, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    ///
+    /// textarea.set_signs([(0, Sign::new("+", Style::default())), (2, Sign::new("~", Style::default()))]);
+    /// assert_eq!(textarea.signs().keys().copied().collect::<Vec<_>>(), [0, 2]);
+    /// ```
+    pub fn set_signs(&mut self, signs: impl IntoIterator<Item = (usize, Sign)>) {
+        self.signs = signs.into_iter().collect();
+    }
 
-            Edit::new(
-                EditKind::DeleteLine(row, line_to_remove.to_owned()),
-                Pos::new(row, col, 0),
-                Pos::new(new_row, new_col, 0),
-            )
-        } else {
-            let line_to_remove = &self.lines[row];
-            let new_col = min(col, self.lines[row + 1].len().saturating_sub(1));
+    /// Get the sign at the line of `row`.
+    pub fn sign(&self, row: usize) -> Option<&Sign> {
+        self.signs.get(&row)
+    }
 
-            if should_yank {
-                self.yank = line_to_remove.to_owned().into();
-            }
+    /// Get all signs keyed by their rows.
+    pub fn signs(&self) -> &BTreeMap<usize, Sign> {
+        &self.signs
+    }
 
-            Edit::new(
-                EditKind::DeleteLine(row, line_to_remove.to_owned()),
-                Pos::new(row, col, 0),
-                Pos::new(row, new_col, 0),
-            )
-        };
+    /// Remove all signs. The sign column is hidden unless it is enabled by [`TextArea::set_sign_column`].
+    pub fn clear_signs(&mut self) {
+        self.signs.clear();
+    }
 
-        self.push_change(edit);
+    /// Set if the sign column is always shown even when no sign is set. This avoids shifting the text horizontally when
+    /// the first sign is set or the last sign is removed. The default value is `false`.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert!(!textarea.sign_column());
+    /// textarea.set_sign_column(true);
+    /// assert!(textarea.sign_column());
+    /// ```
+    pub fn set_sign_column(&mut self, enabled: bool) {
+        self.sign_column = enabled;
+    }
 
-        true
+    /// Get if the sign column is always shown. See [`TextArea::set_sign_column`].
+    pub fn sign_column(&self) -> bool {
+        self.sign_column
     }
 
-    /// Delete string from cursor to end of the line. When the cursor is at end of line, the newline next to the cursor
-    /// is removed. This method returns if some text was deleted or not in the textarea.
+    /// Fold the lines from `start_row` to `end_row` (both inclusive) into one display row. The first line is rendered
+    /// with a suffix showing the number of the folded lines and the other lines are hidden. Folds overlapping the new
+    /// fold are removed, and the cursor on a hidden line moves to the first line. This method returns `false` and does
+    /// nothing when the range has less than two lines or is out of the text.
+    ///
+    /// [`CursorMove::Up`] and [`CursorMove::Down`] skip the hidden lines. Other cursor moves into the hidden lines such
+    /// as [`TextArea::goto_line`], text search, undo, and edits open the fold by removing it. Folds follow their lines
+    /// when lines are inserted or deleted above them, and a fold is removed when its first or last line is deleted.
+    /// Folds are not applied while lines are soft-wrapped by [`TextArea::set_wrap`].
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::{CursorMove, TextArea};
     ///
-    /// let mut textarea = TextArea::from(["abcde"]);
+    /// let mut textarea = TextArea::from(["fn f() {", "    a();", "    b();", "}", ""]);
     ///
-    /// // Move to 'c'
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// textarea.move_cursor(CursorMove::Forward);
+    /// assert!(textarea.fold(0, 3));
+    /// assert_eq!(textarea.folds()[0].lines(), 4);
     ///
-    /// textarea.delete_line_by_end();
-    /// assert_eq!(textarea.lines(), ["ab"]);
+    /// // The cursor skips the folded lines
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.cursor(), (4, 0));
+    ///
+    /// // Jumping into the fold opens it
+    /// assert!(textarea.goto_line(2, None, false));
+    /// assert!(textarea.folds().is_empty());
     /// ```
-    pub fn delete_line_by_end(&mut self) -> bool {
-        if self.delete_selection(false) {
-            return true;
+    pub fn fold(&mut self, start_row: usize, end_row: usize) -> bool {
+        if start_row >= end_row || end_row >= self.lines.len() {
+            return false;
         }
-        if self.delete_piece(self.cursor.1, usize::MAX) {
-            return true;
+        let fold = Fold { start: start_row, end: end_row };
+        self.folds.retain(|f| f.end < start_row || end_row < f.start);
+        let i = self.folds.partition_point(|f| f.start < start_row);
+        self.folds.insert(i, fold);
+        if fold.hides(self.cursor.0) {
+            self.cursor = (start_row, min(self.cursor.1, self.lines.char_count(start_row)));
         }
-        self.delete_next_char() // At the end of the line. Try to delete next line
+        true
     }
 
-    /// Delete string from cursor to head of the line. When the cursor is at head of line, the newline before the cursor
-    /// will be removed. This method returns if some text was deleted or not in the textarea.
+    /// Remove the fold containing the line at `row`. This method returns `false` when no fold contains the line.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abcde"]);
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
     ///
-    /// // Move to 'c'
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.fold(0, 1);
+    /// assert!(!textarea.unfold(2));
+    /// assert!(textarea.unfold(1));
+    /// assert!(textarea.folds().is_empty());
+    /// ```
+    pub fn unfold(&mut self, row: usize) -> bool {
+        let len = self.folds.len();
+        self.folds.retain(|f| !f.contains(row));
+        self.folds.len() != len
+    }
+
+    /// Open the fold at the cursor line, or fold the lines of the selection when no fold is at the cursor line. This
+    /// method returns `false` and does nothing when neither is possible. See [`TextArea::fold`].
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
     ///
-    /// textarea.delete_line_by_head();
-    /// assert_eq!(textarea.lines(), ["cde"]);
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert!(textarea.toggle_fold_at_cursor());
+    /// assert_eq!(textarea.folds()[0].end, 1);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// assert!(textarea.toggle_fold_at_cursor());
+    /// assert!(textarea.folds().is_empty());
     /// ```
-    pub fn delete_line_by_head(&mut self) -> bool {
-        if self.delete_selection(false) {
+    pub fn toggle_fold_at_cursor(&mut self) -> bool {
+        if self.unfold(self.cursor.0) {
             return true;
         }
-        if self.delete_piece(0, self.cursor.1) {
-            return true;
+        let Some(((start, _), (end, _))) = self.selection_range() else {
+            return false;
+        };
+        if !self.fold(start, end) {
+            return false;
         }
-        self.delete_newline()
+        self.cancel_selection();
+        true
     }
 
-    /// Delete a word before cursor. Word boundary appears at spaces, punctuations, and others. For example `fn foo(a)`
-    /// consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at head of line, the newline before the cursor
-    /// will be removed.
-    ///
-    /// This method returns if some text was deleted or not in the textarea.
+    /// Get the folds sorted by their rows. See [`TextArea::fold`].
+    pub fn folds(&self) -> &[Fold] {
+        &self.folds
+    }
+
+    /// Fold blocks of lines by their indentation. A line starts a block when the following lines are more indented than
+    /// it, and the block continues until a line not more indented. Blank lines inside a block belong to it. Blocks
+    /// whose first lines are nested in `level` or more blocks are folded, so `0` folds all top-level blocks and `1`
+    /// keeps them open and folds the blocks inside them. Folds are not nested so blocks inside a folded block are not
+    /// folded separately. Existing folds are replaced and the number of folds is returned.
     ///
+    /// The indentation width counts a tab as the width up to the next tab stop of [`TextArea::tab_length`]. Nesting is
+    /// decided by comparing the widths, so lines indented by different numbers of spaces or by tabs are folded as
+    /// expected.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
-    ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
-    ///
-    /// textarea.move_cursor(CursorMove::End);
+    /// use tui_textarea::TextArea;
     ///
-    /// textarea.delete_word();
-    /// assert_eq!(textarea.lines(), ["aaa bbb "]);
-    /// textarea.delete_word();
-    /// assert_eq!(textarea.lines(), ["aaa bbb"]);
+    /// let mut textarea = TextArea::from([
+    ///     "server:",
+    ///     "  ports:",
+    ///     "    - 80",
+    ///     "",
+    ///     "    - 443",
+    ///     "  host: a",
+    ///     "client:",
+    ///     "  host: b",
+    /// ]);
+    ///
+    /// assert_eq!(textarea.fold_by_indent(0), 2);
+    /// let ranges: Vec<_> = textarea.folds().iter().map(|f| (f.start, f.end)).collect();
+    /// assert_eq!(ranges, [(0, 5), (6, 7)]);
+    ///
+    /// assert_eq!(textarea.fold_by_indent(1), 1);
+    /// let ranges: Vec<_> = textarea.folds().iter().map(|f| (f.start, f.end)).collect();
+    /// assert_eq!(ranges, [(1, 4)]);
     /// ```
-    pub fn delete_word(&mut self) -> bool {
-        if self.delete_selection(false) {
-            return true;
-        }
-        let (r, c) = self.cursor;
-        if let Some(col) = find_word_start_backward(&self.lines[r], c) {
-            self.delete_piece(col, c - col)
-        } else if c > 0 {
-            self.delete_piece(0, c)
-        } else {
-            self.delete_newline()
+    pub fn fold_by_indent(&mut self, level: usize) -> usize {
+        let tab_len = max(self.tab_len, 1) as usize;
+        let indents: Vec<_> = self.lines.iter().map(|l| line_indent_width(&l, tab_len)).collect();
+        self.folds.clear();
+        let folds = fold::indent_folds(&indents, level);
+        for f in &folds {
+            self.fold(f.start, f.end);
         }
+        folds.len()
     }
 
-    /// Delete a word next to cursor. Word boundary appears at spaces, punctuations, and others. For example `fn foo(a)`
-    /// consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at end of line, the newline next to the cursor
-    /// will be removed.
+    /// Remove all folds. See [`TextArea::fold`].
+    /// ```
+    /// use tui_textarea::TextArea;
     ///
-    /// This method returns if some text was deleted or not in the textarea.
+    /// let mut textarea = TextArea::from(["a", "  b", "c", "  d"]);
     ///
+    /// textarea.fold_by_indent(0);
+    /// assert_eq!(textarea.folds().len(), 2);
+    /// textarea.unfold_all();
+    /// assert!(textarea.folds().is_empty());
+    /// ```
+    pub fn unfold_all(&mut self) {
+        self.folds.clear();
+    }
+
+    /// Set the style of the suffix rendered after the first line of a fold, like ` … (12 lines)`. The default value is
+    /// dark gray foreground.
     /// ```
+    /// use ratatui::style::{Color, Style};
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.lines(), [" bbb ccc"]);
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.lines(), ["bbb ccc"]);
+    /// let style = Style::default().fg(Color::Cyan);
+    /// textarea.set_fold_style(style);
+    /// assert_eq!(textarea.fold_style(), style);
     /// ```
-    pub fn delete_next_word(&mut self) -> bool {
-        if self.delete_selection(false) {
-            return true;
+    pub fn set_fold_style(&mut self, style: Style) {
+        self.fold_style = style;
+    }
+
+    /// Get the style of the suffix of folded lines. See [`TextArea::set_fold_style`].
+    pub fn fold_style(&self) -> Style {
+        self.fold_style
+    }
+
+    // Folds applied on rendering. A fold hiding the cursor line is not applied even before it is opened
+    pub(crate) fn shown_folds(&self) -> Vec<Fold> {
+        if self.wrap {
+            return vec![];
         }
-        let (r, c) = self.cursor;
-        let line = &self.lines[r];
-        if let Some(col) = find_word_end_forward(line, c) {
-            self.delete_piece(c, col - c)
+        let (row, _) = self.cursor;
+        let len = self.lines.len();
+        self.folds
+            .iter()
+            .filter(|f| !f.hides(row) && f.end < len)
+            .copied()
+            .collect()
+    }
+
+    // Rows of lines rendered in `height` display rows from the line at `top_row` while folds are applied
+    pub(crate) fn display_rows(&self, top_row: usize, height: usize) -> Vec<usize> {
+        let folds = self.shown_folds();
+        let top = fold::display_row(&folds, top_row);
+        (top..top + height)
+            .map(|d| fold::row_at_display(&folds, d))
+            .take_while(|&row| row < self.lines.len())
+            .collect()
+    }
+
+    // Add the suffix showing the number of the folded lines to the spans of the first line of the fold
+    pub(crate) fn push_fold_suffix(&self, line: &mut Line<'static>, fold: &Fold) {
+        let suffix = Span::styled(format!(" … ({} lines)", fold.lines()), self.fold_style);
+        #[cfg(feature = "ratatui")]
+        line.spans.push(suffix);
+        #[cfg(feature = "tuirs")]
+        line.0.push(suffix);
+    }
+
+    fn open_fold_at_cursor(&mut self) {
+        let (row, _) = self.cursor;
+        self.folds.retain(|f| !f.hides(row));
+    }
+
+    // Number of digits of line numbers. This is the same on all lines so that the text does not shift horizontally
+    pub(crate) fn line_number_width(&self) -> u8 {
+        max(num_digits(self.lines.len()), self.line_number_min_width)
+    }
+
+    // Display cells occupied by the cursor in its line without soft-wrap. Hard tabs and wide characters occupy multiple
+    // cells. The cursor at the end of line occupies one cell
+    pub(crate) fn cursor_cells(&self) -> Range<usize> {
+        self.cells_at(self.cursor)
+    }
+
+    // Walk the characters of the line at the row without soft-wrap with their positions until `f` returns `false`. The
+    // walk starts from the last position before the first character `f` needs to see, where `from` returns `true`. See
+    // `CellIndex::walk`
+    fn walk_cells(&self, row: usize, from: impl Fn(&CellPos) -> bool, f: impl FnMut(CellPos, char) -> bool) -> CellPos {
+        let settings = (self.tab_len, self.display_mask(), self.render_control_chars);
+        self.cells.borrow_mut().walk(&self.lines, row, settings, from, f)
+    }
+
+    // Display columns of the character at the `(row, col)` position without soft-wrap
+    pub(crate) fn cells_at(&self, (row, col): (usize, usize)) -> Range<usize> {
+        let mask = self.display_mask();
+        let mut cells = None;
+        let end = self.walk_cells(
+            row,
+            |p| p.col <= col,
+            |p, c| {
+                if p.col == col {
+                    let width = char_width(c, p.width, self.tab_len, mask, self.render_control_chars);
+                    cells = Some(p.width..p.width + width.max(1));
+                }
+                p.col < col
+            },
+        );
+        cells.unwrap_or(end.width..end.width + 1)
+    }
+
+    // Display columns of the byte range in the line at the row without soft-wrap
+    #[cfg(feature = "hyperlinks")]
+    pub(crate) fn display_cells(&self, row: usize, bytes: Range<usize>) -> Range<usize> {
+        let mut start = 0;
+        let end = self.walk_cells(
+            row,
+            |p| p.offset <= bytes.start,
+            |p, _| {
+                if p.offset == bytes.start {
+                    start = p.width;
+                }
+                p.offset < bytes.end
+            },
+        );
+        start..end.width
+    }
+
+    // Number of display cells of the line at the row without soft-wrap
+    pub(crate) fn display_width(&self, row: usize) -> usize {
+        self.walk_cells(row, |_| true, |_, _| true).width
+    }
+
+    // Returns `true` when the line at the row is wider than the display cells without soft-wrap. The rest of the line
+    // after the cells is not read
+    pub(crate) fn is_wider_than(&self, row: usize, cells: usize) -> bool {
+        self.walk_cells(row, |p| p.width <= cells, |p, _| p.width <= cells)
+            .width
+            > cells
+    }
+
+    // Part of the line at the row rendered without soft-wrap when the first `hidden` display columns are scrolled out and
+    // `max_width` columns are rendered. The part starts at the first character at or after the `hidden` column, and it
+    // ends before the first character at or after the right edge. The byte offset and the column where the part ends
+    // are returned with it
+    pub(crate) fn visible_part(&self, row: usize, hidden: usize, max_width: usize) -> (LinePart, usize, usize) {
+        let right = hidden.saturating_add(max_width);
+        let mut start = None;
+        let end = self.walk_cells(
+            row,
+            |p| p.width <= hidden,
+            |p, _| {
+                if start.is_none() && p.width >= hidden {
+                    start = Some(p);
+                }
+                start.is_none() || p.width < right
+            },
+        );
+        // The whole line is scrolled out when it is narrower than the hidden columns
+        let start = start.unwrap_or(end);
+        let part = LinePart {
+            offset: start.offset,
+            col: start.col,
+            width: start.width,
+            len: self.lines.line_len(row),
+        };
+        (part, end.offset, end.col)
+    }
+
+    // Width of the gutter including the sign column and the space after line numbers. This is 0 when neither of them
+    // is shown
+    pub(crate) fn gutter_width(&self) -> usize {
+        let lnum = if self.line_number_style.is_some() {
+            self.line_number_width() as usize + 1
         } else {
-            let end_col = line.chars().count();
-            if c < end_col {
-                self.delete_piece(c, end_col - c)
-            } else if r + 1 < self.lines.len() {
-                self.cursor = (r + 1, 0);
-                self.delete_newline()
-            } else {
-                false
-            }
+            0
+        };
+        self.sign_column_width() + lnum
+    }
+
+    // Width of the sign column. This is 0 when the sign column is not shown
+    pub(crate) fn sign_column_width(&self) -> usize {
+        if self.sign_column || !self.signs.is_empty() {
+            SIGN_WIDTH
+        } else {
+            0
         }
     }
 
-    /// Paste a string previously deleted by [`TextArea::delete_line_by_head`], [`TextArea::delete_line_by_end`],
-    /// [`TextArea::delete_word`], [`TextArea::delete_next_word`]. This method returns if some text was inserted or not
-    /// in the textarea.
+    /// Set the placeholder text. The text is set in the textarea when no text is input. Setting a non-empty string `""`
+    /// enables the placeholder. The default value is an empty string so the placeholder is disabled by default.
+    /// To customize the text style, see [`TextArea::set_placeholder_style`].
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_text(), "");
+    /// assert!(textarea.placeholder_style().is_none());
     ///
-    /// textarea.delete_next_word();
-    /// textarea.move_cursor(CursorMove::End);
-    /// textarea.paste();
-    /// assert_eq!(textarea.lines(), [" bbb cccaaa"]);
+    /// textarea.set_placeholder_text("Hello");
+    /// assert_eq!(textarea.placeholder_text(), "Hello");
+    /// assert!(textarea.placeholder_style().is_some());
     /// ```
-    pub fn paste(&mut self) -> bool {
-        self.delete_selection(false);
-        match self.yank.clone() {
-            YankText::Piece(s) => self.insert_piece(s),
-            YankText::Chunk(c) => self.insert_chunk(c),
-        }
+    pub fn set_placeholder_text(&mut self, placeholder: impl Into<String>) {
+        self.placeholder = placeholder.into();
+        self.placeholder_lines = None;
     }
 
-    /// Start text selection at the cursor position. If text selection is already ongoing, the start position is reset.
+    /// Set the placeholder as styled lines. This is the same as [`TextArea::set_placeholder_text`] except that each
+    /// span keeps its own style on top of the style set by [`TextArea::set_placeholder_style`]. The placeholder is
+    /// rendered with the same alignment as the text and soft-wrapped when [`TextArea::set_wrap`] is enabled.
+    /// [`TextArea::placeholder_text`] returns the lines joined with newlines.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
-    ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// use ratatui::style::{Modifier, Style};
+    /// use ratatui::text::{Line, Span};
+    /// use tui_textarea::TextArea;
     ///
-    /// textarea.start_selection();
-    /// textarea.move_cursor(CursorMove::WordForward);
-    /// textarea.copy();
-    /// assert_eq!(textarea.yank_text(), "aaa");
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_placeholder_lines(vec![
+    ///     Line::from("Search anything..."),
+    ///     Line::from(vec![
+    ///         Span::raw("Press "),
+    ///         Span::styled("?", Style::default().add_modifier(Modifier::BOLD)),
+    ///         Span::raw(" for help"),
+    ///     ]),
+    /// ]);
+    /// assert_eq!(textarea.placeholder_text(), "Search anything...\nPress ? for help");
     /// ```
-    pub fn start_selection(&mut self) {
-        self.selection_start = Some(self.cursor);
+    pub fn set_placeholder_lines(&mut self, placeholder: impl Into<Text<'a>>) {
+        let text = placeholder.into();
+        self.placeholder = text.lines.iter().map(line_text).collect::<Vec<_>>().join("\n");
+        self.placeholder_lines = Some(text);
     }
 
-    /// Stop the current text selection. This method does nothing if text selection is not ongoing.
+    /// Set the style of the placeholder text. The default style is a dark gray text.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_style(), None); // When the placeholder is disabled
     ///
-    /// textarea.start_selection();
-    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.set_placeholder_text("Enter your message"); // Enable placeholder by setting non-empty text
     ///
-    /// // Cancel the ongoing text selection
-    /// textarea.cancel_selection();
+    /// let style = Style::default().bg(Color::Blue);
+    /// textarea.set_placeholder_style(style);
+    /// assert_eq!(textarea.placeholder_style(), Some(style));
+    /// ```
+    pub fn set_placeholder_style(&mut self, style: Style) {
+        self.placeholder_style = style;
+    }
+
+    /// Get the placeholder text. An empty string means the placeholder is disabled. The default value is an empty string.
+    /// ```
+    /// use tui_textarea::TextArea;
     ///
-    /// // As the result, this `copy` call copies the whole line when no selection
-    /// textarea.copy();
-    /// assert_eq!(textarea.yank_text(), "aaa bbb ccc");
+    /// let textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_text(), "");
     /// ```
-    pub fn cancel_selection(&mut self) {
-        self.selection_start = None;
+    pub fn placeholder_text(&self) -> &'_ str {
+        self.placeholder.as_str()
     }
 
-    pub fn take_selection(&mut self) -> Option<&str> {
-        if let Some((start, end)) = self.take_selection_range() {
-            if start.row == end.row {
-                return Some(&self.lines[start.row][start.offset..end.offset]);
-            }
+    // Text rendered while the textarea is empty
+    pub(crate) fn placeholder_lines(&self) -> Text<'_> {
+        match &self.placeholder_lines {
+            Some(text) => text.clone(),
+            None => Text::from(self.placeholder.as_str()),
         }
-
-        None
     }
 
-    /// Select the entire text. Cursor moves to the end of the text buffer. When text selection is already ongoing,
-    /// it is canceled.
+    /// Get the placeholder style. When the placeholder text is empty, it returns `None` since the placeholder is disabled.
+    /// The default style is a dark gray text.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
-    ///
-    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
-    ///
-    /// textarea.select_all();
+    /// use tui_textarea::TextArea;
     ///
-    /// // Cut the entire text;
-    /// textarea.delete_line(true);
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_style(), None);
     ///
-    /// assert_eq!(textarea.lines(), [""]); // Buffer is now empty
-    /// assert_eq!(textarea.yank_text(), "aaa\nbbb\nccc");
+    /// textarea.set_placeholder_text("hello");
+    /// assert!(textarea.placeholder_style().is_some());
     /// ```
-    pub fn select_all(&mut self) {
-        self.move_cursor(CursorMove::Jump(u64::MAX, u64::MAX));
-        self.selection_start = Some((0, 0));
+    pub fn placeholder_style(&self) -> Option<Style> {
+        if self.placeholder.is_empty() {
+            None
+        } else {
+            Some(self.placeholder_style)
+        }
     }
 
-    /// Return if text selection is ongoing or not.
+    /// Specify a character masking the text. All characters in the textarea will be replaced by this character.
+    /// This API is useful for making a kind of credentials form such as a password input.
     /// ```
-    /// use tui_textarea::{TextArea};
+    /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// assert!(!textarea.is_selecting());
-    /// textarea.start_selection();
-    /// assert!(textarea.is_selecting());
-    /// textarea.cancel_selection();
-    /// assert!(!textarea.is_selecting());
+    /// textarea.set_mask_char('*');
+    /// assert_eq!(textarea.mask_char(), Some('*'));
+    /// textarea.set_mask_char('●');
+    /// assert_eq!(textarea.mask_char(), Some('●'));
     /// ```
-    pub fn is_selecting(&self) -> bool {
-        self.selection_start.is_some()
+    pub fn set_mask_char(&mut self, mask: char) {
+        self.mask = Some(mask);
     }
 
-    fn line_offset(&self, row: usize, col: usize) -> usize {
-        let line = self.lines.get(row).unwrap_or(&self.lines[self.lines.len() - 1]);
-        line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len())
+    /// Clear the masking character previously set by [`TextArea::set_mask_char`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_mask_char('*');
+    /// assert_eq!(textarea.mask_char(), Some('*'));
+    /// textarea.clear_mask_char();
+    /// assert_eq!(textarea.mask_char(), None);
+    /// ```
+    pub fn clear_mask_char(&mut self) {
+        self.mask = None;
     }
 
-    /// Set the style used for text selection. The default style is light blue.
+    /// Get the character to mask text. When no character is set, `None` is returned.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::style::{Style, Color};
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// // Change the selection color from the default to Red
-    /// textarea.set_selection_style(Style::default().bg(Color::Red));
-    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::Red));
+    /// assert_eq!(textarea.mask_char(), None);
+    /// textarea.set_mask_char('*');
+    /// assert_eq!(textarea.mask_char(), Some('*'));
     /// ```
-    pub fn set_selection_style(&mut self, style: Style) {
-        self.select_style = style;
+    pub fn mask_char(&self) -> Option<char> {
+        self.mask
     }
 
-    /// Get the style used for text selection.
+    /// Enable or disable masking the text with the character set by [`TextArea::set_mask_char`]. Disabling the mask
+    /// shows the whole text temporarily, like a "show password" toggle, without clearing the mask character. The mask
+    /// is enabled by default.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::style::{Style, Color};
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::LightBlue));
+    /// textarea.set_mask_char('*');
+    /// textarea.set_mask_enabled(false);
+    /// assert!(!textarea.mask_enabled());
+    /// assert_eq!(textarea.mask_char(), Some('*'));
     /// ```
-    pub fn selection_style(&mut self) -> Style {
-        self.select_style
+    pub fn set_mask_enabled(&mut self, enabled: bool) {
+        self.mask_enabled = enabled;
     }
 
-    fn selection_range(&self) -> Option<(Pos, Pos)> {
-        let (sr, sc) = self.selection_start?;
-        let (er, ec) = self.cursor;
-        let (so, eo) = (self.line_offset(sr, sc), self.line_offset(er, ec));
-        let s = Pos::new(sr, sc, so);
-        let e = Pos::new(er, ec, eo);
-        match (sr, so).cmp(&(er, eo)) {
-            Ordering::Less => Some((s, e)),
-            Ordering::Equal => None,
-            Ordering::Greater => Some((e, s)),
-        }
+    /// Get if masking the text is enabled. See [`TextArea::set_mask_enabled`].
+    pub fn mask_enabled(&self) -> bool {
+        self.mask_enabled
     }
 
-    fn take_selection_range(&mut self) -> Option<(Pos, Pos)> {
-        let range = self.selection_range();
-        self.cancel_selection();
-        range
+    /// Show the character typed last without masking it, like password fields on mobile devices. The character is
+    /// revealed for the number of renderings set by [`TextArea::set_mask_reveal_renders`] while the cursor stays
+    /// right after it. A character whose display width differs from the mask character is not revealed. This is
+    /// disabled by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_mask_char('*');
+    /// textarea.set_mask_reveal_last(true);
+    /// assert!(textarea.mask_reveal_last());
+    /// ```
+    pub fn set_mask_reveal_last(&mut self, enabled: bool) {
+        self.mask_reveal_last = enabled;
+        self.mask_revealed.set(None);
     }
 
-    /// Copy the selection text to the yank buffer. When nothing is selected, this method does nothing.
-    /// To get the yanked text, use [`TextArea::yank_text`].
+    /// Get if the character typed last is revealed. See [`TextArea::set_mask_reveal_last`].
+    pub fn mask_reveal_last(&self) -> bool {
+        self.mask_reveal_last
+    }
+
+    /// Set how many renderings reveal the character typed last when [`TextArea::set_mask_reveal_last`] is enabled.
+    /// The default value is 1, which means the character is masked on the next rendering after it was rendered once.
     /// ```
-    /// use tui_textarea::{TextArea, Key, Input, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["Hello World"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// // Start text selection at 'W'
-    /// textarea.move_cursor(CursorMove::WordForward);
-    /// textarea.start_selection();
+    /// textarea.set_mask_reveal_renders(3);
+    /// assert_eq!(textarea.mask_reveal_renders(), 3);
+    /// ```
+    pub fn set_mask_reveal_renders(&mut self, renders: usize) {
+        self.mask_reveal_renders = renders;
+    }
+
+    /// Get how many renderings reveal the character typed last. See [`TextArea::set_mask_reveal_renders`].
+    pub fn mask_reveal_renders(&self) -> usize {
+        self.mask_reveal_renders
+    }
+
+    /// Set the style of cursor. By default, a cursor is rendered in the reversed color. Setting the same style as
+    /// cursor line hides a cursor.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
     ///
-    /// // Select the word "World" and copy the selected text
-    /// textarea.move_cursor(CursorMove::End);
-    /// textarea.copy();
+    /// let mut textarea = TextArea::default();
     ///
-    /// assert_eq!(textarea.yank_text(), " World");
-    /// assert_eq!(textarea.lines(), ["Hello World"]); // Text does not change
+    /// let style = Style::default().bg(Color::Red);
+    /// textarea.set_cursor_style(style);
+    /// assert_eq!(textarea.cursor_style(), style);
     /// ```
-    pub fn copy(&mut self) {
-        if let Some((start, end)) = self.selection_range() {
-            if start.row == end.row {
-                self.yank = self.lines[start.row][start.offset..end.offset].to_string().into();
-                return;
-            }
-            let mut chunk = vec![self.lines[start.row][start.offset..].to_string()];
-            chunk.extend(self.lines[start.row + 1..end.row].iter().cloned());
-            chunk.push(self.lines[end.row][..end.offset].to_string());
-            self.yank = YankText::Chunk(chunk);
-        } else {
-            let (row, _) = self.cursor;
-            self.yank = self.lines[row].clone().into();
-        }
+    pub fn set_cursor_style(&mut self, style: Style) {
+        self.cursor_style = style;
     }
 
-    fn delete_selection(&mut self, should_yank: bool) -> bool {
-        if let Some((s, e)) = self.take_selection_range() {
-            self.delete_range(s, e, should_yank);
-            return true;
-        }
-        false
+    /// Get the style of cursor.
+    pub fn cursor_style(&self) -> Style {
+        self.cursor_style
     }
 
-    /// Move the cursor to the position specified by the [`CursorMove`] parameter. For each kind of cursor moves, see
-    /// the document of [`CursorMove`].
+    /// Set the style of secondary cursors added by [`TextArea::add_cursor`] and other methods for multi-cursor
+    /// editing. By default, secondary cursors are rendered in the reversed color like the primary cursor. Secondary
+    /// cursors are hidden while the primary cursor is hidden.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// assert_eq!(textarea.cursor(), (0, 1));
-    /// textarea.move_cursor(CursorMove::Down);
-    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// let style = Style::default().bg(Color::Gray);
+    /// textarea.set_secondary_cursor_style(style);
+    /// assert_eq!(textarea.secondary_cursor_style(), style);
     /// ```
-    pub fn move_cursor(&mut self, m: CursorMove) {
-        self.move_cursor_with_shift(m, self.selection_start.is_some());
+    pub fn set_secondary_cursor_style(&mut self, style: Style) {
+        self.secondary_cursor_style = style;
     }
 
-    fn move_cursor_with_shift(&mut self, m: CursorMove, shift: bool) {
-        if let Some(cursor) = m.next_cursor(self.cursor, &self.lines, &self.viewport) {
-            if shift {
-                if self.selection_start.is_none() {
-                    self.start_selection();
-                }
-            } else {
-                self.cancel_selection();
-            }
-            self.cursor = cursor;
-        } else if !shift {
-            self.cancel_selection();
-        }
+    /// Get the style of secondary cursors. See [`TextArea::set_secondary_cursor_style`].
+    pub fn secondary_cursor_style(&self) -> Style {
+        self.secondary_cursor_style
+    }
+
+    /// Get slice of line texts. This method borrows the content, but not moves. Note that the returned slice will
+    /// never be empty because an empty text means a slice containing one empty line. This is correct since any text
+    /// file must end with a newline.
+    ///
+    /// This method exposes the text as `String`s. When the text is stored in a rope (see `TextArea::set_rope`), the
+    /// lines are copied into a vector on the first call after each edit. To read the text, prefer
+    /// [`TextArea::iter_lines`], [`TextArea::line`], [`TextArea::line_len`], [`TextArea::len_lines`], and
+    /// [`TextArea::text`], which don't copy the whole text.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.lines(), [""]);
+    ///
+    /// textarea.insert_char('a');
+    /// assert_eq!(textarea.lines(), ["a"]);
+    ///
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.lines(), ["a", ""]);
+    ///
+    /// textarea.insert_char('b');
+    /// assert_eq!(textarea.lines(), ["a", "b"]);
+    /// ```
+    pub fn lines(&'a self) -> &'a [String] {
+        self.lines.as_slice()
     }
 
-    pub fn move_line(&mut self, key: Key) -> bool {
-        let (row, col) = self.cursor;
-
-        let row_to_swap_with = match key {
-            Key::Up if row > 0 => row - 1,
-            Key::Down if row < self.lines.len().saturating_sub(1) => row + 1,
-            _ => return false,
-        };
+    /// Iterate over the text lines. A line is borrowed when it is stored in one piece. Prefer this and the other line
+    /// accessors such as [`TextArea::line`] and [`TextArea::len_lines`] to [`TextArea::lines`] when scanning the text,
+    /// since they do not depend on how the text is stored.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello", "", "world"]);
+    /// let non_empty: Vec<_> = textarea.iter_lines().filter(|l| !l.is_empty()).collect();
+    /// assert_eq!(non_empty, ["hello", "world"]);
+    /// assert_eq!(textarea.iter_lines().rev().next().as_deref(), Some("world"));
+    /// ```
+    pub fn iter_lines(&self) -> impl DoubleEndedIterator<Item = Cow<'_, str>> + ExactSizeIterator {
+        self.lines.iter()
+    }
 
-        self.push_change(Edit::new(
-            EditKind::MoveLine(false),
-            Pos::new(row, col, col),
-            Pos::new(row_to_swap_with, col, col),
-        ));
+    /// Get the text of the line at the row. `None` is returned when the row is out of the text. The line is borrowed
+    /// when it is stored in one piece, so this does not copy the line unless it is split across chunks of a rope.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello", "world"]);
+    /// assert_eq!(textarea.line(1).as_deref(), Some("world"));
+    /// assert_eq!(textarea.line(2), None);
+    /// ```
+    pub fn line(&self, row: usize) -> Option<Cow<'_, str>> {
+        self.lines.get(row)
+    }
 
-        true
+    /// Get the number of characters in the line at the row. `None` is returned when the row is out of the text. This
+    /// is the same unit as the column of [`TextArea::cursor`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello", "あいう"]);
+    /// assert_eq!(textarea.line_len(1), Some(3));
+    /// assert_eq!(textarea.line_len(2), None);
+    /// ```
+    pub fn line_len(&self, row: usize) -> Option<usize> {
+        (row < self.lines.len()).then(|| self.lines.char_count(row))
     }
 
-    /// Undo the last modification. This method returns if the undo modified text contents or not in the textarea.
+    /// Get the number of display cells of the line at the row. Tabs are expanded with [`TextArea::tab_length`], and
+    /// the mask character and control character notations are taken into account as rendered. Soft-wrap is not
+    /// considered. `None` is returned when the row is out of the text.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc def"]);
+    /// let textarea = TextArea::from(["\tab", "あいう"]);
+    /// assert_eq!(textarea.line_width(0), Some(6));
+    /// assert_eq!(textarea.line_width(1), Some(6));
+    /// assert_eq!(textarea.line_width(2), None);
+    /// ```
+    pub fn line_width(&self, row: usize) -> Option<usize> {
+        (row < self.lines.len()).then(|| self.display_width(row))
+    }
+
+    /// Get the number of lines in the textarea. This is always 1 or more since an empty text has one empty line.
+    /// ```
+    /// use tui_textarea::TextArea;
     ///
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.lines(), [" def"]);
-    /// textarea.undo();
-    /// assert_eq!(textarea.lines(), ["abc def"]);
+    /// assert_eq!(TextArea::default().len_lines(), 1);
+    /// assert_eq!(TextArea::from(["a", "b"]).len_lines(), 2);
     /// ```
-    pub fn undo(&mut self) -> bool {
-        if let Some(cursor) = self.history.undo(&mut self.lines) {
-            self.cancel_selection();
-            self.cursor = cursor;
-            true
-        } else {
-            false
-        }
+    pub fn len_lines(&self) -> usize {
+        self.lines.len()
     }
 
-    /// Redo the last undo change. This method returns if the redo modified text contents or not in the textarea.
+    /// Store the text in a rope instead of one `String` per line, or move it back with `false`. The text is not
+    /// changed. In a rope, inserting or deleting text in a long line and finding a column in it take O(log n) time
+    /// instead of moving or scanning the rest of the line, so this keeps editing responsive on huge files. While the
+    /// rope is enabled, [`TextArea::lines`] copies the whole text on the first call after each edit. Read the text with
+    /// [`TextArea::line`] and [`TextArea::iter_lines`] instead.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc def"]);
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.set_rope(true);
+    /// assert!(textarea.is_rope());
     ///
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.lines(), [" def"]);
-    /// textarea.undo();
-    /// assert_eq!(textarea.lines(), ["abc def"]);
-    /// textarea.redo();
-    /// assert_eq!(textarea.lines(), [" def"]);
+    /// textarea.insert_str("ah, ");
+    /// assert_eq!(textarea.line(0).as_deref(), Some("ah, hello"));
+    /// assert_eq!(textarea.lines(), ["ah, hello", "world"]);
     /// ```
-    pub fn redo(&mut self) -> bool {
-        if let Some(cursor) = self.history.redo(&mut self.lines) {
-            self.cancel_selection();
-            self.cursor = cursor;
-            true
-        } else {
-            false
-        }
+    #[cfg(feature = "rope")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rope")))]
+    pub fn set_rope(&mut self, enabled: bool) {
+        self.lines.set_rope(enabled);
     }
 
-    pub(crate) fn line_spans<'b>(&'b self, cursor_row: usize, line: &'b str, row: usize, lnum_len: u8) -> Line<'b> {
-        let mut hl = LineHighlighter::new(line, self.cursor_style, self.tab_len, self.mask, self.select_style);
-
-        if let Some(style) = self.line_number_style {
-            if cursor_row == row {
-                hl.line_number(row, lnum_len, style);
-            } else {
-                hl.line_number(row, lnum_len, style.add_modifier(Modifier::DIM));
-            }
-        }
-
-        if row == self.cursor.0 {
-            hl.cursor_line(self.cursor.1, self.cursor_line_style);
-        }
-
-        #[cfg(feature = "search")]
-        if let Some(matches) = self.search.matches(line) {
-            hl.search(matches, self.search.style);
-        }
-
-        if let Some((start, end)) = self.selection_range() {
-            hl.selection(row, start.row, start.offset, end.row, end.offset);
-        }
+    /// Return whether the text is stored in a rope. See [`TextArea::set_rope`].
+    #[cfg(feature = "rope")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rope")))]
+    pub fn is_rope(&self) -> bool {
+        self.lines.is_rope()
+    }
 
-        hl.into_spans()
+    /// Get the number of characters in the textarea. This is the same as [`TextArea::char_count`]. Each newline
+    /// between lines is counted as one character.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["abc", "あい"]);
+    /// assert_eq!(textarea.len_chars(), 6);
+    /// ```
+    pub fn len_chars(&self) -> usize {
+        self.char_count()
     }
 
-    /// Build a ratatui (or tui-rs) widget to render the current state of the textarea. The widget instance returned
-    /// from this method can be rendered with [`ratatui::terminal::Frame::render_widget`].
-    /// ```no_run
-    /// use ratatui::backend::CrosstermBackend;
-    /// use ratatui::layout::{Constraint, Direction, Layout};
-    /// use ratatui::Terminal;
+    /// Get the statistics of the text such as the numbers of words and characters. See [`TextStats`] for how they are
+    /// counted. The statistics are cached and only the lines changed after the last call are counted again, so calling
+    /// this method on every frame to render a status line is cheap.
+    /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["The quick brown fox", "jumps over"]);
+    /// let stats = textarea.stats();
+    /// assert_eq!((stats.lines, stats.words, stats.chars), (2, 6, 30));
     ///
-    /// let layout = Layout::default()
-    ///     .direction(Direction::Vertical)
-    ///     .constraints([Constraint::Min(1)].as_ref());
-    /// let backend = CrosstermBackend::new(std::io::stdout());
-    /// let mut term = Terminal::new(backend).unwrap();
+    /// textarea.insert_str("Well, ");
+    /// assert_eq!(textarea.stats().words, 7);
+    /// ```
+    pub fn stats(&self) -> TextStats {
+        self.stats.borrow_mut().stats(&self.lines)
+    }
+
+    /// Get the position of the cursor line as a fraction of the number of lines, from `1 / lines` at the first line to
+    /// `1.0` at the last line. This is useful to show a percentage like `43%` in a status line, like `%p` of Vim's
+    /// status line.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
     ///
-    /// loop {
-    ///     term.draw(|f| {
-    ///         let chunks = layout.split(f.size());
-    ///         let widget = textarea.widget();
-    ///         f.render_widget(widget, chunks[0]);
-    ///     }).unwrap();
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    /// assert_eq!(textarea.cursor_progress(), 0.25);
     ///
-    ///     // ...
-    /// }
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// assert_eq!(textarea.cursor_progress(), 1.0);
     /// ```
-    pub fn widget(&'a self) -> impl Widget + 'a {
-        Renderer::new(self)
+    pub fn cursor_progress(&self) -> f64 {
+        (self.cursor.0 + 1) as f64 / self.lines.len() as f64
     }
 
-    /// Set the style of textarea. By default, textarea is not styled.
+    /// Convert the `(row, col)` position into the offset of the character from the start of the text. A line
+    /// separator is counted as one character regardless of [`TextArea::line_ending`]. A position out of the text is
+    /// clamped to the end of the line or the text.
+    ///
+    /// Offsets of lines are cached and only the offsets after the edited line are recalculated on edits, so this method
+    /// is cheap enough to call on every edit. [`TextArea::position_to_byte_idx`] and
+    /// [`TextArea::position_to_utf16_idx`] are the variants for byte offsets and UTF-16 code unit offsets.
     /// ```
-    /// use ratatui::style::{Style, Color};
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
-    /// let style = Style::default().fg(Color::Red);
-    /// textarea.set_style(style);
-    /// assert_eq!(textarea.style(), style);
+    /// let textarea = TextArea::from(["abc", "あい"]);
+    /// assert_eq!(textarea.position_to_char_idx((1, 1)), 5);
+    /// assert_eq!(textarea.position_to_char_idx((0, 10)), 3);
+    /// assert_eq!(textarea.position_to_char_idx((10, 0)), 4);
     /// ```
-    pub fn set_style(&mut self, style: Style) {
-        self.style = style;
+    pub fn position_to_char_idx(&self, pos: (usize, usize)) -> usize {
+        self.line_offsets.borrow_mut().offset(&self.lines, Unit::Char, pos)
     }
 
-    /// Get the current style of textarea.
-    pub fn style(&self) -> Style {
-        self.style
+    /// Convert the offset of the character from the start of the text into the `(row, col)` position. This is the
+    /// reverse of [`TextArea::position_to_char_idx`]. An offset after the end of the text is clamped to the end.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["abc", "あい"]);
+    /// assert_eq!(textarea.char_idx_to_position(5), (1, 1));
+    /// assert_eq!(textarea.char_idx_to_position(3), (0, 3)); // Line separator
+    /// assert_eq!(textarea.char_idx_to_position(100), (1, 2));
+    /// ```
+    pub fn char_idx_to_position(&self, idx: usize) -> (usize, usize) {
+        self.line_offsets.borrow_mut().position(&self.lines, Unit::Char, idx)
     }
 
-    /// Set the block of textarea. By default, no block is set.
+    /// Convert the `(row, col)` position into the byte offset from the start of the text in UTF-8. A line separator
+    /// is counted as one byte like `\n`. See [`TextArea::position_to_char_idx`] for more details.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::widgets::{Block, Borders};
     ///
-    /// let mut textarea = TextArea::default();
-    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
-    /// textarea.set_block(block);
-    /// assert!(textarea.block().is_some());
+    /// let textarea = TextArea::from(["abc", "あい"]);
+    /// assert_eq!(textarea.position_to_byte_idx((1, 1)), 7);
     /// ```
-    pub fn set_block(&mut self, block: Block<'a>) {
-        self.block = Some(block);
+    pub fn position_to_byte_idx(&self, pos: (usize, usize)) -> usize {
+        self.line_offsets.borrow_mut().offset(&self.lines, Unit::Byte, pos)
     }
 
-    /// Remove the block of textarea which was set by [`TextArea::set_block`].
+    /// Convert the byte offset from the start of the text in UTF-8 into the `(row, col)` position. An offset in the
+    /// middle of a character is rounded down to the start of the character. See
+    /// [`TextArea::char_idx_to_position`] for more details.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::widgets::{Block, Borders};
     ///
-    /// let mut textarea = TextArea::default();
-    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
-    /// textarea.set_block(block);
-    /// textarea.remove_block();
-    /// assert!(textarea.block().is_none());
+    /// let textarea = TextArea::from(["abc", "あい"]);
+    /// assert_eq!(textarea.byte_idx_to_position(7), (1, 1));
+    /// assert_eq!(textarea.byte_idx_to_position(8), (1, 1));
     /// ```
-    pub fn remove_block(&mut self) {
-        self.block = None;
+    pub fn byte_idx_to_position(&self, idx: usize) -> (usize, usize) {
+        self.line_offsets.borrow_mut().position(&self.lines, Unit::Byte, idx)
     }
 
-    /// Get the block of textarea if exists.
-    pub fn block<'s>(&'s self) -> Option<&'s Block<'a>> {
-        self.block.as_ref()
+    /// Convert the `(row, col)` position into the offset in UTF-16 code units from the start of the text, which is
+    /// the default position encoding of LSP. A line separator is counted as one code unit. See
+    /// [`TextArea::position_to_char_idx`] for more details.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["😀", "a"]);
+    /// assert_eq!(textarea.position_to_utf16_idx((0, 1)), 2);
+    /// assert_eq!(textarea.position_to_utf16_idx((1, 1)), 4);
+    /// ```
+    pub fn position_to_utf16_idx(&self, pos: (usize, usize)) -> usize {
+        self.line_offsets.borrow_mut().offset(&self.lines, Unit::Utf16, pos)
     }
 
-    /// Set the length of tab character. Setting 0 disables tab inputs.
+    /// Convert the offset in UTF-16 code units from the start of the text into the `(row, col)` position. An offset in
+    /// the middle of a surrogate pair is rounded down to the start of the character. See
+    /// [`TextArea::char_idx_to_position`] for more details.
     /// ```
-    /// use tui_textarea::{TextArea, Input, Key};
-    ///
-    /// let mut textarea = TextArea::default();
-    /// let tab_input = Input { key: Key::Tab, ctrl: false, alt: false, shift: false };
-    ///
-    /// textarea.set_tab_length(8);
-    /// textarea.input(tab_input.clone());
-    /// assert_eq!(textarea.lines(), ["        "]);
+    /// use tui_textarea::TextArea;
     ///
-    /// textarea.set_tab_length(2);
-    /// textarea.input(tab_input);
-    /// assert_eq!(textarea.lines(), ["          "]);
+    /// let textarea = TextArea::from(["😀", "a"]);
+    /// assert_eq!(textarea.utf16_idx_to_position(4), (1, 1));
+    /// assert_eq!(textarea.utf16_idx_to_position(1), (0, 0));
     /// ```
-    pub fn set_tab_length(&mut self, len: u8) {
-        self.tab_len = len;
+    pub fn utf16_idx_to_position(&self, idx: usize) -> (usize, usize) {
+        self.line_offsets.borrow_mut().position(&self.lines, Unit::Utf16, idx)
     }
 
-    /// Get how many spaces are used for representing tab character. The default value is 4.
-    pub fn tab_length(&self) -> u8 {
-        self.tab_len
+    /// Get the text as one string with the lines joined by the line ending of [`TextArea::line_ending`]. Prefer this
+    /// to joining [`TextArea::lines`] with `\n`, which ignores the configured line ending. A text ending with a newline
+    /// has an empty last line, so the line ending is put at the end only when the last line is empty.
+    /// ```
+    /// use tui_textarea::{LineEnding, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", ""]);
+    /// assert_eq!(textarea.text(), "a\nb\n");
+    ///
+    /// textarea.set_line_ending(LineEnding::CrLf);
+    /// assert_eq!(textarea.text(), "a\r\nb\r\n");
+    /// ```
+    pub fn text(&self) -> String {
+        self.to_string()
     }
 
-    /// Set if a hard tab is used or not for indent. When `true` is set, typing a tab key inserts a hard tab instead of
-    /// spaces. By default, hard tab is disabled.
+    /// Convert [`TextArea`] instance into line texts.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// textarea.set_hard_tab_indent(true);
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["\t"]);
+    /// textarea.insert_char('a');
+    /// textarea.insert_newline();
+    /// textarea.insert_char('b');
+    ///
+    /// assert_eq!(textarea.into_lines(), ["a", "b"]);
     /// ```
-    pub fn set_hard_tab_indent(&mut self, enabled: bool) {
-        self.hard_tab_indent = enabled;
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines.into_vec()
     }
 
-    /// Get if a hard tab is used for indent or not.
+    /// Get the current cursor position. 0-base character-wise (row, col) cursor position.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.cursor(), (0, 0));
     ///
-    /// assert!(!textarea.hard_tab_indent());
-    /// textarea.set_hard_tab_indent(true);
-    /// assert!(textarea.hard_tab_indent());
+    /// textarea.insert_char('a');
+    /// textarea.insert_newline();
+    /// textarea.insert_char('b');
+    ///
+    /// assert_eq!(textarea.cursor(), (1, 1));
     /// ```
-    pub fn hard_tab_indent(&self) -> bool {
-        self.hard_tab_indent
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
     }
 
-    /// Get a string for indent. It consists of spaces by default. When hard tab is enabled, it is a tab character.
+    /// Convert the (column, row) position on the terminal screen into the 0-base character-wise (row, col) position in
+    /// the text. Block borders, line numbers, scroll position, and soft wrap are taken into account based on the last
+    /// rendered state of the textarea. A position after the end of a line is clamped to the end of the line, and a
+    /// position below the last line is clamped to the end of the text. `None` is returned when the position is outside
+    /// the text area or the textarea has never been rendered.
     /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::{Block, Borders, Widget};
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["abc", "あいう"]);
+    /// textarea.set_block(Block::default().borders(Borders::ALL));
     ///
-    /// assert_eq!(textarea.indent(), "    ");
-    /// textarea.set_tab_length(2);
-    /// assert_eq!(textarea.indent(), "  ");
-    /// textarea.set_hard_tab_indent(true);
-    /// assert_eq!(textarea.indent(), "\t");
+    /// let r = Rect { x: 0, y: 0, width: 10, height: 4 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
+    ///
+    /// assert_eq!(textarea.screen_to_cursor(2, 1), Some((0, 1)));
+    /// // Right half of 'い'
+    /// assert_eq!(textarea.screen_to_cursor(4, 2), Some((1, 1)));
+    /// // After the end of the line
+    /// assert_eq!(textarea.screen_to_cursor(8, 1), Some((0, 3)));
+    /// // On the border
+    /// assert_eq!(textarea.screen_to_cursor(0, 0), None);
     /// ```
-    pub fn indent(&self) -> &'static str {
-        if self.hard_tab_indent {
-            "\t"
+    pub fn screen_to_cursor(&self, x: u16, y: u16) -> Option<(usize, usize)> {
+        let (left, top) = self.viewport.origin();
+        let (top_row, top_col, width, height) = self.viewport.rect();
+        if x < left || y < top || x - left >= width || y - top >= height {
+            return None;
+        }
+        let (x, y) = ((x - left) as usize, (y - top) as usize);
+
+        let gutter = self.gutter_width();
+
+        let Some(wrapper) = self.wrapper(width) else {
+            // Extra rows of the ghost text are below the cursor row. Clicking them moves the cursor nowhere
+            let ghost_rows = self.ghost_text_rows();
+            let mut y = y;
+            if ghost_rows > 0 && self.cursor.0 >= top_row as usize {
+                let folds = self.shown_folds();
+                let cursor_y = fold::display_row(&folds, self.cursor.0) - fold::display_row(&folds, top_row as usize);
+                if y > cursor_y + ghost_rows {
+                    y -= ghost_rows;
+                } else if y > cursor_y {
+                    return Some(self.cursor);
+                }
+            }
+            let Some(&row) = self.display_rows(top_row as usize, y + 1).get(y) else {
+                let row = self.lines.len() - 1;
+                return Some((row, self.lines.char_count(row)));
+            };
+            let cell = (x + top_col as usize).saturating_sub(gutter);
+            let unwrapped = Wrapper::new(
+                usize::MAX,
+                0,
+                self.tab_len,
+                self.display_mask(),
+                self.render_control_chars,
+            );
+            return Some((row, unwrapped.col_at(&self.lines.line(row), 0, cell)));
+        };
+
+        let indent = self.wrap_indicator.as_ref().map(|(s, _)| s.width()).unwrap_or(0);
+        let mut y = y + self.viewport.skip() as usize;
+        for (row, line) in self.lines.iter().enumerate().skip(top_row as usize) {
+            let len = line.chars().count();
+            let end_cell = row == self.cursor.0 && self.cursor.1 >= len;
+            let rows = wrapper.height(&line, end_cell);
+            if y < rows {
+                if y >= wrapper.height(&line, false) {
+                    return Some((row, len)); // The extra row for the cursor at the end of line
+                }
+                let cell = x.saturating_sub(gutter + if y > 0 { indent } else { 0 });
+                return Some((row, wrapper.col_at(&line, y, cell)));
+            }
+            y -= rows;
+        }
+
+        let row = self.lines.len() - 1;
+        Some((row, self.lines.char_count(row)))
+    }
+
+    /// Get the (column, row) position of the cursor on the terminal screen. This is the inverse of
+    /// [`TextArea::screen_to_cursor`] and is useful to put a popup such as a completion menu next to the cursor. Block
+    /// borders, line numbers, scroll position, soft wrap, and the display width of the text before the cursor (hard
+    /// tabs and wide characters) are taken into account based on the last rendered state of the textarea. `None` is
+    /// returned when the cursor is scrolled out of the view or the textarea has never been rendered. `None` is also
+    /// returned when the text is aligned to the center or the right by [`TextArea::set_alignment`], since the column
+    /// then depends on the width of the whole rendered line.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::{Block, Borders, Widget};
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "あいう"]);
+    /// textarea.set_block(Block::default().borders(Borders::ALL));
+    ///
+    /// // Not rendered yet
+    /// assert_eq!(textarea.cursor_screen_position(), None);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(1, 2));
+    /// let r = Rect { x: 0, y: 0, width: 10, height: 4 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
+    ///
+    /// // Inside the border, after 'あい' which occupy 4 cells
+    /// assert_eq!(textarea.cursor_screen_position(), Some((5, 2)));
+    /// assert_eq!(textarea.screen_to_cursor(5, 2), Some((1, 2)));
+    ///
+    /// // The column is not known with other alignments
+    /// textarea.set_alignment(ratatui::layout::Alignment::Right);
+    /// textarea.widget().render(r, &mut b);
+    /// assert_eq!(textarea.cursor_screen_position(), None);
+    /// ```
+    pub fn cursor_screen_position(&self) -> Option<(u16, u16)> {
+        self.screen_position(self.cursor)
+    }
+
+    // Position of the character at (row, col) on the terminal screen. The position must be in the text. Only the left
+    // alignment is supported
+    fn screen_position(&self, (row, col): (usize, usize)) -> Option<(u16, u16)> {
+        let (left, top) = self.viewport.origin();
+        let (top_row, top_col, width, height) = self.viewport.rect();
+        if width == 0 || height == 0 || (row as u64) < top_row || self.alignment != Alignment::Left {
+            return None;
+        }
+
+        let gutter = self.gutter_width();
+
+        let (x, y) = if let Some(wrapper) = self.wrapper(width) {
+            let mut y = 0;
+            for line in self.lines.iter_in(top_row as usize..row) {
+                y += wrapper.height(&line, false);
+                if y >= height as usize + self.viewport.skip() as usize {
+                    return None;
+                }
+            }
+            let (sub_row, offset) = wrapper.locate(&self.lines.line(row), col);
+            let y = (y + sub_row).checked_sub(self.viewport.skip() as usize)?;
+            let indent = if sub_row > 0 {
+                self.wrap_indicator.as_ref().map(|(s, _)| s.width()).unwrap_or(0)
+            } else {
+                0
+            };
+            (gutter + indent + offset, y)
         } else {
-            spaces(self.tab_len)
+            let unwrapped = Wrapper::new(
+                usize::MAX,
+                0,
+                self.tab_len,
+                self.display_mask(),
+                self.render_control_chars,
+            );
+            let (_, offset) = unwrapped.locate(&self.lines.line(row), col);
+            let x = (gutter + offset).checked_sub(top_col as usize)?;
+            let folds = self.shown_folds();
+            if folds.iter().any(|f| f.hides(row)) {
+                return None;
+            }
+            let mut y = fold::display_row(&folds, row) - fold::display_row(&folds, top_row as usize);
+            if row > self.cursor.0 && self.cursor.0 >= top_row as usize {
+                y += self.ghost_text_rows();
+            }
+            (x, y)
+        };
+
+        if x >= width as usize || y >= height as usize {
+            return None;
         }
+        Some((left + x as u16, top + y as u16))
+    }
+
+    /// Get the row and column of the text at the top-left of the viewport where the textarea was rendered last, and
+    /// the width and height of the viewport. This is the same as [`Viewport::rect`] of the textarea's own viewport.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+    /// textarea.move_cursor(CursorMove::Bottom);
+    ///
+    /// let r = Rect { x: 0, y: 0, width: 10, height: 4 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
+    ///
+    /// assert_eq!(textarea.viewport_rect(), (6, 0, 10, 4));
+    /// ```
+    pub fn viewport_rect(&self) -> (u64, u64, u16, u16) {
+        self.viewport.rect()
+    }
+
+    /// Show scrollbars with the symbols and styles of the config, or hide them with `None`. The vertical scrollbar is
+    /// rendered at the rightmost column inside the block and the horizontal scrollbar, when enabled by
+    /// [`ScrollbarConfig::horizontal`], at the bottom row inside the block. The text is rendered in the rest of the area.
+    /// By default, no scrollbar is shown.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::Widget;
+    /// use tui_textarea::{ScrollbarConfig, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..8).map(|i| i.to_string()).collect();
+    /// textarea.set_scrollbar(Some(ScrollbarConfig::default()));
+    ///
+    /// let r = Rect { x: 0, y: 0, width: 4, height: 4 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
+    ///
+    /// // The thumb shows the first half of the text is visible
+    /// assert_eq!(b.content[3].symbol(), "█");
+    /// assert_eq!(b.content[7].symbol(), "█");
+    /// assert_eq!(b.content[11].symbol(), "│");
+    /// assert_eq!(textarea.scrollbar_rects(), (Some(Rect::new(3, 0, 1, 4)), None));
+    /// ```
+    pub fn set_scrollbar(&mut self, config: Option<ScrollbarConfig>) {
+        self.scrollbar = config;
+    }
+
+    /// Get the scrollbar config set by [`TextArea::set_scrollbar`].
+    pub fn scrollbar(&self) -> Option<&ScrollbarConfig> {
+        self.scrollbar.as_ref()
+    }
+
+    /// Get the areas of the vertical and the horizontal scrollbars on the terminal screen where the textarea was
+    /// rendered last. `None` is returned for a scrollbar which was not rendered. This is the same as
+    /// [`Viewport::scrollbar_rects`] of the textarea's own viewport and is useful to handle mouse clicks on scrollbars.
+    pub fn scrollbar_rects(&self) -> (Option<Rect>, Option<Rect>) {
+        self.viewport.scrollbar_rects()
     }
 
-    /// Set how many modifications are remembered for undo/redo. Setting 0 disables undo/redo.
-    pub fn set_max_histories(&mut self, max: usize) {
-        self.history = History::new(max);
+    /// Get the range of rows of the text visible in the viewport where the textarea was rendered last. A row partially
+    /// visible with soft wrap is included. An empty range is returned when the textarea has never been rendered. This
+    /// is useful to draw your own scrollbar or minimap.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::{Block, Borders, Widget};
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+    /// textarea.set_block(Block::default().borders(Borders::ALL));
+    /// assert_eq!(textarea.visible_rows(), 0..0);
+    ///
+    /// // 4 rows are visible inside the borders
+    /// let r = Rect { x: 0, y: 0, width: 10, height: 6 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
+    /// assert_eq!(textarea.visible_rows(), 0..4);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.widget().render(r, &mut b);
+    /// assert_eq!(textarea.visible_rows(), 6..10);
+    /// ```
+    pub fn visible_rows(&self) -> Range<usize> {
+        self.visible_rows_in(&self.viewport)
     }
 
-    /// Get how many modifications are remembered for undo/redo. The default value is 50.
-    pub fn max_histories(&self) -> usize {
-        self.history.max_items()
+    pub(crate) fn visible_rows_in(&self, viewport: &Viewport) -> Range<usize> {
+        let (top, _, width, height) = viewport.rect();
+        let top = min(top, self.lines.len() as u64) as usize;
+        if width == 0 || height == 0 {
+            return 0..0;
+        }
+        let Some(wrapper) = self.wrapper(width) else {
+            let rows = self.display_rows(top, height as usize);
+            return match (rows.first(), rows.last()) {
+                (Some(&first), Some(&last)) => first..last + 1,
+                _ => top..top,
+            };
+        };
+        let visible = viewport.skip() as usize + height as usize;
+        let mut rows = 0;
+        for (i, line) in self.lines.iter_in(top..self.lines.len()).enumerate() {
+            rows += wrapper.height(&line, false);
+            if rows >= visible {
+                return top..top + i + 1;
+            }
+        }
+        top..self.lines.len()
     }
 
-    /// Set the style of line at cursor. By default, the cursor line is styled with underline. To stop styling the
-    /// cursor line, set the default style.
+    /// Check if the character at the position is visible in the viewport where the textarea was rendered last. Block
+    /// borders, line numbers, horizontal scroll, and soft wrap are taken into account. The position at the end of a
+    /// line is visible when the cursor put there is visible. `false` is returned for the position out of the text or
+    /// when the textarea has never been rendered. When the text is aligned to the center or the right, columns in the
+    /// screen are not known and a position is visible only when its line is visible and fits in the viewport width.
     /// ```
-    /// use ratatui::style::{Style, Color};
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::Widget;
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["abcdefghij", "klm", "nop"]);
+    /// assert!(!textarea.is_position_visible(0, 0));
     ///
-    /// let style = Style::default().fg(Color::Red);
-    /// textarea.set_cursor_line_style(style);
-    /// assert_eq!(textarea.cursor_line_style(), style);
+    /// let r = Rect { x: 0, y: 0, width: 5, height: 2 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
     ///
-    /// // Disable cursor line style
-    /// textarea.set_cursor_line_style(Style::default());
+    /// assert!(textarea.is_position_visible(0, 4));
+    /// assert!(!textarea.is_position_visible(0, 5)); // Out of the right edge
+    /// assert!(textarea.is_position_visible(1, 3));
+    /// assert!(!textarea.is_position_visible(2, 0)); // Below the viewport
+    /// assert!(!textarea.is_position_visible(1, 4)); // Out of the text
     /// ```
-    pub fn set_cursor_line_style(&mut self, style: Style) {
-        self.cursor_line_style = style;
+    pub fn is_position_visible(&self, row: usize, col: usize) -> bool {
+        match self.lines.get(row) {
+            Some(line) if col > line.chars().count() => false,
+            Some(_) if self.alignment != Alignment::Left => {
+                let (_, _, width, _) = self.viewport.rect();
+                let folded = self.shown_folds().iter().any(|f| f.hides(row));
+                let fits = self.gutter_width() + self.display_width(row) < width as usize;
+                self.visible_rows().contains(&row) && !folded && fits
+            }
+            Some(_) => self.screen_position((row, col)).is_some(),
+            None => false,
+        }
     }
 
-    /// Get the style of cursor line. By default it is styled with underline.
-    pub fn cursor_line_style(&self) -> Style {
-        self.cursor_line_style
+    /// Get how far the viewport is scrolled where the textarea was rendered last, from `0.0` at the top of the text to
+    /// `1.0` at the bottom. `0.0` is returned when the whole text is visible or the textarea has never been rendered.
+    /// This is useful to draw a scrollbar or a percentage indicator.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..12).map(|i| i.to_string()).collect();
+    /// let r = Rect { x: 0, y: 0, width: 10, height: 4 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.widget().render(r, &mut b);
+    /// assert_eq!(textarea.scroll_progress(), 0.0);
+    ///
+    /// // Rows 4..8 are visible. The viewport can scroll to row 8
+    /// textarea.move_cursor(CursorMove::Jump(7, 0));
+    /// textarea.widget().render(r, &mut b);
+    /// assert_eq!(textarea.scroll_progress(), 0.5);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.widget().render(r, &mut b);
+    /// assert_eq!(textarea.scroll_progress(), 1.0);
+    /// ```
+    pub fn scroll_progress(&self) -> f64 {
+        let rows = self.visible_rows();
+        if rows.start == 0 {
+            0.0 // Not rendered yet or at the top
+        } else if self.is_bottom_visible() {
+            1.0
+        } else {
+            // The viewport can scroll until the last row is visible
+            let max = self.lines.len() - rows.len();
+            (rows.start as f64 / max as f64).min(1.0)
+        }
     }
 
-    /// Set the style of line number. By setting the style with this method, line numbers are drawn in textarea, meant
-    /// that line numbers are disabled by default. If you want to show line numbers but don't want to style them, set
-    /// the default style.
+    /// Set text alignment. When [`Alignment::Center`] or [`Alignment::Right`] is set, line number is automatically
+    /// disabled because those alignments don't work well with line numbers.
     /// ```
-    /// use ratatui::style::{Style, Color};
+    /// use ratatui::layout::Alignment;
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// // Show line numbers in dark gray background
-    /// let style = Style::default().bg(Color::DarkGray);
-    /// textarea.set_line_number_style(style);
-    /// assert_eq!(textarea.line_number_style(), Some(style));
+    /// textarea.set_alignment(Alignment::Center);
+    /// assert_eq!(textarea.alignment(), Alignment::Center);
     /// ```
-    pub fn set_line_number_style(&mut self, style: Style) {
-        self.line_number_style = Some(style);
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        if let Alignment::Center | Alignment::Right = alignment {
+            self.line_number_style = None;
+        }
+        self.alignment = alignment;
     }
 
-    /// Remove the style of line number which was set by [`TextArea::set_line_number_style`]. After calling this
-    /// method, Line numbers will no longer be shown.
+    /// Get current text alignment. The default alignment is [`Alignment::Left`].
     /// ```
-    /// use ratatui::style::{Style, Color};
+    /// use ratatui::layout::Alignment;
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// textarea.set_line_number_style(Style::default().bg(Color::DarkGray));
-    /// textarea.remove_line_number();
-    /// assert_eq!(textarea.line_number_style(), None);
+    /// assert_eq!(textarea.alignment(), Alignment::Left);
     /// ```
-    pub fn remove_line_number(&mut self) {
-        self.line_number_style = None;
-    }
-
-    /// Get the style of line number if set.
-    pub fn line_number_style(&self) -> Option<Style> {
-        self.line_number_style
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
     }
 
-    /// Set the placeholder text. The text is set in the textarea when no text is input. Setting a non-empty string `""`
-    /// enables the placeholder. The default value is an empty string so the placeholder is disabled by default.
-    /// To customize the text style, see [`TextArea::set_placeholder_style`].
+    /// Check if the textarea has a empty content.
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_text(), "");
-    /// assert!(textarea.placeholder_style().is_none());
+    /// let textarea = TextArea::default();
+    /// assert!(textarea.is_empty());
     ///
-    /// textarea.set_placeholder_text("Hello");
-    /// assert_eq!(textarea.placeholder_text(), "Hello");
-    /// assert!(textarea.placeholder_style().is_some());
+    /// let textarea = TextArea::from(["hello"]);
+    /// assert!(!textarea.is_empty());
     /// ```
-    pub fn set_placeholder_text(&mut self, placeholder: impl Into<String>) {
-        self.placeholder = placeholder.into();
+    pub fn is_empty(&self) -> bool {
+        self.lines == [""]
     }
 
-    /// Set the style of the placeholder text. The default style is a dark gray text.
+    /// Synchronize the yank buffer with the system clipboard. When enabled, [`TextArea::copy`] and [`TextArea::cut`]
+    /// also write the text to the system clipboard, and [`TextArea::paste`] pastes the clipboard text when it differs
+    /// from the yanked text. When the system clipboard is not available (e.g. no display in a headless environment),
+    /// the internal yank buffer is silently used instead. This is disabled by default.
     /// ```
-    /// use ratatui::style::{Style, Color};
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_style(), None); // When the placeholder is disabled
+    /// textarea.set_clipboard_sync(true);
+    /// assert!(textarea.clipboard_sync());
+    /// ```
+    #[cfg(feature = "clipboard")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "clipboard")))]
+    pub fn set_clipboard_sync(&mut self, enabled: bool) {
+        if enabled != self.clipboard.is_some() {
+            self.clipboard = enabled.then(Clipboard::default);
+        }
+    }
+
+    /// Get if the yank buffer is synchronized with the system clipboard. See [`TextArea::set_clipboard_sync`].
+    #[cfg(feature = "clipboard")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "clipboard")))]
+    pub fn clipboard_sync(&self) -> bool {
+        self.clipboard.is_some()
+    }
+
+    /// Get the yanked text. Text is automatically yanked when deleting strings by [`TextArea::delete_line_by_head`],
+    /// [`TextArea::delete_line_by_end`], [`TextArea::delete_word`], [`TextArea::delete_next_word`],
+    /// [`TextArea::delete_str`], [`TextArea::copy`], and [`TextArea::cut`]. When multiple lines were yanked, they are
+    /// always joined with `\n`.
+    /// ```
+    /// use tui_textarea::TextArea;
     ///
-    /// textarea.set_placeholder_text("Enter your message"); // Enable placeholder by setting non-empty text
+    /// let mut textarea = TextArea::from(["abc"]);
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.yank_text(), "abc");
     ///
-    /// let style = Style::default().bg(Color::Blue);
-    /// textarea.set_placeholder_style(style);
-    /// assert_eq!(textarea.placeholder_style(), Some(style));
+    /// // Multiple lines are joined with \n
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// textarea.delete_str(5);
+    /// assert_eq!(textarea.yank_text(), "abc\nd");
     /// ```
-    pub fn set_placeholder_style(&mut self, style: Style) {
-        self.placeholder_style = style;
+    pub fn yank_text(&self) -> String {
+        self.yank.to_string()
     }
 
-    /// Get the placeholder text. An empty string means the placeholder is disabled. The default value is an empty string.
+    /// Set a yanked text. The text can be inserted by [`TextArea::paste`]. `\n` and `\r\n` are recognized as newline
+    /// but `\r` isn't.
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_text(), "");
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_yank_text("hello\nworld");
+    /// textarea.paste();
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
     /// ```
-    pub fn placeholder_text(&self) -> &'_ str {
-        self.placeholder.as_str()
+    pub fn set_yank_text(&mut self, text: impl Into<String>) {
+        self.yank = YankText::from_text(&text.into());
+        self.push_kill_ring(self.yank.to_string());
     }
 
-    /// Get the placeholder style. When the placeholder text is empty, it returns `None` since the placeholder is disabled.
-    /// The default style is a dark gray text.
+    /// Set the maximum number of entries in the kill ring. Setting 0 disables the kill ring, which is the default.
+    ///
+    /// While the kill ring is enabled, texts yanked by deleting, copying, and cutting are also saved to the kill ring,
+    /// and [`TextArea::paste_rotate`] can replace the pasted text with older entries like `M-y` in Emacs. Consecutive
+    /// deletions in the same direction are merged into one entry. For example, [`TextArea::delete_next_word`] after
+    /// [`TextArea::delete_line_by_end`] appends the deleted word to the last entry, and [`TextArea::delete_word`] after
+    /// [`TextArea::delete_line_by_head`] prepends it. Moving the cursor or other edits between the deletions start a
+    /// new entry. Cutting or copying always adds a new entry.
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_style(), None);
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// textarea.set_kill_ring_size(10);
+    /// assert_eq!(textarea.kill_ring_size(), 10);
     ///
-    /// textarea.set_placeholder_text("hello");
-    /// assert!(textarea.placeholder_style().is_some());
+    /// // Consecutive deletions are merged
+    /// textarea.delete_next_word();
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.kill_ring(), ["aaa "]);
+    /// assert_eq!(textarea.yank_text(), "aaa ");
+    ///
+    /// textarea.set_yank_text("xyz");
+    /// assert_eq!(textarea.kill_ring(), ["xyz", "aaa "]);
     /// ```
-    pub fn placeholder_style(&self) -> Option<Style> {
-        if self.placeholder.is_empty() {
-            None
-        } else {
-            Some(self.placeholder_style)
-        }
+    pub fn set_kill_ring_size(&mut self, size: usize) {
+        self.kill_ring_size = size;
+        self.kill_ring.truncate(size);
     }
 
-    /// Specify a character masking the text. All characters in the textarea will be replaced by this character.
-    /// This API is useful for making a kind of credentials form such as a password input.
+    /// Get the maximum number of entries in the kill ring. See [`TextArea::set_kill_ring_size`].
+    pub fn kill_ring_size(&self) -> usize {
+        self.kill_ring_size
+    }
+
+    /// Get the entries in the kill ring. The newest entry comes first. Lines are joined with `\n` in each entry. See
+    /// [`TextArea::set_kill_ring_size`].
+    pub fn kill_ring(&self) -> &[String] {
+        &self.kill_ring
+    }
+
+    /// Set a regular expression pattern for text search. Setting an empty string stops the text search.
+    /// When a valid pattern is set, all matches will be highlighted in the textarea. Note that the cursor does not
+    /// move. To move the cursor, use [`TextArea::search_forward`] and [`TextArea::search_back`].
+    ///
+    /// Grammar of regular expression follows [regex crate](https://docs.rs/regex/latest/regex). Patterns don't match
+    /// to newlines so match passes across no newline.
+    ///
+    /// When the pattern is invalid, the search pattern will not be updated and an error will be returned.
+    ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["hello, world", "goodbye, world"]);
     ///
-    /// textarea.set_mask_char('*');
-    /// assert_eq!(textarea.mask_char(), Some('*'));
-    /// textarea.set_mask_char('●');
-    /// assert_eq!(textarea.mask_char(), Some('●'));
+    /// // Search "world"
+    /// textarea.set_search_pattern("world").unwrap();
+    ///
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (0, 7));
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (1, 9));
+    ///
+    /// // Stop the text search
+    /// textarea.set_search_pattern("");
+    ///
+    /// // Invalid search pattern
+    /// assert!(textarea.set_search_pattern("(hello").is_err());
     /// ```
-    pub fn set_mask_char(&mut self, mask: char) {
-        self.mask = Some(mask);
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_pattern(&mut self, query: impl AsRef<str>) -> Result<(), regex::Error> {
+        self.restart_search_preview();
+        self.search.set_pattern(query.as_ref())
     }
 
-    /// Clear the masking character previously set by [`TextArea::set_mask_char`].
+    // Matches of a new pattern are previewed from the cursor again
+    #[cfg(feature = "search")]
+    fn restart_search_preview(&mut self) {
+        if let Some(preview) = &mut self.search_preview {
+            preview.pos = None;
+        }
+    }
+
+    /// Get a regular expression which was set by [`TextArea::set_search_pattern`]. When no text search is ongoing, this
+    /// method returns `None`. Note that the regular expression is built with the options set by
+    /// [`TextArea::set_search_options`]. For example, its pattern is surrounded by `\b` when `whole_word` is enabled.
+    ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// textarea.set_mask_char('*');
-    /// assert_eq!(textarea.mask_char(), Some('*'));
-    /// textarea.clear_mask_char();
-    /// assert_eq!(textarea.mask_char(), None);
+    /// assert!(textarea.search_pattern().is_none());
+    /// textarea.set_search_pattern("hello+").unwrap();
+    /// assert!(textarea.search_pattern().is_some());
+    /// assert_eq!(textarea.search_pattern().unwrap().as_str(), "hello+");
     /// ```
-    pub fn clear_mask_char(&mut self) {
-        self.mask = None;
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_pattern(&self) -> Option<&regex::Regex> {
+        self.search.pat.as_ref()
     }
 
-    /// Get the character to mask text. When no character is set, `None` is returned.
+    /// Get the history of the patterns set by [`TextArea::set_search_pattern`]. The newest pattern comes first.
+    ///
+    /// Each pattern is recorded when it is set. Setting an empty pattern stops the search and is not recorded. Invalid
+    /// patterns are not recorded and consecutive duplicates are collapsed. When a pattern is set for each keystroke of
+    /// incremental search, a pattern extending the newest entry replaces it so that only the typed query is recorded.
+    /// The number of entries is capped by [`TextArea::set_max_search_histories`].
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// assert_eq!(textarea.mask_char(), None);
-    /// textarea.set_mask_char('*');
-    /// assert_eq!(textarea.mask_char(), Some('*'));
+    /// textarea.set_search_pattern("f").unwrap();
+    /// textarea.set_search_pattern("fo").unwrap();
+    /// textarea.set_search_pattern("foo").unwrap();
+    /// textarea.set_search_pattern("").unwrap();
+    /// textarea.set_search_pattern("bar").unwrap();
+    /// textarea.set_search_pattern("").unwrap();
+    /// textarea.set_search_pattern("bar").unwrap();
+    ///
+    /// assert_eq!(textarea.search_history(), ["bar", "foo"]);
     /// ```
-    pub fn mask_char(&self) -> Option<char> {
-        self.mask
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_history(&self) -> &[String] {
+        self.search.history()
     }
 
-    /// Set the style of cursor. By default, a cursor is rendered in the reversed color. Setting the same style as
-    /// cursor line hides a cursor.
+    /// Recall the pattern older than the last recalled one from [`TextArea::search_history`] and set it as the search
+    /// pattern, like the Up key in a readline prompt. The pattern of the ongoing search is skipped. `None` is returned
+    /// when there is no older pattern. Recalling a pattern does not change the history.
     /// ```
-    /// use ratatui::style::{Style, Color};
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
+    /// for pat in ["foo", "bar"] {
+    ///     textarea.set_search_pattern(pat).unwrap();
+    ///     textarea.set_search_pattern("").unwrap();
+    /// }
     ///
-    /// let style = Style::default().bg(Color::Red);
-    /// textarea.set_cursor_style(style);
-    /// assert_eq!(textarea.cursor_style(), style);
+    /// assert_eq!(textarea.search_history_prev(), Some("bar"));
+    /// assert_eq!(textarea.search_history_prev(), Some("foo"));
+    /// assert_eq!(textarea.search_pattern().unwrap().as_str(), "foo");
+    /// assert_eq!(textarea.search_history_prev(), None);
+    ///
+    /// assert_eq!(textarea.search_history_next(), Some("bar"));
+    /// assert_eq!(textarea.search_history_next(), None);
     /// ```
-    pub fn set_cursor_style(&mut self, style: Style) {
-        self.cursor_style = style;
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_history_prev(&mut self) -> Option<&str> {
+        self.restart_search_preview();
+        self.search.history_prev()
     }
 
-    /// Get the style of cursor.
-    pub fn cursor_style(&self) -> Style {
-        self.cursor_style
+    /// Recall the pattern newer than the last recalled one from [`TextArea::search_history`] and set it as the search
+    /// pattern, like the Down key in a readline prompt. `None` is returned after the newest pattern, and the search
+    /// pattern is not changed in that case. See [`TextArea::search_history_prev`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_history_next(&mut self) -> Option<&str> {
+        self.restart_search_preview();
+        self.search.history_next()
     }
 
-    /// Get slice of line texts. This method borrows the content, but not moves. Note that the returned slice will
-    /// never be empty because an empty text means a slice containing one empty line. This is correct since any text
-    /// file must end with a newline.
+    /// Set the maximum number of patterns in [`TextArea::search_history`]. Older patterns are removed. Setting 0
+    /// disables the search history. The default value is 50.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.lines(), [""]);
-    ///
-    /// textarea.insert_char('a');
-    /// assert_eq!(textarea.lines(), ["a"]);
+    /// textarea.set_max_search_histories(2);
+    /// assert_eq!(textarea.max_search_histories(), 2);
     ///
-    /// textarea.insert_newline();
-    /// assert_eq!(textarea.lines(), ["a", ""]);
+    /// for pat in ["a", "b", "c"] {
+    ///     textarea.set_search_pattern(pat).unwrap();
+    ///     textarea.set_search_pattern("").unwrap();
+    /// }
+    /// assert_eq!(textarea.search_history(), ["c", "b"]);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_max_search_histories(&mut self, max: usize) {
+        self.search.set_max_history(max);
+    }
+
+    /// Get the maximum number of patterns in the search history. See [`TextArea::set_max_search_histories`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn max_search_histories(&self) -> usize {
+        self.search.max_history()
+    }
+
+    /// Set options of text search such as case-insensitive or whole-word matching. The options are applied on top of
+    /// the pattern set by [`TextArea::set_search_pattern`] so the pattern does not need to be modified. When a search
+    /// pattern is already set, its matches are updated immediately. The options are honored by all search methods
+    /// such as [`TextArea::search_forward`] and [`TextArea::replace_all`]. See [`SearchOptions`] for each option.
     ///
-    /// textarea.insert_char('b');
-    /// assert_eq!(textarea.lines(), ["a", "b"]);
     /// ```
-    pub fn lines(&'a self) -> &'a [String] {
-        &self.lines
+    /// use tui_textarea::{SearchOptions, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["foo Foo foobar"]);
+    /// textarea.set_search_pattern("foo").unwrap();
+    /// assert_eq!(textarea.search_matches().len(), 2);
+    ///
+    /// textarea.set_search_options(SearchOptions {
+    ///     case_insensitive: true,
+    ///     whole_word: true,
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(textarea.search_matches(), [((0, 0), (0, 3)), ((0, 4), (0, 7))]);
+    ///
+    /// // With smart case, the pattern containing an uppercase letter is case-sensitive
+    /// textarea.set_search_options(SearchOptions { smart_case: true, ..Default::default() });
+    /// textarea.set_search_pattern("Foo").unwrap();
+    /// assert_eq!(textarea.search_matches(), [((0, 4), (0, 7))]);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_options(&mut self, options: SearchOptions) {
+        self.search.set_options(options);
     }
 
-    /// Convert [`TextArea`] instance into line texts.
+    /// Get the options of text search set by [`TextArea::set_search_options`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_options(&self) -> SearchOptions {
+        self.search.options()
+    }
+
+    /// Set how the query set by [`TextArea::set_search_pattern`] is matched. See [`SearchMode`] for each mode. When a
+    /// search pattern is already set, it is rebuilt in the new mode. If the query is not a valid pattern in the new
+    /// mode, for example `1.5 (beta)` switching from [`SearchMode::Literal`] to [`SearchMode::Regex`], the search is
+    /// stopped. The default mode is [`SearchMode::Regex`].
+    ///
+    /// In [`SearchMode::Fuzzy`], [`TextArea::search_forward`] and [`TextArea::search_back`] visit the matched lines in
+    /// descending order of their scores instead of the order in the text, and move the cursor to the first matched
+    /// character. [`TextArea::search_matches`] returns the range from the first matched character to the last one in
+    /// each line. [`SearchOptions::whole_word`] is ignored in this mode.
     /// ```
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{SearchMode, TextArea};
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["nothing", "xfxxbx", "foo_bar", "fb"]);
+    /// textarea.set_search_mode(SearchMode::Fuzzy);
+    /// assert_eq!(textarea.search_mode(), SearchMode::Fuzzy);
     ///
-    /// textarea.insert_char('a');
-    /// textarea.insert_newline();
-    /// textarea.insert_char('b');
+    /// textarea.set_search_pattern("fb").unwrap();
+    /// assert_eq!(textarea.search_match_count(None), 3);
     ///
-    /// assert_eq!(textarea.into_lines(), ["a", "b"]);
+    /// // Lines are visited in the order of their scores
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (3, 0));
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (1, 1));
     /// ```
-    pub fn into_lines(self) -> Vec<String> {
-        self.lines
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_mode(&mut self, mode: SearchMode) {
+        self.search.set_mode(mode);
     }
 
-    /// Get the current cursor position. 0-base character-wise (row, col) cursor position.
+    /// Get the mode of text search set by [`TextArea::set_search_mode`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_mode(&self) -> SearchMode {
+        self.search.mode()
+    }
+
+    /// Set the scope of text search. With [`SearchScope::Selection`], the range of the current text selection is
+    /// captured and matches out of the range are neither highlighted, visited by [`TextArea::search_forward`] and
+    /// [`TextArea::search_back`], counted, nor replaced by [`TextArea::replace_next`] and [`TextArea::replace_all`].
+    /// The captured range survives cancelling the selection and follows edits of the text, so moving the cursor does
+    /// not change the scope. When nothing is selected, the scope is [`SearchScope::Document`], which is the default.
     /// ```
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{SearchScope, TextArea};
     ///
-    /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// let mut textarea = TextArea::from(["foo foo", "foo foo"]);
+    /// textarea.set_selection((0, 4), (1, 3));
+    /// textarea.set_search_scope(SearchScope::Selection);
+    /// assert_eq!(textarea.search_scope(), SearchScope::Selection);
+    /// assert_eq!(textarea.search_scope_range(), Some(((0, 4), (1, 3))));
     ///
-    /// textarea.insert_char('a');
-    /// textarea.insert_newline();
-    /// textarea.insert_char('b');
+    /// textarea.set_search_pattern("foo").unwrap();
+    /// assert_eq!(textarea.search_matches(), [((0, 4), (0, 7)), ((1, 0), (1, 3))]);
+    /// assert_eq!(textarea.replace_all("bar"), 2);
+    /// assert_eq!(textarea.lines(), ["foo bar", "bar foo"]);
     ///
-    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// textarea.set_search_scope(SearchScope::Document);
+    /// assert_eq!(textarea.search_scope_range(), None);
     /// ```
-    pub fn cursor(&self) -> (usize, usize) {
-        self.cursor
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_scope(&mut self, scope: SearchScope) {
+        self.search.scope = match scope {
+            SearchScope::Document => None,
+            SearchScope::Selection => self.selection_range(),
+        };
+        self.search.current = None;
     }
 
-    /// Set text alignment. When [`Alignment::Center`] or [`Alignment::Right`] is set, line number is automatically
-    /// disabled because those alignments don't work well with line numbers.
+    /// Get the scope of text search set by [`TextArea::set_search_scope`]. This is useful to show the scope in a status
+    /// bar.
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_scope(&self) -> SearchScope {
+        if self.search.scope.is_some() {
+            SearchScope::Selection
+        } else {
+            SearchScope::Document
+        }
+    }
+
+    /// Get the range of the text where matches are searched as a pair of start and end `(row, col)` positions. `None`
+    /// is returned when the scope is [`SearchScope::Document`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_scope_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        self.search.scope
+    }
+
+    /// Get at most `limit` lines matching the query in [`SearchMode::Fuzzy`] in descending order of their scores. Each
+    /// item is a tuple of the row, the score, and the columns of the matched characters in the line. This is useful to
+    /// draw a picker of the matches in an application. Lines with the same score are sorted by their rows. When the
+    /// search mode is not fuzzy or no text search is ongoing, this method returns an empty `Vec`.
     /// ```
-    /// use ratatui::layout::Alignment;
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{SearchMode, TextArea};
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["nothing", "xfxxbx", "foo_bar", "fb"]);
+    /// textarea.set_search_mode(SearchMode::Fuzzy);
+    /// textarea.set_search_pattern("fb").unwrap();
     ///
-    /// textarea.set_alignment(Alignment::Center);
-    /// assert_eq!(textarea.alignment(), Alignment::Center);
+    /// let matches = textarea.search_ranked_matches(2);
+    /// assert_eq!(matches.len(), 2);
+    /// let (row, _, cols) = &matches[1];
+    /// assert_eq!((*row, cols.as_slice()), (2, &[0, 4][..]));
+    /// assert!(matches[0].1 > matches[1].1);
     /// ```
-    pub fn set_alignment(&mut self, alignment: Alignment) {
-        if let Alignment::Center | Alignment::Right = alignment {
-            self.line_number_style = None;
-        }
-        self.alignment = alignment;
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_ranked_matches(&self, limit: usize) -> Vec<(usize, i32, Vec<usize>)> {
+        let Some(mut ranked) = self.search.ranked(&self.lines) else {
+            return vec![];
+        };
+        ranked.truncate(limit);
+        ranked
+            .into_iter()
+            .map(|(row, m)| {
+                let line = &self.lines.line(row);
+                let cols = m.offsets.iter().map(|&i| line[..i].chars().count()).collect();
+                (row, m.score, cols)
+            })
+            .collect()
     }
 
-    /// Get current text alignment. The default alignment is [`Alignment::Left`].
+    /// Get all non-empty matches of the pattern set by [`TextArea::set_search_pattern`] as pairs of start and end
+    /// positions. Each position is a pair of row and column, and the end position is exclusive. This is useful to show
+    /// the number of matches. When no text search is ongoing, this method returns an empty `Vec`.
+    ///
+    /// When the pattern explicitly matches a newline with `\n`, or uses `.` with the `s` flag (e.g. `(?s)`), the
+    /// pattern is matched against the entire text joined with `\n` so that a match can span multiple lines. Otherwise
+    /// the pattern is matched against each line, so classes like `\s` or `[^a]` don't match across lines.
+    ///
     /// ```
-    /// use ratatui::layout::Alignment;
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["foo bar", "baz foo", "bar"]);
     ///
-    /// assert_eq!(textarea.alignment(), Alignment::Left);
+    /// textarea.set_search_pattern("ba.").unwrap();
+    /// assert_eq!(textarea.search_matches(), [((0, 4), (0, 7)), ((1, 0), (1, 3)), ((2, 0), (2, 3))]);
+    ///
+    /// // Match across line boundaries
+    /// textarea.set_search_pattern("foo\\nbar").unwrap();
+    /// assert_eq!(textarea.search_matches(), [((1, 4), (2, 3))]);
+    /// textarea.set_search_pattern("(?s)bar.baz").unwrap();
+    /// assert_eq!(textarea.search_matches(), [((0, 4), (1, 3))]);
+    ///
+    /// // `\s` doesn't match a line break
+    /// textarea.set_search_pattern("bar\\sbaz").unwrap();
+    /// assert_eq!(textarea.search_matches(), []);
     /// ```
-    pub fn alignment(&self) -> Alignment {
-        self.alignment
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_matches(&self) -> Vec<((usize, usize), (usize, usize))> {
+        let col = |(row, offset): (usize, usize)| (row, self.lines.line(row)[..offset].chars().count());
+        self.search
+            .find_all(&self.lines)
+            .into_iter()
+            .filter(|(start, end)| start != end)
+            .map(|(start, end)| (col(start), col(end)))
+            .collect()
     }
 
-    /// Check if the textarea has a empty content.
+    /// Count non-empty matches of the pattern set by [`TextArea::set_search_pattern`] in the text. When `limit` is set,
+    /// the text is scanned only until the count reaches the limit and the limit is returned. This is cheaper than
+    /// [`TextArea::search_matches`] on a large text and is useful to show the number of matches like "1000+" in a
+    /// status bar. When no text search is ongoing, this method returns 0.
+    ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let textarea = TextArea::default();
-    /// assert!(textarea.is_empty());
+    /// let mut textarea = TextArea::from(["foo bar", "foo foo"]);
     ///
-    /// let textarea = TextArea::from(["hello"]);
-    /// assert!(!textarea.is_empty());
+    /// textarea.set_search_pattern("foo").unwrap();
+    /// assert_eq!(textarea.search_match_count(None), 3);
+    /// assert_eq!(textarea.search_match_count(Some(2)), 2);
+    /// assert_eq!(textarea.search_match_count(Some(10)), 3);
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.lines == [""]
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_match_count(&self, limit: Option<usize>) -> usize {
+        self.search.count(&self.lines, limit)
     }
 
-    /// Get the yanked text. Text is automatically yanked when deleting strings by [`TextArea::delete_line_by_head`],
-    /// [`TextArea::delete_line_by_end`], [`TextArea::delete_word`], [`TextArea::delete_next_word`],
-    /// [`TextArea::delete_str`], [`TextArea::copy`], and [`TextArea::cut`]. When multiple lines were yanked, they are
-    /// always joined with `\n`.
+    /// Get the index of the match which the last [`TextArea::search_forward`] or [`TextArea::search_back`] moved the
+    /// cursor to, in the non-empty matches returned by [`TextArea::search_matches`]. This is useful to show the
+    /// position like "match 3/17" in a status bar with [`TextArea::search_match_count`]. It returns `None` when no
+    /// search moved the cursor since the pattern was set, or the match no longer exists after the text was modified.
+    /// Only the text until the current match is searched, so this is cheap to call on every rendering when the match is
+    /// near the top of the text.
+    ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc"]);
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.yank_text(), "abc");
+    /// let mut textarea = TextArea::from(["foo bar foo", "foo"]);
     ///
-    /// // Multiple lines are joined with \n
-    /// let mut textarea = TextArea::from(["abc", "def"]);
-    /// textarea.delete_str(5);
-    /// assert_eq!(textarea.yank_text(), "abc\nd");
+    /// textarea.set_search_pattern("foo").unwrap();
+    /// assert_eq!(textarea.current_search_match(), None);
+    ///
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (0, 8));
+    /// assert_eq!(textarea.current_search_match(), Some(1));
+    /// assert_eq!(textarea.search_match_count(None), 3);
+    ///
+    /// textarea.search_back(false);
+    /// assert_eq!(textarea.current_search_match(), Some(0));
+    ///
+    /// // Changing the pattern resets the current match
+    /// textarea.set_search_pattern("bar").unwrap();
+    /// assert_eq!(textarea.current_search_match(), None);
     /// ```
-    pub fn yank_text(&self) -> String {
-        self.yank.to_string()
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn current_search_match(&self) -> Option<usize> {
+        let current = self.search.current?;
+        self.search.index_of(&self.lines, current)
     }
 
-    /// Set a yanked text. The text can be inserted by [`TextArea::paste`]. `\n` and `\r\n` are recognized as newline
-    /// but `\r` isn't.
+    /// Search the pattern set by [`TextArea::set_search_pattern`] forward and move the cursor to the next match
+    /// position based on the current cursor position. Text search wraps around a text buffer. It returns `true` when
+    /// some match was found. Otherwise it returns `false`.
+    ///
+    /// The `match_cursor` parameter represents if the search matches to the current cursor position or not. When `true`
+    /// is set and the cursor position matches to the pattern, the cursor will not move. When `false`, the cursor will
+    /// move to the next match ignoring the match at the current position.
+    ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["hello", "helloo", "hellooo"]);
     ///
-    /// textarea.set_yank_text("hello\nworld");
-    /// textarea.paste();
-    /// assert_eq!(textarea.lines(), ["hello", "world"]);
+    /// textarea.set_search_pattern("hello+").unwrap();
+    ///
+    /// // Move to next position
+    /// let match_found = textarea.search_forward(false);
+    /// assert!(match_found);
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// // Since the cursor position matches to "hello+", it does not move
+    /// textarea.search_forward(true);
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// // When `match_current` parameter is set to `false`, match at the cursor position is ignored
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    ///
+    /// // Text search wrap around the buffer
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// // `false` is returned when no match was found
+    /// textarea.set_search_pattern("bye+").unwrap();
+    /// let match_found = textarea.search_forward(false);
+    /// assert!(!match_found);
     /// ```
-    pub fn set_yank_text(&mut self, text: impl Into<String>) {
-        // `str::lines` is not available since it strips a newline at end
-        let lines: Vec<_> = text
-            .into()
-            .split('\n')
-            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
-            .collect();
-        self.yank = lines.into();
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_forward(&mut self, match_cursor: bool) -> bool {
+        if let Some(cursor) = self.search.forward(&self.lines, self.cursor, match_cursor) {
+            if cursor != self.cursor {
+                self.jumps.push(self.cursor);
+                self.ghost_text = None;
+            }
+            self.cursor = cursor;
+            self.open_fold_at_cursor();
+            self.search.current = self.search.match_at(&self.lines, cursor);
+            true
+        } else {
+            self.search.current = None;
+            false
+        }
     }
 
-    /// Set a regular expression pattern for text search. Setting an empty string stops the text search.
-    /// When a valid pattern is set, all matches will be highlighted in the textarea. Note that the cursor does not
-    /// move. To move the cursor, use [`TextArea::search_forward`] and [`TextArea::search_back`].
-    ///
-    /// Grammar of regular expression follows [regex crate](https://docs.rs/regex/latest/regex). Patterns don't match
-    /// to newlines so match passes across no newline.
+    /// Search the pattern set by [`TextArea::set_search_pattern`] backward and move the cursor to the next match
+    /// position based on the current cursor position. Text search wraps around a text buffer. It returns `true` when
+    /// some match was found. Otherwise it returns `false`.
     ///
-    /// When the pattern is invalid, the search pattern will not be updated and an error will be returned.
+    /// The `match_cursor` parameter represents if the search matches to the current cursor position or not. When `true`
+    /// is set and the cursor position matches to the pattern, the cursor will not move. When `false`, the cursor will
+    /// move to the next match ignoring the match at the current position.
     ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["hello, world", "goodbye, world"]);
+    /// let mut textarea = TextArea::from(["hello", "helloo", "hellooo"]);
     ///
-    /// // Search "world"
-    /// textarea.set_search_pattern("world").unwrap();
+    /// textarea.set_search_pattern("hello+").unwrap();
     ///
-    /// assert_eq!(textarea.cursor(), (0, 0));
-    /// textarea.search_forward(false);
-    /// assert_eq!(textarea.cursor(), (0, 7));
-    /// textarea.search_forward(false);
-    /// assert_eq!(textarea.cursor(), (1, 9));
+    /// // Move to next position with wrapping around the text buffer
+    /// let match_found = textarea.search_back(false);
+    /// assert!(match_found);
+    /// assert_eq!(textarea.cursor(), (2, 0));
     ///
-    /// // Stop the text search
-    /// textarea.set_search_pattern("");
+    /// // Since the cursor position matches to "hello+", it does not move
+    /// textarea.search_back(true);
+    /// assert_eq!(textarea.cursor(), (2, 0));
     ///
-    /// // Invalid search pattern
-    /// assert!(textarea.set_search_pattern("(hello").is_err());
+    /// // When `match_current` parameter is set to `false`, match at the cursor position is ignored
+    /// textarea.search_back(false);
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// // `false` is returned when no match was found
+    /// textarea.set_search_pattern("bye+").unwrap();
+    /// let match_found = textarea.search_back(false);
+    /// assert!(!match_found);
     /// ```
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
-    pub fn set_search_pattern(&mut self, query: impl AsRef<str>) -> Result<(), regex::Error> {
-        self.search.set_pattern(query.as_ref())
+    pub fn search_back(&mut self, match_cursor: bool) -> bool {
+        if let Some(cursor) = self.search.back(&self.lines, self.cursor, match_cursor) {
+            if cursor != self.cursor {
+                self.jumps.push(self.cursor);
+                self.ghost_text = None;
+            }
+            self.cursor = cursor;
+            self.open_fold_at_cursor();
+            self.search.current = self.search.match_at(&self.lines, cursor);
+            true
+        } else {
+            self.search.current = None;
+            false
+        }
     }
 
-    /// Get a regular expression which was set by [`TextArea::set_search_pattern`]. When no text search is ongoing, this
-    /// method returns `None`.
+    /// Preview the next match of the pattern set by [`TextArea::set_search_pattern`] without moving the cursor. On the
+    /// next rendering, the viewport follows the match instead of the cursor and the match is highlighted as the current
+    /// match, while the cursor stays at its position. This is useful for incremental search which shows matches while typing
+    /// the query but keeps the user's place until the search is confirmed. It returns the `(row, col)` position of the
+    /// previewed match, or `None` when no match was found.
+    ///
+    /// The first preview looks for a match from the cursor position including the match at the cursor. Following
+    /// previews move to the next match when `forward` is `true`, or to the previous match otherwise. After the pattern
+    /// is changed, matches are previewed from the cursor again. [`TextArea::search_commit`] moves the cursor to the
+    /// previewed match and [`TextArea::search_cancel`] restores the viewport scrolled before the preview. Moving the
+    /// cursor or editing the text also finishes the preview, and the viewport follows the cursor again.
+    /// ```
+    /// use tui_textarea::TextArea;
     ///
+    /// let mut textarea = TextArea::from(["foo", "bar", "foo", "bar"]);
+    /// textarea.set_search_pattern("bar").unwrap();
+    ///
+    /// assert_eq!(textarea.search_preview(true), Some((1, 0)));
+    /// assert_eq!(textarea.search_preview(true), Some((3, 0)));
+    /// assert_eq!(textarea.cursor(), (0, 0)); // The cursor does not move while previewing
+    /// assert_eq!(textarea.previewed_search_match(), Some((3, 0)));
+    ///
+    /// // Move the cursor to the previewed match
+    /// assert!(textarea.search_commit());
+    /// assert_eq!(textarea.cursor(), (3, 0));
+    /// assert_eq!(textarea.previewed_search_match(), None);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_preview(&mut self, forward: bool) -> Option<(usize, usize)> {
+        if self.active_search_preview().is_none() {
+            self.search_preview = None;
+        }
+        let edits = self.edit_count();
+        let preview = self.search_preview.get_or_insert_with(|| SearchPreview {
+            pos: None,
+            scroll: (self.viewport.scroll_top(), self.viewport.skip()),
+            cursor: self.cursor,
+            edits,
+        });
+        let (origin, match_cursor) = match preview.pos {
+            Some((row, col)) if row < self.lines.len() => ((row, col), false),
+            _ => (self.cursor, true),
+        };
+        let found = if forward {
+            self.search.forward(&self.lines, origin, match_cursor)
+        } else {
+            self.search.back(&self.lines, origin, match_cursor)
+        };
+        self.search.current = found.and_then(|pos| self.search.match_at(&self.lines, pos));
+        preview.pos = found;
+        found
+    }
+
+    /// Move the cursor to the match previewed by [`TextArea::search_preview`] and finish the preview. The viewport keeps
+    /// showing the match. The position before the move is recorded in the jump list. It returns `true` when the cursor
+    /// moved to a previewed match. Otherwise it returns `false`.
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_commit(&mut self) -> bool {
+        let Some(cursor) = self.previewed_search_match() else {
+            self.search_preview = None;
+            return false;
+        };
+        self.search_preview = None;
+        if cursor != self.cursor {
+            self.jumps.push(self.cursor);
+            self.ghost_text = None;
+        }
+        self.cursor = cursor;
+        self.open_fold_at_cursor();
+        true
+    }
+
+    /// Finish the preview started by [`TextArea::search_preview`] without moving the cursor. The viewport of the textarea
+    /// is scrolled back to the position before the preview and the current match is cleared.
     /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::Widget as _;
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from((0..10).map(|i| i.to_string()));
+    /// let r = Rect::new(0, 0, 10, 3);
+    /// let mut b = Buffer::empty(r.clone());
     ///
-    /// assert!(textarea.search_pattern().is_none());
-    /// textarea.set_search_pattern("hello+").unwrap();
-    /// assert!(textarea.search_pattern().is_some());
-    /// assert_eq!(textarea.search_pattern().unwrap().as_str(), "hello+");
+    /// textarea.set_search_pattern("8").unwrap();
+    /// textarea.search_preview(true);
+    /// textarea.widget().render(r.clone(), &mut b);
+    /// assert_eq!(textarea.viewport_rect().0, 6); // Scrolled to show the match
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// textarea.search_cancel();
+    /// textarea.widget().render(r.clone(), &mut b);
+    /// assert_eq!(textarea.viewport_rect().0, 0);
+    /// assert_eq!(textarea.current_search_match(), None);
     /// ```
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
-    pub fn search_pattern(&self) -> Option<&regex::Regex> {
-        self.search.pat.as_ref()
+    pub fn search_cancel(&mut self) {
+        // The viewport is not restored when the preview was already finished by moving the cursor or editing the text
+        if let Some(preview) = self.active_search_preview() {
+            let ((row, col), skip) = preview.scroll;
+            self.viewport.set_scroll(row, col, skip);
+            self.search.current = None;
+        }
+        self.search_preview = None;
+    }
+
+    /// Get the `(row, col)` position of the match previewed by [`TextArea::search_preview`]. `None` is returned when no
+    /// preview is ongoing or no match was found.
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn previewed_search_match(&self) -> Option<(usize, usize)> {
+        let (row, col) = self.active_search_preview()?.pos?;
+        let line = self.lines.get(row)?;
+        Some((row, min(col, line.chars().count())))
+    }
+
+    // The preview is finished by moving the cursor or editing the text after it started
+    #[cfg(feature = "search")]
+    fn active_search_preview(&self) -> Option<SearchPreview> {
+        self.search_preview
+            .filter(|preview| preview.cursor == self.cursor && preview.edits == self.edit_count())
     }
 
-    /// Search the pattern set by [`TextArea::set_search_pattern`] forward and move the cursor to the next match
-    /// position based on the current cursor position. Text search wraps around a text buffer. It returns `true` when
-    /// some match was found. Otherwise it returns `false`.
+    /// Replace the match of the pattern set by [`TextArea::set_search_pattern`] at or containing the cursor position, or
+    /// the next match after the cursor, with the `replacement` text. Then the cursor moves to the next match. Text search wraps
+    /// around a text buffer. It returns `true` when some match was replaced. Otherwise it returns `false`.
     ///
-    /// The `match_cursor` parameter represents if the search matches to the current cursor position or not. When `true`
-    /// is set and the cursor position matches to the pattern, the cursor will not move. When `false`, the cursor will
-    /// move to the next match ignoring the match at the current position.
+    /// The replacement text can refer capture groups of the pattern with `$1` or `${name}`. Use `$$` for a literal `$`.
+    /// Empty matches are never replaced. A match of a multi-line pattern spanning lines is replaced as a whole. The
+    /// current text selection is cancelled.
     ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["hello", "helloo", "hellooo"]);
-    ///
-    /// textarea.set_search_pattern("hello+").unwrap();
-    ///
-    /// // Move to next position
-    /// let match_found = textarea.search_forward(false);
-    /// assert!(match_found);
-    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// let mut textarea = TextArea::from(["hello, world", "goodbye, world"]);
     ///
-    /// // Since the cursor position matches to "hello+", it does not move
-    /// textarea.search_forward(true);
+    /// textarea.set_search_pattern(r"(\w+), world").unwrap();
+    /// assert!(textarea.replace_next("$1, ratatui"));
+    /// assert_eq!(textarea.lines(), ["hello, ratatui", "goodbye, world"]);
     /// assert_eq!(textarea.cursor(), (1, 0));
     ///
-    /// // When `match_current` parameter is set to `false`, match at the cursor position is ignored
-    /// textarea.search_forward(false);
-    /// assert_eq!(textarea.cursor(), (2, 0));
-    ///
-    /// // Text search wrap around the buffer
-    /// textarea.search_forward(false);
-    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// assert!(textarea.replace_next("bye"));
+    /// assert_eq!(textarea.lines(), ["hello, ratatui", "bye"]);
     ///
     /// // `false` is returned when no match was found
-    /// textarea.set_search_pattern("bye+").unwrap();
-    /// let match_found = textarea.search_forward(false);
-    /// assert!(!match_found);
+    /// assert!(!textarea.replace_next("bye"));
     /// ```
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
-    pub fn search_forward(&mut self, match_cursor: bool) -> bool {
-        if let Some(cursor) = self.search.forward(&self.lines, self.cursor, match_cursor) {
-            self.cursor = cursor;
-            true
-        } else {
-            false
+    pub fn replace_next(&mut self, replacement: impl AsRef<str>) -> bool {
+        let Some((start, end)) = self.search.find_non_empty(&self.lines, self.cursor, true) else {
+            return false;
+        };
+        let replacement = self.search.expand(&self.lines, start, replacement.as_ref());
+        let edits = Self::replace_edits(&self.lines, start, end, &replacement);
+
+        self.cancel_selection();
+        let before = self.cursor_pos();
+        let after = edits.last().map(|e| e.cursor_after()).unwrap();
+        let after = Pos::new(after.0, after.1, 0);
+        self.push_batch(edits, before, after);
+
+        // The replaced text is not matched again even when it matches the pattern
+        if let Some(((row, start), _)) = self.search.find_non_empty(&self.lines, self.cursor, false) {
+            self.cursor = (row, self.lines.line(row)[..start].chars().count());
+            self.open_fold_at_cursor();
         }
+        true
     }
 
-    /// Search the pattern set by [`TextArea::set_search_pattern`] backward and move the cursor to the next match
-    /// position based on the current cursor position. Text search wraps around a text buffer. It returns `true` when
-    /// some match was found. Otherwise it returns `false`.
-    ///
-    /// The `match_cursor` parameter represents if the search matches to the current cursor position or not. When `true`
-    /// is set and the cursor position matches to the pattern, the cursor will not move. When `false`, the cursor will
-    /// move to the next match ignoring the match at the current position.
+    /// Replace all matches of the pattern set by [`TextArea::set_search_pattern`] with the `replacement` text and
+    /// return the number of replaced matches. The replacement text can refer capture groups as
+    /// [`TextArea::replace_next`]. Empty matches are never replaced. All replacements are undone at once by
+    /// [`TextArea::undo`]. The cursor moves to the start of the first replaced text.
     ///
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["hello", "helloo", "hellooo"]);
-    ///
-    /// textarea.set_search_pattern("hello+").unwrap();
-    ///
-    /// // Move to next position with wrapping around the text buffer
-    /// let match_found = textarea.search_back(false);
-    /// assert!(match_found);
-    /// assert_eq!(textarea.cursor(), (2, 0));
-    ///
-    /// // Since the cursor position matches to "hello+", it does not move
-    /// textarea.search_back(true);
-    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// let mut textarea = TextArea::from(["a-1 b-2", "c-3"]);
     ///
-    /// // When `match_current` parameter is set to `false`, match at the cursor position is ignored
-    /// textarea.search_back(false);
-    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// textarea.set_search_pattern(r"(?<key>\w)-(\d)").unwrap();
+    /// assert_eq!(textarea.replace_all("$2=${key}"), 3);
+    /// assert_eq!(textarea.lines(), ["1=a 2=b", "3=c"]);
     ///
-    /// // `false` is returned when no match was found
-    /// textarea.set_search_pattern("bye+").unwrap();
-    /// let match_found = textarea.search_back(false);
-    /// assert!(!match_found);
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["a-1 b-2", "c-3"]);
     /// ```
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
-    pub fn search_back(&mut self, match_cursor: bool) -> bool {
-        if let Some(cursor) = self.search.back(&self.lines, self.cursor, match_cursor) {
-            self.cursor = cursor;
-            true
+    pub fn replace_all(&mut self, replacement: impl AsRef<str>) -> usize {
+        let replacements = self.search.replacements(&self.lines, replacement.as_ref());
+        let Some(&((row, start), _)) = replacements.first().map(|(m, _)| m) else {
+            return 0;
+        };
+        let after = Pos::new(row, self.lines.line(row)[..start].chars().count(), start);
+
+        // Replace matches from the end of the buffer so that positions of preceding matches are not shifted
+        let mut edits = vec![];
+        for ((start, end), dst) in replacements.iter().rev() {
+            edits.extend(Self::replace_edits(&self.lines, *start, *end, dst));
+        }
+
+        self.cancel_selection();
+        let before = self.cursor_pos();
+        self.push_batch(edits, before, after);
+        replacements.len()
+    }
+
+    // Edits to replace the text between the (row, byte offset) positions with the replacement text. The edits are not
+    // applied
+    #[cfg(feature = "search")]
+    fn replace_edits(
+        lines: &Lines,
+        (start_row, start): (usize, usize),
+        (end_row, end): (usize, usize),
+        replacement: &str,
+    ) -> Vec<Edit> {
+        let col = lines.col_of_offset(start_row, start);
+        let start_pos = Pos::new(start_row, col, start);
+        let end_pos = Pos::new(end_row, lines.col_of_offset(end_row, end), end);
+        let deleted = if start_row == end_row {
+            EditKind::DeleteStr(lines.slice(start_row, start..end).into_owned())
         } else {
-            false
+            let mut chunk = vec![lines.slice(start_row, start..lines.line_len(start_row)).into_owned()];
+            chunk.extend(lines.iter_in(start_row + 1..end_row).map(Cow::into_owned));
+            chunk.push(lines.slice(end_row, 0..end).into_owned());
+            EditKind::DeleteChunk(chunk)
+        };
+        let mut edits = vec![Edit::new(deleted, end_pos, start_pos.clone())];
+
+        let mut chunk: Vec<_> = replacement
+            .split('\n')
+            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+            .collect();
+        let last = chunk.last().unwrap();
+        let (kind, after) = if chunk.len() == 1 {
+            let after = Pos::new(start_row, col + last.chars().count(), start + last.len());
+            (EditKind::InsertStr(chunk.remove(0)), after)
+        } else {
+            let after = Pos::new(start_row + chunk.len() - 1, last.chars().count(), last.len());
+            (EditKind::InsertChunk(chunk), after)
+        };
+        if !matches!(&kind, EditKind::InsertStr(s) if s.is_empty()) {
+            edits.push(Edit::new(kind, start_pos, after));
         }
+        edits
     }
 
     /// Get the text style at matches of text search. The default style is colored with blue in background.
@@ -2443,9 +10878,40 @@ impl<'a> TextArea<'a> {
         self.search.style = style;
     }
 
+    /// Set the text style of the match which the last [`TextArea::search_forward`] or [`TextArea::search_back`] moved
+    /// the cursor to. It is rendered instead of the style set by [`TextArea::set_search_style`] so that users can tell
+    /// which match the cursor is on. By default, the current match is styled the same as other matches.
+    ///
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.current_search_match_style(), textarea.search_style());
+    ///
+    /// let yellow_bg = Style::default().bg(Color::Yellow);
+    /// textarea.set_current_search_match_style(yellow_bg);
+    /// assert_eq!(textarea.current_search_match_style(), yellow_bg);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_current_search_match_style(&mut self, style: Style) {
+        self.search.current_style = Some(style);
+    }
+
+    /// Get the text style of the current match of text search. See [`TextArea::set_current_search_match_style`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn current_search_match_style(&self) -> Style {
+        self.search.current_style.unwrap_or(self.search.style)
+    }
+
     /// Scroll the textarea. See [`Scrolling`] for the argument.
     /// The cursor will not move until it goes out the viewport. When the cursor position is outside the viewport after scroll,
     /// the cursor position will be adjusted to stay in the viewport using the same logic as [`CursorMove::InViewport`].
+    /// The cursor is not adjusted when following the cursor is disabled by [`TextArea::set_cursor_follow`]. The viewport
+    /// cannot be scrolled past the first or last line. When following the cursor is disabled, the viewport is not
+    /// scrolled further once the last line reaches the bottom of the viewport, unless soft wrap is enabled.
     ///
     /// ```
     /// # use ratatui::buffer::Buffer;
@@ -2485,12 +10951,319 @@ impl<'a> TextArea<'a> {
         self.scroll_with_shift(scrolling.into(), self.selection_start.is_some());
     }
 
+    /// Scroll the viewport down by half of its height and move the cursor down by the same number of lines, like
+    /// `Ctrl+D` of Vim. Unlike [`Scrolling::HalfPageDown`], the cursor keeps its position in the viewport. The height
+    /// is the one where the textarea was rendered last. Before the first rendering, this method does nothing.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// // Let's say terminal height is 8
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(2, 0));
+    /// textarea.scroll_half_page_down();
+    /// assert_eq!(textarea.cursor(), (6, 0));
+    /// assert_eq!(textarea.viewport_rect().0, 4);
+    /// ```
+    pub fn scroll_half_page_down(&mut self) {
+        self.scroll_half_page(false);
+    }
+
+    /// Scroll the viewport up by half of its height and move the cursor up by the same number of lines, like `Ctrl+U`
+    /// of Vim. See [`TextArea::scroll_half_page_down`] for more details.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// // Let's say terminal height is 8
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// textarea.scroll_half_page_up();
+    /// assert_eq!(textarea.cursor(), (15, 0));
+    /// assert_eq!(textarea.viewport_rect().0, 8);
+    /// ```
+    pub fn scroll_half_page_up(&mut self) {
+        self.scroll_half_page(true);
+    }
+
+    fn scroll_half_page(&mut self, up: bool) {
+        let (_, _, _, height) = self.viewport.rect();
+        let rows = height as usize / 2;
+        if rows == 0 {
+            return;
+        }
+        let (row, col) = self.cursor;
+        let row = if up {
+            row.saturating_sub(rows)
+        } else {
+            min(row + rows, self.lines.len() - 1)
+        };
+        let shift = self.selection_start.is_some();
+        self.move_cursor_impl(CursorMove::Jump(row as u64, col as u64), shift, false);
+        let scrolling = if up {
+            Scrolling::HalfPageUp
+        } else {
+            Scrolling::HalfPageDown
+        };
+        self.scroll_with_shift(scrolling, shift);
+    }
+
     fn scroll_with_shift(&mut self, scrolling: Scrolling, shift: bool) {
         if shift && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
+            self.start_selection();
+        }
+        // Scroll by display rows so that folded lines are counted as one row
+        let folds = self.shown_folds();
+        let (top, _, _, _) = self.viewport.rect();
+        self.viewport.set_row(fold::display_row(&folds, top as usize) as u64);
+        let lines_len = fold::display_row(&folds, self.lines.len());
+        scrolling.scroll(&mut self.viewport, lines_len);
+        if !self.cursor_follow && !self.wrap() {
+            // Without moving the cursor, nothing pulls the viewport back. Keep the last line at the bottom so that no
+            // empty row is left after it
+            let (_, _, _, height) = self.viewport.rect();
+            let max_row = lines_len.saturating_sub(max(height, 1).into());
+            self.viewport.clamp_row(max_row as u64);
+        }
+        let (top, _, _, _) = self.viewport.rect();
+        self.viewport.set_row(fold::row_at_display(&folds, top as usize) as u64);
+        if self.cursor_follow {
+            self.move_cursor_with_shift(CursorMove::InViewport, shift);
+            self.keep_scroll_padding(shift);
+        }
+        if self.scroll_follow {
+            self.following = self.is_bottom_visible();
+        }
+    }
+
+    /// Enable or disable scrolling the viewport to follow the cursor. When disabled, [`TextArea::scroll`] moves only
+    /// the viewport and keeps the cursor position, and rendering does not scroll the viewport back to the cursor. This
+    /// is useful to let users peek at other parts of the text while typing. By default, the viewport follows the cursor.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorMove, Scrolling, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.widget().render(r.clone(), &mut b);
+    ///
+    /// textarea.set_cursor_follow(false);
+    /// textarea.scroll(Scrolling::Top);
+    /// # textarea.widget().render(r.clone(), &mut b);
+    ///
+    /// // The cursor stays at the last line
+    /// assert_eq!(textarea.cursor(), (19, 0));
+    ///
+    /// // Move the viewport back to the cursor on the next render
+    /// textarea.set_cursor_follow(true);
+    /// ```
+    pub fn set_cursor_follow(&mut self, enabled: bool) {
+        self.cursor_follow = enabled;
+    }
+
+    /// Get if the viewport follows the cursor. See [`TextArea::set_cursor_follow`].
+    pub fn cursor_follow(&self) -> bool {
+        self.cursor_follow
+    }
+
+    /// Set the minimum number of rows and columns kept visible around the cursor, like `scrolloff` and `sidescrolloff`
+    /// options of Vim. The viewport is scrolled before the cursor reaches its edge so that the context around the
+    /// cursor is visible. The padding is not applied at the start and the end of the text. Padding larger than half of
+    /// the viewport keeps the cursor at the center. By default, no padding is set.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// textarea.set_scroll_padding(2, 0);
+    /// assert_eq!(textarea.scroll_padding(), (2, 0));
+    ///
+    /// let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.move_cursor(CursorMove::Jump(6, 0));
+    /// textarea.widget().render(r.clone(), &mut b);
+    ///
+    /// // Two rows below the cursor are visible
+    /// let (top_row, _, _, _) = textarea.viewport_rect();
+    /// assert_eq!(top_row, 1);
+    /// ```
+    pub fn set_scroll_padding(&mut self, rows: u16, cols: u16) {
+        self.scroll_padding = (rows, cols);
+    }
+
+    /// Get the minimum number of rows and columns kept visible around the cursor. See
+    /// [`TextArea::set_scroll_padding`].
+    pub fn scroll_padding(&self) -> (u16, u16) {
+        self.scroll_padding
+    }
+
+    /// Scroll the viewport so that the cursor line is put at the top, the center, or the bottom of the viewport on the
+    /// next rendering, like `zt`, `zz`, and `zb` commands of Vim. The viewport never scrolls before the start of the
+    /// text. This is useful to show a search match in the middle of the screen after moving the cursor to it. The
+    /// cursor does not move. After the rendering, the viewport follows the cursor as usual.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{CursorAlign, CursorMove, TextArea};
+    ///
+    /// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+    /// let r = Rect { x: 0, y: 0, width: 24, height: 5 };
+    /// let mut b = Buffer::empty(r.clone());
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(10, 0));
+    /// textarea.scroll_cursor(CursorAlign::Center);
+    /// textarea.widget().render(r.clone(), &mut b);
+    ///
+    /// // Rows 8..13 are visible and the cursor is at the center
+    /// assert_eq!(textarea.viewport_rect().0, 8);
+    ///
+    /// textarea.scroll_cursor(CursorAlign::Top);
+    /// textarea.widget().render(r.clone(), &mut b);
+    /// assert_eq!(textarea.viewport_rect().0, 10);
+    /// ```
+    pub fn scroll_cursor(&mut self, align: CursorAlign) {
+        self.cursor_align.set(Some(align));
+    }
+
+    // Alignment requested by `scroll_cursor`. It is consumed by the rendering
+    pub(crate) fn take_cursor_align(&self) -> Option<CursorAlign> {
+        self.cursor_align.take()
+    }
+
+    // Position which the viewport follows on rendering. It is the match previewed by `search_preview` while previewing
+    pub(crate) fn scroll_target(&self) -> (usize, usize) {
+        #[cfg(feature = "search")]
+        if let Some(pos) = self.previewed_search_match() {
+            return pos;
+        }
+        self.cursor
+    }
+
+    // After scrolling the viewport, move the cursor out of the scroll padding. Otherwise the next rendering would scroll
+    // the viewport back to keep the padding
+    fn keep_scroll_padding(&mut self, shift: bool) {
+        let (pad_rows, pad_cols) = self.scroll_padding;
+        if (pad_rows, pad_cols) == (0, 0) {
+            return;
+        }
+        let (_, _, width, height) = self.viewport.rect();
+        if width == 0 || height == 0 {
+            return; // Not rendered yet
+        }
+        let (top_row, top_col, _, bottom_col) = self.viewport.position();
+        // Rows are counted by display rows so that folded lines are counted as one row
+        let folds = self.shown_folds();
+        let display = |row: usize| fold::display_row(&folds, row) as u64;
+        let top_row = display(top_row as usize);
+        let bottom_row = top_row + u64::from(height) - 1;
+        let last_row = display(self.lines.len() - 1);
+        let pad_rows = u64::from(min(pad_rows, height.saturating_sub(1) / 2));
+        let pad_cols = u64::from(min(pad_cols, width.saturating_sub(1) / 2));
+
+        let (mut row, mut col) = (display(self.cursor.0), self.cursor.1 as u64);
+        if top_row > 0 {
+            row = max(row, top_row + pad_rows);
+        }
+        if bottom_row < last_row {
+            row = min(row, bottom_row.saturating_sub(pad_rows));
+        }
+        if top_col > 0 {
+            col = max(col, top_col + pad_cols);
+        }
+        col = min(col, bottom_col.saturating_sub(pad_cols));
+        let row = fold::row_at_display(&folds, min(row, last_row) as usize) as u64;
+        let col = min(col, self.lines.char_count(row as usize) as u64);
+        if (row as usize, col as usize) != self.cursor {
+            self.move_cursor_impl(CursorMove::Jump(row, col), shift, false);
         }
-        scrolling.scroll(&mut self.viewport);
-        self.move_cursor_with_shift(CursorMove::InViewport, shift);
+    }
+
+    /// Check if the last row of the text is visible in the viewport.
+    fn is_bottom_visible(&self) -> bool {
+        let (top, _, width, height) = self.viewport.rect();
+        let last = self.lines.len() - 1;
+        if height == 0 || top >= last as u64 {
+            return true; // Not rendered yet or scrolled past the end
+        }
+        let top = top as usize;
+        let Some(wrapper) = self.wrapper(width) else {
+            let folds = self.shown_folds();
+            return fold::display_row(&folds, top) + height as usize > fold::display_row(&folds, last);
+        };
+        let visible = self.viewport.skip() as usize + height as usize;
+        let mut rows = 0;
+        for line in self.lines.iter_in(top..self.lines.len()) {
+            rows += wrapper.height(&line, false);
+            if rows > visible {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Enable or disable follow-tail mode. While following, the viewport is pinned to the last line of the text on
+    /// rendering regardless of the cursor position, so lines appended to the text are always visible like a log
+    /// viewer. Following is disengaged when the textarea is scrolled up with [`TextArea::scroll`] and is re-engaged
+    /// when it is scrolled back to the bottom. By default, follow-tail mode is disabled.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{Scrolling, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_scroll_follow(true);
+    /// assert!(textarea.is_following());
+    ///
+    /// for i in 0..20 {
+    ///     textarea.insert_str(format!("line {}\n", i));
+    /// }
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// // Scrolling up disengages following
+    /// textarea.scroll(Scrolling::PageUp);
+    /// assert!(!textarea.is_following());
+    ///
+    /// // Scrolling back to the bottom re-engages following
+    /// textarea.scroll(Scrolling::PageDown);
+    /// assert!(textarea.is_following());
+    /// ```
+    pub fn set_scroll_follow(&mut self, enabled: bool) {
+        self.scroll_follow = enabled;
+        self.following = enabled;
+    }
+
+    /// Get if follow-tail mode is enabled by [`TextArea::set_scroll_follow`]. Note that this returns `true` even if
+    /// following is disengaged by scrolling up. Use [`TextArea::is_following`] to know the current state.
+    pub fn scroll_follow(&self) -> bool {
+        self.scroll_follow
+    }
+
+    /// Get if the viewport is currently following the last line of the text. This is `true` when follow-tail mode is
+    /// enabled and the textarea is not scrolled up.
+    pub fn is_following(&self) -> bool {
+        self.scroll_follow && self.following
     }
 
     pub fn toggle_line_numbers(&mut self) {
@@ -2506,6 +11279,87 @@ impl<'a> TextArea<'a> {
     pub fn fullscreen(&self) -> Fullscreen {
         self.fullscreen
     }
+
+    /// Enable or disable soft wrap. When enabled, lines longer than the width of the textarea are wrapped onto
+    /// multiple display rows instead of scrolling the textarea horizontally. While wrapping is enabled,
+    /// [`CursorMove::Up`] and [`CursorMove::Down`] move the cursor by display rows. Lines are wrapped after whitespaces
+    /// so that words are kept in one row, and a word longer than a row is wrapped at grapheme boundaries. Grapheme
+    /// clusters such as wide characters, emoji sequences, and tabs are never split across rows. By default, soft wrap is
+    /// disabled.
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget;
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abcdefghij"]);
+    /// textarea.set_wrap(true);
+    /// assert!(textarea.wrap());
+    ///
+    /// // Let's say terminal width is 4. The line is displayed as "abcd", "efgh", "ij"
+    /// # let r = Rect { x: 0, y: 0, width: 4, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.widget().render(r, &mut b);
+    ///
+    /// // Cursor moves by display rows
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.cursor(), (0, 4));
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.cursor(), (0, 8));
+    /// ```
+    pub fn set_wrap(&mut self, enabled: bool) {
+        self.wrap = enabled;
+    }
+
+    /// Get if soft wrap is enabled or not.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert!(!textarea.wrap());
+    /// ```
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Set the indicator put at the start of each continuation row of a soft-wrapped line, with its style. Passing
+    /// `None` removes the indicator. By default, no indicator is shown.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let style = Style::default().fg(Color::DarkGray);
+    ///
+    /// textarea.set_wrap_indicator(Some(("↪".to_string(), style)));
+    /// assert_eq!(textarea.wrap_indicator(), Some(("↪", style)));
+    /// textarea.set_wrap_indicator(None);
+    /// assert_eq!(textarea.wrap_indicator(), None);
+    /// ```
+    pub fn set_wrap_indicator(&mut self, indicator: Option<(String, Style)>) {
+        self.wrap_indicator = indicator;
+    }
+
+    /// Get the indicator of soft-wrapped lines and its style if set.
+    pub fn wrap_indicator(&self) -> Option<(&str, Style)> {
+        self.wrap_indicator.as_ref().map(|(s, style)| (s.as_str(), *style))
+    }
+
+    pub(crate) fn wrapper(&self, width: u16) -> Option<Wrapper> {
+        if !self.wrap || width == 0 {
+            return None;
+        }
+        let gutter = self.gutter_width();
+        let indent = self.wrap_indicator.as_ref().map(|(s, _)| s.width()).unwrap_or(0);
+        let width = (width as usize).saturating_sub(gutter);
+        Some(Wrapper::new(
+            width,
+            indent,
+            self.tab_len,
+            self.display_mask(),
+            self.render_control_chars,
+        ))
+    }
 }
 
 #[cfg(test)]
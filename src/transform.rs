@@ -0,0 +1,193 @@
+/// How text is transformed by [`crate::TextArea::transform_selection`]. Cases of letters are converted with Unicode
+/// case mapping so non-ASCII letters are also converted.
+/// ```
+/// use tui_textarea::TextTransform;
+///
+/// assert_eq!(TextTransform::TitleCase.apply("hello wörld"), "Hello Wörld");
+/// assert_eq!(TextTransform::SnakeCase.apply("let fooBar = HTTPServer;"), "let foo_bar = http_server;");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TextTransform {
+    /// Convert all letters to upper case: `foo bar` → `FOO BAR`.
+    Uppercase,
+    /// Convert all letters to lower case: `Foo BAR` → `foo bar`.
+    Lowercase,
+    /// Convert the first letter of each word to upper case and the rest to lower case: `foo BAR` → `Foo Bar`.
+    TitleCase,
+    /// Swap upper case and lower case of each letter: `Foo Bar` → `fOO bAR`.
+    ToggleCase,
+    /// Convert each identifier to snake case: `fooBar` → `foo_bar`.
+    SnakeCase,
+    /// Convert each identifier to camel case: `foo_bar` → `fooBar`.
+    CamelCase,
+}
+
+impl TextTransform {
+    /// Transform the text. Identifiers for [`TextTransform::SnakeCase`] and [`TextTransform::CamelCase`] are sequences
+    /// of letters, digits, and underscores, and they are split into words at underscores and case boundaries.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Self::Uppercase => text.to_uppercase(),
+            Self::Lowercase => text.to_lowercase(),
+            Self::TitleCase => title_case(text),
+            Self::ToggleCase => text
+                .chars()
+                .flat_map(|c| {
+                    let upper = c.is_uppercase();
+                    let (mut u, mut l) = (c.to_uppercase(), c.to_lowercase());
+                    std::iter::from_fn(move || if upper { l.next() } else { u.next() })
+                })
+                .collect(),
+            Self::SnakeCase => map_idents(text, |words| {
+                words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("_")
+            }),
+            Self::CamelCase => map_idents(text, |words| {
+                let mut ident = String::new();
+                for (i, word) in words.iter().enumerate() {
+                    if i == 0 {
+                        ident.push_str(&word.to_lowercase());
+                    } else {
+                        ident.push_str(&capitalize(word));
+                    }
+                }
+                ident
+            }),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars.as_str().to_lowercase().chars()).collect(),
+        None => String::new(),
+    }
+}
+
+// Convert the first letter of each word to upper case and the rest to lower case. An apostrophe in a word like "don't"
+// does not start a new word
+fn title_case(text: &str) -> String {
+    let mut title = String::with_capacity(text.len());
+    let (mut prev, mut before_prev) = (None, None);
+    for c in text.chars() {
+        let in_word = |c: Option<char>| c.map_or(false, char::is_alphanumeric);
+        let apostrophe = matches!(prev, Some('\'' | '’')) && in_word(before_prev);
+        if !in_word(prev) && !apostrophe {
+            title.extend(c.to_uppercase());
+        } else {
+            title.extend(c.to_lowercase());
+        }
+        (before_prev, prev) = (prev, Some(c));
+    }
+    title
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Replace each identifier in the text with the one built from its words by `f`. Underscores at the start and the end
+// of the identifier are kept
+fn map_idents(text: &str, f: impl Fn(&[&str]) -> String) -> String {
+    let mut mapped = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let len = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+        let (ident, after) = rest.split_at(len);
+        let trimmed = ident.trim_matches('_');
+        if trimmed.is_empty() {
+            mapped.push_str(ident);
+        } else {
+            let head = ident.len() - ident.trim_start_matches('_').len();
+            mapped.push_str(&ident[..head]);
+            mapped.push_str(&f(&split_words(trimmed)));
+            mapped.push_str(&ident[head + trimmed.len()..]);
+        }
+        let len = after.find(is_ident_char).unwrap_or(after.len());
+        let (other, after) = after.split_at(len);
+        mapped.push_str(other);
+        rest = after;
+    }
+    mapped
+}
+
+// Split the identifier into words at underscores and case boundaries: `foo_bar` into `foo` and `bar`, `fooBar` into `foo`
+// and `Bar`, and `HTTPServer` into `HTTP` and `Server`
+fn split_words(ident: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = ident.char_indices().collect();
+    let mut words = vec![];
+    let mut start = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if c == '_' {
+            if let Some(s) = start.take() {
+                words.push(&ident[s..offset]);
+            }
+            continue;
+        }
+        let Some(s) = start else {
+            start = Some(offset);
+            continue;
+        };
+        let prev = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        let boundary = c.is_uppercase()
+            && (prev.is_lowercase()
+                || prev.is_numeric()
+                || prev.is_uppercase() && next.map_or(false, char::is_lowercase));
+        if boundary {
+            words.push(&ident[s..offset]);
+            start = Some(offset);
+        }
+    }
+    if let Some(s) = start {
+        words.push(&ident[s..]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform() {
+        use TextTransform::*;
+        let tests = [
+            (Uppercase, "foo Bär ß", "FOO BÄR SS"),
+            (Lowercase, "FOO Bär Σ", "foo bär σ"),
+            (TitleCase, "hello WORLD, don't éclair", "Hello World, Don't Éclair"),
+            (TitleCase, "'quoted' foo-bar", "'Quoted' Foo-Bar"),
+            (ToggleCase, "Hello Wörld 1", "hELLO wÖRLD 1"),
+            (SnakeCase, "fooBar", "foo_bar"),
+            (SnakeCase, "FooBar HTTPServer foo2Bar", "foo_bar http_server foo2_bar"),
+            (SnakeCase, "FOO_BAR _private __init__", "foo_bar _private __init__"),
+            (SnakeCase, "a.b(c) - d", "a.b(c) - d"),
+            (SnakeCase, "élanVital", "élan_vital"),
+            (CamelCase, "foo_bar", "fooBar"),
+            (CamelCase, "FOO_BAR Foo_bar_baz", "fooBar fooBarBaz"),
+            (CamelCase, "HTTPServer _foo_bar", "httpServer _fooBar"),
+            (CamelCase, "x", "x"),
+            (CamelCase, "", ""),
+        ];
+        for (transform, text, want) in tests {
+            assert_eq!(transform.apply(text), want, "{transform:?} {text:?}");
+        }
+    }
+
+    #[test]
+    fn words() {
+        let tests = [
+            ("foo", &["foo"][..]),
+            ("fooBar", &["foo", "Bar"]),
+            ("FooBar", &["Foo", "Bar"]),
+            ("FOO_BAR", &["FOO", "BAR"]),
+            ("foo__bar", &["foo", "bar"]),
+            ("HTTPServer", &["HTTP", "Server"]),
+            ("v2Api", &["v2", "Api"]),
+        ];
+        for (ident, want) in tests {
+            assert_eq!(split_words(ident), want, "{ident:?}");
+        }
+    }
+}
@@ -1,13 +1,39 @@
+use unicode_width::UnicodeWidthChar as _;
+
 pub fn spaces(size: u8) -> &'static str {
     const SPACES: &str = "                                                                                                                                                                                                                                                                ";
     &SPACES[..size as usize]
 }
 
+/// Notation rendered instead of a control character. C0 control characters and DEL are rendered in caret notation such
+/// as `^L`, and C1 control characters and invisible formatting characters such as bidi controls are rendered with their
+/// code points such as `<200e>`. Tab is not included since it is rendered as spaces.
+pub fn control_char_notation(c: char) -> Option<String> {
+    match c {
+        '\t' => None,
+        '\0'..='\x1f' => Some(format!("^{}", (c as u8 ^ 0x40) as char)),
+        '\x7f' => Some("^?".to_string()),
+        '\u{80}'..='\u{9f}'
+        | '\u{200b}'
+        | '\u{200e}'
+        | '\u{200f}'
+        | '\u{202a}'..='\u{202e}'
+        | '\u{2066}'..='\u{2069}'
+        | '\u{feff}' => Some(format!("<{:04x}>", c as u32)),
+        _ => None,
+    }
+}
+
 pub fn num_digits(i: usize) -> u8 {
     f64::log10(i as f64) as u8 + 1
 }
 
-#[derive(Debug, Clone)]
+/// Start and end positions of a search match. Each position is a pair of row and byte offset in the line.
+#[cfg(feature = "search")]
+pub type MatchPos = ((usize, usize), (usize, usize));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pos {
     pub row: usize,
     pub col: usize,
@@ -19,3 +45,26 @@ impl Pos {
         Self { row, col, offset }
     }
 }
+
+/// Width of the character `c` on screen. `width` is the display width of the text preceding the character in the same
+/// line, which is necessary to calculate the width of a tab character. `control` is `true` when control characters are
+/// rendered with [`control_char_notation`].
+pub fn char_width(c: char, width: usize, tab_len: u8, mask: Option<char>, control: bool) -> usize {
+    if let Some(m) = mask {
+        return m.width().unwrap_or(0);
+    }
+    if control {
+        if let Some(notation) = control_char_notation(c) {
+            return notation.len();
+        }
+    }
+    if c == '\t' {
+        if tab_len == 0 {
+            0
+        } else {
+            tab_len as usize - width % tab_len as usize
+        }
+    } else {
+        c.width().unwrap_or(0)
+    }
+}
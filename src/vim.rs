@@ -0,0 +1,918 @@
+//! Vim emulation on top of [`TextArea`].
+//!
+//! [`Vim`] is a state machine which interprets key inputs as Vim commands and edits a [`TextArea`] accordingly. The
+//! current [`Mode`] can be queried to render a mode indicator.
+//!
+//! ```
+//! use tui_textarea::vim::{Mode, Vim, VimState};
+//! use tui_textarea::{Input, Key, TextArea};
+//!
+//! let mut textarea = TextArea::from(["hello world"]);
+//! let mut vim = Vim::new();
+//!
+//! let key = |c| Input { key: Key::Char(c), ..Default::default() };
+//!
+//! // `ciw` changes the word under the cursor
+//! vim.transition(key('c'), &mut textarea);
+//! vim.transition(key('i'), &mut textarea);
+//! assert_eq!(vim.transition(key('w'), &mut textarea), VimState::Mode(Mode::Insert));
+//! textarea.insert_str("goodbye");
+//! vim.transition(Input { key: Key::Esc, ..Default::default() }, &mut textarea);
+//! assert_eq!(textarea.lines(), ["goodbye world"]);
+//! assert_eq!(vim.mode(), Mode::Normal);
+//!
+//! // Keys which are not Vim commands are returned to the caller
+//! assert_eq!(vim.transition(key('q'), &mut textarea), VimState::Unhandled(key('q')));
+//! ```
+use crate::ratatui::style::{Modifier, Style};
+use crate::{CursorMove, Input, Key, Scrolling, TextArea, TextTransform};
+use std::cmp::{max, min};
+use std::fmt;
+
+/// Mode of the Vim emulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Normal mode
+    Normal,
+    /// Insert mode. Inputs are handled by [`TextArea::input`]
+    Insert,
+    /// Characterwise visual mode
+    Visual,
+    /// Linewise visual mode
+    VisualLine,
+    /// Operator-pending mode waiting for a motion. The character is the operator: `d`, `y`, or `c`
+    Operator(char),
+}
+
+impl Mode {
+    /// Cursor style for the mode. The cursor is a block in all modes except for insert mode, where it is rendered as
+    /// an underline since the cursor of [`TextArea`] is a styled cell and cannot be a thin bar.
+    pub fn cursor_style(&self) -> Style {
+        match self {
+            Self::Insert => Style::default().add_modifier(Modifier::UNDERLINED),
+            _ => Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "NORMAL"),
+            Self::Insert => write!(f, "INSERT"),
+            Self::Visual => write!(f, "VISUAL"),
+            Self::VisualLine => write!(f, "VISUAL LINE"),
+            Self::Operator(c) => write!(f, "OPERATOR({})", c),
+        }
+    }
+}
+
+/// Result of [`Vim::transition`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum VimState {
+    /// The input was handled. The value is the current mode.
+    Mode(Mode),
+    /// The input was handled as a part of a command which needs more inputs, like `g` of `gg` or `2` of `2dw`.
+    Pending,
+    /// The input is not a Vim command. The application can handle it by itself (e.g. quit on `q`).
+    Unhandled(Input),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Motion {
+    Move(CursorMove),
+    WordStart,
+    WordBack,
+    WordEnd,
+    FirstNonBlank,
+    LineEnd,
+    Line(Option<usize>), // `gg` or `G`
+}
+
+impl Motion {
+    fn is_linewise(self) -> bool {
+        matches!(self, Self::Move(CursorMove::Up | CursorMove::Down) | Self::Line(_),)
+    }
+
+    fn is_inclusive(self) -> bool {
+        self == Self::WordEnd
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Punct,
+}
+
+impl CharClass {
+    fn new(c: char) -> Self {
+        if c.is_whitespace() {
+            Self::Space
+        } else if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else {
+            Self::Punct
+        }
+    }
+}
+
+/// State machine of the Vim emulation.
+///
+/// Supported commands are:
+///
+/// - Motions: `h`, `j`, `k`, `l`, `w`, `b`, `e`, `0`, `^`, `$`, `gg`, `G`, and arrow keys
+/// - Operators: `d`, `y`, `c` followed by a motion or the text objects `iw` and `aw`. `dd`, `yy`, `cc` operate on
+///   lines
+/// - Edits: `x`, `X`, `D`, `C`, `s`, `S`, `J`, `r`, `p`, `P`, `u`, `Ctrl+R`, `Ctrl+A`, `Ctrl+X`, and `.` to
+///   repeat the last change
+/// - Entering insert mode: `i`, `a`, `I`, `A`, `o`, `O`
+/// - Visual modes: `v`, `V`, and `y`, `d`, `c`, `x`, `u`, `U`, `~` on the selection
+/// - Scrolling: `Ctrl+E`, `Ctrl+Y`, `Ctrl+D`, `Ctrl+U`, `Ctrl+F`, `Ctrl+B`
+///
+/// Motions, operators, and most edits accept a count like `3j` or `2d3w`. Edits made by one command, including text
+/// typed in an insert session, are undone at once by `u` like Vim.
+#[derive(Clone, Debug)]
+pub struct Vim {
+    mode: Mode,
+    count: usize,
+    op_count: usize,
+    pending: Option<char>,
+    visual_anchor: usize,
+    visual_row: usize,
+    linewise_yank: Option<String>,
+    keys: Vec<Input>,
+    last_change: Vec<Input>,
+    changing: bool,
+    replaying: bool,
+}
+
+impl Default for Vim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vim {
+    /// Create a new Vim emulation state in normal mode.
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Normal,
+            count: 0,
+            op_count: 0,
+            pending: None,
+            visual_anchor: 0,
+            visual_row: 0,
+            linewise_yank: None,
+            keys: vec![],
+            last_change: vec![],
+            changing: false,
+            replaying: false,
+        }
+    }
+
+    /// Get the current mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Handle the key input as a Vim command and edit the textarea. The cursor style of the textarea is updated with
+    /// [`Mode::cursor_style`] when the mode changes.
+    pub fn transition(&mut self, input: Input, textarea: &mut TextArea<'_>) -> VimState {
+        if input.key.is_ignored() {
+            return VimState::Mode(self.mode);
+        }
+
+        if !self.replaying {
+            if self.is_idle() {
+                self.keys.clear();
+            }
+            self.keys.push(input.clone());
+            textarea.begin_undo_group();
+        }
+
+        let prev_mode = self.mode;
+        let state = match self.mode {
+            Mode::Insert => self.insert(input, textarea),
+            _ => self.normal(input, textarea),
+        };
+
+        if self.mode != prev_mode {
+            textarea.set_cursor_style(self.mode.cursor_style());
+        }
+
+        if !self.replaying {
+            if let VimState::Unhandled(_) = state {
+                self.keys.pop();
+                self.count = 0;
+                self.op_count = 0;
+            }
+            if state != VimState::Pending && self.mode != Mode::Insert {
+                textarea.end_undo_group();
+            }
+            if self.is_idle() {
+                if self.changing {
+                    self.last_change = std::mem::take(&mut self.keys);
+                }
+                self.changing = false;
+            }
+        }
+
+        state
+    }
+
+    fn is_idle(&self) -> bool {
+        self.mode == Mode::Normal && self.count == 0 && self.pending.is_none()
+    }
+
+    fn set_mode(&mut self, mode: Mode) -> VimState {
+        if mode == Mode::Insert {
+            self.changing = true; // Insert session is a change to repeat by `.`
+        }
+        self.mode = mode;
+        self.count = 0;
+        self.op_count = 0;
+        self.pending = None;
+        VimState::Mode(mode)
+    }
+
+    fn take_count(&mut self) -> usize {
+        let count = max(self.count, 1).saturating_mul(max(self.op_count, 1));
+        self.count = 0;
+        self.op_count = 0;
+        count
+    }
+
+    fn insert(&mut self, input: Input, textarea: &mut TextArea<'_>) -> VimState {
+        match input {
+            Input { key: Key::Esc, .. }
+            | Input {
+                key: Key::Char('c'),
+                ctrl: true,
+                ..
+            } => {
+                if textarea.cursor().1 > 0 {
+                    textarea.move_cursor(CursorMove::Back);
+                }
+                self.set_mode(Mode::Normal)
+            }
+            input => {
+                textarea.input(input);
+                VimState::Mode(Mode::Insert)
+            }
+        }
+    }
+
+    fn normal(&mut self, input: Input, textarea: &mut TextArea<'_>) -> VimState {
+        if let Some(prefix) = self.pending.take() {
+            return self.prefixed(prefix, input, textarea);
+        }
+
+        let Input { key, ctrl, .. } = input;
+
+        if ctrl {
+            let scrolling = match key {
+                Key::Char('e') => Scrolling::Delta { rows: 1, cols: 0 },
+                Key::Char('y') => Scrolling::Delta { rows: -1, cols: 0 },
+                Key::Char('d') => Scrolling::HalfPageDown,
+                Key::Char('u') => Scrolling::HalfPageUp,
+                Key::Char('f') => Scrolling::PageDown,
+                Key::Char('b') => Scrolling::PageUp,
+                Key::Char('r') if self.mode == Mode::Normal => {
+                    for _ in 0..self.take_count() {
+                        textarea.redo();
+                    }
+                    return VimState::Mode(self.mode);
+                }
+                Key::Char(c @ ('a' | 'x')) if self.mode == Mode::Normal => {
+                    let count = self.take_count() as i64;
+                    let delta = if c == 'a' { count } else { -count };
+                    if textarea.increment_number(delta) {
+                        self.changing = true;
+                    }
+                    return VimState::Mode(self.mode);
+                }
+                _ => return VimState::Unhandled(input),
+            };
+            textarea.scroll(scrolling);
+            return VimState::Mode(self.mode);
+        }
+
+        // Counts
+        match key {
+            Key::Char(c @ '1'..='9') | Key::Char(c @ '0') if c != '0' || self.count > 0 => {
+                let digit = c.to_digit(10).unwrap() as usize;
+                self.count = self.count.saturating_mul(10).saturating_add(digit);
+                return VimState::Pending;
+            }
+            _ => {}
+        }
+
+        if let Some(motion) = Self::motion(key, self.count) {
+            return self.motion_command(motion, textarea);
+        }
+
+        match (self.mode, key) {
+            (_, Key::Char('g')) => {
+                self.pending = Some('g');
+                VimState::Pending
+            }
+            (Mode::Operator(op), Key::Char(c)) if c == op => {
+                let row = textarea.cursor().0;
+                let last = textarea.len_lines() - 1;
+                let end = min(row + self.take_count() - 1, last);
+                self.linewise(op, row, end, textarea)
+            }
+            (Mode::Operator(_), Key::Char(c @ ('i' | 'a'))) => {
+                self.pending = Some(c);
+                VimState::Pending
+            }
+            (Mode::Operator(_), _) => self.set_mode(Mode::Normal),
+            (Mode::Visual | Mode::VisualLine, Key::Esc) => {
+                textarea.cancel_selection();
+                self.set_mode(Mode::Normal)
+            }
+            (Mode::Visual | Mode::VisualLine, Key::Char(c @ ('y' | 'd' | 'c' | 'x'))) => {
+                let op = if c == 'x' { 'd' } else { c };
+                self.visual_operator(op, textarea)
+            }
+            (Mode::Visual | Mode::VisualLine, Key::Char(c @ ('u' | 'U' | '~'))) => self.visual_transform(c, textarea),
+            (Mode::Visual, Key::Char('v')) | (Mode::VisualLine, Key::Char('V')) => {
+                textarea.cancel_selection();
+                self.set_mode(Mode::Normal)
+            }
+            (Mode::Visual, Key::Char('V')) | (Mode::Normal, Key::Char('V')) => {
+                let cursor = textarea.cursor();
+                // The anchor of the selection is the end which is not at the cursor
+                self.visual_anchor = match textarea.selection_range() {
+                    Some((start, end)) if start == cursor => end.0,
+                    Some((start, _)) => start.0,
+                    None => cursor.0,
+                };
+                self.visual_row = cursor.0;
+                self.select_lines(textarea);
+                self.set_mode(Mode::VisualLine)
+            }
+            (Mode::VisualLine, Key::Char('v')) => {
+                textarea.set_selection((self.visual_anchor, 0), (self.visual_row, 0));
+                self.set_mode(Mode::Visual)
+            }
+            (Mode::Normal, Key::Char('v')) => {
+                textarea.start_selection();
+                self.set_mode(Mode::Visual)
+            }
+            (Mode::Normal, Key::Char(op @ ('d' | 'y' | 'c'))) => {
+                self.op_count = self.count;
+                self.count = 0;
+                self.mode = Mode::Operator(op);
+                VimState::Pending
+            }
+            (Mode::Normal, Key::Char(c)) => self.normal_command(c, input, textarea),
+            (Mode::Normal, Key::Esc) => self.set_mode(Mode::Normal),
+            _ => VimState::Unhandled(input),
+        }
+    }
+
+    fn prefixed(&mut self, prefix: char, input: Input, textarea: &mut TextArea<'_>) -> VimState {
+        match (prefix, input.key) {
+            ('g', Key::Char('g')) => {
+                let line = max(self.count, 1);
+                self.count = 0;
+                self.motion_command(Motion::Line(Some(line)), textarea)
+            }
+            ('i' | 'a', Key::Char('w')) => {
+                let Mode::Operator(op) = self.mode else {
+                    return self.set_mode(self.mode);
+                };
+                let (row, col) = textarea.cursor();
+                let Some((start, end)) = word_object(&textarea.line(row).unwrap_or_default(), col, prefix == 'a')
+                else {
+                    return self.set_mode(Mode::Normal);
+                };
+                self.charwise(op, (row, start), (row, end), textarea)
+            }
+            ('r', Key::Char(c)) => {
+                let (row, col) = textarea.cursor();
+                let count = self.take_count();
+                if col + count > textarea.line_len(row).unwrap_or(0) {
+                    return self.set_mode(Mode::Normal);
+                }
+                textarea.set_selection((row, col), (row, col + count));
+                textarea.delete_line(false);
+                textarea.insert_str(c.to_string().repeat(count));
+                textarea.move_cursor(CursorMove::Back);
+                self.changing = true;
+                self.set_mode(Mode::Normal)
+            }
+            _ => {
+                let mode = if let Mode::Operator(_) = self.mode {
+                    Mode::Normal
+                } else {
+                    self.mode
+                };
+                self.set_mode(mode)
+            }
+        }
+    }
+
+    fn motion(key: Key, count: usize) -> Option<Motion> {
+        let motion = match key {
+            Key::Char('h') | Key::Left => Motion::Move(CursorMove::Back),
+            Key::Char('j') | Key::Down => Motion::Move(CursorMove::Down),
+            Key::Char('k') | Key::Up => Motion::Move(CursorMove::Up),
+            Key::Char('l') | Key::Right => Motion::Move(CursorMove::Forward),
+            Key::Char('w') => Motion::WordStart,
+            Key::Char('b') => Motion::WordBack,
+            Key::Char('e') => Motion::WordEnd,
+            Key::Char('0') | Key::Home => Motion::Move(CursorMove::Head),
+            Key::Char('^') => Motion::FirstNonBlank,
+            Key::Char('$') | Key::End => Motion::LineEnd,
+            Key::Char('G') => Motion::Line((count > 0).then_some(count)),
+            _ => return None,
+        };
+        Some(motion)
+    }
+
+    fn motion_command(&mut self, motion: Motion, textarea: &mut TextArea<'_>) -> VimState {
+        match self.mode {
+            Mode::Operator(op) => {
+                let count = self.take_count();
+                self.operator(op, motion, count, textarea)
+            }
+            Mode::VisualLine => {
+                let count = self.take_count();
+                textarea.cancel_selection();
+                textarea.move_cursor_without_jump(CursorMove::Jump(self.visual_row as u64, 0));
+                apply_motion(motion, count, textarea);
+                self.visual_row = textarea.cursor().0;
+                self.select_lines(textarea);
+                VimState::Mode(self.mode)
+            }
+            mode => {
+                let count = self.take_count();
+                apply_motion(motion, count, textarea);
+                // Cursor cannot be placed after the last character in normal mode
+                let (row, col) = textarea.cursor();
+                if mode == Mode::Normal && col > 0 && col >= textarea.line_len(row).unwrap_or(0) {
+                    textarea.move_cursor(CursorMove::Back);
+                }
+                VimState::Mode(self.mode)
+            }
+        }
+    }
+
+    // Select whole lines between the anchor row and the cursor row in linewise visual mode
+    fn select_lines(&self, textarea: &mut TextArea<'_>) {
+        let (anchor, row) = (self.visual_anchor, self.visual_row);
+        let line_len = |row: usize| textarea.line_len(row).unwrap_or(0);
+        if anchor <= row {
+            textarea.set_selection((anchor, 0), (row, line_len(row)));
+        } else {
+            textarea.set_selection((anchor, line_len(anchor)), (row, 0));
+        }
+    }
+
+    fn operator(&mut self, op: char, motion: Motion, count: usize, textarea: &mut TextArea<'_>) -> VimState {
+        let start = textarea.cursor();
+        let line_len = |t: &TextArea<'_>, row: usize| t.line_len(row).unwrap_or(0);
+
+        // `cw` on a non-blank character behaves like `ce`
+        let on_blank = textarea
+            .line(start.0)
+            .unwrap_or_default()
+            .chars()
+            .nth(start.1)
+            .map_or(true, char::is_whitespace);
+        let motion = if op == 'c' && motion == Motion::WordStart && !on_blank {
+            Motion::WordEnd
+        } else {
+            motion
+        };
+
+        apply_motion(motion, count, textarea);
+        let end = textarea.cursor();
+
+        if motion.is_linewise() {
+            return self.linewise(op, min(start.0, end.0), max(start.0, end.0), textarea);
+        }
+
+        let (s, mut e) = if start <= end { (start, end) } else { (end, start) };
+        if motion.is_inclusive() {
+            e.1 = min(e.1 + 1, line_len(textarea, e.0));
+        }
+        // `dw` at the last word of a line does not join the next line
+        if motion == Motion::WordStart && e.0 > s.0 {
+            e = (s.0, line_len(textarea, s.0));
+        }
+        self.charwise(op, s, e, textarea)
+    }
+
+    fn charwise(
+        &mut self,
+        op: char,
+        start: (usize, usize),
+        end: (usize, usize),
+        textarea: &mut TextArea<'_>,
+    ) -> VimState {
+        let next = if op == 'c' { Mode::Insert } else { Mode::Normal };
+        if start == end {
+            textarea.move_cursor_without_jump(CursorMove::Jump(start.0 as u64, start.1 as u64));
+            return self.set_mode(next);
+        }
+        textarea.set_selection(start, end);
+        if op == 'y' {
+            textarea.copy();
+            textarea.cancel_selection();
+            textarea.move_cursor_without_jump(CursorMove::Jump(start.0 as u64, start.1 as u64));
+        } else {
+            textarea.delete_line(true);
+            self.changing = true;
+        }
+        self.linewise_yank = None;
+        self.set_mode(next)
+    }
+
+    fn linewise(&mut self, op: char, start: usize, end: usize, textarea: &mut TextArea<'_>) -> VimState {
+        let text = textarea
+            .iter_lines()
+            .skip(start)
+            .take(end + 1 - start)
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+        let last = textarea.len_lines() - 1;
+        let line_len = |row: usize| textarea.line_len(row).unwrap_or(0);
+
+        let mode = match op {
+            'y' => {
+                textarea.cancel_selection();
+                let col = textarea.cursor().1;
+                textarea.move_cursor_without_jump(CursorMove::Jump(start as u64, col as u64));
+                Mode::Normal
+            }
+            'd' => {
+                if end < last {
+                    textarea.set_selection((start, 0), (end + 1, 0));
+                } else if start > 0 {
+                    textarea.set_selection((start - 1, line_len(start - 1)), (end, line_len(end)));
+                } else {
+                    textarea.set_selection((start, 0), (end, line_len(end)));
+                }
+                textarea.delete_line(false);
+                textarea.cancel_selection();
+                textarea.move_cursor_without_jump(CursorMove::Jump(start as u64, 0));
+                apply_motion(Motion::FirstNonBlank, 1, textarea);
+                self.changing = true;
+                Mode::Normal
+            }
+            _ => {
+                if start < end || line_len(end) > 0 {
+                    textarea.set_selection((start, 0), (end, line_len(end)));
+                    textarea.delete_line(false);
+                }
+                textarea.cancel_selection();
+                textarea.move_cursor_without_jump(CursorMove::Jump(start as u64, 0));
+                Mode::Insert
+            }
+        };
+
+        textarea.set_yank_text(text.clone());
+        self.linewise_yank = Some(text);
+        self.set_mode(mode)
+    }
+
+    fn visual_operator(&mut self, op: char, textarea: &mut TextArea<'_>) -> VimState {
+        let Some((start, end)) = textarea.selection_range() else {
+            return self.set_mode(Mode::Normal);
+        };
+        if self.mode == Mode::VisualLine {
+            textarea.cancel_selection();
+            return self.linewise(op, start.0, end.0, textarea);
+        }
+
+        let end = inclusive_end(textarea, end);
+        self.charwise(op, start, end, textarea)
+    }
+
+    fn visual_transform(&mut self, c: char, textarea: &mut TextArea<'_>) -> VimState {
+        let Some((start, end)) = textarea.selection_range() else {
+            return self.set_mode(Mode::Normal);
+        };
+        let (start, end) = if self.mode == Mode::VisualLine {
+            ((start.0, 0), (end.0, textarea.line_len(end.0).unwrap_or(0)))
+        } else {
+            (start, inclusive_end(textarea, end))
+        };
+        let transform = match c {
+            'u' => TextTransform::Lowercase,
+            'U' => TextTransform::Uppercase,
+            _ => TextTransform::ToggleCase,
+        };
+        textarea.set_selection(start, end);
+        if textarea.transform_selection(transform) {
+            self.changing = true;
+        }
+        textarea.cancel_selection();
+        textarea.move_cursor_without_jump(CursorMove::Jump(start.0 as u64, start.1 as u64));
+        self.set_mode(Mode::Normal)
+    }
+
+    fn normal_command(&mut self, c: char, input: Input, textarea: &mut TextArea<'_>) -> VimState {
+        let (row, col) = textarea.cursor();
+        let line_len = textarea.line_len(row).unwrap_or(0);
+        let mode = match c {
+            'i' => Mode::Insert,
+            'a' => {
+                if col < line_len {
+                    textarea.move_cursor(CursorMove::Forward);
+                }
+                Mode::Insert
+            }
+            'I' => {
+                apply_motion(Motion::FirstNonBlank, 1, textarea);
+                Mode::Insert
+            }
+            'A' => {
+                textarea.move_cursor(CursorMove::End);
+                Mode::Insert
+            }
+            'o' => {
+                textarea.move_cursor(CursorMove::End);
+                textarea.insert_newline();
+                Mode::Insert
+            }
+            'O' => {
+                textarea.move_cursor(CursorMove::Head);
+                textarea.insert_newline();
+                textarea.move_cursor(CursorMove::Up);
+                Mode::Insert
+            }
+            'x' | 's' => {
+                let count = self.take_count();
+                let op = if c == 's' { 'c' } else { 'd' };
+                let end = min(col + count, line_len);
+                return self.charwise(op, (row, col), (row, end), textarea);
+            }
+            'X' => {
+                let count = self.take_count();
+                return self.charwise('d', (row, col.saturating_sub(count)), (row, col), textarea);
+            }
+            'D' | 'C' => {
+                let op = if c == 'C' { 'c' } else { 'd' };
+                return self.charwise(op, (row, col), (row, line_len), textarea);
+            }
+            'S' => return self.linewise('c', row, row, textarea),
+            'J' => {
+                for _ in 0..max(self.take_count(), 2) - 1 {
+                    if textarea.cursor().0 + 1 >= textarea.len_lines() {
+                        break;
+                    }
+                    textarea.move_cursor(CursorMove::End);
+                    textarea.delete_next_char();
+                    self.changing = true;
+                }
+                Mode::Normal
+            }
+            'r' => {
+                self.pending = Some('r');
+                return VimState::Pending;
+            }
+            'p' | 'P' => {
+                let count = self.take_count();
+                self.paste(c == 'P', count, textarea);
+                Mode::Normal
+            }
+            'u' => {
+                for _ in 0..self.take_count() {
+                    textarea.undo();
+                }
+                Mode::Normal
+            }
+            '.' => {
+                let count = self.take_count();
+                let keys = self.last_change.clone();
+                self.replaying = true;
+                for _ in 0..count {
+                    for key in keys.iter().cloned() {
+                        self.transition(key, textarea);
+                    }
+                }
+                self.replaying = false;
+                self.changing = false; // Do not overwrite the last change with `.` itself
+                Mode::Normal
+            }
+            _ => return VimState::Unhandled(input),
+        };
+        self.set_mode(mode)
+    }
+
+    fn paste(&mut self, before: bool, count: usize, textarea: &mut TextArea<'_>) {
+        let text = textarea.yank_text();
+        if text.is_empty() {
+            return;
+        }
+        self.changing = true;
+        textarea.cancel_selection();
+
+        if self.linewise_yank.as_deref() == Some(text.as_str()) {
+            let row = textarea.cursor().0;
+            let body = text.repeat(count);
+            if before {
+                textarea.move_cursor(CursorMove::Head);
+                textarea.insert_str(&body);
+                textarea.move_cursor_without_jump(CursorMove::Jump(row as u64, 0));
+            } else {
+                textarea.move_cursor(CursorMove::End);
+                textarea.insert_str(format!("\n{}", body.strip_suffix('\n').unwrap()));
+                textarea.move_cursor_without_jump(CursorMove::Jump(row as u64 + 1, 0));
+            }
+            apply_motion(Motion::FirstNonBlank, 1, textarea);
+            return;
+        }
+
+        let (row, col) = textarea.cursor();
+        if !before && col < textarea.line_len(row).unwrap_or(0) {
+            textarea.move_cursor(CursorMove::Forward);
+        }
+        for _ in 0..count {
+            textarea.paste();
+        }
+        textarea.move_cursor(CursorMove::Back);
+    }
+}
+
+fn apply_motion(motion: Motion, count: usize, textarea: &mut TextArea<'_>) {
+    match motion {
+        Motion::Move(m) => {
+            for _ in 0..count {
+                let prev = textarea.cursor();
+                textarea.move_cursor(m);
+                if prev == textarea.cursor() {
+                    break;
+                }
+            }
+        }
+        Motion::WordStart | Motion::WordBack | Motion::WordEnd => {
+            let find = match motion {
+                Motion::WordStart => word_start,
+                Motion::WordBack => word_back,
+                _ => word_end,
+            };
+            for _ in 0..count {
+                let (row, col) = find(textarea, textarea.cursor());
+                textarea.move_cursor_without_jump(CursorMove::Jump(row as u64, col as u64));
+            }
+        }
+        Motion::FirstNonBlank => {
+            let row = textarea.cursor().0;
+            let col = textarea
+                .line(row)
+                .unwrap_or_default()
+                .chars()
+                .position(|c| !c.is_whitespace())
+                .unwrap_or(0);
+            textarea.move_cursor_without_jump(CursorMove::Jump(row as u64, col as u64));
+        }
+        Motion::LineEnd => {
+            let row = textarea.cursor().0 + count - 1;
+            textarea.move_cursor_without_jump(CursorMove::Jump(row as u64, u64::MAX));
+        }
+        Motion::Line(line) => {
+            let row = match line {
+                Some(line) => line.saturating_sub(1),
+                None => textarea.len_lines() - 1,
+            };
+            textarea.move_cursor(CursorMove::Jump(row as u64, 0));
+            apply_motion(Motion::FirstNonBlank, 1, textarea);
+        }
+    }
+}
+
+/// Find the position of the start of the next word like Vim's `w` motion.
+fn word_start(textarea: &TextArea<'_>, (mut row, mut col): (usize, usize)) -> (usize, usize) {
+    let mut chars = line_chars(textarea, row);
+    if col < chars.len() {
+        let class = CharClass::new(chars[col]);
+        while col < chars.len() && CharClass::new(chars[col]) == class {
+            col += 1;
+        }
+    }
+    // Skip spaces and newlines. An empty line is regarded as a word
+    loop {
+        while col < chars.len() && chars[col].is_whitespace() {
+            col += 1;
+        }
+        if col < chars.len() || row + 1 >= textarea.len_lines() {
+            break;
+        }
+        row += 1;
+        col = 0;
+        chars = line_chars(textarea, row);
+        if chars.is_empty() {
+            break;
+        }
+    }
+    (row, col)
+}
+
+/// Find the position of the start of the previous word like Vim's `b` motion.
+fn word_back(textarea: &TextArea<'_>, (mut row, mut col): (usize, usize)) -> (usize, usize) {
+    let mut chars = line_chars(textarea, row);
+    col = min(col, chars.len());
+    loop {
+        while col > 0 && chars[col - 1].is_whitespace() {
+            col -= 1;
+        }
+        if col > 0 || row == 0 {
+            break;
+        }
+        row -= 1;
+        chars = line_chars(textarea, row);
+        col = chars.len();
+        if chars.is_empty() {
+            return (row, 0);
+        }
+    }
+    if col == 0 {
+        return (row, 0);
+    }
+    let class = CharClass::new(chars[col - 1]);
+    while col > 0 && CharClass::new(chars[col - 1]) == class {
+        col -= 1;
+    }
+    (row, col)
+}
+
+/// Find the position of the end of the next word like Vim's `e` motion.
+fn word_end(textarea: &TextArea<'_>, (mut row, col): (usize, usize)) -> (usize, usize) {
+    let mut chars = line_chars(textarea, row);
+    let mut col = col + 1;
+    // Skip spaces and newlines
+    loop {
+        while col < chars.len() && chars[col].is_whitespace() {
+            col += 1;
+        }
+        if col < chars.len() || row + 1 >= textarea.len_lines() {
+            break;
+        }
+        row += 1;
+        col = 0;
+        chars = line_chars(textarea, row);
+    }
+    if col >= chars.len() {
+        return (row, chars.len().saturating_sub(1));
+    }
+    let class = CharClass::new(chars[col]);
+    while col + 1 < chars.len() && CharClass::new(chars[col + 1]) == class {
+        col += 1;
+    }
+    (row, col)
+}
+
+// Characters of the line at the row
+fn line_chars(textarea: &TextArea<'_>, row: usize) -> Vec<char> {
+    textarea.line(row).unwrap_or_default().chars().collect()
+}
+
+/// Find the range of `iw` or `aw` text object at the column as a pair of start and end columns.
+// Selection in visual mode includes the character at the end position
+fn inclusive_end(textarea: &TextArea<'_>, end: (usize, usize)) -> (usize, usize) {
+    if end.1 < textarea.line_len(end.0).unwrap_or(0) {
+        (end.0, end.1 + 1)
+    } else if end.0 + 1 < textarea.len_lines() {
+        (end.0 + 1, 0)
+    } else {
+        end
+    }
+}
+
+fn word_object(line: &str, col: usize, around: bool) -> Option<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return None;
+    }
+    let col = min(col, chars.len() - 1);
+    let class = CharClass::new(chars[col]);
+    let mut start = col;
+    while start > 0 && CharClass::new(chars[start - 1]) == class {
+        start -= 1;
+    }
+    let mut end = col + 1;
+    while end < chars.len() && CharClass::new(chars[end]) == class {
+        end += 1;
+    }
+    if around && class != CharClass::Space {
+        if end < chars.len() && chars[end].is_whitespace() {
+            while end < chars.len() && chars[end].is_whitespace() {
+                end += 1;
+            }
+        } else {
+            while start > 0 && chars[start - 1].is_whitespace() {
+                start -= 1;
+            }
+        }
+    }
+    Some((start, end))
+}
@@ -1,36 +1,107 @@
+use crate::fold;
 use crate::ratatui::buffer::Buffer;
-use crate::ratatui::layout::Rect;
-use crate::ratatui::text::Text;
-use crate::ratatui::widgets::{Paragraph, Widget};
+use crate::ratatui::layout::{Alignment, Rect};
+use crate::ratatui::style::Style;
+use crate::ratatui::text::{Span, Text};
+use crate::ratatui::widgets::{Paragraph, StatefulWidget, Widget, Wrap};
 use crate::textarea::TextArea;
-use crate::util::num_digits;
+use crate::wrap::Wrapper;
+#[cfg(feature = "ratatui")]
+use ratatui::text::Line;
 use std::cell::Cell;
 use std::cmp;
+#[cfg(feature = "tuirs")]
+use tui::text::Spans as Line;
+use unicode_width::{UnicodeWidthChar as _, UnicodeWidthStr as _};
 
-#[derive(Default, Debug, Clone)]
+/// Scroll position and size of the area where a [`TextArea`] is rendered. Every [`TextArea`] has its own viewport
+/// used by [`TextArea::widget`]. To render one textarea in multiple panes scrolled to different positions, render it
+/// as [`StatefulWidget`] with separate viewports.
+///
+/// ```
+/// use ratatui::buffer::Buffer;
+/// use ratatui::layout::Rect;
+/// use ratatui::widgets::StatefulWidget;
+/// use tui_textarea::{CursorMove, TextArea, Viewport};
+///
+/// let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+/// let mut left = Viewport::default();
+/// let mut right = Viewport::default();
+/// right.set_focused(false);
+///
+/// let area = Rect::new(0, 0, 10, 4);
+/// let mut buf = Buffer::empty(area);
+///
+/// textarea.move_cursor(CursorMove::Bottom);
+/// textarea.render(area, &mut buf, &mut left);
+/// textarea.render(area, &mut buf, &mut right);
+///
+/// // Only the focused viewport follows the cursor
+/// assert_eq!(left.scroll_top(), (16, 0));
+/// assert_eq!(right.scroll_top(), (0, 0));
+/// ```
+#[derive(Debug, Clone)]
 pub struct Viewport {
+    x: Cell<u16>,
+    y: Cell<u16>,
     width: Cell<u16>,
     height: Cell<u16>,
     row: Cell<u64>,
     col: Cell<u64>,
+    skip: Cell<u16>,
+    scrollbars: Cell<(Option<Rect>, Option<Rect>)>, // Vertical and horizontal scrollbars
+    focused: bool,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            x: Cell::default(),
+            y: Cell::default(),
+            width: Cell::default(),
+            height: Cell::default(),
+            row: Cell::default(),
+            col: Cell::default(),
+            skip: Cell::default(),
+            scrollbars: Cell::default(),
+            focused: true,
+        }
+    }
 }
 
 impl Viewport {
-    fn store(&self, row: u64, col: u64, width: u16, height: u16) {
+    fn store(&self, row: u64, col: u64, skip: u16, area: Rect) {
+        self.x.set(area.x);
+        self.y.set(area.y);
+        let Rect { width, height, .. } = area;
+        self.skip.set(skip);
         self.width.set(width);
         self.height.set(height);
         self.row.set(row);
         self.col.set(col);
     }
 
+    /// Position of the top-left cell of the text area on the terminal screen.
+    pub fn origin(&self) -> (u16, u16) {
+        (self.x.get(), self.y.get())
+    }
+
+    /// Number of display rows of the top line hidden above the viewport when lines are soft-wrapped.
+    pub fn skip(&self) -> u16 {
+        self.skip.get()
+    }
+
+    /// Row and column of the text at the top-left of the viewport.
     pub fn scroll_top(&self) -> (u64, u64) {
         (self.row.get(), self.col.get())
     }
 
+    /// Row and column of the text at the top-left of the viewport, and the width and height of the viewport.
     pub fn rect(&self) -> (u64, u64, u16, u16) {
         (self.row.get(), self.col.get(), self.width.get(), self.height.get())
     }
 
+    /// Rows and columns of the text at the top-left and the bottom-right of the viewport.
     pub fn position(&self) -> (u64, u64, u64, u64) {
         let (row_top, col_top, width, height) = self.rect();
         let row_bottom = row_top.saturating_add(height.into()).saturating_sub(1);
@@ -44,87 +115,2806 @@ impl Viewport {
         )
     }
 
+    /// Areas of the vertical and the horizontal scrollbars on the terminal screen rendered last. `None` is returned for
+    /// a scrollbar which is not rendered.
+    pub fn scrollbar_rects(&self) -> (Option<Rect>, Option<Rect>) {
+        self.scrollbars.get()
+    }
+
+    /// Scroll the viewport by the number of rows and columns.
     pub fn scroll(&mut self, rows: i64, cols: i64) {
         self.row.set(self.row.get().saturating_add_signed(rows));
         self.col.set(self.col.get().saturating_add_signed(cols));
     }
+
+    pub(crate) fn set_row(&self, row: u64) {
+        self.row.set(row);
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_col(&self, col: u64) {
+        self.col.set(col);
+    }
+
+    #[cfg(feature = "search")]
+    pub(crate) fn set_scroll(&self, row: u64, col: u64, skip: u16) {
+        self.row.set(row);
+        self.col.set(col);
+        self.skip.set(skip);
+    }
+
+    pub(crate) fn set_height(&self, height: u16) {
+        self.height.set(height);
+    }
+
+    pub(crate) fn clamp_row(&mut self, max_row: u64) {
+        self.row.set(cmp::min(self.row.get(), max_row));
+    }
+
+    /// Set if the viewport is focused. Only a focused viewport is scrolled to keep the cursor visible on rendering.
+    /// Viewports are focused by default.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Get if the viewport is focused.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
 }
 
-pub struct Renderer<'a>(&'a TextArea<'a>);
+pub struct Renderer<'a>(&'a TextArea<'a>, &'a Viewport);
 
 impl<'a> Renderer<'a> {
     pub fn new(textarea: &'a TextArea<'a>) -> Self {
-        Self(textarea)
+        Self(textarea, &textarea.viewport)
+    }
+
+    pub fn with_viewport(textarea: &'a TextArea<'a>, viewport: &'a Viewport) -> Self {
+        Self(textarea, viewport)
+    }
+
+    // Build the text scrolled horizontally by `top_col`. With left alignment, the text is scrolled here so that only the
+    // visible part of long lines is rendered. This returns the text and the columns which are left to be scrolled.
+    #[inline]
+    fn text(&self, top_row: usize, height: usize, top_col: u64, width: u16) -> (Text<'a>, u64) {
+        let lnum_len = self.0.line_number_width();
+        let rows = self.0.display_rows(top_row, height);
+        let folds = self.0.shown_folds();
+        let bottom_row = rows.last().map_or(top_row, |row| row + 1);
+
+        let gutter = self.0.gutter_width() as u64;
+        let (hidden, scroll) = if self.0.alignment() == Alignment::Left && top_col > gutter {
+            ((top_col - gutter) as usize, 0)
+        } else {
+            (0, top_col)
+        };
+        let max_width = (width as usize).saturating_add(scroll as usize);
+
+        let (row, _) = self.0.cursor();
+        let highlights = self.0.text_highlights(top_row..bottom_row);
+        let ghost = self.0.shown_ghost_text();
+        self.0.begin_render_cache(lnum_len, &highlights, hidden, max_width);
+        let mut lines = Vec::with_capacity(rows.len());
+        for i in rows {
+            let mut spans = self.0.line_spans(row, i, lnum_len, &highlights, hidden, max_width);
+            // The suffix is not shown when the whole line is scrolled out
+            if let Some(fold) = folds.iter().find(|f| f.start == i) {
+                if hidden == 0 || self.0.is_wider_than(i, hidden - 1) {
+                    self.0.push_fold_suffix(&mut spans, fold);
+                }
+            }
+            match ghost {
+                Some(ghost) if i == row => self.push_ghost_lines(&mut lines, spans, ghost, hidden),
+                _ => lines.push(spans),
+            }
+        }
+        self.0.end_render_cache();
+        (Text::from(lines), scroll)
+    }
+
+    /// Insert the ghost text into the cursor line at the cursor. The text after the cursor follows the last line of
+    /// the ghost text and the lines between them are pushed as extra rows. `hidden` is the number of cells of the text
+    /// scrolled out to the left.
+    fn push_ghost_lines(&self, lines: &mut Vec<Line<'a>>, line: Line<'a>, ghost: &str, hidden: usize) {
+        let cells = self.0.cursor_cells();
+        if cells.start < hidden {
+            lines.push(line); // The cursor is scrolled out
+            return;
+        }
+        let gutter = self.0.gutter_width();
+        let x = if hidden == 0 {
+            gutter + cells.start
+        } else {
+            cells.start - hidden
+        };
+        #[cfg(feature = "ratatui")]
+        let spans = line.spans;
+        #[cfg(feature = "tuirs")]
+        let spans = line.0;
+        let (mut current, right) = split_spans(spans, x);
+
+        let tab_len = self.0.tab_length() as usize;
+        let style = self.0.ghost_text_style();
+        let mut cursor_style = self.0.shown_cursor_style().map(|s| style.patch(s));
+        for (i, text) in ghost.split('\n').enumerate() {
+            let mut text = if i == 0 {
+                expand_tabs(text, cells.start, tab_len)
+            } else {
+                lines.push(Line::from(current));
+                current = if hidden == 0 {
+                    vec![Span::raw(" ".repeat(gutter))]
+                } else {
+                    vec![]
+                };
+                skip_cells(&expand_tabs(text, 0, tab_len), hidden)
+            };
+            // The cursor is rendered on the first cell of the ghost text
+            if let Some(cursor_style) = cursor_style.take() {
+                let first = text.chars().next().map_or(0, char::len_utf8);
+                if first == 0 {
+                    current.push(Span::styled(" ", cursor_style));
+                } else {
+                    current.push(Span::styled(text[..first].to_string(), cursor_style));
+                    text.drain(..first);
+                }
+            }
+            if !text.is_empty() {
+                current.push(Span::styled(text, style));
+            }
+        }
+        current.extend(right);
+        lines.push(Line::from(current));
+    }
+
+    // Pairs of the screen row and the row of the line rendered on it. The cursor line is skipped when the ghost text is
+    // inserted into it, and the following lines are pushed down by the extra rows of the ghost text
+    fn screen_rows(&self, area: Rect, top_row: usize) -> Vec<(u16, usize)> {
+        let (cursor_row, _) = self.0.cursor();
+        let ghost = self.0.shown_ghost_text().is_some();
+        let extra = self.0.ghost_text_rows() as u16;
+        let mut y = area.y;
+        let mut rows = vec![];
+        for row in self.0.display_rows(top_row, area.height as usize) {
+            if y >= area.bottom() {
+                break;
+            }
+            if ghost && row == cursor_row {
+                y = y.saturating_add(extra + 1);
+                continue;
+            }
+            rows.push((y, row));
+            y += 1;
+        }
+        rows
     }
 
     #[inline]
-    fn text(&self, top_row: usize, height: usize) -> Text<'a> {
-        let lines_len = self.0.lines().len();
-        let lnum_len = num_digits(lines_len);
-        let bottom_row = cmp::min(top_row + height, lines_len);
+    fn wrapped_text(&self, top_row: usize, height: usize, wrapper: &Wrapper) -> Text<'a> {
+        let lines_len = self.0.len_lines();
+        let lnum_len = self.0.line_number_width();
 
         let (row, _) = self.0.cursor();
-        Text::from_iter(
-            self.0.lines()[top_row..bottom_row]
-                .iter()
-                .enumerate()
-                .map(|(i, line)| self.0.line_spans(row, line, top_row + i, lnum_len)),
-        )
+        // Each line takes at least one display row
+        let highlights = self.0.text_highlights(top_row..cmp::min(top_row + height, lines_len));
+        let mut text = vec![];
+        for i in top_row..lines_len {
+            if text.len() >= height {
+                break;
+            }
+            let line = self.0.line(i).unwrap_or_default();
+            text.extend(self.0.wrapped_line_spans(row, &line, i, lnum_len, &highlights, wrapper));
+        }
+        Text::from(text)
+    }
+
+    /// Calculate the top row and the number of its display rows to skip so that the display row of the position is
+    /// visible when lines are soft-wrapped.
+    fn wrapped_scroll_top(
+        &self,
+        (row, col): (usize, usize),
+        prev_top: u64,
+        prev_skip: u16,
+        height: u16,
+        wrapper: &Wrapper,
+    ) -> (u64, u16) {
+        let line = |row| self.0.line(row).unwrap_or_default();
+        let height = cmp::max(height as usize, 1);
+        let (sub, _) = wrapper.locate(&line(row), col);
+
+        // Keep the display rows of the scroll padding around the cursor visible
+        let padding = cmp::min(self.0.scroll_padding().0 as usize, (height - 1) / 2);
+        let (above_row, above_sub) = self.wrapped_rows_before((row, sub), padding, wrapper);
+        let (row, sub) = self.wrapped_rows_after((row, sub), padding, wrapper);
+
+        let (mut top, mut skip) = (prev_top as usize, prev_skip as usize);
+        if (above_row, above_sub) < (top, skip) {
+            return (above_row as u64, above_sub.try_into().unwrap_or(u16::MAX));
+        }
+        // Every line occupies at least one display row
+        if top + height <= row {
+            (top, skip) = (row + 1 - height, 0);
+        }
+
+        let above: usize = (top..row).map(|r| wrapper.height(&line(r), false)).sum();
+        let mut excess = (above + sub + 1).saturating_sub(skip + height);
+        while excess > 0 {
+            let remaining = wrapper.height(&line(top), false).saturating_sub(skip);
+            if top < row && remaining <= excess {
+                excess -= remaining;
+                (top, skip) = (top + 1, 0);
+            } else {
+                skip += excess;
+                excess = 0;
+            }
+        }
+
+        (top as u64, skip.try_into().unwrap_or(u16::MAX))
+    }
+
+    // Display row `n` rows before the display row `sub` of the line at `row`. It stops at the start of the text
+    fn wrapped_rows_before(
+        &self,
+        (mut row, mut sub): (usize, usize),
+        mut n: usize,
+        wrapper: &Wrapper,
+    ) -> (usize, usize) {
+        while n > sub {
+            if row == 0 {
+                return (0, 0);
+            }
+            n -= sub + 1;
+            row -= 1;
+            sub = wrapper.height(&self.0.line(row).unwrap_or_default(), false) - 1;
+        }
+        (row, sub - n)
+    }
+
+    // Display row `n` rows after the display row `sub` of the line at `row`. It stops at the end of the text
+    fn wrapped_rows_after(
+        &self,
+        (mut row, mut sub): (usize, usize),
+        mut n: usize,
+        wrapper: &Wrapper,
+    ) -> (usize, usize) {
+        let line = |row| self.0.line(row).unwrap_or_default();
+        // The row of the cursor at the end of line may be an extra display row
+        let mut height = cmp::max(wrapper.height(&line(row), false), sub + 1);
+        while sub + n >= height {
+            if row + 1 == self.0.len_lines() {
+                return (row, height - 1);
+            }
+            n -= height - sub;
+            row += 1;
+            sub = 0;
+            height = wrapper.height(&line(row), false);
+        }
+        (row, sub + n)
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Render the placeholder after the gutter of the first line. The cursor is drawn at the start of the placeholder.
+    fn render_placeholder(&self, paragraph: Paragraph<'_>, style: Style, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, style);
+        let gutter = cmp::min(self.0.gutter_width(), area.width as usize) as u16;
+        let signs = cmp::min(self.0.sign_column_width(), gutter as usize) as u16;
+        if let (true, Some(sign)) = (signs > 0, self.0.sign(0)) {
+            buf.set_stringn(area.x, area.y, sign.cells(), signs as usize, style.patch(sign.style));
+        }
+        if let Some(lnum_style) = self.0.line_number_style() {
+            let lnum = format!("{:>width$} ", 1, width = self.0.line_number_width() as usize);
+            let width = (gutter - signs) as usize;
+            buf.set_stringn(area.x + signs, area.y, lnum, width, style.patch(lnum_style));
+        }
+        let area = Rect {
+            x: area.x + gutter,
+            width: area.width - gutter,
+            ..area
+        };
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        paragraph.render(area, buf);
+
+        if let Some(cursor_style) = self.0.shown_cursor_style() {
+            let first = self.0.placeholder_lines().lines.first().map_or(0, |l| l.width());
+            let first = cmp::min(first, area.width as usize) as u16;
+            let x = match self.0.alignment() {
+                Alignment::Left => 0,
+                Alignment::Center => (area.width - first) / 2,
+                Alignment::Right => area.width - first,
+            };
+            buf.set_style(Rect::new(area.x + x, area.y, 1, 1), cursor_style);
+        }
+    }
+
+    /// Wrap the cells of links in OSC 8 escape sequences so that terminals supporting them make the links clickable.
+    /// The symbols of the cells of a link are joined into the first cell and the rest of the cells are skipped on
+    /// drawing. The cursor cell is not included since one cell has only one style. Control characters are removed from
+    /// the targets so that a target cannot end the sequence and inject other escape sequences.
+    #[cfg(feature = "hyperlinks")]
+    fn render_hyperlinks(&self, area: Rect, top_row: usize, top_col: usize, buf: &mut Buffer) {
+        if self.0.alignment() != Alignment::Left {
+            return;
+        }
+        let gutter = self.0.gutter_width();
+        let (cursor_row, _) = self.0.cursor();
+        let cursor = self.0.cursor_cells();
+        // Screen column of the display column of the text
+        let screen_x = |col: usize| cmp::min((gutter + col).saturating_sub(top_col), area.width as usize) as u16;
+
+        for (y, row) in self.screen_rows(area, top_row) {
+            // Links are detected around the visible part of the line
+            let (part, _, end_col) = self
+                .0
+                .visible_part(row, top_col.saturating_sub(gutter), area.width.into());
+            for (range, target) in self.0.links_in(row, part.col..end_col) {
+                let target: String = target.chars().filter(|c| !c.is_control()).collect();
+                let cells = self.0.display_cells(row, range);
+                let mut segments = vec![(screen_x(cells.start), screen_x(cells.end))];
+                if row == cursor_row && cells.start < cursor.end && cursor.start < cells.end {
+                    segments = vec![
+                        (screen_x(cells.start), screen_x(cursor.start)),
+                        (screen_x(cursor.end), screen_x(cells.end)),
+                    ];
+                }
+                for (start, end) in segments {
+                    if start >= end {
+                        continue;
+                    }
+                    let mut text = String::new();
+                    let mut x = start;
+                    while x < end {
+                        let symbol = buf.get(area.x + x, y).symbol();
+                        text.push_str(symbol);
+                        x += cmp::max(symbol.width(), 1) as u16;
+                    }
+                    let link = format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", target, text);
+                    buf.get_mut(area.x + start, y).set_symbol(&link);
+                    for x in start + 1..end {
+                        buf.get_mut(area.x + x, y).set_skip(true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Overlay the truncation indicator on the first and the last cells of rows whose lines continue beyond the left
+    /// or the right edge of the area. `top_col` is the number of columns scrolled out including the gutter.
+    fn render_truncation_indicators(&self, area: Rect, top_row: usize, top_col: usize, buf: &mut Buffer) {
+        let Some((indicator, style)) = self.0.truncation_indicator() else {
+            return;
+        };
+        let len = indicator.width();
+        let width = area.width as usize;
+        if len == 0 || len > width || self.0.alignment() != Alignment::Left {
+            return;
+        }
+        let gutter = self.0.gutter_width();
+        // Columns of the cursor counted in the same way as `top_col`
+        let (cursor_row, _) = self.0.cursor();
+        let cursor = self.0.cursor_cells();
+        let cursor = gutter + cursor.start..gutter + cursor.end;
+        let overlaps = |row: usize, start: usize| row == cursor_row && cursor.start < start + len && start < cursor.end;
+
+        for (y, row) in self.screen_rows(area, top_row) {
+            if top_col > gutter && self.0.is_wider_than(row, 0) && !overlaps(row, top_col) {
+                buf.set_stringn(area.x, y, indicator, len, style);
+            }
+            let right = top_col + width - len;
+            // Only the line up to the right edge is read
+            let beyond = (top_col + width)
+                .checked_sub(gutter)
+                .map_or(true, |cells| self.0.is_wider_than(row, cells));
+            if beyond && !overlaps(row, right) {
+                let x = area.x + (width - len) as u16;
+                // A wide character whose second half is covered by the indicator cannot be rendered
+                if x > area.x {
+                    #[cfg(feature = "ratatui")]
+                    let prev = buf.get(x - 1, y).symbol();
+                    #[cfg(feature = "tuirs")]
+                    let prev = &buf.get(x - 1, y).symbol;
+                    if prev.width() > 1 {
+                        buf.get_mut(x - 1, y).set_symbol(" ");
+                    }
+                }
+                buf.set_stringn(x, y, indicator, len, style);
+            }
+        }
+    }
+}
+
+// Split the spans at the display cell `x`
+fn split_spans(spans: Vec<Span<'_>>, x: usize) -> (Vec<Span<'_>>, Vec<Span<'_>>) {
+    let (mut left, mut right) = (vec![], vec![]);
+    let mut width = 0;
+    for span in spans {
+        let w = span.content.width();
+        if width >= x {
+            right.push(span);
+        } else if width + w <= x {
+            width += w;
+            left.push(span);
+        } else {
+            let mut end = 0;
+            for c in span.content.chars() {
+                let w = c.width().unwrap_or(0);
+                if width + w > x {
+                    break;
+                }
+                width += w;
+                end += c.len_utf8();
+            }
+            let (l, r) = span.content.split_at(end);
+            left.push(Span::styled(l.to_string(), span.style));
+            right.push(Span::styled(r.to_string(), span.style));
+            width = x;
+        }
+    }
+    (left, right)
+}
+
+// Replace tabs with spaces to the next tab stop. `col` is the display column where the text starts
+fn expand_tabs(text: &str, mut col: usize, tab_len: usize) -> String {
+    let mut expanded = String::new();
+    for c in text.chars() {
+        if c == '\t' {
+            let w = if tab_len == 0 { 0 } else { tab_len - col % tab_len };
+            expanded.extend(std::iter::repeat(' ').take(w));
+            col += w;
+        } else {
+            expanded.push(c);
+            col += c.width().unwrap_or(0);
+        }
+    }
+    expanded
+}
+
+// Remove the first `cells` display cells of the text. A wide character crossing the boundary is replaced with spaces
+fn skip_cells(text: &str, cells: usize) -> String {
+    let mut width = 0;
+    for (i, c) in text.char_indices() {
+        if width >= cells {
+            return format!("{}{}", " ".repeat(width - cells), &text[i..]);
+        }
+        width += c.width().unwrap_or(0);
     }
+    " ".repeat(width.saturating_sub(cells))
 }
 
 impl<'a> Widget for Renderer<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let Rect { width, height, .. } = if let Some(b) = self.0.block() {
+        let inner = if let Some(b) = self.0.shown_block() {
             b.inner(area)
         } else {
             area
         };
+        // Scrollbars take the right column and the bottom row inside the block
+        let (text_area, vertical_bar, horizontal_bar) = match self.0.scrollbar() {
+            Some(config) => config.split(inner),
+            None => (inner, None, None),
+        };
+        let Rect { width, height, .. } = text_area;
 
-        fn next_scroll_top(prev_top: u64, cursor: u64, length: u64) -> u64 {
-            if cursor < prev_top {
-                cursor
-            } else if prev_top + length <= cursor {
-                cursor + 1 - length
+        // `padding` is the number of rows or columns kept visible before and after the cursor
+        fn next_scroll_top(prev_top: u64, cursor: u64, length: u64, padding: u64) -> u64 {
+            // Padding larger than half of the viewport keeps the cursor at the center
+            let padding = cmp::min(padding, length.saturating_sub(1) / 2);
+            if cursor < prev_top + padding {
+                cursor.saturating_sub(padding)
+            } else if prev_top + length <= cursor + padding {
+                cursor + padding + 1 - length
             } else {
                 prev_top
             }
         }
 
-        let (row, col) = self.0.cursor();
-        let (top_row, top_col) = self.0.viewport.scroll_top();
-        let top_row = next_scroll_top(top_row, row as u64, height.into());
+        let (row, col) = self.0.scroll_target();
+        let (top_row, top_col) = self.1.scroll_top();
+        let prev_skip = self.1.skip();
+        let wrapper = self.0.wrapper(width);
+
+        // Only focused viewports are scrolled automatically
+        let following = self.1.is_focused() && self.0.is_following();
+        let cursor_follow = self.1.is_focused() && self.0.cursor_follow();
 
-        let line_number_offset = if self.0.line_number_style().is_some() {
-            u64::from(num_digits(row)) + 1
+        let (top_row, top_col, skip) = if !following && !cursor_follow {
+            if wrapper.is_some() {
+                let top_row = cmp::min(top_row, self.0.len_lines().saturating_sub(1) as u64);
+                (top_row, 0, prev_skip)
+            } else {
+                // The last line is not scrolled above the bottom of the viewport
+                let folds = self.0.shown_folds();
+                let max_top = fold::display_row(&folds, self.0.len_lines()).saturating_sub(height.into());
+                let top = cmp::min(fold::display_row(&folds, top_row as usize), max_top);
+                (fold::row_at_display(&folds, top) as u64, top_col, prev_skip)
+            }
+        } else if let Some(wrapper) = &wrapper {
+            let (top_row, skip) = if following {
+                // Pin the last display row of the text to the bottom
+                let last = self.0.len_lines() - 1;
+                let col = self.0.line_len(last).unwrap_or(0).saturating_sub(1);
+                self.wrapped_scroll_top((last, col), 0, 0, height, wrapper)
+            } else if let Some(align) = self.0.take_cursor_align() {
+                let (sub, _) = wrapper.locate(&self.0.line(row).unwrap_or_default(), col);
+                let (top, skip) = self.wrapped_rows_before((row, sub), align.rows_above(height), wrapper);
+                (top as u64, skip.try_into().unwrap_or(u16::MAX))
+            } else {
+                self.wrapped_scroll_top((row, col), top_row, prev_skip, height, wrapper)
+            };
+            (top_row, 0, skip)
         } else {
-            0
-        };
+            let (pad_rows, pad_cols) = self.0.scroll_padding();
+            // Scroll by display rows so that folded lines are counted as one row
+            let folds = self.0.shown_folds();
+            let lines_len = fold::display_row(&folds, self.0.len_lines()) as u64;
+            let row = fold::display_row(&folds, row);
+            let top_row = fold::display_row(&folds, top_row as usize) as u64;
+            let top_row = if following {
+                next_scroll_top(0, lines_len - 1, height.into(), 0)
+            } else if let Some(align) = self.0.take_cursor_align() {
+                (row as u64).saturating_sub(align.rows_above(height) as u64)
+            } else {
+                let top = next_scroll_top(top_row, row as u64, height.into(), pad_rows.into());
+                if top > top_row {
+                    // The padding does not scroll the viewport beyond the end of the text
+                    let max_top = lines_len.saturating_sub(height.into());
+                    cmp::max(
+                        cmp::min(top, max_top),
+                        next_scroll_top(top_row, row as u64, height.into(), 0),
+                    )
+                } else {
+                    top
+                }
+            };
+            let top_row = fold::row_at_display(&folds, top_row as usize) as u64;
 
-        let top_col = next_scroll_top(top_col, col as u64, u64::from(width) - line_number_offset);
+            // Scroll by display cells so that the whole cell of the cursor is visible even on tabs and wide characters
+            let gutter = self.0.gutter_width() as u64;
+            let top_col = if cursor_follow {
+                let cells = self.0.cells_at(self.0.scroll_target());
+                let (start, end) = (cells.start as u64, cells.end as u64);
+                let length = u64::from(width).saturating_sub(gutter);
+                let top_col = next_scroll_top(top_col, end - 1, length, pad_cols.into());
+                cmp::min(
+                    top_col,
+                    start.saturating_sub(cmp::min(pad_cols.into(), length.saturating_sub(1) / 2)),
+                )
+            } else {
+                top_col
+            };
+            (top_row, top_col, 0)
+        };
 
-        let (text, style) = if !self.0.placeholder.is_empty() && self.0.is_empty() {
-            let text = Text::from(self.0.placeholder.as_str());
-            (text, self.0.placeholder_style)
+        let placeholder = !self.0.placeholder.is_empty() && self.0.is_empty() && self.0.shown_ghost_text().is_none();
+        let (text, style, scroll) = if placeholder {
+            (self.0.placeholder_lines(), self.0.placeholder_style, 0)
+        } else if let Some(wrapper) = &wrapper {
+            let height = height as usize + skip as usize;
+            (
+                self.wrapped_text(top_row as usize, height, wrapper),
+                self.0.style(),
+                top_col,
+            )
         } else {
-            (self.text(top_row as usize, height as usize), self.0.style())
+            let (text, scroll) = self.text(top_row as usize, height as usize, top_col, width);
+            (text, self.0.style(), scroll)
         };
 
+        // Rows below the last line of the text
+        let text_rows = text.lines.len().saturating_sub(skip as usize);
+
         // To get fine control over the text color and the surrrounding block they have to be rendered separately
         // see https://github.com/ratatui-org/ratatui/issues/144
-        let mut text_area = area;
+        let invalid = self.0.validation_error().is_some();
+        let style = if invalid {
+            style.patch(self.0.invalid_style())
+        } else {
+            style
+        };
+
         let mut inner = Paragraph::new(text).style(style).alignment(self.0.alignment());
-        if let Some(b) = self.0.block() {
-            text_area = b.inner(area);
-            b.clone().render(area, buf)
+        if let Some(b) = self.0.shown_block() {
+            let b = b.clone();
+            if invalid {
+                b.border_style(self.0.invalid_style()).render(area, buf);
+            } else {
+                b.render(area, buf);
+            }
         }
-        if top_col != 0 {
-            inner = inner.scroll((0, top_col.try_into().unwrap()));
+        if placeholder {
+            // The placeholder is not scrolled since it has no cursor to follow
+            if wrapper.is_some() {
+                inner = inner.wrap(Wrap { trim: false });
+            }
+        } else if scroll != 0 || skip != 0 {
+            inner = inner.scroll((skip, scroll.try_into().unwrap_or(u16::MAX)));
         }
 
         // Store scroll top position for rendering on the next tick
-        self.0.viewport.store(top_row, top_col, width, height);
+        self.1.store(top_row, top_col, skip, text_area);
+        self.1.scrollbars.set((vertical_bar, horizontal_bar));
+
+        if placeholder {
+            self.render_placeholder(inner, style, text_area, buf);
+        } else {
+            inner.render(text_area, buf);
+            if wrapper.is_none() {
+                self.render_truncation_indicators(text_area, top_row as usize, top_col as usize, buf);
+                #[cfg(feature = "hyperlinks")]
+                self.render_hyperlinks(text_area, top_row as usize, top_col as usize, buf);
+            }
+            if let Some((marker, style)) = self.0.empty_line_marker() {
+                for y in text_area.y + cmp::min(text_rows, height as usize) as u16..text_area.bottom() {
+                    buf.set_stringn(text_area.x, y, marker, width as usize, style);
+                }
+            }
+        }
+
+        if let Some(config) = self.0.scrollbar() {
+            let rows = self.0.visible_rows_in(self.1);
+            if let Some(bar) = vertical_bar {
+                config.render_vertical(bar, buf, self.0.len_lines(), rows.len(), rows.start);
+            }
+            if let Some(bar) = horizontal_bar {
+                // Soft-wrapped lines always fit in the viewport
+                let total = if wrapper.is_some() {
+                    0
+                } else {
+                    // The cursor at the end of line takes one more cell
+                    let widest = rows.map(|row| self.0.display_width(row)).max().unwrap_or(0);
+                    self.0.gutter_width() + widest + 1
+                };
+                config.render_horizontal(bar, buf, total, width.into(), top_col as usize);
+            }
+        }
+    }
+}
+
+/// Render the textarea with its own viewport. This is the same as rendering [`TextArea::widget`].
+impl Widget for &TextArea<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Renderer::new(self).render(area, buf);
+    }
+}
+
+/// Render the textarea with the given viewport as state. The scroll position is stored in the viewport instead of the
+/// textarea's own viewport.
+impl StatefulWidget for &TextArea<'_> {
+    type State = Viewport;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Viewport) {
+        Renderer::with_viewport(self, state).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ratatui::buffer::Buffer;
+    use crate::ratatui::layout::{Alignment, Rect};
+    use crate::ratatui::style::{Color, Modifier, Style};
+    use crate::ratatui::widgets::{Block, Borders};
+    use crate::ratatui::widgets::{StatefulWidget, Widget};
+    use crate::{
+        CursorAlign, CursorMove, Highlight, Input, Key, ScrollbarConfig, Scrolling, Sign, TextArea, Viewport,
+        WhitespaceStyle,
+    };
+    #[cfg(feature = "search")]
+    use crate::{SearchMode, SearchScope};
+    use unicode_width::UnicodeWidthStr as _;
+
+    // The symbol of a cell is a method on ratatui and a field on tui-rs
+    fn cell_symbol(b: &Buffer, x: u16, y: u16) -> &str {
+        #[cfg(feature = "ratatui")]
+        let symbol = b.get(x, y).symbol();
+        #[cfg(feature = "tuirs")]
+        let symbol = &b.get(x, y).symbol;
+        symbol
+    }
+
+    fn buffer_lines(b: &Buffer) -> Vec<String> {
+        let Rect { width, height, .. } = b.area;
+        (0..height)
+            .map(|y| {
+                let mut line = String::new();
+                let mut x = 0;
+                while x < width {
+                    let symbol = cell_symbol(b, x, y);
+                    line.push_str(symbol);
+                    x += symbol.width().max(1) as u16;
+                }
+                line
+            })
+            .collect()
+    }
+
+    fn render(textarea: &TextArea<'_>, width: u16, height: u16) -> Vec<String> {
+        let r = Rect { x: 0, y: 0, width, height };
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        buffer_lines(&b)
+    }
+
+    fn render_stateful(textarea: &TextArea<'_>, width: u16, height: u16, viewport: &mut Viewport) -> Vec<String> {
+        let r = Rect { x: 0, y: 0, width, height };
+        let mut b = Buffer::empty(r);
+        StatefulWidget::render(textarea, r, &mut b, viewport);
+        buffer_lines(&b)
+    }
+
+    #[test]
+    fn widget_ref() {
+        let textarea = TextArea::from(["abc", "def"]);
+        let r = Rect::new(0, 0, 4, 2);
+        let mut b = Buffer::empty(r);
+        Widget::render(&textarea, r, &mut b);
+        assert_eq!(buffer_lines(&b), ["abc ", "def "]);
+        assert_eq!(textarea.viewport.rect(), (0, 0, 4, 2));
+    }
+
+    #[test]
+    fn split_view() {
+        let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+        let mut top = Viewport::default();
+        let mut bottom = Viewport::default();
+        bottom.set_focused(false);
+
+        assert_eq!(render_stateful(&textarea, 2, 2, &mut top), ["0 ", "1 "]);
+        assert_eq!(render_stateful(&textarea, 2, 2, &mut bottom), ["0 ", "1 "]);
+
+        // Unfocused viewport does not follow the cursor
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(render_stateful(&textarea, 2, 2, &mut top), ["8 ", "9 "]);
+        assert_eq!(render_stateful(&textarea, 2, 2, &mut bottom), ["0 ", "1 "]);
+
+        bottom.scroll(4, 0);
+        assert_eq!(render_stateful(&textarea, 2, 2, &mut bottom), ["4 ", "5 "]);
+        assert_eq!(top.scroll_top(), (8, 0));
+        assert_eq!(bottom.scroll_top(), (4, 0));
+
+        // The textarea's own viewport is independent of the states
+        assert_eq!(textarea.viewport.scroll_top(), (0, 0));
+    }
+
+    #[test]
+    fn soft_wrap() {
+        let mut textarea = TextArea::from(["abcdefghij", "xy"]);
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 4, 4), ["abcd", "efgh", "ij  ", "xy  "]);
+
+        textarea.set_wrap_indicator(Some((">".to_string(), Style::default())));
+        assert_eq!(render(&textarea, 4, 5), ["abcd", ">efg", ">hij", "xy  ", "    "]);
+
+        textarea.set_wrap(false);
+        assert_eq!(render(&textarea, 4, 2), ["abcd", "xy  "]);
+    }
+
+    #[test]
+    fn soft_wrap_words() {
+        let mut textarea = TextArea::from(["hello world foo", "abc defghijk"]);
+        textarea.set_wrap(true);
+        let want = ["hello   ", "world   ", "foo     ", "abc     ", "defghijk"];
+        assert_eq!(render(&textarea, 8, 5), want);
+
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.cursor(), (0, 6));
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.cursor(), (0, 12));
+    }
+
+    #[test]
+    fn soft_wrap_with_line_number() {
+        let mut textarea = TextArea::from(["abcdefgh"]);
+        textarea.set_wrap(true);
+        textarea.set_line_number_style(Style::default());
+        assert_eq!(render(&textarea, 6, 3), ["1 abcd", "  efgh", "      "]);
+    }
+
+    #[test]
+    fn soft_wrap_scroll() {
+        let mut textarea = TextArea::from(["abcdefghij", "xy", "z"]);
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 4, 2), ["abcd", "efgh"]);
+
+        textarea.move_cursor(CursorMove::Down);
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.cursor(), (0, 8));
+        assert_eq!(render(&textarea, 4, 2), ["efgh", "ij  "]);
+
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.cursor(), (1, 0));
+        assert_eq!(render(&textarea, 4, 2), ["ij  ", "xy  "]);
+
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(render(&textarea, 4, 2), ["xy  ", "z   "]);
+
+        textarea.move_cursor(CursorMove::Up);
+        textarea.move_cursor(CursorMove::Up);
+        assert_eq!(textarea.cursor(), (0, 8));
+        assert_eq!(render(&textarea, 4, 2), ["ij  ", "xy  "]);
+    }
+
+    #[test]
+    fn scroll_padding() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        textarea.set_scroll_padding(2, 0);
+        let top_row = |textarea: &TextArea| {
+            render(textarea, 4, 6);
+            textarea.viewport_rect().0
+        };
+
+        // Near the top, the padding is not applied at the start of the text
+        textarea.move_cursor(CursorMove::Jump(1, 0));
+        assert_eq!(top_row(&textarea), 0);
+        textarea.move_cursor(CursorMove::Jump(3, 0));
+        assert_eq!(top_row(&textarea), 0);
+        textarea.move_cursor(CursorMove::Jump(4, 0));
+        assert_eq!(top_row(&textarea), 1);
+        // In the middle
+        textarea.move_cursor(CursorMove::Jump(10, 0));
+        assert_eq!(top_row(&textarea), 7);
+        textarea.move_cursor(CursorMove::Jump(9, 0));
+        assert_eq!(top_row(&textarea), 7);
+        textarea.move_cursor(CursorMove::Jump(8, 0));
+        assert_eq!(top_row(&textarea), 6);
+        // Near the bottom, the viewport does not go beyond the end of the text
+        textarea.move_cursor(CursorMove::Jump(18, 0));
+        assert_eq!(top_row(&textarea), 14);
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(top_row(&textarea), 14);
+
+        // Scrolling moves the cursor out of the padding instead of being scrolled back
+        textarea.move_cursor(CursorMove::Jump(10, 0));
+        assert_eq!(top_row(&textarea), 8);
+        textarea.scroll((1, 0));
+        assert_eq!(textarea.cursor(), (11, 0));
+        assert_eq!(top_row(&textarea), 9);
+        textarea.scroll((-3, 0));
+        assert_eq!(textarea.cursor(), (9, 0));
+        assert_eq!(top_row(&textarea), 6);
+
+        // Padding larger than half of the viewport keeps the cursor at the center
+        textarea.set_scroll_padding(10, 0);
+        textarea.move_cursor(CursorMove::Jump(15, 0));
+        assert_eq!(top_row(&textarea), 12);
+        textarea.move_cursor(CursorMove::Up);
+        assert_eq!(top_row(&textarea), 12);
+        textarea.move_cursor(CursorMove::Up);
+        assert_eq!(top_row(&textarea), 11);
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(top_row(&textarea), 11);
+
+        // The buffer shorter than the viewport is not scrolled
+        let mut textarea = TextArea::from(["a", "b", "c"]);
+        textarea.set_scroll_padding(2, 0);
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(render(&textarea, 2, 6), ["a ", "b ", "c ", "  ", "  ", "  "]);
+
+        // Columns
+        let mut textarea = TextArea::from(["0123456789"]);
+        textarea.set_scroll_padding(0, 2);
+        textarea.move_cursor(CursorMove::Jump(0, 3));
+        assert_eq!(render(&textarea, 6, 1), ["012345"]);
+        textarea.move_cursor(CursorMove::Jump(0, 4));
+        assert_eq!(render(&textarea, 6, 1), ["123456"]);
+        textarea.move_cursor(CursorMove::Jump(0, 2));
+        assert_eq!(render(&textarea, 6, 1), ["012345"]);
+    }
+
+    #[test]
+    fn visible_rows_and_positions() {
+        // Soft wrap
+        let mut textarea = TextArea::from(["abcdefgh", "ij", "klmnopqr", "st"]);
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 4, 3), ["abcd", "efgh", "ij  "]);
+        assert_eq!(textarea.visible_rows(), 0..2);
+        assert!(textarea.is_position_visible(0, 7));
+        assert!(textarea.is_position_visible(1, 2));
+        assert!(!textarea.is_position_visible(2, 0));
+
+        textarea.move_cursor(CursorMove::Jump(2, 0));
+        assert_eq!(render(&textarea, 4, 3), ["efgh", "ij  ", "klmn"]);
+        assert_eq!(textarea.visible_rows(), 0..3);
+        assert!(!textarea.is_position_visible(0, 0));
+        assert!(textarea.is_position_visible(0, 4));
+        assert!(textarea.is_position_visible(2, 3));
+        assert!(!textarea.is_position_visible(2, 4));
+
+        // Line numbers and horizontal scroll
+        let mut textarea = TextArea::from(["abcdefgh", "ij"]);
+        textarea.set_line_number_style(Style::default());
+        textarea.move_cursor(CursorMove::End);
+        // The gutter is scrolled out with the text
+        assert_eq!(render(&textarea, 6, 2), ["defgh ", "      "]);
+        assert_eq!(textarea.visible_rows(), 0..2);
+        assert!(!textarea.is_position_visible(0, 2));
+        assert!(textarea.is_position_visible(0, 3));
+        assert!(textarea.is_position_visible(0, 8));
+        assert!(!textarea.is_position_visible(1, 1));
+
+        // Columns on the screen are not known with other alignments
+        let mut textarea = TextArea::from(["ab", "cdefghijkl", "m"]);
+        textarea.move_cursor(CursorMove::End);
+        textarea.set_alignment(Alignment::Right);
+        assert_eq!(render(&textarea, 10, 2), ["       ab ", "cdefghijkl"]);
+        assert_eq!(textarea.cursor_screen_position(), None);
+        assert!(textarea.is_position_visible(0, 2));
+        assert!(!textarea.is_position_visible(1, 0)); // Does not fit in the width
+        assert!(!textarea.is_position_visible(2, 0)); // Below the viewport
+    }
+
+    #[test]
+    fn scrollbar() {
+        let mut textarea: TextArea = (0..8).map(|i| format!("{i}{i}{i}{i}{i}{i}")).collect();
+        let config = ScrollbarConfig {
+            thumb: '#',
+            track: '|',
+            horizontal_track: '-',
+            horizontal: true,
+            ..Default::default()
+        };
+        textarea.set_scrollbar(Some(config));
+        textarea.set_block(Block::default().borders(Borders::ALL));
+
+        // The text does not render under the scrollbars
+        #[rustfmt::skip]
+        let expected = [
+            "┌─────┐",
+            "│0000#│",
+            "│1111|│",
+            "│##-- │",
+            "└─────┘",
+        ];
+        assert_eq!(render(&textarea, 7, 5), expected);
+        let rects = textarea.scrollbar_rects();
+        assert_eq!(rects, (Some(Rect::new(5, 1, 1, 2)), Some(Rect::new(1, 3, 4, 1))));
+
+        // The viewport scrolls within the area of the text
+        textarea.move_cursor(CursorMove::Bottom);
+        textarea.move_cursor(CursorMove::End);
+        #[rustfmt::skip]
+        let expected = [
+            "┌─────┐",
+            "│666 |│",
+            "│777 #│",
+            "│--## │",
+            "└─────┘",
+        ];
+        assert_eq!(render(&textarea, 7, 5), expected);
+        assert_eq!(textarea.viewport_rect(), (6, 3, 4, 2));
+
+        // Soft-wrapped lines always fit in the viewport
+        textarea.set_wrap(true);
+        textarea.set_scrollbar(Some(ScrollbarConfig { horizontal: false, ..config }));
+        textarea.move_cursor(CursorMove::Jump(0, 0));
+        #[rustfmt::skip]
+        let expected = [
+            "┌─────┐",
+            "│0000#│",
+            "│00  |│",
+            "│1111|│",
+            "└─────┘",
+        ];
+        assert_eq!(render(&textarea, 7, 5), expected);
+        assert_eq!(textarea.scrollbar_rects(), (Some(Rect::new(5, 1, 1, 3)), None));
+
+        // The thumb fills the track of a one-row viewport
+        textarea.set_block(Block::default());
+        textarea.move_cursor(CursorMove::Jump(3, 0));
+        assert_eq!(render(&textarea, 5, 1), ["3333#"]);
+
+        textarea.set_scrollbar(None);
+        render(&textarea, 7, 5);
+        assert_eq!(textarea.scrollbar_rects(), (None, None));
+    }
+
+    #[test]
+    fn goto_line_centers_cursor() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        render(&textarea, 4, 5);
+
+        assert!(textarea.goto_line(11, None, false));
+        assert_eq!(textarea.cursor(), (10, 0));
+        assert_eq!(render(&textarea, 4, 5), ["8   ", "9   ", "10  ", "11  ", "12  "]);
+
+        // The last line is also centered like `zz` of Vim
+        assert!(textarea.goto_line(100, None, true));
+        assert_eq!(render(&textarea, 4, 5), ["17  ", "18  ", "19  ", "    ", "    "]);
+        assert_eq!(textarea.jump_list(), [(0, 0), (10, 0)]);
+    }
+
+    #[test]
+    fn scroll_cursor() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        let top_row = |textarea: &TextArea| {
+            render(textarea, 4, 5);
+            textarea.viewport_rect().0
+        };
+
+        textarea.move_cursor(CursorMove::Jump(10, 0));
+        assert_eq!(top_row(&textarea), 6);
+        textarea.scroll_cursor(CursorAlign::Center);
+        assert_eq!(top_row(&textarea), 8);
+        textarea.scroll_cursor(CursorAlign::Top);
+        assert_eq!(top_row(&textarea), 10);
+        textarea.scroll_cursor(CursorAlign::Bottom);
+        assert_eq!(top_row(&textarea), 6);
+        // The alignment is applied only once
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(top_row(&textarea), 7);
+
+        // The viewport does not scroll before the start of the text
+        textarea.move_cursor(CursorMove::Jump(1, 0));
+        textarea.scroll_cursor(CursorAlign::Bottom);
+        assert_eq!(top_row(&textarea), 0);
+
+        // The alignment wins over the scroll padding for one frame
+        textarea.set_scroll_padding(1, 0);
+        textarea.move_cursor(CursorMove::Jump(15, 0));
+        textarea.scroll_cursor(CursorAlign::Top);
+        assert_eq!(top_row(&textarea), 15);
+        assert_eq!(top_row(&textarea), 14);
+
+        // Soft-wrapped lines are aligned by display rows
+        let mut textarea = TextArea::from(["abcdefgh", "ij", "klmnopqr", "st"]);
+        textarea.set_wrap(true);
+        textarea.move_cursor(CursorMove::Jump(2, 5));
+        textarea.scroll_cursor(CursorAlign::Center);
+        assert_eq!(render(&textarea, 4, 3), ["klmn", "opqr", "st  "]);
+        textarea.scroll_cursor(CursorAlign::Bottom);
+        assert_eq!(render(&textarea, 4, 3), ["ij  ", "klmn", "opqr"]);
+        textarea.scroll_cursor(CursorAlign::Top);
+        assert_eq!(render(&textarea, 4, 3), ["opqr", "st  ", "    "]);
+    }
+
+    #[test]
+    fn viewport_motions() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        // Before the first rendering, the whole text is the viewport
+        textarea.move_cursor(CursorMove::ViewportBottom);
+        assert_eq!(textarea.cursor(), (19, 0));
+        textarea.move_cursor(CursorMove::ViewportMiddle);
+        assert_eq!(textarea.cursor(), (9, 0));
+        textarea.move_cursor(CursorMove::ViewportTop);
+        assert_eq!(textarea.cursor(), (0, 0));
+        textarea.scroll_half_page_down();
+        assert_eq!(textarea.cursor(), (0, 0));
+
+        textarea.move_cursor(CursorMove::Jump(10, 0));
+        render(&textarea, 4, 5);
+        assert_eq!(textarea.viewport_rect().0, 6);
+        textarea.move_cursor(CursorMove::ViewportTop);
+        assert_eq!(textarea.cursor(), (6, 0));
+        textarea.move_cursor(CursorMove::ViewportBottom);
+        assert_eq!(textarea.cursor(), (10, 0));
+        textarea.move_cursor(CursorMove::ViewportMiddle);
+        assert_eq!(textarea.cursor(), (8, 0));
+
+        // The text shorter than the viewport
+        let mut textarea = TextArea::from(["a", "bc", "d"]);
+        textarea.move_cursor(CursorMove::Jump(1, 2));
+        render(&textarea, 4, 5);
+        textarea.move_cursor(CursorMove::ViewportBottom);
+        assert_eq!(textarea.cursor(), (2, 1));
+        textarea.move_cursor(CursorMove::ViewportMiddle);
+        assert_eq!(textarea.cursor(), (1, 1));
+
+        // Soft-wrapped lines
+        let mut textarea = TextArea::from(["abcdefgh", "ij", "klmnopqr", "st"]);
+        textarea.set_wrap(true);
+        render(&textarea, 4, 4);
+        textarea.move_cursor(CursorMove::ViewportBottom);
+        assert_eq!(textarea.cursor(), (2, 0));
+        textarea.move_cursor(CursorMove::ViewportMiddle);
+        assert_eq!(textarea.cursor(), (1, 0));
+    }
+
+    #[test]
+    fn scroll_half_page() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        render(&textarea, 4, 6);
+        textarea.move_cursor(CursorMove::Jump(1, 0));
+
+        textarea.scroll_half_page_down();
+        assert_eq!(textarea.cursor(), (4, 0));
+        assert_eq!(render(&textarea, 4, 6)[0], "3   ");
+        textarea.scroll_half_page_down();
+        assert_eq!(textarea.cursor(), (7, 0));
+        assert_eq!(render(&textarea, 4, 6)[0], "6   ");
+        textarea.scroll_half_page_up();
+        assert_eq!(textarea.cursor(), (4, 0));
+        assert_eq!(render(&textarea, 4, 6)[0], "3   ");
+
+        // The cursor stops at the end of the text
+        textarea.move_cursor(CursorMove::Jump(18, 0));
+        render(&textarea, 4, 6);
+        textarea.scroll_half_page_down();
+        assert_eq!(textarea.cursor(), (19, 0));
+        textarea.scroll_half_page_up();
+        assert_eq!(textarea.cursor(), (16, 0));
+
+        // Selection is extended
+        textarea.start_selection();
+        textarea.scroll_half_page_up();
+        assert_eq!(textarea.selection_range(), Some(((13, 0), (16, 0))));
+    }
+
+    #[test]
+    fn jump_list_by_viewport_height() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        render(&textarea, 4, 3);
+
+        // Moving more rows than the viewport height records the previous position
+        for _ in 0..3 {
+            textarea.move_cursor(CursorMove::Down);
+        }
+        assert!(textarea.jump_list().is_empty());
+        textarea.move_cursor(CursorMove::ParagraphForward);
+        textarea.move_cursor(CursorMove::ParagraphBack);
+        assert_eq!(textarea.jump_list(), [(3, 0), (19, 0)]);
+
+        // Cursor moves caused by scrolling are not recorded
+        render(&textarea, 4, 3);
+        textarea.scroll_half_page_down();
+        textarea.scroll(Scrolling::PageDown);
+        assert_eq!(textarea.cursor(), (4, 0));
+        assert_eq!(textarea.jump_list(), [(3, 0), (19, 0)]);
+
+        textarea.set_jump_threshold(Some(0));
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.jump_list(), [(3, 0), (19, 0), (4, 0)]);
+    }
+
+    #[test]
+    fn soft_wrap_scroll_padding() {
+        let mut textarea = TextArea::from(["abcdefgh", "ij", "kl", "mnopqrst", "uv"]);
+        textarea.set_wrap(true);
+        textarea.set_scroll_padding(1, 0);
+        assert_eq!(render(&textarea, 4, 3), ["abcd", "efgh", "ij  "]);
+
+        textarea.move_cursor(CursorMove::Jump(1, 0));
+        assert_eq!(render(&textarea, 4, 3), ["efgh", "ij  ", "kl  "]);
+        textarea.move_cursor(CursorMove::Jump(2, 0));
+        assert_eq!(render(&textarea, 4, 3), ["ij  ", "kl  ", "mnop"]);
+        textarea.move_cursor(CursorMove::Jump(3, 0));
+        assert_eq!(render(&textarea, 4, 3), ["kl  ", "mnop", "qrst"]);
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(render(&textarea, 4, 3), ["mnop", "qrst", "uv  "]);
+        textarea.move_cursor(CursorMove::Jump(3, 0));
+        assert_eq!(render(&textarea, 4, 3), ["kl  ", "mnop", "qrst"]);
+        textarea.move_cursor(CursorMove::Jump(0, 4));
+        assert_eq!(render(&textarea, 4, 3), ["abcd", "efgh", "ij  "]);
+    }
+
+    #[test]
+    fn scroll_follow() {
+        let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+        textarea.set_scroll_follow(true);
+        assert_eq!(render(&textarea, 2, 3), ["7 ", "8 ", "9 "]);
+
+        // Appended lines are followed regardless of the cursor position
+        textarea.move_cursor(CursorMove::Bottom);
+        textarea.move_cursor(CursorMove::End);
+        textarea.insert_str("\n10\n11");
+        textarea.move_cursor(CursorMove::Jump(0, 0));
+        assert_eq!(render(&textarea, 2, 3), ["9 ", "10", "11"]);
+
+        textarea.scroll((-2, 0));
+        assert!(!textarea.is_following());
+        assert_eq!(render(&textarea, 2, 3), ["7 ", "8 ", "9 "]);
+        textarea.insert_str("x");
+        assert_eq!(render(&textarea, 2, 3), ["x7", "8 ", "9 "]);
+
+        textarea.scroll((2, 0));
+        assert!(textarea.is_following());
+        assert_eq!(render(&textarea, 2, 3), ["9 ", "10", "11"]);
+
+        textarea.set_scroll_follow(false);
+        assert!(!textarea.is_following());
+    }
+
+    #[test]
+    fn scroll_follow_with_wrap() {
+        let mut textarea = TextArea::from(["ab", "cd", "efghij"]);
+        textarea.set_wrap(true);
+        textarea.set_scroll_follow(true);
+        assert_eq!(render(&textarea, 2, 2), ["gh", "ij"]);
+
+        textarea.scroll((-1, 0));
+        assert!(!textarea.is_following());
+        textarea.scroll((1, 0));
+        assert!(textarea.is_following());
+    }
+
+    #[test]
+    fn scroll_without_cursor() {
+        let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+        textarea.set_cursor_follow(false);
+        assert_eq!(render(&textarea, 2, 3), ["0 ", "1 ", "2 "]);
+
+        textarea.scroll((4, 0));
+        assert_eq!(render(&textarea, 2, 3), ["4 ", "5 ", "6 "]);
+        assert_eq!(textarea.cursor(), (0, 0));
+
+        textarea.scroll(Scrolling::Bottom);
+        assert_eq!(render(&textarea, 2, 3), ["7 ", "8 ", "9 "]);
+
+        // Scrolling past the last line is clamped so that no empty row is left at the bottom
+        textarea.scroll((100, 0));
+        assert_eq!(textarea.viewport.scroll_top(), (7, 0));
+        assert_eq!(render(&textarea, 2, 3), ["7 ", "8 ", "9 "]);
+        textarea.viewport.scroll(100, 0);
+        assert_eq!(render(&textarea, 2, 3), ["7 ", "8 ", "9 "]);
+        assert_eq!(textarea.viewport.scroll_top(), (7, 0));
+
+        textarea.scroll(Scrolling::Top);
+        assert_eq!(render(&textarea, 2, 3), ["0 ", "1 ", "2 "]);
+        textarea.scroll((-100, 0));
+        assert_eq!(textarea.viewport.scroll_top(), (0, 0));
+
+        // Typing does not scroll the viewport
+        textarea.scroll(Scrolling::Bottom);
+        textarea.insert_str("x");
+        assert_eq!(render(&textarea, 2, 3), ["7 ", "8 ", "9 "]);
+        assert_eq!(textarea.lines()[0], "x0");
+
+        textarea.set_cursor_follow(true);
+        assert_eq!(render(&textarea, 2, 3), ["x0", "1 ", "2 "]);
+    }
+
+    #[test]
+    fn cursor_hidden() {
+        let mut textarea = TextArea::from(["abc"]);
+        let r = Rect::new(0, 0, 4, 1);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert!(b.get(0, 0).modifier.contains(Modifier::REVERSED));
+        assert!(b.get(1, 0).modifier.contains(Modifier::UNDERLINED));
+
+        textarea.set_cursor_hidden(true);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        for x in 0..4 {
+            assert!(b.get(x, 0).modifier.is_empty(), "x={x}");
+        }
+    }
+
+    #[test]
+    fn focus() {
+        let mut textarea = TextArea::from(["abc"]);
+        textarea.set_block(Block::default().borders(Borders::ALL).title("on"));
+        textarea.set_block_unfocused(Block::default().borders(Borders::ALL).title("off"));
+        let r = Rect::new(0, 0, 5, 3);
+
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b)[0], "┌on─┐");
+        assert!(b.get(1, 1).modifier.contains(Modifier::REVERSED));
+
+        // The cursor is hidden while unfocused by default
+        textarea.set_focus(false);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b), ["┌off┐", "│abc│", "└───┘"]);
+        for x in 1..4 {
+            assert!(b.get(x, 1).modifier.is_empty(), "x={x}");
+        }
+
+        textarea.set_cursor_style_unfocused(Some(Style::default().add_modifier(Modifier::BOLD)));
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(b.get(1, 1).modifier, Modifier::BOLD);
+        assert!(b.get(2, 1).modifier.contains(Modifier::UNDERLINED));
+
+        // The block is the same in both states when no block is set for the unfocused state
+        textarea.remove_block_unfocused();
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b)[0], "┌on─┐");
+    }
+
+    #[test]
+    fn overwrite_cursor_style() {
+        let mut textarea = TextArea::from(["abc"]);
+        let r = Rect::new(0, 0, 3, 1);
+        let cursor_modifier = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            b.get(0, 0).modifier
+        };
+
+        // The normal cursor style is used until the style for the overwrite mode is set
+        textarea.set_overwrite_mode(true);
+        assert!(cursor_modifier(&textarea).contains(Modifier::REVERSED));
+
+        textarea.set_overwrite_cursor_style(Style::default().add_modifier(Modifier::BOLD));
+        assert_eq!(cursor_modifier(&textarea), Modifier::BOLD);
+
+        textarea.set_overwrite_mode(false);
+        assert!(cursor_modifier(&textarea).contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn highlights() {
+        let mut textarea = TextArea::from(["abc", "def"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.move_cursor(CursorMove::Jump(1, 2));
+        let r = Rect::new(0, 0, 3, 2);
+        let fgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..2)
+                .map(|y| (0..3).map(|x| b.get(x, y).fg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        use Color::{Blue as B, Red as R, Reset as N};
+        assert_eq!(fgs(&textarea), [[N, N, N], [N, N, N]]);
+
+        // Rendered lines are updated when highlights are set
+        let red = Style::default().fg(Color::Red);
+        let blue = Style::default().fg(Color::Blue);
+        textarea.set_highlights(vec![
+            Highlight {
+                start: (0, 1),
+                end: (1, 2),
+                style: blue,
+                priority: 2,
+            },
+            Highlight {
+                start: (0, 0),
+                end: (0, 3),
+                style: red,
+                priority: 1,
+            },
+        ]);
+        assert_eq!(fgs(&textarea), [[R, B, B], [B, B, N]]);
+
+        textarea.set_highlights(vec![]);
+        assert_eq!(fgs(&textarea), [[N, N, N], [N, N, N]]);
+    }
+
+    #[test]
+    fn signs() {
+        let mut textarea = TextArea::from(["abc", "def"]);
+        textarea.set_cursor_line_style(Style::default());
+        assert_eq!(render(&textarea, 6, 2), ["abc   ", "def   "]);
+
+        let red = Style::default().fg(Color::Red);
+        textarea.set_sign(1, Some(Sign::new("E", red)));
+        assert_eq!(render(&textarea, 6, 2), ["  abc ", "E def "]);
+
+        let r = Rect::new(0, 0, 6, 2);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(b.get(0, 1).fg, Color::Red);
+
+        // The sign column is put before line numbers
+        textarea.set_line_number_style(Style::default());
+        assert_eq!(render(&textarea, 8, 2), ["  1 abc ", "E 2 def "]);
+
+        // The sign column is scrolled out with the text
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 6, 2), ["1 abc ", "2 def "]);
+
+        textarea.move_cursor(CursorMove::Head);
+        textarea.clear_signs();
+        assert_eq!(render(&textarea, 6, 2), ["1 abc ", "2 def "]);
+        textarea.set_sign_column(true);
+        assert_eq!(render(&textarea, 8, 2), ["  1 abc ", "  2 def "]);
+
+        // Continuation rows of soft-wrapped lines are indented by the sign column
+        let mut textarea = TextArea::from(["abcdef"]);
+        textarea.set_sign(0, Some(Sign::new("●", red)));
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 6, 2), ["● abcd", "  ef  "]);
+    }
+
+    #[test]
+    fn placeholder() {
+        use super::Line; // `Spans` on tui-rs
+        use crate::ratatui::text::Span;
+
+        let mut textarea = TextArea::default();
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        textarea.set_placeholder_lines(vec![
+            Line::from("Search"),
+            Line::from(vec![Span::raw("Press "), Span::styled("?", bold)]),
+        ]);
+        let r = Rect::new(0, 0, 9, 3);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b), ["Search   ", "Press ?  ", "         "]);
+        assert_eq!(b.get(0, 0).fg, Color::DarkGray);
+        assert!(b.get(0, 0).modifier.contains(Modifier::REVERSED)); // Cursor
+        assert!(!b.get(1, 0).modifier.contains(Modifier::REVERSED));
+        assert!(b.get(6, 1).modifier.contains(Modifier::BOLD));
+        assert_eq!(b.get(6, 1).fg, Color::DarkGray);
+
+        // The placeholder is put after the gutter
+        textarea.set_line_number_style(Style::default());
+        assert_eq!(render(&textarea, 9, 2), ["1 Search ", "  Press ?"]);
+
+        // Aligned and soft-wrapped as the text
+        textarea.remove_line_number();
+        textarea.set_placeholder_text("abc def");
+        textarea.set_alignment(Alignment::Right);
+        assert_eq!(render(&textarea, 9, 2), ["  abc def", "         "]);
+        textarea.set_wrap(true);
+        textarea.set_alignment(Alignment::Left);
+        assert_eq!(render(&textarea, 5, 2), ["abc  ", "def  "]);
+
+        let mut b = Buffer::empty(r);
+        textarea.set_alignment(Alignment::Center);
+        textarea.set_wrap(false);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b)[0], " abc def ");
+        assert!(b.get(1, 0).modifier.contains(Modifier::REVERSED));
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn multiline_search_highlight() {
+        let mut textarea = TextArea::from(["ab", "cd", "ef"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_pattern("b\\ncd\\ne").unwrap();
+        let r = Rect::new(0, 0, 3, 3);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+
+        let style = textarea.search_style();
+        #[rustfmt::skip]
+        let expected = [
+            (0, 0, false), (1, 0, true), (2, 0, false),
+            (0, 1, true),  (1, 1, true), (2, 1, false),
+            (0, 2, true),  (1, 2, false), (2, 2, false),
+        ];
+        for (x, y, highlighted) in expected {
+            assert_eq!(b.get(x, y).bg == style.bg.unwrap(), highlighted, "x={x}, y={y}");
+        }
+    }
+
+    #[test]
+    fn secondary_cursors() {
+        let mut textarea = TextArea::from(["abc", "de", "abc"]);
+        textarea.set_cursor_style(Style::default().bg(Color::Red));
+        textarea.set_secondary_cursor_style(Style::default().bg(Color::Green));
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_selection_style(Style::default().bg(Color::Blue));
+        textarea.set_selection((0, 0), (0, 1));
+        textarea.select_next_occurrence();
+        textarea.add_cursor(1, 2);
+        let r = Rect::new(0, 0, 4, 3);
+
+        let bgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..3)
+                .map(|y| (0..4).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as S, Green as G, Red as C, Reset as N};
+        // Secondary cursors at the end of line and after the selection
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [S, C, N, N],
+                [N, N, G, N],
+                [S, G, N, N],
+            ],
+        );
+
+        // Secondary cursors are hidden with the primary cursor
+        textarea.set_cursor_hidden(true);
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [S, N, N, N],
+                [N, N, N, N],
+                [S, N, N, N],
+            ],
+        );
+    }
+
+    #[test]
+    fn column_selection() {
+        let mut textarea = TextArea::from(["abcdef", "x", "あいう"]);
+        textarea.set_cursor_style(Style::default().bg(Color::Red));
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_selection_style(Style::default().bg(Color::Blue));
+        textarea.move_cursor(CursorMove::Jump(0, 2));
+        textarea.start_column_selection();
+        textarea.move_cursor(CursorMove::Jump(2, 2));
+        let r = Rect::new(0, 0, 7, 3);
+
+        let bgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..3)
+                .map(|y| (0..7).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as S, Red as C, Reset as N};
+        // Only the rectangle between the display columns is highlighted. The short row is not highlighted. The second
+        // cell of a wide character is not styled
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [N, N, S, S, N, N, N],
+                [N, N, N, N, N, N, N],
+                [N, N, S, N, C, N, N],
+            ],
+        );
+
+        textarea.cancel_selection();
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [N, N, N, N, N, N, N],
+                [N, N, N, N, N, N, N],
+                [N, N, N, N, C, N, N],
+            ],
+        );
+    }
+
+    #[test]
+    fn linewise_selection() {
+        let mut textarea = TextArea::from(["abcd", "ef", "ghij"]);
+        textarea.set_cursor_style(Style::default().bg(Color::Red));
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_selection_style(Style::default().bg(Color::Blue));
+        textarea.move_cursor(CursorMove::Jump(0, 2));
+        textarea.start_selection_linewise();
+        textarea.move_cursor(CursorMove::Jump(1, 1));
+        let r = Rect::new(0, 0, 6, 3);
+
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        let bgs = (0..3)
+            .map(|y| (0..6).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        use Color::{Blue as S, Red as C, Reset as N};
+        // The whole lines are highlighted including the cell of the newline at end of the bottom row
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs,
+            [
+                [S, S, S, S, S, N],
+                [S, C, S, N, N, N],
+                [N, N, N, N, N, N],
+            ],
+        );
+    }
+
+    #[test]
+    fn yank_flash() {
+        let mut textarea = TextArea::from(["abcd", "ef"]);
+        textarea.set_cursor_style(Style::default().bg(Color::Red));
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_selection_style(Style::default().bg(Color::Blue));
+        textarea.set_yank_flash(Some((Style::default().bg(Color::Yellow), 2)));
+        let r = Rect::new(0, 0, 5, 2);
+
+        let bgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..2)
+                .map(|y| (0..5).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as S, Red as C, Reset as N, Yellow as Y};
+        textarea.set_selection((0, 1), (1, 1));
+        textarea.copy();
+        let flashed = [[N, Y, Y, Y, S], [Y, C, N, N, N]];
+        let selected = [[N, S, S, S, S], [S, C, N, N, N]];
+        // The flash is layered over the selection and lasts for the number of renderings
+        assert_eq!(bgs(&textarea), flashed);
+        assert_eq!(bgs(&textarea), flashed);
+        assert_eq!(bgs(&textarea), selected);
+
+        // Moving the cursor does not cancel the flash
+        textarea.cancel_selection();
+        textarea.copy();
+        textarea.move_cursor(CursorMove::Forward);
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [N, N, N, N, N],
+                [Y, Y, C, N, N],
+            ],
+        );
+
+        // Editing the text cancels the flash
+        textarea.copy();
+        textarea.insert_char('x');
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [N, N, N, N, N],
+                [N, N, N, C, N],
+            ],
+        );
+
+        // Cutting does not flash
+        textarea.set_selection((0, 0), (0, 2));
+        textarea.cut();
+        #[rustfmt::skip]
+        assert_eq!(
+            bgs(&textarea),
+            [
+                [C, N, N, N, N],
+                [N, N, N, N, N],
+            ],
+        );
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn highlight_layers() {
+        let mut textarea = TextArea::from(["abcdef", "ghijkl"]);
+        textarea.set_cursor_style(Style::default().bg(Color::Red));
+        textarea.set_cursor_line_style(Style::default().bg(Color::Gray));
+        textarea.set_selection_style(Style::default().bg(Color::Blue));
+        textarea.set_search_style(Style::default().bg(Color::Green));
+        textarea.set_search_pattern("ab|hi").unwrap();
+        textarea.move_cursor(CursorMove::Forward);
+        textarea.start_selection();
+        textarea.move_cursor(CursorMove::Jump(1, 4));
+        let r = Rect::new(0, 0, 8, 2);
+
+        let bgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..2)
+                .map(|y| (0..8).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as S, Gray as L, Green as M, Red as C, Reset as N};
+        // Search matches are over the selection even where the selection starts inside the match, and the cursor is
+        // over everything
+        assert_eq!(bgs(&textarea), [[M, M, S, S, S, S, S, N], [S, M, M, S, C, L, N, N]],);
+
+        textarea.set_cursor_line_style_in_selection(None);
+        assert_eq!(bgs(&textarea), [[M, M, S, S, S, S, S, N], [S, M, M, S, C, N, N, N]],);
+
+        // The cursor line style is back after the selection is cancelled
+        textarea.cancel_selection();
+        assert_eq!(bgs(&textarea), [[M, M, N, N, N, N, N, N], [L, M, M, L, C, L, N, N]],);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn current_search_match_highlight() {
+        let mut textarea = TextArea::from(["ab ab", "x", "ab"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_style(Style::default().bg(Color::Blue));
+        textarea.set_current_search_match_style(Style::default().bg(Color::Yellow));
+        textarea.set_search_pattern("ab").unwrap();
+
+        let bgs = |textarea: &TextArea, height: u16| {
+            let r = Rect::new(0, 0, 5, height);
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..height)
+                .map(|y| (0..2).map(|x| b.get(x * 3 + 1, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as M, Reset as N, Yellow as C};
+        // No current match before searching
+        assert_eq!(bgs(&textarea, 3), [[M, M], [N, N], [M, N]]);
+
+        textarea.search_forward(false);
+        assert_eq!(bgs(&textarea, 3), [[M, C], [N, N], [M, N]]);
+        textarea.search_forward(false);
+        assert_eq!(bgs(&textarea, 3), [[M, M], [N, N], [C, N]]);
+
+        // The current match is scrolled out
+        textarea.search_forward(false);
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(bgs(&textarea, 1), [[M, N]]);
+        textarea.move_cursor(CursorMove::Top);
+        assert_eq!(bgs(&textarea, 3), [[C, M], [N, N], [M, N]]);
+
+        // The match shifted by an edit is no longer current
+        textarea.insert_char('x');
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(bgs(&textarea, 3), [[M, M], [N, N], [M, N]]);
+        assert_eq!(textarea.current_search_match(), None);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn fuzzy_search_highlight() {
+        let mut textarea = TextArea::from(["a_b_c", "abc"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_style(Style::default().bg(Color::Blue));
+        textarea.set_current_search_match_style(Style::default().bg(Color::Yellow));
+        textarea.set_search_mode(SearchMode::Fuzzy);
+        textarea.set_search_pattern("ac").unwrap();
+        let r = Rect::new(0, 0, 5, 2);
+
+        let bgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..2)
+                .map(|y| (0..5).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as M, Reset as N, Yellow as C};
+        // Only the matched characters are highlighted. The cursor is over the highlights
+        assert_eq!(bgs(&textarea), [[N, N, N, N, M], [M, N, M, N, N]]);
+
+        textarea.search_forward(false);
+        assert_eq!(textarea.cursor(), (1, 0));
+        assert_eq!(bgs(&textarea), [[M, N, N, N, M], [N, N, C, N, N]]);
+
+        // Switching the mode updates the highlights
+        textarea.set_search_mode(SearchMode::Literal);
+        assert_eq!(bgs(&textarea), [[N; 5], [N; 5]]);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn search_scope_highlight() {
+        let mut textarea = TextArea::from(["ab ab", "ab ab"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_style(Style::default().bg(Color::Blue));
+        textarea.set_selection((0, 3), (1, 2));
+        textarea.set_search_scope(SearchScope::Selection);
+        textarea.cancel_selection();
+        textarea.move_cursor(CursorMove::Top);
+        textarea.set_search_pattern("ab").unwrap();
+        let r = Rect::new(0, 0, 5, 2);
+
+        let bgs = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..2)
+                .map(|y| (0..5).map(|x| b.get(x, y).bg).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        use Color::{Blue as M, Reset as N};
+        // Matches out of the scope are not highlighted
+        assert_eq!(bgs(&textarea), [[N, N, N, M, M], [M, M, N, N, N]]);
+
+        // Changing the scope updates the highlights
+        textarea.set_search_scope(SearchScope::Document);
+        assert_eq!(bgs(&textarea), [[M, M, N, M, M], [M, M, N, M, M]]);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn search_preview_scroll() {
+        let mut textarea = TextArea::from(["a".repeat(20), "b".repeat(20), "c".repeat(10) + "x"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_current_search_match_style(Style::default().bg(Color::Yellow));
+        let r = Rect::new(0, 0, 5, 2);
+        let render = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            b
+        };
+
+        textarea.move_cursor(CursorMove::Jump(0, 3));
+        render(&textarea);
+        textarea.set_search_pattern("x").unwrap();
+        assert_eq!(textarea.search_preview(true), Some((2, 10)));
+
+        // The viewport follows the previewed match instead of the cursor
+        let b = render(&textarea);
+        assert_eq!(textarea.viewport_rect(), (1, 6, 5, 2));
+        assert_eq!(b.get(4, 1).bg, Color::Yellow);
+        assert_eq!(textarea.cursor(), (0, 3));
+
+        // The viewport before the preview is restored
+        textarea.search_cancel();
+        let b = render(&textarea);
+        assert_eq!(textarea.viewport_rect(), (0, 0, 5, 2));
+        assert_eq!(cell_symbol(&b, 3, 0), "a");
+
+        // The viewport stays at the match after committing
+        textarea.search_preview(true);
+        render(&textarea);
+        assert!(textarea.search_commit());
+        render(&textarea);
+        assert_eq!(textarea.viewport_rect(), (1, 6, 5, 2));
+        assert_eq!(textarea.cursor(), (2, 10));
+
+        // Moving the cursor finishes the preview and the viewport follows the cursor again
+        textarea.move_cursor(CursorMove::Jump(0, 3));
+        render(&textarea);
+        textarea.search_preview(true);
+        render(&textarea);
+        assert_eq!(textarea.viewport_rect(), (1, 6, 5, 2));
+        textarea.move_cursor(CursorMove::Forward);
+        assert_eq!(textarea.previewed_search_match(), None);
+        render(&textarea);
+        assert_eq!(textarea.viewport_rect(), (0, 4, 5, 2));
+        assert!(!textarea.search_commit());
+        assert_eq!(textarea.cursor(), (0, 4));
+
+        // Editing the text also finishes the preview
+        assert_eq!(textarea.search_preview(true), Some((2, 10)));
+        render(&textarea);
+        assert_eq!(textarea.viewport_rect(), (1, 6, 5, 2));
+        textarea.insert_char('y');
+        textarea.move_cursor(CursorMove::Back);
+        assert_eq!(textarea.previewed_search_match(), None);
+        render(&textarea);
+        assert_eq!(textarea.viewport_rect().0, 0);
+
+        // Canceling the finished preview does not scroll the viewport back
+        textarea.search_preview(true);
+        render(&textarea);
+        textarea.move_cursor(CursorMove::Bottom);
+        render(&textarea);
+        let rect = textarea.viewport_rect();
+        textarea.search_cancel();
+        render(&textarea);
+        assert_eq!(textarea.viewport_rect(), rect);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn current_multiline_search_match_scrolled_out() {
+        let mut textarea = TextArea::from(["a", "b", "a", "b"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_style(Style::default().bg(Color::Blue));
+        textarea.set_current_search_match_style(Style::default().bg(Color::Yellow));
+        textarea.set_search_pattern("a\\nb").unwrap();
+        textarea.search_forward(true);
+        assert_eq!(textarea.current_search_match(), Some(0));
+
+        // Only the second line of the current match is in the viewport
+        let mut viewport = Viewport::default();
+        viewport.set_focused(false);
+        viewport.scroll(1, 0);
+        let r = Rect::new(0, 0, 1, 3);
+        let mut b = Buffer::empty(r);
+        StatefulWidget::render(&textarea, r, &mut b, &mut viewport);
+        let bgs: Vec<_> = (0..3).map(|y| b.get(0, y).bg).collect();
+        assert_eq!(bgs, [Color::Yellow, Color::Blue, Color::Blue]);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn multiline_search_highlight_in_viewport() {
+        let mut textarea = TextArea::from(["ab", "cd", "ab", "cd", "ab"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_pattern("b\\nc").unwrap();
+        textarea.scroll((2, 0));
+        let r = Rect::new(0, 0, 2, 2);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+
+        // Only the match in the visible rows 2..4 is highlighted
+        let bg = textarea.search_style().bg.unwrap();
+        let highlighted: Vec<_> = (0..2)
+            .flat_map(|y| (0..2).map(move |x| (x, y)))
+            .filter(|&(x, y)| b.get(x, y).bg == bg)
+            .collect();
+        assert_eq!(highlighted, [(1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn matching_bracket_highlight() {
+        let mut textarea = TextArea::from(["(a", "[b])", "c"]);
+        let style = Style::default().bg(Color::Yellow);
+        textarea.set_matching_bracket_style(style);
+        textarea.set_cursor_line_style(Style::default());
+        let r = Rect::new(0, 0, 5, 3);
+
+        let highlighted = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            let mut cells = vec![];
+            for y in 0..3 {
+                for x in 0..5 {
+                    if b.get(x, y).bg == Color::Yellow {
+                        cells.push((x, y));
+                    }
+                }
+            }
+            cells
+        };
+
+        assert_eq!(highlighted(&textarea), [(3, 1)]);
+        textarea.move_cursor(CursorMove::Jump(1, 2));
+        assert_eq!(highlighted(&textarea), [(0, 1)]);
+        // Bracket immediately before the cursor
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(highlighted(&textarea), [(0, 0)]);
+        // No bracket at the cursor
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(highlighted(&textarea), []);
+
+        // Layered over the selection
+        textarea.move_cursor(CursorMove::Top);
+        textarea.set_selection((2, 0), (0, 0));
+        assert_eq!(textarea.cursor(), (0, 0));
+        assert_eq!(highlighted(&textarea), [(3, 1)]);
+
+        textarea.remove_matching_bracket_style();
+        textarea.cancel_selection();
+        assert_eq!(highlighted(&textarea), []);
+    }
+
+    #[test]
+    fn show_whitespace() {
+        let mut textarea = TextArea::from(["a b\tc ", "\td"]);
+        textarea.set_show_whitespace(WhitespaceStyle::default());
+        assert_eq!(render(&textarea, 10, 2), ["a·b→c·    ", "→   d     "]);
+
+        // Trailing whitespaces are detected on each rendering
+        textarea.move_cursor(CursorMove::End);
+        textarea.insert_char(' ');
+        let r = Rect::new(0, 0, 10, 2);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        let trailing = WhitespaceStyle::default().trailing_style.fg.unwrap();
+        assert_eq!(b.get(1, 0).fg, WhitespaceStyle::default().style.fg.unwrap());
+        assert_eq!(b.get(5, 0).fg, trailing);
+        assert_eq!(b.get(6, 0).fg, trailing);
+        // Cursor column is not affected by the glyphs
+        assert_eq!(textarea.cursor(), (0, 7));
+
+        textarea.hide_whitespace();
+        assert_eq!(render(&textarea, 10, 2), ["a b c     ", "    d     "]);
+    }
+
+    #[test]
+    fn indent_guides() {
+        let mut textarea = TextArea::from(["a", "  b", "    c", "", "  d", "", "e"]);
+        textarea.set_tab_length(2);
+        textarea.set_indent_guides(Some(Style::default().fg(Color::DarkGray)));
+        textarea.move_cursor(CursorMove::Bottom);
+        let want = ["a     ", "│ b   ", "│ │ c ", "│     ", "│ d   ", "      ", "e     "];
+        assert_eq!(render(&textarea, 6, 7), want);
+
+        textarea.set_indent_guide_char('|');
+        textarea.set_tab_length(4);
+        let want = ["a     ", "| b   ", "|   c ", "|     ", "| d   ", "      ", "e     "];
+        assert_eq!(render(&textarea, 6, 7), want);
+
+        // Guides are not drawn while the text is masked
+        textarea.set_mask_char('*');
+        let want = ["*     ", "***   ", "***** ", "      ", "***   ", "      ", "*     "];
+        assert_eq!(render(&textarea, 6, 7), want);
+        textarea.clear_mask_char();
+
+        textarea.set_indent_guides(None);
+        let want = ["a     ", "  b   ", "    c ", "      ", "  d   ", "      ", "e     "];
+        assert_eq!(render(&textarea, 6, 7), want);
+    }
+
+    #[test]
+    fn rulers() {
+        let mut textarea = TextArea::from(["abcdefgh", "ab", ""]);
+        textarea.set_line_number_style(Style::default());
+        textarea.set_ruler_columns(vec![(4, Style::default().bg(Color::Red))]);
+        textarea.move_cursor(CursorMove::Jump(0, 3));
+        let ruler_cols = |textarea: &TextArea<'_>| {
+            let r = Rect::new(0, 0, 8, 3);
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (0..3)
+                .map(|y| (0..8).filter(|&x| b.get(x, y).bg == Color::Red).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        // The ruler is put after the gutter and short lines are padded
+        assert_eq!(ruler_cols(&textarea), [[6], [6], [6]]);
+        assert_eq!(render(&textarea, 8, 3), ["1 abcdef", "2 ab    ", "3       "]);
+
+        // The ruler stays at the column of the text on scrolling horizontally
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 8, 3), ["bcdefgh ", "b       ", "        "]);
+        assert_eq!(ruler_cols(&textarea), [[3], [3], [3]]);
+
+        textarea.set_ruler_columns(vec![]);
+        assert_eq!(ruler_cols(&textarea), [[0u16; 0]; 3]);
+    }
+
+    #[test]
+    fn empty_line_marker() {
+        let mut textarea = TextArea::from(["a", ""]);
+        textarea.set_line_number_style(Style::default());
+        textarea.set_cursor_line_style(Style::default().add_modifier(Modifier::UNDERLINED));
+        let style = Style::default().fg(Color::DarkGray);
+        textarea.set_empty_line_marker(Some(("~".to_string(), style)));
+
+        // Markers are put at the leftmost column of rows below the last line
+        let r = Rect::new(0, 0, 5, 4);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b), ["1 a  ", "2    ", "~    ", "~    "]);
+        assert_eq!(b.get(0, 2).fg, Color::DarkGray);
+        assert!(!b.get(0, 2).modifier.contains(Modifier::UNDERLINED));
+
+        // Soft-wrapped lines take the rows of markers
+        textarea.set_wrap(true);
+        textarea.insert_str("bcdef");
+        assert_eq!(render(&textarea, 5, 4), ["1 bcd", "  efa", "2    ", "~    "]);
+        textarea.set_wrap(false);
+
+        // Markers are not shown with the placeholder
+        let mut textarea = TextArea::default();
+        textarea.set_empty_line_marker(Some(("~".to_string(), style)));
+        assert_eq!(render(&textarea, 3, 2), ["   ", "~  "]);
+        textarea.set_placeholder_text("abc");
+        assert_eq!(render(&textarea, 3, 2), ["abc", "   "]);
+
+        textarea.set_empty_line_marker(None);
+        textarea.set_placeholder_text("");
+        assert_eq!(render(&textarea, 3, 2), ["   ", "   "]);
+    }
+
+    #[test]
+    fn folds() {
+        let mut textarea: TextArea = (0..10).map(|i| format!("{i}")).collect();
+        textarea.set_line_number_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        assert!(textarea.fold(1, 3));
+        assert!(textarea.fold(5, 8));
+
+        // Folded lines are rendered in one row with the suffix
+        let r = Rect::new(0, 0, 16, 5);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(
+            buffer_lines(&b),
+            [
+                " 1 0            ",
+                " 2 1 … (3 lines)",
+                " 5 4            ",
+                " 6 5 … (4 lines)",
+                "10 9            ",
+            ],
+        );
+        assert_eq!(b.get(5, 1).fg, Color::DarkGray);
+        assert_eq!(textarea.visible_rows(), 0..10);
+        assert_eq!(textarea.screen_to_cursor(3, 2), Some((4, 0)));
+
+        // The viewport scrolls by display rows
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(render(&textarea, 16, 2), [" 6 5 … (4 lines)", "10 9            "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((3, 1)));
+        textarea.scroll((-1, 0));
+        assert_eq!(textarea.cursor(), (5, 0));
+        assert_eq!(render(&textarea, 16, 2), [" 5 4            ", " 6 5 … (4 lines)"]);
+
+        // Folds are not applied to soft-wrapped lines
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 16, 2), [" 5 4            ", " 6 5            "]);
+    }
+
+    #[test]
+    fn ghost_text() {
+        let mut textarea = TextArea::from(["fn f", "x"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.move_cursor(CursorMove::Jump(0, 2));
+
+        // The ghost text is inserted at the cursor and the cursor is rendered on its first cell
+        textarea.set_ghost_text(Some("ab".to_string()));
+        let r = Rect::new(0, 0, 8, 3);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b), ["fnab f  ", "x       ", "        "]);
+        assert_eq!(b.get(2, 0).fg, Color::DarkGray);
+        assert!(b.get(2, 0).modifier.contains(Modifier::REVERSED));
+        assert_eq!(b.get(3, 0).fg, Color::DarkGray);
+        assert!(!b.get(3, 0).modifier.contains(Modifier::REVERSED));
+        assert!(!b.get(4, 0).modifier.contains(Modifier::REVERSED));
+        assert_eq!(textarea.cursor_screen_position(), Some((2, 0)));
+        assert_eq!(textarea.lines(), ["fn f", "x"]);
+
+        // Extra lines of the ghost text push the following lines down
+        textarea.set_ghost_text(Some("a\n\tb\nc".to_string()));
+        textarea.set_line_number_style(Style::default());
+        assert_eq!(
+            render(&textarea, 10, 5),
+            ["1 fna     ", "      b   ", "  c f     ", "2 x       ", "          "],
+        );
+        assert_eq!(textarea.screen_to_cursor(3, 3), Some((1, 1)));
+        assert_eq!(textarea.screen_to_cursor(4, 1), Some((0, 2)));
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.ghost_text(), None);
+
+        // The ghost text is not rendered while lines are soft-wrapped
+        textarea.set_ghost_text(Some("yz".to_string()));
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 10, 2), ["1 fn f    ", "2 x       "]);
+    }
+
+    #[test]
+    fn unicode_input() {
+        let mut textarea = TextArea::from(["ab"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.move_cursor(CursorMove::Jump(0, 1));
+        textarea.set_ghost_text(Some("xyz".to_string()));
+
+        // The digits being composed are shown instead of the ghost text
+        textarea.start_unicode_input();
+        for c in "3b".chars() {
+            textarea.input(Input {
+                key: Key::Char(c),
+                ..Default::default()
+            });
+        }
+        assert_eq!(render(&textarea, 6, 1), ["au3bb "]);
+        textarea.cancel_unicode_input();
+        assert_eq!(render(&textarea, 6, 1), ["axyzb "]);
+    }
+
+    #[test]
+    fn truncation_indicator() {
+        let mut textarea = TextArea::from(["abcdefgh", "ab", "abcdefghij", "\tあいう"]);
+        textarea.set_truncation_indicator(Some(("…".to_string(), Style::default())));
+        assert_eq!(render(&textarea, 6, 4), ["abcde…", "ab    ", "abcde…", "     …"]);
+
+        // Indicators at both edges after scrolling horizontally. The cursor is not covered
+        textarea.move_cursor(CursorMove::Jump(2, 9));
+        assert_eq!(render(&textarea, 6, 4), ["…fgh  ", "…     ", "…fghij", "… いう"]);
+        textarea.move_cursor(CursorMove::Jump(2, 4));
+        assert_eq!(render(&textarea, 6, 4), ["…fgh  ", "…     ", "efghij", "… いう"]);
+
+        // The gutter is not scrolled out
+        textarea.set_line_number_style(Style::default());
+        textarea.move_cursor(CursorMove::Jump(0, 0));
+        assert_eq!(render(&textarea, 6, 4), ["1 abc…", "2 ab  ", "3 abc…", "4    …"]);
+
+        textarea.set_truncation_indicator(None);
+        assert_eq!(render(&textarea, 6, 4), ["1 abcd", "2 ab  ", "3 abcd", "4     "]);
+    }
+
+    #[test]
+    fn control_chars() {
+        let mut textarea = TextArea::from(["a\x0cb\x1b", "\u{200f}c"]);
+        textarea.move_cursor(CursorMove::Jump(0, 2));
+        assert_eq!(render(&textarea, 8, 2), ["a^Lb^[  ", "<200f>c "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((3, 0)));
+        assert_eq!(textarea.screen_to_cursor(2, 0), Some((0, 1)));
+
+        let r = Rect::new(0, 0, 8, 2);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(b.get(1, 0).fg, Color::Blue);
+        assert_eq!(b.get(2, 0).fg, Color::Blue);
+        assert_eq!(b.get(3, 0).fg, Color::Reset);
+
+        // Soft-wrap does not split the notation
+        textarea.set_wrap(true);
+        assert_eq!(render(&textarea, 2, 4), ["a ", "^L", "b ", "^["]);
+        textarea.set_wrap(false);
+
+        // The text is not changed
+        textarea.move_cursor(CursorMove::Head);
+        textarea.start_selection();
+        textarea.move_cursor(CursorMove::End);
+        textarea.copy();
+        assert_eq!(textarea.yank_text(), "a\x0cb\x1b");
+        assert_eq!(textarea.lines()[0], "a\x0cb\x1b");
+    }
+
+    #[test]
+    fn links() {
+        let mut textarea = TextArea::from(["a http://b.c d"]);
+        textarea.set_cursor_line_style(Style::default());
+        let r = Rect::new(0, 0, 16, 1);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        let underlined = |b: &Buffer, x| b.get(x, 0).modifier.contains(Modifier::UNDERLINED);
+        let cells: Vec<_> = (0..16).filter(|&x| underlined(&b, x)).collect();
+        assert_eq!(cells, (2..12).collect::<Vec<_>>());
+
+        textarea.set_link_detector(None);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert!((0..16).all(|x| !underlined(&b, x)));
+        assert_eq!(buffer_lines(&b), ["a http://b.c d  "]);
+    }
+
+    #[test]
+    #[cfg(feature = "hyperlinks")]
+    fn hyperlinks() {
+        let mut textarea = TextArea::from(["a http://b.c d"]);
+        textarea.move_cursor(CursorMove::Jump(0, 5));
+        let r = Rect::new(0, 0, 16, 1);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+
+        // The cursor cell splits the link
+        assert_eq!(b.get(2, 0).symbol(), "\x1b]8;;http://b.c\x1b\\htt\x1b]8;;\x1b\\");
+        assert!(b.get(3, 0).skip && b.get(4, 0).skip);
+        assert_eq!(b.get(5, 0).symbol(), "p");
+        assert!(!b.get(5, 0).skip);
+        assert_eq!(b.get(6, 0).symbol(), "\x1b]8;;http://b.c\x1b\\://b.c\x1b]8;;\x1b\\");
+        assert!((7..12).all(|x| b.get(x, 0).skip));
+        assert!(!b.get(12, 0).skip);
+
+        // C0 and C1 control characters in targets cannot inject escape sequences
+        let mut textarea = TextArea::from(["link"]);
+        textarea.set_link_detector(Some(|line| {
+            vec![(0..line.len(), "a\x1b]8;;b\x07c\u{9b}d\x1b\\".to_string())]
+        }));
+        textarea.move_cursor(CursorMove::End);
+        let r = Rect::new(0, 0, 6, 1);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(b.get(0, 0).symbol(), "\x1b]8;;a]8;;bcd\\\x1b\\link\x1b]8;;\x1b\\");
+    }
+
+    #[test]
+    fn mask_reveal_last() {
+        let mut textarea = TextArea::default();
+        textarea.set_mask_char('*');
+        textarea.set_mask_reveal_last(true);
+        textarea.insert_char('a');
+        textarea.insert_char('b');
+        assert_eq!(render(&textarea, 4, 1), ["*b  "]);
+        assert_eq!(render(&textarea, 4, 1), ["**  "]);
+
+        textarea.set_mask_reveal_renders(2);
+        textarea.insert_char('c');
+        assert_eq!(render(&textarea, 4, 1), ["**c "]);
+        assert_eq!(render(&textarea, 4, 1), ["**c "]);
+        assert_eq!(render(&textarea, 4, 1), ["*** "]);
+
+        // Moving the cursor masks the character
+        textarea.insert_char('d');
+        textarea.move_cursor(CursorMove::Back);
+        assert_eq!(render(&textarea, 5, 1), ["**** "]);
+
+        // Characters wider than the mask character are not revealed
+        let mut textarea = TextArea::default();
+        textarea.set_mask_char('*');
+        textarea.set_mask_reveal_last(true);
+        textarea.insert_char('あ');
+        assert_eq!(render(&textarea, 3, 1), ["*  "]);
+
+        // Text inserted at once is not revealed
+        textarea.insert_str("xy");
+        assert_eq!(render(&textarea, 4, 1), ["*** "]);
+    }
+
+    #[test]
+    fn mask_enabled() {
+        let mut textarea = TextArea::from(["aあb"]);
+        textarea.set_mask_char('*');
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 5, 1), ["***  "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((3, 0)));
+
+        textarea.set_mask_enabled(false);
+        assert_eq!(render(&textarea, 5, 1), ["aあb "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((4, 0)));
+        assert_eq!(textarea.mask_char(), Some('*'));
+
+        textarea.set_mask_enabled(true);
+        assert_eq!(render(&textarea, 5, 1), ["***  "]);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn mask_hides_search_matches() {
+        let mut textarea = TextArea::from(["secret"]);
+        textarea.set_cursor_style(Style::default());
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_search_pattern("cr").unwrap();
+        let style = textarea.search_style();
+        let r = Rect::new(0, 0, 6, 1);
+
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(b.get(2, 0).bg, style.bg.unwrap());
+
+        textarea.set_mask_char('*');
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b), ["******"]);
+        for x in 0..6 {
+            assert_ne!(b.get(x, 0).bg, style.bg.unwrap(), "x={x}");
+        }
+    }
+
+    #[test]
+    fn single_line_up_down_on_wrapped_line() {
+        let mut textarea = TextArea::from(["abcdefgh"]);
+        textarea.set_wrap(true);
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 5, 2), ["abcde", "fgh  "]);
+        textarea.move_cursor(CursorMove::Up);
+        assert_eq!(textarea.cursor(), (0, 3));
+
+        textarea.set_single_line(true);
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(textarea.cursor(), (0, 3));
+        textarea.move_cursor(CursorMove::End);
+        textarea.move_cursor(CursorMove::Up);
+        assert_eq!(textarea.cursor(), (0, 8));
+    }
+
+    #[test]
+    fn invalid_style() {
+        let mut textarea = TextArea::from(["ab"]);
+        textarea.set_block(Block::default().borders(Borders::ALL));
+        textarea.set_validator(|lines| {
+            if lines[0].len() > 2 {
+                Err("too long".into())
+            } else {
+                Ok(())
+            }
+        });
+        let r = Rect::new(0, 0, 5, 3);
+
+        let render = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            (b.get(0, 0).fg, b.get(2, 1).fg)
+        };
+
+        assert_eq!(render(&textarea), (Color::Reset, Color::Reset));
+        textarea.input(Input {
+            key: Key::Char('c'),
+            ..Default::default()
+        });
+        assert_eq!(textarea.validation_error(), Some("too long"));
+        assert_eq!(render(&textarea), (Color::Red, Color::Red));
+
+        // Clearing the validator restores the original style
+        textarea.clear_validator();
+        assert_eq!(render(&textarea), (Color::Reset, Color::Reset));
+    }
+
+    #[test]
+    fn duplicate_lines_scrolls_to_cursor() {
+        let mut textarea = TextArea::from(["a", "b", "c"]);
+        textarea.set_line_number_style(Style::default());
+        textarea.move_cursor(CursorMove::Bottom);
+        textarea.set_selection((1, 0), (2, 1));
+        assert_eq!(render(&textarea, 4, 3), ["1 a ", "2 b ", "3 c "]);
+        textarea.duplicate_lines();
+        assert_eq!(render(&textarea, 4, 3), ["3 c ", "4 b ", "5 c "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((3, 2)));
+    }
+
+    #[test]
+    fn cursor_screen_position() {
+        let mut textarea = TextArea::from(["a\tb🐶c", "xyz", "1", "2", "3"]);
+        textarea.move_cursor(CursorMove::Jump(0, 4));
+        render(&textarea, 10, 3);
+        assert_eq!(textarea.cursor_screen_position(), Some((7, 0)));
+
+        textarea.set_line_number_style(Style::default());
+        render(&textarea, 10, 3);
+        assert_eq!(textarea.cursor_screen_position(), Some((9, 0)));
+
+        // Scrolled vertically
+        textarea.move_cursor(CursorMove::Bottom);
+        render(&textarea, 10, 3);
+        assert_eq!(textarea.cursor_screen_position(), Some((3, 2)));
+
+        // Cursor is out of the view
+        textarea.move_cursor(CursorMove::Top);
+        assert_eq!(textarea.cursor_screen_position(), None);
+
+        // Scrolled horizontally
+        let mut textarea = TextArea::from(["abcdefgh"]);
+        textarea.set_line_number_style(Style::default());
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 6, 1), ["defgh "]);
+        assert_eq!(textarea.viewport_rect(), (0, 5, 6, 1));
+        assert_eq!(textarea.cursor_screen_position(), Some((5, 0)));
+
+        // Soft wrap
+        let mut textarea = TextArea::from(["abcdefgh", "ab"]);
+        textarea.set_wrap(true);
+        textarea.set_wrap_indicator(Some((">".to_string(), Style::default())));
+        textarea.move_cursor(CursorMove::Jump(0, 5));
+        assert_eq!(render(&textarea, 4, 4), ["abcd", ">efg", ">h  ", "ab  "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((2, 1)));
+        textarea.move_cursor(CursorMove::Jump(1, 2));
+        render(&textarea, 4, 4);
+        assert_eq!(textarea.cursor_screen_position(), Some((2, 3)));
+        assert_eq!(textarea.screen_to_cursor(2, 3), Some((1, 2)));
+    }
+
+    #[test]
+    fn long_line_horizontal_scroll() {
+        let line = "abcdefghij".repeat(1000);
+        let mut textarea = TextArea::from([line.as_str(), "a\tbあい"]);
+        assert_eq!(render(&textarea, 6, 2), ["abcdef", "a   b "]);
+
+        textarea.move_cursor(CursorMove::Jump(0, 5003));
+        assert_eq!(render(&textarea, 6, 2), ["ijabcd", "      "]);
+
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 6, 2), ["fghij ", "      "]);
+
+        // The width of the line is necessary for other alignments
+        textarea.move_cursor(CursorMove::Top);
+        textarea.move_cursor(CursorMove::Head);
+        textarea.set_alignment(Alignment::Right);
+        textarea.set_selection((1, 0), (1, 1));
+        assert_eq!(render(&textarea, 6, 2)[1], " a   b");
+
+        // Wide characters and tabs partially scrolled out are padded
+        let mut textarea = TextArea::from(["abcdefghij", "aあいう", "\tbc"]);
+        textarea.set_line_number_style(Style::default());
+        textarea.move_cursor(CursorMove::Jump(0, 9));
+        assert_eq!(render(&textarea, 6, 3), ["efghij", " う   ", "bc    "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((5, 0)));
+        textarea.move_cursor(CursorMove::Head);
+        assert_eq!(render(&textarea, 6, 3), ["1 abcd", "2 aあ ", "3     "]);
+
+        // Columns beyond the range of u16
+        let mut textarea = TextArea::from(["x".repeat(100000)]);
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render(&textarea, 4, 1), ["xxx "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((3, 0)));
+    }
+
+    #[test]
+    #[cfg(feature = "rope")]
+    fn long_line_parts_in_rope() {
+        let line = "abc\tあい https://example.com/x ".repeat(2000);
+        let r = Rect::new(0, 0, 12, 1);
+        let buffer = |textarea: &TextArea| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            b
+        };
+        let mut vec = TextArea::from([line.as_str()]);
+        let mut rope = TextArea::from([line.as_str()]);
+        rope.set_rope(true);
+        let highlight = Highlight {
+            start: (0, 30005),
+            end: (0, 30008),
+            style: Style::default().fg(Color::Red),
+            priority: 0,
+        };
+        for textarea in [&mut vec, &mut rope] {
+            textarea.set_highlights(vec![highlight]);
+            textarea.move_cursor(CursorMove::Jump(0, 30000));
+        }
+        assert_eq!(buffer(&vec), buffer(&rope));
+
+        // Positions recorded before the edit are moved by it
+        for textarea in [&mut vec, &mut rope] {
+            textarea.move_cursor(CursorMove::Jump(0, 10));
+            textarea.insert_str("\tあ");
+            textarea.move_cursor(CursorMove::Jump(0, 40000));
+            textarea.delete_char();
+        }
+        let mut fresh = TextArea::from([rope.lines()[0].as_str()]);
+        fresh.move_cursor(CursorMove::Jump(0, 39999));
+        fresh.set_highlights(rope.highlights().to_vec());
+        assert_eq!(buffer(&vec), buffer(&rope));
+        assert_eq!(buffer(&fresh), buffer(&rope));
+        assert_eq!(rope.cursor_cells(), fresh.cursor_cells());
+    }
+
+    #[test]
+    fn render_cache_invalidation() {
+        let mut textarea = TextArea::from(["abc", "def"]);
+        textarea.set_cursor_line_style(Style::default());
+        let r = Rect::new(0, 0, 4, 2);
+        let cell = |textarea: &TextArea, x: u16, y: u16| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            let c = b.get(x, y);
+            (cell_symbol(&b, x, y).to_string(), c.fg, c.bg)
+        };
+        let plain = |s: &str| (s.to_string(), Color::Reset, Color::Reset);
+
+        assert_eq!(cell(&textarea, 1, 0), plain("b"));
+        // Styles
+        textarea.set_cursor_line_style(Style::default().bg(Color::Red));
+        assert_eq!(cell(&textarea, 1, 0), ("b".to_string(), Color::Reset, Color::Red));
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_line_styles(0, vec![(1..2, Style::default().fg(Color::Green))]);
+        assert_eq!(cell(&textarea, 1, 0), ("b".to_string(), Color::Green, Color::Reset));
+        textarea.clear_line_styles();
+        assert_eq!(cell(&textarea, 1, 0), plain("b"));
+        // Editing text
+        textarea.move_cursor(CursorMove::Down);
+        textarea.insert_char('x');
+        assert_eq!(cell(&textarea, 0, 1), plain("x"));
+        // Selection
+        textarea.set_selection((0, 0), (0, 2));
+        let bg = textarea.selection_style().bg.unwrap();
+        assert_eq!(cell(&textarea, 1, 0), ("b".to_string(), Color::Reset, bg));
+        textarea.cancel_selection();
+        assert_eq!(cell(&textarea, 1, 0), plain("b"));
+        // Cursor
+        textarea.move_cursor(CursorMove::Jump(0, 1));
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert!(b.get(1, 0).modifier.contains(Modifier::REVERSED));
+        textarea.move_cursor(CursorMove::Forward);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert!(!b.get(1, 0).modifier.contains(Modifier::REVERSED));
+
+        // Clearing cache does not change the rendering
+        textarea.clear_render_cache();
+        assert_eq!(render(&textarea, 4, 2), ["abc ", "xdef"]);
+    }
+
+    #[test]
+    fn render_cache_line_edits() {
+        let mut textarea = TextArea::from(["abc", "def", "ghi"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_line_number_style(Style::default());
+        assert_eq!(render(&textarea, 5, 3), ["1 abc", "2 def", "3 ghi"]);
+        // Lines below the inserted line are shifted
+        textarea.insert_newline();
+        assert_eq!(render(&textarea, 5, 4), ["1    ", "2 abc", "3 def", "4 ghi"]);
+        textarea.undo();
+        assert_eq!(render(&textarea, 5, 3), ["1 abc", "2 def", "3 ghi"]);
+        textarea.redo();
+        textarea.move_cursor(CursorMove::Jump(2, 0));
+        textarea.delete_line_by_end();
+        assert_eq!(render(&textarea, 5, 4), ["1    ", "2 abc", "3    ", "4 ghi"]);
+        textarea.select_all();
+        textarea.insert_str("x");
+        assert_eq!(render(&textarea, 5, 1), ["1 x  "]);
+
+        // Indent guides of a blank line follow edits of the surrounding lines
+        let mut textarea = TextArea::from(["    a", "", "    b"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_indent_guides(Some(Style::default().fg(Color::Green)));
+        let fg = |textarea: &TextArea, x: u16, y: u16| {
+            let r = Rect::new(0, 0, 5, 3);
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            b.get(x, y).fg
+        };
+        assert_eq!(fg(&textarea, 0, 1), Color::Green);
+        textarea.move_cursor(CursorMove::End);
+        textarea.delete_line_by_head();
+        assert_eq!(fg(&textarea, 0, 1), Color::Reset);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn render_cache_search() {
+        let mut textarea = TextArea::from(["abc", "def"]);
+        textarea.set_cursor_line_style(Style::default());
+        let r = Rect::new(0, 0, 4, 2);
+        let bg = |textarea: &TextArea, x: u16, y: u16| {
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            b.get(x, y).bg
+        };
+
+        assert_eq!(bg(&textarea, 1, 1), Color::Reset);
+        textarea.set_search_pattern("e").unwrap();
+        let style = textarea.search_style().bg.unwrap();
+        assert_eq!(bg(&textarea, 1, 1), style);
+        textarea.set_search_style(Style::default().bg(Color::Red));
+        assert_eq!(bg(&textarea, 1, 1), Color::Red);
+        textarea.set_search_pattern("f").unwrap();
+        assert_eq!(bg(&textarea, 1, 1), Color::Reset);
+        assert_eq!(bg(&textarea, 2, 1), Color::Red);
+        textarea.set_search_pattern("").unwrap();
+        assert_eq!(bg(&textarea, 2, 1), Color::Reset);
+
+        // Matches of a multi-line pattern follow edits of the other lines
+        textarea.set_search_pattern("c\\nd").unwrap();
+        assert_eq!(bg(&textarea, 0, 1), Color::Red);
+        textarea.move_cursor(CursorMove::Jump(0, 3));
+        textarea.insert_char('x');
+        assert_eq!(bg(&textarea, 0, 1), Color::Reset);
+    }
+
+    #[test]
+    fn hscroll_by_display_cells() {
+        // Render the textarea and return the lines and the cell of the cursor
+        fn render_cursor(textarea: &TextArea<'_>, width: u16) -> (Vec<String>, (u16, u16)) {
+            let r = Rect::new(0, 0, width, 1);
+            let mut b = Buffer::empty(r);
+            textarea.widget().render(r, &mut b);
+            let (x, y) = textarea.cursor_screen_position().unwrap();
+            assert!(b.get(x, y).modifier.contains(Modifier::REVERSED), "{:?}", (x, y));
+            (buffer_lines(&b), (x, y))
+        }
+
+        let mut textarea = TextArea::from(["🐶🐱🐭🐹🐰"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.move_cursor(CursorMove::End);
+        // The half of the wide character scrolled out is padded
+        assert_eq!(render_cursor(&textarea, 6), (vec![" 🐹🐰 ".to_string()], (5, 0)));
+        textarea.move_cursor(CursorMove::Back);
+        assert_eq!(render_cursor(&textarea, 6), (vec![" 🐹🐰 ".to_string()], (3, 0)));
+        textarea.move_cursor(CursorMove::Head);
+        assert_eq!(render_cursor(&textarea, 6), (vec!["🐶🐱🐭".to_string()], (0, 0)));
+
+        let mut textarea = TextArea::from(["\t\t\tx"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_tab_length(4);
+        textarea.move_cursor(CursorMove::Jump(0, 2));
+        // The whole tab under the cursor is visible
+        assert_eq!(render_cursor(&textarea, 6), (vec!["      ".to_string()], (2, 0)));
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render_cursor(&textarea, 6), (vec!["    x ".to_string()], (5, 0)));
+
+        let mut textarea = TextArea::from(["a\tあいう🐶b"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_tab_length(4);
+        textarea.move_cursor(CursorMove::Jump(0, 5));
+        assert_eq!(render_cursor(&textarea, 6), (vec!["いう🐶".to_string()], (4, 0)));
+        textarea.move_cursor(CursorMove::End);
+        assert_eq!(render_cursor(&textarea, 6), (vec!["う🐶b ".to_string()], (5, 0)));
+    }
+
+    #[test]
+    fn stable_gutter_width() {
+        let mut textarea: TextArea = (1..=9).map(|i| i.to_string()).collect();
+        textarea.set_line_number_style(Style::default());
+        textarea.move_cursor(CursorMove::Bottom);
+        textarea.move_cursor(CursorMove::End);
+        textarea.insert_str("abcdef");
+        assert_eq!(render(&textarea, 8, 1), ["9abcdef "]);
+
+        // The cursor is on the 9th line but the horizontal scroll considers the gutter widened for the 10th line
+        textarea.insert_newline();
+        textarea.move_cursor(CursorMove::Up);
+        textarea.move_cursor(CursorMove::End);
+        let r = Rect::new(0, 0, 8, 1);
+        let mut b = Buffer::empty(r);
+        textarea.widget().render(r, &mut b);
+        assert_eq!(buffer_lines(&b), ["9abcdef "]);
+        assert_eq!(textarea.cursor_screen_position(), Some((7, 0)));
+        assert!(b.get(7, 0).modifier.contains(Modifier::REVERSED));
+
+        // Reserve the width to avoid the shift
+        let mut textarea: TextArea = (1..=9).map(|i| i.to_string()).collect();
+        textarea.set_line_number_style(Style::default());
+        textarea.set_line_number_min_width(2);
+        let before = render(&textarea, 6, 9);
+        assert_eq!(before[0], " 1 1  ");
+        textarea.move_cursor(CursorMove::Bottom);
+        textarea.move_cursor(CursorMove::End);
+        textarea.insert_newline();
+        textarea.move_cursor(CursorMove::Top);
+        assert_eq!(render(&textarea, 6, 9), before);
+        assert_eq!(render(&textarea, 6, 10)[9], "10    ");
+    }
+
+    #[test]
+    fn relative_line_numbers() {
+        let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+        textarea.set_line_number_style(Style::default());
+        textarea.set_relative_line_numbers(true);
+        textarea.move_cursor(CursorMove::Jump(2, 0));
+        assert_eq!(render(&textarea, 5, 4), [" 2 0 ", " 1 1 ", " 3 2 ", " 1 3 "]);
+
+        textarea.move_cursor(CursorMove::Down);
+        assert_eq!(render(&textarea, 5, 4), [" 3 0 ", " 2 1 ", " 1 2 ", " 4 3 "]);
+
+        textarea.toggle_line_numbers();
+        assert_eq!(render(&textarea, 5, 4), ["0    ", "1    ", "2    ", "3    "]);
+        textarea.toggle_line_numbers();
+        assert_eq!(render(&textarea, 5, 4), [" 3 0 ", " 2 1 ", " 1 2 ", " 4 3 "]);
+
+        textarea.set_relative_line_numbers(false);
+        assert_eq!(render(&textarea, 5, 4), [" 1 0 ", " 2 1 ", " 3 2 ", " 4 3 "]);
+    }
+
+    #[test]
+    fn screen_to_cursor() {
+        let mut textarea = TextArea::from(["a\tb", "🐶🐱", "x"]);
+        textarea.set_line_number_style(Style::default());
+        assert_eq!(textarea.screen_to_cursor(0, 0), None); // Not rendered yet
+        assert_eq!(
+            render(&textarea, 8, 4),
+            ["1 a   b ", "2 🐶🐱  ", "3 x     ", "        "]
+        );
+
+        #[rustfmt::skip]
+        let tests = [
+            ((0, 0), Some((0, 0))), // Line number
+            ((2, 0), Some((0, 0))),
+            ((3, 0), Some((0, 1))), // Hard tab
+            ((5, 0), Some((0, 1))),
+            ((6, 0), Some((0, 2))),
+            ((7, 0), Some((0, 3))),
+            ((3, 1), Some((1, 0))), // Right half of wide char
+            ((4, 1), Some((1, 1))),
+            ((7, 1), Some((1, 2))),
+            ((2, 3), Some((2, 1))), // Below the last line
+            ((2, 2), Some((2, 0))),
+            ((8, 0), None),
+            ((0, 4), None),
+        ];
+        for test in tests {
+            let ((x, y), want) = test;
+            assert_eq!(textarea.screen_to_cursor(x, y), want, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn screen_to_cursor_with_wrap() {
+        let mut textarea = TextArea::from(["abcdefghij", "xy"]);
+        textarea.set_wrap(true);
+        textarea.set_wrap_indicator(Some((">".to_string(), Style::default())));
+        assert_eq!(render(&textarea, 4, 3), ["abcd", ">efg", ">hij"]);
+
+        #[rustfmt::skip]
+        let tests = [
+            ((1, 0), (0, 1)),
+            ((0, 1), (0, 4)), // Wrap indicator
+            ((2, 1), (0, 5)),
+            ((3, 2), (0, 9)),
+        ];
+        for test in tests {
+            let ((x, y), want) = test;
+            assert_eq!(textarea.screen_to_cursor(x, y), Some(want), "{test:?}");
+        }
+
+        textarea.move_cursor(CursorMove::Bottom);
+        assert_eq!(render(&textarea, 4, 3), [">efg", ">hij", "xy  "]);
+        assert_eq!(textarea.screen_to_cursor(1, 0), Some((0, 4)));
+        assert_eq!(textarea.screen_to_cursor(1, 2), Some((1, 1)));
+    }
+
+    #[test]
+    fn mouse_click_and_drag() {
+        use crate::{Input, Key};
+
+        let mut textarea = TextArea::from(["abc", "def"]);
+        render(&textarea, 4, 2);
+        let mouse = |key| Input { key, ..Default::default() };
+
+        textarea.input(mouse(Key::MouseClick(1, 0)));
+        assert_eq!(textarea.cursor(), (0, 1));
+        assert!(!textarea.is_selecting());
+
+        textarea.input(mouse(Key::MouseDrag(2, 1)));
+        assert_eq!(textarea.selection_range(), Some(((0, 1), (1, 2))));
+
+        textarea.input(mouse(Key::MouseClick(0, 1)));
+        assert_eq!(textarea.cursor(), (1, 0));
+        assert!(!textarea.is_selecting());
+
+        // Clicking clears secondary cursors
+        textarea.add_cursor(0, 0);
+        textarea.input(mouse(Key::MouseClick(0, 1)));
+        assert!(textarea.secondary_cursors().is_empty());
+
+        // Shift+click extends the selection
+        let input = Input {
+            shift: true,
+            ..mouse(Key::MouseClick(3, 0))
+        };
+        textarea.input(input);
+        assert_eq!(textarea.selection_range(), Some(((0, 3), (1, 0))));
 
-        inner.render(text_area, buf);
+        // Clicking outside the textarea does nothing
+        textarea.input(mouse(Key::MouseClick(5, 5)));
+        assert_eq!(textarea.cursor(), (0, 3));
+        assert!(textarea.is_selecting());
     }
 }
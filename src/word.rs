@@ -1,3 +1,7 @@
+use std::borrow::Cow;
+use std::iter;
+use std::mem;
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 enum CharKind {
     Space,
@@ -66,3 +70,180 @@ pub fn find_word_start_backward(line: &str, start_col: usize) -> Option<usize> {
     }
     Some(0)
 }
+
+// Start column of the word ending at `col`. Words consist of characters which are neither whitespaces nor ASCII
+// punctuations as `find_word_start_backward`. `col` is returned when the character before it is not in a word
+pub fn find_word_prefix_start(line: &str, col: usize) -> usize {
+    let idx = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+    let len = line[..idx]
+        .chars()
+        .rev()
+        .take_while(|&c| CharKind::new(c) == CharKind::Other)
+        .count();
+    line[..idx].chars().count() - len
+}
+
+// Words in the pieces of a line split in the same way as `find_word_prefix_start`. A word in one piece is borrowed, and
+// a word split across pieces is joined into an owned string
+pub struct ChunkWords<'a, I> {
+    chunks: I,
+    current: &'a str,
+    pending: String, // Start of the word continuing in the next pieces
+}
+
+impl<'a, I: Iterator<Item = &'a str>> ChunkWords<'a, I> {
+    pub fn new(chunks: I) -> Self {
+        Self {
+            chunks,
+            current: "",
+            pending: String::new(),
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for ChunkWords<'a, I> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let is_word = |c: char| CharKind::new(c) == CharKind::Other;
+        loop {
+            if self.pending.is_empty() {
+                self.current = self.current.trim_start_matches(|c| !is_word(c));
+            }
+            if let Some(end) = self.current.find(|c| !is_word(c)) {
+                let (word, rest) = self.current.split_at(end);
+                self.current = rest;
+                if self.pending.is_empty() {
+                    return Some(Cow::Borrowed(word));
+                }
+                self.pending.push_str(word);
+                return Some(Cow::Owned(mem::take(&mut self.pending)));
+            }
+            // The rest of the piece is a word which may continue in the next piece
+            let Some(chunk) = self.chunks.next() else {
+                let word = mem::take(&mut self.current);
+                if self.pending.is_empty() {
+                    return (!word.is_empty()).then_some(Cow::Borrowed(word));
+                }
+                self.pending.push_str(word);
+                return Some(Cow::Owned(mem::take(&mut self.pending)));
+            };
+            self.pending.push_str(self.current);
+            self.current = chunk;
+        }
+    }
+}
+
+// Ranges of columns of the words in the line split in the same way as `ChunkWords`. Their boundaries are where
+// `find_word_end_forward` and `find_word_start_backward` stop
+pub fn word_ranges(line: &str) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    let mut start = None;
+    for (col, c) in line.chars().chain(iter::once(' ')).enumerate() {
+        match (CharKind::new(c) == CharKind::Other, start) {
+            (true, None) => start = Some(col),
+            (false, Some(s)) => {
+                ranges.push((s, col));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+// Range of columns of the identifier, which consists of letters, digits, and underscores, at `col`. When the character
+// at `col` is not in an identifier, the identifier ending at `col` is returned
+pub fn find_identifier(line: &str, col: usize) -> Option<(usize, usize)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let chars: Vec<char> = line.chars().collect();
+    let col = if chars.get(col).map_or(false, |&c| is_ident(c)) {
+        col
+    } else {
+        col.checked_sub(1)
+            .filter(|&i| chars.get(i).map_or(false, |&c| is_ident(c)))?
+    };
+    let start = chars[..col].iter().rev().take_while(|&&c| is_ident(c)).count();
+    let end = chars[col..].iter().take_while(|&&c| is_ident(c)).count();
+    Some((col - start, col + end))
+}
+
+// Kind of character to split a word into sub-words. Only ASCII letters and digits are split at case and digit
+// boundaries. Other letters such as Cyrillic and CJK follow the rules of words. Underscores separate sub-words like
+// spaces
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum SubWordKind {
+    Space,
+    Punct,
+    Lower,
+    Upper,
+    Digit,
+    Other,
+}
+
+impl SubWordKind {
+    fn new(c: char) -> Self {
+        match c {
+            'a'..='z' => Self::Lower,
+            'A'..='Z' => Self::Upper,
+            '0'..='9' => Self::Digit,
+            '_' => Self::Space,
+            _ => match CharKind::new(c) {
+                CharKind::Space => Self::Space,
+                CharKind::Punct => Self::Punct,
+                CharKind::Other => Self::Other,
+            },
+        }
+    }
+}
+
+// Check if a sub-word boundary is between `prev` and `cur` characters. `next` is the character following `cur`
+fn is_subword_boundary(prev: SubWordKind, cur: SubWordKind, next: Option<SubWordKind>) -> bool {
+    use SubWordKind::*;
+    match (prev, cur) {
+        // The last upper case letter of an acronym starts a new sub-word like `HTTPResponse`
+        (Upper, Upper) => next == Some(Lower),
+        (Upper, Lower) => false,
+        (Other, Lower | Upper | Digit) | (Lower | Upper | Digit, Other) => false,
+        _ => prev != cur,
+    }
+}
+
+pub fn find_subword_end_forward(line: &str, start_col: usize) -> Option<usize> {
+    let kinds: Vec<_> = line.chars().map(SubWordKind::new).collect();
+    if start_col >= kinds.len() {
+        return None;
+    }
+    for col in start_col + 1..kinds.len() {
+        if is_subword_boundary(kinds[col - 1], kinds[col], kinds.get(col + 1).copied()) {
+            return Some(col);
+        }
+    }
+    Some(kinds.len())
+}
+
+// Find the end of the next sub-word skipping spaces before it
+pub fn find_subword_end_after_spaces(line: &str, start_col: usize) -> Option<usize> {
+    let kinds: Vec<_> = line.chars().map(SubWordKind::new).collect();
+    if start_col >= kinds.len() {
+        return None;
+    }
+    let start = kinds[start_col..]
+        .iter()
+        .position(|&k| k != SubWordKind::Space)
+        .map_or(kinds.len(), |i| start_col + i);
+    find_subword_end_forward(line, start).or(Some(kinds.len()))
+}
+
+pub fn find_subword_start_backward(line: &str, start_col: usize) -> Option<usize> {
+    let kinds: Vec<_> = line.chars().map(SubWordKind::new).collect();
+    if start_col == 0 {
+        return None;
+    }
+    for col in (1..start_col.min(kinds.len())).rev() {
+        if is_subword_boundary(kinds[col - 1], kinds[col], kinds.get(col + 1).copied()) {
+            return Some(col);
+        }
+    }
+    Some(0)
+}
@@ -0,0 +1,212 @@
+use crate::util::char_width;
+use unicode_segmentation::UnicodeSegmentation as _;
+
+/// Grapheme cluster of a line, which is never split across display rows.
+#[derive(Clone, Copy, Debug)]
+struct Grapheme {
+    col: usize,   // Character column where the grapheme starts
+    end: usize,   // Character column after the grapheme
+    width: usize, // Number of cells occupied by the grapheme
+    space: bool,  // The grapheme is whitespace, after which a row can be wrapped
+}
+
+/// Calculate how a logical line is soft-wrapped into multiple display rows. Rows are wrapped at word boundaries after
+/// whitespaces, and words longer than a row are wrapped at grapheme boundaries. Widths are measured in terminal cells so
+/// grapheme clusters such as wide characters, emoji sequences, and hard tabs are never split across rows.
+#[derive(Clone, Copy, Debug)]
+pub struct Wrapper {
+    width: usize,
+    cont_width: usize,
+    tab_len: u8,
+    mask: Option<char>,
+    control: bool,
+}
+
+impl Wrapper {
+    /// `width` is the number of cells available for text in a display row. `indent` is the number of cells occupied by
+    /// the wrap indicator at the start of continuation rows. `control` is `true` when control characters are rendered
+    /// in their notation.
+    pub fn new(width: usize, indent: usize, tab_len: u8, mask: Option<char>, control: bool) -> Self {
+        let width = width.max(1);
+        let cont_width = width.saturating_sub(indent).max(1);
+        Self {
+            width,
+            cont_width,
+            tab_len,
+            mask,
+            control,
+        }
+    }
+
+    fn graphemes(&self, line: &str) -> Vec<Grapheme> {
+        let mut total = 0;
+        let mut col = 0;
+        line.graphemes(true)
+            .map(|g| {
+                let start = col;
+                let mut width = 0;
+                for c in g.chars() {
+                    let w = char_width(c, total, self.tab_len, self.mask, self.control);
+                    total += w;
+                    width += w;
+                    col += 1;
+                }
+                let space = g.chars().all(char::is_whitespace);
+                Grapheme {
+                    col: start,
+                    end: col,
+                    width,
+                    space,
+                }
+            })
+            .collect()
+    }
+
+    fn split(&self, graphemes: &[Grapheme], end_cell: bool) -> Vec<usize> {
+        let mut rows = vec![0];
+        let mut used = 0;
+        let mut limit = self.width;
+        // Column after the last whitespace in the row, and the cells used until the column
+        let mut word_start = None;
+        for (i, g) in graphemes.iter().enumerate() {
+            if used > 0 && used + g.width > limit {
+                // Move the word being split to the next row when the entire word fits there
+                let word = word_start.filter(|&(_, before)| {
+                    let rest = graphemes[i..].iter().take_while(|g| !g.space);
+                    !g.space && used - before + rest.map(|g| g.width).sum::<usize>() <= self.cont_width
+                });
+                if let Some((col, before)) = word {
+                    rows.push(col);
+                    used -= before;
+                } else {
+                    rows.push(g.col);
+                    used = 0;
+                }
+                limit = self.cont_width;
+                word_start = None;
+            }
+            used += g.width;
+            if g.space {
+                word_start = Some((g.end, used));
+            }
+        }
+        if end_cell && used > 0 && used + 1 > limit {
+            rows.push(graphemes.last().map_or(0, |g| g.end));
+        }
+        rows
+    }
+
+    /// Return the character columns where each display row of the line starts. The first element is always 0. When
+    /// `end_cell` is `true`, one extra cell is reserved at the end of the line for the cursor.
+    pub fn rows(&self, line: &str, end_cell: bool) -> Vec<usize> {
+        self.split(&self.graphemes(line), end_cell)
+    }
+
+    /// Return the index of the display row at the character column `col`, and the display offset of the column from
+    /// the start of the row.
+    pub fn locate(&self, line: &str, col: usize) -> (usize, usize) {
+        let graphemes = self.graphemes(line);
+        let len = graphemes.last().map_or(0, |g| g.end);
+        let rows = self.split(&graphemes, col >= len);
+        let row = rows.iter().rposition(|&start| start <= col).unwrap_or(0);
+        let offset = graphemes
+            .iter()
+            .filter(|g| rows[row] <= g.col && g.col < col)
+            .map(|g| g.width)
+            .sum();
+        (row, offset)
+    }
+
+    /// Return the character column which is displayed at the display `offset` in the display `row` of the line. When
+    /// the row is shorter than the offset, the column of the last grapheme in the row is returned.
+    pub fn col_at(&self, line: &str, row: usize, offset: usize) -> usize {
+        let graphemes = self.graphemes(line);
+        let rows = self.split(&graphemes, false);
+        let row = row.min(rows.len() - 1);
+        let (start, next) = (rows[row], rows.get(row + 1).copied());
+        let mut cells = 0;
+        let mut last = start;
+        for g in graphemes
+            .iter()
+            .filter(|g| start <= g.col && next.map_or(true, |n| g.col < n))
+        {
+            cells += g.width;
+            if cells > offset {
+                return g.col;
+            }
+            last = g.col;
+        }
+        match next {
+            Some(_) => last,
+            None => graphemes.last().map_or(0, |g| g.end),
+        }
+    }
+
+    /// Return the number of display rows which the line occupies.
+    pub fn height(&self, line: &str, end_cell: bool) -> usize {
+        self.rows(line, end_cell).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_rows() {
+        #[rustfmt::skip]
+        let tests = [
+            ("", 4, false, &[0][..]),
+            ("abcd", 4, false, &[0][..]),
+            ("abcd", 4, true, &[0, 4][..]),
+            ("abcdefghij", 4, false, &[0, 4, 8][..]),
+            ("あいう", 4, false, &[0, 2][..]),
+            ("aあいう", 4, false, &[0, 2][..]),
+            ("🐶🐱🐭", 3, false, &[0, 1, 2][..]),
+            ("a\tb", 4, false, &[0, 2][..]),
+            ("e\u{301}abc", 4, false, &[0][..]),
+            ("abcde\u{301}", 4, false, &[0, 4][..]),
+            ("a\x1bbc", 4, false, &[0, 3][..]),
+            // Family emoji joined with ZWJ at the boundary is not split
+            ("ab\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}c", 4, false, &[0, 2, 7][..]),
+            ("abc\u{1f468}\u{200d}\u{1f469}", 6, false, &[0, 3][..]),
+            // Wrapped at word boundaries
+            ("ab cd ef", 4, false, &[0, 3, 6][..]),
+            ("ab cd ef", 5, false, &[0, 5][..]), // Whitespace overflowing the row starts the next row
+            ("ab  cdef", 4, false, &[0, 4][..]),
+            ("abc defgh", 4, false, &[0, 4, 8][..]),
+            ("a bcdefgh", 4, false, &[0, 4, 8][..]), // Word longer than a row is not moved
+            ("a bcd", 4, false, &[0, 2][..]),
+            ("abcd ef", 4, false, &[0, 4][..]),
+            ("ab cd", 5, true, &[0, 5][..]),
+        ];
+        for test in tests {
+            let (line, width, end_cell, want) = test;
+            let w = Wrapper::new(width, 0, 4, None, true);
+            assert_eq!(w.rows(line, end_cell), want, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn wrap_rows_with_indent() {
+        let w = Wrapper::new(4, 1, 4, None, true);
+        assert_eq!(w.rows("abcdefghij", false), [0, 4, 7]);
+    }
+
+    #[test]
+    fn wrap_locate_and_col_at() {
+        let w = Wrapper::new(4, 0, 4, None, true);
+        assert_eq!(w.locate("abcdefghij", 0), (0, 0));
+        assert_eq!(w.locate("abcdefghij", 5), (1, 1));
+        assert_eq!(w.locate("abcdefghij", 10), (2, 2));
+        assert_eq!(w.locate("abcd", 4), (1, 0));
+        assert_eq!(w.locate("aあいう", 2), (1, 0));
+        assert_eq!(w.locate("aあいう", 3), (1, 2));
+
+        assert_eq!(w.col_at("abcdefghij", 1, 1), 5);
+        assert_eq!(w.col_at("abcdefghij", 2, 3), 10);
+        assert_eq!(w.col_at("abcdefghij", 0, 10), 3);
+        assert_eq!(w.col_at("あいうえ", 0, 1), 0);
+        assert_eq!(w.col_at("あいうえ", 1, 3), 3);
+    }
+}
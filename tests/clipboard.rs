@@ -0,0 +1,54 @@
+#![cfg(feature = "clipboard")]
+
+use tui_textarea::{CursorMove, Input, Key, TextArea};
+
+// The system clipboard may not be available in the test environment. These tests check that the yank buffer works
+// regardless of the clipboard availability.
+
+#[test]
+fn copy_cut_paste_with_clipboard_sync() {
+    let mut t = TextArea::from(["abc", "あいう"]);
+    t.set_clipboard_sync(true);
+
+    t.start_selection();
+    t.move_cursor(CursorMove::Down);
+    t.copy();
+    assert_eq!(t.yank_text(), "abc\n");
+    t.cancel_selection();
+    t.move_cursor(CursorMove::End);
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["abc", "あいうabc", ""]);
+
+    t.move_cursor(CursorMove::Top);
+    t.move_cursor(CursorMove::Head);
+    assert!(t.input(Input {
+        key: Key::Cut,
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["あいうabc", ""]);
+    assert_eq!(t.yank_text(), "abc");
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["abcあいうabc", ""]);
+
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["あいうabc", ""]);
+}
+
+#[test]
+fn clone_with_clipboard_sync() {
+    let mut t = TextArea::from(["abc"]);
+    t.set_clipboard_sync(true);
+    t.copy();
+
+    let mut t2 = t.clone();
+    assert!(t2.clipboard_sync());
+    t2.move_cursor(CursorMove::End);
+    assert!(t2.paste());
+    assert_eq!(t2.lines(), ["abcabc"]);
+
+    t.set_clipboard_sync(false);
+    assert!(!t.clipboard_sync());
+    t.set_yank_text("x");
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["xabc"]);
+}
@@ -18,10 +18,14 @@ fn empty_textarea() {
         Bottom,
         WordForward,
         WordBack,
+        SubWordForward,
+        SubWordBack,
+        SubWordEnd,
         ParagraphForward,
         ParagraphBack,
         Jump(0, 0),
         Jump(u64::MAX, u64::MAX),
+        MatchingBracket,
     ] {
         t.move_cursor(m);
         assert_eq!(t.cursor(), (0, 0), "{:?}", m);
@@ -235,3 +239,104 @@ fn bottom_trim() {
         assert_eq!(t.cursor(), (t.lines().len() - 1, col), "{:?}", t.lines());
     }
 }
+
+#[test]
+fn matching_bracket() {
+    let mut t = TextArea::from(["fn f(x: [u8]) {", "    g({ x })", "}"]);
+    for (start, end) in [
+        ((0, 4), (0, 12)),
+        ((0, 12), (0, 4)),
+        ((0, 8), (0, 11)),
+        ((0, 14), (2, 0)),
+        ((2, 0), (0, 14)),
+        ((2, 1), (0, 14)),
+        ((1, 5), (1, 11)),
+        ((1, 6), (1, 10)),
+        ((1, 12), (1, 5)),
+        ((0, 0), (0, 0)),
+        ((1, 0), (1, 0)),
+    ] {
+        t.move_cursor(CursorMove::Jump(start.0, start.1));
+        t.move_cursor(CursorMove::MatchingBracket);
+        assert_eq!(t.cursor(), end, "{:?}", start);
+    }
+
+    // Unmatched bracket does not move the cursor
+    let mut t = TextArea::from(["((", ")]"]);
+    t.move_cursor(CursorMove::MatchingBracket);
+    assert_eq!(t.cursor(), (0, 0));
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.move_cursor(CursorMove::MatchingBracket);
+    assert_eq!(t.cursor(), (1, 1));
+
+    // Search is bounded by the limit
+    let mut t = TextArea::from(["{", "", "", "}"]);
+    t.set_matching_bracket_limit(3);
+    assert_eq!(t.matching_bracket_limit(), 3);
+    t.move_cursor(CursorMove::MatchingBracket);
+    assert_eq!(t.cursor(), (0, 0));
+    t.set_matching_bracket_limit(4);
+    t.move_cursor(CursorMove::MatchingBracket);
+    assert_eq!(t.cursor(), (3, 0));
+}
+
+#[test]
+fn subword_forward() {
+    #[rustfmt::skip]
+    let tests = [
+        (&["parseHTTPResponse_v2"][..], &[(0, 5), (0, 9), (0, 17), (0, 18), (0, 19), (0, 20), (0, 20)][..]),
+        (&["foo_bar baz", "x"], &[(0, 3), (0, 4), (0, 7), (0, 8), (0, 11), (1, 0), (1, 1)]),
+        (&["fooBar(a1)"], &[(0, 3), (0, 6), (0, 7), (0, 8), (0, 9), (0, 10)]),
+        (&["ABC DEF"], &[(0, 3), (0, 4), (0, 7)]),
+        // Non-ASCII words are not split
+        (&["приветМир мир"], &[(0, 9), (0, 10), (0, 13)]),
+        (&["日本語のテキスト abc"], &[(0, 8), (0, 9), (0, 12)]),
+        (&["fooБар"], &[(0, 6)]),
+    ];
+
+    for (text, positions) in tests {
+        let mut t = TextArea::from(text.iter().copied());
+        for &pos in positions {
+            t.move_cursor(CursorMove::SubWordForward);
+            assert_eq!(t.cursor(), pos, "{:?}", text);
+        }
+    }
+}
+
+#[test]
+fn subword_back() {
+    #[rustfmt::skip]
+    let tests = [
+        (&["parseHTTPResponse_v2"][..], &[(0, 19), (0, 18), (0, 17), (0, 9), (0, 5), (0, 0), (0, 0)][..]),
+        (&["x", "foo_bar baz"], &[(1, 8), (1, 7), (1, 4), (1, 3), (1, 0), (0, 1), (0, 0)]),
+        (&["ABCDef"], &[(0, 3), (0, 0)]),
+        (&["приветМир мир"], &[(0, 10), (0, 9), (0, 0)]),
+    ];
+
+    for (text, positions) in tests {
+        let mut t = TextArea::from(text.iter().copied());
+        t.move_cursor(BOTTOM_RIGHT);
+        for &pos in positions {
+            t.move_cursor(CursorMove::SubWordBack);
+            assert_eq!(t.cursor(), pos, "{:?}", text);
+        }
+    }
+}
+
+#[test]
+fn subword_end() {
+    #[rustfmt::skip]
+    let tests = [
+        (&["parseHTTPResponse_v2"][..], &[(0, 5), (0, 9), (0, 17), (0, 19), (0, 20), (0, 20)][..]),
+        (&["  foo__bar  ", "baz"], &[(0, 5), (0, 10), (0, 12), (1, 0), (1, 3)]),
+        (&["приветМир мир"], &[(0, 9), (0, 13)]),
+    ];
+
+    for (text, positions) in tests {
+        let mut t = TextArea::from(text.iter().copied());
+        for &pos in positions {
+            t.move_cursor(CursorMove::SubWordEnd);
+            assert_eq!(t.cursor(), pos, "{:?}", text);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use tui_textarea::TextArea;
+use tui_textarea::{CursorMove, Input, Key, TextArea, TextEdit};
 
 // Regression test for #4
 #[test]
@@ -8,3 +8,228 @@ fn disable_history() {
     assert!(t.insert_str("hello"));
     assert_eq!(t.lines(), ["hello"]);
 }
+
+// Apply the edit to the lines checking the deleted text is correct
+fn apply_edit(lines: &mut Vec<String>, edit: &TextEdit) {
+    let byte_offset = |line: &str, col: usize| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+    let (start, end) = (edit.start, edit.end);
+    let head = &lines[start.0][..byte_offset(&lines[start.0], start.1)];
+    let tail = &lines[end.0][byte_offset(&lines[end.0], end.1)..];
+
+    let text = lines[start.0..=end.0].join("\n");
+    let deleted_start = head.len();
+    let deleted_end = text.len() - tail.len();
+    assert_eq!(&text[deleted_start..deleted_end], edit.deleted, "{edit:?}");
+
+    let replaced = format!("{}{}{}", head, edit.inserted, tail);
+    lines.splice(start.0..=end.0, replaced.split('\n').map(String::from));
+}
+
+fn assert_edits(t: &mut TextArea<'_>, lines: &mut Vec<String>) {
+    for edit in t.take_edits() {
+        apply_edit(lines, &edit);
+    }
+    assert_eq!(lines, t.lines());
+}
+
+#[test]
+fn edit_tracking() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    let mut lines = t.lines().to_vec();
+    t.set_edit_tracking(true);
+
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.delete_line_by_head();
+    t.paste();
+    t.insert_newline();
+    t.paste_str("x\ny\nz");
+    t.delete_line_by_end();
+    t.move_lines_up();
+    t.move_lines_down();
+    t.duplicate_lines();
+    t.move_cursor(CursorMove::Top);
+    t.delete_next_char();
+    t.delete_char();
+    t.move_cursor(CursorMove::Bottom);
+    t.move_cursor(CursorMove::End);
+    t.delete_next_char();
+    assert_edits(&mut t, &mut lines);
+
+    while t.undo() {}
+    assert_edits(&mut t, &mut lines);
+    assert_eq!(lines, ["abc", "def", "ghi"]);
+
+    while t.redo() {}
+    assert_edits(&mut t, &mut lines);
+
+    t.set_edit_tracking(false);
+    t.insert_str("foo");
+    assert!(t.take_edits().is_empty());
+}
+
+#[test]
+fn edit_tracking_batch() {
+    let mut t = TextArea::from(["a", "b", "c"]);
+    let mut lines = t.lines().to_vec();
+    t.set_edit_tracking(true);
+
+    t.start_selection();
+    t.move_cursor(CursorMove::Bottom);
+    t.indent();
+    t.move_lines_up();
+    t.duplicate_lines();
+    assert_edits(&mut t, &mut lines);
+
+    t.begin_undo_group();
+    t.insert_str("x\ny");
+    t.delete_newline();
+    t.insert_char('z');
+    t.end_undo_group();
+    assert_edits(&mut t, &mut lines);
+
+    while t.undo() {}
+    assert_edits(&mut t, &mut lines);
+    while t.redo() {}
+    assert_edits(&mut t, &mut lines);
+
+    t.set_single_line(true);
+    assert_edits(&mut t, &mut lines);
+    assert_eq!(lines.len(), 1);
+}
+
+// Simple xorshift random number generator to generate inputs reproducibly
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+#[test]
+fn edit_tracking_random_inputs() {
+    const KEYS: &[Key] = &[
+        Key::Char('a'),
+        Key::Char('b'),
+        Key::Char(' '),
+        Key::Char('('),
+        Key::Char('あ'),
+        Key::Char('🐶'),
+        Key::Char('d'),
+        Key::Char('h'),
+        Key::Char('j'),
+        Key::Char('k'),
+        Key::Char('m'),
+        Key::Char('r'),
+        Key::Char('u'),
+        Key::Char('w'),
+        Key::Char('x'),
+        Key::Char('y'),
+        Key::Backspace,
+        Key::Enter,
+        Key::Left,
+        Key::Right,
+        Key::Up,
+        Key::Down,
+        Key::Tab,
+        Key::BackTab,
+        Key::Delete,
+        Key::Home,
+        Key::End,
+        Key::Cut,
+        Key::Paste,
+    ];
+
+    for seed in 1..=50 {
+        let mut rng = Rng(seed);
+        let mut t = TextArea::from(["hello", "", "world", "🐶🐱 あいう"]);
+        let mut lines = t.lines().to_vec();
+        t.set_edit_tracking(true);
+        t.set_auto_indent(rng.next(2) == 0);
+
+        for _ in 0..300 {
+            let input = Input {
+                key: KEYS[rng.next(KEYS.len())],
+                ctrl: rng.next(3) == 0,
+                alt: rng.next(4) == 0,
+                shift: rng.next(4) == 0,
+            };
+            t.input(input);
+            if rng.next(10) == 0 {
+                assert_edits(&mut t, &mut lines);
+            }
+        }
+        assert_edits(&mut t, &mut lines);
+
+        while t.undo() {}
+        assert_edits(&mut t, &mut lines);
+        while t.redo() {}
+        assert_edits(&mut t, &mut lines);
+    }
+}
+
+#[test]
+fn history_bytes_random_inputs() {
+    const KEYS: &[Key] = &[
+        Key::Char('a'),
+        Key::Char(' '),
+        Key::Char('あ'),
+        Key::Char('🐶'),
+        Key::Backspace,
+        Key::Enter,
+        Key::Left,
+        Key::Right,
+        Key::Up,
+        Key::Down,
+        Key::Tab,
+        Key::Delete,
+        Key::Home,
+        Key::End,
+        Key::Cut,
+        Key::Paste,
+    ];
+
+    for seed in 1..=50 {
+        let mut rng = Rng(seed);
+        let init = ["hello", "", "world", "🐶🐱 あいう"];
+        let mut t = TextArea::from(init);
+        t.set_max_histories(1000);
+        let mut limited = t.clone();
+        let max_bytes = 200 + rng.next(5000);
+        limited.set_max_history_bytes(max_bytes);
+
+        for _ in 0..200 {
+            let input = Input {
+                key: KEYS[rng.next(KEYS.len())],
+                alt: rng.next(4) == 0,
+                shift: rng.next(4) == 0,
+                ..Default::default()
+            };
+            t.input(input.clone());
+            limited.input(input);
+            assert_eq!(t.lines(), limited.lines());
+            assert!(limited.history_size_bytes() <= max_bytes, "seed={seed}");
+        }
+        let last = t.lines().to_vec();
+
+        // Undo steps on the limited history are the same as the first steps on the unlimited history
+        let mut undone = vec![];
+        while t.undo() {
+            undone.push(t.lines().to_vec());
+        }
+        assert_eq!(t.lines(), init, "seed={seed}");
+        let mut steps = 0;
+        while limited.undo() {
+            assert_eq!(limited.lines(), undone[steps], "seed={seed}");
+            steps += 1;
+        }
+
+        while t.redo() {}
+        while limited.redo() {}
+        assert_eq!(t.lines(), last, "seed={seed}");
+        assert_eq!(limited.lines(), last, "seed={seed}");
+    }
+}
@@ -1,8 +1,6 @@
-use tui_textarea::{Input, Key, TextArea};
+use tui_textarea::{Input, InputResult, Key, TextArea};
 
-// Sanity test for checking textarea does not crash against all combination of inputs
-#[test]
-fn test_input_all_combinations_sanity() {
+fn all_input_combinations() -> Vec<Input> {
     use Key::*;
 
     fn push_all_modifiers_combination(inputs: &mut Vec<Input>, key: Key) {
@@ -25,6 +23,7 @@ fn test_input_all_combinations_sanity() {
     }
     for k in [
         Null,
+        Unidentified("Help"),
         Char('あ'),
         Char('🐶'),
         Backspace,
@@ -49,6 +48,13 @@ fn test_input_all_combinations_sanity() {
         push_all_modifiers_combination(&mut inputs, k);
     }
 
+    inputs
+}
+
+// Sanity test for checking textarea does not crash against all combination of inputs
+#[test]
+fn test_input_all_combinations_sanity() {
+    let inputs = all_input_combinations();
     let mut t = TextArea::from(["abc", "def", "ghi", "jkl", "mno", "pqr"]);
 
     for input in inputs {
@@ -61,6 +67,77 @@ fn test_input_all_combinations_sanity() {
     }
 }
 
+#[test]
+fn test_ignore_null_and_unidentified_keys() {
+    let mut t = TextArea::from(["abc"]);
+    t.start_macro_recording();
+    for key in [Key::Null, Key::Unidentified("Help")] {
+        for ctrl in [true, false] {
+            let input = Input {
+                key,
+                ctrl,
+                ..Default::default()
+            };
+            assert!(!t.input(input.clone()), "{input:?}");
+            assert!(!t.input_without_shortcuts(input.clone()), "{input:?}");
+            assert!(!t.single_line_input(input.clone()), "{input:?}");
+        }
+    }
+    assert_eq!(t.lines(), ["abc"]);
+    assert_eq!(t.cursor(), (0, 0));
+    assert_eq!(t.stop_macro_recording(), []);
+}
+
+#[test]
+fn test_read_only_ignores_modifications() {
+    let lines = ["abc", "def", "ghi", "jkl", "mno", "pqr"];
+    let mut t = TextArea::from(lines);
+    t.set_read_only(true);
+
+    for input in all_input_combinations() {
+        assert!(!t.input(input.clone()), "{input:?}");
+        assert!(!t.input_without_shortcuts(input.clone()), "{input:?}");
+        assert!(!t.single_line_input(input.clone()), "{input:?}");
+        assert_eq!(t.lines(), lines, "{input:?}");
+    }
+}
+
+#[test]
+fn test_read_only_allows_selection_and_copy() {
+    let mut t = TextArea::from(["abc", "def"]);
+    t.set_read_only(true);
+    let key = |key, ctrl, shift| Input { key, ctrl, alt: false, shift };
+
+    t.input(key(Key::Right, false, true));
+    t.input(key(Key::Right, false, true));
+    assert_eq!(t.selection_range(), Some(((0, 0), (0, 2))));
+
+    // Cut is handled as copy
+    assert!(!t.input(key(Key::Char('x'), true, false)));
+    assert_eq!(t.yank_text(), "ab");
+    assert_eq!(t.lines(), ["abc", "def"]);
+
+    t.input(key(Key::Char('a'), true, false));
+    t.input(key(Key::Char('c'), true, false));
+    assert_eq!(t.yank_text(), "abc\ndef");
+
+    // Toggling read-only keeps the undo history, and edits by methods are recorded as usual
+    t.cancel_selection();
+    t.set_read_only(false);
+    t.insert_str("x");
+    t.set_read_only(true);
+    assert!(!t.input(key(Key::Char('z'), true, false)));
+    t.insert_str("y");
+    t.set_read_only(false);
+    assert_eq!(t.lines(), ["abc", "defxy"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "defx"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "def"]);
+    assert!(!t.is_modified());
+    assert!(!t.undo());
+}
+
 #[test]
 fn test_insert_multi_code_unit_emoji() {
     let mut t = TextArea::default();
@@ -75,3 +152,62 @@ fn test_insert_multi_code_unit_emoji() {
     }
     assert_eq!(t.lines(), ["👨‍👩‍👧‍👦"]);
 }
+
+#[test]
+fn test_tab_with_selection_indents_lines() {
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.set_selection((0, 1), (1, 1));
+    assert!(t.input(Input {
+        key: Key::Tab,
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["    ab", "    cd"]);
+    assert!(t.selection_range().is_some());
+
+    assert!(t.input(Input {
+        key: Key::BackTab,
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["ab", "cd"]);
+    assert!(!t.input(Input {
+        key: Key::Tab,
+        shift: true,
+        ..Default::default()
+    }));
+
+    // Without selection, Tab inserts a tab
+    t.cancel_selection();
+    assert!(t.input(Input {
+        key: Key::Tab,
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["ab", "c   d"]);
+}
+
+#[test]
+fn test_single_line_keeps_one_line() {
+    let mut t = TextArea::from(["abc"]);
+    t.set_single_line(true);
+    t.set_yank_text("x\ny");
+
+    for input in all_input_combinations() {
+        let res = t.handle_input(input.clone());
+        if input.key == Key::Enter {
+            assert_eq!(res, InputResult::Submit, "{input:?}");
+        }
+        assert_eq!(t.lines().len(), 1, "{input:?}");
+        t.undo();
+        assert_eq!(t.lines().len(), 1, "{input:?}");
+        t.redo();
+        assert_eq!(t.lines().len(), 1, "{input:?}");
+    }
+
+    // `input` does not modify the text on Enter
+    let mut t = TextArea::from(["abc"]);
+    t.set_single_line(true);
+    assert!(!t.input(Input {
+        key: Key::Enter,
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["abc"]);
+}
@@ -1,6 +1,17 @@
 use std::cmp;
+use std::collections::HashMap;
 use std::fmt::Debug;
-use tui_textarea::{CursorMove, TextArea};
+use std::io;
+use std::iter;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use tui_textarea::TextAreaState;
+use tui_textarea::{
+    default_keymap, CursorMove, EditAction, Highlight, IndentKind, Input, InputResult, Key, LineEnding, SaveOptions,
+    Scrolling, Sign, SortOrder, TextArea, TextTransform, UndoGrouping,
+};
+#[cfg(feature = "search")]
+use tui_textarea::{SearchMode, SearchOptions, SearchScope};
 
 fn assert_undo_redo<T: Debug>(
     before_pos: (usize, usize),
@@ -1315,6 +1326,8 @@ fn test_delete_selection_on_delete_operations() {
         test_case!(delete_line_by_head()),
         test_case!(delete_word()),
         test_case!(delete_next_word()),
+        test_case!(delete_subword()),
+        test_case!(delete_next_subword()),
         test_case!(delete_str(3)),
     ];
 
@@ -1609,3 +1622,4472 @@ fn test_delete_next_word() {
     t.test((1, 0), (1, 0, &["word  ことば 🐶", "x"], " "));
     t.test((1, 2), (1, 2, t.0, ""));
 }
+
+#[test]
+fn test_delete_subword() {
+    let t = DeleteTester(&["getHTTP_v2 ことば", "x"], |t| t.delete_subword());
+    t.test((0, 0), (0, 0, t.0, ""));
+    t.test((0, 3), (0, 0, &["HTTP_v2 ことば", "x"], "get"));
+    t.test((0, 7), (0, 3, &["get_v2 ことば", "x"], "HTTP"));
+    t.test((0, 8), (0, 7, &["getHTTPv2 ことば", "x"], "_"));
+    t.test((0, 10), (0, 9, &["getHTTP_v ことば", "x"], "2"));
+    t.test((0, 14), (0, 11, &["getHTTP_v2 ", "x"], "ことば"));
+    t.test((1, 0), (0, 14, &["getHTTP_v2 ことばx"], ""));
+}
+
+#[test]
+fn test_delete_next_subword() {
+    let t = DeleteTester(&["getHTTP_v2 ことば", "x"], |t| t.delete_next_subword());
+    t.test((0, 0), (0, 0, &["HTTP_v2 ことば", "x"], "get"));
+    t.test((0, 3), (0, 3, &["get_v2 ことば", "x"], "HTTP"));
+    t.test((0, 7), (0, 7, &["getHTTPv2 ことば", "x"], "_"));
+    t.test((0, 8), (0, 8, &["getHTTP_2 ことば", "x"], "v"));
+    t.test((0, 11), (0, 11, &["getHTTP_v2 ", "x"], "ことば"));
+    t.test((0, 14), (0, 14, &["getHTTP_v2 ことばx"], ""));
+    t.test((1, 1), (1, 1, t.0, ""));
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_replace_next() {
+    let mut t = TextArea::from(["ab ab", "ab"]);
+    t.set_search_pattern("ab").unwrap();
+    t.move_cursor(CursorMove::Jump(0, 2));
+
+    // Match after the cursor is replaced first
+    assert!(t.replace_next("x"));
+    assert_eq!(t.lines(), ["ab x", "ab"]);
+    assert_eq!(t.cursor(), (1, 0));
+    assert!(t.replace_next("x"));
+    assert_eq!(t.lines(), ["ab x", "x"]);
+    assert_eq!(t.cursor(), (0, 0)); // Wrap around
+    assert!(t.replace_next("x"));
+    assert_eq!(t.lines(), ["x x", "x"]);
+    assert!(!t.replace_next("x"));
+
+    t.undo();
+    assert_eq!(t.lines(), ["ab x", "x"]);
+    t.redo();
+    assert_eq!(t.lines(), ["x x", "x"]);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_replace_next_in_match() {
+    let mut t = TextArea::from(["foo foo foo"]);
+    t.set_search_pattern("foo").unwrap();
+
+    // The match containing the cursor is replaced
+    t.move_cursor(CursorMove::Jump(0, 5));
+    assert!(t.replace_next("x"));
+    assert_eq!(t.lines(), ["foo x foo"]);
+    assert_eq!(t.cursor(), (0, 6));
+
+    // The match just before the cursor does not contain it
+    t.move_cursor(CursorMove::Jump(0, 3));
+    assert!(t.replace_next("y"));
+    assert_eq!(t.lines(), ["foo x y"]);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_replace_with_newline() {
+    let mut t = TextArea::from(["a,b,c", "d"]);
+    t.set_search_pattern(",").unwrap();
+    assert_eq!(t.replace_all("\n"), 2);
+    assert_eq!(t.lines(), ["a", "b", "c", "d"]);
+    t.undo();
+    assert_eq!(t.lines(), ["a,b,c", "d"]);
+    assert_eq!(t.cursor(), (0, 0));
+    t.redo();
+    assert_eq!(t.lines(), ["a", "b", "c", "d"]);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_replace_multiline_match() {
+    let mut t = TextArea::from(["foo", "bar foo", "bar", "foo"]);
+    t.set_search_pattern(r"(\w+)\nbar").unwrap();
+
+    // The match containing the cursor spans lines
+    t.move_cursor(CursorMove::Jump(0, 1));
+    assert!(t.replace_next("$1-bar"));
+    assert_eq!(t.lines(), ["foo-bar foo", "bar", "foo"]);
+    assert_eq!(t.cursor(), (0, 8));
+    assert!(t.replace_next("x\ny"));
+    assert_eq!(t.lines(), ["foo-bar x", "y", "foo"]);
+    assert!(!t.replace_next("x"));
+    t.undo();
+    assert_eq!(t.lines(), ["foo-bar foo", "bar", "foo"]);
+    t.undo();
+    assert_eq!(t.lines(), ["foo", "bar foo", "bar", "foo"]);
+
+    assert_eq!(t.replace_all(""), 2);
+    assert_eq!(t.lines(), [" ", "foo"]);
+    assert_eq!(t.cursor(), (0, 0));
+    t.undo();
+    assert_eq!(t.lines(), ["foo", "bar foo", "bar", "foo"]);
+    t.redo();
+    assert_eq!(t.lines(), [" ", "foo"]);
+
+    // Matches spanning lines out of the scope are not replaced
+    t.undo();
+    t.set_selection((1, 0), (3, 3));
+    t.set_search_scope(SearchScope::Selection);
+    assert_eq!(t.replace_all("x"), 1);
+    assert_eq!(t.lines(), ["foo", "bar x", "foo"]);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_replace_skip_empty_matches() {
+    let mut t = TextArea::from(["aaa", "", "ba"]);
+    t.set_search_pattern("a*").unwrap();
+    assert_eq!(t.replace_all("x"), 2);
+    assert_eq!(t.lines(), ["x", "", "bx"]);
+
+    t.set_search_pattern("^").unwrap();
+    assert!(!t.replace_next("x"));
+    assert_eq!(t.replace_all("x"), 0);
+    assert_eq!(t.lines(), ["x", "", "bx"]);
+}
+
+#[test]
+fn test_ignore_input_when_unfocused() {
+    let enter = Input {
+        key: Key::Enter,
+        ..Default::default()
+    };
+    let mut t = TextArea::default();
+    t.set_single_line(true);
+    t.set_focus(false);
+
+    // Inputs are handled regardless of the focus by default
+    assert!(t.input(Input {
+        key: Key::Char('a'),
+        ..Default::default()
+    }));
+    assert_eq!(t.handle_input(enter.clone()), InputResult::Submit);
+
+    t.set_ignore_input_when_unfocused(true);
+    assert_eq!(t.handle_input(enter.clone()), InputResult::Unchanged);
+    for input in [Key::Char('b'), Key::Backspace] {
+        let input = Input {
+            key: input,
+            ..Default::default()
+        };
+        assert!(!t.input(input.clone()));
+        assert!(!t.input_without_shortcuts(input.clone()));
+        assert!(!t.single_line_input(input));
+    }
+    assert_eq!(t.lines(), ["a"]);
+
+    // Editing methods still work
+    assert!(t.insert_str("b"));
+    t.set_focus(true);
+    assert!(t.input(Input {
+        key: Key::Char('c'),
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["abc"]);
+    assert_eq!(t.handle_input(enter), InputResult::Submit);
+}
+
+#[test]
+fn test_marks_follow_edits() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    t.move_cursor(CursorMove::Jump(1, 2));
+    t.set_mark('a');
+    t.move_cursor(CursorMove::Jump(2, 1));
+    t.set_mark('b');
+
+    // Join the lines before the marks
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert!(t.delete_newline());
+    assert_eq!(t.lines(), ["abcdef", "ghi"]);
+    assert_eq!(t.mark('a'), Some((0, 5)));
+    assert_eq!(t.mark('b'), Some((1, 1)));
+
+    // Marks are restored by undo and redo
+    assert!(t.undo());
+    assert_eq!(t.mark('a'), Some((1, 2)));
+    assert!(t.redo());
+    assert_eq!(t.mark('a'), Some((0, 5)));
+
+    // Replacing text around the mark moves it to the end of the inserted text
+    assert!(t.apply_edit((0, 4), (1, 0), "X"));
+    assert_eq!(t.lines(), ["abcdXghi"]);
+    assert_eq!(t.mark('a'), Some((0, 5)));
+    assert_eq!(t.mark('b'), Some((0, 6)));
+
+    // Deleting the whole line removes the marks
+    t.move_cursor(CursorMove::Jump(0, 5));
+    t.insert_str("\n");
+    assert_eq!(t.marks(), &HashMap::from([('a', (1, 0)), ('b', (1, 1))]));
+    assert!(t.delete_line(false));
+    assert_eq!(t.lines(), ["abcdX"]);
+    assert!(t.marks().is_empty());
+    assert!(!t.jump_to_mark('a'));
+}
+
+#[test]
+fn test_highlights_follow_edits() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    let h = |start, end| Highlight {
+        start,
+        end,
+        ..Default::default()
+    };
+    t.set_highlights(vec![h((0, 1), (1, 2)), h((2, 0), (2, 3))]);
+    let ranges = |t: &TextArea| t.highlights().iter().map(|h| (h.start, h.end)).collect::<Vec<_>>();
+
+    // Join the lines in the first highlight
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert!(t.delete_newline());
+    assert_eq!(ranges(&t), [((0, 1), (0, 5)), ((1, 0), (1, 3))]);
+
+    // Highlights are restored by undo
+    assert!(t.undo());
+    assert_eq!(ranges(&t), [((0, 1), (1, 2)), ((2, 0), (2, 3))]);
+
+    // Deleting the start of the highlight cuts it off
+    t.move_cursor(CursorMove::Jump(0, 0));
+    t.delete_next_char();
+    t.delete_next_char();
+    assert_eq!(ranges(&t), [((0, 0), (1, 2)), ((2, 0), (2, 3))]);
+
+    // Deleting the entire text of the highlight removes it
+    t.move_cursor(CursorMove::Jump(2, 0));
+    assert!(t.delete_line_by_end());
+    assert_eq!(ranges(&t), [((0, 0), (1, 2))]);
+    assert_eq!(t.highlights_at(1, 1).len(), 1);
+    assert!(t.highlights_at(1, 2).is_empty());
+}
+
+#[test]
+fn test_signs_follow_lines() {
+    let mut t = TextArea::from(["a", "b", "c", "d"]);
+    let sign = |s: &str| Sign::new(s, Default::default());
+    t.set_signs([(1, sign("1")), (2, sign("2")), (3, sign("3"))]);
+    let rows = |t: &TextArea| {
+        t.signs()
+            .iter()
+            .map(|(row, sign)| (*row, sign.symbol.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    // Editing inside a line does not move signs
+    t.insert_str("xy");
+    assert_eq!(rows(&t), [(1, "1".into()), (2, "2".into()), (3, "3".into())]);
+
+    // Deleting a line removes its sign and moves signs below it
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert!(t.delete_line(false));
+    assert_eq!(t.lines(), ["xya", "c", "d"]);
+    assert_eq!(rows(&t), [(1, "2".into()), (2, "3".into())]);
+
+    // Undo moves signs back with their lines but the removed sign is not restored
+    assert!(t.undo());
+    assert_eq!(rows(&t), [(2, "2".into()), (3, "3".into())]);
+
+    // When lines are joined, the sign of the upper line is kept
+    t.move_cursor(CursorMove::Jump(3, 0));
+    assert!(t.delete_newline());
+    assert_eq!(t.lines(), ["xya", "b", "cd"]);
+    assert_eq!(rows(&t), [(2, "2".into())]);
+}
+
+#[test]
+fn test_folds() {
+    let mut t = TextArea::from(["a", "b", "c", "d", "e", "f"]);
+    let ranges = |t: &TextArea| t.folds().iter().map(|f| (f.start, f.end)).collect::<Vec<_>>();
+
+    assert!(!t.fold(1, 1));
+    assert!(!t.fold(4, 6));
+    assert!(t.fold(3, 4));
+    assert!(t.fold(1, 2));
+    assert_eq!(ranges(&t), [(1, 2), (3, 4)]);
+
+    // Overlapping folds are replaced
+    assert!(t.fold(2, 3));
+    assert_eq!(ranges(&t), [(2, 3)]);
+    assert!(t.fold(0, 1));
+
+    // Up and Down skip hidden lines
+    t.move_cursor(CursorMove::Down);
+    assert_eq!(t.cursor(), (2, 0));
+    t.move_cursor(CursorMove::Down);
+    assert_eq!(t.cursor(), (4, 0));
+    t.move_cursor(CursorMove::Up);
+    assert_eq!(t.cursor(), (2, 0));
+    t.move_cursor(CursorMove::Up);
+    assert_eq!(t.cursor(), (0, 0));
+    assert_eq!(ranges(&t), [(0, 1), (2, 3)]);
+
+    // Other moves into a hidden line open the fold
+    t.move_cursor(CursorMove::Jump(3, 0));
+    assert_eq!(ranges(&t), [(0, 1)]);
+
+    // Folds follow inserted and deleted lines
+    t.move_cursor(CursorMove::Jump(2, 0));
+    t.insert_newline();
+    assert_eq!(ranges(&t), [(0, 1)]);
+    assert!(t.fold(4, 5));
+    t.move_cursor(CursorMove::Jump(2, 0));
+    assert!(t.delete_line(false));
+    assert_eq!(ranges(&t), [(0, 1), (3, 4)]);
+
+    // A fold is removed with its lines
+    t.move_cursor(CursorMove::Jump(3, 0));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(5, 0));
+    assert!(t.cut());
+    assert_eq!(t.lines(), ["a", "b", "c", "f"]);
+    assert_eq!(ranges(&t), [(0, 1)]);
+    t.move_cursor(CursorMove::Jump(0, 0));
+    t.select_all();
+    assert!(t.cut());
+    assert!(t.folds().is_empty());
+    assert!(t.undo());
+    assert!(t.folds().is_empty());
+}
+
+#[test]
+fn test_fold_by_indent() {
+    let mut t = TextArea::from(["a:", "\tb:", "      c", "", "\t  d", "e:", "  f"]);
+    let ranges = |t: &TextArea| t.folds().iter().map(|f| (f.start, f.end)).collect::<Vec<_>>();
+
+    // A tab is as wide as 4 spaces by default
+    assert_eq!(t.fold_by_indent(1), 1);
+    assert_eq!(ranges(&t), [(1, 4)]);
+
+    // A tab is wider than 6 spaces
+    t.set_tab_length(8);
+    assert_eq!(t.fold_by_indent(1), 1);
+    assert_eq!(ranges(&t), [(2, 4)]);
+    assert_eq!(t.fold_by_indent(0), 2);
+    assert_eq!(ranges(&t), [(0, 4), (5, 6)]);
+
+    // The cursor in a new fold moves to its first line and moves by the folds
+    t.unfold_all();
+    t.move_cursor(CursorMove::Jump(2, 1));
+    t.set_tab_length(4);
+    t.fold_by_indent(1);
+    assert_eq!(t.cursor(), (1, 1));
+    t.move_cursor(CursorMove::Down);
+    assert_eq!(t.cursor(), (5, 1));
+    t.unfold_all();
+    assert!(t.folds().is_empty());
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_fold_opened_by_search() {
+    let mut t = TextArea::from(["a", "b", "c"]);
+    assert!(t.fold(0, 2));
+    t.set_search_pattern("c").unwrap();
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (2, 0));
+    assert!(t.folds().is_empty());
+}
+
+#[test]
+fn test_snippet_stops() {
+    let mut t = TextArea::from(["x"]);
+    t.move_cursor(CursorMove::End);
+    assert!(t.insert_snippet("(${1:a}, $2)$0"));
+    assert_eq!(t.lines(), ["x(a, )"]);
+    assert!(t.is_snippet_active());
+
+    // Typing in the tab stop moves the following tab stops
+    t.insert_str("bc");
+    t.insert_char('d');
+    assert_eq!(t.lines(), ["x(bcd, )"]);
+    assert!(t.snippet_next());
+    assert_eq!(t.cursor(), (0, 7));
+    assert_eq!(t.selection_range(), None);
+    t.insert_str("e\nf");
+    assert!(t.snippet_prev());
+    assert_eq!(t.selection_range(), Some(((0, 2), (0, 5))));
+    assert!(t.snippet_next());
+    assert_eq!(t.selection_range(), Some(((0, 7), (1, 1))));
+    assert!(t.snippet_next());
+    assert_eq!(t.cursor(), (1, 2));
+    assert!(!t.snippet_next());
+    assert!(!t.is_snippet_active());
+    assert!(!t.snippet_prev());
+
+    // The typed text is undone normally and the insertion is undone at once
+    while t.lines() != ["x(a, )"] {
+        assert!(t.undo());
+    }
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["x"]);
+    assert!(!t.undo());
+}
+
+#[test]
+fn test_snippet_cancelled_by_edit() {
+    let mut t = TextArea::from(["abc"]);
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+
+    // The snippet replaces the selection in one undo unit
+    assert!(t.insert_snippet("${1:x}-$2"));
+    assert_eq!(t.lines(), ["x-"]);
+    assert!(t.is_snippet_active());
+    t.move_cursor(CursorMove::Head);
+    t.insert_char('y');
+    assert!(t.is_snippet_active());
+
+    // An edit outside the tab stop ends visiting tab stops
+    t.move_cursor(CursorMove::End);
+    t.insert_char('z');
+    assert!(!t.is_snippet_active());
+    assert!(!t.snippet_next());
+
+    assert!(t.undo());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["x-"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+
+    // Undoing the insertion also ends visiting tab stops
+    assert!(t.insert_snippet("$1 $2"));
+    assert!(t.undo());
+    assert!(!t.is_snippet_active());
+}
+
+#[test]
+fn test_word_before_cursor() {
+    let mut t = TextArea::from(["ab  c->dé", "日本語", ""]);
+    let tests = [
+        // (cursor, prefix, start)
+        ((0, 0), "", 0),
+        ((0, 1), "a", 0),
+        ((0, 2), "ab", 0),
+        ((0, 3), "", 3),
+        ((0, 5), "c", 4),
+        ((0, 6), "", 6), // Inside punctuations
+        ((0, 7), "", 7),
+        ((0, 9), "dé", 7),
+        ((1, 2), "日本", 0),
+        ((2, 0), "", 0),
+    ];
+    for ((row, col), prefix, start) in tests {
+        t.move_cursor(CursorMove::Jump(row, col));
+        assert_eq!(t.word_before_cursor(), (prefix.into(), start), "{:?}", (row, col));
+    }
+}
+
+#[test]
+fn test_complete_word() {
+    let mut t = TextArea::from(["x.fo y"]);
+    t.move_cursor(CursorMove::Jump(0, 4));
+    t.insert_char('o');
+    assert!(t.complete_word("foobar"));
+    assert_eq!(t.lines(), ["x.foobar y"]);
+    assert_eq!(t.cursor(), (0, 8));
+
+    // The completion is one undo unit separated from typed characters
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["x.foo y"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["x.fo y"]);
+
+    // Nothing to replace at the head of line
+    t.move_cursor(CursorMove::Head);
+    assert!(!t.complete_word(""));
+    assert!(t.complete_word("z"));
+    assert_eq!(t.lines(), ["zx.fo y"]);
+    assert_eq!(t.cursor(), (0, 1));
+
+    // The selection is cancelled instead of being replaced
+    t.move_cursor(CursorMove::Jump(0, 6));
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    assert!(t.complete_word("yes"));
+    assert_eq!(t.lines(), ["zx.fo yes"]);
+    assert!(t.selection_range().is_none());
+}
+
+#[test]
+fn test_ghost_text() {
+    let mut t = TextArea::from(["let x"]);
+    t.move_cursor(CursorMove::End);
+    t.insert_str(" = ");
+
+    t.set_ghost_text(Some("vec![\n    1,\n]".to_string()));
+    assert!(t.accept_ghost_text());
+    assert_eq!(t.lines(), ["let x = vec![", "    1,", "]"]);
+    assert_eq!(t.cursor(), (2, 1));
+    assert_eq!(t.ghost_text(), None);
+    assert!(!t.accept_ghost_text());
+
+    // Accepting is one undo unit separated from typed text
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["let x = "]);
+
+    // Edits and cursor moves remove the ghost text
+    t.set_ghost_text(Some("1;".to_string()));
+    t.insert_char('2');
+    assert_eq!(t.ghost_text(), None);
+    t.set_ghost_text(Some(";".to_string()));
+    t.move_cursor(CursorMove::Head);
+    assert_eq!(t.ghost_text(), None);
+
+    // A cursor move which does not move the cursor keeps it
+    t.set_ghost_text(Some("x".to_string()));
+    t.move_cursor(CursorMove::Head);
+    assert_eq!(t.ghost_text(), Some("x"));
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_records_jump() {
+    let mut t = TextArea::from(["foo", "bar", "foo"]);
+    t.set_search_pattern("foo").unwrap();
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (2, 0));
+    assert!(t.search_forward(true)); // The cursor does not move
+    assert_eq!(t.jump_list(), [(0, 0)]);
+
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (0, 0));
+    assert!(t.search_back(false));
+    assert_eq!(t.cursor(), (2, 0));
+    // Positions after the current one were forgotten
+    assert_eq!(t.jump_list(), [(0, 0)]);
+    assert!(!t.jump_forward());
+}
+
+#[test]
+fn test_internal_moves_not_recorded_as_jumps() {
+    let lines = ["foo", "bar", "baz"];
+    let mut t = TextArea::builder().lines(lines).cursor((2, 1)).build();
+    assert_eq!(t.cursor(), (2, 1));
+    assert_eq!(t.jump_list(), [(0, 0); 0]);
+
+    t.move_cursor(CursorMove::Top);
+    t.select_all();
+    assert_eq!(t.cursor(), (2, 3));
+    assert_eq!(t.jump_list(), [(2, 1)]);
+
+    // Explicit jumps are recorded
+    t.cancel_selection();
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert_eq!(t.jump_list(), [(2, 1), (2, 3)]);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_replace_all_without_history() {
+    let mut t = TextArea::from(["foo bar foo"]);
+    t.set_max_histories(0);
+    t.set_search_pattern("foo").unwrap();
+    assert_eq!(t.replace_all(""), 2);
+    assert_eq!(t.lines(), [" bar "]);
+    assert!(!t.undo());
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_multiline() {
+    let mut t = TextArea::from(["foo", "bar baz", "foo", "bar"]);
+    t.set_search_pattern("foo\\nbar").unwrap();
+    assert_eq!(t.search_matches(), [((0, 0), (1, 3)), ((2, 0), (3, 3))]);
+
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (2, 0));
+    assert!(t.search_forward(true));
+    assert_eq!(t.cursor(), (2, 0));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (0, 0)); // Wrap around
+    assert!(t.search_back(false));
+    assert_eq!(t.cursor(), (2, 0)); // Wrap around
+    t.move_cursor(CursorMove::Down);
+    assert!(t.search_back(false));
+    assert_eq!(t.cursor(), (2, 0));
+
+    // `s` flag makes `.` match newlines
+    t.set_search_pattern("(?s)baz.*?bar").unwrap();
+    assert_eq!(t.search_matches(), [((1, 4), (3, 3))]);
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (1, 4));
+
+    // Without the flag, the pattern matches within each line
+    t.set_search_pattern("baz.*bar").unwrap();
+    assert_eq!(t.search_matches(), []);
+    assert!(!t.search_forward(false));
+
+    // Columns are counted in characters
+    let mut t = TextArea::from(["🐶あ", "い🐱"]);
+    t.set_search_pattern("あ\nい").unwrap();
+    assert_eq!(t.search_matches(), [((0, 1), (1, 1))]);
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (0, 1));
+
+    // Empty matches are not included
+    t.set_search_pattern("(?s)x*").unwrap();
+    assert_eq!(t.search_matches(), []);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_match_count() {
+    let mut t: TextArea = std::iter::repeat("a b").take(1000).collect();
+    assert_eq!(t.search_match_count(None), 0);
+    t.set_search_pattern("\\w").unwrap();
+    assert_eq!(t.search_match_count(None), 2000);
+    assert_eq!(t.search_match_count(Some(100)), 100);
+    t.set_search_pattern("b\\na").unwrap();
+    assert_eq!(t.search_match_count(None), 999);
+    assert_eq!(t.search_match_count(Some(1000)), 999);
+
+    // Multi-line search moves the cursor across many lines
+    t.move_cursor(CursorMove::Jump(500, 0));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (500, 2));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (501, 2));
+    assert!(t.search_back(false));
+    assert_eq!(t.cursor(), (500, 2));
+    t.move_cursor(CursorMove::Jump(999, 2));
+    assert!(t.search_forward(true));
+    assert_eq!(t.cursor(), (0, 2)); // Wrap around
+    t.move_cursor(CursorMove::Jump(0, 0));
+    assert!(t.search_back(false));
+    assert_eq!(t.cursor(), (998, 2)); // Wrap around
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_options() {
+    let mut t = TextArea::from(["Foo foo", "foobar FOO"]);
+    t.set_search_pattern("foo").unwrap();
+    assert_eq!(t.search_matches().len(), 2);
+
+    // Changing options updates matches of the current pattern
+    let options = SearchOptions {
+        case_insensitive: true,
+        whole_word: true,
+        ..Default::default()
+    };
+    t.set_search_options(options);
+    assert_eq!(t.search_options(), options);
+    assert_eq!(
+        t.search_matches(),
+        [((0, 0), (0, 3)), ((0, 4), (0, 7)), ((1, 7), (1, 10))]
+    );
+    assert_eq!(t.search_pattern().unwrap().as_str(), r"\b(?:foo)\b");
+
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (0, 4));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (1, 7));
+    assert!(t.search_back(false));
+    assert_eq!(t.cursor(), (0, 4));
+
+    // Pattern with word boundaries is not changed by whole-word option
+    t.set_search_pattern(r"\bfoo\b").unwrap();
+    assert_eq!(t.search_pattern().unwrap().as_str(), r"(?:\bfoo\b)");
+    assert_eq!(t.search_matches().len(), 3);
+
+    // Replacing text honors the options
+    t.set_search_options(SearchOptions {
+        smart_case: true,
+        ..Default::default()
+    });
+    t.set_search_pattern("foo").unwrap();
+    assert_eq!(t.replace_all("x"), 4);
+    assert_eq!(t.lines(), ["x x", "xbar x"]);
+    t.undo();
+    t.set_search_pattern("Foo").unwrap();
+    assert_eq!(t.replace_all("x"), 1);
+    assert_eq!(t.lines(), ["x foo", "foobar FOO"]);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_history() {
+    let mut t = TextArea::from(["foo bar", "baz foo"]);
+    assert_eq!(t.max_search_histories(), 50);
+    assert_eq!(t.search_history(), [""; 0]);
+
+    t.set_search_pattern("baz").unwrap();
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (1, 0));
+    t.set_search_pattern("").unwrap();
+    t.set_search_pattern("foo").unwrap();
+    t.set_search_pattern("").unwrap();
+    assert_eq!(t.search_history(), ["foo", "baz"]);
+
+    // Recalled pattern is applied
+    assert_eq!(t.search_history_prev(), Some("foo"));
+    assert_eq!(t.search_history_prev(), Some("baz"));
+    assert_eq!(t.search_pattern().unwrap().as_str(), "baz");
+    t.move_cursor(CursorMove::Top);
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (1, 0));
+    assert_eq!(t.search_history_next(), Some("foo"));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (1, 4));
+    assert_eq!(t.search_history_next(), None);
+    assert_eq!(t.search_pattern().unwrap().as_str(), "foo");
+    assert_eq!(t.search_history(), ["foo", "baz"]);
+
+    // Search options are applied to the recalled pattern
+    t.set_search_options(SearchOptions {
+        whole_word: true,
+        ..Default::default()
+    });
+    assert_eq!(t.search_history_prev(), Some("foo"));
+    assert_eq!(t.search_pattern().unwrap().as_str(), r"\b(?:foo)\b");
+
+    // Distinct patterns set one after another are all recorded
+    t.set_search_pattern("bar").unwrap();
+    t.set_search_pattern("ba").unwrap();
+    t.set_search_pattern("ba").unwrap();
+    assert_eq!(t.search_history(), ["ba", "bar", "foo", "baz"]);
+
+    t.set_max_search_histories(1);
+    assert_eq!(t.search_history(), ["ba"]);
+    t.set_max_search_histories(0);
+    t.set_search_pattern("bar").unwrap();
+    assert_eq!(t.search_history(), [""; 0]);
+    assert_eq!(t.search_history_prev(), None);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_literal_mode() {
+    let mut t = TextArea::from(["v1.5 (beta)", "v105 beta", "a\\nb", "c"]);
+    assert_eq!(t.search_mode(), SearchMode::Regex);
+    t.set_search_mode(SearchMode::Literal);
+
+    t.set_search_pattern("1.5 (beta)").unwrap();
+    assert_eq!(t.search_matches(), [((0, 1), (0, 11))]);
+    assert_eq!(t.search_pattern().unwrap().as_str(), r"1\.5 \(beta\)");
+
+    // `\n` in the query is not a newline
+    t.set_search_pattern("a\\nb").unwrap();
+    assert_eq!(t.search_matches(), [((2, 0), (2, 4))]);
+    // A newline in the query matches across lines
+    t.set_search_pattern("b\nc").unwrap();
+    assert_eq!(t.search_matches(), [((2, 3), (3, 1))]);
+
+    // Options are applied to the literal query
+    t.set_search_options(SearchOptions {
+        case_insensitive: true,
+        whole_word: true,
+        ..Default::default()
+    });
+    t.set_search_pattern("BETA").unwrap();
+    assert_eq!(t.search_matches(), [((0, 6), (0, 10)), ((1, 5), (1, 9))]);
+    t.set_search_options(SearchOptions::default());
+
+    // The search is stopped when the query is not valid in the new mode
+    t.set_search_pattern("v1.5 (").unwrap();
+    assert_eq!(t.search_match_count(None), 1);
+    t.set_search_mode(SearchMode::Regex);
+    assert!(t.search_pattern().is_none());
+    assert!(!t.search_forward(false));
+
+    // The query is rebuilt when it is valid in the new mode
+    t.set_search_pattern("v1.5").unwrap();
+    assert_eq!(t.search_match_count(None), 2);
+    t.set_search_mode(SearchMode::Literal);
+    assert_eq!(t.search_match_count(None), 1);
+
+    // Replacement text is expanded as usual
+    t.set_search_pattern("(beta)").unwrap();
+    assert_eq!(t.replace_all("$$"), 1);
+    assert_eq!(t.lines()[0], "v1.5 $");
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_fuzzy_mode() {
+    let mut t = TextArea::from(["fxxxxb", "nothing", "foo_bar", "FooBar", "fb"]);
+    t.set_search_mode(SearchMode::Fuzzy);
+    t.set_search_pattern("fb").unwrap();
+    assert_eq!(t.search_match_count(None), 3);
+    assert_eq!(t.search_match_count(Some(2)), 2);
+    // Matches are spans from the first matched character to the last one in the text order
+    assert_eq!(
+        t.search_matches(),
+        [((0, 0), (0, 6)), ((2, 0), (2, 5)), ((4, 0), (4, 2))]
+    );
+
+    let ranked = t.search_ranked_matches(10);
+    let rows: Vec<_> = ranked.iter().map(|(row, _, _)| *row).collect();
+    assert_eq!(rows, [4, 2, 0]);
+    assert!(ranked.windows(2).all(|w| w[0].1 > w[1].1));
+    assert_eq!(ranked[1].2, [0, 4]);
+    assert_eq!(t.search_ranked_matches(1).len(), 1);
+    assert_eq!(t.search_ranked_matches(0), []);
+
+    // Smart case
+    t.set_search_options(SearchOptions {
+        smart_case: true,
+        ..Default::default()
+    });
+    let rows: Vec<_> = t.search_ranked_matches(10).into_iter().map(|(row, _, _)| row).collect();
+    assert_eq!(rows, [4, 2, 3, 0]);
+    t.set_search_pattern("FB").unwrap();
+    let rows: Vec<_> = t.search_ranked_matches(10).into_iter().map(|(row, _, _)| row).collect();
+    assert_eq!(rows, [3]);
+    t.set_search_options(SearchOptions::default());
+    t.set_search_pattern("fb").unwrap();
+
+    // Lines are visited in descending order of the scores
+    let tests = [
+        // (cursor, match_cursor, cursor after search_forward, cursor after search_back)
+        ((1, 0), false, (4, 0), (0, 0)),
+        ((4, 1), false, (2, 0), (0, 0)),
+        ((2, 3), false, (0, 0), (4, 0)),
+        ((0, 3), false, (4, 0), (2, 0)),
+        ((2, 3), true, (2, 0), (2, 0)),
+        ((1, 0), true, (4, 0), (0, 0)),
+    ];
+    for test in tests {
+        let (pos, match_cursor, forward, back) = test;
+        t.move_cursor(CursorMove::Jump(pos.0 as _, pos.1 as _));
+        assert!(t.search_forward(match_cursor), "{test:?}");
+        assert_eq!(t.cursor(), forward, "{test:?}");
+        t.move_cursor(CursorMove::Jump(pos.0 as _, pos.1 as _));
+        assert!(t.search_back(match_cursor), "{test:?}");
+        assert_eq!(t.cursor(), back, "{test:?}");
+    }
+
+    // Current match is the match of the line
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert!(t.search_forward(false));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (2, 0));
+    assert_eq!(t.current_search_match(), Some(1));
+
+    // No ranked match in other modes
+    t.set_search_mode(SearchMode::Regex);
+    assert_eq!(t.search_ranked_matches(10), []);
+    assert_eq!(t.search_pattern().unwrap().as_str(), "fb");
+    assert_eq!(t.search_match_count(None), 1);
+
+    t.set_search_mode(SearchMode::Fuzzy);
+    t.set_search_pattern("xyz").unwrap();
+    assert!(!t.search_forward(false));
+    assert!(!t.search_back(false));
+    assert_eq!(t.search_ranked_matches(10), []);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_scope() {
+    let mut t = TextArea::from(["ab ab", "ab ab", "ab ab", "ab ab"]);
+    assert_eq!(t.search_scope(), SearchScope::Document);
+
+    // Nothing is selected
+    t.set_search_scope(SearchScope::Selection);
+    assert_eq!(t.search_scope(), SearchScope::Document);
+
+    // The scope survives cancelling the selection
+    t.set_selection((0, 3), (2, 2));
+    t.set_search_scope(SearchScope::Selection);
+    t.cancel_selection();
+    t.move_cursor(CursorMove::Bottom);
+    assert_eq!(t.search_scope(), SearchScope::Selection);
+    assert_eq!(t.search_scope_range(), Some(((0, 3), (2, 2))));
+
+    t.set_search_pattern("ab").unwrap();
+    assert_eq!(
+        t.search_matches(),
+        [((0, 3), (0, 5)), ((1, 0), (1, 2)), ((1, 3), (1, 5)), ((2, 0), (2, 2))],
+    );
+    assert_eq!(t.search_match_count(None), 4);
+    assert_eq!(t.search_match_count(Some(2)), 2);
+
+    let tests = [
+        // (cursor, match_cursor, cursor after search_forward, cursor after search_back)
+        ((3, 0), false, (0, 3), (2, 0)),
+        ((0, 0), false, (0, 3), (2, 0)),
+        ((0, 3), false, (1, 0), (2, 0)),
+        ((1, 3), false, (2, 0), (1, 0)),
+        ((1, 3), true, (1, 3), (1, 3)),
+        ((2, 3), true, (0, 3), (2, 0)),
+    ];
+    for test in tests {
+        let (pos, match_cursor, forward, back) = test;
+        t.move_cursor(CursorMove::Jump(pos.0 as _, pos.1 as _));
+        assert!(t.search_forward(match_cursor), "{test:?}");
+        assert_eq!(t.cursor(), forward, "{test:?}");
+        t.move_cursor(CursorMove::Jump(pos.0 as _, pos.1 as _));
+        assert!(t.search_back(match_cursor), "{test:?}");
+        assert_eq!(t.cursor(), back, "{test:?}");
+    }
+    t.move_cursor(CursorMove::Jump(0, 3));
+    assert!(t.search_forward(false));
+    assert_eq!(t.current_search_match(), Some(1));
+
+    // Multi-line matches must be entirely in the scope
+    t.set_search_pattern("ab\\nab").unwrap();
+    assert_eq!(t.search_matches(), [((0, 3), (1, 2)), ((1, 3), (2, 2))]);
+    t.move_cursor(CursorMove::Jump(1, 3));
+    assert!(t.search_forward(false));
+    assert_eq!(t.cursor(), (0, 3));
+
+    // Fuzzy matches are searched in the part of the line in the scope
+    t.set_search_mode(SearchMode::Fuzzy);
+    t.set_search_pattern("ab").unwrap();
+    assert_eq!(
+        t.search_matches(),
+        [((0, 3), (0, 5)), ((1, 0), (1, 2)), ((2, 0), (2, 2))],
+    );
+    assert_eq!(t.search_match_count(None), 3);
+    t.set_search_mode(SearchMode::Regex);
+
+    // The scope follows edits. Text inserted at the end of the scope is included
+    t.move_cursor(CursorMove::Jump(0, 0));
+    t.insert_char('x');
+    t.move_cursor(CursorMove::Jump(2, 2));
+    t.insert_str(" ab");
+    assert_eq!(t.search_scope_range(), Some(((0, 4), (2, 5))));
+    assert_eq!(t.search_match_count(None), 5);
+
+    t.move_cursor(CursorMove::Jump(0, 0));
+    assert!(t.replace_next("Z"));
+    assert_eq!(t.lines(), ["xab Z", "ab ab", "ab ab ab", "ab ab"]);
+    assert_eq!(t.cursor(), (1, 0));
+    assert_eq!(t.replace_all("Y"), 4);
+    assert_eq!(t.lines(), ["xab Z", "Y Y", "Y Y ab", "ab ab"]);
+    assert_eq!(t.search_scope_range(), Some(((0, 4), (2, 3))));
+    assert_eq!(t.replace_all("Y"), 0);
+
+    t.set_search_scope(SearchScope::Document);
+    assert_eq!(t.search_scope_range(), None);
+    assert_eq!(t.search_match_count(None), 4);
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_preview() {
+    let mut t = TextArea::from(["foo bar", "bar", "baz", "bar foo"]);
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(!t.search_commit());
+
+    t.set_search_pattern("ba.").unwrap();
+    // The first preview includes the match at the cursor
+    assert_eq!(t.search_preview(true), Some((2, 0)));
+    assert_eq!(t.current_search_match(), Some(2));
+    assert_eq!(t.search_preview(true), Some((3, 0)));
+    assert_eq!(t.search_preview(true), Some((0, 4))); // Wrap around
+    assert_eq!(t.search_preview(false), Some((3, 0)));
+    assert_eq!(t.cursor(), (1, 1));
+    assert_eq!(t.previewed_search_match(), Some((3, 0)));
+
+    // Changing the pattern previews from the cursor again
+    t.set_search_pattern("bar").unwrap();
+    assert_eq!(t.previewed_search_match(), None);
+    assert_eq!(t.search_preview(false), Some((1, 0)));
+    t.set_search_pattern("xyz").unwrap();
+    assert_eq!(t.search_preview(true), None);
+    assert_eq!(t.current_search_match(), None);
+    assert!(!t.search_commit());
+    assert_eq!(t.cursor(), (1, 1));
+
+    // Cancel keeps the cursor
+    t.set_search_pattern("foo").unwrap();
+    assert_eq!(t.search_preview(true), Some((3, 4)));
+    t.search_cancel();
+    assert_eq!(t.cursor(), (1, 1));
+    assert_eq!(t.previewed_search_match(), None);
+    assert_eq!(t.current_search_match(), None);
+
+    // Commit moves the cursor and records the jump
+    assert_eq!(t.search_preview(true), Some((3, 4)));
+    assert_eq!(t.search_preview(true), Some((0, 0)));
+    assert!(t.search_commit());
+    assert_eq!(t.cursor(), (0, 0));
+    assert_eq!(t.current_search_match(), Some(0));
+    assert_eq!(t.previewed_search_match(), None);
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (1, 1));
+
+    // Moving the cursor or editing the text finishes the preview
+    assert_eq!(t.search_preview(true), Some((3, 4)));
+    t.move_cursor(CursorMove::Bottom);
+    assert_eq!(t.previewed_search_match(), None);
+    assert!(!t.search_commit());
+    assert_eq!(t.cursor(), (3, 1));
+    assert_eq!(t.search_preview(true), Some((3, 4)));
+    t.move_cursor(CursorMove::End);
+    t.delete_line_by_head();
+    assert_eq!(t.previewed_search_match(), None);
+    assert!(!t.search_commit());
+    assert_eq!(t.cursor(), (3, 0));
+
+    // The next preview starts from the cursor
+    assert_eq!(t.search_preview(true), Some((0, 0)));
+}
+
+#[test]
+fn test_set_selection() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+
+    // Backward selection
+    t.set_selection((2, 1), (0, 1));
+    assert_eq!(t.cursor(), (0, 1));
+    assert_eq!(t.selection_range(), Some(((0, 1), (2, 1))));
+    t.copy();
+    assert!(t.delete_next_char());
+    assert_eq!(t.lines(), ["ahi"]);
+    assert_eq!(t.yank_text(), "bc\ndef\ng");
+    assert_eq!(t.selection_range(), None);
+
+    // Out-of-range positions are clamped
+    t.set_selection((10, 10), (0, 0));
+    assert_eq!(t.selection_range(), Some(((0, 0), (0, 3))));
+    t.cancel_selection();
+    assert_eq!(t.selection_range(), None);
+
+    // Empty selection
+    t.set_selection((0, 1), (0, 1));
+    assert_eq!(t.selection_range(), Some(((0, 1), (0, 1))));
+    assert!(!t.delete_str(0));
+}
+
+#[test]
+fn test_max_chars() {
+    let mut t = TextArea::default();
+    t.set_max_chars(Some(6));
+
+    // Multi-byte characters are counted as one character
+    assert!(t.insert_str("あい🐶"));
+    assert_eq!(t.char_count(), 3);
+    assert!(t.insert_newline());
+    assert!(t.insert_char('é'));
+    assert_eq!(t.char_count(), 5);
+
+    // Text exceeding the limit is truncated
+    assert!(t.insert_str("うえお"));
+    assert_eq!(t.lines(), ["あい🐶", "éう"]);
+    assert_eq!(t.char_count(), 6);
+
+    assert!(!t.insert_char('x'));
+    assert!(!t.insert_newline());
+    assert!(!t.insert_str("x"));
+    assert!(!t.insert_tab());
+    t.set_yank_text("x\ny");
+    assert!(!t.paste());
+    assert_eq!(t.lines(), ["あい🐶", "éう"]);
+
+    // Deleting selection makes room for inserted text
+    t.set_selection((0, 1), (0, 3));
+    assert!(t.insert_str("🐱\n🐭"));
+    assert_eq!(t.lines(), ["あ🐱", "", "éう"]);
+    assert_eq!(t.char_count(), 6);
+
+    t.set_max_chars(None);
+    assert!(t.insert_char('x'));
+    assert_eq!(t.char_count(), 7);
+}
+
+#[test]
+fn test_max_chars_paste_truncated() {
+    let tests = [
+        // (max chars, yank text, lines after)
+        (4, "あい\nうえ", &["aあい", ""][..]),
+        (5, "あい\nうえ", &["aあい", "う"][..]),
+        (3, "あい\nうえ", &["aあい"][..]),
+        (2, "あい\nうえ", &["aあ"][..]),
+        (3, "🐶\n\n🐱", &["a🐶", ""][..]),
+    ];
+
+    for test in tests {
+        let (max, yank, after) = test;
+        let mut t = TextArea::from(["a"]);
+        t.move_cursor(CursorMove::End);
+        t.set_max_chars(Some(max));
+        t.set_yank_text(yank);
+        assert!(t.paste(), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert!(t.char_count() <= max, "{test:?}");
+    }
+}
+
+#[test]
+fn test_is_modified() {
+    let mut t = TextArea::from(["abc"]);
+    assert!(!t.is_modified());
+
+    // Typing after saving is not merged into the undo unit before saving
+    t.move_cursor(CursorMove::End);
+    t.insert_char('d');
+    t.mark_saved();
+    t.insert_char('e');
+    assert!(t.is_modified());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abcd"]);
+    assert!(!t.is_modified());
+    assert!(t.undo());
+    assert!(t.is_modified());
+    assert!(t.redo());
+    assert!(!t.is_modified());
+
+    // The saved point cannot be reached after undoing and making another edit
+    assert!(t.undo());
+    t.insert_char('x');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+    assert!(t.is_modified());
+    t.mark_saved();
+    assert!(!t.is_modified());
+
+    // Changing the max histories keeps the state
+    t.set_max_histories(10);
+    assert!(!t.is_modified());
+
+    // The saved point is forgotten with old edits
+    t.set_max_histories(2);
+    t.set_undo_grouping(UndoGrouping::None);
+    for c in ['1', '2', '3'] {
+        t.insert_char(c);
+    }
+    while t.undo() {}
+    assert_eq!(t.lines(), ["abc1"]);
+    assert!(t.is_modified());
+
+    // Edits are detected without undo history
+    let mut t = TextArea::from(["abc"]);
+    t.set_max_histories(0);
+    let count = t.edit_count();
+    t.insert_char('x');
+    assert!(t.is_modified());
+    assert!(t.edit_count() > count);
+    t.mark_saved();
+    assert!(!t.is_modified());
+    t.delete_char();
+    assert!(t.is_modified());
+
+    // Undo and redo are counted as modifications
+    let mut t = TextArea::default();
+    t.insert_str("abc");
+    let count = t.edit_count();
+    t.undo();
+    assert_eq!(t.edit_count(), count + 1);
+    t.redo();
+    assert_eq!(t.edit_count(), count + 2);
+    assert!(!t.redo());
+    assert_eq!(t.edit_count(), count + 2);
+}
+
+#[test]
+fn test_clone_eq_display() {
+    let mut t = TextArea::from(["hello", "world"]);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.insert_str("!");
+    let before = t.clone();
+
+    let mut preview = t.clone();
+    assert_eq!(preview.cursor(), t.cursor());
+    assert_eq!(preview, t);
+    preview.move_cursor(CursorMove::Top);
+    assert_eq!(preview, t, "cursor is not compared");
+
+    preview.insert_str("abc");
+    assert_ne!(preview, t);
+    assert_eq!(t, before, "original is not affected by editing clone");
+    assert!(preview.undo());
+    assert_eq!(preview, t);
+    assert!(preview.undo(), "undo history is cloned");
+    while preview.undo() {}
+    assert_eq!(preview.lines(), ["hello", "world"]);
+    assert_eq!(t.lines(), ["he!orld"]);
+
+    assert_eq!(t.text(), "he!orld");
+    let mut t = TextArea::from(["a", "b", ""]);
+    assert_eq!(t.text(), "a\nb\n");
+    t.set_line_ending(LineEnding::CrLf);
+    assert_eq!(t.text(), "a\r\nb\r\n");
+    assert_eq!(t.to_string(), t.text());
+}
+
+#[test]
+fn test_line_accessors() {
+    let mut t = TextArea::from(["abc", "\tあい", ""]);
+    assert_eq!(t.len_lines(), 3);
+    assert_eq!(t.len_chars(), 8);
+    assert_eq!(t.iter_lines().len(), 3);
+    assert!(t.iter_lines().eq(t.lines().iter().map(String::as_str)));
+    assert_eq!(t.line(0).as_deref(), Some("abc"));
+    assert_eq!(t.line(3), None);
+    assert_eq!(t.line_len(1), Some(3));
+    assert_eq!(t.line_len(2), Some(0));
+    assert_eq!(t.line_len(3), None);
+    assert_eq!(t.line_width(1), Some(8));
+    assert_eq!(t.line_width(3), None);
+
+    t.set_tab_length(2);
+    assert_eq!(t.line_width(1), Some(6));
+    t.set_mask_char('*');
+    assert_eq!(t.line_width(1), Some(3));
+
+    t.move_cursor(CursorMove::Bottom);
+    t.insert_str("x\ny");
+    assert_eq!(t.len_lines(), 4);
+    assert_eq!(t.iter_lines().rev().collect::<Vec<_>>(), ["y", "x", "\tあい", "abc"]);
+}
+
+#[test]
+fn test_offset_conversions() {
+    // Check conversions at every position against offsets calculated from the whole text
+    fn check(t: &TextArea<'_>, context: &str) {
+        let (mut c, mut b, mut u) = (0, 0, 0);
+        for (row, line) in t.iter_lines().enumerate() {
+            for (col, ch) in line.chars().chain(iter::once('\n')).enumerate() {
+                let pos = (row, col);
+                assert_eq!(t.position_to_char_idx(pos), c, "char {pos:?} {context}");
+                assert_eq!(t.position_to_byte_idx(pos), b, "byte {pos:?} {context}");
+                assert_eq!(t.position_to_utf16_idx(pos), u, "utf16 {pos:?} {context}");
+                assert_eq!(t.char_idx_to_position(c), pos, "char {c} {context}");
+                assert_eq!(t.byte_idx_to_position(b), pos, "byte {b} {context}");
+                assert_eq!(t.utf16_idx_to_position(u), pos, "utf16 {u} {context}");
+                c += 1;
+                b += ch.len_utf8();
+                u += ch.len_utf16();
+            }
+        }
+        assert_eq!(c - 1, t.len_chars(), "{context}");
+        let last = t.len_lines() - 1;
+        let end = (last, t.line_len(last).unwrap());
+        assert_eq!(t.char_idx_to_position(c + 10), end, "{context}");
+    }
+
+    let mut t = TextArea::from(["aあ😀", "", "b"]);
+    check(&t, "initial");
+
+    type Op = fn(&mut TextArea);
+    let ops: &[(&str, Op)] = &[
+        ("insert char", |t| {
+            t.move_cursor(CursorMove::Jump(0, 1));
+            t.insert_char('😀');
+        }),
+        ("insert newline", |t| {
+            t.insert_newline();
+        }),
+        ("insert str", |t| {
+            t.move_cursor(CursorMove::Bottom);
+            t.insert_str("x\nyあ\nz");
+        }),
+        ("delete char", |t| {
+            t.move_cursor(CursorMove::Top);
+            t.move_cursor(CursorMove::End);
+            t.delete_char();
+        }),
+        ("delete newline", |t| {
+            t.move_cursor(CursorMove::Jump(1, 0));
+            t.delete_newline();
+        }),
+        ("delete line", |t| {
+            t.move_cursor(CursorMove::Bottom);
+            t.delete_line(false);
+        }),
+        ("move lines", |t| {
+            t.move_lines_up();
+        }),
+        ("sort lines", |t| {
+            t.select_all();
+            t.sort_lines(SortOrder::Ascending);
+        }),
+        ("undo", |t| {
+            t.undo();
+        }),
+        ("undo", |t| {
+            t.undo();
+        }),
+        ("redo", |t| {
+            t.redo();
+        }),
+        ("paste", |t| {
+            t.set_yank_text("p\nq");
+            t.move_cursor(CursorMove::Top);
+            t.paste();
+        }),
+        ("single line", |t| {
+            t.set_single_line(true);
+        }),
+    ];
+    for (name, op) in ops {
+        op(&mut t);
+        check(&t, name);
+    }
+}
+
+#[test]
+fn test_stats() {
+    let mut t = TextArea::from(["Hello, world!", "e\u{301}🇯🇵 漢字です", ""]);
+    let stats = t.stats();
+    assert_eq!(stats.lines, 3);
+    assert_eq!(stats.words, 7, "{stats:?}");
+    assert_eq!(stats.chars, 13 + 1 + 9 + 1);
+    assert_eq!(stats.graphemes, 13 + 1 + 7 + 1);
+    assert_eq!(stats.bytes, t.text().len());
+    assert_eq!(t.stats(), stats);
+
+    // The cached statistics are updated on every kind of change
+    let check = |t: &TextArea<'_>, context: &str| {
+        let expected = TextArea::from(t.iter_lines()).stats();
+        assert_eq!(t.stats(), expected, "{context}");
+    };
+    t.insert_str("one two");
+    check(&t, "insert");
+    t.delete_word();
+    check(&t, "delete");
+    t.insert_str("x y\nz\n\nw");
+    check(&t, "insert lines");
+    t.move_cursor(CursorMove::Top);
+    t.insert_newline();
+    check(&t, "insert newline");
+    t.delete_newline();
+    check(&t, "delete newline");
+    t.move_lines_down();
+    check(&t, "move lines");
+    t.select_all();
+    t.sort_lines(SortOrder::Descending);
+    check(&t, "sort lines");
+    t.join_lines();
+    check(&t, "join lines");
+    t.undo();
+    t.undo();
+    check(&t, "undo twice");
+    t.undo();
+    check(&t, "undo");
+    t.redo();
+    check(&t, "redo");
+    t.set_read_only(true);
+    t.insert_str("three ");
+    check(&t, "read-only");
+    t.set_single_line(true);
+    check(&t, "single-line");
+
+    let mut t = TextArea::default();
+    assert_eq!(t.stats().lines, 1);
+    assert_eq!(t.stats().words, 0);
+    assert_eq!(t.cursor_progress(), 1.0);
+    t.insert_str("a\nb\nc\nd\ne");
+    assert_eq!(t.cursor_progress(), 1.0);
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert_eq!(t.cursor_progress(), 0.4);
+}
+
+#[test]
+fn test_builder_defaults() {
+    let mut built = TextArea::builder().build();
+    let mut default = TextArea::default();
+
+    macro_rules! assert_same_getters {
+        ($($getter:ident),+ $(,)?) => {
+            $(
+                assert_eq!(
+                    format!("{:?}", built.$getter()),
+                    format!("{:?}", default.$getter()),
+                    stringify!($getter),
+                );
+            )+
+        };
+    }
+
+    assert_same_getters!(
+        lines,
+        cursor,
+        block,
+        style,
+        cursor_style,
+        cursor_line_style,
+        cursor_line_style_in_selection,
+        selection_style,
+        placeholder_text,
+        placeholder_style,
+        line_number_style,
+        relative_line_numbers,
+        line_number_min_width,
+        tab_length,
+        hard_tab_indent,
+        max_histories,
+        max_history_bytes,
+        undo_grouping,
+        undo_group_limit,
+        wrap,
+        wrap_indicator,
+        is_read_only,
+        is_single_line,
+        newline_replacement,
+        is_modified,
+        edit_count,
+        is_selecting,
+        keep_selection_on_copy,
+        yank_flash,
+        ghost_text,
+        ghost_text_style,
+        comment_tokens,
+        auto_indent,
+        auto_indent_backspace,
+        auto_pairs,
+        auto_pair_chars,
+        overwrite_mode,
+        overwrite_cursor_style,
+        matching_bracket_style,
+        matching_bracket_limit,
+        line_ending,
+        show_whitespace,
+        indent_guides,
+        indent_guide_char,
+        ruler_columns,
+        empty_line_marker,
+        truncation_indicator,
+        render_control_chars,
+        control_char_style,
+        link_style,
+        edit_tracking,
+        invalid_style,
+        max_chars,
+        is_cursor_hidden,
+        is_focused,
+        cursor_style_unfocused,
+        ignore_input_when_unfocused,
+        sign_column,
+        fold_style,
+        mask_char,
+        mask_enabled,
+        mask_reveal_last,
+        mask_reveal_renders,
+        secondary_cursor_style,
+        scrollbar,
+        alignment,
+        kill_ring_size,
+        jump_threshold,
+        pending_keys_timeout,
+        replay_pending_keys,
+        cursor_follow,
+        scroll_padding,
+        scroll_follow,
+        fullscreen,
+        column_padding,
+        comment_blank_lines,
+    );
+    #[cfg(feature = "search")]
+    assert_same_getters!(search_pattern, search_options, search_style, current_search_match_style);
+}
+
+#[test]
+fn test_builder() {
+    let t = TextArea::builder()
+        .placeholder("placeholder")
+        .tab_length(2)
+        .hard_tab_indent(true)
+        .max_histories(10)
+        .wrap(true)
+        .read_only(true)
+        .lines(["abc", "def"])
+        .cursor((1, 2))
+        .build();
+
+    assert_eq!(t.placeholder_text(), "placeholder");
+    assert_eq!(t.tab_length(), 2);
+    assert!(t.hard_tab_indent());
+    assert_eq!(t.max_histories(), 10);
+    assert!(t.wrap());
+    assert!(t.is_read_only());
+    assert_eq!(t.lines(), ["abc", "def"]);
+    assert_eq!(t.cursor(), (1, 2));
+    assert!(!t.is_modified());
+    assert_eq!(t.edit_count(), 0);
+
+    let mut t = TextArea::builder().lines(Vec::<String>::new()).build();
+    assert_eq!(t.lines(), [""]);
+    assert!(!t.undo());
+
+    let t = TextArea::builder()
+        .lines(["hello", "world"])
+        .cursor((0, 3))
+        .single_line(true)
+        .build();
+    assert_eq!(t.lines(), ["hello world"]);
+    assert_eq!(t.cursor(), (0, 3));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_snapshot_restore() {
+    fn round_trip(state: &TextAreaState) -> TextAreaState {
+        let json = serde_json::to_string(state).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    let mut t = TextArea::from(["abc", "def", "ghi", "jkl"]);
+    t.move_cursor(CursorMove::Jump(3, 1));
+    t.set_mark('a');
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(t.fold(2, 3));
+    #[cfg(feature = "search")]
+    t.set_search_pattern("e.").unwrap();
+
+    let state = t.snapshot();
+    assert_eq!(round_trip(&state), state);
+    assert_eq!(state.lines, None);
+    assert_eq!(state.history, None);
+
+    let mut u = TextArea::from(["abc", "def", "ghi", "jkl"]);
+    u.restore(round_trip(&state));
+    assert_eq!(u.cursor(), (1, 1));
+    assert_eq!(u.selection_range(), Some(((0, 2), (1, 1))));
+    assert_eq!(u.marks()[&'a'], (3, 1));
+    assert_eq!(u.folds(), t.folds());
+    #[cfg(feature = "search")]
+    assert_eq!(u.search_pattern().map(|p| p.as_str()), Some("e."));
+    assert_eq!(u.snapshot(), state);
+
+    // Positions are clamped and folds out of the text are ignored
+    let mut u = TextArea::from(["a", "bcd"]);
+    u.restore(state.clone());
+    assert_eq!(u.cursor(), (1, 1));
+    assert_eq!(u.selection_range(), Some(((0, 1), (1, 1))));
+    assert_eq!(u.marks()[&'a'], (1, 1));
+    assert!(u.folds().is_empty());
+
+    // The text is replaced
+    let state = t.snapshot_with_text();
+    let mut u = TextArea::default();
+    u.restore(round_trip(&state));
+    assert_eq!(u.lines(), t.lines());
+    assert_eq!(u.cursor(), (1, 1));
+    assert!(u.is_modified());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_snapshot_restore_history() {
+    let mut t = TextArea::from(["abc"]);
+    t.set_undo_grouping(UndoGrouping::None);
+    t.insert_char('x');
+    t.mark_saved();
+    t.insert_char('y');
+    t.insert_char('z');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["xyabc"]);
+
+    let json = serde_json::to_string(&t.snapshot_with_history()).unwrap();
+    let state: TextAreaState = serde_json::from_str(&json).unwrap();
+
+    // Undo, redo and the saved point are restored on the same text
+    let mut u = TextArea::from(["xyabc"]);
+    u.restore(state.clone());
+    assert!(u.is_modified());
+    assert!(u.redo());
+    assert_eq!(u.lines(), ["xyzabc"]);
+    assert!(!u.redo());
+    assert!(u.undo());
+    assert!(u.undo());
+    assert_eq!(u.lines(), ["xabc"]);
+    assert!(!u.is_modified());
+    assert!(u.undo());
+    assert_eq!(u.lines(), ["abc"]);
+    assert!(!u.undo());
+
+    // The history is not restored on other text
+    let mut u = TextArea::from(["xyabcd"]);
+    u.restore(state.clone());
+    assert!(!u.undo());
+    assert_eq!(u.lines(), ["xyabcd"]);
+
+    // Old edits are forgotten with the max histories
+    let mut u = TextArea::from(["xyabc"]);
+    u.set_max_histories(2);
+    u.restore(state);
+    assert!(u.undo());
+    assert_eq!(u.lines(), ["xabc"]);
+    assert!(!u.is_modified());
+    assert!(!u.undo());
+    assert!(u.redo());
+    assert!(u.redo());
+    assert_eq!(u.lines(), ["xyzabc"]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_restore_invalid_history() {
+    let mut t = TextArea::from(["abc"]);
+    t.insert_char('x');
+    t.insert_newline();
+    t.insert_str("yz");
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["x", "abc"]);
+    let json = serde_json::to_value(t.snapshot_with_history()).unwrap();
+
+    let restore = |json: serde_json::Value| {
+        let state: TextAreaState = serde_json::from_value(json).unwrap();
+        let mut u = TextArea::from(["x", "abc"]);
+        u.restore(state);
+        u
+    };
+
+    // The valid history is restored
+    let mut u = restore(json.clone());
+    assert!(u.redo());
+    assert_eq!(u.lines(), ["x", "yzabc"]);
+
+    // The edit to redo is at a row out of the text
+    let last = json["history"]["edits"].as_array().unwrap().len() - 1;
+    let mut corrupted = json.clone();
+    corrupted["history"]["edits"][last]["before"]["row"] = 7.into();
+    let mut u = restore(corrupted);
+    assert!(!u.redo());
+    assert!(!u.undo());
+    assert_eq!(u.lines(), ["x", "abc"]);
+
+    // The deleted text in the edit to undo does not match the text
+    let edits = json["history"]["edits"].as_array().unwrap();
+    let mut corrupted = json.clone();
+    corrupted["history"]["edits"] = edits.iter().cloned().rev().collect();
+    let mut u = restore(corrupted);
+    assert!(!u.undo());
+    assert_eq!(u.lines(), ["x", "abc"]);
+
+    // Histories of various edits are restored entirely
+    let mut t = TextArea::from(["foo", "bar", "baz"]);
+    t.set_undo_grouping(UndoGrouping::None);
+    t.insert_str("a\nb\nc");
+    t.move_lines_down();
+    t.duplicate_line();
+    t.join_lines();
+    t.sort_lines(SortOrder::Descending);
+    t.move_cursor(CursorMove::Top);
+    t.delete_str(3);
+    t.set_selection((0, 1), (2, 1));
+    t.cut();
+    t.delete_line_by_end();
+    t.select_all();
+    t.transform_selection(TextTransform::Uppercase);
+    t.undo();
+    t.undo();
+    let lines = t.lines().to_vec();
+    let mut u = TextArea::from(lines.clone());
+    u.restore(t.snapshot_with_history());
+    while u.undo() {}
+    assert_eq!(u.lines(), ["foo", "bar", "baz"]);
+    while u.redo() {}
+    t.redo();
+    t.redo();
+    assert_eq!(u.lines(), t.lines());
+
+    // The history which cannot be decoded is dropped and the rest of the state is restored
+    let mut corrupted = json.clone();
+    corrupted["history"]["edits"][0]["kind"] = serde_json::json!({"UnknownEdit": 42});
+    corrupted["cursor"] = serde_json::json!([1, 2]);
+    let state: TextAreaState = serde_json::from_value(corrupted).unwrap();
+    assert_eq!(state.history, None);
+    let mut u = restore(serde_json::to_value(state).unwrap());
+    assert!(!u.undo());
+    assert_eq!(u.cursor(), (1, 2));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_restore_forward_compatibility() {
+    // Unknown fields are ignored and missing fields are filled with default values
+    let json = r#"{"cursor":[1,2],"unknown_field":{"nested":[1,2,3]},"marks":{"a":[0,1]}}"#;
+    let state: TextAreaState = serde_json::from_str(json).unwrap();
+    assert_eq!(state.cursor, (1, 2));
+    assert_eq!(state.selection_start, None);
+
+    let mut t = TextArea::from(["abc", "def"]);
+    t.restore(state);
+    assert_eq!(t.cursor(), (1, 2));
+    assert_eq!(t.marks()[&'a'], (0, 1));
+    assert!(!t.is_selecting());
+    assert!(!t.is_modified());
+
+    let state: TextAreaState = serde_json::from_str("{}").unwrap();
+    t.restore(state);
+    assert_eq!(t.cursor(), (0, 0));
+    assert!(t.marks().is_empty());
+}
+
+#[test]
+fn test_undo_grouping() {
+    fn type_str(t: &mut TextArea<'_>, s: &str) {
+        for c in s.chars() {
+            t.insert_char(c);
+        }
+    }
+
+    #[rustfmt::skip]
+    let tests = [
+        // (grouping, text to type, lines after each undo)
+        (UndoGrouping::ByWord, "foo bar", &["foo", ""][..]),
+        (UndoGrouping::ByWord, "foo  bar", &["foo", ""][..]),
+        (UndoGrouping::ByWord, "あい🐶 うえ", &["あい🐶", ""][..]),
+        (UndoGrouping::ByWord, "a\nb", &["a\n", "a", ""][..]),
+        (UndoGrouping::ByEdit, "foo bar", &[""][..]),
+        (UndoGrouping::ByEdit, "ab\ncd", &["ab\n", "ab", ""][..]),
+        (UndoGrouping::None, "abc", &["ab", "a", ""][..]),
+    ];
+
+    for test in tests {
+        let (grouping, input, undone) = test;
+        let mut t = TextArea::default();
+        t.set_undo_grouping(grouping);
+        type_str(&mut t, input);
+        let lines = t.lines().to_vec();
+        let cursor = t.cursor();
+
+        for expected in undone {
+            assert!(t.undo(), "{test:?}");
+            assert_eq!(t.lines().join("\n"), *expected, "{test:?}");
+        }
+        assert!(!t.undo(), "{test:?}");
+
+        while t.redo() {}
+        assert_eq!(t.lines(), lines, "{test:?}");
+        assert_eq!(t.cursor(), cursor, "{test:?}");
+    }
+}
+
+#[test]
+fn test_undo_grouping_boundaries() {
+    // Cursor movement ends undo unit
+    let mut t = TextArea::default();
+    for c in "abc".chars() {
+        t.insert_char(c);
+    }
+    t.move_cursor(CursorMove::Back);
+    t.move_cursor(CursorMove::Forward);
+    t.insert_char('d');
+    t.insert_char('e');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+    assert_eq!(t.cursor(), (0, 3));
+
+    // Inserting at another position ends undo unit
+    t.move_cursor(CursorMove::Head);
+    t.insert_char('x');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+    assert_eq!(t.cursor(), (0, 0));
+
+    // Deletion ends undo unit
+    t.move_cursor(CursorMove::End);
+    t.insert_char('d');
+    t.delete_char();
+    t.insert_char('e');
+    t.insert_char('f');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abcd"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+    assert_eq!(t.cursor(), (0, 3));
+
+    // Undo ends undo unit
+    t.insert_char('d');
+    t.undo();
+    t.insert_char('e');
+    t.insert_char('f');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+
+    // Limit of characters in one unit
+    let mut t = TextArea::default();
+    t.set_undo_group_limit(Some(2));
+    for c in "abcde".chars() {
+        t.insert_char(c);
+    }
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abcd"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["ab"]);
+    assert_eq!(t.cursor(), (0, 2));
+
+    // Many characters take only one history entry
+    let mut t = TextArea::default();
+    t.set_max_histories(2);
+    assert_eq!(t.undo_grouping(), UndoGrouping::ByWord);
+    for c in "abcdefghij".chars() {
+        t.insert_char(c);
+    }
+    assert!(t.undo());
+    assert_eq!(t.lines(), [""]);
+}
+
+#[test]
+fn test_paste_str() {
+    let tests = [
+        // (text, lines after)
+        ("xyz", &["axyzb"][..]),
+        ("x\ny", &["ax", "yb"][..]),
+        ("x\r\ny", &["ax", "yb"][..]),
+        ("x\ry\r", &["ax", "y", "b"][..]),
+        ("🐶\r\n\r\n🐱", &["a🐶", "", "🐱b"][..]),
+        ("", &["ab"][..]),
+    ];
+
+    for test in tests {
+        let (text, after) = test;
+        let mut t = TextArea::from(["ab"]);
+        t.set_yank_text("yank");
+        t.move_cursor(CursorMove::Forward);
+        assert_eq!(t.paste_str(text), !text.is_empty(), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.yank_text(), "yank", "{test:?}");
+
+        assert_eq!(t.undo(), !text.is_empty(), "{test:?}");
+        assert_eq!(t.lines(), ["ab"], "{test:?}");
+    }
+
+    // Selected text is replaced
+    let mut t = TextArea::from(["abc"]);
+    t.select_all();
+    assert!(t.paste_str("x"));
+    assert_eq!(t.lines(), ["x"]);
+}
+
+#[test]
+fn test_auto_indent() {
+    #[rustfmt::skip]
+    let tests = [
+        // (line, cursor col, lines after, cursor after)
+        ("  foo", 5, &["  foo", "  "][..], (1, 2)),
+        ("\t foo", 5, &["\t foo", "\t "][..], (1, 2)),
+        ("  foo", 3, &["  f", "  oo"][..], (1, 2)),
+        ("    foo", 2, &["  ", "    foo"][..], (1, 2)),
+        ("foo", 3, &["foo", ""][..], (1, 0)),
+        ("    ", 4, &["", ""][..], (1, 0)),
+        ("\t\t", 1, &["", ""][..], (1, 0)),
+        ("  あい", 3, &["  あ", "  い"][..], (1, 2)),
+    ];
+
+    for test in tests {
+        let (line, col, after, cursor) = test;
+        let mut t = TextArea::from([line]);
+        t.set_auto_indent(true);
+        t.move_cursor(CursorMove::Jump(0, col));
+        assert!(t.insert_newline(), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.cursor(), cursor, "{test:?}");
+
+        // Newline and indentation are one undo unit
+        assert!(t.undo(), "{test:?}");
+        assert_eq!(t.lines(), [line], "{test:?}");
+        assert_eq!(t.cursor(), (0, col as usize), "{test:?}");
+        assert!(!t.undo(), "{test:?}");
+        assert!(t.redo(), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+    }
+
+    // Disabled by default
+    let mut t = TextArea::from(["  foo"]);
+    assert!(!t.auto_indent());
+    t.move_cursor(CursorMove::End);
+    t.insert_newline();
+    assert_eq!(t.lines(), ["  foo", ""]);
+
+    // Inside undo group started by user
+    let mut t = TextArea::from(["  foo"]);
+    t.set_auto_indent(true);
+    t.move_cursor(CursorMove::End);
+    t.begin_undo_group();
+    t.insert_newline();
+    t.insert_str("bar");
+    t.end_undo_group();
+    assert_eq!(t.lines(), ["  foo", "  bar"]);
+    t.undo();
+    assert_eq!(t.lines(), ["  foo"]);
+
+    // Indentation is truncated by max chars
+    let mut t = TextArea::from(["    foo"]);
+    t.set_auto_indent(true);
+    t.set_max_chars(Some(10));
+    t.move_cursor(CursorMove::End);
+    t.insert_newline();
+    assert_eq!(t.lines(), ["    foo", "  "]);
+}
+
+#[test]
+fn test_auto_indent_backspace() {
+    let mut t = TextArea::from(["    foo"]);
+    t.set_auto_indent(true);
+    assert!(t.auto_indent_backspace());
+    t.move_cursor(CursorMove::End);
+    t.insert_newline();
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["    foo", ""]);
+    assert_eq!(t.cursor(), (1, 0));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["    foo", "    "]);
+    assert_eq!(t.cursor(), (1, 4));
+
+    // Only indentation inserted automatically is removed at once
+    t.move_cursor(CursorMove::Back);
+    t.move_cursor(CursorMove::Forward);
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["    foo", "   "]);
+
+    let mut t = TextArea::from(["    foo"]);
+    t.set_auto_indent(true);
+    t.set_auto_indent_backspace(false);
+    t.move_cursor(CursorMove::End);
+    t.insert_newline();
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["    foo", "   "]);
+}
+
+#[test]
+fn test_auto_pairs() {
+    #[rustfmt::skip]
+    let tests = [
+        // (line, cursor col, typed char, line after, cursor col after)
+        ("", 0, '(', "()", 1),
+        ("a ", 2, '[', "a []", 3),
+        ("f", 1, '(', "f()", 2),
+        ("x", 0, '(', "(x", 1),
+        ("", 0, '"', "\"\"", 1),
+        ("it", 2, '\'', "it'", 3),
+        ("()", 1, ')', "())", 2), // Not inserted automatically
+        ("", 0, ')', ")", 1),
+        ("あ", 1, '{', "あ{}", 2),
+    ];
+    for test in tests {
+        let (line, col, c, after, col_after) = test;
+        let mut t = TextArea::from([line]);
+        t.set_auto_pairs(true);
+        t.move_cursor(CursorMove::Jump(0, col));
+        assert!(t.insert_char(c), "{test:?}");
+        assert_eq!(t.lines(), [after], "{test:?}");
+        assert_eq!(t.cursor(), (0, col_after), "{test:?}");
+
+        // One undo unit
+        assert!(t.undo(), "{test:?}");
+        assert_eq!(t.lines(), [line], "{test:?}");
+    }
+
+    // Disabled by default
+    let mut t = TextArea::default();
+    assert!(!t.auto_pairs());
+    t.insert_char('(');
+    assert_eq!(t.lines(), ["("]);
+
+    // Nested pairs are typed over from inside
+    let mut t = TextArea::default();
+    t.set_auto_pairs(true);
+    for c in "f((a".chars() {
+        t.insert_char(c);
+    }
+    assert_eq!(t.lines(), ["f((a))"]);
+    assert!(!t.insert_char(')'));
+    assert!(!t.insert_char(')'));
+    assert!(t.insert_char(')'));
+    assert_eq!(t.lines(), ["f((a)))"]);
+    assert_eq!(t.cursor(), (0, 7));
+
+    // Moving the cursor forgets the inserted closing characters
+    let mut t = TextArea::default();
+    t.set_auto_pairs(true);
+    t.insert_char('[');
+    t.move_cursor(CursorMove::Back);
+    t.move_cursor(CursorMove::Forward);
+    t.insert_char(']');
+    assert_eq!(t.lines(), ["[]]"]);
+}
+
+#[test]
+fn test_auto_pairs_backspace() {
+    let mut t = TextArea::from(["x"]);
+    t.set_auto_pairs(true);
+    t.move_cursor(CursorMove::End);
+    t.insert_char('(');
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["x"]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["x()"]);
+    assert_eq!(t.cursor(), (0, 2));
+
+    // Only the pair inserted automatically is removed at once
+    let mut t = TextArea::from(["()"]);
+    t.set_auto_pairs(true);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), [")"]);
+
+    // The pair becomes empty again after its content is deleted
+    let mut t = TextArea::default();
+    t.set_auto_pairs(true);
+    t.insert_char('(');
+    t.insert_char('a');
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["()"]);
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), [""]);
+}
+
+#[test]
+fn test_overwrite_mode() {
+    #[rustfmt::skip]
+    let tests = [
+        // (line, cursor col, typed chars, line after, cursor col after)
+        ("abc", 0, "xy", "xyc", 2),
+        ("abc", 2, "xyz", "abxyz", 5), // Appended at the end of line
+        ("", 0, "a", "a", 1),
+        ("あいう", 1, "xy", "あxy", 3), // Wide characters replaced with narrow ones
+        ("abc", 1, "あ", "aあc", 2),
+        ("🐶🐱", 0, "a", "a🐱", 1),
+    ];
+    for test in tests {
+        let (line, col, typed, after, col_after) = test;
+        let mut t = TextArea::from([line]);
+        t.set_overwrite_mode(true);
+        t.move_cursor(CursorMove::Jump(0, col));
+        for c in typed.chars() {
+            assert!(t.insert_char(c), "{test:?}");
+        }
+        assert_eq!(t.lines(), [after], "{test:?}");
+        assert_eq!(t.cursor(), (0, col_after), "{test:?}");
+
+        while t.undo() {}
+        assert_eq!(t.lines(), [line], "{test:?}");
+    }
+
+    // Each replacement is undone one by one
+    let mut t = TextArea::from(["abc"]);
+    t.set_overwrite_mode(true);
+    t.insert_char('x');
+    t.insert_char('y');
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["xbc"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+    assert!(!t.undo());
+
+    // Disabled by default
+    let mut t = TextArea::from(["abc"]);
+    assert!(!t.overwrite_mode());
+    t.insert_char('x');
+    assert_eq!(t.lines(), ["xabc"]);
+
+    // Backspace deletes, pasting inserts, and a newline splits the line as usual
+    let mut t = TextArea::from(["abcd"]);
+    t.set_overwrite_mode(true);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["acd"]);
+    t.set_yank_text("xy");
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["axycd"]);
+    assert!(t.insert_char('\n'));
+    assert_eq!(t.lines(), ["axy", "cd"]);
+
+    // The selection is replaced with the typed character
+    let mut t = TextArea::from(["abcd"]);
+    t.set_overwrite_mode(true);
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(0, 2));
+    assert!(t.insert_char('x'));
+    assert_eq!(t.lines(), ["xcd"]);
+
+    // Repeated with a count
+    let mut t = TextArea::from(["abcd"]);
+    t.set_overwrite_mode(true);
+    assert!(t.perform_n(EditAction::InsertChar('x'), 3));
+    assert_eq!(t.lines(), ["xxxd"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abcd"]);
+
+    // Toggled with the Insert key
+    let mut t = TextArea::from(["abc"]);
+    let insert = Input {
+        key: Key::Insert,
+        ..Default::default()
+    };
+    assert!(!t.input(insert.clone()));
+    assert!(t.overwrite_mode());
+    t.input(Input {
+        key: Key::Char('x'),
+        ..Default::default()
+    });
+    assert_eq!(t.lines(), ["xbc"]);
+    t.input(insert);
+    assert!(!t.overwrite_mode());
+    t.input(Input {
+        key: Key::Char('y'),
+        ..Default::default()
+    });
+    assert_eq!(t.lines(), ["xybc"]);
+}
+
+#[test]
+fn test_column_selection() {
+    // Wide characters and tabs are counted by display width. A tab across the left edge is not selected
+    let mut t = TextArea::from(["abcdef", "あいう", "\tx", "ab"]);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(2, 1));
+    assert!(t.is_column_selecting());
+    assert!(t.is_selecting());
+    t.copy();
+    assert_eq!(t.yank_text(), "cd\nい\n");
+    assert_eq!(t.lines(), ["abcdef", "あいう", "\tx", "ab"]);
+
+    // Cut deletes the fragments as one undo unit and yanks them
+    assert!(t.cut());
+    assert_eq!(t.lines(), ["abef", "あう", "\tx", "ab"]);
+    assert_eq!(t.yank_text(), "cd\nい\n");
+    assert!(!t.is_selecting());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abcdef", "あいう", "\tx", "ab"]);
+    assert!(!t.undo());
+
+    // Normal selection is not column selection
+    t.start_selection();
+    assert!(!t.is_column_selecting());
+    t.start_column_selection();
+    t.cancel_selection();
+    assert!(!t.is_column_selecting());
+
+    // Typing a character replaces the fragments
+    let mut t = TextArea::from(["abc", "def"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(1, 2));
+    assert!(t.insert_char('x'));
+    assert_eq!(t.lines(), ["axc", "df"]);
+    assert_eq!(t.cursor(), (0, 2));
+}
+
+#[test]
+fn test_linewise_selection() {
+    // Columns of the start position and the cursor are ignored
+    let mut t = TextArea::from(["aaa", "bbb", "ccc", "ddd"]);
+    t.move_cursor(CursorMove::Jump(2, 2));
+    t.start_selection_linewise();
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(t.is_linewise_selecting());
+    assert!(!t.is_column_selecting());
+    assert_eq!(t.selection_range(), Some(((1, 0), (3, 0))));
+    assert_eq!(t.selected_text().as_deref(), Some("bbb\nccc\n"));
+    assert_eq!(t.selection_line_count(), 2);
+    t.copy();
+    assert_eq!(t.yank_text(), "bbb\nccc\n");
+
+    // Cut removes the whole lines as one undo unit
+    assert!(t.cut());
+    assert_eq!(t.lines(), ["aaa", "ddd"]);
+    assert_eq!(t.cursor(), (1, 0));
+    assert!(!t.is_selecting());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["aaa", "bbb", "ccc", "ddd"]);
+    assert!(t.redo());
+
+    // Pasting inserts the lines above the cursor line without splitting it
+    t.move_cursor(CursorMove::Jump(0, 1));
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["bbb", "ccc", "aaa", "ddd"]);
+    assert_eq!(t.cursor(), (2, 1));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["aaa", "ddd"]);
+
+    // Cutting the last lines removes the newline before them
+    let mut t = TextArea::from(["aaa", "bbb", "ccc"]);
+    t.move_cursor(CursorMove::Jump(1, 0));
+    t.start_selection_linewise();
+    t.move_cursor(CursorMove::Bottom);
+    assert!(t.cut());
+    assert_eq!(t.lines(), ["aaa"]);
+    assert_eq!(t.cursor(), (0, 0));
+    assert_eq!(t.yank_text(), "bbb\nccc\n");
+
+    // Cutting all lines leaves an empty line
+    t.select_line();
+    assert!(t.cut());
+    assert_eq!(t.lines(), [""]);
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["aaa", ""]);
+
+    // Pasting over the linewise selection replaces the lines and keeps the line break after them
+    let mut t = TextArea::from(["aaa", "bbb", "ccc"]);
+    t.set_yank_text("x");
+    t.select_line();
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["x", "bbb", "ccc"]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["", "bbb", "ccc"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["aaa", "bbb", "ccc"]);
+
+    // Lines copied linewise replace the selected lines
+    t.select_line();
+    t.copy();
+    t.move_cursor(CursorMove::Down);
+    t.start_selection_linewise();
+    t.move_cursor(CursorMove::Down);
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["aaa", "aaa"]);
+
+    // Typing over the linewise selection also keeps the line break
+    t.move_cursor(CursorMove::Top);
+    t.select_line();
+    assert!(t.insert_char('z'));
+    assert_eq!(t.lines(), ["z", "aaa"]);
+    t.move_cursor(CursorMove::Top);
+    t.start_selection_linewise();
+    t.move_cursor(CursorMove::Down);
+    assert!(t.insert_str("w"));
+    assert_eq!(t.lines(), ["w"]);
+
+    // Other selections reset the linewise mode
+    t.select_line();
+    t.start_selection();
+    assert!(!t.is_linewise_selecting());
+    t.select_line();
+    t.cancel_selection();
+    assert!(!t.is_linewise_selecting());
+
+    // Linewise text is pasted as text in single-line mode
+    let mut t = TextArea::from(["aaa"]);
+    t.select_line();
+    t.copy();
+    t.set_single_line(true);
+    t.cancel_selection();
+    t.move_cursor(CursorMove::End);
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["aaaaaa"]);
+}
+
+#[test]
+fn test_select_word() {
+    let mut t = TextArea::from(["foo.bar  baz", "  "]);
+    for (col, range) in [
+        (0, (0, 3)),
+        (2, (0, 3)),
+        (3, (0, 3)),
+        (4, (4, 7)),
+        (7, (4, 7)),
+        (9, (9, 12)),
+        (12, (9, 12)),
+    ] {
+        t.cancel_selection();
+        t.move_cursor(CursorMove::Jump(0, col));
+        assert!(t.select_word(), "col={}", col);
+        assert_eq!(t.selection_range(), Some(((0, range.0), (0, range.1))), "col={}", col);
+        assert_eq!(t.cursor(), (0, range.1), "col={}", col);
+    }
+
+    // No word at or before the cursor
+    for (row, col) in [(0, 8), (1, 1)] {
+        t.cancel_selection();
+        t.move_cursor(CursorMove::Jump(row, col));
+        assert!(!t.select_word(), "row={} col={}", row, col);
+    }
+    assert!(!t.is_selecting());
+}
+
+#[test]
+fn test_paste_column_block() {
+    let mut t = TextArea::from(["ab", "cd", "ef", "g"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(2, 2));
+    t.copy();
+    assert_eq!(t.yank_text(), "b\nd\nf");
+    t.cancel_selection();
+
+    // Short rows are padded and lines are added after the end of text
+    t.move_cursor(CursorMove::Jump(2, 2));
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["ab", "cd", "efb", "g d", "  f"]);
+    assert_eq!(t.cursor(), (2, 2));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["ab", "cd", "ef", "g"]);
+
+    // Pasting over column selection replaces it
+    t.move_cursor(CursorMove::Jump(0, 0));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["bb", "dd", "fef", "g"]);
+
+    // The block is inserted as text when the yanked text is set as a string
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.set_yank_text("x\ny");
+    t.move_cursor(CursorMove::Jump(0, 1));
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["ax", "yb", "cd"]);
+
+    // In single-line mode, the fragments are pasted in the line
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(1, 2));
+    t.copy();
+    t.cancel_selection();
+    t.set_single_line(true);
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["ab cdb d"]);
+}
+
+#[test]
+fn test_insert_str_columnwise() {
+    #[rustfmt::skip]
+    let tests = [
+        // (lines, anchor, cursor, padding, inserted, lines after, cursor after)
+        (&["abc", "abc"][..], (0, 1), (1, 1), false, "|", &["a|bc", "a|bc"][..], (1, 2)),
+        (&["abc", "", "abc"], (0, 2), (2, 2), false, "|", &["ab|c", "", "ab|c"], (2, 3)),
+        (&["abc", "", "abc"], (0, 2), (2, 2), true, "|", &["ab|c", "  |", "ab|c"], (2, 3)),
+        (&["abc", "a", "abc"], (0, 3), (2, 3), false, ";", &["abc;", "a", "abc;"], (2, 4)),
+        // Inserted at the left edge
+        (&["abcd", "abcd"], (1, 3), (0, 1), false, "xy", &["axybcd", "axybcd"], (0, 3)),
+        // Wide characters and tabs
+        (&["あいう", "abcdef", "\tx"], (0, 1), (1, 2), false, "|", &["あ|いう", "ab|cdef", "\tx"], (1, 3)),
+        (&["あいう", "abcdef", "\tx"], (0, 2), (2, 1), true, "|", &["あい|う", "abcd|ef", "\t|x"], (2, 2)),
+        // Only the first line is inserted
+        (&["ab", "cd"], (0, 1), (1, 1), false, "x\ny", &["axb", "cxd"], (1, 2)),
+    ];
+    for test in tests {
+        let (lines, anchor, cursor, padding, inserted, after, cursor_after) = test;
+        let mut t = TextArea::from(lines.iter().copied());
+        t.set_column_padding(padding);
+        t.move_cursor(CursorMove::Jump(anchor.0 as u64, anchor.1 as u64));
+        t.start_column_selection();
+        t.move_cursor(CursorMove::Jump(cursor.0 as u64, cursor.1 as u64));
+        assert!(t.insert_str_columnwise(inserted), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.cursor(), cursor_after, "{test:?}");
+        assert!(t.is_column_selecting(), "{test:?}");
+
+        // One undo unit
+        assert!(t.undo(), "{test:?}");
+        assert_eq!(t.lines(), lines, "{test:?}");
+        assert!(!t.undo(), "{test:?}");
+    }
+
+    // Without column selection, the string is inserted at the cursor
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(t.insert_str_columnwise("x"));
+    assert_eq!(t.lines(), ["ab", "cxd"]);
+    assert!(!t.insert_str_columnwise(""));
+
+    // Replace the column selection
+    let mut t = TextArea::from(["a: 1", "bb: 2", "c"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(1, 3));
+    assert!(t.delete_column_selection());
+    assert_eq!(t.lines(), ["a1", "b 2", "c"]);
+    assert!(t.insert_str_columnwise("="));
+    assert_eq!(t.lines(), ["a=1", "b= 2", "c"]);
+    assert!(!t.delete_column_selection());
+
+    // Limited by max chars
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.set_max_chars(Some(5));
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(!t.insert_str_columnwise("x"));
+    assert_eq!(t.lines(), ["ab", "cd"]);
+}
+
+#[test]
+fn test_multi_cursor() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    assert!(t.add_cursor_below());
+    assert!(t.add_cursor_below());
+    assert!(!t.add_cursor_below());
+    assert_eq!(t.secondary_cursors(), [(1, 1), (2, 1)]);
+
+    // Typing at every cursor
+    let input = |t: &mut TextArea<'_>, key| t.input(Input { key, ..Default::default() });
+    input(&mut t, Key::Char('x'));
+    input(&mut t, Key::Char('y'));
+    assert_eq!(t.lines(), ["axybc", "dxyef", "gxyhi"]);
+    assert_eq!(t.cursor(), (0, 3));
+    assert_eq!(t.secondary_cursors(), [(1, 3), (2, 3)]);
+
+    // Deleting and moving at every cursor
+    input(&mut t, Key::Backspace);
+    input(&mut t, Key::Right);
+    input(&mut t, Key::Delete);
+    assert_eq!(t.lines(), ["axb", "dxe", "gxh"]);
+    assert_eq!(t.secondary_cursors(), [(1, 3), (2, 3)]);
+
+    // Undo reverts the edits of all cursors at once
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["axbc", "dxef", "gxhi"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["axybc", "dxyef", "gxyhi"]);
+
+    // Newlines at earlier cursors shift later cursors
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+    assert!(t.add_cursor(1, 1));
+    assert!(t.add_cursor(0, 2));
+    t.perform(EditAction::InsertNewline);
+    assert_eq!(t.lines(), ["a", "b", "", "c", "d"]);
+    assert_eq!(t.cursor(), (1, 0));
+    assert_eq!(t.secondary_cursors(), [(2, 0), (4, 0)]);
+
+    // Overlapping cursors are merged
+    let mut t = TextArea::from(["abc", "def"]);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    assert!(t.add_cursor(1, 2));
+    assert!(t.add_cursor(0, 1));
+    assert!(!t.add_cursor(0, 1));
+    assert!(!t.add_cursor(0, 2));
+    t.perform(EditAction::MoveCursor(CursorMove::Head));
+    assert_eq!(t.secondary_cursors(), [(1, 0)]);
+    t.perform(EditAction::MoveCursor(CursorMove::Top));
+    assert!(t.secondary_cursors().is_empty());
+
+    // Deleting text across another cursor merges it
+    let mut t = TextArea::from(["ab"]);
+    t.move_cursor(CursorMove::End);
+    assert!(t.add_cursor(0, 1));
+    t.perform(EditAction::DeleteWord);
+    assert_eq!(t.lines(), [""]);
+    assert!(t.secondary_cursors().is_empty());
+
+    // Paste at every cursor
+    let mut t = TextArea::from(["a", "b"]);
+    t.move_cursor(CursorMove::End);
+    t.add_cursor_below();
+    t.set_yank_text("!");
+    t.perform(EditAction::Paste);
+    assert_eq!(t.lines(), ["a!", "b!"]);
+
+    // Direct method calls edit only at the primary cursor
+    t.insert_char('?');
+    assert_eq!(t.lines(), ["a!?", "b!"]);
+
+    // Above
+    let mut t = TextArea::from(["abcd", "ab", "abcd"]);
+    t.move_cursor(CursorMove::Jump(2, 3));
+    assert!(t.add_cursor_above());
+    assert!(t.add_cursor_above());
+    assert!(!t.add_cursor_above());
+    assert_eq!(t.secondary_cursors(), [(0, 3), (1, 2)]);
+
+    t.clear_secondary_cursors();
+    assert!(t.secondary_cursors().is_empty());
+}
+
+#[test]
+fn test_select_next_occurrence() {
+    let mut t = TextArea::from(["foo bar", "bar foo", "foobar"]);
+
+    // The identifier at the cursor is selected first
+    assert!(t.select_next_occurrence());
+    assert_eq!(t.selection_range(), Some(((0, 0), (0, 3))));
+    assert!(t.secondary_cursors().is_empty());
+
+    assert!(t.select_next_occurrence());
+    assert!(t.select_next_occurrence());
+    assert!(!t.select_next_occurrence());
+    assert_eq!(t.secondary_cursors(), [(1, 7), (2, 3)]);
+
+    // Typing replaces all selections
+    t.perform(EditAction::InsertChar('x'));
+    assert_eq!(t.lines(), ["x bar", "bar x", "xbar"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo bar", "bar foo", "foobar"]);
+
+    // The search wraps around
+    let mut t = TextArea::from(["a-a-a"]);
+    t.set_selection((0, 2), (0, 3));
+    assert!(t.select_next_occurrence());
+    assert!(t.select_next_occurrence());
+    assert_eq!(t.secondary_cursors(), [(0, 1), (0, 5)]);
+    assert!(!t.select_next_occurrence());
+
+    // Multi-line selection
+    let mut t = TextArea::from(["a", "b", "a", "b"]);
+    t.set_selection((0, 0), (1, 1));
+    assert!(t.select_next_occurrence());
+    assert_eq!(t.secondary_cursors(), [(3, 1)]);
+
+    // Nothing to select
+    let mut t = TextArea::from(["  "]);
+    assert!(!t.select_next_occurrence());
+}
+
+#[test]
+fn test_keep_selection_on_copy() {
+    let mut t = TextArea::from(["abc", "def"]);
+    assert!(t.keep_selection_on_copy());
+    t.set_selection((0, 1), (1, 1));
+    t.copy();
+    assert_eq!(t.selection_range(), Some(((0, 1), (1, 1))));
+
+    t.set_keep_selection_on_copy(false);
+    for start in [TextArea::start_column_selection, TextArea::start_selection_linewise] {
+        t.move_cursor(CursorMove::Jump(0, 0));
+        start(&mut t);
+        t.move_cursor(CursorMove::Down);
+        t.copy();
+        assert!(!t.is_selecting());
+    }
+    assert_eq!(t.yank_text(), "abc\ndef\n");
+    assert_eq!(t.lines(), ["abc", "def"]);
+}
+
+#[test]
+fn test_selected_text() {
+    let mut t = TextArea::from(["abc", "あいう", "def"]);
+    assert_eq!(t.selected_text(), None);
+    assert_eq!(t.selection_line_count(), 0);
+
+    #[rustfmt::skip]
+    let tests = [
+        // (start, end, selected text, line count)
+        ((0, 1), (0, 3), Some("bc"), 1),
+        ((1, 2), (1, 0), Some("あい"), 1),
+        ((0, 2), (2, 1), Some("c\nあいう\nd"), 3),
+        ((2, 0), (0, 0), Some("abc\nあいう\n"), 2),
+        ((1, 1), (1, 1), None, 0),
+    ];
+    for test in tests {
+        let (start, end, text, count) = test;
+        t.set_selection(start, end);
+        t.set_yank_text("yanked");
+        assert_eq!(t.selected_text().as_deref(), text, "{test:?}");
+        assert_eq!(t.selection_line_count(), count, "{test:?}");
+        // No side effects
+        assert_eq!(t.cursor(), end, "{test:?}");
+        assert!(t.is_selecting(), "{test:?}");
+        assert_eq!(t.yank_text(), "yanked", "{test:?}");
+    }
+
+    // Column selection
+    t.move_cursor(CursorMove::Jump(0, 0));
+    t.start_column_selection();
+    t.move_cursor(CursorMove::Jump(2, 2));
+    assert_eq!(t.selected_text().as_deref(), Some("ab\nあ\nde"));
+    assert_eq!(t.selection_line_count(), 3);
+
+    // `take_selection` cancels the selection
+    t.set_selection((0, 0), (0, 2));
+    assert_eq!(t.take_selection().as_deref(), Some("ab"));
+    assert_eq!(t.selected_text(), None);
+    t.set_selection((0, 0), (1, 2));
+    assert_eq!(t.take_selection(), None);
+    assert!(!t.is_selecting());
+}
+
+#[test]
+fn test_auto_pairs_wrap_selection() {
+    let mut t = TextArea::from(["foo bar", "baz"]);
+    t.set_auto_pairs(true);
+    t.set_selection((0, 4), (1, 2));
+    assert!(t.insert_char('('));
+    assert_eq!(t.lines(), ["foo (bar", "ba)z"]);
+    assert_eq!(t.selection_range(), Some(((0, 5), (1, 2))));
+    assert_eq!(t.cursor(), (1, 2));
+
+    // The selection is kept to wrap it again
+    assert!(t.insert_char('"'));
+    assert_eq!(t.lines(), ["foo (\"bar", "ba\")z"]);
+
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo (bar", "ba)z"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo bar", "baz"]);
+
+    // Selection on one line backward
+    let mut t = TextArea::from(["abc"]);
+    t.set_auto_pairs(true);
+    t.set_selection((0, 2), (0, 1));
+    assert!(t.insert_char('['));
+    assert_eq!(t.lines(), ["a[b]c"]);
+    assert_eq!(t.cursor(), (0, 2));
+    assert_eq!(t.selection_range(), Some(((0, 2), (0, 3))));
+
+    // Other characters replace the selection
+    assert!(t.insert_char('x'));
+    assert_eq!(t.lines(), ["a[x]c"]);
+}
+
+#[test]
+fn test_toggle_comment() {
+    #[rustfmt::skip]
+    let tests = [
+        // (lines, selection, lines after)
+        (&["a"][..], None, &["// a"][..]),
+        (&["  a"][..], None, &["  // a"][..]),
+        (&["  // a"][..], None, &["  a"][..]),
+        (&["//a"][..], None, &["a"][..]),
+        (&[""][..], None, &["// "][..]),
+        (&["    a", "", "  b"][..], Some(((0, 0), (2, 1))), &["  //   a", "", "  // b"][..]),
+        (&["// a", "", "  // b"][..], Some(((0, 0), (2, 1))), &["a", "", "  b"][..]),
+        (&["// a", "b"][..], Some(((0, 0), (1, 1))), &["// // a", "// b"][..]),
+        // The line where the selection ends at its head is not touched
+        (&["a", "b"][..], Some(((0, 0), (1, 0))), &["// a", "b"][..]),
+    ];
+    for test in tests {
+        let (lines, selection, after) = test;
+        let mut t = TextArea::from(lines.iter().copied());
+        t.set_comment_tokens(Some("//"), None);
+        if let Some((start, end)) = selection {
+            t.set_selection(start, end);
+        }
+        assert!(t.toggle_comment(), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+
+        // One undo unit
+        assert!(t.undo(), "{test:?}");
+        assert_eq!(t.lines(), lines, "{test:?}");
+    }
+
+    // Nothing happens without the tokens
+    let mut t = TextArea::from(["a"]);
+    assert_eq!(t.comment_tokens(), (None, None));
+    assert!(!t.toggle_comment());
+
+    // The selection is kept on the same characters
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.set_comment_tokens(Some("#"), None);
+    t.set_selection((0, 1), (1, 2));
+    t.toggle_comment();
+    assert_eq!(t.lines(), ["# ab", "# cd"]);
+    assert_eq!(t.selection_range(), Some(((0, 3), (1, 4))));
+    t.toggle_comment();
+    assert_eq!(t.lines(), ["ab", "cd"]);
+    assert_eq!(t.selection_range(), Some(((0, 1), (1, 2))));
+}
+
+#[test]
+fn test_toggle_block_comment() {
+    let mut t = TextArea::from(["let x = a + b;"]);
+    t.set_comment_tokens(Some("//"), Some(("/*", "*/")));
+
+    // Wrap the selection in one line
+    t.set_selection((0, 13), (0, 8));
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["let x = /*a + b*/;"]);
+    assert_eq!(t.cursor(), (0, 10));
+    assert_eq!(t.selection_range(), Some(((0, 10), (0, 15))));
+
+    // Unwrap the selection wrapped by the tokens
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["let x = a + b;"]);
+    assert_eq!(t.selection_range(), Some(((0, 8), (0, 13))));
+
+    // Unwrap the selection including the tokens
+    t.undo();
+    t.set_selection((0, 8), (0, 17));
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["let x = a + b;"]);
+    assert_eq!(t.selection_range(), Some(((0, 8), (0, 13))));
+
+    // The selection over lines uses line comments
+    let mut t = TextArea::from(["a", "b"]);
+    t.set_comment_tokens(Some("//"), Some(("/*", "*/")));
+    t.select_all();
+    t.toggle_comment();
+    assert_eq!(t.lines(), ["// a", "// b"]);
+
+    // Block comments are used without the line comment token
+    let mut t = TextArea::from(["  a", "b"]);
+    t.set_comment_tokens(None, Some(("<!--", "-->")));
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["  <!--a-->", "b"]);
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["  a", "b"]);
+    t.select_all();
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["<!--  a", "b-->"]);
+    assert_eq!(t.selection_range(), Some(((0, 4), (1, 1))));
+    assert!(t.toggle_comment());
+    assert_eq!(t.lines(), ["  a", "b"]);
+}
+
+#[test]
+fn test_indent_dedent_selection() {
+    let mut t = TextArea::from(["foo", "", "\tbar", "baz"]);
+    t.set_selection((0, 2), (2, 1));
+
+    assert!(t.indent_selection());
+    assert_eq!(t.lines(), ["    foo", "", "    \tbar", "baz"]);
+    assert_eq!(t.selection_range(), Some(((0, 6), (2, 5))));
+    assert_eq!(t.cursor(), (2, 5));
+
+    // The selection is kept so the operation can be repeated
+    assert!(t.indent_selection());
+    assert_eq!(t.lines(), ["        foo", "", "        \tbar", "baz"]);
+
+    assert!(t.dedent_selection());
+    assert!(t.dedent_selection());
+    assert_eq!(t.lines(), ["foo", "", "\tbar", "baz"]);
+    assert_eq!(t.selection_range(), Some(((0, 2), (2, 1))));
+    assert!(t.dedent_selection());
+    assert_eq!(t.lines(), ["foo", "", "bar", "baz"]);
+    assert_eq!(t.selection_range(), Some(((0, 2), (2, 0))));
+    assert!(!t.dedent_selection());
+
+    // Each operation is one undo step
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo", "", "\tbar", "baz"]);
+    assert!(t.undo());
+    assert!(t.undo());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["    foo", "", "    \tbar", "baz"]);
+    assert!(t.redo());
+    assert_eq!(t.lines(), ["        foo", "", "        \tbar", "baz"]);
+
+    // Line where the selection ends at its head is not touched
+    let mut t = TextArea::from(["a", "b"]);
+    t.set_hard_tab_indent(true);
+    t.set_selection((0, 0), (1, 0));
+    assert!(t.indent_selection());
+    assert_eq!(t.lines(), ["\ta", "b"]);
+    assert_eq!(t.selection_range(), Some(((0, 0), (1, 0))));
+
+    // Cursor line without selection
+    let mut t = TextArea::from(["a", "b"]);
+    t.move_cursor(CursorMove::Down);
+    assert!(t.indent_selection());
+    assert_eq!(t.lines(), ["a", "    b"]);
+    assert_eq!(t.cursor(), (1, 0));
+}
+
+#[test]
+fn test_dedent_mixed_indentation() {
+    #[rustfmt::skip]
+    let tests = [
+        // (line, tab length, line after)
+        ("      foo", 4, "  foo"),
+        ("\t\tfoo", 4, "\tfoo"),
+        ("  \tfoo", 4, "foo"),
+        ("\t  foo", 4, "  foo"),
+        ("   foo", 4, "foo"),
+        (" \t foo", 2, " foo"),
+        ("  foo", 2, "foo"),
+        ("foo", 4, "foo"),
+        ("    ", 2, "  "),
+    ];
+
+    for test in tests {
+        let (line, tab_len, after) = test;
+        let mut t = TextArea::from([line]);
+        t.set_tab_length(tab_len);
+        t.select_all();
+        assert_eq!(t.dedent_selection(), line != after, "{test:?}");
+        assert_eq!(t.lines(), [after], "{test:?}");
+    }
+}
+
+#[test]
+fn test_move_lines() {
+    let mut t = TextArea::from(["a", "bc", "d"]);
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert!(t.move_lines_up());
+    assert_eq!(t.lines(), ["bc", "a", "d"]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert!(!t.move_lines_up());
+    assert_eq!(t.lines(), ["bc", "a", "d"]);
+
+    assert!(t.move_lines_down());
+    assert!(t.move_lines_down());
+    assert_eq!(t.lines(), ["a", "d", "bc"]);
+    assert_eq!(t.cursor(), (2, 1));
+    assert!(!t.move_lines_down());
+
+    // Each move is one undo entry
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "bc", "d"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["bc", "a", "d"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "bc", "d"]);
+    assert!(!t.undo());
+
+    // Alt+Up and Alt+Down
+    let alt = |key| Input {
+        key,
+        alt: true,
+        ..Default::default()
+    };
+    let mut t = TextArea::from(["a", "b"]);
+    assert!(t.input(alt(Key::Down)));
+    assert_eq!(t.lines(), ["b", "a"]);
+    assert!(t.input(alt(Key::Up)));
+    assert_eq!(t.lines(), ["a", "b"]);
+}
+
+#[test]
+fn test_move_lines_with_selection() {
+    let mut t = TextArea::from(["a", "b", "c", "d", "e"]);
+    t.set_selection((1, 1), (2, 1));
+    assert!(t.move_lines_down());
+    assert_eq!(t.lines(), ["a", "d", "b", "c", "e"]);
+    assert_eq!(t.selection_range(), Some(((2, 1), (3, 1))));
+    assert!(t.move_lines_up());
+    assert!(t.move_lines_up());
+    assert_eq!(t.lines(), ["b", "c", "a", "d", "e"]);
+    assert_eq!(t.selection_range(), Some(((0, 1), (1, 1))));
+    assert!(!t.move_lines_up());
+
+    // Line where the selection ends at column 0 is not moved, but the selection end follows the block
+    let mut t = TextArea::from(["a", "b", "c", "d", "e"]);
+    t.set_selection((1, 0), (3, 0));
+    assert!(t.move_lines_down());
+    assert_eq!(t.lines(), ["a", "d", "b", "c", "e"]);
+    assert_eq!(t.selection_range(), Some(((2, 0), (4, 0))));
+    assert!(t.move_lines_up());
+    assert!(t.move_lines_up());
+    assert_eq!(t.lines(), ["b", "c", "a", "d", "e"]);
+    assert_eq!(t.selection_range(), Some(((0, 0), (2, 0))));
+
+    // Backward selection ending at column 0 of the following line
+    let mut t = TextArea::from(["a", "b", "c"]);
+    t.set_selection((2, 0), (0, 0));
+    assert!(t.move_lines_down());
+    assert_eq!(t.lines(), ["c", "a", "b"]);
+    assert_eq!(t.cursor(), (1, 0));
+    assert_eq!(t.selection_range(), Some(((1, 0), (2, 1))));
+    assert!(!t.move_lines_down());
+
+    // Single line selection ending at column 0 of the same line moves the line
+    let mut t = TextArea::from(["a", "b"]);
+    t.set_selection((0, 0), (0, 0));
+    assert!(t.move_lines_down());
+    assert_eq!(t.lines(), ["b", "a"]);
+
+    // Undo reverts the whole block move
+    let mut t = TextArea::from(["a", "b", "c", "d"]);
+    t.set_selection((1, 0), (3, 0));
+    assert!(t.move_lines_up());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "b", "c", "d"]);
+    assert!(!t.undo());
+}
+
+#[test]
+fn test_duplicate_lines() {
+    let mut t = TextArea::from(["abc", "def"]);
+    t.set_yank_text("yank");
+    t.move_cursor(CursorMove::Jump(1, 2));
+    assert!(t.duplicate_lines());
+    assert_eq!(t.lines(), ["abc", "def", "def"]);
+    assert_eq!(t.cursor(), (2, 2));
+    assert_eq!(t.yank_text(), "yank");
+
+    // Backward selection over multiple lines
+    let mut t = TextArea::from(["a", "bc", "d", "e"]);
+    t.set_selection((2, 1), (0, 1));
+    assert!(t.duplicate_lines());
+    assert_eq!(t.lines(), ["a", "bc", "d", "a", "bc", "d", "e"]);
+    assert_eq!(t.cursor(), (3, 1));
+    assert_eq!(t.selection_range(), Some(((3, 1), (5, 1))));
+
+    // One undo reverts the duplication and restores the cursor
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "bc", "d", "e"]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert!(t.redo());
+    assert_eq!(t.lines(), ["a", "bc", "d", "a", "bc", "d", "e"]);
+
+    // Line where the selection ends at column 0 is not duplicated
+    let mut t = TextArea::from(["a", "b", "c"]);
+    t.set_selection((0, 0), (1, 0));
+    assert!(t.duplicate_lines());
+    assert_eq!(t.lines(), ["a", "a", "b", "c"]);
+    assert_eq!(t.selection_range(), Some(((1, 0), (2, 0))));
+
+    // Duplicating the last line
+    let mut t = TextArea::from(["a", "b"]);
+    t.move_cursor(CursorMove::Bottom);
+    assert!(t.duplicate_lines());
+    assert_eq!(t.lines(), ["a", "b", "b"]);
+    assert_eq!(t.cursor(), (2, 0));
+
+    // Not duplicated when the copy exceeds the max number of characters
+    let mut t = TextArea::from(["abc"]);
+    t.set_max_chars(Some(6));
+    assert!(!t.duplicate_lines());
+    assert_eq!(t.lines(), ["abc"]);
+    t.set_max_chars(Some(7));
+    assert!(t.duplicate_lines());
+    assert_eq!(t.lines(), ["abc", "abc"]);
+
+    // Ctrl+D
+    let mut t = TextArea::from(["a", "b"]);
+    t.set_selection((0, 0), (1, 1));
+    assert!(t.input(Input {
+        key: Key::Char('d'),
+        ctrl: true,
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["a", "b", "a", "b"]);
+}
+
+#[test]
+fn test_copy_masked_text() {
+    let mut t = TextArea::from(["pass", "word"]);
+    t.set_mask_char('*');
+    t.select_all();
+    t.copy();
+    assert_eq!(t.yank_text(), "pass\nword");
+
+    t.cut();
+    assert_eq!(t.lines(), [""]);
+    assert_eq!(t.yank_text(), "pass\nword");
+}
+
+#[test]
+fn test_single_line() {
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.set_single_line(true);
+    assert_eq!(t.lines(), ["ab cd"]);
+    assert_eq!(t.cursor(), (0, 5));
+    assert!(!t.undo());
+
+    assert!(!t.insert_newline());
+    assert!(!t.insert_char('\n'));
+    assert!(!t.duplicate_lines());
+    assert_eq!(t.lines(), ["ab cd"]);
+
+    t.set_newline_replacement(", ");
+    assert!(t.insert_str("\nx\r\ny"));
+    assert_eq!(t.lines(), ["ab cd, x, y"]);
+    assert!(t.paste_str("\rz"));
+    assert_eq!(t.lines(), ["ab cd, x, y, z"]);
+    t.set_yank_text("1\n2");
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["ab cd, x, y, z1, 2"]);
+
+    // Inserted text is replaced before truncated to the max number of characters
+    let mut t = TextArea::default();
+    t.set_single_line(true);
+    t.set_newline_replacement("--");
+    t.set_max_chars(Some(4));
+    assert!(t.insert_str("a\nbcd"));
+    assert_eq!(t.lines(), ["a--b"]);
+}
+
+#[test]
+fn test_validator() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut t = TextArea::default();
+    {
+        let count = count.clone();
+        t.set_validator(move |lines| {
+            count.fetch_add(1, Ordering::Relaxed);
+            if lines.len() == 1 && lines[0].len() <= 3 {
+                Ok(())
+            } else {
+                Err(format!("{} lines", lines.len()))
+            }
+        });
+    }
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+    assert_eq!(t.validation_error(), None);
+
+    let key = |key, ctrl| Input {
+        key,
+        ctrl,
+        ..Default::default()
+    };
+    for c in "abcd".chars() {
+        assert!(t.input(key(Key::Char(c), false)));
+    }
+    assert_eq!(count.load(Ordering::Relaxed), 5);
+    assert_eq!(t.validation_error(), Some("1 lines"));
+
+    // Validation runs once per undo/redo input
+    assert!(t.input(key(Key::Char('z'), true)));
+    assert_eq!(count.load(Ordering::Relaxed), 6);
+    assert_eq!(t.lines(), [""]);
+    assert_eq!(t.validation_error(), None);
+    assert!(t.input(key(Key::Char('y'), true)));
+    assert_eq!(count.load(Ordering::Relaxed), 7);
+    assert_eq!(t.validation_error(), Some("1 lines"));
+
+    // Inputs which don't modify the text don't run validation
+    assert!(!t.input(key(Key::Left, false)));
+    assert_eq!(count.load(Ordering::Relaxed), 7);
+
+    assert!(t.input_without_shortcuts(key(Key::Char('\n'), false)));
+    assert_eq!(t.validation_error(), Some("2 lines"));
+    assert!(t.single_line_input(key(Key::Backspace, false)));
+    assert_eq!(t.validation_error(), Some("1 lines"));
+    assert_eq!(count.load(Ordering::Relaxed), 9);
+
+    t.clear_validator();
+    assert_eq!(t.validation_error(), None);
+    assert!(t.validate());
+    assert_eq!(count.load(Ordering::Relaxed), 9);
+}
+
+#[test]
+fn test_apply_edit() {
+    #[rustfmt::skip]
+    let tests = [
+        // (start, end, text, lines after edit)
+        ((0, 1), (0, 3), "X", &["aXd", "efgh", "ijkl"][..]),
+        ((0, 1), (0, 1), "X\nY", &["aX", "Ybcd", "efgh", "ijkl"]),
+        ((0, 2), (2, 2), "", &["abkl"]),
+        ((0, 2), (2, 2), "X\nY\nZ", &["abX", "Y", "Zkl"]),
+        ((1, 0), (2, 0), "", &["abcd", "ijkl"]),
+        ((0, 4), (1, 0), "", &["abcdefgh", "ijkl"]),
+        ((2, 4), (2, 4), "\n", &["abcd", "efgh", "ijkl", ""]),
+        ((2, 4), (2, 4), "mn\nop", &["abcd", "efgh", "ijklmn", "op"]),
+        ((0, 0), (2, 4), "", &[""]),
+        ((0, 0), (2, 4), "x\r\ny", &["x", "y"]),
+        ((1, 1), (1, 3), "FG", &["abcd", "eFGh", "ijkl"]),
+    ];
+
+    for (start, end, text, want) in tests {
+        let mut t = TextArea::from(["abcd", "efgh", "ijkl"]);
+        assert!(t.apply_edit(start, end, text), "{start:?} {end:?} {text:?}");
+        assert_eq!(t.lines(), want, "{start:?} {end:?} {text:?}");
+        assert!(t.undo(), "{start:?} {end:?} {text:?}");
+        assert_eq!(t.lines(), ["abcd", "efgh", "ijkl"], "{start:?} {end:?} {text:?}");
+        assert!(t.redo(), "{start:?} {end:?} {text:?}");
+        assert_eq!(t.lines(), want, "{start:?} {end:?} {text:?}");
+    }
+
+    let mut t = TextArea::from(["abcd", "efgh"]);
+    assert!(!t.apply_edit((0, 1), (0, 1), ""));
+    assert!(!t.apply_edit((0, 2), (0, 1), "x"));
+    assert!(!t.apply_edit((0, 5), (0, 5), "x"));
+    assert!(!t.apply_edit((2, 0), (2, 0), "x"));
+    assert!(!t.apply_edit((0, 0), (1, 5), "x"));
+    assert_eq!(t.lines(), ["abcd", "efgh"]);
+}
+
+#[test]
+fn test_apply_edit_cursor() {
+    #[rustfmt::skip]
+    let tests = [
+        // (cursor, start, end, text, cursor after edit)
+        // Edit after the cursor
+        ((0, 1), (0, 2), (1, 1), "X\nY", (0, 1)),
+        ((0, 2), (0, 2), (0, 2), "X", (0, 2)),
+        // Edit inside the cursor
+        ((0, 3), (0, 2), (1, 1), "X", (0, 2)),
+        ((1, 0), (0, 2), (1, 1), "X\nY", (0, 2)),
+        // Edit before the cursor in the same line
+        ((1, 3), (1, 0), (1, 2), "X", (1, 2)),
+        ((1, 3), (1, 1), (1, 1), "X\nY", (2, 3)),
+        ((1, 3), (0, 2), (1, 1), "X", (0, 5)),
+        ((1, 3), (0, 2), (1, 3), "X\nYZ", (1, 2)),
+        // Edit before the cursor in previous lines
+        ((2, 1), (0, 2), (1, 1), "", (1, 1)),
+        ((2, 1), (0, 0), (0, 0), "X\n\n", (4, 1)),
+        ((2, 1), (0, 1), (1, 4), "X", (1, 1)),
+    ];
+
+    for (cursor, start, end, text, want) in tests {
+        let mut t = TextArea::from(["abcd", "efgh", "ijkl"]);
+        t.move_cursor(CursorMove::Jump(cursor.0 as u64, cursor.1 as u64));
+        assert!(t.apply_edit(start, end, text));
+        assert_eq!(t.cursor(), want, "{cursor:?} {start:?} {end:?} {text:?}");
+        t.undo();
+        assert_eq!(t.cursor(), cursor, "{cursor:?} {start:?} {end:?} {text:?}");
+    }
+
+    // Selection is kept at the same text
+    let mut t = TextArea::from(["abcd", "efgh", "ijkl"]);
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(2, 2));
+    assert!(t.apply_edit((0, 0), (0, 4), "X\nY"));
+    assert_eq!(t.selection_range(), Some(((2, 1), (3, 2))));
+    assert!(t.apply_edit((3, 0), (3, 1), ""));
+    assert_eq!(t.selection_range(), Some(((2, 1), (3, 1))));
+    assert!(t.cut());
+    assert_eq!(t.yank_text(), "fgh\nj");
+}
+
+#[test]
+fn test_apply_edit_distinct_undo() {
+    let mut t = TextArea::default();
+    for c in "abc".chars() {
+        t.insert_char(c);
+    }
+    assert!(t.apply_edit((0, 0), (0, 0), "x"));
+    for c in "def".chars() {
+        t.insert_char(c);
+    }
+    assert_eq!(t.lines(), ["xabcdef"]);
+    t.undo();
+    assert_eq!(t.lines(), ["xabc"]);
+    t.undo();
+    assert_eq!(t.lines(), ["abc"]);
+    t.undo();
+    assert_eq!(t.lines(), [""]);
+}
+
+#[test]
+fn test_apply_edit_mirrors_tracked_edits() {
+    let mut t = TextArea::from(["hello", "world"]);
+    let mut mirror = t.clone();
+    t.set_edit_tracking(true);
+
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.insert_str("xy\nz");
+    t.delete_line_by_head();
+    t.move_lines_down();
+    t.delete_newline();
+    t.undo();
+    t.paste();
+
+    for edit in t.take_edits() {
+        mirror.apply_edit(edit.start, edit.end, &edit.inserted);
+    }
+    assert_eq!(mirror.lines(), t.lines());
+}
+
+#[test]
+fn test_kill_ring() {
+    let mut t = TextArea::from(["aaa bbb ccc", "ddd eee"]);
+    t.set_kill_ring_size(3);
+
+    // Kill ring is disabled by default
+    let mut u = TextArea::from(["abc"]);
+    assert!(u.delete_next_word());
+    assert!(u.kill_ring().is_empty());
+
+    // Forward kills are appended
+    t.move_cursor(CursorMove::Jump(0, 4));
+    assert!(t.delete_next_word());
+    assert!(t.delete_next_word());
+    assert_eq!(t.kill_ring(), ["bbb "]);
+    assert!(t.delete_line_by_end());
+    assert_eq!(t.kill_ring(), ["bbb ccc"]);
+    assert_eq!(t.yank_text(), "bbb ccc");
+    assert_eq!(t.lines(), ["aaa ", "ddd eee"]);
+
+    // Backward kills are prepended
+    assert!(t.delete_word());
+    assert!(t.delete_word());
+    assert_eq!(t.kill_ring(), ["aaa ", "bbb ccc"]);
+
+    // Other edits between kills start a new entry
+    t.move_cursor(CursorMove::Down);
+    t.move_cursor(CursorMove::End);
+    assert!(t.delete_word());
+    assert!(t.insert_str("x"));
+    assert!(t.delete_word());
+    assert_eq!(t.kill_ring(), ["x", "eee", "aaa "]);
+
+    // Moving the cursor starts a new entry
+    t.move_cursor(CursorMove::Back);
+    assert!(t.delete_word());
+    assert_eq!(t.kill_ring(), ["ddd", "x", "eee"]);
+    assert_eq!(t.lines(), ["", " "]);
+
+    // Kills in the different direction start a new entry
+    let mut t = TextArea::from(["aaa bbb"]);
+    t.set_kill_ring_size(3);
+    t.move_cursor(CursorMove::Jump(0, 4));
+    assert!(t.delete_next_word());
+    assert!(t.delete_word());
+    assert_eq!(t.kill_ring(), [" ", "bbb"]);
+    t.undo();
+    t.undo();
+    t.move_cursor(CursorMove::Jump(0, 4));
+    assert!(t.delete_line_by_end());
+    assert_eq!(t.kill_ring(), ["bbb", " ", "bbb"]);
+
+    // Cut and copy always add new entries. The oldest entry is dropped
+    let mut t = TextArea::from(["abc", "def"]);
+    t.set_kill_ring_size(2);
+    assert!(t.cut());
+    t.copy();
+    assert_eq!(t.kill_ring(), ["def", "abc"]);
+    assert!(t.cut());
+    assert_eq!(t.kill_ring(), ["def", "def"]);
+    t.set_kill_ring_size(1);
+    assert_eq!(t.kill_ring(), ["def"]);
+}
+
+#[test]
+fn test_paste_rotate() {
+    let mut t = TextArea::default();
+    t.set_kill_ring_size(10);
+    for text in ["a\nb", "c", "d"] {
+        t.set_yank_text(text);
+    }
+    assert!(!t.paste_rotate()); // Nothing was pasted
+
+    assert!(t.insert_str("x"));
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["xd"]);
+    assert!(t.paste_rotate());
+    assert_eq!(t.lines(), ["xc"]);
+    assert!(t.paste_rotate());
+    assert_eq!(t.lines(), ["xa", "b"]);
+    assert_eq!(t.cursor(), (1, 1));
+    assert!(t.paste_rotate());
+    assert_eq!(t.lines(), ["xd"]);
+    assert_eq!(t.cursor(), (0, 2));
+    assert_eq!(t.kill_ring(), ["d", "c", "a\nb"]);
+    assert_eq!(t.yank_text(), "d");
+
+    // Rotation after moving the cursor does nothing
+    t.move_cursor(CursorMove::Back);
+    assert!(!t.paste_rotate());
+    assert!(t.paste());
+    assert_eq!(t.lines(), ["xdd"]);
+
+    // Rotation after other edits does nothing
+    assert!(t.insert_char('y'));
+    assert!(!t.paste_rotate());
+
+    // Alt+Y inserts the character by default and rotates the pasted text once it is bound
+    let key = |c, ctrl, alt| Input {
+        key: Key::Char(c),
+        ctrl,
+        alt,
+        shift: false,
+    };
+    let mut t = TextArea::default();
+    t.set_kill_ring_size(10);
+    t.set_yank_text("foo");
+    t.set_yank_text("bar");
+    assert!(t.input(key('v', true, false)));
+    assert!(t.input(key('y', false, true)));
+    assert_eq!(t.lines(), ["bary"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["bar"]);
+    t.keymap_mut().insert(key('y', false, true), EditAction::PasteRotate);
+    assert!(t.input(key('v', true, false)));
+    assert!(t.input(key('y', false, true)));
+    assert_eq!(t.lines(), ["barfoo"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["barbar"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["bar"]);
+}
+
+#[test]
+fn test_transform_selection() {
+    #[rustfmt::skip]
+    let tests = [
+        // (lines, cursor, selection end, transform, lines after, cursor after)
+        (&["foo bar"][..], (0, 5), None, TextTransform::Uppercase, &["foo BAR"][..], (0, 5)),
+        (&["foo bar"][..], (0, 7), None, TextTransform::TitleCase, &["foo Bar"][..], (0, 7)),
+        (&["größe"][..], (0, 4), None, TextTransform::Uppercase, &["GRÖSSE"][..], (0, 4)),
+        (&["aB cD", "eF"][..], (0, 1), Some((1, 1)), TextTransform::ToggleCase, &["ab Cd", "EF"][..], (1, 1)),
+        (&["fooBar", "bazQux"][..], (1, 6), Some((0, 0)), TextTransform::SnakeCase, &["foo_bar", "baz_qux"][..], (0, 0)),
+        (&["x = foo_bar"][..], (0, 6), None, TextTransform::CamelCase, &["x = fooBar"][..], (0, 6)),
+        (&["ß"][..], (0, 0), Some((0, 1)), TextTransform::Uppercase, &["SS"][..], (0, 2)),
+    ];
+    for test in tests {
+        let (lines, cursor, end, transform, after, cursor_after) = test;
+        let mut t = TextArea::from(lines.iter().copied());
+        match end {
+            Some(end) => t.set_selection(cursor, end),
+            None => t.move_cursor(CursorMove::Jump(cursor.0 as u64, cursor.1 as u64)),
+        }
+        assert!(t.transform_selection(transform), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.cursor(), cursor_after, "{test:?}");
+        assert_eq!(t.is_selecting(), end.is_some(), "{test:?}");
+
+        // One undo unit
+        assert!(t.undo(), "{test:?}");
+        assert_eq!(t.lines(), lines, "{test:?}");
+        assert!(!t.undo(), "{test:?}");
+    }
+
+    // Nothing to transform
+    let mut t = TextArea::from(["a + b", "FOO"]);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    assert!(!t.transform_selection(TextTransform::Uppercase));
+    t.set_selection((1, 0), (1, 3));
+    assert!(!t.transform_selection(TextTransform::Uppercase));
+    assert_eq!(t.selection_range(), Some(((1, 0), (1, 3))));
+}
+
+#[test]
+fn test_transpose_chars() {
+    let tests = [
+        // (line, cursor col, after, cursor col)
+        ("abc", 1, Some("bac"), 2),
+        ("abc", 2, Some("acb"), 3),
+        ("abc", 3, Some("acb"), 3),
+        ("あいう", 1, Some("いあう"), 2),
+        // Flags and combining marks are not split
+        ("a🇯🇵b", 1, Some("🇯🇵ab"), 3),
+        ("a🇯🇵b", 3, Some("ab🇯🇵"), 4),
+        ("e\u{301}x", 2, Some("xe\u{301}"), 3),
+        ("a🇯🇵", 3, Some("🇯🇵a"), 3),
+        // The cursor in the middle of a grapheme cluster
+        ("a🇯🇵b", 2, Some("🇯🇵ab"), 3),
+        ("abc", 0, None, 0),
+        ("a", 1, None, 1),
+        ("", 0, None, 0),
+    ];
+    for (line, col, after, want_col) in tests {
+        let mut t = TextArea::from([line]);
+        t.move_cursor(CursorMove::Jump(0, col));
+        let test = (line, col);
+        assert_eq!(t.transpose_chars(), after.is_some(), "{test:?}");
+        assert_eq!(t.lines(), [after.unwrap_or(line)], "{test:?}");
+        assert_eq!(t.cursor(), (0, want_col), "{test:?}");
+        if after.is_some() {
+            assert!(t.undo(), "{test:?}");
+            assert_eq!(t.lines(), [line], "{test:?}");
+            assert!(!t.undo(), "{test:?}");
+        }
+    }
+
+    // Ctrl+T inserts the character by default and swaps once it is bound
+    let ctrl_t = Input {
+        key: Key::Char('t'),
+        ctrl: true,
+        ..Default::default()
+    };
+    let mut t = TextArea::from(["ab"]);
+    t.move_cursor(CursorMove::End);
+    assert!(t.input(ctrl_t.clone()));
+    assert_eq!(t.lines(), ["abt"]);
+    t.keymap_mut().insert(ctrl_t.clone(), EditAction::TransposeChars);
+    assert!(t.input(ctrl_t));
+    assert_eq!(t.lines(), ["atb"]);
+}
+
+#[test]
+fn test_transpose_words() {
+    let tests = [
+        // (line, cursor col, after, cursor col)
+        ("foo bar", 0, Some("bar foo"), 7),
+        ("foo bar", 4, Some("bar foo"), 7),
+        ("foo bar", 7, Some("bar foo"), 7),
+        ("foo bar baz", 5, Some("foo baz bar"), 11),
+        ("foo bar baz", 11, Some("foo baz bar"), 11),
+        // Whitespaces and punctuations between words are kept
+        ("(foo, bar)", 5, Some("(bar, foo)"), 9),
+        ("  foo  ->  bar!", 0, Some("  bar  ->  foo!"), 14),
+        ("あ い", 0, Some("い あ"), 3),
+        ("foo", 0, None, 0),
+        ("foo ()", 0, None, 0),
+        ("", 0, None, 0),
+    ];
+    for (line, col, after, want_col) in tests {
+        let mut t = TextArea::from([line]);
+        t.move_cursor(CursorMove::Jump(0, col));
+        let test = (line, col);
+        assert_eq!(t.transpose_words(), after.is_some(), "{test:?}");
+        assert_eq!(t.lines(), [after.unwrap_or(line)], "{test:?}");
+        assert_eq!(t.cursor(), (0, want_col), "{test:?}");
+        if after.is_some() {
+            assert!(t.undo(), "{test:?}");
+            assert_eq!(t.lines(), [line], "{test:?}");
+            assert!(!t.undo(), "{test:?}");
+        }
+    }
+
+    // Alt+T inserts the character by default and swaps once it is bound
+    let alt_t = Input {
+        key: Key::Char('t'),
+        alt: true,
+        ..Default::default()
+    };
+    let mut t = TextArea::from(["a b"]);
+    assert!(t.input(alt_t.clone()));
+    assert_eq!(t.lines(), ["ta b"]);
+    t.keymap_mut().insert(alt_t.clone(), EditAction::TransposeWords);
+    assert!(t.input(alt_t));
+    assert_eq!(t.lines(), ["b ta"]);
+}
+
+#[test]
+fn test_unicode_input() {
+    let key = |key| Input { key, ..Default::default() };
+    let type_keys = |t: &mut TextArea, keys: &str| {
+        for c in keys.chars() {
+            t.input(key(Key::Char(c)));
+        }
+    };
+
+    let mut t = TextArea::default();
+    t.insert_str("ab");
+    t.start_unicode_input();
+    assert_eq!(t.unicode_input(), Some(""));
+    type_keys(&mut t, "1F1");
+    assert_eq!(t.unicode_input(), Some("1F1"));
+    assert!(!t.input(key(Key::Backspace)));
+    type_keys(&mut t, "60000");
+    // Digits more than 6 are ignored
+    assert_eq!(t.unicode_input(), Some("1F6000"));
+    assert!(!t.input(key(Key::Backspace)));
+    assert_eq!(t.lines(), ["ab"]);
+    assert!(t.input(key(Key::Enter)));
+    assert_eq!(t.lines(), ["ab😀"]);
+    assert_eq!(t.cursor(), (0, 3));
+    assert_eq!(t.unicode_input(), None);
+
+    // The committed character is one undo entry separated from typed text
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["ab"]);
+    assert!(t.redo());
+
+    // A non-hex key commits the character and is handled as usual
+    t.start_unicode_input();
+    type_keys(&mut t, "e9");
+    assert!(t.input(key(Key::Left)));
+    assert_eq!(t.lines(), ["ab😀é"]);
+    assert_eq!(t.cursor(), (0, 3));
+
+    // Invalid code points are not inserted
+    for digits in ["d800", "110000", ""] {
+        t.start_unicode_input();
+        type_keys(&mut t, digits);
+        assert!(!t.input(key(Key::Enter)), "{digits:?}");
+        assert_eq!(t.lines(), ["ab😀é"], "{digits:?}");
+    }
+
+    // Esc cancels composing. Backspace without digits also cancels it
+    t.start_unicode_input();
+    type_keys(&mut t, "41");
+    assert!(!t.input(key(Key::Esc)));
+    assert_eq!(t.unicode_input(), None);
+    t.start_unicode_input();
+    assert!(!t.input(key(Key::Backspace)));
+    assert_eq!(t.unicode_input(), None);
+    assert_eq!(t.lines(), ["ab😀é"]);
+
+    // Enter commits the character instead of submitting in single-line mode
+    let mut t = TextArea::default();
+    t.set_single_line(true);
+    t.start_unicode_input();
+    type_keys(&mut t, "41");
+    assert_eq!(t.handle_input(key(Key::Enter)), InputResult::Modified);
+    assert_eq!(t.lines(), ["A"]);
+    assert_eq!(t.handle_input(key(Key::Enter)), InputResult::Submit);
+}
+
+#[test]
+fn test_handle_paste() {
+    let mut t = TextArea::default();
+    t.set_auto_pairs(true);
+    t.input(Input {
+        key: Key::Char('a'),
+        ..Default::default()
+    });
+
+    // Pasted text is inserted verbatim without auto-pairs and is not merged with typed characters
+    assert!(t.handle_paste("(b\r\n\tc\rd\n"));
+    assert_eq!(t.lines(), ["a(b", "\tc", "d", ""]);
+    assert_eq!(t.cursor(), (3, 0));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a"]);
+
+    // The selection is replaced in one undo entry
+    t.select_all();
+    assert!(t.handle_paste("x\ny"));
+    assert_eq!(t.lines(), ["x", "y"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a"]);
+    assert!(!t.handle_paste(""));
+
+    // Newlines are replaced in single-line mode
+    t.set_single_line(true);
+    assert!(t.handle_paste("b\r\nc"));
+    assert_eq!(t.lines(), ["ab c"]);
+
+    t.set_read_only(true);
+    assert!(!t.handle_paste("d"));
+    assert_eq!(t.lines(), ["ab c"]);
+}
+
+#[test]
+fn test_keymap() {
+    let ctrl = |c| Input {
+        key: Key::Char(c),
+        ctrl: true,
+        ..Default::default()
+    };
+
+    let mut t = TextArea::from(["abc"]);
+    t.move_cursor(CursorMove::Jump(0, 1));
+
+    // Ignored binding does nothing
+    t.keymap_mut().insert(ctrl('k'), EditAction::Ignore);
+    assert!(!t.input(ctrl('k')));
+    assert_eq!(t.lines(), ["abc"]);
+
+    // Removed binding falls back to inserting the character like other unbound characters
+    t.keymap_mut().remove(&ctrl('k'));
+    assert!(t.input(ctrl('k')));
+    assert_eq!(t.lines(), ["akbc"]);
+    assert!(t.input(ctrl('z')));
+    assert_eq!(t.lines(), ["abc"]);
+
+    // Rebind undo to another key
+    t.keymap_mut().insert(ctrl('z'), EditAction::Ignore);
+    t.keymap_mut().insert(ctrl('u'), EditAction::Undo);
+    assert!(t.input(ctrl('j')));
+    assert_eq!(t.lines(), ["bc"]);
+    assert!(!t.input(ctrl('z')));
+    assert!(t.input(ctrl('u')));
+    assert_eq!(t.lines(), ["abc"]);
+
+    // Custom bindings
+    t.keymap_mut().insert(
+        Input {
+            key: Key::F(2),
+            ..Default::default()
+        },
+        EditAction::InsertChar('!'),
+    );
+    t.keymap_mut().insert(ctrl('l'), EditAction::Scroll(Scrolling::Bottom));
+    assert!(t.input(Input {
+        key: Key::F(2),
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["a!bc"]);
+
+    // Characters with Ctrl+Alt are inserted for AltGr
+    assert!(t.input(Input {
+        key: Key::Char('@'),
+        ctrl: true,
+        alt: true,
+        shift: false,
+    }));
+    assert_eq!(t.lines(), ["a!@bc"]);
+    assert!(!t.input(ctrl('l')));
+    assert_eq!(t.cursor(), (0, 0));
+
+    // Modifying actions are ignored while read-only and cut is handled as copy
+    t.set_read_only(true);
+    t.select_all();
+    assert!(!t.input(ctrl('x')));
+    assert_eq!(t.lines(), ["a!@bc"]);
+    assert_eq!(t.yank_text(), "a!@bc");
+    assert!(!t.input(Input {
+        key: Key::F(2),
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["a!@bc"]);
+    t.set_read_only(false);
+
+    // Multi-line actions are not performed in single-line input
+    let mut t = TextArea::from(["ab"]);
+    t.keymap_mut().insert(ctrl('o'), EditAction::InsertNewline);
+    t.keymap_mut()
+        .insert(ctrl('b'), EditAction::MoveCursor(CursorMove::Head));
+    assert!(!t.single_line_input(ctrl('o')));
+    assert_eq!(t.lines(), ["ab"]);
+    t.single_line_input(ctrl('b'));
+    assert_eq!(t.cursor(), (0, 0));
+
+    *t.keymap_mut() = default_keymap();
+    assert!(t.input(ctrl('o'))); // Not bound by default
+    assert_eq!(t.lines(), ["oab"]);
+    assert!(t.input(ctrl('k')));
+    assert_eq!(t.lines(), ["o"]);
+}
+
+#[test]
+fn test_key_sequences() {
+    let keys = |s: &str| -> Vec<Input> { s.split(' ').map(|k| k.parse().unwrap()).collect() };
+    let type_keys = |t: &mut TextArea, s: &str| {
+        let mut modified = false;
+        for input in keys(s) {
+            modified |= t.input(input);
+        }
+        modified
+    };
+
+    let mut t = TextArea::from(["abc"]);
+    t.key_sequences_mut()
+        .insert(keys("ctrl+x ctrl+e"), EditAction::MoveCursor(CursorMove::End));
+    t.key_sequences_mut()
+        .insert(keys("ctrl+q r t"), EditAction::TransposeChars);
+    // Ctrl+Q alone does nothing instead of inserting the character
+    t.keymap_mut().insert(keys("ctrl+q")[0].clone(), EditAction::Ignore);
+
+    // Complete sequence
+    assert!(!t.input(keys("ctrl+x")[0].clone()));
+    assert_eq!(t.pending_keys(), keys("ctrl+x"));
+    assert!(!t.input(keys("ctrl+e")[0].clone()));
+    assert!(t.pending_keys().is_empty());
+    assert_eq!(t.cursor(), (0, 3));
+
+    // Ctrl+X is bound to cut. It is performed when the next key does not continue any sequence
+    t.select_all();
+    assert!(type_keys(&mut t, "ctrl+x d"));
+    assert_eq!(t.lines(), ["d"]);
+    assert!(t.pending_keys().is_empty());
+
+    // Prefix stops matching. Buffered keys are replayed and the last key is handled as a new input
+    assert!(type_keys(&mut t, "ctrl+q r x"));
+    assert_eq!(t.lines(), ["drx"]);
+    assert!(type_keys(&mut t, "ctrl+q r t"));
+    assert_eq!(t.lines(), ["dxr"]);
+
+    // The key stopping the prefix can start a new sequence
+    assert!(type_keys(&mut t, "ctrl+q r ctrl+x"));
+    assert_eq!(t.lines(), ["dxrr"]);
+    assert_eq!(t.pending_keys(), keys("ctrl+x"));
+    t.cancel_pending_keys();
+    assert!(t.pending_keys().is_empty());
+
+    // Discard buffered keys
+    t.set_replay_pending_keys(false);
+    assert!(type_keys(&mut t, "ctrl+q r y"));
+    assert_eq!(t.lines(), ["dxrry"]);
+
+    // Flush pending keys
+    assert!(!type_keys(&mut t, "ctrl+q r"));
+    assert!(!t.flush_pending_keys());
+    assert!(t.pending_keys().is_empty());
+    assert_eq!(t.lines(), ["dxrry"]);
+    t.set_replay_pending_keys(true);
+    assert!(!type_keys(&mut t, "ctrl+q r"));
+    assert!(t.flush_pending_keys());
+    assert_eq!(t.lines(), ["dxrryr"]);
+
+    // Timed out keys are resolved before the next key
+    t.set_pending_keys_timeout(Some(Duration::ZERO));
+    assert!(type_keys(&mut t, "ctrl+q r"));
+    assert!(t.pending_keys().is_empty());
+    assert_eq!(t.lines(), ["dxrryrr"]);
+    t.set_pending_keys_timeout(None);
+
+    // Mouse inputs resolve pending keys
+    t.key_sequences_mut()
+        .insert(keys("g g"), EditAction::MoveCursor(CursorMove::Head));
+    assert!(!type_keys(&mut t, "g"));
+    assert!(t.input(Input {
+        key: Key::MouseClick(0, 0),
+        ..Default::default()
+    }));
+    assert_eq!(t.lines(), ["dxrryrrg"]);
+
+    // Multi-line actions bound to sequences are skipped in single-line input
+    let mut t = TextArea::from(["ab"]);
+    t.key_sequences_mut().insert(keys("g g"), EditAction::InsertNewline);
+    for input in keys("g g") {
+        assert!(!t.single_line_input(input));
+    }
+    assert_eq!(t.lines(), ["ab"]);
+}
+
+#[test]
+fn test_macro() {
+    let inputs = |s: &str| -> Vec<Input> { s.split(' ').map(|k| k.parse().unwrap()).collect() };
+
+    let mut t = TextArea::from(["foo bar", "baz qux", "quux"]);
+    t.keymap_mut()
+        .insert(inputs("alt+t")[0].clone(), EditAction::TransposeWords);
+    assert_eq!(t.stop_macro_recording(), []);
+
+    // Swap the first two words and go to the next line
+    t.start_macro_recording();
+    for input in inputs("ctrl+right alt+t down home") {
+        t.input(input);
+    }
+    // Ignored inputs are not recorded
+    t.set_ignore_input_when_unfocused(true);
+    t.set_focus(false);
+    assert!(!t.input(inputs("x")[0].clone()));
+    t.set_focus(true);
+    let recorded = t.stop_macro_recording();
+    assert_eq!(recorded, inputs("ctrl+right alt+t down home"));
+    assert_eq!(t.lines(), ["bar foo", "baz qux", "quux"]);
+
+    assert_eq!(t.replay(&recorded, 0), 0);
+    assert_eq!(t.replay(&[], 3), 0);
+
+    // The replay stops when the cursor cannot move down
+    assert_eq!(t.replay(&recorded, 5), 2);
+    assert_eq!(t.lines(), ["bar foo", "qux baz", "quux"]);
+    assert_eq!(t.cursor(), (2, 4));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["bar foo", "baz qux", "quux"]);
+    assert!(t.redo());
+    assert_eq!(t.lines(), ["bar foo", "qux baz", "quux"]);
+
+    // Replayed inputs are not recorded again
+    let mut t = TextArea::default();
+    t.start_macro_recording();
+    t.input(inputs("a")[0].clone());
+    assert_eq!(t.replay(&inputs("b c"), 2), 2);
+    t.single_line_input(inputs("d")[0].clone());
+    assert_eq!(t.stop_macro_recording(), inputs("a d"));
+    assert_eq!(t.lines(), ["abcbcd"]);
+}
+
+#[test]
+fn test_perform_n() {
+    // (action, count, lines before, cursor before, lines after, cursor after)
+    type Test = (
+        EditAction,
+        usize,
+        &'static [&'static str],
+        (usize, usize),
+        &'static [&'static str],
+        (usize, usize),
+    );
+    let tests: &[Test] = &[
+        (EditAction::DeleteNextChar, 3, &["abcde"], (0, 1), &["ae"], (0, 1)),
+        (EditAction::DeleteNextChar, 3, &["ab", "cd"], (0, 1), &["ad"], (0, 1)),
+        (EditAction::DeleteNextChar, 100, &["ab", "cd"], (0, 1), &["a"], (0, 1)),
+        (EditAction::DeleteChar, 3, &["ab", "cd"], (1, 1), &["ad"], (0, 1)),
+        (EditAction::DeleteChar, 100, &["ab", "cd"], (1, 1), &["d"], (0, 0)),
+        (EditAction::DeleteNextWord, 3, &["aa bb cc"], (0, 0), &[" cc"], (0, 0)),
+        (
+            EditAction::DeleteNextWord,
+            100,
+            &["aa bb", "cc dd"],
+            (0, 3),
+            &["aa "],
+            (0, 3),
+        ),
+        (EditAction::DeleteWord, 3, &["aa bb cc"], (0, 8), &["aa "], (0, 3)),
+        (
+            EditAction::DeleteWord,
+            100,
+            &["aa bb", "cc dd"],
+            (1, 2),
+            &[" dd"],
+            (0, 0),
+        ),
+        (EditAction::DeleteLine, 2, &["a", "b", "c"], (0, 0), &["c"], (0, 0)),
+        (EditAction::DeleteLine, 2, &["ab", "c", "de"], (1, 0), &["ab"], (0, 0)),
+        (EditAction::DeleteLine, 5, &["ab", "c", "de"], (0, 1), &[""], (0, 0)),
+        (EditAction::DeleteLine, 2, &["a", "bcd", "ef"], (0, 1), &["ef"], (0, 1)),
+        (EditAction::InsertChar('x'), 3, &["ab"], (0, 1), &["axxxb"], (0, 4)),
+        (EditAction::InsertNewline, 2, &["ab"], (0, 1), &["a", "", "b"], (2, 0)),
+        (EditAction::DeleteLineByEnd, 3, &["ab", "cd"], (0, 1), &["a"], (0, 1)),
+        (
+            EditAction::MoveCursor(CursorMove::Down),
+            100,
+            &["a", "b", "c"],
+            (0, 0),
+            &["a", "b", "c"],
+            (2, 0),
+        ),
+        (
+            EditAction::MoveCursor(CursorMove::Forward),
+            4,
+            &["ab", "cd"],
+            (0, 0),
+            &["ab", "cd"],
+            (1, 1),
+        ),
+        (EditAction::TransposeChars, 2, &["abc"], (0, 1), &["bca"], (0, 3)),
+    ];
+
+    for (action, n, before, cursor, after, cursor_after) in tests.iter().copied() {
+        let mut t = TextArea::from(before.iter().copied());
+        t.move_cursor(CursorMove::Jump(cursor.0 as u64, cursor.1 as u64));
+        t.set_edit_tracking(true);
+        let modified = t.perform_n(action, n);
+        assert_eq!(t.lines(), after, "{action:?} x{n} on {before:?}");
+        assert_eq!(t.cursor(), cursor_after, "{action:?} x{n} on {before:?}");
+        assert_eq!(modified, before != after, "{action:?} x{n} on {before:?}");
+
+        let deletes = !matches!(
+            action,
+            EditAction::InsertNewline | EditAction::DeleteLineByEnd | EditAction::TransposeChars
+        );
+        if modified && deletes {
+            assert_eq!(t.take_edits().len(), 1, "{action:?} x{n} on {before:?}");
+        }
+
+        // One undo reverts the entire operation
+        if modified {
+            assert!(t.undo());
+            assert_eq!(t.lines(), before, "{action:?} x{n} on {before:?}");
+            assert!(!t.undo());
+        }
+    }
+
+    // Count 0 does nothing
+    let mut t = TextArea::from(["abc"]);
+    assert!(!t.perform_n(EditAction::DeleteNextChar, 0));
+    assert_eq!(t.lines(), ["abc"]);
+
+    // Cut lines are yanked in the same form as cutting one line
+    let mut t = TextArea::from(["a", "b", "c"]);
+    assert!(t.perform_n(EditAction::Cut, 2));
+    assert_eq!(t.lines(), ["c"]);
+    assert_eq!(t.yank_text(), "a\nb");
+    let mut t = TextArea::from(["a", "b", "c"]);
+    assert!(t.perform_n(EditAction::Cut, 1));
+    assert_eq!(t.yank_text(), "a");
+    assert!(t.perform_n(EditAction::Cut, 5));
+    assert_eq!(t.lines(), [""]);
+    assert_eq!(t.yank_text(), "b\nc");
+}
+
+#[test]
+fn test_line_operations() {
+    type Op = fn(&mut TextArea) -> usize;
+    type Selection = Option<((usize, usize), (usize, usize))>;
+    // (name, operation, lines before, selection, number of affected lines, lines after)
+    type Test = (
+        &'static str,
+        Op,
+        &'static [&'static str],
+        Selection,
+        usize,
+        &'static [&'static str],
+    );
+    #[rustfmt::skip]
+    let tests: [Test; 14] = [
+        ("join all", |t| t.join_lines(), &["a", "  b", "", "\tc  "], None, 4, &["a b c  "]),
+        ("join selected", |t| t.join_lines(), &["a", "  b", "c"], Some(((0, 1), (1, 1))), 2, &["a b", "c"]),
+        ("join selection ending at head", |t| t.join_lines(), &["a", "b", "c"], Some(((0, 0), (2, 0))), 2, &["a b", "c"]),
+        ("join one line", |t| t.join_lines(), &["a", "b"], Some(((0, 0), (0, 1))), 0, &["a", "b"]),
+        ("join empty first line", |t| t.join_lines(), &["", " b"], None, 2, &["b"]),
+        ("sort", |t| t.sort_lines(SortOrder::Ascending), &["b", "B", "a", "A"], None, 2, &["A", "B", "a", "b"]),
+        ("sort desc", |t| t.sort_lines(SortOrder::Descending), &["b", "B", "a", "A"], None, 2, &["b", "a", "B", "A"]),
+        // Sort is stable
+        ("sort icase", |t| t.sort_lines(SortOrder::AscendingCaseInsensitive), &["b", "B", "a", "A"], None, 4, &["a", "A", "b", "B"]),
+        ("sort icase desc", |t| t.sort_lines(SortOrder::DescendingCaseInsensitive), &["a", "B", "A", "b"], None, 4, &["B", "b", "a", "A"]),
+        ("sort selected", |t| t.sort_lines(SortOrder::Ascending), &["c", "b", "a"], Some(((1, 1), (2, 0))), 0, &["c", "b", "a"]),
+        ("reverse", |t| t.reverse_lines(), &["a", "b", "c"], None, 2, &["c", "b", "a"]),
+        ("reverse selected", |t| t.reverse_lines(), &["a", "b", "c"], Some(((2, 1), (1, 0))), 2, &["a", "c", "b"]),
+        ("dedup", |t| t.dedup_lines(), &["a", "b", "a", "", "b", ""], None, 3, &["a", "b", ""]),
+        ("dedup selected", |t| t.dedup_lines(), &["a", "a", "b", "a"], Some(((1, 0), (3, 1))), 1, &["a", "a", "b"]),
+    ];
+
+    for (name, op, before, selection, want_count, want_lines) in tests {
+        let mut t = TextArea::from(before.iter().copied());
+        if let Some((start, end)) = selection {
+            t.set_selection(start, end);
+        }
+        let cursor = t.cursor();
+        assert_eq!(op(&mut t), want_count, "{name}");
+        assert_eq!(t.lines(), want_lines, "{name}");
+        if want_count == 0 {
+            assert!(!t.undo(), "{name}");
+            continue;
+        }
+        let first = selection.map_or(0, |(s, e)| s.0.min(e.0));
+        assert_eq!(t.cursor(), (first, 0), "{name}");
+        assert!(!t.is_selecting(), "{name}");
+
+        // One undo unit
+        assert!(t.undo(), "{name}");
+        assert_eq!(t.lines(), before, "{name}");
+        assert_eq!(t.cursor(), cursor, "{name}");
+        assert!(!t.undo(), "{name}");
+        assert!(t.redo(), "{name}");
+        assert_eq!(t.lines(), want_lines, "{name}");
+    }
+}
+
+#[test]
+fn test_convert_indent() {
+    #[rustfmt::skip]
+    let tests = [
+        // (tab length, to tabs, line before, cursor before, line after, cursor after)
+        (4, false, "\tfoo", 1, "    foo", 4),
+        (4, false, "\tfoo", 0, "    foo", 0),
+        (4, false, "\ta\tb", 4, "    a\tb", 7),
+        (4, false, " \t x", 2, "     x", 4),
+        (4, false, "   \t\tx", 4, "        x", 4),
+        (4, false, "   \t\tx", 5, "        x", 8),
+        (3, false, "  \t\tx", 3, "      x", 3),
+        (4, false, "    x", 2, "    x", 2),
+        (4, false, "\t", 1, "    ", 4),
+        (4, true, "    foo", 4, "\tfoo", 1),
+        (4, true, "      foo", 6, "\t  foo", 3),
+        (4, true, "  \tfoo", 3, "\tfoo", 1),
+        (4, true, " \t  foo", 4, "\t  foo", 3),
+        // Cursor in the middle of the new tab moves to the tab
+        (4, true, "      foo", 2, "\t  foo", 0),
+        (4, true, "      foo", 5, "\t  foo", 2),
+        (3, true, "       x", 7, "\t\t x", 3),
+        (3, true, "  \t x", 4, "\t x", 2),
+        (4, true, "  x\t", 3, "  x\t", 3),
+        (4, true, "\tx", 0, "\tx", 0),
+    ];
+
+    for test in tests {
+        let (tab_len, to_tabs, before, col, after, want_col) = test;
+        let mut t = TextArea::from([before, "x"]);
+        t.set_tab_length(tab_len);
+        t.move_cursor(CursorMove::Jump(0, col));
+        let count = if to_tabs {
+            t.convert_indent_to_tabs()
+        } else {
+            t.convert_indent_to_spaces()
+        };
+        assert_eq!(count, (before != after) as usize, "{test:?}");
+        assert_eq!(t.lines(), [after, "x"], "{test:?}");
+        assert_eq!(t.cursor(), (0, want_col as usize), "{test:?}");
+        assert_undo_redo((0, col as usize), &[before, "x"], &[after, "x"], &mut t, test);
+    }
+
+    // All lines are converted as one undo unit
+    let mut t = TextArea::from(["a", "\tb", "\t\tc", "  \td"]);
+    t.set_selection((0, 0), (1, 1));
+    assert_eq!(t.convert_indent_to_spaces(), 3);
+    assert_eq!(t.lines(), ["a", "    b", "        c", "    d"]);
+    assert!(!t.is_selecting());
+    assert_eq!(t.convert_indent_to_tabs(), 3);
+    assert_eq!(t.lines(), ["a", "\tb", "\t\tc", "\td"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "    b", "        c", "    d"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "\tb", "\t\tc", "  \td"]);
+    assert!(!t.undo());
+
+    // Zero tab length does nothing
+    let mut t = TextArea::from(["\tx"]);
+    t.set_tab_length(0);
+    assert_eq!(t.convert_indent_to_spaces(), 0);
+    assert_eq!(t.convert_indent_to_tabs(), 0);
+    assert_eq!(t.lines(), ["\tx"]);
+}
+
+#[test]
+fn test_detect_indent() {
+    let tests: [(&[&str], IndentKind); 9] = [
+        (&[], IndentKind::Unknown),
+        (&["a", "", "b"], IndentKind::Unknown),
+        (&["a", "   ", "b"], IndentKind::Unknown),
+        (&["a", "\tb", "\t\tc"], IndentKind::Tabs),
+        (&["a", "  b", "    c", "      d", "  e"], IndentKind::Spaces(2)),
+        (&["a", "    b", "        c", "    d"], IndentKind::Spaces(4)),
+        // Tabs and spaces are counted by lines
+        (&["a", "\tb", "  c", "  d"], IndentKind::Spaces(2)),
+        (&["a", "\tb", "  c"], IndentKind::Tabs),
+        // Too deep first indentation
+        (&["          a", "          b"], IndentKind::Spaces(10)),
+    ];
+
+    for (lines, want) in tests {
+        let t = TextArea::from(lines.iter().copied());
+        assert_eq!(t.detect_indent(), want, "{lines:?}");
+    }
+}
+
+#[test]
+fn test_trim_trailing_whitespace() {
+    let tests = [
+        // (lines before, cursor, keep cursor line, lines after, cursor after)
+        (&["a ", "b", "c\t \t"][..], (0, 0), false, &["a", "b", "c"][..], (0, 0)),
+        (&["a ", "b", "c\t \t"], (2, 4), false, &["a", "b", "c"], (2, 1)),
+        (&["a ", "b", "c\t \t"], (2, 2), false, &["a", "b", "c"], (2, 1)),
+        (&["a ", "b", "c\t \t"], (2, 1), false, &["a", "b", "c"], (2, 1)),
+        (&["a ", "b", "c\t \t"], (2, 4), true, &["a", "b", "c\t \t"], (2, 4)),
+        (&["a b ", "  "], (1, 2), true, &["a b", "  "], (1, 2)),
+        (&["a b ", "  "], (1, 2), false, &["a b", ""], (1, 0)),
+        (&["  x", "\ty"], (1, 1), false, &["  x", "\ty"], (1, 1)),
+        (&["あ　 "], (0, 3), false, &["あ　"], (0, 2)),
+    ];
+
+    for test in tests {
+        let (before, cursor, keep, after, want_cursor) = test;
+        let mut t = TextArea::from(before.iter().copied());
+        t.move_cursor(CursorMove::Jump(cursor.0 as u64, cursor.1 as u64));
+        let modified = before.iter().zip(after).filter(|(b, a)| b != a).count();
+        assert_eq!(t.trim_trailing_whitespace(keep), modified, "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.cursor(), want_cursor, "{test:?}");
+        assert_undo_redo(cursor, before, after, &mut t, test);
+    }
+
+    let mut t = TextArea::from(["a ", "b "]);
+    t.set_selection((0, 0), (1, 2));
+    assert_eq!(t.trim_trailing_whitespace(false), 2);
+    assert!(!t.is_selecting());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a ", "b "]);
+    assert!(!t.undo());
+}
+
+#[test]
+fn test_ensure_final_newline() {
+    let mut t = TextArea::from(["a", "b"]);
+    t.set_selection((0, 0), (0, 1));
+    assert!(t.ensure_final_newline());
+    assert_eq!(t.lines(), ["a", "b", ""]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert!(t.is_selecting());
+    assert!(!t.ensure_final_newline());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a", "b"]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert!(!t.undo());
+
+    let mut t = TextArea::default();
+    assert!(!t.ensure_final_newline());
+    assert_eq!(t.lines(), [""]);
+
+    let mut t = TextArea::from(["a"]);
+    t.set_single_line(true);
+    assert!(!t.ensure_final_newline());
+    assert_eq!(t.lines(), ["a"]);
+}
+
+#[test]
+fn test_line_ending_round_trip() {
+    let tests = [
+        // (file content, lines, line ending, written content)
+        ("", &[""][..], LineEnding::Lf, ""),
+        ("a\nb", &["a", "b"], LineEnding::Lf, "a\nb"),
+        ("a\nb\n", &["a", "b", ""], LineEnding::Lf, "a\nb\n"),
+        ("a\r\nb", &["a", "b"], LineEnding::CrLf, "a\r\nb"),
+        ("a\r\nb\r\n", &["a", "b", ""], LineEnding::CrLf, "a\r\nb\r\n"),
+        ("\r\n", &["", ""], LineEnding::CrLf, "\r\n"),
+        // Mixed line endings are kept line by line
+        (
+            "a\r\nb\nc\r\n",
+            &["a", "b", "c", ""],
+            LineEnding::Mixed,
+            "a\r\nb\nc\r\n",
+        ),
+        ("a\r\nb\nc\n", &["a", "b", "c", ""], LineEnding::Mixed, "a\r\nb\nc\n"),
+        ("a\r\nb\n", &["a", "b", ""], LineEnding::Mixed, "a\r\nb\n"),
+    ];
+
+    let path = std::env::temp_dir().join(format!("tui-textarea-line-ending-{}", std::process::id()));
+    for test in tests {
+        let (content, lines, ending, written) = test;
+        std::fs::write(&path, content).unwrap();
+        let t = TextArea::new_from_file(&std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(t.lines(), lines, "{test:?}");
+        assert_eq!(t.line_ending(), ending, "{test:?}");
+        let mut buf = vec![];
+        t.write_to(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), written, "{test:?}");
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_set_line_ending() {
+    let mut t = TextArea::from(["a", "b", ""]);
+    let written = |t: &TextArea| {
+        let mut buf = vec![];
+        t.write_to(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+    assert_eq!(t.line_ending(), LineEnding::Lf);
+    assert_eq!(written(&t), "a\nb\n");
+    t.set_line_ending(LineEnding::CrLf);
+    assert_eq!(written(&t), "a\r\nb\r\n");
+    // Mixed keeps the current line ending on writing
+    t.set_line_ending(LineEnding::Mixed);
+    assert_eq!(t.line_ending(), LineEnding::Mixed);
+    assert_eq!(written(&t), "a\r\nb\r\n");
+    t.set_line_ending(LineEnding::Lf);
+    assert_eq!(written(&t), "a\nb\n");
+}
+
+#[test]
+fn test_to_writer_round_trip() {
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    const PARTS: &[&str] = &["a", "bc", " ", "\t", "あ", "🐶", "\r", "  x  "];
+
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..500 {
+        // Line endings are all `\n`, all `\r\n`, or mixed line by line
+        let endings = rng.next(3);
+        let mut content = String::new();
+        for _ in 0..rng.next(8) {
+            let newline = ["\n", "\r\n"][if endings == 2 { rng.next(2) } else { endings }];
+            for _ in 0..rng.next(5) {
+                content.push_str(PARTS[rng.next(PARTS.len())]);
+            }
+            // Avoid making "\r\n" from a trailing "\r" in a file using "\n"
+            if content.ends_with('\r') {
+                content.push('a');
+            }
+            content.push_str(newline);
+        }
+        // Text without trailing newline
+        if rng.next(2) == 0 {
+            content.push_str(PARTS[rng.next(PARTS.len() - 2)]);
+        }
+
+        for threshold in [0, usize::MAX] {
+            let t = TextArea::new_from_reader_with_threshold(content.as_bytes(), threshold).unwrap();
+            let mut buf = vec![];
+            let written = t.to_writer(&mut buf, SaveOptions::default()).unwrap();
+            assert_eq!(String::from_utf8(buf).unwrap(), content);
+            assert_eq!(written, content.len(), "{content:?}");
+        }
+    }
+}
+
+#[test]
+fn test_mixed_line_endings_after_edits() {
+    let write = |t: &TextArea, options: SaveOptions| {
+        let mut buf = vec![];
+        t.to_writer(&mut buf, options).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+    let mut t = TextArea::new_from_reader("a\r\nbc\nd\r\ne".as_bytes()).unwrap();
+    assert_eq!(t.line_ending(), LineEnding::Mixed);
+
+    // A new line break takes the line ending of the split line
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.insert_newline();
+    assert_eq!(write(&t, SaveOptions::default()), "a\r\nb\nc\nd\r\ne");
+
+    // Joined line takes the line ending of the next line
+    t.move_cursor(CursorMove::Jump(2, 1));
+    t.delete_next_char();
+    assert_eq!(write(&t, SaveOptions::default()), "a\r\nb\ncd\r\ne");
+
+    // Inserted lines take the line ending of the line before them
+    let mut t = TextArea::new_from_reader("a\r\nbc\nd\r\ne".as_bytes()).unwrap();
+    t.move_cursor(CursorMove::End);
+    t.insert_str("\nx\ny");
+    assert_eq!(write(&t, SaveOptions::default()), "a\r\nx\r\ny\r\nbc\nd\r\ne");
+
+    // Explicit line ending overrides the kept ones
+    let options = SaveOptions {
+        line_ending: Some(LineEnding::Lf),
+        ensure_trailing_newline: true,
+        ..Default::default()
+    };
+    assert_eq!(write(&t, options), "a\nx\ny\nbc\nd\ne\n");
+    t.set_line_ending(LineEnding::CrLf);
+    assert_eq!(write(&t, SaveOptions::default()), "a\r\nx\r\ny\r\nbc\r\nd\r\ne");
+    t.set_line_ending(LineEnding::Mixed);
+    assert_eq!(write(&t, SaveOptions::default()), "a\r\nx\r\ny\r\nbc\nd\r\ne");
+}
+
+#[test]
+fn test_to_writer_options() {
+    let tests = [
+        // (content, options, written content)
+        ("a \nb\t\n", SaveOptions::default(), "a \nb\t\n"),
+        (
+            "a \nb\t\n",
+            SaveOptions {
+                trim_trailing_whitespace: true,
+                ..Default::default()
+            },
+            "a\nb\n",
+        ),
+        (
+            " \t a\u{3000}\n \n",
+            SaveOptions {
+                trim_trailing_whitespace: true,
+                ..Default::default()
+            },
+            " \t a\u{3000}\n\n",
+        ),
+        (
+            "a\nb",
+            SaveOptions {
+                ensure_trailing_newline: true,
+                ..Default::default()
+            },
+            "a\nb\n",
+        ),
+        (
+            "a\nb\n",
+            SaveOptions {
+                ensure_trailing_newline: true,
+                ..Default::default()
+            },
+            "a\nb\n",
+        ),
+        (
+            "",
+            SaveOptions {
+                ensure_trailing_newline: true,
+                ..Default::default()
+            },
+            "",
+        ),
+        (
+            "a\nb  ",
+            SaveOptions {
+                ensure_trailing_newline: true,
+                trim_trailing_whitespace: true,
+                ..Default::default()
+            },
+            "a\nb\n",
+        ),
+        (
+            "a\n  ",
+            SaveOptions {
+                ensure_trailing_newline: true,
+                trim_trailing_whitespace: true,
+                ..Default::default()
+            },
+            "a\n",
+        ),
+        (
+            "a\r\nb",
+            SaveOptions {
+                line_ending: Some(LineEnding::Lf),
+                ..Default::default()
+            },
+            "a\nb",
+        ),
+        (
+            "a\nb",
+            SaveOptions {
+                line_ending: Some(LineEnding::CrLf),
+                ensure_trailing_newline: true,
+                ..Default::default()
+            },
+            "a\r\nb\r\n",
+        ),
+        (
+            "a\r\nb",
+            SaveOptions {
+                line_ending: Some(LineEnding::Mixed),
+                ..Default::default()
+            },
+            "a\r\nb",
+        ),
+    ];
+
+    for test in tests {
+        let (content, options, expected) = test;
+        let t = TextArea::new_from_reader(content.as_bytes()).unwrap();
+        let mut buf = vec![];
+        let written = t.to_writer(&mut buf, options).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), expected, "{test:?}");
+        assert_eq!(written, expected.len(), "{test:?}");
+        // The text in the textarea is not modified
+        let mut buf = vec![];
+        t.write_to(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), content, "{test:?}");
+        assert!(!t.is_modified(), "{test:?}");
+    }
+}
+
+#[test]
+fn test_new_from_reader() {
+    let t = TextArea::new_from_reader("a\nb\r\nc".as_bytes()).unwrap();
+    assert_eq!(t.lines(), ["a", "b", "c"]);
+    assert_eq!(t.line_ending(), LineEnding::Mixed);
+
+    let t = TextArea::new_from_reader(io::empty()).unwrap();
+    assert_eq!(t.lines(), [""]);
+
+    // Lines longer than the buffer of the reader
+    let long = "x".repeat(10000);
+    let text = format!("{long}\n{long}\n");
+    let t = TextArea::new_from_reader(io::BufReader::with_capacity(16, text.as_bytes())).unwrap();
+    assert_eq!(t.lines(), [long.as_str(), long.as_str(), ""]);
+
+    let text = "line\n".repeat(100000);
+    let t = TextArea::new_from_reader(text.as_bytes()).unwrap();
+    assert_eq!(t.lines().len(), 100001);
+
+    let err = TextArea::new_from_reader(&b"a\n\xff\n"[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_new_from_reader_with_threshold() {
+    let text = "ab\r\ncd\r\n\r\nあい\r\n";
+    // The small buffer of the reader splits `\r\n` across chunks
+    let read = |threshold| {
+        let reader = io::BufReader::with_capacity(3, text.as_bytes());
+        TextArea::new_from_reader_with_threshold(reader, threshold).unwrap()
+    };
+    let mut buffered = read(0);
+    let mut t = read(usize::MAX);
+    assert_eq!(buffered.len_lines(), 5);
+    assert_eq!(buffered.line_ending(), LineEnding::CrLf);
+    assert_eq!(buffered.lines(), ["ab", "cd", "", "あい", ""]);
+    assert_eq!(buffered, t);
+
+    let ops: &[fn(&mut TextArea<'_>) -> bool] = &[
+        |t| {
+            t.move_cursor(CursorMove::Jump(1, 1));
+            t.insert_newline()
+        },
+        |t| t.insert_str("x\ny"),
+        |t| {
+            t.move_cursor(CursorMove::Jump(4, 0));
+            t.delete_newline()
+        },
+        |t| t.delete_next_char(),
+        |t| t.undo(),
+    ];
+    for (i, op) in ops.iter().enumerate() {
+        assert_eq!(op(&mut buffered), op(&mut t), "{i}");
+        assert_eq!(buffered.lines(), t.lines(), "{i}");
+        assert!(buffered.iter_lines().eq(t.iter_lines()), "{i}");
+    }
+
+    let err = TextArea::new_from_reader_with_threshold(&b"a\n\xff\n"[..], 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_insert_from_reader() {
+    let tests = [
+        // (content, number of read lines, lines after insertion, cursor)
+        ("", 0, &["ab"][..], (0, 1)),
+        ("x", 1, &["axb"], (0, 2)),
+        ("x\n", 1, &["ax", "b"], (1, 0)),
+        ("x\ny", 2, &["ax", "yb"], (1, 1)),
+        ("x\r\ny\r\n", 2, &["ax", "y", "b"], (2, 0)),
+        ("x\ry\r", 2, &["ax", "y", "b"], (2, 0)),
+        ("x\n\r\n\r", 3, &["ax", "", "", "b"], (3, 0)),
+        ("\n", 1, &["a", "b"], (1, 0)),
+        ("\r", 1, &["a", "b"], (1, 0)),
+    ];
+
+    for test in tests {
+        let (content, count, lines, cursor) = test;
+        let mut t = TextArea::from(["ab"]);
+        t.move_cursor(CursorMove::Forward);
+        assert_eq!(
+            t.insert_from_reader(&mut content.as_bytes()).unwrap(),
+            count,
+            "{test:?}"
+        );
+        assert_eq!(t.lines(), lines, "{test:?}");
+        assert_eq!(t.cursor(), cursor, "{test:?}");
+        // Undone at once
+        t.undo();
+        assert_eq!(t.lines(), ["ab"], "{test:?}");
+        assert!(!t.undo(), "{test:?}");
+    }
+
+    // Selection is replaced in one undo unit
+    let mut t = TextArea::from(["abc", "def"]);
+    t.move_cursor(CursorMove::Forward);
+    t.start_selection();
+    t.move_cursor(CursorMove::Down);
+    assert_eq!(t.insert_from_reader(&mut "x\ny".as_bytes()).unwrap(), 2);
+    assert_eq!(t.lines(), ["ax", "yef"]);
+    assert_eq!(t.selection_range(), None);
+    t.undo();
+    assert_eq!(t.lines(), ["abc", "def"]);
+    assert!(!t.undo());
+
+    // Lines longer than the buffer of the reader
+    let long = "x".repeat(10000);
+    let text = format!("{long}\r\n{long}");
+    let mut t = TextArea::default();
+    let mut reader = io::BufReader::with_capacity(16, text.as_bytes());
+    assert_eq!(t.insert_from_reader(&mut reader).unwrap(), 2);
+    assert_eq!(t.lines(), [long.as_str(), long.as_str()]);
+    assert_eq!(t.cursor(), (1, 10000));
+
+    // Text is not modified on invalid UTF-8
+    let mut t = TextArea::from(["ab"]);
+    let err = t.insert_from_reader(&mut &b"x\ny\xff\n"[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(t.lines(), ["ab"]);
+    assert!(!t.is_modified());
+
+    // Lines are joined in single-line mode
+    let mut t = TextArea::default();
+    t.set_single_line(true);
+    assert_eq!(t.insert_from_reader(&mut "a\nb\n".as_bytes()).unwrap(), 2);
+    assert_eq!(t.lines().len(), 1);
+}
+
+#[test]
+fn test_insert_file() {
+    let path = std::env::temp_dir().join(format!("tui-textarea-insert-file-{}", std::process::id()));
+    std::fs::write(&path, "x\ny\n").unwrap();
+    let mut t = TextArea::from(["ab"]);
+    t.move_cursor(CursorMove::End);
+    assert_eq!(t.insert_file(&path).unwrap(), 2);
+    assert_eq!(t.lines(), ["abx", "y", ""]);
+    assert_eq!(t.cursor(), (2, 0));
+    std::fs::remove_file(&path).unwrap();
+
+    let err = t.insert_file(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(t.lines(), ["abx", "y", ""]);
+}
+
+#[cfg(feature = "rope")]
+#[test]
+fn test_rope_storage() {
+    type Op = fn(&mut TextArea<'_>) -> bool;
+    let ops: &[(&str, Op)] = &[
+        ("insert_str", |t| t.insert_str("foo bar\n  baz あい\n😀x")),
+        ("insert_char", |t| t.insert_char('y')),
+        ("insert_newline", |t| t.insert_newline()),
+        ("insert_tab", |t| t.insert_tab()),
+        ("move_top", |t| {
+            t.move_cursor(CursorMove::Jump(0, 4));
+            true
+        }),
+        ("delete_char", |t| t.delete_char()),
+        ("delete_next_char", |t| t.delete_next_char()),
+        ("delete_str", |t| t.delete_str(12)),
+        ("delete_word", |t| t.delete_word()),
+        ("delete_line_by_end", |t| t.delete_line_by_end()),
+        ("select", |t| {
+            t.set_selection((0, 1), (2, 1));
+            true
+        }),
+        ("cut", |t| t.cut()),
+        ("paste", |t| t.paste()),
+        ("delete_newline", |t| {
+            t.move_cursor(CursorMove::Jump(1, 0));
+            t.delete_newline()
+        }),
+        ("sort_lines", |t| t.sort_lines(SortOrder::Descending) > 0),
+        ("join_lines", |t| t.join_lines() > 0),
+        ("move_lines_down", |t| {
+            t.move_cursor(CursorMove::Top);
+            t.move_lines_down()
+        }),
+        ("undo", |t| t.undo()),
+        ("undo", |t| t.undo()),
+        ("redo", |t| t.redo()),
+    ];
+
+    let mut t = TextArea::from(["abc", "", "def"]);
+    let mut rope = t.clone();
+    rope.set_rope(true);
+    assert!(rope.is_rope());
+    for (name, op) in ops {
+        assert_eq!(op(&mut rope), op(&mut t), "{name}");
+        assert_eq!(rope.lines(), t.lines(), "{name}");
+        assert_eq!(rope.cursor(), t.cursor(), "{name}");
+        assert!(rope.iter_lines().eq(t.iter_lines()), "{name}");
+        let lens: Vec<_> = (0..t.len_lines()).map(|row| t.line_len(row)).collect();
+        assert!((0..t.len_lines()).map(|row| rope.line_len(row)).eq(lens), "{name}");
+    }
+
+    #[cfg(feature = "search")]
+    for t in [&mut t, &mut rope] {
+        t.set_search_pattern("[a-z]\\n").unwrap();
+        t.replace_all("-");
+    }
+    assert_eq!(rope.lines(), t.lines());
+
+    rope.set_rope(false);
+    assert!(!rope.is_rope());
+    assert_eq!(rope, t);
+}
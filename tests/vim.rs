@@ -0,0 +1,278 @@
+#![cfg(feature = "vim")]
+
+use tui_textarea::vim::{Mode, Vim, VimState};
+use tui_textarea::{CursorMove, Input, Key, TextArea};
+
+fn keys(s: &str) -> Vec<Input> {
+    s.chars()
+        .map(|c| {
+            let key = match c {
+                '\x1b' => Key::Esc,
+                '\n' => Key::Enter,
+                c => Key::Char(c),
+            };
+            Input { key, ..Default::default() }
+        })
+        .collect()
+}
+
+fn run(vim: &mut Vim, textarea: &mut TextArea<'_>, s: &str) -> VimState {
+    let mut state = VimState::Mode(vim.mode());
+    for input in keys(s) {
+        state = vim.transition(input, textarea);
+    }
+    state
+}
+
+#[test]
+fn operator_and_motion() {
+    #[rustfmt::skip]
+    let tests = [
+        // (lines, cursor, keys, lines after, cursor after, yank)
+        (&["foo bar baz"][..], (0, 0), "dw", &["bar baz"][..], (0, 0), "foo "),
+        (&["foo bar baz"], (0, 0), "d2w", &["baz"], (0, 0), "foo bar "),
+        (&["foo bar baz"], (0, 0), "2dw", &["baz"], (0, 0), "foo bar "),
+        (&["foo bar", "baz"], (0, 4), "dw", &["foo ", "baz"], (0, 4), "bar"),
+        (&["foo bar baz"], (0, 4), "de", &["foo  baz"], (0, 4), "bar"),
+        (&["foo bar baz"], (0, 4), "d$", &["foo "], (0, 4), "bar baz"),
+        (&["foo bar baz"], (0, 4), "d0", &["bar baz"], (0, 0), "foo "),
+        (&["foo bar baz"], (0, 8), "db", &["foo baz"], (0, 4), "bar "),
+        (&["foo bar baz"], (0, 5), "diw", &["foo  baz"], (0, 4), "bar"),
+        (&["foo bar baz"], (0, 5), "daw", &["foo baz"], (0, 4), "bar "),
+        (&["foo bar baz"], (0, 8), "daw", &["foo bar"], (0, 7), " baz"),
+        (&["foo bar baz"], (0, 5), "yiw", &["foo bar baz"], (0, 4), "bar"),
+        (&["a", "b", "c", "d"], (1, 0), "dd", &["a", "c", "d"], (1, 0), "b\n"),
+        (&["a", "b", "c", "d"], (1, 0), "2dd", &["a", "d"], (1, 0), "b\nc\n"),
+        (&["a", "b", "c", "d"], (1, 0), "dj", &["a", "d"], (1, 0), "b\nc\n"),
+        (&["a", "b", "c", "d"], (2, 0), "dk", &["a", "d"], (1, 0), "b\nc\n"),
+        (&["a", "b", "c", "d"], (3, 0), "dd", &["a", "b", "c"], (2, 0), "d\n"),
+        (&["a", "b", "c", "d"], (2, 0), "dG", &["a", "b"], (1, 0), "c\nd\n"),
+        (&["a", "b", "c", "d"], (1, 0), "dgg", &["c", "d"], (0, 0), "a\nb\n"),
+        (&["a", "b"], (0, 0), "yj", &["a", "b"], (0, 0), "a\nb\n"),
+        (&["abc"], (0, 1), "2x", &["a"], (0, 1), "bc"),
+        (&["abc"], (0, 2), "X", &["ac"], (0, 1), "b"),
+        (&["abc"], (0, 1), "D", &["a"], (0, 1), "bc"),
+        (&["abc"], (0, 0), "3rx", &["xxx"], (0, 2), "abc"),
+        (&["a", "b", "c"], (0, 0), "3J", &["abc"], (0, 2), "abc"),
+    ];
+
+    for test in tests {
+        let (lines, (row, col), input, after, cursor, yank) = test;
+        let mut t = TextArea::from(lines.iter().copied());
+        t.set_yank_text("abc");
+        t.move_cursor(CursorMove::Jump(row, col));
+        let mut vim = Vim::new();
+        assert_eq!(run(&mut vim, &mut t, input), VimState::Mode(Mode::Normal), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.cursor(), cursor, "{test:?}");
+        assert_eq!(t.yank_text(), yank, "{test:?}");
+    }
+}
+
+#[test]
+fn change_and_insert() {
+    #[rustfmt::skip]
+    let tests = [
+        (&["foo bar"][..], (0, 0), "cwxyz\x1b", &["xyz bar"][..], (0, 2)),
+        (&["foo bar"], (0, 4), "ciwxyz\x1b", &["foo xyz"], (0, 6)),
+        (&["foo", "bar"], (0, 1), "ccxyz\x1b", &["xyz", "bar"], (0, 2)),
+        (&["foo"], (0, 1), "Cx\x1b", &["fx"], (0, 1)),
+        (&["foo"], (0, 0), "sx\x1b", &["xoo"], (0, 0)),
+        (&["foo"], (0, 1), "ix\x1b", &["fxoo"], (0, 1)),
+        (&["foo"], (0, 1), "ax\x1b", &["foxo"], (0, 2)),
+        (&["  foo"], (0, 4), "Ix\x1b", &["  xfoo"], (0, 2)),
+        (&["foo"], (0, 0), "Ax\x1b", &["foox"], (0, 3)),
+        (&["foo"], (0, 0), "ox\x1b", &["foo", "x"], (1, 0)),
+        (&["foo"], (0, 0), "Ox\x1b", &["x", "foo"], (0, 0)),
+    ];
+
+    for test in tests {
+        let (lines, (row, col), input, after, cursor) = test;
+        let mut t = TextArea::from(lines.iter().copied());
+        t.move_cursor(CursorMove::Jump(row, col));
+        let mut vim = Vim::new();
+        assert_eq!(run(&mut vim, &mut t, input), VimState::Mode(Mode::Normal), "{test:?}");
+        assert_eq!(t.lines(), after, "{test:?}");
+        assert_eq!(t.cursor(), cursor, "{test:?}");
+
+        // One undo reverts whole command including the insert session
+        assert!(t.undo(), "{test:?}");
+        assert_eq!(t.lines(), lines, "{test:?}");
+        assert!(!t.undo(), "{test:?}");
+    }
+}
+
+#[test]
+fn modes_and_cursor_style() {
+    let mut t = TextArea::from(["foo"]);
+    let mut vim = Vim::new();
+    assert_eq!(vim.mode(), Mode::Normal);
+
+    assert_eq!(run(&mut vim, &mut t, "d"), VimState::Pending);
+    assert_eq!(vim.mode(), Mode::Operator('d'));
+    assert_eq!(run(&mut vim, &mut t, "\x1b"), VimState::Mode(Mode::Normal));
+
+    assert_eq!(run(&mut vim, &mut t, "i"), VimState::Mode(Mode::Insert));
+    assert_eq!(t.cursor_style(), Mode::Insert.cursor_style());
+    assert_eq!(run(&mut vim, &mut t, "\x1b"), VimState::Mode(Mode::Normal));
+    assert_eq!(t.cursor_style(), Mode::Normal.cursor_style());
+
+    assert_eq!(run(&mut vim, &mut t, "v"), VimState::Mode(Mode::Visual));
+    assert_eq!(run(&mut vim, &mut t, "V"), VimState::Mode(Mode::VisualLine));
+    assert_eq!(run(&mut vim, &mut t, "\x1b"), VimState::Mode(Mode::Normal));
+    assert!(!t.is_selecting());
+
+    assert_eq!(vim.mode().to_string(), "NORMAL");
+    assert_eq!(Mode::VisualLine.to_string(), "VISUAL LINE");
+
+    let q = keys("q").remove(0);
+    assert_eq!(vim.transition(q.clone(), &mut t), VimState::Unhandled(q));
+}
+
+#[test]
+fn visual_mode() {
+    // Selection in visual mode includes the character under the cursor
+    let mut t = TextArea::from(["foo bar baz"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "wved");
+    assert_eq!(t.lines(), ["foo  baz"]);
+    assert_eq!(t.yank_text(), "bar");
+
+    let mut t = TextArea::from(["foo bar baz"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "$vbby");
+    assert_eq!(t.yank_text(), "bar baz");
+    assert_eq!(t.cursor(), (0, 4));
+
+    let mut t = TextArea::from(["a", "b", "c", "d"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "jVj");
+    assert_eq!(t.selection_range(), Some(((1, 0), (2, 1))));
+    run(&mut vim, &mut t, "d");
+    assert_eq!(t.lines(), ["a", "d"]);
+    run(&mut vim, &mut t, "p");
+    assert_eq!(t.lines(), ["a", "d", "b", "c"]);
+    assert_eq!(t.cursor(), (2, 0));
+    run(&mut vim, &mut t, "ggP");
+    assert_eq!(t.lines(), ["b", "c", "a", "d", "b", "c"]);
+    assert_eq!(t.cursor(), (0, 0));
+
+    // Linewise visual mode upward
+    let mut t = TextArea::from(["a", "b", "c", "d"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "GVkk");
+    assert_eq!(t.selection_range(), Some(((1, 0), (3, 1))));
+    run(&mut vim, &mut t, "c");
+    assert_eq!(vim.mode(), Mode::Insert);
+    assert_eq!(t.lines(), ["a", ""]);
+}
+
+#[test]
+fn repeat_last_change() {
+    let mut t = TextArea::from(["foo bar baz qux"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "dw");
+    run(&mut vim, &mut t, ".");
+    assert_eq!(t.lines(), ["baz qux"]);
+
+    // Motions and yanks do not overwrite the last change
+    run(&mut vim, &mut t, "wyiw0");
+    run(&mut vim, &mut t, "2.");
+    assert_eq!(t.lines(), [""]);
+
+    // `.` with an insert session
+    let mut t = TextArea::from(["a", "b"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "Ax!\x1bj.");
+    assert_eq!(t.lines(), ["ax!", "bx!"]);
+
+    // One undo reverts one repeat
+    t.undo();
+    assert_eq!(t.lines(), ["ax!", "b"]);
+}
+
+#[test]
+fn counts_and_motions() {
+    let mut t = TextArea::from(["a", "b", "c", "  d e"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "2j");
+    assert_eq!(t.cursor(), (2, 0));
+    run(&mut vim, &mut t, "G");
+    assert_eq!(t.cursor(), (3, 2));
+    run(&mut vim, &mut t, "2G");
+    assert_eq!(t.cursor(), (1, 0));
+    run(&mut vim, &mut t, "G$");
+    assert_eq!(t.cursor(), (3, 4));
+    run(&mut vim, &mut t, "0");
+    assert_eq!(t.cursor(), (3, 0));
+    run(&mut vim, &mut t, "^e");
+    assert_eq!(t.cursor(), (3, 4));
+    run(&mut vim, &mut t, "10k");
+    assert_eq!(t.cursor(), (0, 0));
+
+    // Only `G` records the position in the jump list. Word and line motions don't
+    assert_eq!(t.jump_list(), [(2, 0), (3, 2), (1, 0)]);
+    run(&mut vim, &mut t, "3wb$");
+    assert_eq!(t.jump_list(), [(2, 0), (3, 2), (1, 0)]);
+}
+
+#[test]
+fn undo_redo() {
+    let mut t = TextArea::from(["a", "b", "c"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "ddxix\x1b");
+    assert_eq!(t.lines(), ["x", "c"]);
+    run(&mut vim, &mut t, "u");
+    assert_eq!(t.lines(), ["", "c"]);
+    run(&mut vim, &mut t, "2u");
+    assert_eq!(t.lines(), ["a", "b", "c"]);
+    let ctrl_r = Input {
+        key: Key::Char('r'),
+        ctrl: true,
+        ..Default::default()
+    };
+    vim.transition(ctrl_r, &mut t);
+    assert_eq!(t.lines(), ["b", "c"]);
+}
+
+#[test]
+fn increment_number() {
+    let mut t = TextArea::from(["x = 9;"]);
+    let mut vim = Vim::new();
+    let ctrl = |c| Input {
+        key: Key::Char(c),
+        ctrl: true,
+        ..Default::default()
+    };
+    vim.transition(ctrl('a'), &mut t);
+    assert_eq!(t.lines(), ["x = 10;"]);
+    assert_eq!(t.cursor(), (0, 5));
+
+    run(&mut vim, &mut t, "15");
+    vim.transition(ctrl('x'), &mut t);
+    assert_eq!(t.lines(), ["x = -5;"]);
+
+    // Repeated by `.`
+    run(&mut vim, &mut t, ".");
+    assert_eq!(t.lines(), ["x = -20;"]);
+    run(&mut vim, &mut t, "u");
+    assert_eq!(t.lines(), ["x = -5;"]);
+}
+
+#[test]
+fn visual_case_transform() {
+    let mut t = TextArea::from(["foo bar", "baz"]);
+    let mut vim = Vim::new();
+    run(&mut vim, &mut t, "veU");
+    assert_eq!(t.lines(), ["FOO bar", "baz"]);
+    assert_eq!(vim.mode(), Mode::Normal);
+    assert_eq!(t.cursor(), (0, 0));
+
+    run(&mut vim, &mut t, "wvjhh~");
+    assert_eq!(t.lines(), ["FOO BAR", "BAz"]);
+
+    run(&mut vim, &mut t, "ggVju");
+    assert_eq!(t.lines(), ["foo bar", "baz"]);
+    run(&mut vim, &mut t, "u");
+    assert_eq!(t.lines(), ["FOO BAR", "BAz"]);
+}